@@ -1,6 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::address::ExecutionAddressCycleInfo;
+use massa_models::canonical_json::CanonicalJson;
 use massa_models::endorsement::EndorsementId;
 use massa_models::operation::OperationId;
 use massa_models::slot::{IndexedSlot, Slot};
@@ -50,6 +51,8 @@ pub struct AddressInfo {
     pub cycle_infos: Vec<ExecutionAddressCycleInfo>,
 }
 
+impl CanonicalJson for AddressInfo {}
+
 impl std::fmt::Display for AddressInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Address {} (thread {}):", self.address, self.thread)?;
@@ -177,3 +180,55 @@ impl std::fmt::Display for AddressFilter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use jsonrpsee::core::__reexports::serde_json;
+    use massa_models::canonical_json::CanonicalJson;
+    use massa_models::{
+        address::Address,
+        amount::Amount,
+        slot::{IndexedSlot, Slot},
+    };
+    use std::str::FromStr;
+
+    use super::AddressInfo;
+    use crate::slot::SlotAmount;
+
+    #[test]
+    fn canonical_address_info() {
+        let info = AddressInfo {
+            address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
+                .unwrap(),
+            thread: 3,
+            final_balance: Amount::from_str("10").unwrap(),
+            final_roll_count: 1,
+            final_datastore_keys: vec![vec![1, 2]],
+            candidate_balance: Amount::from_str("12.5").unwrap(),
+            candidate_roll_count: 2,
+            candidate_datastore_keys: vec![],
+            deferred_credits: vec![SlotAmount {
+                slot: Slot::new(20, 3),
+                amount: Amount::zero(),
+            }],
+            next_block_draws: vec![Slot::new(13, 3)],
+            next_endorsement_draws: vec![IndexedSlot {
+                slot: Slot::new(13, 4),
+                index: 7,
+            }],
+            created_blocks: vec![],
+            created_operations: vec![],
+            created_endorsements: vec![],
+            cycle_infos: vec![],
+        };
+        let canonical = info.to_canonical_json().unwrap();
+        assert_eq!(
+            canonical,
+            r#"{"address":"AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x","candidate_balance":"12.5","candidate_datastore_keys":[],"candidate_roll_count":2,"created_blocks":[],"created_endorsements":[],"created_operations":[],"cycle_infos":[],"deferred_credits":[{"amount":"0","slot":{"period":20,"thread":3}}],"final_balance":"10","final_datastore_keys":[[1,2]],"final_roll_count":1,"next_block_draws":[{"period":13,"thread":3}],"next_endorsement_draws":[{"index":7,"slot":{"period":13,"thread":4}}],"thread":3}"#
+        );
+
+        // the canonical output of a parsed canonical output is unchanged
+        let parsed: AddressInfo = serde_json::from_str(&canonical).unwrap();
+        assert_eq!(parsed.to_canonical_json().unwrap(), canonical);
+    }
+}
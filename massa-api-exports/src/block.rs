@@ -1,6 +1,14 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use massa_models::{address::Address, block::Block, block_id::BlockId, slot::Slot};
+use massa_models::{
+    address::Address,
+    block::{Block, ConfirmationInfo},
+    block_header::BlockHeader,
+    block_id::BlockId,
+    canonical_json::CanonicalJson,
+    endorsement::EndorsementId,
+    slot::Slot,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +23,15 @@ pub struct BlockInfo {
     pub content: Option<BlockInfoContent>,
 }
 
+/// Confirmation of a block
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BlockConfirmation {
+    /// block id
+    pub id: BlockId,
+    /// confirmation of the block, None if it is not active in the graph
+    pub confirmation: Option<ConfirmationInfo>,
+}
+
 /// Block content
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct BlockInfoContent {
@@ -28,8 +45,70 @@ pub struct BlockInfoContent {
     pub is_discarded: bool,
     /// block
     pub block: Block,
+    /// state of each endorsement index of the block
+    #[serde(default)]
+    pub endorsement_coverage: Vec<EndorsementCoverage>,
+}
+
+/// State of an endorsement index of a block
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub enum EndorsementCoverage {
+    /// an endorsement was included at this index
+    Included(EndorsementId),
+    /// no endorsement was included at this index.
+    /// Contains the address that was drawn to endorse at this index,
+    /// or `None` if the draw is out of the history kept by the node.
+    Missing(Option<Address>),
+}
+
+impl EndorsementCoverage {
+    /// Get the state of each of the `endorsement_count` endorsement indices of a block header.
+    /// `expected_endorsers` are the addresses drawn to endorse at the slot of the block, if known.
+    pub fn from_header(
+        header: &BlockHeader,
+        endorsement_count: u32,
+        expected_endorsers: Option<&[Address]>,
+    ) -> Vec<EndorsementCoverage> {
+        let mut coverage: Vec<EndorsementCoverage> = (0..endorsement_count as usize)
+            .map(|index| {
+                EndorsementCoverage::Missing(
+                    expected_endorsers.and_then(|endorsers| endorsers.get(index).copied()),
+                )
+            })
+            .collect();
+        for endorsement in &header.endorsements {
+            if let Some(state) = coverage.get_mut(endorsement.content.index as usize) {
+                *state = EndorsementCoverage::Included(endorsement.id);
+            }
+        }
+        coverage
+    }
 }
 
+impl std::fmt::Display for EndorsementCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EndorsementCoverage::Included(endorsement_id) => {
+                write!(f, "included ({})", endorsement_id)
+            }
+            EndorsementCoverage::Missing(Some(address)) => {
+                write!(f, "missing (drawn: {})", address)
+            }
+            EndorsementCoverage::Missing(None) => write!(f, "missing"),
+        }
+    }
+}
+
+/// Ratio of the endorsement indices of a block header that are filled
+pub fn endorsement_fill_ratio(header: &BlockHeader, endorsement_count: u32) -> f64 {
+    if endorsement_count == 0 {
+        return 1.0;
+    }
+    header.endorsements.len() as f64 / endorsement_count as f64
+}
+
+impl CanonicalJson for BlockInfo {}
+
 impl std::fmt::Display for BlockInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(content) = &self.content {
@@ -43,6 +122,21 @@ impl std::fmt::Display for BlockInfo {
                 display_if_true(content.is_discarded, " (discarded)"),
             )?;
             writeln!(f, "Block: {}", content.block)?;
+            let missing: Vec<String> = content
+                .endorsement_coverage
+                .iter()
+                .enumerate()
+                .filter_map(|(index, state)| match state {
+                    EndorsementCoverage::Included(_) => None,
+                    EndorsementCoverage::Missing(Some(address)) => {
+                        Some(format!("{} ({})", index, address))
+                    }
+                    EndorsementCoverage::Missing(None) => Some(index.to_string()),
+                })
+                .collect();
+            if !missing.is_empty() {
+                writeln!(f, "Missing endorsements: {}", missing.join(", "))?;
+            }
         } else {
             writeln!(f, "Block {} not found", self.id)?;
         }
@@ -67,6 +161,9 @@ pub struct BlockSummary {
     pub creator: Address,
     /// the block parents
     pub parents: Vec<BlockId>,
+    /// ratio of filled endorsement indices, `None` if the block header is not known anymore
+    #[serde(default)]
+    pub endorsement_fill_ratio: Option<f64>,
 }
 
 impl std::fmt::Display for BlockSummary {
@@ -81,6 +178,9 @@ impl std::fmt::Display for BlockSummary {
         )?;
         writeln!(f, "Slot: {}", self.slot)?;
         writeln!(f, "Creator: {}", self.creator)?;
+        if let Some(ratio) = self.endorsement_fill_ratio {
+            writeln!(f, "Endorsements filled: {:.0}%", ratio * 100.0)?;
+        }
         writeln!(f, "Parents' IDs:")?;
         for parent in &self.parents {
             writeln!(f, "\t- {}", parent)?;
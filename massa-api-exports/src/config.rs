@@ -14,6 +14,8 @@ use serde::Deserialize;
 pub struct APIConfig {
     /// when looking for next draw we want to look at max `draw_lookahead_period_count`
     pub draw_lookahead_period_count: u64,
+    /// max number of past periods for which the endorsers drawn for a block are looked up
+    pub endorsement_attribution_lookback_period_count: u64,
     /// bind for the private API
     pub bind_private: SocketAddr,
     /// bind for the public API
@@ -62,6 +64,8 @@ pub struct APIConfig {
     pub max_parameter_size: u32,
     /// max gas in a block
     pub max_gas_per_block: u64,
+    /// max serialized size of the operations of a block
+    pub max_block_size: u32,
     /// base gas used by any operation
     pub base_operation_gas_cost: u64,
     /// Amount required for a SinglePass compilation (ExecuteSC operation)
@@ -82,6 +86,16 @@ pub struct APIConfig {
     pub chain_id: u64,
     /// Delta to compute upper bounds when fetching deferred credits
     pub deferred_credits_delta: MassaTime,
+    /// max number of slots of the range of a deferred credits request
+    pub max_deferred_credits_slot_range: u64,
     /// minimal fees to include an operation in a block
     pub minimal_fees: Amount,
+    /// how long a pool snapshot is retained to request its pages by sequence number
+    pub pool_snapshot_retention: MassaTime,
+    /// max number of denunciation evidences accepted per second from each client IP address
+    pub max_denunciation_evidences_per_second: u64,
+    /// max number of event data patterns in a filter
+    pub max_event_data_patterns: u64,
+    /// max length in bytes of an event data pattern
+    pub max_event_data_pattern_length: u64,
 }
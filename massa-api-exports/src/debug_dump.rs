@@ -0,0 +1,231 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::net::SocketAddr;
+
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    block_id::BlockId,
+    slot::Slot,
+    stats::{ConsensusStats, ExecutionBacklog, ExecutionStats},
+};
+use massa_pool_exports::PooledOperationSummary;
+use massa_signature::PublicKey;
+use serde::{Deserialize, Serialize};
+
+/// Sections to include in a debug dump of the node, all of them by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DebugDumpRequest {
+    /// include the pool section
+    pub pool: bool,
+    /// include the block graph section
+    pub graph: bool,
+    /// include the storage section
+    pub storage: bool,
+    /// include the block factory section
+    pub factory: bool,
+    /// include the execution section
+    pub execution: bool,
+    /// include the node settings section
+    pub config: bool,
+}
+
+impl Default for DebugDumpRequest {
+    fn default() -> Self {
+        DebugDumpRequest {
+            pool: true,
+            graph: true,
+            storage: true,
+            factory: true,
+            execution: true,
+            config: true,
+        }
+    }
+}
+
+/// Dump of the runtime state of the node, to help debugging it.
+///
+/// A section is `None` if it was not requested or if its component failed to provide it.
+/// The lists of items are capped: compare their length with the counts of the section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DebugDump {
+    /// pool state
+    pub pool: Option<PoolDump>,
+    /// block graph state
+    pub graph: Option<GraphDump>,
+    /// storage usage
+    pub storage: Option<StorageDump>,
+    /// block factory state
+    pub factory: Option<FactoryDump>,
+    /// execution progress
+    pub execution: Option<ExecutionDump>,
+    /// node settings, without the secrets
+    pub config: Option<ConfigDump>,
+}
+
+/// Pool section of a debug dump
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolDump {
+    /// number of operations in the pool
+    pub operation_count: usize,
+    /// number of endorsements in the pool
+    pub endorsement_count: usize,
+    /// number of denunciations in the pool
+    pub denunciation_count: usize,
+    /// final periods of each thread, as known by the pool
+    pub final_cs_periods: Vec<u64>,
+    /// best ranked operations of the pool, with their metadata
+    pub operations: Vec<PooledOperationSummary>,
+}
+
+/// Block graph section of a debug dump
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphDump {
+    /// consensus statistics
+    pub stats: Option<ConsensusStats>,
+    /// number of blocks not processed yet
+    pub incoming_block_count: usize,
+    /// number of blocks waiting for their slot
+    pub waiting_for_slot_block_count: usize,
+    /// number of blocks waiting for their dependencies
+    pub waiting_for_dependencies_block_count: usize,
+    /// number of active blocks
+    pub active_block_count: usize,
+    /// number of discarded blocks kept in cache
+    pub discarded_block_count: usize,
+    /// best parents and their period in each thread
+    pub best_parents: Vec<(BlockId, u64)>,
+    /// latest final block and its period in each thread
+    pub latest_final_blocks_periods: Vec<(BlockId, u64)>,
+    /// number of maximal cliques
+    pub clique_count: usize,
+    /// oldest blocks waiting for their dependencies, with the blocks they miss
+    pub waiting_for_dependencies: Vec<WaitingBlockDump>,
+    /// most recent discarded blocks, with their parents
+    pub discarded_blocks: Vec<DiscardedBlockDump>,
+}
+
+/// Block of a debug dump waiting for its dependencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitingBlockDump {
+    /// id of the block
+    pub block_id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// blocks not known yet, including the block itself if only its header was received
+    pub missing_dependencies: Vec<BlockId>,
+}
+
+/// Discarded block of a debug dump
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscardedBlockDump {
+    /// id of the block
+    pub block_id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// creator of the block
+    pub creator: Address,
+    /// parents of the block
+    pub parents: Vec<BlockId>,
+    /// why the block was discarded
+    pub reason: String,
+}
+
+/// Storage section of a debug dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageDump {
+    /// stored operations
+    pub operations: StorageUsageDump,
+    /// stored endorsements
+    pub endorsements: StorageUsageDump,
+    /// stored blocks
+    pub blocks: StorageUsageDump,
+    /// estimated size in bytes of all the stored objects
+    pub bytes: StorageUsageDump,
+}
+
+/// Usage of a storage category in a debug dump
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageUsageDump {
+    /// number of stored objects, or stored bytes
+    pub stored: usize,
+    /// cap of the category, if any
+    pub max: Option<usize>,
+}
+
+/// Block factory section of a debug dump
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FactoryDump {
+    /// latest slots for which an address of the node was drawn, the latest first
+    pub production_history: Vec<SlotProductionDump>,
+}
+
+/// Block production of the node at a slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotProductionDump {
+    /// slot of the block
+    pub slot: Slot,
+    /// drawn block producer
+    pub producer: Address,
+    /// outcome of the production
+    pub outcome: SlotProductionOutcomeDump,
+}
+
+/// Outcome of the block production of the node at a slot
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlotProductionOutcomeDump {
+    /// a block was produced
+    Produced {
+        /// id of the block
+        block_id: BlockId,
+        /// number of operations included in the block
+        operation_count: usize,
+        /// number of endorsements included in the block
+        endorsement_count: usize,
+        /// number of denunciations included in the block header
+        denunciation_count: usize,
+    },
+    /// no block was produced
+    Skipped {
+        /// why the block was not produced
+        reason: String,
+    },
+}
+
+/// Execution section of a debug dump
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionDump {
+    /// execution statistics and cursors
+    pub stats: ExecutionStats,
+    /// inputs waiting to be processed by the execution thread
+    pub backlog: ExecutionBacklog,
+}
+
+/// Node settings section of a debug dump.
+/// The node keypair is left out: only its public key is given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigDump {
+    /// public key of the node
+    pub node_public_key: PublicKey,
+    /// address of the node
+    pub node_address: Address,
+    /// chain id
+    pub chain_id: u64,
+    /// number of threads
+    pub thread_count: u8,
+    /// last start period
+    pub last_start_period: u64,
+    /// bind of the public API
+    pub bind_public: SocketAddr,
+    /// bind of the private API
+    pub bind_private: SocketAddr,
+    /// bind of the API v2
+    pub bind_api: SocketAddr,
+    /// maximum number of arguments per request
+    pub max_arguments: u64,
+    /// maximum number of connections
+    pub max_connections: u32,
+    /// minimal fees of the operations accepted by the node
+    pub minimal_fees: Amount,
+}
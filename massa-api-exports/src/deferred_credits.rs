@@ -0,0 +1,37 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, amount::Amount, slot::Slot};
+use serde::{Deserialize, Serialize};
+
+/// Slot range of the deferred credits to list
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DeferredCreditsRequest {
+    /// first slot of the range (included)
+    pub min_slot: Slot,
+    /// last slot of the range (included)
+    pub max_slot: Slot,
+    /// only list the credits of these addresses
+    #[serde(default)]
+    pub addresses: Option<Vec<Address>>,
+}
+
+/// Amount credited to an address at a slot
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DeferredCredit {
+    /// slot at which the amount is credited
+    pub slot: Slot,
+    /// credited address
+    pub address: Address,
+    /// credited amount
+    pub amount: Amount,
+}
+
+impl std::fmt::Display for DeferredCredit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} to {} at slot {}",
+            self.amount, self.address, self.slot
+        )
+    }
+}
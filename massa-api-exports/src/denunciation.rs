@@ -0,0 +1,62 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_signature::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+
+/// Signed block header or endorsement, in the same form as an operation input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecureShareInput {
+    /// public key of the creator
+    pub creator_public_key: PublicKey,
+    /// signature of the content
+    pub signature: Signature,
+    /// serialized content, without the signature and the public key
+    pub serialized_content: Vec<u8>,
+}
+
+/// Two objects signed by the same staker for the same slot, as observed by a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DenunciationEvidence {
+    /// two different block headers
+    BlockHeaders(SecureShareInput, SecureShareInput),
+    /// two different endorsements with the same index
+    Endorsements(SecureShareInput, SecureShareInput),
+}
+
+/// Reason why a denunciation evidence is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceRejection {
+    /// an object of the pair cannot be deserialized
+    InvalidEncoding,
+    /// the signature of an object of the pair is invalid
+    InvalidSignature,
+    /// the objects are the same, or differ in slot, index or creator
+    NotConflicting,
+    /// the slot is before the last start of the network, or final for too long to be denounced
+    Expired,
+    /// the slot is too far ahead of the current slot to be denounced yet
+    TooFarInFuture,
+    /// a denunciation of the same slot, and endorsement index, was already executed
+    AlreadyDenounced,
+}
+
+/// Outcome of the submission of a denunciation evidence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvidenceOutcome {
+    /// both objects were forwarded to the denunciation pool
+    Accepted,
+    /// the evidence was dropped
+    Rejected(EvidenceRejection),
+}
+
+impl std::fmt::Display for EvidenceOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvidenceOutcome::Accepted => write!(f, "accepted"),
+            EvidenceOutcome::Rejected(reason) => write!(f, "rejected: {:?}", reason),
+        }
+    }
+}
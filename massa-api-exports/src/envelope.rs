@@ -0,0 +1,26 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::version::Version;
+use serde::{Deserialize, Serialize};
+
+/// HTTP header requesting the public API to wrap the results of its responses in a
+/// [`ResponseEnvelope`], with the value `1`.
+/// The node sets it on the responses it wrapped.
+pub const ENVELOPE_HEADER: &str = "massa-envelope";
+
+/// Revision of the responses of the public API,
+/// incremented on every breaking change of a response
+pub const API_REVISION: u32 = 1;
+
+/// Result of a public API response, along with the node it comes from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseEnvelope<T> {
+    /// version of the node
+    pub node_version: Version,
+    /// id of the chain of the node
+    pub chain_id: u64,
+    /// revision of the API responses
+    pub api_revision: u32,
+    /// result of the call
+    pub result: T,
+}
@@ -1,12 +1,17 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_execution_exports::{GasProfileSection, ReadOnlyCallTrace, ReadOnlyErrorKind};
 use massa_final_state::StateChanges;
+use massa_hash::Hash;
 use massa_models::{
     address::Address, amount::Amount, block_id::BlockId, operation::OperationId,
     output_event::SCOutputEvent, slot::Slot,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::VecDeque, fmt::Display};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Display,
+};
 
 /// The result of the read-only execution.
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -30,6 +35,17 @@ pub struct ExecuteReadOnlyResponse {
     pub gas_cost: u64,
     /// state changes caused by the execution step
     pub state_changes: StateChanges,
+    /// cause of the failure when the execution itself failed, `None` on success or when the
+    /// request was rejected before being executed
+    #[serde(default)]
+    pub error_kind: Option<ReadOnlyErrorKind>,
+    /// gas consumed by each section of the execution, set only when profiling was requested
+    #[serde(default)]
+    pub gas_profile: Option<BTreeMap<GasProfileSection, u64>>,
+    /// tree of the calls made by the execution, set only when the trace was requested,
+    /// including for a failed execution
+    #[serde(default)]
+    pub call_trace: Option<ReadOnlyCallTrace>,
 }
 
 impl Display for ExecuteReadOnlyResponse {
@@ -39,12 +55,25 @@ impl Display for ExecuteReadOnlyResponse {
             f,
             "Result: {}",
             match &self.result {
-                ReadOnlyResult::Error(e) =>
-                    format!("an error occurred during the execution: {}", e),
+                ReadOnlyResult::Error(e) => match self.error_kind {
+                    Some(kind) =>
+                        format!("an error occurred during the execution ({}): {}", kind, e),
+                    None => format!("an error occurred during the execution: {}", e),
+                },
                 ReadOnlyResult::Ok(ret) => format!("success, returned value: {:?}", ret),
             }
         )?;
         writeln!(f, "Gas cost: {}", self.gas_cost)?;
+        if let Some(gas_profile) = &self.gas_profile {
+            writeln!(f, "Gas profile:")?;
+            for (section, gas) in gas_profile {
+                writeln!(f, "\t{:?}: {}", section, gas)?;
+            }
+        }
+        if let Some(call_trace) = &self.call_trace {
+            writeln!(f, "Call trace:")?;
+            fmt_call_trace(f, call_trace, 1)?;
+        }
         if !self.output_events.is_empty() {
             writeln!(f, "Generated events:",)?;
             for event in self.output_events.iter() {
@@ -55,6 +84,36 @@ impl Display for ExecuteReadOnlyResponse {
     }
 }
 
+/// Write a call of a read-only execution trace and its sub-calls, one per line
+fn fmt_call_trace(
+    f: &mut std::fmt::Formatter<'_>,
+    call_trace: &ReadOnlyCallTrace,
+    depth: usize,
+) -> std::fmt::Result {
+    write!(f, "{}{}", "\t".repeat(depth), call_trace.target)?;
+    if let Some(function) = &call_trace.function {
+        write!(f, " {}", function)?;
+    }
+    if let Some(parameter_size) = call_trace.parameter_size {
+        write!(f, " ({} bytes of parameter)", parameter_size)?;
+    }
+    write!(f, ", coins: {}", call_trace.coins)?;
+    if let Some(gas_used) = call_trace.gas_used {
+        write!(f, ", gas used: {}", gas_used)?;
+    }
+    if call_trace.omitted_calls > 0 {
+        write!(f, ", omitted calls: {}", call_trace.omitted_calls)?;
+    }
+    match &call_trace.error {
+        Some(error) => writeln!(f, ", failed: {}", error)?,
+        None => writeln!(f)?,
+    }
+    for sub_call in call_trace.sub_calls.iter() {
+        fmt_call_trace(f, sub_call, depth + 1)?;
+    }
+    Ok(())
+}
+
 /// read only bytecode execution request
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct ReadOnlyBytecodeExecution {
@@ -68,6 +127,15 @@ pub struct ReadOnlyBytecodeExecution {
     pub operation_datastore: Option<Vec<u8>>,
     /// fee
     pub fee: Option<Amount>,
+    /// report the gas consumed by each section of the execution, which may slightly slow it down
+    #[serde(default)]
+    pub profile: bool,
+    /// seed of the random number generator, to draw the same numbers across executions
+    #[serde(default)]
+    pub rng_seed: Option<[u8; 32]>,
+    /// record the tree of the calls made by the execution
+    #[serde(default)]
+    pub include_trace: bool,
 }
 
 /// read SC call request
@@ -87,6 +155,15 @@ pub struct ReadOnlyCall {
     pub coins: Option<Amount>,
     /// fee
     pub fee: Option<Amount>,
+    /// report the gas consumed by each section of the execution, which may slightly slow it down
+    #[serde(default)]
+    pub profile: bool,
+    /// seed of the random number generator, to draw the same numbers across executions
+    #[serde(default)]
+    pub rng_seed: Option<[u8; 32]>,
+    /// record the tree of the calls made by the execution
+    #[serde(default)]
+    pub include_trace: bool,
 }
 
 /// Context of the transfer
@@ -121,3 +198,110 @@ pub struct Transfer {
     /// Context
     pub context: TransferContext,
 }
+
+/// Gas budget of the execution of a slot.
+/// The asynchronous messages are projected from the candidate pool, assuming none of them is executed before the slot.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SlotExecutionBudget {
+    /// slot
+    pub slot: Slot,
+    /// maximum gas of the asynchronous messages executed at the slot
+    pub max_async_gas: u64,
+    /// number of asynchronous messages that can be executed at the slot
+    pub ready_async_message_count: u64,
+    /// gas reserved by the asynchronous messages that can be executed at the slot, can exceed `max_async_gas`
+    pub ready_async_gas: u64,
+    /// number of asynchronous messages that would be executed at the slot
+    pub projected_async_message_count: u64,
+    /// gas reserved by the asynchronous messages that would be executed at the slot
+    pub projected_async_gas: u64,
+    /// gas still available to the asynchronous messages at the slot
+    pub async_gas_headroom: u64,
+    /// maximum gas of the operations of the block of the slot
+    pub max_block_gas: u64,
+}
+
+impl From<massa_execution_exports::SlotExecutionBudget> for SlotExecutionBudget {
+    fn from(budget: massa_execution_exports::SlotExecutionBudget) -> Self {
+        SlotExecutionBudget {
+            slot: budget.slot,
+            max_async_gas: budget.max_async_gas,
+            ready_async_message_count: budget.ready_async_message_count,
+            ready_async_gas: budget.ready_async_gas,
+            projected_async_message_count: budget.projected_async_message_count,
+            projected_async_gas: budget.projected_async_gas,
+            async_gas_headroom: budget.async_gas_headroom(),
+            max_block_gas: budget.max_block_gas,
+        }
+    }
+}
+
+impl Display for SlotExecutionBudget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Slot: {}", self.slot)?;
+        writeln!(
+            f,
+            "Asynchronous messages: {} ready ({} gas), {} projected ({} gas of {})",
+            self.ready_async_message_count,
+            self.ready_async_gas,
+            self.projected_async_message_count,
+            self.projected_async_gas,
+            self.max_async_gas
+        )?;
+        writeln!(f, "Block operations: {} gas", self.max_block_gas)
+    }
+}
+
+/// Digest of the state changes of an executed slot, for subscribers mirroring the state to check
+/// that they applied the changes correctly
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SlotExecutionDigest {
+    /// executed slot
+    pub slot: Slot,
+    /// block executed at the slot, if any
+    pub block_id: Option<BlockId>,
+    /// whether the slot is final, a speculative execution may be reverted by a blockclique change
+    pub is_final: bool,
+    /// number of ledger entries changed
+    pub ledger_changes_count: usize,
+    /// number of asynchronous messages emitted
+    pub async_messages_emitted: usize,
+    /// number of asynchronous messages taken out of the pool, executed or not
+    pub async_messages_taken: usize,
+    /// digest of the final state after applying the slot, set once the slot is final
+    pub final_state: Option<FinalStateDigest>,
+}
+
+/// Digest of the final state after applying a final slot
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FinalStateDigest {
+    /// change id of the final state, which is the final slot
+    pub change_id: Slot,
+    /// fingerprint of the final state
+    pub fingerprint: Hash,
+    /// fingerprint of each component of the final state, by name
+    pub component_fingerprints: BTreeMap<String, Hash>,
+}
+
+impl From<&massa_execution_exports::SlotExecutionOutput> for SlotExecutionDigest {
+    fn from(slot_output: &massa_execution_exports::SlotExecutionOutput) -> Self {
+        let output = slot_output.output();
+        let digest = &output.digest;
+        SlotExecutionDigest {
+            slot: output.slot,
+            block_id: output.block_info.as_ref().map(|info| info.block_id),
+            is_final: slot_output.is_final(),
+            ledger_changes_count: digest.ledger_changes_count,
+            async_messages_emitted: digest.async_messages_emitted,
+            async_messages_taken: digest.async_messages_taken,
+            final_state: digest
+                .final_state
+                .as_ref()
+                .map(|final_state| FinalStateDigest {
+                    change_id: final_state.change_id,
+                    fingerprint: final_state.fingerprint,
+                    component_fingerprints: final_state.component_fingerprints.clone(),
+                }),
+        }
+    }
+}
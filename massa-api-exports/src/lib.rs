@@ -17,8 +17,16 @@ pub mod block;
 pub mod config;
 /// datastore serialization / deserialization
 pub mod datastore;
+/// node runtime state dumps
+pub mod debug_dump;
+/// deferred credits
+pub mod deferred_credits;
+/// denunciation evidences submitted by the clients
+pub mod denunciation;
 /// endorsements
 pub mod endorsement;
+/// envelope of the public API responses
+pub mod envelope;
 /// models error
 pub mod error;
 /// execution
@@ -31,10 +39,18 @@ pub mod node;
 pub mod operation;
 /// page
 pub mod page;
+/// pool snapshots
+pub mod pool;
 /// rolls
 pub mod rolls;
+/// slashing statistics
+pub mod slashing;
 /// slots
 pub mod slot;
+/// sequenced subscription notifications
+pub mod subscription;
+/// human-readable summaries of the API responses
+pub mod summary;
 
 /// Dumb utils function to display nicely boolean value
 fn display_if_true(value: bool, text: &str) -> String {
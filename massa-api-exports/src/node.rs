@@ -1,8 +1,13 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::amount::Amount;
+use massa_models::block::{FilledBlock, SecureShareBlock};
+use massa_models::block_header::SecuredHeader;
+use massa_models::canonical_json::CanonicalJson;
+use massa_models::endorsement::SecureShareEndorsement;
 use massa_models::node::NodeId;
-use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
+use massa_models::operation::SecureShareOperation;
+use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats, NetworkTrafficStats};
 use massa_models::{config::CompactConfig, slot::Slot, version::Version};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
@@ -34,10 +39,19 @@ pub struct NodeStatus {
     pub next_slot: Slot,
     /// consensus stats
     pub consensus_stats: ConsensusStats,
+    /// number of cliques currently in the block graph
+    #[serde(default)]
+    pub clique_count: usize,
+    /// fitness difference between the blockclique and the best alternative clique, if any
+    #[serde(default)]
+    pub fitness_gap_to_second_clique: Option<u64>,
     /// pool stats (operation count and endorsement count)
     pub pool_stats: (usize, usize),
     /// network stats
     pub network_stats: NetworkStats,
+    /// connection counts and traffic exchanged with peers since the node started, if available
+    #[serde(default)]
+    pub network_traffic: Option<NetworkTrafficStats>,
     /// execution stats
     pub execution_stats: ExecutionStats,
     /// compact configuration
@@ -48,6 +62,8 @@ pub struct NodeStatus {
     pub minimal_fees: Amount,
 }
 
+impl CanonicalJson for NodeStatus {}
+
 impl std::fmt::Display for NodeStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Node's ID: {}", self.node_id)?;
@@ -72,12 +88,22 @@ impl std::fmt::Display for NodeStatus {
 
         writeln!(f, "{}", self.consensus_stats)?;
 
+        writeln!(f, "Clique count: {}", self.clique_count)?;
+        match self.fitness_gap_to_second_clique {
+            Some(gap) => writeln!(f, "Fitness gap to second clique: {}", gap)?,
+            None => writeln!(f, "No alternative clique")?,
+        }
+        writeln!(f)?;
+
         writeln!(f, "Pool stats:")?;
         writeln!(f, "\tOperations count: {}", self.pool_stats.0)?;
         writeln!(f, "\tEndorsements count: {}", self.pool_stats.1)?;
         writeln!(f)?;
 
         writeln!(f, "{}", self.network_stats)?;
+        if let Some(network_traffic) = &self.network_traffic {
+            writeln!(f, "{}", network_traffic)?;
+        }
 
         writeln!(f, "{}", self.execution_stats)?;
 
@@ -94,3 +120,18 @@ impl std::fmt::Display for NodeStatus {
         Ok(())
     }
 }
+
+/// Event sent on the multiplexed node events subscription
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NodeEvent {
+    /// new produced block
+    Block(SecureShareBlock),
+    /// new produced block header
+    BlockHeader(SecuredHeader),
+    /// new produced block with its operations content
+    FilledBlock(FilledBlock),
+    /// new received operation
+    Operation(SecureShareOperation),
+    /// new received endorsement
+    Endorsement(SecureShareEndorsement),
+}
@@ -1,8 +1,10 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::{
+    address::Address,
     block_id::BlockId,
-    operation::{OperationId, SecureShareOperation},
+    canonical_json::CanonicalJson,
+    operation::{OperationId, OperationType, SecureShareOperation},
 };
 
 use massa_signature::{PublicKey, Signature};
@@ -39,15 +41,25 @@ pub struct OperationInfo {
     pub operation: SecureShareOperation,
     /// true if the operation execution succeeded, false if failed, None means unknown
     pub op_exec_status: Option<bool>,
+    /// true if the operation recently expired without being executed
+    #[serde(default)]
+    pub is_expired: bool,
+    /// best confirmation score (from 0 to 100) of the active blocks including the operation,
+    /// None if no active block includes it
+    #[serde(default)]
+    pub confirmation_score: Option<u8>,
 }
 
+impl CanonicalJson for OperationInfo {}
+
 impl std::fmt::Display for OperationInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(
             f,
-            "Operation {}{}{}{}",
+            "Operation {}{}{}{}{}",
             self.id,
             display_if_true(self.in_pool, "in pool"),
+            display_if_true(self.is_expired, "expired"),
             display_option_bool(
                 self.is_operation_final,
                 "operation is final",
@@ -65,6 +77,81 @@ impl std::fmt::Display for OperationInfo {
     }
 }
 
+/// Kind of an operation, without its content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OperationKind {
+    /// transfer coins
+    Transaction,
+    /// buy rolls
+    RollBuy,
+    /// sell rolls
+    RollSell,
+    /// execute a smart contract
+    ExecuteSC,
+    /// call a stored smart contract
+    CallSC,
+}
+
+impl From<&OperationType> for OperationKind {
+    fn from(op: &OperationType) -> Self {
+        match op {
+            OperationType::Transaction { .. } => OperationKind::Transaction,
+            OperationType::RollBuy { .. } => OperationKind::RollBuy,
+            OperationType::RollSell { .. } => OperationKind::RollSell,
+            OperationType::ExecuteSC { .. } => OperationKind::ExecuteSC,
+            OperationType::CallSC { .. } => OperationKind::CallSC,
+        }
+    }
+}
+
+impl std::fmt::Display for OperationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperationKind::Transaction => write!(f, "transaction"),
+            OperationKind::RollBuy => write!(f, "roll buy"),
+            OperationKind::RollSell => write!(f, "roll sell"),
+            OperationKind::ExecuteSC => write!(f, "execute SC"),
+            OperationKind::CallSC => write!(f, "call SC"),
+        }
+    }
+}
+
+/// Filter applied by the node on the new operations stream.
+/// An operation is sent if it matches at least one of the criteria.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationStreamFilter {
+    /// operations created by one of these addresses
+    #[serde(default)]
+    pub creator_addresses: Vec<Address>,
+    /// operations targeting one of these addresses (transaction recipient or called smart contract)
+    #[serde(default)]
+    pub recipient_addresses: Vec<Address>,
+    /// operations of one of these kinds
+    #[serde(default)]
+    pub operation_kinds: Vec<OperationKind>,
+}
+
+impl OperationStreamFilter {
+    /// true if the filter has no criteria
+    pub fn is_empty(&self) -> bool {
+        self.creator_addresses.is_empty()
+            && self.recipient_addresses.is_empty()
+            && self.operation_kinds.is_empty()
+    }
+
+    /// true if the operation matches at least one of the filter criteria
+    pub fn matches(&self, operation: &SecureShareOperation) -> bool {
+        self.creator_addresses
+            .contains(&operation.content_creator_address)
+            || operation
+                .get_target_address()
+                .map_or(false, |addr| self.recipient_addresses.contains(&addr))
+            || self
+                .operation_kinds
+                .contains(&OperationKind::from(&operation.content.op))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use jsonrpsee::core::__reexports::serde_json::{self, Value};
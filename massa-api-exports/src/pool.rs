@@ -0,0 +1,78 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, slot::Slot};
+use massa_pool_exports::{
+    PoolEvent, PoolEventKind, PoolSnapshot, PooledEndorsementInfo, PooledOperationInfo,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::page::{PageRequest, PagedVec, PagedVecV2};
+
+/// Kind of items of the pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolItemKind {
+    /// operations
+    Operations,
+    /// endorsements
+    Endorsements,
+}
+
+/// Items of a page of a pool snapshot
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolSnapshotItems {
+    /// metadata of the pooled operations
+    Operations(PagedVecV2<PooledOperationInfo>),
+    /// metadata of the pooled endorsements
+    Endorsements(PagedVecV2<PooledEndorsementInfo>),
+}
+
+/// Page of a snapshot of the pool.
+///
+/// The items are not retained by the node: an id may refer to an item that has since left the pool.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PoolSnapshotPage {
+    /// sequence number of the snapshot
+    pub sequence: u64,
+    /// latest slot at the time the snapshot was taken
+    pub slot: Option<Slot>,
+    /// requested page of items
+    pub items: PoolSnapshotItems,
+}
+
+impl PoolSnapshotPage {
+    /// Build the requested page of a snapshot
+    pub fn new<T: Clone + Serialize>(
+        snapshot: PoolSnapshot<T>,
+        page_request: Option<PageRequest>,
+        items: impl FnOnce(PagedVecV2<T>) -> PoolSnapshotItems,
+    ) -> Self {
+        PoolSnapshotPage {
+            sequence: snapshot.sequence,
+            slot: snapshot.slot,
+            items: items(PagedVec::new(snapshot.items.to_vec(), page_request).into()),
+        }
+    }
+}
+
+/// Filter applied by the node on the pool events stream.
+/// An event is sent if it matches all the criteria, an empty criterion matching any event.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolEventFilter {
+    /// events of one of these kinds
+    #[serde(default)]
+    pub kinds: Vec<PoolEventKind>,
+    /// events about an operation or endorsement created by one of these addresses
+    #[serde(default)]
+    pub creator_addresses: Vec<Address>,
+}
+
+impl PoolEventFilter {
+    /// true if the event matches all the filter criteria
+    pub fn matches(&self, event: &PoolEvent) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&event.kind))
+            && (self.creator_addresses.is_empty()
+                || self.creator_addresses.contains(&event.creator))
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use serde::{Deserialize, Serialize};
+
+/// Final slashing of the executed denunciations targeting the slots of a cycle
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SlashingStats {
+    /// cycle of the denounced slots
+    pub cycle: u64,
+    /// number of executed denunciations
+    pub denunciation_count: u64,
+    /// number of slashed rolls
+    pub slashed_rolls: u64,
+    /// number of distinct denounced addresses
+    pub slashed_address_count: u64,
+}
+
+impl From<massa_execution_exports::SlashingStats> for SlashingStats {
+    fn from(stats: massa_execution_exports::SlashingStats) -> Self {
+        SlashingStats {
+            cycle: stats.cycle,
+            denunciation_count: stats.denunciation_count,
+            slashed_rolls: stats.slashed_rolls,
+            slashed_address_count: stats.slashed_address_count,
+        }
+    }
+}
+
+impl std::fmt::Display for SlashingStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cycle {}: {} denunciation(s), {} roll(s) slashed from {} address(es)",
+            self.cycle, self.denunciation_count, self.slashed_rolls, self.slashed_address_count
+        )
+    }
+}
@@ -12,3 +12,9 @@ pub struct SlotAmount {
     /// amount
     pub amount: Amount,
 }
+
+impl std::fmt::Display for SlotAmount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at slot {}", self.amount, self.slot)
+    }
+}
@@ -0,0 +1,13 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use serde::{Deserialize, Serialize};
+
+/// Notification of a sequenced subscription, numbered by the node
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SubItem<T> {
+    /// number of the notification in the subscription, starting at 0.
+    /// The notifications the node dropped for a lagging subscriber still consume their number.
+    pub seq: u64,
+    /// the notification itself
+    pub item: T,
+}
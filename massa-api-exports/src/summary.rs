@@ -0,0 +1,819 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Human-readable summaries of the common API responses, for command line tools.
+//!
+//! A summary is a list of `label: value` lines, with all the values starting on the same
+//! column. A value spanning several lines is continued on that column. The formats are stable:
+//! * amounts use the `Amount` display, e.g. `12.5`
+//! * slots are written `period.thread`, e.g. `12.3`, followed by their position relative to
+//!   the current slot when it is known, e.g. `12.3 (4 slots ago)`
+//! * durations only show their non-zero units, e.g. `1h 5s`
+//! * fields the node did not fill are written `unknown`, empty lists are written `none`
+
+use std::cmp::Ordering;
+
+use massa_models::slot::Slot;
+use massa_time::MassaTime;
+
+use crate::address::AddressInfo;
+use crate::block::{BlockInfo, EndorsementCoverage};
+use crate::node::NodeStatus;
+use crate::operation::{OperationInfo, OperationKind};
+
+/// column at which the values start
+const VALUE_COLUMN: usize = 20;
+
+/// Timing parameters used to locate slots relative to the current one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotTiming {
+    /// number of threads
+    pub thread_count: u8,
+    /// latest slot, `None` if unknown or before genesis
+    pub current_slot: Option<Slot>,
+}
+
+impl SlotTiming {
+    /// Timing of the node when `status` was retrieved
+    pub fn from_status(status: &NodeStatus) -> Self {
+        SlotTiming {
+            thread_count: status.config.thread_count,
+            current_slot: status.last_slot,
+        }
+    }
+}
+
+/// Format a slot as `period.thread`
+pub fn format_slot(slot: &Slot) -> String {
+    format!("{}.{}", slot.period, slot.thread)
+}
+
+/// Format a slot as `period.thread`, followed by its distance to the current slot if it is known
+pub fn format_relative_slot(slot: &Slot, timing: &SlotTiming) -> String {
+    let Some(current_slot) = timing.current_slot else {
+        return format_slot(slot);
+    };
+    let relative = match slot.cmp(&current_slot) {
+        Ordering::Equal => Ok("current slot".to_string()),
+        Ordering::Less => current_slot
+            .slots_since(slot, timing.thread_count)
+            .map(|count| format!("{} ago", plural(count, "slot"))),
+        Ordering::Greater => slot
+            .slots_since(&current_slot, timing.thread_count)
+            .map(|count| format!("in {}", plural(count, "slot"))),
+    };
+    match relative {
+        Ok(relative) => format!("{} ({})", format_slot(slot), relative),
+        Err(_) => format_slot(slot),
+    }
+}
+
+/// Format a duration with its non-zero units among days, hours, minutes and seconds.
+/// Durations shorter than a second are written in milliseconds.
+pub fn format_duration(duration: MassaTime) -> String {
+    let seconds = duration.as_millis() / 1000;
+    if seconds == 0 {
+        return format!("{}ms", duration.as_millis());
+    }
+    [
+        (seconds / 86400, "d"),
+        (seconds / 3600 % 24, "h"),
+        (seconds / 60 % 60, "m"),
+        (seconds % 60, "s"),
+    ]
+    .iter()
+    .filter(|(value, _)| *value > 0)
+    .map(|(value, unit)| format!("{}{}", value, unit))
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Summary of the status of a node
+pub fn summarize_status(status: &NodeStatus) -> String {
+    let timing = SlotTiming::from_status(status);
+    let mut summary = Summary::default();
+    summary.line("Node ID", &status.node_id);
+    summary.line(
+        "IP",
+        status
+            .node_ip
+            .map_or_else(|| "none".to_string(), |ip| ip.to_string()),
+    );
+    summary.line("Version", &status.version);
+    summary.line("Chain ID", status.chain_id);
+    summary.line("Current time", status.current_time.format_instant());
+    summary.line(
+        "Last slot",
+        status.last_slot.map_or_else(
+            || "none (before genesis)".to_string(),
+            |slot| format_slot(&slot),
+        ),
+    );
+    summary.line(
+        "Next slot",
+        format_relative_slot(&status.next_slot, &timing),
+    );
+    summary.line(
+        "Cycle",
+        format!(
+            "{} (next in {})",
+            status.current_cycle,
+            format_duration(status.next_cycle_time.saturating_sub(status.current_time))
+        ),
+    );
+    summary.line(
+        "Final cursor",
+        format_relative_slot(&status.execution_stats.final_cursor, &timing),
+    );
+    summary.line(
+        "Candidate cursor",
+        format_relative_slot(&status.execution_stats.active_cursor, &timing),
+    );
+    summary.line(
+        "Cliques",
+        match status.fitness_gap_to_second_clique {
+            Some(gap) => format!("{} (fitness gap {})", status.clique_count, gap),
+            None => format!("{} (no alternative)", status.clique_count),
+        },
+    );
+    summary.line(
+        "Pool",
+        format!(
+            "{}, {}",
+            plural(status.pool_stats.0 as u64, "operation"),
+            plural(status.pool_stats.1 as u64, "endorsement")
+        ),
+    );
+    summary.line(
+        "Peers",
+        format!(
+            "{} in, {} out, {} banned",
+            status.network_stats.in_connection_count,
+            status.network_stats.out_connection_count,
+            status.network_stats.banned_peer_count
+        ),
+    );
+    summary.line("Minimal fees", status.minimal_fees);
+    summary.0
+}
+
+/// Summary of the state of an address
+pub fn summarize_address(info: &AddressInfo, timing: &SlotTiming) -> String {
+    let mut summary = Summary::default();
+    summary.line(
+        "Address",
+        format!("{} (thread {})", info.address, info.thread),
+    );
+    summary.line(
+        "Balance",
+        format!(
+            "final {}, candidate {}",
+            info.final_balance, info.candidate_balance
+        ),
+    );
+    summary.line(
+        "Rolls",
+        format!(
+            "final {}, candidate {}, active {}",
+            info.final_roll_count,
+            info.candidate_roll_count,
+            info.cycle_infos
+                .last()
+                .and_then(|cycle_info| cycle_info.active_rolls)
+                .map_or_else(|| "unknown".to_string(), |rolls| rolls.to_string())
+        ),
+    );
+    summary.list(
+        "Locked coins",
+        info.deferred_credits.iter().map(|credit| {
+            format!(
+                "{} until {}",
+                credit.amount,
+                format_relative_slot(&credit.slot, timing)
+            )
+        }),
+    );
+    summary.line(
+        "Block draws",
+        match info.next_block_draws.iter().min() {
+            Some(slot) => format!(
+                "{}, next at {}",
+                info.next_block_draws.len(),
+                format_relative_slot(slot, timing)
+            ),
+            None => "none".to_string(),
+        },
+    );
+    summary.line(
+        "Endorsement draws",
+        match info
+            .next_endorsement_draws
+            .iter()
+            .min_by_key(|draw| draw.slot)
+        {
+            Some(draw) => format!(
+                "{}, next at {} index {}",
+                info.next_endorsement_draws.len(),
+                format_relative_slot(&draw.slot, timing),
+                draw.index
+            ),
+            None => "none".to_string(),
+        },
+    );
+    summary.list(
+        "Cycles",
+        info.cycle_infos.iter().map(|cycle_info| {
+            format!(
+                "{} ({}): {} produced, {} missed, {}",
+                cycle_info.cycle,
+                if cycle_info.is_final {
+                    "final"
+                } else {
+                    "candidate"
+                },
+                cycle_info.ok_count,
+                cycle_info.nok_count,
+                cycle_info.active_rolls.map_or_else(
+                    || "active rolls unknown".to_string(),
+                    |rolls| plural(rolls, "active roll")
+                )
+            )
+        }),
+    );
+    summary.0
+}
+
+/// Summary of an operation and of its inclusion
+pub fn summarize_operation(info: &OperationInfo, timing: &SlotTiming) -> String {
+    let mut summary = Summary::default();
+    summary.line("Operation", &info.id);
+    summary.line("Type", OperationKind::from(&info.operation.content.op));
+    summary.line("Creator", &info.operation.content_creator_address);
+    summary.line("Fee", info.operation.content.fee);
+    summary.line(
+        "Expires",
+        format_relative_slot(
+            &Slot::new(info.operation.content.expire_period, info.thread),
+            timing,
+        ),
+    );
+    summary.line("In pool", if info.in_pool { "yes" } else { "no" });
+    summary.list("In blocks", info.in_blocks.iter());
+    summary.line(
+        "Finality",
+        match info.is_operation_final {
+            Some(true) => "final",
+            Some(false) => "not final",
+            None => "unknown",
+        },
+    );
+    summary.line(
+        "Execution",
+        match info.op_exec_status {
+            Some(true) => "success",
+            Some(false) => "failure",
+            None if info.is_expired => "expired",
+            None => "unknown",
+        },
+    );
+    summary.0
+}
+
+/// Summary of a block and of its status
+pub fn summarize_block(info: &BlockInfo, timing: &SlotTiming) -> String {
+    let mut summary = Summary::default();
+    summary.line("Block", &info.id);
+    let Some(content) = &info.content else {
+        summary.line("Status", "not found");
+        return summary.0;
+    };
+    let status: Vec<&str> = [
+        (content.is_final, "final"),
+        (content.is_candidate, "candidate"),
+        (content.is_in_blockclique, "blockclique"),
+        (content.is_discarded, "discarded"),
+    ]
+    .into_iter()
+    .filter_map(|(is_set, status)| is_set.then_some(status))
+    .collect();
+    summary.line(
+        "Status",
+        if status.is_empty() {
+            "unknown".to_string()
+        } else {
+            status.join(", ")
+        },
+    );
+    let header = &content.block.header;
+    summary.line("Slot", format_relative_slot(&header.content.slot, timing));
+    summary.line("Creator", &header.content_creator_address);
+    summary.line("Parents", header.content.parents.len());
+    summary.line("Operations", content.block.operations.len());
+    if content.endorsement_coverage.is_empty() {
+        summary.line("Endorsements", header.content.endorsements.len());
+    } else {
+        let missing: Vec<String> = content
+            .endorsement_coverage
+            .iter()
+            .enumerate()
+            .filter_map(|(index, state)| match state {
+                EndorsementCoverage::Included(_) => None,
+                EndorsementCoverage::Missing(Some(address)) => {
+                    Some(format!("{} ({})", index, address))
+                }
+                EndorsementCoverage::Missing(None) => Some(index.to_string()),
+            })
+            .collect();
+        let included = content.endorsement_coverage.len() - missing.len();
+        summary.line(
+            "Endorsements",
+            if missing.is_empty() {
+                format!("{}/{}", included, content.endorsement_coverage.len())
+            } else {
+                format!(
+                    "{}/{}, missing {}",
+                    included,
+                    content.endorsement_coverage.len(),
+                    missing.join(", ")
+                )
+            },
+        );
+    }
+    summary.0
+}
+
+/// `count` followed by `noun`, in the plural if `count` is not 1
+fn plural(count: u64, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Summary being written
+#[derive(Default)]
+struct Summary(String);
+
+impl Summary {
+    /// Add a `label: value` line
+    fn line(&mut self, label: &str, value: impl std::fmt::Display) {
+        let label = format!("{}:", label);
+        self.0.push_str(&format!(
+            "{:<width$}{}\n",
+            label,
+            value,
+            width = VALUE_COLUMN
+        ));
+    }
+
+    /// Add a value per line, or `none` if there is no value
+    fn list<T: std::fmt::Display>(&mut self, label: &str, values: impl Iterator<Item = T>) {
+        let mut values = values.peekable();
+        if values.peek().is_none() {
+            self.line(label, "none");
+            return;
+        }
+        for (index, value) in values.enumerate() {
+            if index == 0 {
+                self.line(label, value);
+            } else {
+                self.0
+                    .push_str(&format!("{:width$}{}\n", "", value, width = VALUE_COLUMN));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    use massa_hash::Hash;
+    use massa_models::address::{Address, ExecutionAddressCycleInfo};
+    use massa_models::amount::Amount;
+    use massa_models::block::Block;
+    use massa_models::block_header::{BlockHeader, BlockHeaderSerializer};
+    use massa_models::block_id::BlockId;
+    use massa_models::config::{CompactConfig, CHAINID, THREAD_COUNT};
+    use massa_models::endorsement::EndorsementId;
+    use massa_models::node::NodeId;
+    use massa_models::operation::{Operation, OperationSerializer, OperationType};
+    use massa_models::secure_share::{Id, SecureShareContent};
+    use massa_models::slot::IndexedSlot;
+    use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
+    use massa_models::version::Version;
+    use massa_signature::KeyPair;
+
+    use super::*;
+    use crate::block::BlockInfoContent;
+    use crate::slot::SlotAmount;
+
+    /// timing with the current slot in the middle of period 12
+    fn timing() -> SlotTiming {
+        SlotTiming {
+            thread_count: THREAD_COUNT,
+            current_slot: Some(Slot::new(12, 3)),
+        }
+    }
+
+    fn node_status(keypair: &KeyPair) -> NodeStatus {
+        let current_time = MassaTime::from_millis(1_640_995_200_000);
+        NodeStatus {
+            node_id: NodeId::new(keypair.get_public_key()),
+            node_ip: None,
+            version: Version::from_str("SAND.2.4").unwrap(),
+            current_time,
+            current_cycle: 5,
+            current_cycle_time: current_time.saturating_sub(MassaTime::from_millis(10_000)),
+            next_cycle_time: current_time.saturating_add(MassaTime::from_millis(64_500)),
+            connected_nodes: BTreeMap::new(),
+            last_slot: Some(Slot::new(12, 3)),
+            next_slot: Slot::new(12, 4),
+            consensus_stats: ConsensusStats {
+                start_timespan: current_time,
+                end_timespan: current_time,
+                final_block_count: 0,
+                stale_block_count: 0,
+                clique_count: 2,
+            },
+            clique_count: 2,
+            fitness_gap_to_second_clique: Some(120),
+            pool_stats: (12, 1),
+            network_stats: NetworkStats {
+                in_connection_count: 3,
+                out_connection_count: 5,
+                known_peer_count: 10,
+                banned_peer_count: 1,
+                active_node_count: 8,
+            },
+            network_traffic: None,
+            execution_stats: ExecutionStats {
+                time_window_start: current_time,
+                time_window_end: current_time,
+                final_block_count: 0,
+                final_executed_operations_count: 0,
+                active_cursor: Slot::new(12, 3),
+                final_cursor: Slot::new(10, 3),
+            },
+            config: CompactConfig::default(),
+            chain_id: 77,
+            minimal_fees: Amount::from_str("0.01").unwrap(),
+        }
+    }
+
+    fn address_info(address: Address) -> AddressInfo {
+        AddressInfo {
+            address,
+            thread: 3,
+            final_balance: Amount::zero(),
+            final_roll_count: 0,
+            final_datastore_keys: vec![],
+            candidate_balance: Amount::zero(),
+            candidate_roll_count: 0,
+            candidate_datastore_keys: vec![],
+            deferred_credits: vec![],
+            next_block_draws: vec![],
+            next_endorsement_draws: vec![],
+            created_blocks: vec![],
+            created_operations: vec![],
+            created_endorsements: vec![],
+            cycle_infos: vec![],
+        }
+    }
+
+    fn operation_info(keypair: &KeyPair) -> OperationInfo {
+        let operation = Operation::new_verifiable(
+            Operation {
+                fee: Amount::from_str("0.01").unwrap(),
+                expire_period: 42,
+                op: OperationType::Transaction {
+                    recipient_address: Address::from_public_key(&keypair.get_public_key()),
+                    amount: Amount::from_str("1").unwrap(),
+                },
+            },
+            OperationSerializer::new(),
+            keypair,
+            *CHAINID,
+        )
+        .unwrap();
+        OperationInfo {
+            id: operation.id,
+            in_pool: true,
+            in_blocks: vec![],
+            is_operation_final: None,
+            thread: 3,
+            operation,
+            op_exec_status: None,
+            is_expired: false,
+            confirmation_score: None,
+        }
+    }
+
+    fn block(keypair: &KeyPair) -> Block {
+        let header = BlockHeader::new_verifiable(
+            BlockHeader {
+                current_version: 0,
+                announced_version: None,
+                slot: Slot::new(12, 1),
+                parents: vec![
+                    BlockId::generate_from_hash(Hash::compute_from("parent 0".as_bytes())),
+                    BlockId::generate_from_hash(Hash::compute_from("parent 1".as_bytes())),
+                ],
+                operation_merkle_root: Hash::compute_from(&Vec::new()),
+                endorsements: vec![],
+                denunciations: vec![],
+            },
+            BlockHeaderSerializer::new(),
+            keypair,
+            *CHAINID,
+        )
+        .unwrap();
+        Block {
+            header,
+            operations: vec![],
+        }
+    }
+
+    #[test]
+    fn status_summary() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let status = node_status(&keypair);
+        assert_eq!(
+            summarize_status(&status),
+            format!(
+                r#"Node ID:            {node_id}
+IP:                 none
+Version:            SAND.2.4
+Chain ID:           77
+Current time:       2022-01-01T00:00:00Z
+Last slot:          12.3
+Next slot:          12.4 (in 1 slot)
+Cycle:              5 (next in 1m 4s)
+Final cursor:       10.3 (64 slots ago)
+Candidate cursor:   12.3 (current slot)
+Cliques:            2 (fitness gap 120)
+Pool:               12 operations, 1 endorsement
+Peers:              3 in, 5 out, 1 banned
+Minimal fees:       0.01
+"#,
+                node_id = status.node_id
+            )
+        );
+    }
+
+    #[test]
+    fn partial_status_summary() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let mut status = node_status(&keypair);
+        status.node_ip = Some("127.0.0.1".parse().unwrap());
+        status.current_cycle = 0;
+        status.next_cycle_time = status
+            .current_time
+            .saturating_add(MassaTime::from_millis(500));
+        status.last_slot = None;
+        status.next_slot = Slot::new(0, 0);
+        status.execution_stats.active_cursor = Slot::new(0, 0);
+        status.execution_stats.final_cursor = Slot::new(0, 0);
+        status.clique_count = 1;
+        status.fitness_gap_to_second_clique = None;
+        status.pool_stats = (0, 0);
+        status.network_stats.in_connection_count = 0;
+        status.network_stats.out_connection_count = 0;
+        status.network_stats.banned_peer_count = 0;
+        status.minimal_fees = Amount::zero();
+        assert_eq!(
+            summarize_status(&status),
+            format!(
+                r#"Node ID:            {node_id}
+IP:                 127.0.0.1
+Version:            SAND.2.4
+Chain ID:           77
+Current time:       2022-01-01T00:00:00Z
+Last slot:          none (before genesis)
+Next slot:          0.0
+Cycle:              0 (next in 500ms)
+Final cursor:       0.0
+Candidate cursor:   0.0
+Cliques:            1 (no alternative)
+Pool:               0 operations, 0 endorsements
+Peers:              0 in, 0 out, 0 banned
+Minimal fees:       0
+"#,
+                node_id = status.node_id
+            )
+        );
+    }
+
+    #[test]
+    fn address_summary() {
+        let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let mut info = address_info(address);
+        info.final_balance = Amount::from_str("10").unwrap();
+        info.candidate_balance = Amount::from_str("12.5").unwrap();
+        info.final_roll_count = 1;
+        info.candidate_roll_count = 2;
+        info.deferred_credits = vec![
+            SlotAmount {
+                slot: Slot::new(14, 3),
+                amount: Amount::from_str("5").unwrap(),
+            },
+            SlotAmount {
+                slot: Slot::new(10, 3),
+                amount: Amount::from_str("0.25").unwrap(),
+            },
+        ];
+        info.next_block_draws = vec![Slot::new(20, 0), Slot::new(13, 3)];
+        info.next_endorsement_draws = vec![IndexedSlot {
+            slot: Slot::new(12, 5),
+            index: 7,
+        }];
+        info.cycle_infos = vec![
+            ExecutionAddressCycleInfo {
+                cycle: 4,
+                is_final: true,
+                ok_count: 3,
+                nok_count: 1,
+                active_rolls: Some(1),
+            },
+            ExecutionAddressCycleInfo {
+                cycle: 5,
+                is_final: false,
+                ok_count: 1,
+                nok_count: 0,
+                active_rolls: None,
+            },
+        ];
+        assert_eq!(
+            summarize_address(&info, &timing()),
+            format!(
+                r#"Address:            {address} (thread 3)
+Balance:            final 10, candidate 12.5
+Rolls:              final 1, candidate 2, active unknown
+Locked coins:       5 until 14.3 (in 64 slots)
+                    0.25 until 10.3 (64 slots ago)
+Block draws:        2, next at 13.3 (in 32 slots)
+Endorsement draws:  1, next at 12.5 (in 2 slots) index 7
+Cycles:             4 (final): 3 produced, 1 missed, 1 active roll
+                    5 (candidate): 1 produced, 0 missed, active rolls unknown
+"#,
+                address = address
+            )
+        );
+    }
+
+    #[test]
+    fn empty_address_summary() {
+        let address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let timing = SlotTiming {
+            thread_count: THREAD_COUNT,
+            current_slot: None,
+        };
+        assert_eq!(
+            summarize_address(&address_info(address), &timing),
+            format!(
+                r#"Address:            {address} (thread 3)
+Balance:            final 0, candidate 0
+Rolls:              final 0, candidate 0, active unknown
+Locked coins:       none
+Block draws:        none
+Endorsement draws:  none
+Cycles:             none
+"#,
+                address = address
+            )
+        );
+    }
+
+    #[test]
+    fn operation_summary() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let mut info = operation_info(&keypair);
+        let block_ids = [
+            BlockId::generate_from_hash(Hash::compute_from("block 1".as_bytes())),
+            BlockId::generate_from_hash(Hash::compute_from("block 2".as_bytes())),
+        ];
+        info.in_pool = false;
+        info.in_blocks = block_ids.to_vec();
+        info.is_operation_final = Some(true);
+        info.op_exec_status = Some(false);
+        assert_eq!(
+            summarize_operation(&info, &timing()),
+            format!(
+                r#"Operation:          {operation_id}
+Type:               transaction
+Creator:            {creator}
+Fee:                0.01
+Expires:            42.3 (in 960 slots)
+In pool:            no
+In blocks:          {block_id_1}
+                    {block_id_2}
+Finality:           final
+Execution:          failure
+"#,
+                operation_id = info.id,
+                creator = info.operation.content_creator_address,
+                block_id_1 = block_ids[0],
+                block_id_2 = block_ids[1]
+            )
+        );
+    }
+
+    #[test]
+    fn partial_operation_summary() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let info = operation_info(&keypair);
+        let timing = SlotTiming {
+            thread_count: THREAD_COUNT,
+            current_slot: None,
+        };
+        assert_eq!(
+            summarize_operation(&info, &timing),
+            format!(
+                r#"Operation:          {operation_id}
+Type:               transaction
+Creator:            {creator}
+Fee:                0.01
+Expires:            42.3
+In pool:            yes
+In blocks:          none
+Finality:           unknown
+Execution:          unknown
+"#,
+                operation_id = info.id,
+                creator = info.operation.content_creator_address
+            )
+        );
+    }
+
+    #[test]
+    fn block_summary() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let block = block(&keypair);
+        let creator = block.header.content_creator_address;
+        let endorser = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+        let info = BlockInfo {
+            id: block.header.id,
+            content: Some(BlockInfoContent {
+                is_final: false,
+                is_in_blockclique: true,
+                is_candidate: true,
+                is_discarded: false,
+                block,
+                endorsement_coverage: vec![
+                    EndorsementCoverage::Included(EndorsementId::new(Hash::compute_from(
+                        "endorsement".as_bytes(),
+                    ))),
+                    EndorsementCoverage::Missing(Some(endorser)),
+                    EndorsementCoverage::Missing(None),
+                ],
+            }),
+        };
+        assert_eq!(
+            summarize_block(&info, &timing()),
+            format!(
+                r#"Block:              {block_id}
+Status:             candidate, blockclique
+Slot:               12.1 (2 slots ago)
+Creator:            {creator}
+Parents:            2
+Operations:         0
+Endorsements:       1/3, missing 1 ({endorser}), 2
+"#,
+                block_id = info.id,
+                creator = creator,
+                endorser = endorser
+            )
+        );
+    }
+
+    #[test]
+    fn missing_block_summary() {
+        let info = BlockInfo {
+            id: BlockId::generate_from_hash(Hash::compute_from("block".as_bytes())),
+            content: None,
+        };
+        assert_eq!(
+            summarize_block(&info, &timing()),
+            format!(
+                r#"Block:              {block_id}
+Status:             not found
+"#,
+                block_id = info.id
+            )
+        );
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(MassaTime::from_millis(0)), "0ms");
+        assert_eq!(format_duration(MassaTime::from_millis(999)), "999ms");
+        assert_eq!(format_duration(MassaTime::from_millis(3_605_000)), "1h 5s");
+        assert_eq!(
+            format_duration(MassaTime::from_millis(
+                1000 * (8 * 86400 + 3600 + 3 * 60 + 6)
+            )),
+            "8d 1h 3m 6s"
+        );
+    }
+}
@@ -6,23 +6,30 @@ use crate::api_trait::MassaApiServer;
 use crate::{ApiServer, ApiV2, StopHandle, API};
 use async_trait::async_trait;
 use futures::future::{self, Either};
-use futures::StreamExt;
+use futures::{Stream, StreamExt};
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult, SubscriptionResult};
 use jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage};
 use massa_api_exports::config::APIConfig;
 use massa_api_exports::error::ApiError;
+use massa_api_exports::execution::SlotExecutionDigest;
+use massa_api_exports::node::NodeEvent;
+use massa_api_exports::operation::OperationStreamFilter;
 use massa_api_exports::page::{PageRequest, PagedVec, PagedVecV2};
+use massa_api_exports::pool::PoolEventFilter;
+use massa_api_exports::subscription::SubItem;
 use massa_api_exports::ApiRequest;
 use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
-use massa_execution_exports::ExecutionController;
+use massa_execution_exports::{ExecutionChannels, ExecutionController};
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
+use massa_models::prehash::PreHashSet;
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_models::version::Version;
-use massa_pool_exports::PoolBroadcasts;
+use massa_pool_exports::{ExpiredOperation, PoolBroadcasts};
 use massa_time::MassaTime;
 use serde::Serialize;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
 use tokio_stream::wrappers::BroadcastStream;
 
 impl API<ApiV2> {
@@ -31,6 +38,7 @@ impl API<ApiV2> {
         consensus_controller: Box<dyn ConsensusController>,
         consensus_broadcasts: ConsensusBroadcasts,
         execution_controller: Box<dyn ExecutionController>,
+        execution_channels: ExecutionChannels,
         pool_broadcasts: PoolBroadcasts,
         api_settings: APIConfig,
         version: Version,
@@ -39,6 +47,7 @@ impl API<ApiV2> {
             consensus_controller,
             consensus_broadcasts,
             execution_controller,
+            execution_channels,
             pool_broadcasts,
             api_settings,
             version,
@@ -53,7 +62,7 @@ impl ApiServer for API<ApiV2> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        crate::serve(self.into_rpc(), url, api_config, None).await
     }
 }
 
@@ -118,7 +127,11 @@ impl MassaApiServer for API<ApiV2> {
     }
 
     async fn subscribe_new_blocks(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
-        broadcast_via_ws(self.0.consensus_broadcasts.block_sender.clone(), pending).await
+        broadcast_via_ws(
+            self.0.consensus_broadcasts.block_sender.subscribe(),
+            pending,
+        )
+        .await
     }
 
     async fn subscribe_new_blocks_headers(
@@ -126,7 +139,7 @@ impl MassaApiServer for API<ApiV2> {
         pending: PendingSubscriptionSink,
     ) -> SubscriptionResult {
         broadcast_via_ws(
-            self.0.consensus_broadcasts.block_header_sender.clone(),
+            self.0.consensus_broadcasts.block_header_sender.subscribe(),
             pending,
         )
         .await
@@ -137,7 +150,7 @@ impl MassaApiServer for API<ApiV2> {
         pending: PendingSubscriptionSink,
     ) -> SubscriptionResult {
         broadcast_via_ws(
-            self.0.consensus_broadcasts.filled_block_sender.clone(),
+            self.0.consensus_broadcasts.filled_block_sender.subscribe(),
             pending,
         )
         .await
@@ -147,18 +160,228 @@ impl MassaApiServer for API<ApiV2> {
         &self,
         pending: PendingSubscriptionSink,
     ) -> SubscriptionResult {
-        broadcast_via_ws(self.0.pool_broadcasts.operation_sender.clone(), pending).await
+        broadcast_via_ws(self.0.pool_broadcasts.operation_sender.subscribe(), pending).await
+    }
+
+    async fn subscribe_new_blocks_seq(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        sequenced_broadcast_via_ws(
+            self.0.consensus_broadcasts.block_sender.subscribe(),
+            pending,
+        )
+        .await
+    }
+
+    async fn subscribe_new_blocks_headers_seq(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        sequenced_broadcast_via_ws(
+            self.0.consensus_broadcasts.block_header_sender.subscribe(),
+            pending,
+        )
+        .await
+    }
+
+    async fn subscribe_new_filled_blocks_seq(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        sequenced_broadcast_via_ws(
+            self.0.consensus_broadcasts.filled_block_sender.subscribe(),
+            pending,
+        )
+        .await
+    }
+
+    async fn subscribe_new_operations_seq(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        sequenced_broadcast_via_ws(self.0.pool_broadcasts.operation_sender.subscribe(), pending)
+            .await
+    }
+
+    async fn subscribe_new_operations_filtered(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: OperationStreamFilter,
+    ) -> SubscriptionResult {
+        if filter.is_empty() {
+            pending
+                .reject(ApiError::BadRequest(
+                    "operation filter must have at least one criterion".into(),
+                ))
+                .await;
+            return Ok(());
+        }
+        if filter.creator_addresses.len() as u64 > self.0.api_settings.max_arguments
+            || filter.recipient_addresses.len() as u64 > self.0.api_settings.max_arguments
+        {
+            pending
+                .reject(ApiError::BadRequest("too many arguments".into()))
+                .await;
+            return Ok(());
+        }
+
+        broadcast_filtered_via_ws(
+            self.0.pool_broadcasts.operation_sender.subscribe(),
+            pending,
+            move |operation| filter.matches(operation),
+        )
+        .await
+    }
+
+    async fn subscribe_operation_expiries(
+        &self,
+        pending: PendingSubscriptionSink,
+        addresses: Vec<Address>,
+    ) -> SubscriptionResult {
+        if addresses.is_empty() {
+            pending
+                .reject(ApiError::BadRequest(
+                    "at least one address must be given".into(),
+                ))
+                .await;
+            return Ok(());
+        }
+        if addresses.len() as u64 > self.0.api_settings.max_arguments {
+            pending
+                .reject(ApiError::BadRequest("too many arguments".into()))
+                .await;
+            return Ok(());
+        }
+
+        let addresses: PreHashSet<Address> = addresses.into_iter().collect();
+        broadcast_filtered_via_ws(
+            self.0.pool_broadcasts.expired_operation_sender.subscribe(),
+            pending,
+            move |expired: &ExpiredOperation| {
+                addresses.contains(&expired.operation.content_creator_address)
+            },
+        )
+        .await
+    }
+
+    async fn subscribe_pool_events(
+        &self,
+        pending: PendingSubscriptionSink,
+        filter: PoolEventFilter,
+    ) -> SubscriptionResult {
+        if filter.creator_addresses.len() as u64 > self.0.api_settings.max_arguments {
+            pending
+                .reject(ApiError::BadRequest("too many arguments".into()))
+                .await;
+            return Ok(());
+        }
+
+        broadcast_filtered_via_ws(
+            self.0.pool_broadcasts.pool_event_sender.subscribe(),
+            pending,
+            move |event| filter.matches(event),
+        )
+        .await
+    }
+
+    async fn subscribe_new_slot_execution_digests(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> SubscriptionResult {
+        let stream = BroadcastStream::new(
+            self.0
+                .execution_channels
+                .slot_execution_output_sender
+                .subscribe(),
+        )
+        .map(|res| res.map(|slot_output| SlotExecutionDigest::from(&slot_output)));
+        stream_via_ws(stream, pending, |_| true).await
+    }
+
+    async fn subscribe_all(&self, pending: PendingSubscriptionSink) -> SubscriptionResult {
+        let consensus_broadcasts = &self.0.consensus_broadcasts;
+        let pool_broadcasts = &self.0.pool_broadcasts;
+        let stream = futures::stream::select_all([
+            BroadcastStream::new(consensus_broadcasts.block_sender.subscribe())
+                .map(|res| res.map(NodeEvent::Block))
+                .boxed(),
+            BroadcastStream::new(consensus_broadcasts.block_header_sender.subscribe())
+                .map(|res| res.map(NodeEvent::BlockHeader))
+                .boxed(),
+            BroadcastStream::new(consensus_broadcasts.filled_block_sender.subscribe())
+                .map(|res| res.map(NodeEvent::FilledBlock))
+                .boxed(),
+            BroadcastStream::new(pool_broadcasts.operation_sender.subscribe())
+                .map(|res| res.map(NodeEvent::Operation))
+                .boxed(),
+            BroadcastStream::new(pool_broadcasts.endorsement_sender.subscribe())
+                .map(|res| res.map(NodeEvent::Endorsement))
+                .boxed(),
+        ]);
+        stream_via_ws(stream, pending, |_| true).await
     }
 }
 
-// Brodcast the stream(sender) content via a WebSocket
+// Brodcast the stream(receiver) content via a WebSocket
 async fn broadcast_via_ws<T: Serialize + Send + Clone + 'static>(
-    sender: tokio::sync::broadcast::Sender<T>,
+    receiver: tokio::sync::broadcast::Receiver<T>,
+    pending: PendingSubscriptionSink,
+) -> SubscriptionResult {
+    broadcast_filtered_via_ws(receiver, pending, |_| true).await
+}
+
+// Brodcast the stream(receiver) items accepted by `filter` via a WebSocket
+async fn broadcast_filtered_via_ws<T, F>(
+    receiver: tokio::sync::broadcast::Receiver<T>,
+    pending: PendingSubscriptionSink,
+    filter: F,
+) -> SubscriptionResult
+where
+    T: Serialize + Send + Clone + 'static,
+    F: Fn(&T) -> bool + Send,
+{
+    stream_via_ws(BroadcastStream::new(receiver), pending, filter).await
+}
+
+// Brodcast the stream(receiver) content via a WebSocket, numbering the items.
+// The items dropped because the subscriber lagged behind still consume their numbers,
+// so that the subscriber sees the gap.
+async fn sequenced_broadcast_via_ws<T: Serialize + Send + Clone + 'static>(
+    receiver: tokio::sync::broadcast::Receiver<T>,
     pending: PendingSubscriptionSink,
 ) -> SubscriptionResult {
+    let mut next_seq: u64 = 0;
+    let stream = BroadcastStream::new(receiver).filter_map(move |res| {
+        let item = match res {
+            Ok(item) => {
+                let seq = next_seq;
+                next_seq = next_seq.saturating_add(1);
+                Some(Ok(SubItem { seq, item }))
+            }
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                next_seq = next_seq.saturating_add(skipped);
+                None
+            }
+        };
+        future::ready(item)
+    });
+    stream_via_ws(stream, pending, |_| true).await
+}
+
+// Send the stream items accepted by `filter` via a WebSocket
+async fn stream_via_ws<T, S, F>(
+    stream: S,
+    pending: PendingSubscriptionSink,
+    filter: F,
+) -> SubscriptionResult
+where
+    T: Serialize + Send,
+    S: Stream<Item = Result<T, BroadcastStreamRecvError>> + Send,
+    F: Fn(&T) -> bool + Send,
+{
     let sink = pending.accept().await?;
     let closed = sink.closed();
-    let stream = BroadcastStream::new(sender.subscribe());
     futures::pin_mut!(closed, stream);
 
     loop {
@@ -168,6 +391,11 @@ async fn broadcast_via_ws<T: Serialize + Send + Clone + 'static>(
 
             // received new item from the stream.
             Either::Right((Some(Ok(item)), c)) => {
+                if !filter(&item) {
+                    closed = c;
+                    continue;
+                }
+
                 let notif = SubscriptionMessage::from_json(&item)?;
 
                 if sink.send(notif).await.is_err() {
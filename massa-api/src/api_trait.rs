@@ -2,7 +2,9 @@
 //! Json RPC API for a massa-node
 use jsonrpsee::core::{RpcResult, SubscriptionResult};
 use jsonrpsee::proc_macros::rpc;
+use massa_api_exports::operation::OperationStreamFilter;
 use massa_api_exports::page::PagedVecV2;
+use massa_api_exports::pool::PoolEventFilter;
 use massa_api_exports::ApiRequest;
 use massa_models::address::Address;
 use massa_models::block_id::BlockId;
@@ -57,4 +59,80 @@ pub trait MassaApi {
 		item = Operation
 	)]
     async fn subscribe_new_operations(&self) -> SubscriptionResult;
+
+    /// New produced blocks, numbered by the node.
+    #[subscription(
+        name = "subscribe_new_blocks_seq" => "new_blocks_seq",
+        unsubscribe = "unsubscribe_new_blocks_seq",
+        item = SubItem<Block>
+    )]
+    async fn subscribe_new_blocks_seq(&self) -> SubscriptionResult;
+
+    /// New produced blocks headers, numbered by the node.
+    #[subscription(
+        name = "subscribe_new_blocks_headers_seq" => "new_blocks_headers_seq",
+        unsubscribe = "unsubscribe_new_blocks_headers_seq",
+        item = SubItem<SecureShare<BlockHeader, BlockId>>
+    )]
+    async fn subscribe_new_blocks_headers_seq(&self) -> SubscriptionResult;
+
+    /// New produced blocks with operations content, numbered by the node.
+    #[subscription(
+        name = "subscribe_new_filled_blocks_seq" => "new_filled_blocks_seq",
+        unsubscribe = "unsubscribe_new_filled_blocks_seq",
+        item = SubItem<FilledBlock>
+    )]
+    async fn subscribe_new_filled_blocks_seq(&self) -> SubscriptionResult;
+
+    /// New produced operations, numbered by the node.
+    #[subscription(
+        name = "subscribe_new_operations_seq" => "new_operations_seq",
+        unsubscribe = "unsubscribe_new_operations_seq",
+        item = SubItem<Operation>
+    )]
+    async fn subscribe_new_operations_seq(&self) -> SubscriptionResult;
+
+    /// New produced operations matching at least one of the filter criteria.
+    #[subscription(
+		name = "subscribe_new_operations_filtered" => "new_operations_filtered",
+		unsubscribe = "unsubscribe_new_operations_filtered",
+		item = Operation
+	)]
+    async fn subscribe_new_operations_filtered(
+        &self,
+        filter: OperationStreamFilter,
+    ) -> SubscriptionResult;
+
+    /// Operations created by one of the given addresses that expired without being executed.
+    #[subscription(
+		name = "subscribe_operation_expiries" => "operation_expiries",
+		unsubscribe = "unsubscribe_operation_expiries",
+		item = ExpiredOperation
+	)]
+    async fn subscribe_operation_expiries(&self, addresses: Vec<Address>) -> SubscriptionResult;
+
+    /// Admissions and removals of operations and endorsements in the pool matching the filter.
+    #[subscription(
+		name = "subscribe_pool_events" => "pool_events",
+		unsubscribe = "unsubscribe_pool_events",
+		item = PoolEvent
+	)]
+    async fn subscribe_pool_events(&self, filter: PoolEventFilter) -> SubscriptionResult;
+
+    /// Digests of the state changes of the executed slots, speculative and final.
+    #[subscription(
+		name = "subscribe_new_slot_execution_digests" => "new_slot_execution_digests",
+		unsubscribe = "unsubscribe_new_slot_execution_digests",
+		item = SlotExecutionDigest
+	)]
+    async fn subscribe_new_slot_execution_digests(&self) -> SubscriptionResult;
+
+    /// New produced blocks, blocks headers, filled blocks, operations and endorsements
+    /// multiplexed in a single subscription.
+    #[subscription(
+		name = "subscribe_all" => "all_events",
+		unsubscribe = "unsubscribe_all",
+		item = NodeEvent
+	)]
+    async fn subscribe_all(&self) -> SubscriptionResult;
 }
@@ -0,0 +1,115 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Address of the client of the request being handled.
+//!
+//! The handlers of the API methods don't see the connection the request came from. The
+//! [`ClientAddrLayer`] runs each HTTP request in a task-local scope, which the [`ClientAddrLogger`]
+//! fills with the address of the peer when the server reports the connection. The handlers then
+//! read it with [`client_ip`]. The calls of WebSocket connections run outside of that scope, and
+//! have no known address.
+
+use std::error::Error as StdError;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+
+use hyper::{Body, Request, Response};
+use jsonrpsee::server::logger::{self, HttpRequest, Logger, MethodKind, Params, TransportProtocol};
+use tower::{Layer, Service};
+
+tokio::task_local! {
+    /// address of the client of the HTTP request handled by the current task
+    static CLIENT_IP: Arc<OnceLock<IpAddr>>;
+}
+
+/// IP address of the client of the request being handled, if known
+pub(crate) fn client_ip() -> Option<IpAddr> {
+    CLIENT_IP
+        .try_with(|client_ip| client_ip.get().copied())
+        .ok()
+        .flatten()
+}
+
+/// Server logger recording the address of the peer of each request into its task-local scope
+#[derive(Clone)]
+pub(crate) struct ClientAddrLogger;
+
+impl Logger for ClientAddrLogger {
+    type Instant = ();
+
+    fn on_connect(&self, remote_addr: SocketAddr, _request: &HttpRequest, _t: TransportProtocol) {
+        // outside of a request scope, the address is not needed
+        let _ = CLIENT_IP.try_with(|client_ip| client_ip.set(remote_addr.ip()));
+    }
+
+    fn on_request(&self, _transport: TransportProtocol) -> Self::Instant {}
+
+    fn on_call(
+        &self,
+        _method_name: &str,
+        _params: Params,
+        _kind: MethodKind,
+        _transport: TransportProtocol,
+    ) {
+    }
+
+    fn on_result(
+        &self,
+        _method_name: &str,
+        _success_or_error: logger::MethodResponseResult,
+        _started_at: Self::Instant,
+        _transport: TransportProtocol,
+    ) {
+    }
+
+    fn on_response(
+        &self,
+        _result: &str,
+        _started_at: Self::Instant,
+        _transport: TransportProtocol,
+    ) {
+    }
+
+    fn on_disconnect(&self, _remote_addr: SocketAddr, _transport: TransportProtocol) {}
+}
+
+/// Layer running each request in a scope holding the address of its client
+#[derive(Clone)]
+pub(crate) struct ClientAddrLayer;
+
+impl<S> Layer<S> for ClientAddrLayer {
+    type Service = ClientAddrService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientAddrService { inner }
+    }
+}
+
+/// Service built by [`ClientAddrLayer`]
+#[derive(Clone)]
+pub(crate) struct ClientAddrService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for ClientAddrService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Error: Into<Box<dyn StdError + Send + Sync>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = Box<dyn StdError + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let client_ip = Arc::new(OnceLock::new());
+        // the server may report the connection when called or when polled: both are in scope
+        let response = CLIENT_IP.sync_scope(client_ip.clone(), || self.inner.call(request));
+        Box::pin(CLIENT_IP.scope(client_ip, async move { response.await.map_err(Into::into) }))
+    }
+}
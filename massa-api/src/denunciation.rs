@@ -0,0 +1,162 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Checks of the denunciation evidences submitted through the public API
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use massa_api_exports::{
+    config::APIConfig,
+    denunciation::{DenunciationEvidence, EvidenceRejection, SecureShareInput},
+};
+use massa_execution_exports::ExecutionController;
+use massa_models::{
+    block_header::{BlockHeaderDeserializer, SecuredHeader},
+    config::{DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, MAX_DENUNCIATIONS_PER_BLOCK_HEADER},
+    denunciation::{Denunciation, DenunciationError, DenunciationIndex, DenunciationPrecursor},
+    endorsement::{EndorsementDeserializer, SecureShareEndorsement},
+    secure_share::{Id, SecureShare, SecureShareContent, SecureShareDeserializer},
+};
+use massa_serialization::{DeserializeError, Deserializer};
+
+/// Number of evidence items accepted per second from each client IP address.
+/// The clients of unknown address share the same limit.
+pub(crate) struct EvidenceRateLimiter {
+    limit: u64,
+    /// start of the current window of each client, and the items counted in it
+    windows: HashMap<Option<IpAddr>, (Instant, u64)>,
+}
+
+impl EvidenceRateLimiter {
+    pub(crate) fn new(limit: u64) -> Self {
+        EvidenceRateLimiter {
+            limit,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Maximum number of items accepted per second from a client
+    pub(crate) fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Count `items` more evidences from `client` in its current second.
+    /// Returns false, counting nothing, if it would exceed the limit.
+    pub(crate) fn try_acquire(&mut self, client: Option<IpAddr>, items: u64) -> bool {
+        let now = Instant::now();
+        // forget the clients whose window is over
+        self.windows
+            .retain(|_, (start, _)| now.duration_since(*start) < Duration::from_secs(1));
+        let (_, count) = self.windows.entry(client).or_insert((now, 0));
+        match count.checked_add(items) {
+            Some(new_count) if new_count <= self.limit => {
+                *count = new_count;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Check an evidence and return the precursors of its two objects.
+///
+/// `final_period` is the period of the last final slot, `current_period` the one of the slot closest to now.
+pub(crate) fn check_denunciation_evidence(
+    evidence: DenunciationEvidence,
+    api_cfg: &APIConfig,
+    execution_controller: &dyn ExecutionController,
+    final_period: u64,
+    current_period: u64,
+) -> Result<[DenunciationPrecursor; 2], EvidenceRejection> {
+    let (denunciation, precursors) = match evidence {
+        DenunciationEvidence::BlockHeaders(input_1, input_2) => {
+            let deserializer = SecureShareDeserializer::new(
+                BlockHeaderDeserializer::new(
+                    api_cfg.thread_count,
+                    ENDORSEMENT_COUNT,
+                    MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+                    None,
+                    api_cfg.chain_id,
+                ),
+                api_cfg.chain_id,
+            );
+            let header_1: SecuredHeader = decode_secure_share(input_1, &deserializer)?;
+            let header_2: SecuredHeader = decode_secure_share(input_2, &deserializer)?;
+            let denunciation =
+                Denunciation::try_from((&header_1, &header_2)).map_err(rejection_reason)?;
+            (
+                denunciation,
+                [
+                    DenunciationPrecursor::from(&header_1),
+                    DenunciationPrecursor::from(&header_2),
+                ],
+            )
+        }
+        DenunciationEvidence::Endorsements(input_1, input_2) => {
+            let deserializer = SecureShareDeserializer::new(
+                EndorsementDeserializer::new(api_cfg.thread_count, ENDORSEMENT_COUNT),
+                api_cfg.chain_id,
+            );
+            let endorsement_1: SecureShareEndorsement =
+                decode_secure_share(input_1, &deserializer)?;
+            let endorsement_2: SecureShareEndorsement =
+                decode_secure_share(input_2, &deserializer)?;
+            let denunciation = Denunciation::try_from((&endorsement_1, &endorsement_2))
+                .map_err(rejection_reason)?;
+            (
+                denunciation,
+                [
+                    DenunciationPrecursor::from(&endorsement_1),
+                    DenunciationPrecursor::from(&endorsement_2),
+                ],
+            )
+        }
+    };
+
+    // same window as the one the denunciation pool applies to the precursors it receives
+    let slot = denunciation.get_slot();
+    if slot.period <= api_cfg.last_start_period
+        || Denunciation::is_expired(&slot.period, &final_period, &DENUNCIATION_EXPIRE_PERIODS)
+    {
+        return Err(EvidenceRejection::Expired);
+    }
+    if slot.period.saturating_sub(current_period) > DENUNCIATION_EXPIRE_PERIODS {
+        return Err(EvidenceRejection::TooFarInFuture);
+    }
+
+    let (speculative, is_final) = execution_controller
+        .get_denunciation_execution_status(&DenunciationIndex::from(&denunciation));
+    if speculative || is_final {
+        return Err(EvidenceRejection::AlreadyDenounced);
+    }
+    Ok(precursors)
+}
+
+/// Deserialize a signed object submitted in the same form as an operation input
+fn decode_secure_share<T, ID, Deser>(
+    input: SecureShareInput,
+    deserializer: &SecureShareDeserializer<T, Deser>,
+) -> Result<SecureShare<T, ID>, EvidenceRejection>
+where
+    T: std::fmt::Display + SecureShareContent,
+    ID: Id,
+    Deser: Deserializer<T>,
+{
+    let mut serialized = Vec::new();
+    serialized.extend(input.signature.to_bytes());
+    serialized.extend(input.creator_public_key.to_bytes());
+    serialized.extend(input.serialized_content);
+    match deserializer.deserialize::<DeserializeError>(&serialized) {
+        Ok((rest, secure_share)) if rest.is_empty() => Ok(secure_share),
+        _ => Err(EvidenceRejection::InvalidEncoding),
+    }
+}
+
+fn rejection_reason(err: DenunciationError) -> EvidenceRejection {
+    match err {
+        DenunciationError::Signature(_) => EvidenceRejection::InvalidSignature,
+        DenunciationError::InvalidInput(_) => EvidenceRejection::NotConflicting,
+        DenunciationError::Serialization(_) => EvidenceRejection::InvalidEncoding,
+    }
+}
@@ -0,0 +1,147 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! Opt-in response envelopes of the public API.
+//!
+//! When an HTTP request carries the `massa-envelope: 1` header, the `result` of every JSON-RPC
+//! response of the body, batched or not, is replaced by a [`ResponseEnvelope`] around it. Error
+//! responses, WebSocket upgrades and requests without the header are left untouched, so the
+//! handlers don't know about envelopes.
+
+use std::error::Error as StdError;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use hyper::header::{HeaderValue, CONTENT_LENGTH, UPGRADE};
+use hyper::{Body, Request, Response, StatusCode};
+use massa_api_exports::envelope::{ResponseEnvelope, API_REVISION, ENVELOPE_HEADER};
+use massa_models::version::Version;
+use serde_json::Value;
+use tower::{Layer, Service};
+
+/// Layer wrapping the results of the responses requesting it in a [`ResponseEnvelope`]
+#[derive(Clone)]
+pub(crate) struct EnvelopeLayer {
+    node_version: Version,
+    chain_id: u64,
+}
+
+impl EnvelopeLayer {
+    pub(crate) fn new(node_version: Version, chain_id: u64) -> Self {
+        EnvelopeLayer {
+            node_version,
+            chain_id,
+        }
+    }
+
+    /// Wrap the result of a JSON-RPC response, or of each response of a batch
+    fn wrap(&self, body: &mut Value) {
+        match body {
+            Value::Array(responses) => {
+                for response in responses {
+                    self.wrap(response);
+                }
+            }
+            Value::Object(response) => {
+                if let Some(result) = response.get_mut("result") {
+                    let envelope = ResponseEnvelope {
+                        node_version: self.node_version,
+                        chain_id: self.chain_id,
+                        api_revision: API_REVISION,
+                        result: result.take(),
+                    };
+                    *result = serde_json::to_value(envelope)
+                        .expect("could not serialize a response envelope");
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<S> Layer<S> for EnvelopeLayer {
+    type Service = EnvelopeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        EnvelopeService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service built by [`EnvelopeLayer`]
+#[derive(Clone)]
+pub(crate) struct EnvelopeService<S> {
+    inner: S,
+    layer: EnvelopeLayer,
+}
+
+impl<S> Service<Request<Body>> for EnvelopeService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Error: Into<Box<dyn StdError + Send + Sync>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = Box<dyn StdError + Send + Sync>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let requested = request
+            .headers()
+            .get(ENVELOPE_HEADER)
+            .is_some_and(|value| value == "1")
+            && !request.headers().contains_key(UPGRADE);
+        let response = self.inner.call(request);
+        let layer = self.layer.clone();
+        Box::pin(async move {
+            let response = response.await.map_err(Into::into)?;
+            if !requested || response.status() != StatusCode::OK {
+                return Ok(response);
+            }
+            let (mut parts, body) = response.into_parts();
+            let bytes = hyper::body::to_bytes(body).await?;
+            let Ok(mut body) = serde_json::from_slice::<Value>(&bytes) else {
+                return Ok(Response::from_parts(parts, Body::from(bytes)));
+            };
+            layer.wrap(&mut body);
+            parts.headers.remove(CONTENT_LENGTH);
+            parts
+                .headers
+                .insert(ENVELOPE_HEADER, HeaderValue::from_static("1"));
+            Ok(Response::from_parts(parts, Body::from(body.to_string())))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_wrap_results_only() {
+        let layer = EnvelopeLayer::new("TEST.1.0".parse().unwrap(), 77);
+        let mut body = json!([
+            {"jsonrpc": "2.0", "id": 1, "result": [1, 2]},
+            {"jsonrpc": "2.0", "id": 2, "error": {"code": -32601, "message": "Method not found"}},
+        ]);
+        layer.wrap(&mut body);
+        assert_eq!(
+            body,
+            json!([
+                {"jsonrpc": "2.0", "id": 1, "result": {
+                    "node_version": "TEST.1.0",
+                    "chain_id": 77,
+                    "api_revision": API_REVISION,
+                    "result": [1, 2],
+                }},
+                {"jsonrpc": "2.0", "id": 2, "error": {"code": -32601, "message": "Method not found"}},
+            ])
+        );
+    }
+}
@@ -5,6 +5,8 @@
 #![warn(unused_crate_dependencies)]
 
 use api_trait::MassaApiServer;
+use client_addr::{ClientAddrLayer, ClientAddrLogger};
+use envelope::EnvelopeLayer;
 use hyper::Method;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use jsonrpsee::proc_macros::rpc;
@@ -14,20 +16,31 @@ use jsonrpsee::RpcModule;
 use massa_api_exports::execution::Transfer;
 use massa_api_exports::{
     address::{AddressFilter, AddressInfo},
-    block::{BlockInfo, BlockSummary},
+    block::{BlockConfirmation, BlockInfo, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    debug_dump::{DebugDump, DebugDumpRequest},
+    deferred_credits::{DeferredCredit, DeferredCreditsRequest},
+    denunciation::{DenunciationEvidence, EvidenceOutcome},
     endorsement::EndorsementInfo,
     error::ApiError::WrongAPI,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
+    execution::{
+        ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, SlotExecutionBudget,
+    },
     node::NodeStatus,
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    pool::{PoolItemKind, PoolSnapshotPage},
+    slashing::SlashingStats,
     TimeInterval,
 };
-use massa_consensus_exports::{ConsensusBroadcasts, ConsensusController};
-use massa_execution_exports::ExecutionController;
-use massa_models::clique::Clique;
+use massa_consensus_exports::{
+    command_timing::ConsensusCommandTiming, ConsensusBroadcasts, ConsensusController,
+};
+use massa_execution_exports::{ExecutionChannels, ExecutionController};
+use massa_factory_exports::ProductionHistory;
+use massa_final_state::FinalStateController;
+use massa_models::clique::CliqueInfo;
 use massa_models::composite::PubkeySig;
 use massa_models::node::NodeId;
 use massa_models::operation::OperationId;
@@ -37,7 +50,7 @@ use massa_models::{
     address::Address, block::Block, block_id::BlockId, endorsement::EndorsementId,
     execution::EventFilter, slot::Slot, version::Version,
 };
-use massa_pool_exports::{PoolBroadcasts, PoolController};
+use massa_pool_exports::{PoolBroadcasts, PoolController, SelectionAudit};
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::{ProtocolConfig, ProtocolController};
 use massa_storage::Storage;
@@ -57,6 +70,10 @@ use massa_grpc as _;
 
 mod api;
 mod api_trait;
+mod client_addr;
+mod denunciation;
+mod envelope;
+mod pool_snapshot;
 mod private;
 mod public;
 
@@ -87,14 +104,22 @@ pub struct Public {
     pub node_id: NodeId,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// limit of the denunciation evidences submitted per second
+    pub(crate) evidence_rate_limiter: parking_lot::Mutex<denunciation::EvidenceRateLimiter>,
+    /// pool snapshots retained to be paged
+    pub(crate) pool_snapshots: parking_lot::Mutex<pool_snapshot::PoolSnapshots>,
 }
 
 /// Private API content
 pub struct Private {
     /// link to the protocol component
     pub protocol_controller: Box<dyn ProtocolController>,
+    /// link to the consensus component
+    pub consensus_controller: Box<dyn ConsensusController>,
     /// link to the execution component
     pub execution_controller: Box<dyn ExecutionController>,
+    /// link to the pool component
+    pub pool_controller: Box<dyn PoolController>,
     /// API settings
     pub api_settings: APIConfig,
     /// Mechanism by which to gracefully shut down.
@@ -102,6 +127,14 @@ pub struct Private {
     pub stop_cv: Arc<(Mutex<bool>, Condvar)>,
     /// User wallet
     pub node_wallet: Arc<RwLock<Wallet>>,
+    /// addresses whose operations are left out of the blocks produced by the node
+    pub production_blocklist: Arc<RwLock<PreHashSet<Address>>>,
+    /// link to the final state
+    pub final_state: Arc<RwLock<dyn FinalStateController>>,
+    /// shared storage, to report its usage
+    pub storage: Storage,
+    /// block production history, shared with the block factory
+    pub production_history: Arc<RwLock<ProductionHistory>>,
 }
 
 /// API v2 content
@@ -112,6 +145,8 @@ pub struct ApiV2 {
     pub consensus_broadcasts: ConsensusBroadcasts,
     /// link to the execution component
     pub execution_controller: Box<dyn ExecutionController>,
+    /// channels with the outputs broadcasted by the execution
+    pub execution_channels: ExecutionChannels,
     /// channels with informations broadcasted by the pool
     pub pool_broadcasts: PoolBroadcasts,
     /// API settings
@@ -149,6 +184,7 @@ async fn serve<T>(
     api: RpcModule<T>,
     url: &SocketAddr,
     api_config: &APIConfig,
+    envelope: Option<EnvelopeLayer>,
 ) -> Result<StopHandle, JsonRpseeError> {
     let mut server_builder = ServerBuilder::new()
         .max_request_body_size(api_config.max_request_body_size)
@@ -159,7 +195,8 @@ async fn serve<T>(
         } else {
             BatchRequestConfig::Disabled
         })
-        .ping_interval(api_config.ping_interval.to_duration());
+        .ping_interval(api_config.ping_interval.to_duration())
+        .set_logger(ClientAddrLogger);
 
     if api_config.enable_http && !api_config.enable_ws {
         server_builder = server_builder.http_only();
@@ -190,7 +227,9 @@ async fn serve<T>(
 
     let middleware = tower::ServiceBuilder::new()
         .layer(cors)
-        .layer(allowed_hosts);
+        .layer(allowed_hosts)
+        .layer(ClientAddrLayer)
+        .option_layer(envelope);
 
     let server = server_builder
         .set_middleware(middleware)
@@ -234,6 +273,13 @@ pub trait MassaRpc {
     #[method(name = "node_sign_message")]
     async fn node_sign_message(&self, arg: Vec<u8>) -> RpcResult<PubkeySig>;
 
+    /// Sign message with node's key, prefixed with a domain separator
+    /// (`MASSA_NODE_SIGNED_MESSAGE:` + domain + message length) so that the signature
+    /// can never be mistaken for an operation, endorsement or block header signature.
+    /// Returns the public key that signed the message and the signature.
+    #[method(name = "node_sign_message_v2")]
+    async fn node_sign_message_v2(&self, message: Vec<u8>, domain: String) -> RpcResult<PubkeySig>;
+
     /// Add a vector of new secret(private) keys for the node to use to stake.
     /// No confirmation to expect.
     #[method(name = "add_staking_secret_keys")]
@@ -262,6 +308,36 @@ pub trait MassaRpc {
     #[method(name = "get_staking_addresses")]
     async fn get_staking_addresses(&self) -> RpcResult<PreHashSet<Address>>;
 
+    /// Returns a dump of the runtime state of the node (pool, block graph, storage, block factory,
+    /// execution and settings), to help debugging it. The lists of items of the dump are capped
+    /// and the secrets of the node are left out. All the sections are included if no request is given.
+    #[method(name = "node_debug_dump")]
+    async fn node_debug_dump(&self, arg: Option<DebugDumpRequest>) -> RpcResult<DebugDump>;
+
+    /// Replace the set of addresses whose operations are left out of the blocks produced by the node.
+    /// An empty list clears the blocklist.
+    /// Block validation, propagation and endorsement production are not affected.
+    #[method(name = "node_set_production_blocklist")]
+    async fn node_set_production_blocklist(&self, arg: Vec<Address>) -> RpcResult<()>;
+
+    /// Compact the final state database on disk, purging the entries deleted since its last compaction.
+    /// This is I/O intensive: run it during low traffic. The final state keeps being written meanwhile.
+    #[method(name = "node_compact_final_state")]
+    async fn node_compact_final_state(&self) -> RpcResult<()>;
+
+    /// Returns the audit of the operation selection of the node for each given slot.
+    /// An audit is only kept for the recent slots, if the audit is enabled in the pool settings.
+    #[method(name = "node_get_selection_audit")]
+    async fn node_get_selection_audit(
+        &self,
+        arg: Vec<Slot>,
+    ) -> RpcResult<Vec<Option<SelectionAudit>>>;
+
+    /// Returns the timings of the last commands executed by the consensus worker, oldest first.
+    /// They are only recorded if the metrics are enabled.
+    #[method(name = "node_get_consensus_command_timings")]
+    async fn node_get_consensus_command_timings(&self) -> RpcResult<Vec<ConsensusCommandTiming>>;
+
     /// Bans given IP address(es).
     /// No confirmation to expect.
     #[method(name = "node_ban_by_ip")]
@@ -331,9 +407,9 @@ pub trait MassaRpc {
     #[method(name = "get_status")]
     async fn get_status(&self) -> RpcResult<NodeStatus>;
 
-    /// Get cliques.
+    /// Get cliques with their fitness, block count and tip blocks.
     #[method(name = "get_cliques")]
-    async fn get_cliques(&self) -> RpcResult<Vec<Clique>>;
+    async fn get_cliques(&self) -> RpcResult<Vec<CliqueInfo>>;
 
     /// Returns the active stakers and their active roll counts for the current cycle.
     #[method(name = "get_stakers")]
@@ -342,6 +418,50 @@ pub trait MassaRpc {
         page_request: Option<PageRequest>,
     ) -> RpcResult<PagedVec<(Address, u64)>>;
 
+    /// Returns a page of a snapshot of the operations or endorsements in the pool.
+    /// Without `sequence`, a new snapshot is taken. The next pages of the same snapshot are requested
+    /// with its `sequence`, while it is retained by the node.
+    /// The returned ids may refer to items that have since left the pool.
+    #[method(name = "get_pool_snapshot")]
+    async fn get_pool_snapshot(
+        &self,
+        kind: PoolItemKind,
+        page_request: Option<PageRequest>,
+        sequence: Option<u64>,
+    ) -> RpcResult<PoolSnapshotPage>;
+
+    /// Returns a page of the final deferred credits between two slots (both included), sorted by slot then address.
+    /// If addresses are given, only their credits are returned.
+    #[method(name = "get_deferred_credits")]
+    async fn get_deferred_credits(
+        &self,
+        request: DeferredCreditsRequest,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<DeferredCredit>>;
+
+    /// Returns the final slashing statistics of the cycles between `min_cycle` and `max_cycle` (both included), sorted by cycle.
+    /// Only the cycles whose executed denunciations are still kept by the node are returned.
+    #[method(name = "get_slashing_stats")]
+    async fn get_slashing_stats(
+        &self,
+        min_cycle: u64,
+        max_cycle: u64,
+    ) -> RpcResult<Vec<SlashingStats>>;
+
+    /// Submits pairs of conflicting block headers or endorsements signed by the same staker, observed outside of the node.
+    /// The valid pairs are forwarded to the denunciation pool, to be denounced in the blocks produced later.
+    /// Returns, in order, whether each pair was accepted or why it was rejected.
+    #[method(name = "submit_denunciation_evidence")]
+    async fn submit_denunciation_evidence(
+        &self,
+        evidences: Vec<DenunciationEvidence>,
+    ) -> RpcResult<Vec<EvidenceOutcome>>;
+
+    /// Returns the gas budget of the execution of a slot: the gas of the asynchronous messages that can be executed at the slot,
+    /// the gas of the ones that would be executed, projected from the candidate asynchronous pool, and the gas available to the operations of the block.
+    #[method(name = "get_slot_execution_budget")]
+    async fn get_slot_execution_budget(&self, slot: Slot) -> RpcResult<SlotExecutionBudget>;
+
     /// Returns operation(s) information associated to a given list of operation(s) ID(s).
     #[method(name = "get_operations")]
     async fn get_operations(&self, arg: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>>;
@@ -354,6 +474,19 @@ pub trait MassaRpc {
     #[method(name = "get_blocks")]
     async fn get_blocks(&self, arg: Vec<BlockId>) -> RpcResult<Vec<BlockInfo>>;
 
+    /// Returns a page of the ids of the blocks created by an address, sorted by slot.
+    /// Only the blocks currently kept in the node storage are returned, older blocks are not archived.
+    #[method(name = "get_blocks_by_creator")]
+    async fn get_blocks_by_creator(
+        &self,
+        address: Address,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<BlockId>>;
+
+    /// Returns the confirmation of a given list of block(s) ID(s), taking the cliques of the graph into account
+    #[method(name = "get_block_confirmation")]
+    async fn get_block_confirmation(&self, arg: Vec<BlockId>) -> RpcResult<Vec<BlockConfirmation>>;
+
     /// Get information on the block at a slot in the blockclique.
     /// If there is no block at this slot a `None` is returned.
     #[method(name = "get_blockclique_block_by_slot")]
@@ -375,9 +508,13 @@ pub trait MassaRpc {
     #[method(name = "get_addresses")]
     async fn get_addresses(&self, arg: Vec<Address>) -> RpcResult<Vec<AddressInfo>>;
 
-    /// Get addresses bytecode.
+    /// Get the bytecode of addresses, in their final or candidate state.
+    /// Only the bytecode is read from the ledger, `None` being returned for addresses without bytecode.
     #[method(name = "get_addresses_bytecode")]
-    async fn get_addresses_bytecode(&self, args: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>>;
+    async fn get_addresses_bytecode(
+        &self,
+        args: Vec<AddressFilter>,
+    ) -> RpcResult<Vec<Option<Vec<u8>>>>;
 
     /// Get all the transfers for a slot
     #[method(name = "get_slots_transfers")]
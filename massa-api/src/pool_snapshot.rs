@@ -0,0 +1,71 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Pool snapshots retained for a while, so that the pages of a snapshot can be requested by its sequence number
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use massa_pool_exports::{PoolSnapshot, PooledEndorsementInfo, PooledOperationInfo};
+
+/// Max number of snapshots of each kind retained at once
+const MAX_RETAINED_SNAPSHOTS: usize = 16;
+
+/// Latest snapshots of a kind of pool items
+pub(crate) struct RetainedSnapshots<T> {
+    /// how long a snapshot is retained after it was taken
+    retention: Duration,
+    /// snapshots along with when they were taken, oldest first
+    snapshots: VecDeque<(Instant, PoolSnapshot<T>)>,
+}
+
+impl<T> RetainedSnapshots<T> {
+    pub(crate) fn new(retention: Duration) -> Self {
+        RetainedSnapshots {
+            retention,
+            snapshots: VecDeque::new(),
+        }
+    }
+
+    /// Retain a snapshot that was just taken, dropping the oldest one if too many are retained
+    pub(crate) fn retain(&mut self, snapshot: PoolSnapshot<T>) {
+        self.prune();
+        if self.retention.is_zero() {
+            return;
+        }
+        if self.snapshots.len() >= MAX_RETAINED_SNAPSHOTS {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back((Instant::now(), snapshot));
+    }
+
+    /// Get the retained snapshot of sequence number `sequence`, if it did not expire
+    pub(crate) fn get(&mut self, sequence: u64) -> Option<PoolSnapshot<T>> {
+        self.prune();
+        self.snapshots
+            .iter()
+            .find(|(_, snapshot)| snapshot.sequence == sequence)
+            .map(|(_, snapshot)| snapshot.clone())
+    }
+
+    /// Drop the expired snapshots
+    fn prune(&mut self) {
+        let retention = self.retention;
+        self.snapshots
+            .retain(|(taken_at, _)| taken_at.elapsed() < retention);
+    }
+}
+
+/// Snapshots of the operations and endorsements of the pool
+pub(crate) struct PoolSnapshots {
+    pub(crate) operations: RetainedSnapshots<PooledOperationInfo>,
+    pub(crate) endorsements: RetainedSnapshots<PooledEndorsementInfo>,
+}
+
+impl PoolSnapshots {
+    pub(crate) fn new(retention: Duration) -> Self {
+        PoolSnapshots {
+            operations: RetainedSnapshots::new(retention),
+            endorsements: RetainedSnapshots::new(retention),
+        }
+    }
+}
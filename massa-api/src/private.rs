@@ -6,26 +6,54 @@ use async_trait::async_trait;
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_api_exports::{
     address::{AddressFilter, AddressInfo},
-    block::{BlockInfo, BlockSummary},
+    block::{BlockConfirmation, BlockInfo, BlockSummary},
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    debug_dump::{
+        ConfigDump, DebugDump, DebugDumpRequest, DiscardedBlockDump, ExecutionDump, FactoryDump,
+        GraphDump, PoolDump, SlotProductionDump, SlotProductionOutcomeDump, StorageDump,
+        StorageUsageDump, WaitingBlockDump,
+    },
+    deferred_credits::{DeferredCredit, DeferredCreditsRequest},
+    denunciation::{DenunciationEvidence, EvidenceOutcome},
     endorsement::EndorsementInfo,
     error::ApiError,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, Transfer},
+    execution::{
+        ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, SlotExecutionBudget,
+        Transfer,
+    },
     node::NodeStatus,
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    pool::{PoolItemKind, PoolSnapshotPage},
+    slashing::SlashingStats,
     ListType, ScrudOperation, TimeInterval,
 };
+use massa_consensus_exports::{
+    block_status::DiscardReason, command_timing::ConsensusCommandTiming, ConsensusController,
+};
 use massa_execution_exports::ExecutionController;
+use massa_factory_exports::{ProductionHistory, SlotProductionOutcome};
+use massa_final_state::FinalStateController;
 use massa_hash::Hash;
 use massa_models::{
-    address::Address, block::Block, block_id::BlockId, clique::Clique, composite::PubkeySig,
-    endorsement::EndorsementId, execution::EventFilter, node::NodeId, operation::OperationId,
-    output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot,
+    address::Address,
+    block::Block,
+    block_id::BlockId,
+    clique::CliqueInfo,
+    composite::{compute_node_signed_message_hash, PubkeySig},
+    endorsement::EndorsementId,
+    execution::EventFilter,
+    node::NodeId,
+    operation::OperationId,
+    output_event::SCOutputEvent,
+    prehash::PreHashSet,
+    slot::Slot,
 };
+use massa_pool_exports::{PoolController, SelectionAudit};
 use massa_protocol_exports::{PeerId, ProtocolController};
 use massa_signature::KeyPair;
+use massa_storage::{Storage, StorageCategoryUsage};
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::net::{IpAddr, SocketAddr};
@@ -42,21 +70,166 @@ impl API<Private> {
     /// generate a new private API
     pub fn new(
         protocol_controller: Box<dyn ProtocolController>,
+        consensus_controller: Box<dyn ConsensusController>,
         execution_controller: Box<dyn ExecutionController>,
+        pool_controller: Box<dyn PoolController>,
         api_settings: APIConfig,
         stop_cv: Arc<(Mutex<bool>, Condvar)>,
         node_wallet: Arc<RwLock<Wallet>>,
+        production_blocklist: Arc<RwLock<PreHashSet<Address>>>,
+        final_state: Arc<RwLock<dyn FinalStateController>>,
+        storage: Storage,
+        production_history: Arc<RwLock<ProductionHistory>>,
     ) -> Self {
         API(Private {
             protocol_controller,
+            consensus_controller,
             execution_controller,
+            pool_controller,
             api_settings,
             stop_cv,
             node_wallet,
+            production_blocklist,
+            final_state,
+            storage,
+            production_history,
         })
     }
 }
 
+/// Maximum number of items listed in each section of a debug dump
+const DEBUG_DUMP_MAX_ITEMS: usize = 100;
+
+impl Private {
+    /// Assemble a dump of the runtime state of the node with the requested sections
+    pub(crate) fn debug_dump(&self, request: DebugDumpRequest) -> DebugDump {
+        DebugDump {
+            pool: request.pool.then(|| self.debug_dump_pool()),
+            graph: request.graph.then(|| self.debug_dump_graph()),
+            storage: request.storage.then(|| {
+                let stats = self.storage.get_stats();
+                let usage = |usage: StorageCategoryUsage| StorageUsageDump {
+                    stored: usage.stored,
+                    max: usage.max,
+                };
+                StorageDump {
+                    operations: usage(stats.operations),
+                    endorsements: usage(stats.endorsements),
+                    blocks: usage(stats.blocks),
+                    bytes: usage(stats.bytes),
+                }
+            }),
+            factory: request.factory.then(|| self.debug_dump_factory()),
+            execution: request.execution.then(|| ExecutionDump {
+                stats: self.execution_controller.get_stats(),
+                backlog: self.execution_controller.get_backlog(),
+            }),
+            config: request.config.then(|| {
+                let node_public_key = self.api_settings.keypair.get_public_key();
+                ConfigDump {
+                    node_public_key,
+                    node_address: Address::from_public_key(&node_public_key),
+                    chain_id: self.api_settings.chain_id,
+                    thread_count: self.api_settings.thread_count,
+                    last_start_period: self.api_settings.last_start_period,
+                    bind_public: self.api_settings.bind_public,
+                    bind_private: self.api_settings.bind_private,
+                    bind_api: self.api_settings.bind_api,
+                    max_arguments: self.api_settings.max_arguments,
+                    max_connections: self.api_settings.max_connections,
+                    minimal_fees: self.api_settings.minimal_fees,
+                }
+            }),
+        }
+    }
+
+    fn debug_dump_pool(&self) -> PoolDump {
+        PoolDump {
+            operation_count: self.pool_controller.get_operation_count(),
+            endorsement_count: self.pool_controller.get_endorsement_count(),
+            denunciation_count: self.pool_controller.get_denunciation_count(),
+            final_cs_periods: self.pool_controller.get_final_cs_periods(),
+            operations: self
+                .pool_controller
+                .get_operations_sample(DEBUG_DUMP_MAX_ITEMS),
+        }
+    }
+
+    fn debug_dump_graph(&self) -> GraphDump {
+        let summary = self
+            .consensus_controller
+            .get_block_graph_summary(DEBUG_DUMP_MAX_ITEMS);
+        GraphDump {
+            stats: self.consensus_controller.get_stats().ok(),
+            incoming_block_count: summary.incoming_block_count,
+            waiting_for_slot_block_count: summary.waiting_for_slot_block_count,
+            waiting_for_dependencies_block_count: summary.waiting_for_dependencies_block_count,
+            active_block_count: summary.active_block_count,
+            discarded_block_count: summary.discarded_block_count,
+            best_parents: summary.best_parents,
+            latest_final_blocks_periods: summary.latest_final_blocks_periods,
+            clique_count: summary.clique_count,
+            waiting_for_dependencies: summary
+                .waiting_for_dependencies
+                .into_iter()
+                .map(|block| WaitingBlockDump {
+                    block_id: block.block_id,
+                    slot: block.slot,
+                    missing_dependencies: block.missing_dependencies,
+                })
+                .collect(),
+            discarded_blocks: summary
+                .discarded_blocks
+                .into_iter()
+                .map(|block| DiscardedBlockDump {
+                    block_id: block.block_id,
+                    slot: block.slot,
+                    creator: block.creator,
+                    parents: block.parents,
+                    reason: match block.reason {
+                        DiscardReason::Invalid(reason) => format!("invalid: {}", reason),
+                        DiscardReason::Stale => "stale".to_string(),
+                        DiscardReason::Final => "final".to_string(),
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    fn debug_dump_factory(&self) -> FactoryDump {
+        FactoryDump {
+            production_history: self
+                .production_history
+                .read()
+                .iter()
+                .take(DEBUG_DUMP_MAX_ITEMS)
+                .map(|report| SlotProductionDump {
+                    slot: report.slot,
+                    producer: report.producer,
+                    outcome: match &report.outcome {
+                        SlotProductionOutcome::Produced {
+                            block_id,
+                            operation_count,
+                            endorsement_count,
+                            denunciation_count,
+                        } => SlotProductionOutcomeDump::Produced {
+                            block_id: *block_id,
+                            operation_count: *operation_count,
+                            endorsement_count: *endorsement_count,
+                            denunciation_count: *denunciation_count,
+                        },
+                        SlotProductionOutcome::Skipped { reason } => {
+                            SlotProductionOutcomeDump::Skipped {
+                                reason: reason.clone(),
+                            }
+                        }
+                    },
+                })
+                .collect(),
+        }
+    }
+}
+
 #[async_trait]
 impl RpcServer for API<Private> {
     async fn serve(
@@ -64,7 +237,7 @@ impl RpcServer for API<Private> {
         url: &SocketAddr,
         settings: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, settings).await
+        crate::serve(self.into_rpc(), url, settings, None).await
     }
 }
 
@@ -97,6 +270,23 @@ impl MassaRpcServer for API<Private> {
         })
     }
 
+    async fn node_sign_message_v2(&self, message: Vec<u8>, domain: String) -> RpcResult<PubkeySig> {
+        let hash = compute_node_signed_message_hash(&message, &domain)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        let signature = match self.0.api_settings.keypair.sign(&hash) {
+            Ok(signature) => signature,
+            Err(e) => {
+                return Err(
+                    ApiError::InconsistencyError(format!("error signing message: {}", e)).into(),
+                );
+            }
+        };
+        Ok(PubkeySig {
+            public_key: self.0.api_settings.keypair.get_public_key(),
+            signature,
+        })
+    }
+
     async fn add_staking_secret_keys(&self, secret_keys: Vec<String>) -> RpcResult<()> {
         let keypairs = match secret_keys.iter().map(|x| KeyPair::from_str(x)).collect() {
             Ok(keypairs) => keypairs,
@@ -147,6 +337,42 @@ impl MassaRpcServer for API<Private> {
         Ok(w_wallet.get_wallet_address_list())
     }
 
+    async fn node_debug_dump(&self, request: Option<DebugDumpRequest>) -> RpcResult<DebugDump> {
+        Ok(self.0.debug_dump(request.unwrap_or_default()))
+    }
+
+    async fn node_set_production_blocklist(&self, addresses: Vec<Address>) -> RpcResult<()> {
+        *self.0.production_blocklist.write() = addresses.into_iter().collect();
+        Ok(())
+    }
+
+    async fn node_compact_final_state(&self) -> RpcResult<()> {
+        // the locks are only held to get the job: the final state keeps being written while compacting
+        let compaction_job = self.0.final_state.read().get_compaction_job();
+        // the compaction can take a while, keep it off the async runtime
+        tokio::task::spawn_blocking(compaction_job)
+            .await
+            .map_err(|err| ApiError::InternalServerError(err.to_string()))?
+            .map_err(|err| ApiError::InternalServerError(err.to_string()).into())
+    }
+
+    async fn node_get_selection_audit(
+        &self,
+        slots: Vec<Slot>,
+    ) -> RpcResult<Vec<Option<SelectionAudit>>> {
+        if slots.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        Ok(slots
+            .iter()
+            .map(|slot| self.0.pool_controller.get_selection_audit(slot))
+            .collect())
+    }
+
+    async fn node_get_consensus_command_timings(&self) -> RpcResult<Vec<ConsensusCommandTiming>> {
+        Ok(self.0.consensus_controller.get_command_timings())
+    }
+
     async fn node_ban_by_ip(&self, _ips: Vec<IpAddr>) -> RpcResult<()> {
         //TODO: Reinvoke
         // let network_command_sender = self.0.network_command_sender.clone();
@@ -203,14 +429,46 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<NodeStatus>()
     }
 
-    async fn get_cliques(&self) -> RpcResult<Vec<Clique>> {
-        crate::wrong_api::<Vec<Clique>>()
+    async fn get_cliques(&self) -> RpcResult<Vec<CliqueInfo>> {
+        crate::wrong_api::<Vec<CliqueInfo>>()
     }
 
     async fn get_stakers(&self, _: Option<PageRequest>) -> RpcResult<PagedVec<(Address, u64)>> {
         crate::wrong_api::<PagedVec<(Address, u64)>>()
     }
 
+    async fn get_pool_snapshot(
+        &self,
+        _: PoolItemKind,
+        _: Option<PageRequest>,
+        _: Option<u64>,
+    ) -> RpcResult<PoolSnapshotPage> {
+        crate::wrong_api::<PoolSnapshotPage>()
+    }
+
+    async fn get_deferred_credits(
+        &self,
+        _: DeferredCreditsRequest,
+        _: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<DeferredCredit>> {
+        crate::wrong_api::<PagedVec<DeferredCredit>>()
+    }
+
+    async fn get_slashing_stats(&self, _: u64, _: u64) -> RpcResult<Vec<SlashingStats>> {
+        crate::wrong_api::<Vec<SlashingStats>>()
+    }
+
+    async fn submit_denunciation_evidence(
+        &self,
+        _: Vec<DenunciationEvidence>,
+    ) -> RpcResult<Vec<EvidenceOutcome>> {
+        crate::wrong_api::<Vec<EvidenceOutcome>>()
+    }
+
+    async fn get_slot_execution_budget(&self, _: Slot) -> RpcResult<SlotExecutionBudget> {
+        crate::wrong_api::<SlotExecutionBudget>()
+    }
+
     async fn get_operations(&self, _: Vec<OperationId>) -> RpcResult<Vec<OperationInfo>> {
         crate::wrong_api::<Vec<OperationInfo>>()
     }
@@ -223,6 +481,18 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<BlockInfo>>()
     }
 
+    async fn get_blocks_by_creator(
+        &self,
+        _: Address,
+        _: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<BlockId>> {
+        crate::wrong_api::<PagedVec<BlockId>>()
+    }
+
+    async fn get_block_confirmation(&self, _: Vec<BlockId>) -> RpcResult<Vec<BlockConfirmation>> {
+        crate::wrong_api::<Vec<BlockConfirmation>>()
+    }
+
     async fn get_blockclique_block_by_slot(&self, _: Slot) -> RpcResult<Option<Block>> {
         crate::wrong_api::<Option<Block>>()
     }
@@ -242,8 +512,11 @@ impl MassaRpcServer for API<Private> {
         crate::wrong_api::<Vec<AddressInfo>>()
     }
 
-    async fn get_addresses_bytecode(&self, _: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>> {
-        crate::wrong_api::<Vec<Vec<u8>>>()
+    async fn get_addresses_bytecode(
+        &self,
+        _: Vec<AddressFilter>,
+    ) -> RpcResult<Vec<Option<Vec<u8>>>> {
+        crate::wrong_api::<Vec<Option<Vec<u8>>>>()
     }
 
     async fn send_operations(&self, _: Vec<OperationInput>) -> RpcResult<Vec<OperationId>> {
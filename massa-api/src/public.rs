@@ -1,30 +1,43 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 #![allow(clippy::too_many_arguments)]
 
+use crate::client_addr::client_ip;
+use crate::denunciation::{check_denunciation_evidence, EvidenceRateLimiter};
+use crate::envelope::EnvelopeLayer;
+use crate::pool_snapshot::{PoolSnapshots, RetainedSnapshots};
 use crate::{MassaRpcServer, Public, RpcServer, StopHandle, Value, API};
 use async_trait::async_trait;
 use itertools::{izip, Itertools};
 use jsonrpsee::core::{Error as JsonRpseeError, RpcResult};
 use massa_api_exports::{
     address::{AddressFilter, AddressInfo},
-    block::{BlockInfo, BlockInfoContent, BlockSummary},
+    block::{
+        endorsement_fill_ratio, BlockConfirmation, BlockInfo, BlockInfoContent, BlockSummary,
+        EndorsementCoverage,
+    },
     config::APIConfig,
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    debug_dump::{DebugDump, DebugDumpRequest},
+    deferred_credits::{DeferredCredit, DeferredCreditsRequest},
+    denunciation::{DenunciationEvidence, EvidenceOutcome},
     endorsement::EndorsementInfo,
     error::ApiError,
     execution::{
-        ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult, Transfer,
+        ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult,
+        SlotExecutionBudget, Transfer,
     },
     node::NodeStatus,
     operation::{OperationInfo, OperationInput},
     page::{PageRequest, PagedVec},
+    pool::{PoolItemKind, PoolSnapshotItems, PoolSnapshotPage},
+    slashing::SlashingStats,
     slot::SlotAmount,
     TimeInterval,
 };
 use massa_consensus_exports::block_status::DiscardReason;
-use massa_consensus_exports::ConsensusController;
+use massa_consensus_exports::{command_timing::ConsensusCommandTiming, ConsensusController};
 use massa_execution_exports::{
-    ExecutionController, ExecutionQueryRequest, ExecutionQueryRequestItem,
+    ExecutionController, ExecutionQueryError, ExecutionQueryRequest, ExecutionQueryRequestItem,
     ExecutionQueryResponseItem, ExecutionStackElement, ReadOnlyExecutionRequest,
     ReadOnlyExecutionTarget,
 };
@@ -33,10 +46,10 @@ use massa_models::{
     amount::Amount,
     block::{Block, BlockGraphStatus},
     block_id::BlockId,
-    clique::Clique,
+    clique::{fitness_gap_to_second_clique, CliqueInfo},
     composite::PubkeySig,
-    config::CompactConfig,
-    datastore::DatastoreDeserializer,
+    config::{CompactConfig, ENDORSEMENT_COUNT},
+    datastore::{DatastoreDeserializer, DatastoreLimits},
     endorsement::EndorsementId,
     endorsement::SecureShareEndorsement,
     error::ModelsError,
@@ -53,7 +66,7 @@ use massa_models::{
     timeslots::{get_latest_block_slot_at_timestamp, time_range_to_slot_range},
     version::Version,
 };
-use massa_pool_exports::PoolController;
+use massa_pool_exports::{PoolController, PoolSnapshot, SelectionAudit};
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::{PeerConnectionType, ProtocolConfig, ProtocolController};
 use massa_serialization::{DeserializeError, Deserializer};
@@ -81,6 +94,12 @@ impl API<Public> {
         storage: Storage,
         mip_store: MipStore,
     ) -> Self {
+        let evidence_rate_limiter = parking_lot::Mutex::new(EvidenceRateLimiter::new(
+            api_settings.max_denunciation_evidences_per_second,
+        ));
+        let pool_snapshots = parking_lot::Mutex::new(PoolSnapshots::new(
+            api_settings.pool_snapshot_retention.to_duration(),
+        ));
         API(Public {
             consensus_controller,
             api_settings,
@@ -93,6 +112,8 @@ impl API<Public> {
             protocol_config,
             storage,
             keypair_factory: KeyPairFactory { mip_store },
+            evidence_rate_limiter,
+            pool_snapshots,
         })
     }
 }
@@ -104,7 +125,8 @@ impl RpcServer for API<Public> {
         url: &SocketAddr,
         api_config: &APIConfig,
     ) -> Result<StopHandle, JsonRpseeError> {
-        crate::serve(self.into_rpc(), url, api_config).await
+        let envelope = EnvelopeLayer::new(self.0.version, self.0.api_settings.chain_id);
+        crate::serve(self.into_rpc(), url, api_config, Some(envelope)).await
     }
 }
 
@@ -119,6 +141,10 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<PubkeySig>()
     }
 
+    async fn node_sign_message_v2(&self, _: Vec<u8>, _: String) -> RpcResult<PubkeySig> {
+        crate::wrong_api::<PubkeySig>()
+    }
+
     async fn add_staking_secret_keys(&self, _: Vec<String>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -232,6 +258,9 @@ impl MassaRpcServer for API<Public> {
             bytecode,
             operation_datastore,
             fee,
+            profile,
+            rng_seed,
+            include_trace,
         } in reqs
         {
             let address = if let Some(addr) = address {
@@ -279,6 +308,9 @@ impl MassaRpcServer for API<Public> {
                 }],
                 coins: None,
                 fee,
+                profile,
+                rng_seed,
+                include_trace,
             };
 
             // check if fee is enough
@@ -293,6 +325,9 @@ impl MassaRpcServer for API<Public> {
                         gas_cost: 0,
                         output_events: Default::default(),
                         state_changes: Default::default(),
+                        error_kind: None,
+                        gas_profile: None,
+                        call_trace: None,
                     };
                     res.push(result);
                     continue;
@@ -312,9 +347,20 @@ impl MassaRpcServer for API<Public> {
                     |res| ReadOnlyResult::Ok(res.call_result.clone()),
                 ),
                 gas_cost: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
+                gas_profile: result
+                    .as_ref()
+                    .map_or_else(|_| None, |v| v.gas_profile.clone()),
+                call_trace: result.as_ref().map_or_else(
+                    |err| err.readonly_call_trace().cloned(),
+                    |v| v.call_trace.clone(),
+                ),
                 output_events: result
                     .as_ref()
                     .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
+                error_kind: result
+                    .as_ref()
+                    .err()
+                    .and_then(|err| err.readonly_error_kind()),
                 state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
             };
 
@@ -343,6 +389,9 @@ impl MassaRpcServer for API<Public> {
             caller_address,
             coins,
             fee,
+            profile,
+            rng_seed,
+            include_trace,
         } in reqs
         {
             let caller_address = if let Some(addr) = caller_address {
@@ -381,6 +430,9 @@ impl MassaRpcServer for API<Public> {
                 ],
                 coins,
                 fee,
+                profile,
+                rng_seed,
+                include_trace,
             };
 
             if let Some(fee) = fee {
@@ -394,6 +446,9 @@ impl MassaRpcServer for API<Public> {
                         gas_cost: 0,
                         output_events: Default::default(),
                         state_changes: Default::default(),
+                        error_kind: None,
+                        gas_profile: None,
+                        call_trace: None,
                     };
                     res.push(result);
                     continue;
@@ -413,9 +468,20 @@ impl MassaRpcServer for API<Public> {
                     |res| ReadOnlyResult::Ok(res.call_result.clone()),
                 ),
                 gas_cost: result.as_ref().map_or_else(|_| 0, |v| v.gas_cost),
+                gas_profile: result
+                    .as_ref()
+                    .map_or_else(|_| None, |v| v.gas_profile.clone()),
+                call_trace: result.as_ref().map_or_else(
+                    |err| err.readonly_call_trace().cloned(),
+                    |v| v.call_trace.clone(),
+                ),
                 output_events: result
                     .as_ref()
                     .map_or_else(|_| Default::default(), |v| v.out.events.clone().0),
+                error_kind: result
+                    .as_ref()
+                    .err()
+                    .and_then(|err| err.readonly_error_kind()),
                 state_changes: result.map_or_else(|_| Default::default(), |v| v.out.state_changes),
             };
 
@@ -434,6 +500,29 @@ impl MassaRpcServer for API<Public> {
         crate::wrong_api::<PreHashSet<Address>>()
     }
 
+    async fn node_debug_dump(&self, _: Option<DebugDumpRequest>) -> RpcResult<DebugDump> {
+        crate::wrong_api::<DebugDump>()
+    }
+
+    async fn node_set_production_blocklist(&self, _: Vec<Address>) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_compact_final_state(&self) -> RpcResult<()> {
+        crate::wrong_api::<()>()
+    }
+
+    async fn node_get_selection_audit(
+        &self,
+        _: Vec<Slot>,
+    ) -> RpcResult<Vec<Option<SelectionAudit>>> {
+        crate::wrong_api::<Vec<Option<SelectionAudit>>>()
+    }
+
+    async fn node_get_consensus_command_timings(&self) -> RpcResult<Vec<ConsensusCommandTiming>> {
+        crate::wrong_api::<Vec<ConsensusCommandTiming>>()
+    }
+
     async fn node_ban_by_ip(&self, _: Vec<IpAddr>) -> RpcResult<()> {
         crate::wrong_api::<()>()
     }
@@ -476,12 +565,18 @@ impl MassaRpcServer for API<Public> {
             Ok(consensus_stats) => consensus_stats,
             Err(e) => return Err(ApiError::ConsensusError(e.to_string()).into()),
         };
+        let cliques = self.0.consensus_controller.get_cliques_info();
+        let clique_count = cliques.len();
+        let fitness_gap_to_second_clique = fitness_gap_to_second_clique(&cliques);
 
         let (network_stats, peers) = match self.0.protocol_controller.get_stats() {
             Ok((stats, peers)) => (stats, peers),
             Err(e) => return Err(ApiError::ProtocolError(e.to_string()).into()),
         };
 
+        // the traffic section is optional: the status is still served without it
+        let network_traffic = self.0.protocol_controller.get_network_stats().ok();
+
         let pool_stats = (
             self.0.pool_command_sender.get_operation_count(),
             self.0.pool_command_sender.get_endorsement_count(),
@@ -550,7 +645,10 @@ impl MassaRpcServer for API<Public> {
             next_slot,
             execution_stats,
             consensus_stats,
+            clique_count,
+            fitness_gap_to_second_clique,
             network_stats,
+            network_traffic,
             pool_stats,
             config,
             current_cycle,
@@ -560,8 +658,8 @@ impl MassaRpcServer for API<Public> {
     }
 
     /// get cliques
-    async fn get_cliques(&self) -> RpcResult<Vec<Clique>> {
-        Ok(self.0.consensus_controller.get_cliques())
+    async fn get_cliques(&self) -> RpcResult<Vec<CliqueInfo>> {
+        Ok(self.0.consensus_controller.get_cliques_info())
     }
 
     /// get stakers
@@ -604,20 +702,215 @@ impl MassaRpcServer for API<Public> {
         Ok(paged_vec)
     }
 
+    /// get a page of a pool snapshot
+    async fn get_pool_snapshot(
+        &self,
+        kind: PoolItemKind,
+        page_request: Option<PageRequest>,
+        sequence: Option<u64>,
+    ) -> RpcResult<PoolSnapshotPage> {
+        let pool = &self.0.pool_command_sender;
+        Ok(match kind {
+            PoolItemKind::Operations => PoolSnapshotPage::new(
+                get_or_take_snapshot(
+                    sequence,
+                    || pool.snapshot_operations(),
+                    |snapshots| &mut snapshots.operations,
+                    &self.0.pool_snapshots,
+                )?,
+                page_request,
+                PoolSnapshotItems::Operations,
+            ),
+            PoolItemKind::Endorsements => PoolSnapshotPage::new(
+                get_or_take_snapshot(
+                    sequence,
+                    || pool.snapshot_endorsements(),
+                    |snapshots| &mut snapshots.endorsements,
+                    &self.0.pool_snapshots,
+                )?,
+                page_request,
+                PoolSnapshotItems::Endorsements,
+            ),
+        })
+    }
+
+    /// get a page of the final deferred credits of a slot range
+    async fn get_deferred_credits(
+        &self,
+        request: DeferredCreditsRequest,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<DeferredCredit>> {
+        let api_cfg = &self.0.api_settings;
+        if request.min_slot > request.max_slot {
+            return Err(ApiError::BadRequest("min_slot must not be after max_slot".into()).into());
+        }
+        // a range whose slot count overflows is too large as well
+        let too_large = request
+            .max_slot
+            .slots_since(&request.min_slot, api_cfg.thread_count)
+            .map_or(true, |slots| {
+                slots >= api_cfg.max_deferred_credits_slot_range
+            });
+        if too_large {
+            return Err(ApiError::BadRequest(format!(
+                "slot range too large: at most {} slots are accepted per request",
+                api_cfg.max_deferred_credits_slot_range
+            ))
+            .into());
+        }
+        if let Some(addresses) = &request.addresses {
+            if addresses.len() as u64 > api_cfg.max_arguments {
+                return Err(ApiError::BadRequest("too many arguments".into()).into());
+            }
+        }
+
+        let credits: Vec<DeferredCredit> = self
+            .0
+            .execution_controller
+            .get_deferred_credits_range(request.min_slot, request.max_slot, request.addresses)
+            .into_iter()
+            .flat_map(|(slot, credits)| {
+                credits
+                    .into_iter()
+                    .map(move |(address, amount)| DeferredCredit {
+                        slot,
+                        address,
+                        amount,
+                    })
+            })
+            .collect();
+
+        Ok(PagedVec::new(credits, page_request))
+    }
+
+    /// get the final slashing statistics of a cycle range
+    async fn get_slashing_stats(
+        &self,
+        min_cycle: u64,
+        max_cycle: u64,
+    ) -> RpcResult<Vec<SlashingStats>> {
+        if min_cycle > max_cycle {
+            return Err(
+                ApiError::BadRequest("min_cycle must not be after max_cycle".into()).into(),
+            );
+        }
+        Ok(self
+            .0
+            .execution_controller
+            .get_cycle_slashing_stats(min_cycle, max_cycle)
+            .into_iter()
+            .map(SlashingStats::from)
+            .collect())
+    }
+
+    /// check denunciation evidences and forward the valid ones to the denunciation pool
+    async fn submit_denunciation_evidence(
+        &self,
+        evidences: Vec<DenunciationEvidence>,
+    ) -> RpcResult<Vec<EvidenceOutcome>> {
+        let api_cfg = &self.0.api_settings;
+        if evidences.len() as u64 > api_cfg.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        {
+            let mut rate_limiter = self.0.evidence_rate_limiter.lock();
+            // a batch over the limit would never be accepted, retrying it is pointless
+            if evidences.len() as u64 > rate_limiter.limit() {
+                return Err(ApiError::BadRequest(format!(
+                    "at most {} denunciation evidences are accepted per second",
+                    rate_limiter.limit()
+                ))
+                .into());
+            }
+            if !rate_limiter.try_acquire(client_ip(), evidences.len() as u64) {
+                return Err(ApiError::BadRequest(
+                    "too many denunciation evidences submitted, retry later".into(),
+                )
+                .into());
+            }
+        }
+
+        let final_period = self.0.execution_controller.get_stats().final_cursor.period;
+        let current_period = timeslots::get_closest_slot_to_timestamp(
+            api_cfg.thread_count,
+            api_cfg.t0,
+            api_cfg.genesis_timestamp,
+            MassaTime::now(),
+        )
+        .period;
+        Ok(evidences
+            .into_iter()
+            .map(|evidence| {
+                match check_denunciation_evidence(
+                    evidence,
+                    api_cfg,
+                    self.0.execution_controller.as_ref(),
+                    final_period,
+                    current_period,
+                ) {
+                    Ok(precursors) => {
+                        for precursor in precursors {
+                            self.0
+                                .pool_command_sender
+                                .add_denunciation_precursor(precursor);
+                        }
+                        EvidenceOutcome::Accepted
+                    }
+                    Err(reason) => EvidenceOutcome::Rejected(reason),
+                }
+            })
+            .collect())
+    }
+
+    /// get the gas budget of the execution of a slot
+    async fn get_slot_execution_budget(&self, slot: Slot) -> RpcResult<SlotExecutionBudget> {
+        if slot.thread >= self.0.api_settings.thread_count {
+            return Err(ApiError::BadRequest("invalid slot thread".into()).into());
+        }
+        Ok(self.0.execution_controller.get_slot_budget(slot).into())
+    }
+
     /// get operations
     async fn get_operations(
         &self,
         operations_ids: Vec<OperationId>,
     ) -> RpcResult<Vec<OperationInfo>> {
         // get the operations and the list of blocks that contain them from storage
-        let secure_share_operations: Vec<SecureShareOperation> = {
+        let mut found_operations: Vec<Option<SecureShareOperation>> = {
             let read_ops = self.0.storage.read_operations();
             operations_ids
                 .iter()
-                .filter_map(|id| read_ops.get(id).cloned())
+                .map(|id| read_ops.get(id).cloned())
                 .collect()
         };
 
+        // the operations missing from storage may have expired without being executed
+        let missing_ids: Vec<OperationId> = operations_ids
+            .iter()
+            .zip(found_operations.iter())
+            .filter(|(_, operation)| operation.is_none())
+            .map(|(id, _)| *id)
+            .collect();
+        let mut expired_ids = PreHashSet::<OperationId>::default();
+        if !missing_ids.is_empty() {
+            let expired_operations = self
+                .0
+                .pool_command_sender
+                .get_expired_operations(&missing_ids);
+            for (operation, expired) in found_operations
+                .iter_mut()
+                .filter(|operation| operation.is_none())
+                .zip(expired_operations)
+            {
+                if let Some(expired) = expired {
+                    expired_ids.insert(expired.operation.id);
+                    *operation = Some(expired.operation);
+                }
+            }
+        }
+        let secure_share_operations: Vec<SecureShareOperation> =
+            found_operations.into_iter().flatten().collect();
+
         let storage_info: Vec<(SecureShareOperation, PreHashSet<BlockId>)> = {
             let read_blocks = self.0.storage.read_blocks();
             secure_share_operations
@@ -646,6 +939,29 @@ impl MassaRpcServer for API<Public> {
         // ask pool whether it carries the operations
         let in_pool = self.0.pool_command_sender.contains_operations(&ops);
 
+        // confirmation score of the blocks including the operations
+        let including_blocks: Vec<BlockId> = storage_info
+            .iter()
+            .flat_map(|(_, in_blocks)| in_blocks.iter().copied())
+            .collect::<PreHashSet<BlockId>>()
+            .into_iter()
+            .collect();
+        let block_scores: PreHashMap<BlockId, u8> = if including_blocks.is_empty() {
+            PreHashMap::default()
+        } else {
+            let confirmations = self
+                .0
+                .consensus_controller
+                .get_confirmation_info(&including_blocks);
+            including_blocks
+                .into_iter()
+                .zip(confirmations)
+                .filter_map(|(id, confirmation)| {
+                    confirmation.map(|confirmation| (id, confirmation.confirmation_score))
+                })
+                .collect()
+        };
+
         let op_exec_statuses = self.0.execution_controller.get_ops_exec_status(&ops);
 
         // compute operation finality and operation execution status from *_op_exec_statuses
@@ -675,6 +991,11 @@ impl MassaRpcServer for API<Public> {
         for (id, (operation, in_blocks), in_pool, is_operation_final, op_exec_status) in
             zipped_iterator
         {
+            let confirmation_score = in_blocks
+                .iter()
+                .filter_map(|block_id| block_scores.get(block_id))
+                .max()
+                .copied();
             #[cfg(feature = "execution-trace")]
             {
                 let mut transfer = None;
@@ -693,6 +1014,8 @@ impl MassaRpcServer for API<Public> {
                     operation,
                     in_blocks: in_blocks.into_iter().collect(),
                     op_exec_status,
+                    is_expired: expired_ids.contains(&id),
+                    confirmation_score,
                 });
             }
             #[cfg(not(feature = "execution-trace"))]
@@ -707,6 +1030,8 @@ impl MassaRpcServer for API<Public> {
                     operation,
                     in_blocks: in_blocks.into_iter().collect(),
                     op_exec_status,
+                    is_expired: expired_ids.contains(&id),
+                    confirmation_score,
                 });
             }
         }
@@ -813,36 +1138,94 @@ impl MassaRpcServer for API<Public> {
     /// Returns only active blocks are returned
     async fn get_blocks(&self, mut ids: Vec<BlockId>) -> RpcResult<Vec<BlockInfo>> {
         let mut blocks: Vec<Block> = Vec::with_capacity(ids.len());
-        {
-            let block_storage_lock = self.0.storage.read_blocks();
+        self.0.storage.with_blocks(|block_storage| {
             ids.retain(|id| {
-                if let Some(wrapped_block) = block_storage_lock.get(id) {
+                if let Some(wrapped_block) = block_storage.get(id) {
                     blocks.push(wrapped_block.content.clone());
                     return true;
                 };
                 false
-            });
-        }
+            })
+        });
         let block_statuses = self.0.consensus_controller.get_block_statuses(&ids);
+
+        // endorsers drawn for blocks older than the lookback are not attributed
+        let cur_period = timeslots::get_current_latest_block_slot(
+            self.0.api_settings.thread_count,
+            self.0.api_settings.t0,
+            self.0.api_settings.genesis_timestamp,
+        )
+        .expect("could not get latest current slot")
+        .map_or(0, |slot| slot.period);
+        let min_attributed_period = cur_period.saturating_sub(
+            self.0
+                .api_settings
+                .endorsement_attribution_lookback_period_count,
+        );
+
         let res = ids
             .into_iter()
             .zip(blocks)
             .zip(block_statuses)
-            .map(|((id, content), graph_status)| BlockInfo {
-                id,
-                content: Some(BlockInfoContent {
-                    is_final: graph_status == BlockGraphStatus::Final,
-                    is_in_blockclique: graph_status == BlockGraphStatus::ActiveInBlockclique,
-                    is_candidate: graph_status == BlockGraphStatus::ActiveInBlockclique
-                        || graph_status == BlockGraphStatus::ActiveInAlternativeCliques,
-                    is_discarded: graph_status == BlockGraphStatus::Discarded,
-                    block: content,
-                }),
+            .map(|((id, content), graph_status)| {
+                let slot = content.header.content.slot;
+                let expected_endorsers = if slot.period >= min_attributed_period {
+                    // fails if the draws of the slot are not kept by the selector anymore
+                    self.0
+                        .selector_controller
+                        .get_selection(slot)
+                        .ok()
+                        .map(|selection| selection.endorsements)
+                } else {
+                    None
+                };
+                let endorsement_coverage = EndorsementCoverage::from_header(
+                    &content.header.content,
+                    ENDORSEMENT_COUNT,
+                    expected_endorsers.as_deref(),
+                );
+                BlockInfo {
+                    id,
+                    content: Some(BlockInfoContent {
+                        is_final: graph_status == BlockGraphStatus::Final,
+                        is_in_blockclique: graph_status == BlockGraphStatus::ActiveInBlockclique,
+                        is_candidate: graph_status == BlockGraphStatus::ActiveInBlockclique
+                            || graph_status == BlockGraphStatus::ActiveInAlternativeCliques,
+                        is_discarded: graph_status == BlockGraphStatus::Discarded,
+                        block: content,
+                        endorsement_coverage,
+                    }),
+                }
             })
             .collect();
         Ok(res)
     }
 
+    /// get a page of the blocks created by an address
+    async fn get_blocks_by_creator(
+        &self,
+        address: Address,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<PagedVec<BlockId>> {
+        let block_ids = self
+            .0
+            .storage
+            .with_blocks(|blocks| blocks.get_blocks_created_by(&address, None, usize::MAX));
+        Ok(PagedVec::new(block_ids, page_request))
+    }
+
+    async fn get_block_confirmation(&self, ids: Vec<BlockId>) -> RpcResult<Vec<BlockConfirmation>> {
+        if ids.len() as u64 > self.0.api_settings.max_arguments {
+            return Err(ApiError::BadRequest("too many arguments".into()).into());
+        }
+        let confirmations = self.0.consensus_controller.get_confirmation_info(&ids);
+        Ok(ids
+            .into_iter()
+            .zip(confirmations)
+            .map(|(id, confirmation)| BlockConfirmation { id, confirmation })
+            .collect())
+    }
+
     async fn get_blockclique_block_by_slot(&self, slot: Slot) -> RpcResult<Option<Block>> {
         let block_id_option = self
             .0
@@ -857,9 +1240,7 @@ impl MassaRpcServer for API<Public> {
         let res = self
             .0
             .storage
-            .read_blocks()
-            .get(&block_id)
-            .map(|b| b.content.clone());
+            .with_blocks(|blocks| blocks.get(&block_id).map(|b| b.content.clone()));
         Ok(res)
     }
 
@@ -905,6 +1286,10 @@ impl MassaRpcServer for API<Public> {
                 is_in_blockclique: blockclique.block_ids.contains(&id),
                 slot: exported_block.header.content.slot,
                 creator: exported_block.header.content_creator_address,
+                endorsement_fill_ratio: Some(endorsement_fill_ratio(
+                    &exported_block.header.content,
+                    ENDORSEMENT_COUNT,
+                )),
                 parents: exported_block.header.content.parents,
             });
         }
@@ -918,6 +1303,7 @@ impl MassaRpcServer for API<Public> {
                     slot,
                     creator,
                     parents,
+                    endorsement_fill_ratio: None,
                 });
             }
         }
@@ -949,43 +1335,42 @@ impl MassaRpcServer for API<Public> {
     /// get addresses
     async fn get_addresses(&self, addresses: Vec<Address>) -> RpcResult<Vec<AddressInfo>> {
         // get info from storage about which blocks the addresses have created
-        let created_blocks: Vec<PreHashSet<BlockId>> = {
-            let lck = self.0.storage.read_blocks();
+        let created_blocks: Vec<PreHashSet<BlockId>> = self.0.storage.with_blocks(|lck| {
             addresses
                 .iter()
                 .map(|address| {
-                    lck.get_blocks_created_by(address)
-                        .cloned()
-                        .unwrap_or_default()
+                    lck.get_blocks_created_by(address, None, usize::MAX)
+                        .into_iter()
+                        .collect()
                 })
                 .collect()
-        };
+        });
 
         // get info from storage about which operations the addresses have created
-        let created_operations: Vec<PreHashSet<OperationId>> = {
-            let lck = self.0.storage.read_operations();
-            addresses
-                .iter()
-                .map(|address| {
-                    lck.get_operations_created_by(address)
-                        .cloned()
-                        .unwrap_or_default()
-                })
-                .collect()
-        };
+        let created_operations: Vec<PreHashSet<OperationId>> =
+            self.0.storage.with_operations(|lck| {
+                addresses
+                    .iter()
+                    .map(|address| {
+                        lck.get_operations_created_by(address)
+                            .cloned()
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            });
 
         // get info from storage about which endorsements the addresses have created
-        let created_endorsements: Vec<PreHashSet<EndorsementId>> = {
-            let lck = self.0.storage.read_endorsements();
-            addresses
-                .iter()
-                .map(|address| {
-                    lck.get_endorsements_created_by(address)
-                        .cloned()
-                        .unwrap_or_default()
-                })
-                .collect()
-        };
+        let created_endorsements: Vec<PreHashSet<EndorsementId>> =
+            self.0.storage.with_endorsements(|lck| {
+                addresses
+                    .iter()
+                    .map(|address| {
+                        lck.get_endorsements_created_by(address)
+                            .cloned()
+                            .unwrap_or_default()
+                    })
+                    .collect()
+            });
 
         // Compute a limit (as a slot) for deferred credits as it can be quite huge
         let bound_ts = MassaTime::now().saturating_add(self.0.api_settings.deferred_credits_delta);
@@ -1115,7 +1500,10 @@ impl MassaRpcServer for API<Public> {
     }
 
     /// get addresses bytecode
-    async fn get_addresses_bytecode(&self, args: Vec<AddressFilter>) -> RpcResult<Vec<Vec<u8>>> {
+    async fn get_addresses_bytecode(
+        &self,
+        args: Vec<AddressFilter>,
+    ) -> RpcResult<Vec<Option<Vec<u8>>>> {
         let queries = args
             .into_iter()
             .map(|arg| {
@@ -1141,16 +1529,20 @@ impl MassaRpcServer for API<Public> {
             .query_state(ExecutionQueryRequest { requests: queries })
             .responses;
 
-        let res: Result<Vec<Vec<u8>>, ApiError> = responses
+        // addresses that don't exist, and user addresses whose bytecode is empty, have no bytecode
+        let res: Result<Vec<Option<Vec<u8>>>, ApiError> = responses
             .into_iter()
             .map(|value| match value {
                 Ok(item) => match item {
-                    ExecutionQueryResponseItem::Bytecode(bytecode) => Ok(bytecode.0),
+                    ExecutionQueryResponseItem::Bytecode(bytecode) if bytecode.0.is_empty() => {
+                        Ok(None)
+                    }
+                    ExecutionQueryResponseItem::Bytecode(bytecode) => Ok(Some(bytecode.0)),
                     _ => Err(ApiError::InternalServerError(
                         "unexpected response type".to_string(),
                     )),
                 },
-                Err(err) => Err(ApiError::InternalServerError(err.to_string())),
+                Err(ExecutionQueryError::NotFound(_)) => Ok(None),
             })
             .collect();
 
@@ -1223,10 +1615,25 @@ impl MassaRpcServer for API<Public> {
     /// * emitter address
     /// * original caller address
     /// * operation id
+    /// * is final
+    /// * is error
+    /// * substrings of the data
     async fn get_filtered_sc_output_event(
         &self,
         filter: EventFilter,
     ) -> RpcResult<Vec<SCOutputEvent>> {
+        let api_cfg = &self.0.api_settings;
+        if filter.data_contains.len() as u64 > api_cfg.max_event_data_patterns {
+            return Err(ApiError::BadRequest("too many event data patterns".into()).into());
+        }
+        if filter
+            .data_contains
+            .iter()
+            .any(|pattern| pattern.len() as u64 > api_cfg.max_event_data_pattern_length)
+        {
+            return Err(ApiError::BadRequest("event data pattern too long".into()).into());
+        }
+
         let events = self
             .0
             .execution_controller
@@ -1341,6 +1748,16 @@ fn check_input_operation(
     let (rest, op): (&[u8], SecureShareOperation) = operation_deserializer
         .deserialize::<DeserializeError>(&op_serialized)
         .map_err(|err| ApiError::ModelsError(ModelsError::DeserializeError(err.to_string())))?;
+    if let Some(datastore) = op.get_datastore() {
+        datastore
+            .validate(&DatastoreLimits {
+                max_entry_count: api_cfg.max_op_datastore_entry_count,
+                max_key_length: api_cfg.max_op_datastore_key_length,
+                max_value_length: api_cfg.max_op_datastore_value_length,
+                max_total_size: api_cfg.max_block_size as u64,
+            })
+            .map_err(|err| ApiError::BadRequest(err.to_string()))?;
+    }
     match op.content.op {
         OperationType::CallSC { .. } => {
             let gas_usage =
@@ -1380,3 +1797,29 @@ fn check_input_operation(
         .into())
     }
 }
+
+/// Get the retained pool snapshot of sequence number `sequence`,
+/// or take a new snapshot with `take` and retain it if no sequence number is given
+fn get_or_take_snapshot<T>(
+    sequence: Option<u64>,
+    take: impl FnOnce() -> PoolSnapshot<T>,
+    retained: impl FnOnce(&mut PoolSnapshots) -> &mut RetainedSnapshots<T>,
+    pool_snapshots: &parking_lot::Mutex<PoolSnapshots>,
+) -> Result<PoolSnapshot<T>, ApiError> {
+    match sequence {
+        Some(sequence) => retained(&mut pool_snapshots.lock())
+            .get(sequence)
+            .ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "pool snapshot {} is not retained anymore, request a new one",
+                    sequence
+                ))
+            }),
+        None => {
+            // the pool is not queried while holding the lock on the retained snapshots
+            let snapshot = take();
+            retained(&mut pool_snapshots.lock()).retain(snapshot.clone());
+            Ok(snapshot)
+        }
+    }
+}
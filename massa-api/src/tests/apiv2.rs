@@ -7,21 +7,34 @@ use jsonrpsee::{
     rpc_params,
     ws_client::WsClientBuilder,
 };
+use massa_api_exports::execution::SlotExecutionDigest;
+use massa_api_exports::operation::{OperationKind, OperationStreamFilter};
+use massa_api_exports::pool::PoolEventFilter;
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_consensus_exports::MockConsensusController;
-use massa_execution_exports::MockExecutionController;
+use massa_execution_exports::{
+    EventStore, ExecutionOutput, FinalStateDigest, MockExecutionController, SlotExecutionOutput,
+};
+use massa_final_state::StateChanges;
+use massa_hash::Hash;
 use massa_models::{
     address::Address,
+    amount::Amount,
     block::{FilledBlock, SecureShareBlock},
     block_header::BlockHeader,
     block_id::BlockId,
-    config::VERSION,
-    operation::SecureShareOperation,
-    secure_share::SecureShare,
+    config::{CHAINID, VERSION},
+    endorsement::SecureShareEndorsement,
+    operation::{Operation, OperationSerializer, OperationType, SecureShareOperation},
+    secure_share::{SecureShare, SecureShareContent},
+    slot::Slot,
 };
+use massa_pool_exports::{ExpiredOperation, PoolEvent, PoolEventKind, PoolObjectId};
 use massa_protocol_exports::test_exports::tools::{
-    create_block, create_operation_with_expire_period,
+    create_block, create_endorsement, create_operation_with_expire_period,
 };
 use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use serde_json::Value;
 
 use crate::{tests::mock::get_apiv2_server, ApiServer};
@@ -147,7 +160,8 @@ async fn subscribe_new_blocks() {
     let addr: SocketAddr = "[::]:5033".parse().unwrap();
     let (mut api_server, api_config) = get_apiv2_server(&addr);
 
-    let (tx, _rx) = tokio::sync::broadcast::channel::<SecureShareBlock>(10);
+    let tx = DedupBroadcaster::<SecureShareBlock>::new("test".to_string(), 10, 10);
+    let _rx = tx.subscribe();
 
     api_server.0.consensus_broadcasts.block_sender = tx.clone();
 
@@ -243,7 +257,8 @@ async fn subscribe_new_filled_blocks() {
         addr.to_string().split(':').last().unwrap()
     ))
     .unwrap();
-    let (tx, _rx) = tokio::sync::broadcast::channel::<FilledBlock>(10);
+    let tx = DedupBroadcaster::<FilledBlock>::new("test".to_string(), 10, 10);
+    let _rx = tx.subscribe();
 
     api_server.0.consensus_broadcasts.filled_block_sender = tx.clone();
 
@@ -266,6 +281,7 @@ async fn subscribe_new_filled_blocks() {
     let filled = FilledBlock {
         header: block.content.header,
         operations: vec![],
+        locally_produced: false,
     };
 
     tokio::spawn(async move {
@@ -297,7 +313,8 @@ async fn subscribe_new_operations() {
         addr.to_string().split(':').last().unwrap()
     ))
     .unwrap();
-    let (tx, _rx) = tokio::sync::broadcast::channel::<SecureShareOperation>(10);
+    let tx = DedupBroadcaster::<SecureShareOperation>::new("test".to_string(), 10, 10);
+    let _rx = tx.subscribe();
 
     let operation = create_operation_with_expire_period(&KeyPair::generate(0).unwrap(), 500000);
 
@@ -333,3 +350,437 @@ async fn subscribe_new_operations() {
 
     api_handle.stop().await;
 }
+
+#[tokio::test]
+async fn subscribe_new_operations_filtered() {
+    let addr: SocketAddr = "[::]:5050".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let tx = DedupBroadcaster::<SecureShareOperation>::new("test".to_string(), 10, 10);
+    let _rx = tx.subscribe();
+
+    let keypair_a = KeyPair::generate(0).unwrap();
+    let keypair_b = KeyPair::generate(0).unwrap();
+    let keypair_c = KeyPair::generate(0).unwrap();
+    let target = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let new_op = |keypair: &KeyPair, op: OperationType| {
+        Operation::new_verifiable(
+            Operation {
+                fee: Amount::default(),
+                op,
+                expire_period: 500000,
+            },
+            OperationSerializer::new(),
+            keypair,
+            *CHAINID,
+        )
+        .unwrap()
+    };
+    let transaction = create_operation_with_expire_period(&keypair_a, 500000);
+    let roll_buy = new_op(&keypair_b, OperationType::RollBuy { roll_count: 1 });
+    let call_sc = new_op(
+        &keypair_c,
+        OperationType::CallSC {
+            target_addr: target,
+            target_func: "test".to_string(),
+            param: vec![],
+            max_gas: 1_000_000,
+            coins: Amount::default(),
+        },
+    );
+
+    api_server.0.pool_broadcasts.operation_sender = tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let client1 = WsClientBuilder::default().build(&uri).await.unwrap();
+
+    // an empty filter is rejected
+    let empty_sub: Result<Subscription<Value>, _> = client1
+        .subscribe(
+            "subscribe_new_operations_filtered",
+            rpc_params![OperationStreamFilter::default()],
+            "unsubscribe_new_operations_filtered",
+        )
+        .await;
+    assert!(empty_sub.is_err());
+
+    let mut sub1: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_new_operations_filtered",
+            rpc_params![OperationStreamFilter {
+                creator_addresses: vec![roll_buy.content_creator_address],
+                ..Default::default()
+            }],
+            "unsubscribe_new_operations_filtered",
+        )
+        .await
+        .unwrap();
+    let mut sub2: Subscription<Value> = client1
+        .subscribe(
+            "subscribe_new_operations_filtered",
+            rpc_params![OperationStreamFilter {
+                recipient_addresses: vec![target],
+                operation_kinds: vec![OperationKind::RollSell],
+                ..Default::default()
+            }],
+            "unsubscribe_new_operations_filtered",
+        )
+        .await
+        .unwrap();
+
+    let to_send = vec![transaction, roll_buy.clone(), call_sc.clone()];
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        for op in to_send {
+            let _ = tx.send(op).unwrap();
+        }
+    });
+
+    let obj = tokio::time::timeout(Duration::from_secs(4), sub1.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(obj["id"].as_str().unwrap(), &roll_buy.id.to_string());
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), sub1.next())
+            .await
+            .is_err()
+    );
+
+    let obj = tokio::time::timeout(Duration::from_secs(4), sub2.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(obj["id"].as_str().unwrap(), &call_sc.id.to_string());
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), sub2.next())
+            .await
+            .is_err()
+    );
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn subscribe_operation_expiries() {
+    let addr: SocketAddr = "[::]:5057".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let (tx, _rx) = tokio::sync::broadcast::channel::<ExpiredOperation>(10);
+
+    let watched_keypair = KeyPair::generate(0).unwrap();
+    let other_keypair = KeyPair::generate(0).unwrap();
+    let watched_op = create_operation_with_expire_period(&watched_keypair, 10);
+    let other_op = create_operation_with_expire_period(&other_keypair, 10);
+
+    api_server.0.pool_broadcasts.expired_operation_sender = tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let client = WsClientBuilder::default().build(&uri).await.unwrap();
+
+    // at least one address must be given
+    let empty_sub: Result<Subscription<Value>, _> = client
+        .subscribe(
+            "subscribe_operation_expiries",
+            rpc_params![Vec::<Address>::new()],
+            "unsubscribe_operation_expiries",
+        )
+        .await;
+    assert!(empty_sub.is_err());
+
+    let mut sub: Subscription<Value> = client
+        .subscribe(
+            "subscribe_operation_expiries",
+            rpc_params![vec![watched_op.content_creator_address]],
+            "unsubscribe_operation_expiries",
+        )
+        .await
+        .unwrap();
+
+    let to_send = vec![other_op, watched_op.clone()];
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        for operation in to_send {
+            tx.send(ExpiredOperation {
+                operation,
+                expired_at: MassaTime::now(),
+            })
+            .unwrap();
+        }
+    });
+
+    let obj = tokio::time::timeout(Duration::from_secs(4), sub.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        obj["operation"]["id"].as_str().unwrap(),
+        &watched_op.id.to_string()
+    );
+    assert!(tokio::time::timeout(Duration::from_millis(500), sub.next())
+        .await
+        .is_err());
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn subscribe_pool_events() {
+    let addr: SocketAddr = "[::]:5065".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let sender = DedupBroadcaster::new("test_pool_events_api".to_string(), 10, 0);
+    api_server.0.pool_broadcasts.pool_event_sender = sender.clone();
+
+    let watched_keypair = KeyPair::generate(0).unwrap();
+    let other_keypair = KeyPair::generate(0).unwrap();
+    let watched_op = create_operation_with_expire_period(&watched_keypair, 10);
+    let other_op = create_operation_with_expire_period(&other_keypair, 10);
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let client = WsClientBuilder::default().build(&uri).await.unwrap();
+
+    let mut sub: Subscription<Value> = client
+        .subscribe(
+            "subscribe_pool_events",
+            rpc_params![PoolEventFilter {
+                kinds: vec![PoolEventKind::Expired],
+                creator_addresses: vec![watched_op.content_creator_address],
+            }],
+            "unsubscribe_pool_events",
+        )
+        .await
+        .unwrap();
+
+    let to_send = vec![
+        (PoolEventKind::Admitted, &watched_op),
+        (PoolEventKind::Expired, &other_op),
+        (PoolEventKind::Expired, &watched_op),
+    ]
+    .into_iter()
+    .map(|(kind, op)| PoolEvent {
+        kind,
+        object: PoolObjectId::Operation(op.id),
+        creator: op.content_creator_address,
+        slot_context: None,
+        reason: None,
+    })
+    .collect::<Vec<_>>();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        for event in to_send {
+            sender.send(event).unwrap();
+        }
+    });
+
+    // only the expiry of the watched operation matches the filter
+    let obj = tokio::time::timeout(Duration::from_secs(4), sub.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(obj["kind"].as_str().unwrap(), "expired");
+    assert_eq!(
+        obj["object"]["operation"].as_str().unwrap(),
+        &watched_op.id.to_string()
+    );
+    assert!(tokio::time::timeout(Duration::from_millis(500), sub.next())
+        .await
+        .is_err());
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn subscribe_new_slot_execution_digests() {
+    let addr: SocketAddr = "[::]:5068".parse().unwrap();
+    let (api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let sender = api_server
+        .0
+        .execution_channels
+        .slot_execution_output_sender
+        .clone();
+
+    let slot = Slot::new(1, 0);
+    let fingerprint = Hash::compute_from(b"final state");
+    let ledger_fingerprint = Hash::compute_from(b"ledger");
+    let output = ExecutionOutput {
+        slot,
+        block_info: None,
+        state_changes: StateChanges::default(),
+        events: EventStore::default(),
+        #[cfg(feature = "execution-trace")]
+        slot_trace: None,
+        #[cfg(feature = "dump-block")]
+        storage: None,
+        deferred_credits_execution: vec![],
+        cancel_async_message_execution: vec![],
+        auto_sell_execution: vec![],
+        digest: massa_execution_exports::SlotExecutionDigest {
+            ledger_changes_count: 2,
+            async_messages_emitted: 1,
+            async_messages_taken: 0,
+            final_state: None,
+        },
+    };
+    let mut final_output = output.clone();
+    final_output.digest.final_state = Some(FinalStateDigest {
+        change_id: slot,
+        fingerprint,
+        component_fingerprints: BTreeMap::from([("ledger".to_string(), ledger_fingerprint)]),
+    });
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let client = WsClientBuilder::default().build(&uri).await.unwrap();
+    let mut sub: Subscription<SlotExecutionDigest> = client
+        .subscribe(
+            "subscribe_new_slot_execution_digests",
+            rpc_params![],
+            "unsubscribe_new_slot_execution_digests",
+        )
+        .await
+        .unwrap();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        sender
+            .send(SlotExecutionOutput::ExecutedSlot(output))
+            .unwrap();
+        sender
+            .send(SlotExecutionOutput::FinalizedSlot(final_output))
+            .unwrap();
+    });
+
+    let digest = tokio::time::timeout(Duration::from_secs(4), sub.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert_eq!(digest.slot, slot);
+    assert!(!digest.is_final);
+    assert_eq!(digest.ledger_changes_count, 2);
+    assert_eq!(digest.async_messages_emitted, 1);
+    assert!(digest.final_state.is_none());
+
+    let digest = tokio::time::timeout(Duration::from_secs(4), sub.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(digest.is_final);
+    let final_state = digest.final_state.expect("missing final state digest");
+    assert_eq!(final_state.change_id, slot);
+    assert_eq!(final_state.fingerprint, fingerprint);
+    assert_eq!(
+        final_state.component_fingerprints,
+        BTreeMap::from([("ledger".to_string(), ledger_fingerprint)])
+    );
+
+    api_handle.stop().await;
+}
+
+#[tokio::test]
+async fn subscribe_all() {
+    let addr: SocketAddr = "[::]:5051".parse().unwrap();
+    let (mut api_server, api_config) = get_apiv2_server(&addr);
+
+    let uri = Url::parse(&format!(
+        "ws://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .unwrap();
+    let block_tx = DedupBroadcaster::<SecureShareBlock>::new("test".to_string(), 10, 10);
+    let _block_rx = block_tx.subscribe();
+    let operation_tx = DedupBroadcaster::<SecureShareOperation>::new("test".to_string(), 10, 10);
+    let _operation_rx = operation_tx.subscribe();
+    let endorsement_tx =
+        DedupBroadcaster::<SecureShareEndorsement>::new("test".to_string(), 10, 10);
+    let _endorsement_rx = endorsement_tx.subscribe();
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let block = create_block(&keypair);
+    let operation = create_operation_with_expire_period(&keypair, 500000);
+    let endorsement = create_endorsement();
+
+    api_server.0.consensus_broadcasts.block_sender = block_tx.clone();
+    api_server.0.pool_broadcasts.operation_sender = operation_tx.clone();
+    api_server.0.pool_broadcasts.endorsement_sender = endorsement_tx.clone();
+
+    let api_handle = api_server
+        .serve(&addr, &api_config)
+        .await
+        .expect("failed to start MASSA API V2");
+
+    let client = WsClientBuilder::default().build(&uri).await.unwrap();
+    let mut sub: Subscription<Value> = client
+        .subscribe("subscribe_all", rpc_params![], "unsubscribe_all")
+        .await
+        .unwrap();
+
+    let block_id = block.id;
+    let operation_id = operation.id;
+    let endorsement_id = endorsement.id;
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = block_tx.send(block).unwrap();
+        let _ = operation_tx.send(operation).unwrap();
+        let _ = endorsement_tx.send(endorsement).unwrap();
+    });
+
+    // events of different kinds are only interleaved on a best-effort basis
+    let mut received = BTreeMap::new();
+    for _ in 0..3 {
+        let obj = tokio::time::timeout(Duration::from_secs(4), sub.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let (kind, event) = obj.as_object().unwrap().iter().next().unwrap();
+        received.insert(kind.clone(), event["id"].as_str().unwrap().to_string());
+    }
+    assert_eq!(received["Block"], block_id.to_string());
+    assert_eq!(received["Operation"], operation_id.to_string());
+    assert_eq!(received["Endorsement"], endorsement_id.to_string());
+
+    api_handle.stop().await;
+}
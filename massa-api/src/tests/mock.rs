@@ -2,22 +2,30 @@
 //!
 //!
 
-use std::{collections::HashMap, net::SocketAddr};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Condvar, Mutex},
+};
 
 use massa_api_exports::config::APIConfig;
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_consensus_exports::{ConsensusBroadcasts, MockConsensusController};
-use massa_execution_exports::{GasCosts, MockExecutionController};
+use massa_execution_exports::{ExecutionChannels, GasCosts, MockExecutionController};
+use massa_factory_exports::ProductionHistory;
+use massa_final_state::MockFinalStateController;
 use massa_models::amount::Amount;
 use massa_models::config::CHAINID;
 use massa_models::{
     config::{
-        BASE_OPERATION_GAS_COST, ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MAX_DATASTORE_VALUE_LENGTH,
-        MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_MESSAGE_SIZE,
+        BASE_OPERATION_GAS_COST, ENDORSEMENT_COUNT, GENESIS_TIMESTAMP, MAX_BLOCK_SIZE,
+        MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK, MAX_MESSAGE_SIZE,
         MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
         MAX_OPERATION_DATASTORE_VALUE_LENGTH, MAX_PARAMETERS_SIZE,
         MIP_STORE_STATS_BLOCK_CONSIDERED, PERIODS_PER_CYCLE, T0, THREAD_COUNT, VERSION,
     },
     node::NodeId,
+    prehash::PreHashSet,
 };
 use massa_pool_exports::{MockPoolController, PoolBroadcasts};
 use massa_pos_exports::MockSelectorController;
@@ -25,11 +33,13 @@ use massa_protocol_exports::{MockProtocolController, PeerCategoryInfo, ProtocolC
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use massa_wallet::test_exports::create_test_wallet;
 use num::rational::Ratio;
+use parking_lot::RwLock;
 use tempfile::NamedTempFile;
 use tokio::sync::broadcast;
 
-use crate::{ApiV2, Public, API};
+use crate::{ApiV2, Private, Public, API};
 
 pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
     let keypair = KeyPair::generate(0).unwrap();
@@ -38,6 +48,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         bind_public: "[::]:0".parse().unwrap(),
         bind_api: *addr,
         draw_lookahead_period_count: 10,
+        endorsement_attribution_lookback_period_count: 64,
         max_arguments: 128,
         openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
         bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
@@ -57,6 +68,7 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
         max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
+        max_block_size: MAX_BLOCK_SIZE,
         base_operation_gas_cost: BASE_OPERATION_GAS_COST,
         sp_compilation_cost: GasCosts::default().sp_compilation_cost,
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
@@ -69,7 +81,12 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
         last_start_period: 0,
         chain_id: *CHAINID,
         deferred_credits_delta: MassaTime::from_millis(24 * 3600 * 2),
+        max_deferred_credits_slot_range: 320,
         minimal_fees: Amount::zero(),
+        pool_snapshot_retention: MassaTime::from_millis(30000),
+        max_denunciation_evidences_per_second: 20,
+        max_event_data_patterns: 2,
+        max_event_data_pattern_length: 64,
     };
 
     // let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
@@ -85,20 +102,29 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
     let exec_ctrl = MockExecutionController::new();
 
     let pool_broadcasts = PoolBroadcasts {
-        endorsement_sender: broadcast::channel(100).0,
-        operation_sender: broadcast::channel(100).0,
+        endorsement_sender: DedupBroadcaster::new("test_endorsements".to_string(), 100, 100),
+        operation_sender: DedupBroadcaster::new("test_operations".to_string(), 100, 100),
+        expired_operation_sender: broadcast::channel(100).0,
+        pool_event_sender: DedupBroadcaster::new("test_pool_events".to_string(), 100, 0),
     };
 
     let consensus_broadcasts = ConsensusBroadcasts {
         block_header_sender: broadcast::channel(100).0,
-        block_sender: broadcast::channel(100).0,
-        filled_block_sender: broadcast::channel(100).0,
+        block_sender: DedupBroadcaster::new("test_blocks".to_string(), 100, 100),
+        filled_block_sender: DedupBroadcaster::new("test_filled_blocks".to_string(), 100, 100),
+    };
+
+    let execution_channels = ExecutionChannels {
+        slot_execution_output_sender: broadcast::channel(100).0,
+        #[cfg(feature = "execution-trace")]
+        slot_execution_traces_sender: broadcast::channel(100).0,
     };
 
     let api = API::<ApiV2>::new(
         Box::new(consensus_ctrl),
         consensus_broadcasts,
         Box::new(exec_ctrl),
+        execution_channels,
         pool_broadcasts,
         api_config.clone(),
         *VERSION,
@@ -107,6 +133,71 @@ pub(crate) fn get_apiv2_server(addr: &SocketAddr) -> (API<ApiV2>, APIConfig) {
     (api, api_config)
 }
 
+pub(crate) fn start_private_api() -> (API<Private>, APIConfig) {
+    let keypair = KeyPair::generate(0).unwrap();
+    let api_config: APIConfig = APIConfig {
+        bind_private: "[::]:0".parse().unwrap(),
+        bind_public: "[::]:0".parse().unwrap(),
+        bind_api: "[::]:0".parse().unwrap(),
+        draw_lookahead_period_count: 10,
+        endorsement_attribution_lookback_period_count: 64,
+        max_arguments: 128,
+        openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
+        bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
+        bootstrap_blacklist_path: "base_config/bootstrap_blacklist.json".parse().unwrap(),
+        max_request_body_size: 52428800,
+        max_response_body_size: 52428800,
+        max_connections: 100,
+        max_subscriptions_per_connection: 1024,
+        max_log_length: 4096,
+        allow_hosts: vec![],
+        batch_request_limit: 16,
+        ping_interval: MassaTime::from_millis(60000),
+        enable_http: true,
+        enable_ws: true,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        max_gas_per_block: MAX_GAS_PER_BLOCK,
+        max_block_size: MAX_BLOCK_SIZE,
+        base_operation_gas_cost: BASE_OPERATION_GAS_COST,
+        sp_compilation_cost: GasCosts::default().sp_compilation_cost,
+        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+        max_parameter_size: MAX_PARAMETERS_SIZE,
+        thread_count: THREAD_COUNT,
+        keypair: keypair.clone(),
+        genesis_timestamp: *GENESIS_TIMESTAMP,
+        t0: T0,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        last_start_period: 0,
+        chain_id: *CHAINID,
+        deferred_credits_delta: MassaTime::from_millis(24 * 3600 * 2),
+        max_deferred_credits_slot_range: 320,
+        minimal_fees: Amount::zero(),
+        pool_snapshot_retention: MassaTime::from_millis(30000),
+        max_denunciation_evidences_per_second: 20,
+        max_event_data_patterns: 2,
+        max_event_data_pattern_length: 64,
+    };
+
+    let api_private = API::<Private>::new(
+        Box::new(MockProtocolController::new()),
+        Box::new(MockConsensusController::new()),
+        Box::new(MockExecutionController::new()),
+        Box::new(MockPoolController::new()),
+        api_config.clone(),
+        Arc::new((Mutex::new(false), Condvar::new())),
+        Arc::new(RwLock::new(create_test_wallet(None))),
+        Arc::new(RwLock::new(PreHashSet::default())),
+        Arc::new(RwLock::new(MockFinalStateController::new())),
+        massa_storage::Storage::create_root(),
+        Arc::new(RwLock::new(ProductionHistory::new(10))),
+    );
+
+    (api_private, api_config)
+}
+
 pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
     let keypair = KeyPair::generate(0).unwrap();
     let api_config: APIConfig = APIConfig {
@@ -114,6 +205,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         bind_public: addr,
         bind_api: "[::]:0".parse().unwrap(),
         draw_lookahead_period_count: 10,
+        endorsement_attribution_lookback_period_count: 64,
         max_arguments: 128,
         openrpc_spec_path: "base_config/openrpc.json".parse().unwrap(),
         bootstrap_whitelist_path: "base_config/bootstrap_whitelist.json".parse().unwrap(),
@@ -133,6 +225,7 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
         max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
+        max_block_size: MAX_BLOCK_SIZE,
         base_operation_gas_cost: BASE_OPERATION_GAS_COST,
         sp_compilation_cost: GasCosts::default().sp_compilation_cost,
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
@@ -145,7 +238,12 @@ pub(crate) fn start_public_api(addr: SocketAddr) -> (API<Public>, APIConfig) {
         last_start_period: 0,
         chain_id: *CHAINID,
         deferred_credits_delta: MassaTime::from_millis(24 * 3600 * 2),
+        max_deferred_credits_slot_range: 320,
         minimal_fees: Amount::zero(),
+        pool_snapshot_retention: MassaTime::from_millis(30000),
+        max_denunciation_evidences_per_second: 20,
+        max_event_data_patterns: 2,
+        max_event_data_pattern_length: 64,
     };
 
     let shared_storage: massa_storage::Storage = massa_storage::Storage::create_root();
@@ -3,5 +3,6 @@
 //!
 mod apiv2;
 mod mock;
+mod private;
 mod public;
 mod server;
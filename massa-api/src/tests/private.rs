@@ -0,0 +1,270 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+//!
+//!
+
+use massa_api_exports::debug_dump::{DebugDumpRequest, SlotProductionOutcomeDump};
+use massa_consensus_exports::{
+    block_graph_export::{BlockGraphSummary, DiscardedBlockSummary, WaitingBlockSummary},
+    block_status::DiscardReason,
+    MockConsensusController,
+};
+use massa_execution_exports::MockExecutionController;
+use massa_factory_exports::{SlotProductionOutcome, SlotProductionReport};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    block_id::BlockId,
+    config::THREAD_COUNT,
+    operation::OperationId,
+    secure_share::Id,
+    slot::Slot,
+    stats::{ConsensusStats, ExecutionBacklog, ExecutionStats},
+};
+use massa_pool_exports::{MockPoolController, PooledOperationSummary};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+
+use crate::tests::mock::start_private_api;
+
+fn block_id(name: &str) -> BlockId {
+    BlockId::generate_from_hash(massa_hash::Hash::compute_from(name.as_bytes()))
+}
+
+fn creator() -> Address {
+    Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key())
+}
+
+fn mock_pool_controller() -> MockPoolController {
+    let mut pool_ctrl = MockPoolController::new();
+    pool_ctrl.expect_get_operation_count().returning(|| 3);
+    pool_ctrl.expect_get_endorsement_count().returning(|| 2);
+    pool_ctrl.expect_get_denunciation_count().returning(|| 1);
+    pool_ctrl
+        .expect_get_final_cs_periods()
+        .returning(|| vec![4; THREAD_COUNT as usize]);
+    pool_ctrl
+        .expect_get_operations_sample()
+        .returning(|max_items| {
+            // the sample is bounded by the pool itself
+            assert_eq!(max_items, 100);
+            vec![PooledOperationSummary {
+                id: OperationId::new(massa_hash::Hash::compute_from(b"op")),
+                creator_address: creator(),
+                thread: 0,
+                fee: Amount::from_raw(100),
+                size: 10,
+                max_gas_usage: 0,
+                max_spending: Amount::from_raw(100),
+                validity_start_period: 0,
+                validity_end_period: 10,
+            }]
+        });
+    pool_ctrl
+}
+
+fn mock_consensus_controller() -> MockConsensusController {
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_block_graph_summary()
+        .returning(|_| BlockGraphSummary {
+            incoming_block_count: 0,
+            waiting_for_slot_block_count: 1,
+            waiting_for_dependencies_block_count: 1,
+            active_block_count: 5,
+            discarded_block_count: 2,
+            best_parents: vec![(block_id("best"), 4)],
+            latest_final_blocks_periods: vec![(block_id("final"), 3)],
+            clique_count: 1,
+            waiting_for_dependencies: vec![WaitingBlockSummary {
+                block_id: block_id("waiting"),
+                slot: Slot::new(5, 0),
+                missing_dependencies: vec![block_id("missing")],
+            }],
+            discarded_blocks: vec![
+                DiscardedBlockSummary {
+                    block_id: block_id("invalid"),
+                    slot: Slot::new(4, 0),
+                    creator: creator(),
+                    parents: vec![],
+                    reason: DiscardReason::Invalid("bad signature".to_string()),
+                },
+                DiscardedBlockSummary {
+                    block_id: block_id("stale"),
+                    slot: Slot::new(3, 1),
+                    creator: creator(),
+                    parents: vec![block_id("parent")],
+                    reason: DiscardReason::Stale,
+                },
+            ],
+        });
+    consensus_ctrl.expect_get_stats().returning(|| {
+        Ok(ConsensusStats {
+            start_timespan: MassaTime::from_millis(0),
+            end_timespan: MassaTime::from_millis(1000),
+            final_block_count: 10,
+            stale_block_count: 1,
+            clique_count: 1,
+        })
+    });
+    consensus_ctrl
+}
+
+fn mock_execution_controller() -> MockExecutionController {
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl.expect_get_stats().returning(|| ExecutionStats {
+        time_window_start: MassaTime::from_millis(0),
+        time_window_end: MassaTime::from_millis(1000),
+        final_block_count: 10,
+        final_executed_operations_count: 42,
+        active_cursor: Slot::new(5, 1),
+        final_cursor: Slot::new(3, 1),
+    });
+    exec_ctrl
+        .expect_get_backlog()
+        .returning(|| ExecutionBacklog {
+            pending_finalized_block_count: 1,
+            pending_blockclique_update: true,
+            pending_block_metadata_count: 2,
+            pending_readonly_request_count: 0,
+            active_history_length: 4,
+        });
+    exec_ctrl
+}
+
+#[test]
+fn debug_dump_all_sections() {
+    let (mut api_private, _config) = start_private_api();
+    api_private.0.pool_controller = Box::new(mock_pool_controller());
+    api_private.0.consensus_controller = Box::new(mock_consensus_controller());
+    api_private.0.execution_controller = Box::new(mock_execution_controller());
+    {
+        let mut production_history = api_private.0.production_history.write();
+        production_history.push(SlotProductionReport {
+            slot: Slot::new(4, 0),
+            producer: creator(),
+            outcome: SlotProductionOutcome::Skipped {
+                reason: "no connections".to_string(),
+            },
+        });
+        production_history.push(SlotProductionReport {
+            slot: Slot::new(5, 0),
+            producer: creator(),
+            outcome: SlotProductionOutcome::Produced {
+                block_id: block_id("produced"),
+                operation_count: 2,
+                endorsement_count: 16,
+                denunciation_count: 0,
+            },
+        });
+    }
+
+    let dump = api_private.0.debug_dump(DebugDumpRequest::default());
+
+    let pool = dump.pool.expect("missing pool section");
+    assert_eq!(pool.operation_count, 3);
+    assert_eq!(pool.endorsement_count, 2);
+    assert_eq!(pool.denunciation_count, 1);
+    assert_eq!(pool.operations.len(), 1);
+
+    let graph = dump.graph.expect("missing graph section");
+    assert_eq!(graph.waiting_for_slot_block_count, 1);
+    assert_eq!(graph.waiting_for_dependencies_block_count, 1);
+    assert_eq!(
+        graph.waiting_for_dependencies[0].missing_dependencies,
+        vec![block_id("missing")]
+    );
+    assert_eq!(graph.discarded_block_count, 2);
+    assert_eq!(graph.discarded_blocks[0].reason, "invalid: bad signature");
+    assert_eq!(graph.discarded_blocks[1].reason, "stale");
+    assert_eq!(graph.discarded_blocks[1].parents, vec![block_id("parent")]);
+    assert_eq!(graph.best_parents, vec![(block_id("best"), 4)]);
+    assert_eq!(graph.clique_count, 1);
+    assert_eq!(
+        graph
+            .stats
+            .expect("missing consensus stats")
+            .final_block_count,
+        10
+    );
+
+    let storage = dump.storage.expect("missing storage section");
+    assert_eq!(storage.operations.stored, 0);
+    assert_eq!(storage.blocks.stored, 0);
+    assert_eq!(storage.blocks.max, None);
+
+    // the latest production comes first
+    let factory = dump.factory.expect("missing factory section");
+    assert_eq!(factory.production_history.len(), 2);
+    assert_eq!(factory.production_history[0].slot, Slot::new(5, 0));
+    assert!(matches!(
+        factory.production_history[0].outcome,
+        SlotProductionOutcomeDump::Produced {
+            operation_count: 2,
+            ..
+        }
+    ));
+    assert_eq!(
+        factory.production_history[1].outcome,
+        SlotProductionOutcomeDump::Skipped {
+            reason: "no connections".to_string()
+        }
+    );
+
+    let execution = dump.execution.expect("missing execution section");
+    assert_eq!(execution.stats.active_cursor, Slot::new(5, 1));
+    assert_eq!(execution.stats.final_cursor, Slot::new(3, 1));
+    assert_eq!(execution.backlog.pending_finalized_block_count, 1);
+    assert!(execution.backlog.pending_blockclique_update);
+    assert_eq!(execution.backlog.active_history_length, 4);
+
+    assert!(dump.config.is_some());
+}
+
+#[test]
+fn debug_dump_respects_request() {
+    // the default mocks have no expectations: querying a component of an excluded section panics
+    let (mut api_private, _config) = start_private_api();
+    api_private.0.execution_controller = Box::new(mock_execution_controller());
+
+    let dump = api_private.0.debug_dump(DebugDumpRequest {
+        pool: false,
+        graph: false,
+        storage: false,
+        factory: false,
+        execution: true,
+        config: false,
+    });
+    assert!(dump.pool.is_none());
+    assert!(dump.graph.is_none());
+    assert!(dump.storage.is_none());
+    assert!(dump.factory.is_none());
+    assert!(dump.execution.is_some());
+    assert!(dump.config.is_none());
+
+    // omitted flags include their section
+    let request: DebugDumpRequest = serde_json::from_str(r#"{"pool": false}"#).unwrap();
+    assert!(!request.pool);
+    assert!(request.graph && request.storage && request.factory);
+    assert!(request.execution && request.config);
+}
+
+#[test]
+fn debug_dump_redacts_keypair() {
+    let (api_private, config) = start_private_api();
+
+    let dump = api_private.0.debug_dump(DebugDumpRequest {
+        pool: false,
+        graph: false,
+        storage: false,
+        factory: false,
+        execution: false,
+        config: true,
+    });
+    let dump_config = dump.config.clone().expect("missing config section");
+    assert_eq!(dump_config.node_public_key, config.keypair.get_public_key());
+
+    let json = serde_json::to_string(&dump).unwrap();
+    assert!(!json.contains(&config.keypair.to_string()));
+    assert!(!json.contains("keypair"));
+    assert!(json.contains(&config.keypair.get_public_key().to_string()));
+}
@@ -14,44 +14,62 @@ use jsonrpsee::{
 };
 use massa_api_exports::{
     address::{AddressFilter, AddressInfo},
-    block::{BlockInfo, BlockSummary},
+    block::{BlockConfirmation, BlockInfo, BlockSummary, EndorsementCoverage},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    deferred_credits::{DeferredCredit, DeferredCreditsRequest},
+    denunciation::{DenunciationEvidence, EvidenceOutcome, EvidenceRejection, SecureShareInput},
     endorsement::EndorsementInfo,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall},
+    envelope::{ResponseEnvelope, API_REVISION, ENVELOPE_HEADER},
+    execution::{
+        ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyResult,
+        SlotExecutionBudget,
+    },
     operation::{OperationInfo, OperationInput},
+    page::PageRequest,
+    pool::{PoolSnapshotItems, PoolSnapshotPage},
+    slashing::SlashingStats,
     TimeInterval,
 };
 use massa_consensus_exports::{
     block_graph_export::BlockGraphExport, block_status::ExportCompiledBlock,
     MockConsensusController,
 };
-use massa_pool_exports::MockPoolController;
-use massa_pos_exports::MockSelectorController;
+use massa_pool_exports::{
+    ExpiredOperation, MockPoolController, PoolSnapshot, PooledEndorsementInfo,
+};
+use massa_pos_exports::{MockSelectorController, PosError, Selection};
 
-use crate::{tests::mock::start_public_api, RpcServer};
+use crate::{denunciation::EvidenceRateLimiter, tests::mock::start_public_api, RpcServer};
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionQueryResponse, ExecutionQueryResponseItem,
-    MockExecutionController, ReadOnlyExecutionOutput,
+    ExecutionAddressInfo, ExecutionError, ExecutionQueryError, ExecutionQueryResponse,
+    ExecutionQueryResponseItem, GasProfileSection, MockExecutionController, ReadOnlyCallTrace,
+    ReadOnlyErrorKind, ReadOnlyExecutionOutput, ReadOnlyExecutionTarget,
 };
 use massa_models::{
     address::Address,
     amount::Amount,
-    block::{Block, BlockGraphStatus},
+    block::{Block, BlockGraphStatus, ConfirmationInfo, SecureShareBlock},
+    block_id::BlockId,
     bytecode::Bytecode,
-    clique::Clique,
-    endorsement::EndorsementId,
+    clique::{Clique, CliqueInfo},
+    config::{CHAINID, ENDORSEMENT_COUNT, THREAD_COUNT, VERSION},
+    denunciation::DenunciationPrecursor,
+    endorsement::{Endorsement, EndorsementId, EndorsementSerializer},
     execution::EventFilter,
     node::NodeId,
     operation::OperationId,
     output_event::SCOutputEvent,
     prehash::{CapacityAllocator, PreHashMap},
     slot::Slot,
-    stats::{ConsensusStats, ExecutionStats, NetworkStats},
+    stats::{
+        ConsensusStats, ExecutionStats, MessageTrafficStats, NetworkStats, NetworkTrafficStats,
+    },
 };
 use massa_protocol_exports::{
     test_exports::tools::{
-        create_block, create_call_sc_op_with_too_much_gas, create_endorsement,
-        create_execute_sc_op_with_too_much_gas, create_operation_with_expire_period,
+        create_block, create_block_with_endorsements, create_call_sc_op_with_too_much_gas,
+        create_endorsement, create_execute_sc_op_with_too_much_gas,
+        create_operation_with_expire_period,
     },
     MockProtocolController,
 };
@@ -86,6 +104,9 @@ async fn get_status() {
             clique_count: 30,
         })
     });
+    consensus_ctrl
+        .expect_get_cliques_info()
+        .returning(get_two_cliques_info);
 
     let mut protocol_ctrl = MockProtocolController::new();
     protocol_ctrl.expect_get_stats().returning(|| {
@@ -100,6 +121,24 @@ async fn get_status() {
             HashMap::new(),
         ))
     });
+    protocol_ctrl.expect_get_network_stats().returning(|| {
+        Ok(NetworkTrafficStats {
+            in_connection_count: 10,
+            out_connection_count: 5,
+            banned_peer_count: 0,
+            handshake_count: 20,
+            handshake_failure_count: 5,
+            blocks: MessageTrafficStats {
+                bytes_sent: 1000,
+                bytes_received: 2000,
+            },
+            operations: MessageTrafficStats {
+                bytes_sent: 300,
+                bytes_received: 400,
+            },
+            ..Default::default()
+        })
+    });
 
     let mut pool_ctrl = MockPoolController::new();
     pool_ctrl.expect_get_operation_count().returning(|| 1024);
@@ -127,13 +166,43 @@ async fn get_status() {
 
     assert_eq!(response.network_stats.in_connection_count, 10);
     assert_eq!(response.network_stats.out_connection_count, 5);
+    let network_traffic = response.network_traffic.unwrap();
+    assert_eq!(network_traffic.in_connection_count, 10);
+    assert_eq!(network_traffic.handshake_failure_rate(), Some(0.25));
+    assert_eq!(network_traffic.blocks.bytes_received, 2000);
+    assert_eq!(network_traffic.operations.bytes_sent, 300);
+    assert_eq!(network_traffic.endorsements, MessageTrafficStats::default());
     assert_eq!(response.config.thread_count, 32);
+    assert_eq!(response.clique_count, 2);
+    assert_eq!(response.fitness_gap_to_second_clique, Some(250));
     // Chain id == 77 for Node in sandbox mode otherwise it is always greater
     assert!(response.chain_id >= 77);
 
     api_public_handle.stop().await;
 }
 
+/// a blockclique of fitness 1000 and an alternative clique of fitness 750
+fn get_two_cliques_info() -> Vec<CliqueInfo> {
+    vec![
+        CliqueInfo::new(
+            &Clique {
+                block_ids: Default::default(),
+                fitness: 750,
+                is_blockclique: false,
+            },
+            vec![],
+        ),
+        CliqueInfo::new(
+            &Clique {
+                block_ids: Default::default(),
+                fitness: 1000,
+                is_blockclique: true,
+            },
+            vec![],
+        ),
+    ]
+}
+
 #[tokio::test]
 async fn get_cliques() {
     let addr: SocketAddr = "[::]:5002".parse().unwrap();
@@ -141,8 +210,8 @@ async fn get_cliques() {
 
     let mut consensus_ctrl = MockConsensusController::new();
     consensus_ctrl
-        .expect_get_cliques()
-        .returning(|| vec![Clique::default()]);
+        .expect_get_cliques_info()
+        .returning(get_two_cliques_info);
 
     api_public.0.consensus_controller = Box::new(consensus_ctrl);
 
@@ -158,10 +227,125 @@ async fn get_cliques() {
         ))
         .unwrap();
     let params = rpc_params![];
-    let response: Vec<massa_models::clique::Clique> =
-        client.request("get_cliques", params).await.unwrap();
+    let response: Vec<CliqueInfo> = client.request("get_cliques", params).await.unwrap();
 
-    assert_eq!(response.len(), 1);
+    assert_eq!(response.len(), 2);
+    assert!(!response[0].is_blockclique);
+    assert_eq!(response[0].fitness, 750);
+    assert!(response[1].is_blockclique);
+    assert_eq!(response[1].fitness, 1000);
+
+    // the enriched form stays readable as the former clique format
+    let legacy: Vec<Clique> = client.request("get_cliques", rpc_params![]).await.unwrap();
+    assert_eq!(legacy.len(), 2);
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn response_envelope() {
+    let addr: SocketAddr = "[::]:5061".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_cliques_info()
+        .returning(get_two_cliques_info);
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+    let url = format!(
+        "http://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    );
+
+    // without the header, the responses are unchanged
+    let client = HttpClientBuilder::default().build(&url).unwrap();
+    let response: Vec<CliqueInfo> = client.request("get_cliques", rpc_params![]).await.unwrap();
+    assert_eq!(response.len(), 2);
+
+    // with it, the results are wrapped
+    let mut headers = hyper::HeaderMap::new();
+    headers.insert(
+        ENVELOPE_HEADER,
+        hyper::header::HeaderValue::from_static("1"),
+    );
+    let client = HttpClientBuilder::default()
+        .set_headers(headers)
+        .build(&url)
+        .unwrap();
+    let response: ResponseEnvelope<Vec<CliqueInfo>> =
+        client.request("get_cliques", rpc_params![]).await.unwrap();
+    assert_eq!(response.node_version, *VERSION);
+    assert_eq!(response.chain_id, *CHAINID);
+    assert_eq!(response.api_revision, API_REVISION);
+    assert_eq!(response.result.len(), 2);
+
+    // but not the errors
+    let response: Result<ResponseEnvelope<Vec<CliqueInfo>>, Error> =
+        client.request("get_clique", rpc_params![]).await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("Method not found"));
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_block_confirmation() {
+    let addr: SocketAddr = "[::]:5059".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+    let active_id =
+        BlockId::generate_from_hash(massa_hash::Hash::compute_from("active".as_bytes()));
+    let unknown_id =
+        BlockId::generate_from_hash(massa_hash::Hash::compute_from("unknown".as_bytes()));
+    let confirmation = ConfirmationInfo {
+        is_final: false,
+        in_blockclique: true,
+        descendants_fitness: 340,
+        fitness_gap: Some(170),
+        confirmation_score: 42,
+    };
+
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_confirmation_info()
+        .returning(move |ids| {
+            ids.iter()
+                .map(|id| (*id == active_id).then_some(confirmation))
+                .collect()
+        });
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let response: Vec<BlockConfirmation> = client
+        .request(
+            "get_block_confirmation",
+            rpc_params![vec![unknown_id, active_id]],
+        )
+        .await
+        .unwrap();
+
+    // the confirmations are returned in the requested order
+    assert_eq!(response.len(), 2);
+    assert_eq!(response[0].id, unknown_id);
+    assert!(response[0].confirmation.is_none());
+    assert_eq!(response[1].id, active_id);
+    assert_eq!(response[1].confirmation, Some(confirmation));
 
     api_public_handle.stop().await;
 }
@@ -179,6 +363,9 @@ async fn get_operations() {
     pool_ctrl
         .expect_contains_operations()
         .returning(|ids| ids.iter().map(|_id| true).collect());
+    pool_ctrl
+        .expect_get_expired_operations()
+        .returning(|ids| vec![None; ids.len()]);
 
     let mut exec_ctrl = MockExecutionController::new();
     exec_ctrl
@@ -206,6 +393,73 @@ async fn get_operations() {
     let response: Vec<OperationInfo> = client.request("get_operations", params).await.unwrap();
 
     assert_eq!(response.len(), 1);
+    assert!(!response[0].is_expired);
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_expired_operations() {
+    let addr: SocketAddr = "[::]:5056".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+    let keypair = KeyPair::generate(0).unwrap();
+    let stored_op = create_operation_with_expire_period(&keypair, 500000);
+    let expired_op = create_operation_with_expire_period(&keypair, 10);
+    api_public
+        .0
+        .storage
+        .store_operations(vec![stored_op.clone()]);
+
+    // the pool only remembers the operation that is not in storage anymore
+    let mut pool_ctrl = MockPoolController::new();
+    pool_ctrl
+        .expect_contains_operations()
+        .returning(|ids| ids.iter().map(|_id| false).collect());
+    let expired = ExpiredOperation {
+        operation: expired_op.clone(),
+        expired_at: MassaTime::now(),
+    };
+    pool_ctrl
+        .expect_get_expired_operations()
+        .returning(move |ids| {
+            ids.iter()
+                .map(|id| (*id == expired.operation.id).then(|| expired.clone()))
+                .collect()
+        });
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_ops_exec_status()
+        .returning(|op| op.iter().map(|_op| (None, None)).collect());
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+    api_public.0.pool_command_sender = Box::new(pool_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let params = rpc_params![vec![
+        expired_op.id,
+        OperationId::from_str("O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC").unwrap(),
+        stored_op.id
+    ]];
+    let response: Vec<OperationInfo> = client.request("get_operations", params).await.unwrap();
+
+    // the operations are returned in the requested order, unknown ones are skipped
+    assert_eq!(response.len(), 2);
+    assert_eq!(response[0].id, expired_op.id);
+    assert!(response[0].is_expired);
+    assert!(!response[0].in_pool);
+    assert_eq!(response[1].id, stored_op.id);
+    assert!(!response[1].is_expired);
 
     api_public_handle.stop().await;
 }
@@ -312,6 +566,224 @@ async fn get_blocks() {
     api_public_handle.stop().await;
 }
 
+/// creates a block at slot (1, 0) endorsed at every index except 3 and 7,
+/// and the addresses drawn to endorse it
+fn create_block_missing_two_endorsements() -> (SecureShareBlock, Vec<Address>) {
+    let slot = Slot::new(1, 0);
+    let endorsed_block = create_block(&KeyPair::generate(0).unwrap()).id;
+    let endorsers: Vec<KeyPair> = (0..ENDORSEMENT_COUNT)
+        .map(|_| KeyPair::generate(0).unwrap())
+        .collect();
+    let endorsements = endorsers
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index != 3 && *index != 7)
+        .map(|(index, keypair)| {
+            Endorsement::new_verifiable(
+                Endorsement {
+                    slot,
+                    index: index as u32,
+                    endorsed_block,
+                },
+                EndorsementSerializer::new(),
+                keypair,
+                *CHAINID,
+            )
+            .unwrap()
+        })
+        .collect();
+    let block = create_block_with_endorsements(&KeyPair::generate(0).unwrap(), slot, endorsements);
+    let expected_endorsers = endorsers
+        .iter()
+        .map(|keypair| Address::from_public_key(&keypair.get_public_key()))
+        .collect();
+    (block, expected_endorsers)
+}
+
+/// asserts that the coverage of `block` has the two missing indices of
+/// `create_block_missing_two_endorsements` and the included endorsements everywhere else
+fn assert_endorsement_coverage(
+    block: &SecureShareBlock,
+    coverage: &[EndorsementCoverage],
+    expected_endorsers: Option<&[Address]>,
+) {
+    assert_eq!(coverage.len(), ENDORSEMENT_COUNT as usize);
+    for (index, state) in coverage.iter().enumerate() {
+        if index == 3 || index == 7 {
+            let expected = expected_endorsers.map(|endorsers| endorsers[index]);
+            assert_eq!(state, &EndorsementCoverage::Missing(expected));
+        } else {
+            let endorsement = block
+                .content
+                .header
+                .content
+                .endorsements
+                .iter()
+                .find(|endorsement| endorsement.content.index == index as u32)
+                .unwrap();
+            assert_eq!(state, &EndorsementCoverage::Included(endorsement.id));
+        }
+    }
+}
+
+#[tokio::test]
+async fn get_blocks_endorsement_coverage() {
+    let addr: SocketAddr = "[::]:5052".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+    // make the block slot recent enough to be attributed
+    api_public.0.api_settings.genesis_timestamp = MassaTime::now();
+
+    let (block, expected_endorsers) = create_block_missing_two_endorsements();
+    api_public.0.storage.store_block(block.clone());
+
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_block_statuses()
+        .returning(|param| param.iter().map(|_| BlockGraphStatus::Final).collect());
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let mut selector_ctrl = MockSelectorController::new();
+    let endorsers = expected_endorsers.clone();
+    selector_ctrl
+        .expect_get_selection()
+        .times(1)
+        .returning(move |_slot| {
+            Ok(Selection {
+                endorsements: endorsers.clone(),
+                producer: endorsers[0],
+            })
+        });
+    // then the draws of the slot are not in the selector history anymore
+    selector_ctrl
+        .expect_get_selection()
+        .returning(|slot| Err(PosError::CycleUnavailable(slot.get_cycle(128))));
+    api_public.0.selector_controller = Box::new(selector_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    for expected in [Some(expected_endorsers.as_slice()), None] {
+        let response: Vec<BlockInfo> = client
+            .request("get_blocks", rpc_params![vec![block.id]])
+            .await
+            .unwrap();
+        let content = response[0].content.as_ref().unwrap();
+        assert_endorsement_coverage(&block, &content.endorsement_coverage, expected);
+    }
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_blocks_endorsement_coverage_out_of_lookback() {
+    let addr: SocketAddr = "[::]:5053".parse().unwrap();
+    // the genesis of the default config is far in the past: the block is out of the lookback
+    let (mut api_public, config) = start_public_api(addr);
+
+    let (block, _) = create_block_missing_two_endorsements();
+    api_public.0.storage.store_block(block.clone());
+
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_block_statuses()
+        .returning(|param| param.iter().map(|_| BlockGraphStatus::Final).collect());
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let mut selector_ctrl = MockSelectorController::new();
+    selector_ctrl.expect_get_selection().never();
+    api_public.0.selector_controller = Box::new(selector_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: Vec<BlockInfo> = client
+        .request("get_blocks", rpc_params![vec![block.id]])
+        .await
+        .unwrap();
+    let content = response[0].content.as_ref().unwrap();
+    assert_endorsement_coverage(&block, &content.endorsement_coverage, None);
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_graph_interval_endorsement_fill_ratio() {
+    let addr: SocketAddr = "[::]:5054".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let (block, _) = create_block_missing_two_endorsements();
+    let mut consensus_ctrl = MockConsensusController::new();
+    consensus_ctrl
+        .expect_get_block_graph_status()
+        .returning(move |_start, _end| {
+            let mut active = PreHashMap::with_capacity(1);
+            active.insert(
+                block.id,
+                ExportCompiledBlock {
+                    header: block.content.header.clone(),
+                    children: vec![],
+                    is_final: true,
+                },
+            );
+            Ok(BlockGraphExport {
+                genesis_blocks: vec![],
+                active_blocks: active,
+                discarded_blocks: PreHashMap::default(),
+                best_parents: vec![],
+                latest_final_blocks_periods: vec![],
+                gi_head: PreHashMap::default(),
+                max_cliques: vec![Clique {
+                    is_blockclique: true,
+                    ..Default::default()
+                }],
+            })
+        });
+    api_public.0.consensus_controller = Box::new(consensus_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let params = rpc_params![TimeInterval {
+        start: Some(MassaTime::now()),
+        end: Some(MassaTime::now())
+    }];
+    let response: Vec<BlockSummary> = client.request("get_graph_interval", params).await.unwrap();
+    assert_eq!(response.len(), 1);
+    assert_eq!(
+        response[0].endorsement_fill_ratio,
+        Some((ENDORSEMENT_COUNT - 2) as f64 / ENDORSEMENT_COUNT as f64)
+    );
+
+    api_public_handle.stop().await;
+}
+
 #[tokio::test]
 async fn get_blockclique_block_by_slot() {
     let addr: SocketAddr = "[::]:5007".parse().unwrap();
@@ -446,6 +918,13 @@ async fn get_graph_interval() {
         .await
         .unwrap();
     assert!(response.len() == 2);
+    for summary in response {
+        if summary.is_stale {
+            assert_eq!(summary.endorsement_fill_ratio, None);
+        } else {
+            assert_eq!(summary.endorsement_fill_ratio, Some(0.0));
+        }
+    }
     api_public_handle.stop().await;
 }
 
@@ -673,26 +1152,66 @@ async fn get_filtered_sc_output_event() {
         .await;
 
     assert_eq!(response.unwrap().len(), 1);
-    api_public_handle.stop().await;
-}
 
-#[tokio::test]
-async fn execute_read_only_bytecode() {
-    let addr: SocketAddr = "[::]:5012".parse().unwrap();
-    let (mut api_public, config) = start_public_api(addr);
+    // the mock configuration accepts 2 patterns of at most 64 bytes
+    let response: Result<Vec<SCOutputEvent>, Error> = client
+        .request(
+            "get_filtered_sc_output_event",
+            rpc_params![EventFilter {
+                data_contains: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                ..Default::default()
+            }],
+        )
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("too many event data patterns"));
 
-    let mut exec_ctrl = MockExecutionController::new();
-    exec_ctrl
-        .expect_execute_readonly_request()
-        .returning(|_req| {
-            Ok(ReadOnlyExecutionOutput {
-                out: massa_execution_exports::ExecutionOutput {
-                    slot: Slot {
-                        period: 1,
-                        thread: 5,
-                    },
-                    block_info: None,
-                    state_changes: massa_final_state::StateChanges::default(),
+    let response: Result<Vec<SCOutputEvent>, Error> = client
+        .request(
+            "get_filtered_sc_output_event",
+            rpc_params![EventFilter {
+                data_contains: vec!["a".repeat(65)],
+                ..Default::default()
+            }],
+        )
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("event data pattern too long"));
+
+    let response: Result<Vec<SCOutputEvent>, Error> = client
+        .request(
+            "get_filtered_sc_output_event",
+            rpc_params![EventFilter {
+                data_contains: vec!["ma".to_string(), "a".repeat(64)],
+                ..Default::default()
+            }],
+        )
+        .await;
+    assert!(response.is_ok());
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn execute_read_only_bytecode() {
+    let addr: SocketAddr = "[::]:5012".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_execute_readonly_request()
+        .returning(|_req| {
+            Ok(ReadOnlyExecutionOutput {
+                out: massa_execution_exports::ExecutionOutput {
+                    slot: Slot {
+                        period: 1,
+                        thread: 5,
+                    },
+                    block_info: None,
+                    state_changes: massa_final_state::StateChanges::default(),
                     events: massa_execution_exports::EventStore::default(),
                     #[cfg(feature = "execution-trace")]
                     slot_trace: None,
@@ -701,9 +1220,12 @@ async fn execute_read_only_bytecode() {
                     deferred_credits_execution: vec![],
                     cancel_async_message_execution: vec![],
                     auto_sell_execution: vec![],
+                    digest: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                gas_profile: None,
+                call_trace: None,
             })
         });
 
@@ -727,7 +1249,10 @@ async fn execute_read_only_bytecode() {
             Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap()
         ),
         operation_datastore: None,
-        fee: None
+        fee: None,
+        profile: false,
+        rng_seed: None,
+        include_trace: false,
     }]];
     let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
         .request("execute_read_only_bytecode", params.clone())
@@ -741,6 +1266,9 @@ async fn execute_read_only_bytecode() {
         address: None,
         operation_datastore: None,
         fee: None,
+        profile: false,
+        rng_seed: None,
+        include_trace: false,
     }]];
     let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
         .request("execute_read_only_bytecode", params.clone())
@@ -753,7 +1281,10 @@ async fn execute_read_only_bytecode() {
         bytecode: "hi".as_bytes().to_vec(),
         address: None,
         operation_datastore: Some("hi".as_bytes().to_vec()),
-        fee: None
+        fee: None,
+        profile: false,
+        rng_seed: None,
+        include_trace: false,
     }]];
     let response: Result<Vec<ExecuteReadOnlyResponse>, Error> = client
         .request("execute_read_only_bytecode", params.clone())
@@ -771,7 +1302,7 @@ async fn execute_read_only_call() {
     let mut exec_ctrl = MockExecutionController::new();
     exec_ctrl
         .expect_execute_readonly_request()
-        .returning(|_req| {
+        .returning(|req| {
             Ok(ReadOnlyExecutionOutput {
                 out: massa_execution_exports::ExecutionOutput {
                     slot: Slot {
@@ -788,9 +1319,26 @@ async fn execute_read_only_call() {
                     deferred_credits_execution: vec![],
                     cancel_async_message_execution: vec![],
                     auto_sell_execution: vec![],
+                    digest: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                gas_profile: req
+                    .profile
+                    .then(|| BTreeMap::from([(GasProfileSection::Instantiation, 100)])),
+                call_trace: req.include_trace.then(|| ReadOnlyCallTrace {
+                    target: Address::from_str(
+                        "AS12cx6BJHSrBPPSE86E6LYgYS44dvXoHW77cdPbTT8H41wm6xGN5",
+                    )
+                    .unwrap(),
+                    function: Some("hello".to_string()),
+                    parameter_size: Some(0),
+                    coins: Amount::zero(),
+                    gas_used: Some(100),
+                    error: None,
+                    sub_calls: vec![],
+                    omitted_calls: 0,
+                }),
             })
         });
 
@@ -823,6 +1371,9 @@ async fn execute_read_only_call() {
         caller_address: None,
         fee: None,
         coins: None,
+        profile: true,
+        rng_seed: None,
+        include_trace: true,
     }]];
     let response: Vec<ExecuteReadOnlyResponse> = client
         .request("execute_read_only_call", params.clone())
@@ -830,6 +1381,142 @@ async fn execute_read_only_call() {
         .unwrap();
 
     assert_eq!(response.len(), 1);
+    assert_eq!(
+        response[0].gas_profile,
+        Some(BTreeMap::from([(GasProfileSection::Instantiation, 100)]))
+    );
+    let call_trace = response[0].call_trace.as_ref().expect("missing call trace");
+    assert_eq!(call_trace.function.as_deref(), Some("hello"));
+    assert_eq!(call_trace.gas_used, Some(100));
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn execute_read_only_call_error_kinds() {
+    let addr: SocketAddr = "[::]:5060".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    // the called function names the failure returned by the execution
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_execute_readonly_request()
+        .returning(|req| {
+            let ReadOnlyExecutionTarget::FunctionCall {
+                target_addr,
+                target_func,
+                ..
+            } = req.target
+            else {
+                panic!("unexpected bytecode execution");
+            };
+            let kind = match target_func.as_str() {
+                "out_of_gas" => ReadOnlyErrorKind::OutOfGas,
+                "target_not_found" => ReadOnlyErrorKind::TargetNotFound,
+                "bytecode_trap" => ReadOnlyErrorKind::BytecodeTrap,
+                "datastore_access_denied" => ReadOnlyErrorKind::DatastoreAccessDenied,
+                _ => {
+                    return Err(ExecutionError::RuntimeError(
+                        "insufficient balance".to_string(),
+                    ))
+                }
+            };
+            // a failed execution keeps the calls it made up to its failure
+            let call_trace = req.include_trace.then(|| {
+                Box::new(ReadOnlyCallTrace {
+                    target: target_addr,
+                    function: Some(target_func.clone()),
+                    parameter_size: Some(0),
+                    coins: Amount::zero(),
+                    gas_used: None,
+                    error: Some(target_func.clone()),
+                    sub_calls: vec![],
+                    omitted_calls: 0,
+                })
+            });
+            Err(ExecutionError::ReadOnlyError {
+                kind,
+                message: target_func,
+                call_trace,
+            })
+        });
+
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let functions = [
+        "out_of_gas",
+        "target_not_found",
+        "bytecode_trap",
+        "datastore_access_denied",
+        "other",
+    ];
+    let params = rpc_params![functions
+        .iter()
+        .map(|function| ReadOnlyCall {
+            max_gas: 1000000,
+            target_address: Address::from_str(
+                "AS12cx6BJHSrBPPSE86E6LYgYS44dvXoHW77cdPbTT8H41wm6xGN5"
+            )
+            .unwrap(),
+            target_function: function.to_string(),
+            parameter: vec![],
+            caller_address: None,
+            fee: None,
+            coins: None,
+            profile: false,
+            rng_seed: None,
+            include_trace: true,
+        })
+        .collect::<Vec<_>>()];
+    let response: Vec<ExecuteReadOnlyResponse> = client
+        .request("execute_read_only_call", params)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response
+            .iter()
+            .map(|response| response.error_kind)
+            .collect::<Vec<_>>(),
+        vec![
+            Some(ReadOnlyErrorKind::OutOfGas),
+            Some(ReadOnlyErrorKind::TargetNotFound),
+            Some(ReadOnlyErrorKind::BytecodeTrap),
+            Some(ReadOnlyErrorKind::DatastoreAccessDenied),
+            None,
+        ]
+    );
+    assert!(response
+        .iter()
+        .all(|response| matches!(response.result, ReadOnlyResult::Error(_))));
+    // only the read-only errors carry a trace
+    assert_eq!(
+        response
+            .iter()
+            .map(|response| response
+                .call_trace
+                .as_ref()
+                .and_then(|call_trace| call_trace.error.clone()))
+            .collect::<Vec<_>>(),
+        vec![
+            Some("out_of_gas".to_string()),
+            Some("target_not_found".to_string()),
+            Some("bytecode_trap".to_string()),
+            Some("datastore_access_denied".to_string()),
+            None,
+        ]
+    );
     api_public_handle.stop().await;
 }
 
@@ -898,13 +1585,18 @@ async fn get_addresses_bytecode() {
     let addr: SocketAddr = "[::]:5019".parse().unwrap();
     let (mut api_public, config) = start_public_api(addr);
 
+    // a smart contract, a user address and an unknown address
     let mut exec_ctrl: MockExecutionController = MockExecutionController::new();
     exec_ctrl
         .expect_query_state()
         .returning(|_| ExecutionQueryResponse {
-            responses: vec![Ok(ExecutionQueryResponseItem::Bytecode(Bytecode(
-                "massa".as_bytes().to_vec(),
-            )))],
+            responses: vec![
+                Ok(ExecutionQueryResponseItem::Bytecode(Bytecode(
+                    "massa".as_bytes().to_vec(),
+                ))),
+                Ok(ExecutionQueryResponseItem::Bytecode(Bytecode(Vec::new()))),
+                Err(ExecutionQueryError::NotFound("Account".to_string())),
+            ],
             candidate_cursor: massa_models::slot::Slot::new(1, 2),
             final_cursor: Slot::new(1, 7),
             final_state_fingerprint: massa_hash::Hash::compute_from(&Vec::new()),
@@ -925,22 +1617,29 @@ async fn get_addresses_bytecode() {
         .unwrap();
 
     let params = rpc_params![];
-    let response: Result<Vec<Vec<u8>>, Error> = client
+    let response: Result<Vec<Option<Vec<u8>>>, Error> = client
         .request("get_addresses_bytecode", params.clone())
         .await;
     assert!(response.unwrap_err().to_string().contains("Invalid params"));
 
-    let params = rpc_params![vec![AddressFilter {
-        address: Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
-            .unwrap(),
-        is_final: true
-    }]];
-    let response: Vec<Vec<u8>> = client
+    let address =
+        Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let params = rpc_params![vec![
+        AddressFilter {
+            address,
+            is_final: true
+        };
+        3
+    ]];
+    let response: Vec<Option<Vec<u8>>> = client
         .request("get_addresses_bytecode", params.clone())
         .await
         .unwrap();
 
-    assert!(response.len() == 1);
+    assert_eq!(
+        response,
+        vec![Some("massa".as_bytes().to_vec()), None, None]
+    );
 
     api_public_handle.stop().await;
 }
@@ -1029,6 +1728,17 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
+    let response: Result<(), Error> = client
+        .request(
+            "node_sign_message_v2",
+            rpc_params![Vec::<u8>::new(), "domain".to_string()],
+        )
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
     let response: Result<(), Error> = client
         .request(
             "remove_staking_addresses",
@@ -1048,6 +1758,12 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
+    let response: Result<(), Error> = client.request("node_debug_dump", params.clone()).await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
     let response: Result<(), Error> = client
         .request("node_ban_by_ip", rpc_params![Vec::<IpAddr>::new()])
         .await;
@@ -1217,6 +1933,14 @@ async fn wrong_api() {
         .to_string()
         .contains("The wrong API (either Public or Private) was called"));
 
+    let response: Result<(), Error> = client
+        .request("node_get_consensus_command_timings", params.clone())
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("The wrong API (either Public or Private) was called"));
+
     let response: Result<(), Error> = client
         .request("add_staking_secret_keys", rpc_params![Vec::<String>::new()])
         .await;
@@ -1345,3 +2069,609 @@ async fn get_stakers() {
 
     api_public_handle.stop().await;
 }
+
+#[tokio::test]
+async fn get_deferred_credits() {
+    let addr: SocketAddr = "[::]:5058".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let addr1 = Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+    let addr2 = Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+    let credits: BTreeMap<Slot, Vec<(Address, Amount)>> = BTreeMap::from([
+        (Slot::new(1, 0), vec![(addr1, Amount::from_raw(1))]),
+        (
+            Slot::new(1, 1),
+            vec![(addr1, Amount::from_raw(2)), (addr2, Amount::from_raw(3))],
+        ),
+        (Slot::new(2, 0), vec![(addr2, Amount::from_raw(4))]),
+    ]);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_deferred_credits_range()
+        .withf(move |min_slot, max_slot, addresses| {
+            *min_slot == Slot::new(1, 0)
+                && *max_slot == Slot::new(3, 1)
+                && addresses
+                    .as_deref()
+                    .map_or(true, |addresses| addresses == [addr1, addr2])
+        })
+        .returning(move |_, _, _| credits.clone());
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+    let request =
+        |min_slot: Slot, max_slot: Slot, addresses: Option<Vec<Address>>| DeferredCreditsRequest {
+            min_slot,
+            max_slot,
+            addresses,
+        };
+
+    // the credits are flattened in the order of the slots then of the addresses
+    let response: Vec<DeferredCredit> = client
+        .request(
+            "get_deferred_credits",
+            rpc_params![
+                request(Slot::new(1, 0), Slot::new(3, 1), Some(vec![addr1, addr2])),
+                Option::<PageRequest>::None
+            ],
+        )
+        .await
+        .unwrap();
+    let expected = vec![
+        (Slot::new(1, 0), addr1, 1),
+        (Slot::new(1, 1), addr1, 2),
+        (Slot::new(1, 1), addr2, 3),
+        (Slot::new(2, 0), addr2, 4),
+    ];
+    assert_eq!(
+        response,
+        expected
+            .iter()
+            .map(|(slot, address, amount)| DeferredCredit {
+                slot: *slot,
+                address: *address,
+                amount: Amount::from_raw(*amount),
+            })
+            .collect::<Vec<_>>()
+    );
+
+    // the credits are paginated
+    let response: Vec<DeferredCredit> = client
+        .request(
+            "get_deferred_credits",
+            rpc_params![
+                request(Slot::new(1, 0), Slot::new(3, 1), None),
+                PageRequest {
+                    limit: 2,
+                    offset: 1
+                }
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.len(), 2);
+    assert_eq!(
+        (response[0].slot, response[0].address),
+        (Slot::new(1, 1), addr2)
+    );
+    assert_eq!(response[1].slot, Slot::new(2, 0));
+
+    // an inverted range is rejected
+    let response: Result<Vec<DeferredCredit>, Error> = client
+        .request(
+            "get_deferred_credits",
+            rpc_params![
+                request(Slot::new(3, 1), Slot::new(1, 0), None),
+                Option::<PageRequest>::None
+            ],
+        )
+        .await;
+    assert!(response.is_err());
+
+    // so is a range larger than the configured cap
+    let thread_count = config.thread_count as u64;
+    let max_slot = Slot::new(1 + config.max_deferred_credits_slot_range / thread_count, 0);
+    let response: Result<Vec<DeferredCredit>, Error> = client
+        .request(
+            "get_deferred_credits",
+            rpc_params![
+                request(Slot::new(1, 0), max_slot, None),
+                Option::<PageRequest>::None
+            ],
+        )
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("slot range too large"));
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_blocks_by_creator() {
+    let addr: SocketAddr = "[::]:5063".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+    let keypair = KeyPair::generate(0).unwrap();
+    let other_keypair = KeyPair::generate(0).unwrap();
+    let creator = Address::from_public_key(&keypair.get_public_key());
+
+    // stored out of slot order, with a block of another creator in between
+    let blocks: Vec<SecureShareBlock> = [3, 1, 2]
+        .into_iter()
+        .map(|period| create_block_with_endorsements(&keypair, Slot::new(period, 0), vec![]))
+        .collect();
+    for block in &blocks {
+        api_public.0.storage.store_block(block.clone());
+    }
+    let other_block = create_block_with_endorsements(&other_keypair, Slot::new(2, 1), vec![]);
+    api_public.0.storage.store_block(other_block);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: Vec<BlockId> = client
+        .request(
+            "get_blocks_by_creator",
+            rpc_params![creator, Option::<PageRequest>::None],
+        )
+        .await
+        .unwrap();
+    assert_eq!(response, vec![blocks[1].id, blocks[2].id, blocks[0].id]);
+
+    let response: Vec<BlockId> = client
+        .request(
+            "get_blocks_by_creator",
+            rpc_params![
+                creator,
+                PageRequest {
+                    limit: 2,
+                    offset: 1
+                }
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(response, vec![blocks[0].id]);
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_slashing_stats() {
+    let addr: SocketAddr = "[::]:5062".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_cycle_slashing_stats()
+        .withf(|min_cycle, max_cycle| *min_cycle == 2 && *max_cycle == 5)
+        .times(1)
+        .returning(|_, _| {
+            vec![
+                massa_execution_exports::SlashingStats {
+                    cycle: 3,
+                    denunciation_count: 4,
+                    slashed_rolls: 5,
+                    slashed_address_count: 2,
+                },
+                massa_execution_exports::SlashingStats {
+                    cycle: 4,
+                    denunciation_count: 1,
+                    slashed_rolls: 0,
+                    slashed_address_count: 1,
+                },
+            ]
+        });
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: Vec<SlashingStats> = client
+        .request("get_slashing_stats", rpc_params![2, 5])
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        vec![
+            SlashingStats {
+                cycle: 3,
+                denunciation_count: 4,
+                slashed_rolls: 5,
+                slashed_address_count: 2,
+            },
+            SlashingStats {
+                cycle: 4,
+                denunciation_count: 1,
+                slashed_rolls: 0,
+                slashed_address_count: 1,
+            },
+        ]
+    );
+
+    // an inverted range is rejected without querying the execution
+    let response: Result<Vec<SlashingStats>, Error> = client
+        .request("get_slashing_stats", rpc_params![5, 2])
+        .await;
+    assert!(response.is_err());
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_slot_execution_budget() {
+    let addr: SocketAddr = "[::]:5064".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl
+        .expect_get_slot_budget()
+        .withf(|slot| *slot == Slot::new(10, 3))
+        .times(1)
+        .returning(|slot| massa_execution_exports::SlotExecutionBudget {
+            slot,
+            max_async_gas: 1_000_000,
+            ready_async_message_count: 3,
+            ready_async_gas: 1_200_000,
+            projected_async_message_count: 2,
+            projected_async_gas: 800_000,
+            max_block_gas: 4_000_000,
+        });
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: SlotExecutionBudget = client
+        .request("get_slot_execution_budget", rpc_params![Slot::new(10, 3)])
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        SlotExecutionBudget {
+            slot: Slot::new(10, 3),
+            max_async_gas: 1_000_000,
+            ready_async_message_count: 3,
+            ready_async_gas: 1_200_000,
+            projected_async_message_count: 2,
+            projected_async_gas: 800_000,
+            async_gas_headroom: 200_000,
+            max_block_gas: 4_000_000,
+        }
+    );
+
+    // a slot in a thread that does not exist is rejected without querying the execution
+    let response: Result<SlotExecutionBudget, Error> = client
+        .request(
+            "get_slot_execution_budget",
+            rpc_params![Slot::new(10, THREAD_COUNT)],
+        )
+        .await;
+    assert!(response.is_err());
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn get_pool_snapshot() {
+    let addr: SocketAddr = "[::]:5055".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let endorsements: Vec<PooledEndorsementInfo> = (0..3)
+        .map(|index| {
+            let endorsement = create_endorsement();
+            PooledEndorsementInfo {
+                id: endorsement.id,
+                slot: endorsement.content.slot,
+                index,
+                endorsed_block: endorsement.content.endorsed_block,
+            }
+        })
+        .collect();
+    let snapshot_items = endorsements.clone();
+    let mut pool_ctrl = MockPoolController::new();
+    pool_ctrl
+        .expect_snapshot_endorsements()
+        .times(1)
+        .returning(move || PoolSnapshot {
+            sequence: 7,
+            slot: Some(Slot::new(10, 2)),
+            items: snapshot_items.clone().into(),
+        });
+    api_public.0.pool_command_sender = Box::new(pool_ctrl);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: PoolSnapshotPage = client
+        .request(
+            "get_pool_snapshot",
+            rpc_params![
+                "endorsements",
+                PageRequest {
+                    limit: 2,
+                    offset: 1
+                }
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.sequence, 7);
+    assert_eq!(response.slot, Some(Slot::new(10, 2)));
+    let PoolSnapshotItems::Endorsements(page) = response.items else {
+        panic!("expected endorsements");
+    };
+    let page = serde_json::to_value(page).unwrap();
+    assert_eq!(page["total_count"], 3);
+    assert_eq!(
+        page["content"],
+        serde_json::to_value(&endorsements[1..]).unwrap()
+    );
+
+    // the other pages of the snapshot are requested by its sequence number, without taking a new one
+    let response: PoolSnapshotPage = client
+        .request(
+            "get_pool_snapshot",
+            rpc_params![
+                "endorsements",
+                PageRequest {
+                    limit: 1,
+                    offset: 0
+                },
+                7
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.sequence, 7);
+    let PoolSnapshotItems::Endorsements(page) = response.items else {
+        panic!("expected endorsements");
+    };
+    let page = serde_json::to_value(page).unwrap();
+    assert_eq!(page["total_count"], 3);
+    assert_eq!(
+        page["content"],
+        serde_json::to_value(&endorsements[..1]).unwrap()
+    );
+
+    // a snapshot that was not retained can't be paged
+    let response: Result<PoolSnapshotPage, _> = client
+        .request(
+            "get_pool_snapshot",
+            rpc_params![
+                "endorsements",
+                PageRequest {
+                    limit: 1,
+                    offset: 0
+                },
+                99
+            ],
+        )
+        .await;
+    assert!(response
+        .unwrap_err()
+        .to_string()
+        .contains("pool snapshot 99 is not retained anymore"));
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn submit_denunciation_evidence() {
+    let addr: SocketAddr = "[::]:5066".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let endorse = |slot: Slot, index: u32, block_seed: &str| {
+        let endorsement = Endorsement::new_verifiable(
+            Endorsement {
+                slot,
+                index,
+                endorsed_block: BlockId::generate_from_hash(massa_hash::Hash::compute_from(
+                    block_seed.as_bytes(),
+                )),
+            },
+            EndorsementSerializer::new(),
+            &keypair,
+            *CHAINID,
+        )
+        .unwrap();
+        SecureShareInput {
+            creator_public_key: endorsement.content_creator_pub_key,
+            signature: endorsement.signature,
+            serialized_content: endorsement.serialized_data,
+        }
+    };
+    let valid_slot = Slot::new(990, 3);
+    let evidences = vec![
+        // two endorsements of different blocks at the same slot and index
+        DenunciationEvidence::Endorsements(
+            endorse(valid_slot, 4, "blk1"),
+            endorse(valid_slot, 4, "blk2"),
+        ),
+        // the same but final for too long
+        DenunciationEvidence::Endorsements(
+            endorse(Slot::new(10, 3), 4, "blk1"),
+            endorse(Slot::new(10, 3), 4, "blk2"),
+        ),
+        // different indexes do not conflict
+        DenunciationEvidence::Endorsements(
+            endorse(valid_slot, 4, "blk1"),
+            endorse(valid_slot, 5, "blk2"),
+        ),
+    ];
+
+    let mut exec_ctrl = MockExecutionController::new();
+    exec_ctrl.expect_get_stats().returning(|| ExecutionStats {
+        time_window_start: MassaTime::now(),
+        time_window_end: MassaTime::now(),
+        final_block_count: 0,
+        final_executed_operations_count: 0,
+        active_cursor: Slot::new(1000, 0),
+        final_cursor: Slot::new(1000, 0),
+    });
+    exec_ctrl
+        .expect_get_denunciation_execution_status()
+        .returning(|_| (false, false));
+    api_public.0.execution_controller = Box::new(exec_ctrl);
+
+    // only the precursors of the valid evidence reach the pool
+    let public_key = keypair.get_public_key();
+    let mut pool_ctrl = MockPoolController::new();
+    pool_ctrl
+        .expect_add_denunciation_precursor()
+        .withf(move |precursor| {
+            matches!(
+                precursor,
+                DenunciationPrecursor::Endorsement(de_p)
+                    if de_p.slot == valid_slot && de_p.index == 4 && de_p.public_key == public_key
+            )
+        })
+        .times(2)
+        .returning(|_| ());
+    api_public.0.pool_command_sender = Box::new(pool_ctrl);
+    // 3 items per second: the second call from the same client goes over the limit
+    *api_public.0.evidence_rate_limiter.lock() = EvidenceRateLimiter::new(3);
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let response: Vec<EvidenceOutcome> = client
+        .request("submit_denunciation_evidence", rpc_params![evidences])
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        vec![
+            EvidenceOutcome::Accepted,
+            EvidenceOutcome::Rejected(EvidenceRejection::Expired),
+            EvidenceOutcome::Rejected(EvidenceRejection::NotConflicting),
+        ]
+    );
+
+    let response: Result<Vec<EvidenceOutcome>, Error> = client
+        .request(
+            "submit_denunciation_evidence",
+            rpc_params![vec![DenunciationEvidence::Endorsements(
+                endorse(valid_slot, 4, "blk1"),
+                endorse(valid_slot, 4, "blk3"),
+            )]],
+        )
+        .await;
+    assert!(response.is_err());
+
+    api_public_handle.stop().await;
+}
+
+#[tokio::test]
+async fn submit_denunciation_evidence_over_rate_limit() {
+    let addr: SocketAddr = "[::]:5067".parse().unwrap();
+    let (mut api_public, config) = start_public_api(addr);
+    *api_public.0.evidence_rate_limiter.lock() = EvidenceRateLimiter::new(1);
+    // the whole call is rejected before checking any evidence
+    api_public.0.execution_controller = Box::new(MockExecutionController::new());
+    api_public.0.pool_command_sender = Box::new(MockPoolController::new());
+
+    let api_public_handle = api_public
+        .serve(&addr, &config)
+        .await
+        .expect("failed to start PUBLIC API");
+    let client = HttpClientBuilder::default()
+        .build(format!(
+            "http://localhost:{}",
+            addr.to_string().split(':').last().unwrap()
+        ))
+        .unwrap();
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let input = SecureShareInput {
+        creator_public_key: keypair.get_public_key(),
+        signature: keypair
+            .sign(&massa_hash::Hash::compute_from(b"evidence"))
+            .unwrap(),
+        serialized_content: vec![],
+    };
+    let evidence = DenunciationEvidence::Endorsements(input.clone(), input);
+    let response: Result<Vec<EvidenceOutcome>, Error> = client
+        .request(
+            "submit_denunciation_evidence",
+            rpc_params![vec![evidence.clone(), evidence]],
+        )
+        .await;
+    let err = response.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("at most 1 denunciation evidences are accepted per second"));
+
+    api_public_handle.stop().await;
+}
+
+#[test]
+fn evidence_rate_limit_per_client() {
+    let client_1: Option<IpAddr> = Some("10.0.0.1".parse().unwrap());
+    let client_2: Option<IpAddr> = Some("10.0.0.2".parse().unwrap());
+    let mut rate_limiter = EvidenceRateLimiter::new(3);
+    assert!(rate_limiter.try_acquire(client_1, 3));
+    assert!(!rate_limiter.try_acquire(client_1, 1));
+    // the other clients are not held back by the first one
+    assert!(rate_limiter.try_acquire(client_2, 2));
+    assert!(rate_limiter.try_acquire(None, 3));
+    assert!(!rate_limiter.try_acquire(client_2, 2));
+}
@@ -357,6 +357,21 @@ impl AsyncPool {
         fetched_messages
     }
 
+    /// Query from the database the messages sent to `destination`, found with the `message_info_cache`.
+    pub fn fetch_messages_to(
+        &self,
+        destination: &Address,
+    ) -> BTreeMap<AsyncMessageId, AsyncMessage> {
+        self.message_info_cache
+            .iter()
+            .filter(|(_, message_info)| &message_info.destination == destination)
+            .filter_map(|(message_id, _)| {
+                self.fetch_message(message_id)
+                    .map(|message| (*message_id, message))
+            })
+            .collect()
+    }
+
     /// Deserializes the key and value, useful after bootstrap
     pub fn is_key_value_valid(&self, serialized_key: &[u8], serialized_value: &[u8]) -> bool {
         if !serialized_key.starts_with(ASYNC_POOL_PREFIX.as_bytes()) {
@@ -1419,4 +1434,57 @@ mod tests {
 
         assert_eq!(pool2.message_info_cache, message_info_cache1);
     }
+
+    #[test]
+    fn test_pool_fetch_messages_to() {
+        // Init a pool with messages to two destinations
+        // and check that only the messages to the requested one are fetched
+
+        let config = AsyncPoolConfig::default();
+        let temp_dir = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+            max_ledger_backups: 100,
+        };
+        let db: ShareableMassaDBController = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>,
+        ));
+        let mut pool = AsyncPool::new(config, db);
+
+        let message = create_message();
+        let mut other_message = message.clone();
+        other_message.destination =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        other_message.emission_index = 1;
+
+        let mut changes = AsyncPoolChanges::default();
+        changes.0.insert(
+            message.compute_id(),
+            SetUpdateOrDelete::Set(message.clone()),
+        );
+        changes.0.insert(
+            other_message.compute_id(),
+            SetUpdateOrDelete::Set(other_message.clone()),
+        );
+        let mut batch = DBBatch::new();
+        pool.apply_changes_to_batch(&changes, &mut batch);
+        pool.db
+            .write()
+            .write_batch(batch, DBBatch::new(), Some(Slot::new(1, 0)));
+
+        let fetched = pool.fetch_messages_to(&message.destination);
+        assert_eq!(
+            fetched.into_iter().collect::<Vec<_>>(),
+            vec![(message.compute_id(), message)]
+        );
+        let fetched = pool.fetch_messages_to(&other_message.destination);
+        assert_eq!(
+            fetched.into_iter().collect::<Vec<_>>(),
+            vec![(other_message.compute_id(), other_message)]
+        );
+    }
 }
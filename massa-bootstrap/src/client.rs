@@ -27,7 +27,7 @@ use crate::{
     error::BootstrapError,
     messages::{BootstrapClientMessage, BootstrapServerMessage},
     settings::IpType,
-    BootstrapConfig, GlobalBootstrapState,
+    BootstrapConfig, GlobalBootstrapState, ImportProgressCallback,
 };
 
 /// Specifies a common interface that can be used by standard, or mockers
@@ -91,6 +91,19 @@ fn stream_final_state_and_consensus(
                     // Set final state
                     let mut write_final_state = global_bootstrap_state.final_state.write();
 
+                    // Reject the part before writing anything, so that the bootstrap can resume
+                    // from the same cursor with another server
+                    if let BootstrapClientMessage::AskBootstrapPart {
+                        last_state_step, ..
+                    } = &next_bootstrap_message
+                    {
+                        write_final_state.check_bootstrap_part(
+                            &state_part,
+                            last_state_step,
+                            slot,
+                        )?;
+                    }
+
                     // We only need to receive the initial_state once
                     if let Some(last_start_period) = last_start_period {
                         write_final_state.set_last_start_period(last_start_period);
@@ -99,6 +112,7 @@ fn stream_final_state_and_consensus(
                         write_final_state.set_last_slot_before_downtime(last_slot_before_downtime);
                     }
 
+                    let new_keys = state_part.new_elements.len() as u64;
                     let (last_state_step, last_versioning_step) = write_final_state
                         .get_database()
                         .write()
@@ -109,6 +123,15 @@ fn stream_final_state_and_consensus(
                                 e
                             ))
                         })?;
+                    drop(write_final_state);
+
+                    // Report the import progress
+                    global_bootstrap_state.imported_keys = global_bootstrap_state
+                        .imported_keys
+                        .saturating_add(new_keys);
+                    if let Some(import_progress) = &global_bootstrap_state.import_progress {
+                        import_progress(global_bootstrap_state.imported_keys, None);
+                    }
 
                     // Set consensus blocks
                     if let Some(graph) = global_bootstrap_state.graph.as_mut() {
@@ -176,6 +199,7 @@ fn stream_final_state_and_consensus(
                     };
                     let mut write_final_state = global_bootstrap_state.final_state.write();
                     write_final_state.reset();
+                    global_bootstrap_state.imported_keys = 0;
                     return Err(BootstrapError::GeneralError(String::from("Slot too old")));
                 }
                 // At this point, we have successfully received the next message from the server, and it's an error-message String
@@ -395,6 +419,34 @@ fn filter_bootstrap_list(
 /// with a sig-int handler setup.
 #[allow(clippy::too_many_arguments)]
 pub fn get_state(
+    bootstrap_config: &BootstrapConfig,
+    final_state: Arc<RwLock<dyn FinalStateController>>,
+    connector: impl BSConnector,
+    version: Version,
+    genesis_timestamp: MassaTime,
+    end_timestamp: Option<MassaTime>,
+    restart_from_snapshot_at_period: Option<u64>,
+    interupted: Arc<(Mutex<bool>, Condvar)>,
+    massa_metrics: MassaMetrics,
+) -> Result<GlobalBootstrapState, BootstrapError> {
+    get_state_with_import_progress(
+        bootstrap_config,
+        final_state,
+        connector,
+        version,
+        genesis_timestamp,
+        end_timestamp,
+        restart_from_snapshot_at_period,
+        interupted,
+        massa_metrics,
+        None,
+    )
+}
+
+/// Same as `get_state`, calling `import_progress` while the final state is imported
+/// from a bootstrap server. See `ImportProgressCallback` for the frequency of the calls.
+#[allow(clippy::too_many_arguments)]
+pub fn get_state_with_import_progress(
     bootstrap_config: &BootstrapConfig,
     final_state: Arc<RwLock<dyn FinalStateController>>,
     mut connector: impl BSConnector,
@@ -404,13 +456,14 @@ pub fn get_state(
     restart_from_snapshot_at_period: Option<u64>,
     interupted: Arc<(Mutex<bool>, Condvar)>,
     massa_metrics: MassaMetrics,
+    import_progress: Option<ImportProgressCallback>,
 ) -> Result<GlobalBootstrapState, BootstrapError> {
     massa_trace!("bootstrap.lib.get_state", {});
 
     // If we restart from a snapshot, do not bootstrap
     if restart_from_snapshot_at_period.is_some() {
         massa_trace!("bootstrap.lib.get_state.init_from_snapshot", {});
-        return Ok(GlobalBootstrapState::new(final_state));
+        return Ok(GlobalBootstrapState::new(final_state, import_progress));
     }
 
     // if we are before genesis, do not bootstrap
@@ -471,7 +524,7 @@ pub fn get_state(
                 Some(slot),
             );
         }
-        return Ok(GlobalBootstrapState::new(final_state));
+        return Ok(GlobalBootstrapState::new(final_state, import_progress));
     }
 
     // If the two conditions above are not verified, we need to bootstrap
@@ -486,7 +539,7 @@ pub fn get_state(
             last_consensus_step: StreamingStep::Started,
             send_last_start_period: true,
         };
-    let mut global_bootstrap_state = GlobalBootstrapState::new(final_state);
+    let mut global_bootstrap_state = GlobalBootstrapState::new(final_state, import_progress);
 
     let limit = bootstrap_config.rate_limit;
     loop {
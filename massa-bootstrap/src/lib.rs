@@ -29,7 +29,7 @@ mod tools;
 /// white/black list
 pub mod white_black_list;
 
-pub use client::{get_state, DefaultConnector};
+pub use client::{get_state, get_state_with_import_progress, DefaultConnector};
 pub use listener::BootstrapTcpListener;
 pub use messages::{
     BootstrapClientMessage, BootstrapClientMessageDeserializer, BootstrapClientMessageSerializer,
@@ -52,14 +52,37 @@ pub struct GlobalBootstrapState {
 
     /// list of network peers
     pub peers: Option<BootstrapPeers>,
+
+    /// number of final state keys imported so far
+    imported_keys: u64,
+
+    /// called after each imported part of the final state
+    import_progress: Option<ImportProgressCallback>,
 }
 
+/// Callback reporting the progress of the final state import during bootstrap.
+///
+/// It is called with the number of final state keys imported so far and an estimate of the total
+/// number of keys, `None` when the server does not provide one.
+///
+/// It is called once per part received from the server, after the part was written to disk.
+/// The new keys of a part weigh at most `max_final_state_elements_size` bytes, so it is called
+/// at least once per such amount of imported data. Parts only carrying updates of already imported
+/// keys also trigger a call, with an unchanged count.
+/// The count restarts from 0 when the server asks to bootstrap again from scratch.
+pub type ImportProgressCallback = Arc<dyn Fn(u64, Option<u64>) + Send + Sync>;
+
 impl GlobalBootstrapState {
-    fn new(final_state: Arc<RwLock<dyn FinalStateController>>) -> Self {
+    fn new(
+        final_state: Arc<RwLock<dyn FinalStateController>>,
+        import_progress: Option<ImportProgressCallback>,
+    ) -> Self {
         Self {
             final_state,
             graph: None,
             peers: None,
+            imported_keys: 0,
+            import_progress,
         }
     }
 }
@@ -0,0 +1,210 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use super::tools::parametric_test;
+use massa_async_pool::{AsyncMessage, AsyncPoolChanges};
+use massa_db_exports::{DBBatch, MassaDBConfig, MassaDBController};
+use massa_db_worker::MassaDB;
+use massa_executed_ops::ExecutedOpsChanges;
+use massa_final_state::test_exports::assert_final_states_equivalent;
+use massa_final_state::{FinalState, FinalStateConfig, FinalStateController, StateChanges};
+use massa_hash::Hash;
+use massa_ledger_exports::{
+    LedgerChanges, LedgerEntry, LedgerEntryUpdate, SetOrDelete, SetOrKeep, SetUpdateOrDelete,
+};
+use massa_ledger_worker::FinalLedger;
+use massa_models::{
+    address::Address, amount::Amount, bytecode::Bytecode, config::THREAD_COUNT,
+    operation::OperationId, prehash::PreHashMap, slot::Slot,
+};
+use massa_pos_exports::{DeferredCredits, MockSelectorControllerWrapper, PoSChanges};
+use massa_signature::KeyPair;
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use num::rational::Ratio;
+use parking_lot::RwLock;
+use rand::rngs::SmallRng;
+use rand::Rng;
+use serial_test::serial;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// creates an empty final state attached to the last genesis slot
+fn get_empty_final_state(temp_dir: &TempDir) -> FinalState {
+    let db = Arc::new(RwLock::new(Box::new(MassaDB::new(MassaDBConfig {
+        path: temp_dir.path().to_path_buf(),
+        max_history_length: 10,
+        max_final_state_elements_size: 100_000_000,
+        max_versioning_elements_size: 100_000_000,
+        thread_count: THREAD_COUNT,
+        max_ledger_backups: 10,
+    }))
+        as Box<(dyn MassaDBController + 'static)>));
+    let config = FinalStateConfig {
+        thread_count: THREAD_COUNT,
+        initial_rolls_path: PathBuf::from_str("../massa-node/base_config/initial_rolls.json")
+            .unwrap(),
+        ..Default::default()
+    };
+    let ledger = FinalLedger::new(config.ledger_config.clone(), db.clone());
+    let mip_store = MipStore::try_from((
+        [],
+        MipStatsConfig {
+            block_count_considered: 10,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        },
+    ))
+    .unwrap();
+    let mut final_state = FinalState::new(
+        db,
+        config,
+        Box::new(ledger),
+        Box::new(MockSelectorControllerWrapper::new()),
+        mip_store,
+        true,
+    )
+    .unwrap();
+
+    let mut batch = DBBatch::new();
+    final_state.pos_state.create_initial_cycle(&mut batch);
+    let slot = final_state.db.read().get_change_id().unwrap();
+    final_state
+        .db
+        .write()
+        .write_batch(batch, DBBatch::new(), Some(slot));
+    final_state
+}
+
+/// generates an address from the given rng so that a seed fully reproduces a case
+fn gen_seeded_address(rng: &mut SmallRng) -> Address {
+    // keypair version 0 followed by the secret key bytes
+    let mut bytes = vec![0u8];
+    bytes.extend(rng.gen::<[u8; 32]>());
+    let keypair = KeyPair::from_bytes(&bytes).unwrap();
+    Address::from_public_key(&keypair.get_public_key())
+}
+
+/// generates random state changes touching only fresh addresses, messages and operations,
+/// so that two such changes always commute
+fn gen_independent_state_changes(rng: &mut SmallRng) -> StateChanges {
+    let mut changes = StateChanges::default();
+
+    let mut ledger_changes = LedgerChanges::default();
+    for _ in 0..rng.gen_range(0..10) {
+        let mut datastore = BTreeMap::new();
+        for _ in 0..rng.gen_range(0..5) {
+            datastore.insert(
+                rng.gen::<[u8; 8]>().to_vec(),
+                rng.gen::<[u8; 16]>().to_vec(),
+            );
+        }
+        let change = if rng.gen_bool(0.5) {
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: Amount::from_raw(rng.gen_range(0..1_000_000_000_000)),
+                bytecode: Bytecode(rng.gen::<[u8; 8]>().to_vec()),
+                datastore,
+            })
+        } else {
+            SetUpdateOrDelete::Update(LedgerEntryUpdate {
+                balance: SetOrKeep::Set(Amount::from_raw(rng.gen_range(0..1_000_000_000_000))),
+                bytecode: SetOrKeep::Keep,
+                datastore: datastore
+                    .into_iter()
+                    .map(|(key, value)| (key, SetOrDelete::Set(value)))
+                    .collect(),
+            })
+        };
+        ledger_changes.0.insert(gen_seeded_address(rng), change);
+    }
+    changes.ledger_changes = ledger_changes;
+
+    let mut async_pool_changes = AsyncPoolChanges::default();
+    for _ in 0..rng.gen_range(0..10) {
+        let message = AsyncMessage::new(
+            Slot::new(rng.gen_range(1..100), rng.gen_range(0..THREAD_COUNT)),
+            rng.gen(),
+            gen_seeded_address(rng),
+            gen_seeded_address(rng),
+            String::from("test"),
+            rng.gen_range(1..10_000_000),
+            Amount::from_raw(rng.gen_range(0..1_000_000_000)),
+            Amount::from_raw(rng.gen_range(0..1_000_000_000)),
+            Slot::new(200, 0),
+            Slot::new(300, 0),
+            rng.gen::<[u8; 4]>().to_vec(),
+            None,
+            None,
+        );
+        async_pool_changes
+            .0
+            .insert(message.compute_id(), SetUpdateOrDelete::Set(message));
+    }
+    changes.async_pool_changes = async_pool_changes;
+
+    let mut executed_ops_changes = ExecutedOpsChanges::default();
+    for _ in 0..rng.gen_range(0..10) {
+        executed_ops_changes.insert(
+            OperationId::new(Hash::compute_from(&rng.gen::<[u8; 32]>())),
+            (
+                rng.gen_bool(0.5),
+                Slot::new(rng.gen_range(10..100), rng.gen_range(0..THREAD_COUNT)),
+            ),
+        );
+    }
+    changes.executed_ops_changes = executed_ops_changes;
+
+    let mut deferred_credits = DeferredCredits::new();
+    for _ in 0..rng.gen_range(0..5) {
+        let mut credits = PreHashMap::default();
+        credits.insert(
+            gen_seeded_address(rng),
+            Amount::from_raw(rng.gen_range(1..1_000_000_000)),
+        );
+        deferred_credits.credits.insert(
+            Slot::new(rng.gen_range(10..100), rng.gen_range(0..THREAD_COUNT)),
+            credits,
+        );
+    }
+    changes.pos_changes = PoSChanges {
+        deferred_credits,
+        ..Default::default()
+    };
+
+    changes
+}
+
+#[test]
+#[serial]
+fn test_final_state_independent_changes_commute() {
+    parametric_test(
+        Duration::from_secs(10),
+        (),
+        vec![2_468_101_214, 11_235_813_213_455],
+        |_, rng| {
+            let changes_a = gen_independent_state_changes(rng);
+            let changes_b = gen_independent_state_changes(rng);
+
+            let temp_dir_1 = TempDir::new().unwrap();
+            let temp_dir_2 = TempDir::new().unwrap();
+            let mut final_state_1 = get_empty_final_state(&temp_dir_1);
+            let mut final_state_2 = get_empty_final_state(&temp_dir_2);
+            assert_final_states_equivalent(&final_state_1, &final_state_2);
+
+            let first_slot = final_state_1
+                .get_slot()
+                .get_next_slot(THREAD_COUNT)
+                .unwrap();
+            let second_slot = first_slot.get_next_slot(THREAD_COUNT).unwrap();
+
+            final_state_1.finalize(first_slot, changes_a.clone());
+            final_state_1.finalize(second_slot, changes_b.clone());
+
+            final_state_2.finalize(first_slot, changes_b);
+            final_state_2.finalize(second_slot, changes_a);
+
+            assert_final_states_equivalent(&final_state_1, &final_state_2);
+        },
+    );
+}
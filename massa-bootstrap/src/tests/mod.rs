@@ -2,6 +2,7 @@
 
 mod binders;
 mod config;
+mod final_state;
 mod messages;
 mod scenarios;
 pub(crate) mod tools;
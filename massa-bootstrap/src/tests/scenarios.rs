@@ -10,8 +10,10 @@ use massa_models::datastore::Datastore;
 use massa_models::{address::Address, node::NodeId};
 use massa_signature::KeyPair;
 use massa_test_framework::TestUniverse;
+use parking_lot::Mutex;
 use serial_test::serial;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[test]
 #[serial]
@@ -67,6 +69,47 @@ fn test_bootstrap_server() {
     client_universe.compare_database(server_universe.database.clone());
 }
 
+#[test]
+#[serial]
+fn test_bootstrap_import_progress() {
+    let port = 8072;
+    let server_keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&server_keypair.get_public_key());
+    let server_universe = BootstrapServerTestUniverseBuilder::new()
+        .set_port(port)
+        .set_keypair(&server_keypair)
+        .set_address_balance(&address, Amount::from_mantissa_scale(100, 0).unwrap())
+        .set_bytecode(&address, Bytecode(vec![0x00, 0x01, 0x02, 0x03]))
+        .build();
+    let mut client_universe = BootstrapClientTestUniverse::new(
+        BootstrapClientForeignControllers::new_with_mocks(),
+        BootstrapConfig::default(),
+    );
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let reports_clone = reports.clone();
+    client_universe.global_bootstrap_state.import_progress =
+        Some(Arc::new(move |imported_keys, total_estimate| {
+            reports_clone.lock().push((imported_keys, total_estimate));
+        }));
+    client_universe
+        .launch_bootstrap(port, NodeId::new(server_keypair.get_public_key()))
+        .unwrap();
+
+    let reports = reports.lock();
+    assert!(!reports.is_empty(), "import progress was never reported");
+    assert!(reports.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    assert!(reports
+        .iter()
+        .all(|(_, total_estimate)| total_estimate.is_none()));
+    let (imported_keys, _) = reports.last().unwrap();
+    assert!(*imported_keys > 0);
+    assert_eq!(
+        *imported_keys,
+        client_universe.global_bootstrap_state.imported_keys
+    );
+    drop(server_universe);
+}
+
 // Regression test for Issue #3932
 #[test]
 #[serial]
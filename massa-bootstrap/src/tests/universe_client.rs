@@ -5,7 +5,9 @@ use std::{
 
 use massa_db_exports::{MassaDBConfig, MassaDBController, ShareableMassaDBController};
 use massa_db_worker::MassaDB;
-use massa_final_state::MockFinalStateController;
+use massa_final_state::{
+    test_exports::assert_final_state_databases_equivalent, MockFinalStateController,
+};
 use massa_models::{
     config::{
         MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE, THREAD_COUNT,
@@ -51,7 +53,7 @@ impl TestUniverse for BootstrapClientTestUniverse {
 
     fn new(controllers: Self::ForeignControllers, config: Self::Config) -> Self {
         let global_bootstrap_state =
-            GlobalBootstrapState::new(controllers.final_state_controller.clone());
+            GlobalBootstrapState::new(controllers.final_state_controller.clone(), None);
         let disk_ledger_client = TempDir::new().expect("cannot create temp directory");
         let database = Arc::new(RwLock::new(Box::new(MassaDB::new(MassaDBConfig {
             path: disk_ledger_client.path().to_path_buf(),
@@ -77,6 +79,11 @@ impl TestUniverse for BootstrapClientTestUniverse {
             .write()
             .expect_get_database()
             .return_const(database.clone());
+        controllers
+            .final_state_controller
+            .write()
+            .expect_check_bootstrap_part()
+            .returning(|_, _, _| Ok(()));
         let client_mip_store = MipStore::try_from_db(
             database.clone(),
             MipStatsConfig {
@@ -143,9 +150,6 @@ impl BootstrapClientTestUniverse {
 
     //TODO: Add consensus blocks and peers
     pub fn compare_database(&self, other_database: ShareableMassaDBController) {
-        assert_eq!(
-            self.database.read().get_entire_database(),
-            other_database.read().get_entire_database()
-        );
+        assert_final_state_databases_equivalent(&self.database, &other_database);
     }
 }
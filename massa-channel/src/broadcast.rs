@@ -0,0 +1,230 @@
+//! Tokio broadcast sender wrapper that suppresses duplicate sends.
+//!
+//! The same object can be announced several times through different paths of the node.
+//! A `DedupBroadcaster` keeps a bounded LRU of the ids it recently sent and drops any object
+//! whose id is still in that window, so that subscribers receive each object once.
+//! Subscribers keep using plain `tokio::sync::broadcast::Receiver`s.
+//!
+//! Prometheus metrics exposed for each broadcaster :
+//! - total sent objects
+//! - total suppressed duplicates
+//! - total receiver lag events (an object was evicted before all receivers saw it)
+
+use std::sync::Arc;
+
+use massa_models::has_id::HasId;
+use parking_lot::Mutex;
+use prometheus::IntCounter;
+use schnellru::{ByLength, LruMap};
+use tokio::sync::broadcast::{self, error::SendError, Receiver, Sender};
+
+/// Snapshot of the metrics of a `DedupBroadcaster`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DedupBroadcasterStats {
+    /// objects sent to the receivers
+    pub sent: u64,
+    /// duplicates that were not sent
+    pub suppressed: u64,
+    /// sends that evicted an object not yet seen by every receiver
+    pub lag_events: u64,
+}
+
+/// Broadcast sender that does not send again an object whose id was sent recently
+#[derive(Clone)]
+pub struct DedupBroadcaster<T: HasId> {
+    sender: Sender<T>,
+    /// number of objects the underlying channel can hold
+    channel_capacity: usize,
+    /// ids of the recently sent objects, `None` if suppression is disabled
+    recent_ids: Option<Arc<Mutex<LruMap<T::Id, ()>>>>,
+    sent: IntCounter,
+    suppressed: IntCounter,
+    lag_events: IntCounter,
+}
+
+impl<T: HasId + Clone> DedupBroadcaster<T> {
+    /// Create a broadcaster over a new tokio broadcast channel
+    ///
+    /// Arguments:
+    /// - name: name of the broadcast objects, used as prefix of its metrics (e.g. `blocks_broadcast_total_sent`)
+    /// - channel_capacity: capacity of the underlying broadcast channel
+    /// - dedup_capacity: number of recently sent ids remembered to suppress duplicates,
+    ///   0 disables the suppression
+    pub fn new(name: String, channel_capacity: usize, dedup_capacity: u32) -> Self {
+        let sent = IntCounter::new(
+            format!("{}_broadcast_total_sent", name),
+            "Total objects sent",
+        )
+        .expect("Failed to create counter");
+        let suppressed = IntCounter::new(
+            format!("{}_broadcast_total_suppressed", name),
+            "Total duplicate objects suppressed",
+        )
+        .expect("Failed to create counter");
+        let lag_events = IntCounter::new(
+            format!("{}_broadcast_total_lag_events", name),
+            "Total objects evicted before being seen by every receiver",
+        )
+        .expect("Failed to create counter");
+
+        #[cfg(not(feature = "test-exports"))]
+        {
+            use tracing::debug;
+            for counter in [&sent, &suppressed, &lag_events] {
+                if let Err(e) = prometheus::register(Box::new(counter.clone())) {
+                    debug!("Failed to register broadcast counter for {} : {}", name, e);
+                }
+            }
+        }
+
+        DedupBroadcaster {
+            sender: broadcast::channel(channel_capacity).0,
+            channel_capacity,
+            recent_ids: (dedup_capacity > 0)
+                .then(|| Arc::new(Mutex::new(LruMap::new(ByLength::new(dedup_capacity))))),
+            sent,
+            suppressed,
+            lag_events,
+        }
+    }
+
+    /// Send an object to all the current receivers, unless an object with the same id
+    /// was sent within the dedup window.
+    ///
+    /// Returns the number of receivers the object was sent to, `Ok(0)` if it was suppressed,
+    /// or an error if there is no receiver.
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let Some(recent_ids) = &self.recent_ids else {
+            return self.send_unchecked(value);
+        };
+        let id = value.get_id();
+        // keep the lock while sending so that concurrent duplicates can't both get through
+        let mut recent_ids = recent_ids.lock();
+        if recent_ids.peek(&id).is_some() {
+            self.suppressed.inc();
+            return Ok(0);
+        }
+        let receiver_count = self.send_unchecked(value)?;
+        recent_ids.insert(id, ());
+        Ok(receiver_count)
+    }
+
+    fn send_unchecked(&self, value: T) -> Result<usize, SendError<T>> {
+        if self.sender.len() >= self.channel_capacity {
+            self.lag_events.inc();
+        }
+        let receiver_count = self.sender.send(value)?;
+        self.sent.inc();
+        Ok(receiver_count)
+    }
+
+    /// Create a new receiver that will get all the objects sent after this call
+    pub fn subscribe(&self) -> Receiver<T> {
+        self.sender.subscribe()
+    }
+
+    /// Number of active receivers
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Get the current metrics of the broadcaster
+    pub fn get_stats(&self) -> DedupBroadcasterStats {
+        DedupBroadcasterStats {
+            sent: self.sent.get(),
+            suppressed: self.suppressed.get(),
+            lag_events: self.lag_events.get(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Object(u64);
+
+    impl HasId for Object {
+        type Id = u64;
+
+        fn get_id(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_duplicate_from_different_entry_points_is_received_once() {
+        let broadcaster = DedupBroadcaster::new("test_dedup".to_string(), 10, 10);
+        let mut receiver = broadcaster.subscribe();
+
+        // two holders of the broadcaster announce the same object
+        let header_path = broadcaster.clone();
+        let filled_block_path = broadcaster.clone();
+        let object = Object(1);
+        assert_eq!(header_path.send(object.clone()).unwrap(), 1);
+        assert_eq!(filled_block_path.send(object.clone()).unwrap(), 0);
+
+        assert_eq!(receiver.try_recv().unwrap(), object);
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(
+            broadcaster.get_stats(),
+            DedupBroadcasterStats {
+                sent: 1,
+                suppressed: 1,
+                lag_events: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_distinct_objects_are_not_suppressed() {
+        let broadcaster = DedupBroadcaster::new("test_distinct".to_string(), 10, 10);
+        let mut receiver = broadcaster.subscribe();
+
+        for id in 0..5 {
+            broadcaster.send(Object(id)).unwrap();
+        }
+        for id in 0..5 {
+            assert_eq!(receiver.try_recv().unwrap(), Object(id));
+        }
+        assert_eq!(broadcaster.get_stats().suppressed, 0);
+    }
+
+    #[test]
+    fn test_duplicate_outside_window_is_sent_again() {
+        let broadcaster = DedupBroadcaster::new("test_window".to_string(), 10, 2);
+        let mut receiver = broadcaster.subscribe();
+
+        for id in [0, 1, 2, 0] {
+            broadcaster.send(Object(id)).unwrap();
+        }
+        let received: Vec<u64> = std::iter::from_fn(|| receiver.try_recv().ok())
+            .map(|object| object.0)
+            .collect();
+        assert_eq!(received, vec![0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_zero_dedup_capacity_disables_suppression() {
+        let broadcaster = DedupBroadcaster::new("test_disabled".to_string(), 10, 0);
+        let mut receiver = broadcaster.subscribe();
+
+        broadcaster.send(Object(1)).unwrap();
+        broadcaster.send(Object(1)).unwrap();
+        assert_eq!(receiver.try_recv().unwrap(), Object(1));
+        assert_eq!(receiver.try_recv().unwrap(), Object(1));
+        assert_eq!(broadcaster.get_stats().suppressed, 0);
+    }
+
+    #[test]
+    fn test_lag_events() {
+        let broadcaster = DedupBroadcaster::new("test_lag".to_string(), 2, 10);
+        let _receiver = broadcaster.subscribe();
+
+        for id in 0..4 {
+            broadcaster.send(Object(id)).unwrap();
+        }
+        assert_eq!(broadcaster.get_stats().lag_events, 2);
+    }
+}
@@ -19,6 +19,7 @@ use std::sync::Arc;
 use receiver::MassaReceiver;
 use sender::MassaSender;
 
+pub mod broadcast;
 pub mod receiver;
 pub mod sender;
 
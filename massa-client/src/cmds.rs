@@ -22,7 +22,7 @@ use massa_models::{
     operation::{Operation, OperationId, OperationType},
     slot::Slot,
 };
-use massa_sdk::Client;
+use massa_sdk::{Client, MassaRpc};
 use massa_signature::KeyPair;
 use massa_wallet::Wallet;
 
@@ -187,7 +187,7 @@ pub enum Command {
     #[strum(
         ascii_case_insensitive,
         props(
-            args = "start=slot_period,slot_thread end=slot_period,slot_thread emitter_address=Address caller_address=Address operation_id=OperationId is_final=bool is_error=bool",
+            args = "start=slot_period,slot_thread end=slot_period,slot_thread emitter_address=Address caller_address=Address operation_id=OperationId is_final=bool is_error=bool data=Substring (repeatable, all must match)",
             pwd_not_needed = "true"
         ),
         message = "show events emitted by smart contracts with various filters"
@@ -543,7 +543,9 @@ impl Command {
                 // get address signature
                 if let Some(addr_sig) = wallet.sign_message(&addr, msg.clone()) {
                     // get node signature
-                    match client.private.node_sign_message(msg).await {
+                    #[allow(deprecated)]
+                    let node_sig_res = client.private.node_sign_message(msg).await;
+                    match node_sig_res {
                         // print concatenation
                         Ok(node_sig) => {
                             if !json {
@@ -631,7 +633,13 @@ impl Command {
                     "is_error",
                 ];
                 let mut p: HashMap<&str, &str> = HashMap::new();
+                let mut data_contains = Vec::new();
                 for v in parameters {
+                    // the data substrings may contain '='
+                    if let Some(("data", pattern)) = v.split_once('=') {
+                        data_contains.push(pattern.to_string());
+                        continue;
+                    }
                     let s: Vec<&str> = v.split('=').collect();
                     if s.len() == 2 && p_list.contains(&s[0]) {
                         p.insert(s[0], s[1]);
@@ -647,6 +655,7 @@ impl Command {
                     original_operation_id: parse_key_value(&p, p_list[4])?,
                     is_final: parse_key_value(&p, p_list[5])?,
                     is_error: parse_key_value(&p, p_list[6])?,
+                    data_contains,
                 };
                 match client.public.get_filtered_sc_output_event(filter).await {
                     Ok(events) => Ok(Box::new(events)),
@@ -1126,6 +1135,9 @@ impl Command {
                         address,
                         operation_datastore: None, // TODO - #3072
                         fee,
+                        profile: false,
+                        rng_seed: None,
+                        include_trace: false,
                     })
                     .await
                 {
@@ -1162,6 +1174,9 @@ impl Command {
                         max_gas,
                         coins,
                         fee,
+                        profile: false,
+                        rng_seed: None,
+                        include_trace: false,
                     })
                     .await
                 {
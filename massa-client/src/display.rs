@@ -11,7 +11,7 @@ use massa_api_exports::{
 use massa_models::composite::PubkeySig;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
-use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
+use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats, NetworkTrafficStats};
 use massa_models::{address::Address, config::CompactConfig, operation::OperationId};
 use massa_signature::{KeyPair, PublicKey};
 use massa_wallet::Wallet;
@@ -247,6 +247,16 @@ impl Output for NodeStatus {
 
         self.consensus_stats.pretty_print();
 
+        println!("Clique count: {}", Style::Protocol.style(self.clique_count));
+        match self.fitness_gap_to_second_clique {
+            Some(gap) => println!(
+                "Fitness gap to second clique: {}",
+                Style::Protocol.style(gap)
+            ),
+            None => println!("{}", Style::Unknown.style("No alternative clique")),
+        }
+        println!();
+
         println!("Pool stats:");
         println!(
             "\tOperations count: {}",
@@ -259,6 +269,9 @@ impl Output for NodeStatus {
         println!();
 
         self.network_stats.pretty_print();
+        if let Some(network_traffic) = &self.network_traffic {
+            network_traffic.pretty_print();
+        }
         self.execution_stats.pretty_print();
 
         if !self.connected_nodes.is_empty() {
@@ -334,6 +347,42 @@ impl Output for NetworkStats {
     }
 }
 
+impl Output for NetworkTrafficStats {
+    fn pretty_print(&self) {
+        println!("Network traffic:");
+        println!(
+            "\tIn connections: {}",
+            Style::Protocol.style(self.in_connection_count)
+        );
+        println!(
+            "\tOut connections: {}",
+            Style::Protocol.style(self.out_connection_count)
+        );
+        println!(
+            "\tBanned peers: {}",
+            Style::Bad.style(self.banned_peer_count)
+        );
+        println!(
+            "\tHandshakes: {} ({} failed)",
+            Style::Protocol.style(self.handshake_count),
+            Style::Bad.style(self.handshake_failure_count)
+        );
+        for (category, traffic) in [
+            ("Blocks", &self.blocks),
+            ("Operations", &self.operations),
+            ("Endorsements", &self.endorsements),
+            ("Peers", &self.peers),
+        ] {
+            println!(
+                "\t{}: {} bytes sent, {} bytes received",
+                category,
+                Style::Protocol.style(traffic.bytes_sent),
+                Style::Protocol.style(traffic.bytes_received)
+            );
+        }
+    }
+}
+
 impl Output for CompactConfig {
     fn pretty_print(&self) {
         println!("Config:");
@@ -502,6 +551,9 @@ impl Output for Vec<OperationInfo> {
             if info.in_pool {
                 print!(", {}", Style::Pending.style("in pool"));
             }
+            if info.is_expired {
+                print!(", {}", Style::Bad.style("expired"));
+            }
             if let Some(f) = info.is_operation_final {
                 print!(
                     ", operation is {}",
@@ -104,6 +104,7 @@ async fn run(args: Args) -> Result<()> {
     let client_config = ClientConfig {
         max_request_body_size: SETTINGS.client.max_request_body_size,
         request_timeout: SETTINGS.client.request_timeout,
+        connect_timeout: SETTINGS.client.connect_timeout,
         max_concurrent_requests: SETTINGS.client.max_concurrent_requests,
         certificate_store: SETTINGS.client.certificate_store.clone(),
         id_kind: SETTINGS.client.id_kind.clone(),
@@ -114,6 +115,7 @@ async fn run(args: Args) -> Result<()> {
     let http_config = HttpConfig {
         client_config,
         enabled: SETTINGS.client.http.enabled,
+        proxy_url: SETTINGS.client.http.proxy_url.clone(),
     };
 
     // TODO: move settings loading in another crate ... see #1277
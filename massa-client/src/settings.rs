@@ -36,6 +36,7 @@ pub struct DefaultNode {
 pub struct ClientSettings {
     pub max_request_body_size: u32,
     pub request_timeout: MassaTime,
+    pub connect_timeout: MassaTime,
     pub max_concurrent_requests: usize,
     pub certificate_store: String,
     pub id_kind: String,
@@ -50,6 +51,7 @@ pub struct ClientSettings {
 #[derive(Debug, Deserialize, Clone)]
 pub struct HttpSettings {
     pub enabled: bool,
+    pub proxy_url: Option<String>,
 }
 
 #[cfg(test)]
@@ -27,3 +27,54 @@ pub struct BlockGraphExport {
     /// List of maximal cliques of compatible blocks.
     pub max_cliques: Vec<Clique>,
 }
+
+/// Summary of the block graph, listing a capped number of the blocks being waited for or discarded
+#[derive(Debug, Clone, Default)]
+pub struct BlockGraphSummary {
+    /// Number of blocks not processed yet.
+    pub incoming_block_count: usize,
+    /// Number of blocks waiting for their slot.
+    pub waiting_for_slot_block_count: usize,
+    /// Number of blocks waiting for their dependencies.
+    pub waiting_for_dependencies_block_count: usize,
+    /// Number of active blocks.
+    pub active_block_count: usize,
+    /// Number of discarded blocks kept in cache.
+    pub discarded_block_count: usize,
+    /// Best parents hashes in each thread.
+    pub best_parents: Vec<(BlockId, u64)>,
+    /// Latest final period and block hash in each thread.
+    pub latest_final_blocks_periods: Vec<(BlockId, u64)>,
+    /// Number of maximal cliques.
+    pub clique_count: usize,
+    /// Blocks waiting for their dependencies, the oldest first.
+    pub waiting_for_dependencies: Vec<WaitingBlockSummary>,
+    /// Discarded blocks, the most recent first.
+    pub discarded_blocks: Vec<DiscardedBlockSummary>,
+}
+
+/// Block waiting for its dependencies, with the blocks it misses
+#[derive(Debug, Clone)]
+pub struct WaitingBlockSummary {
+    /// Id of the block.
+    pub block_id: BlockId,
+    /// Slot of the block.
+    pub slot: Slot,
+    /// Blocks not known yet, including the block itself if only its header was received.
+    pub missing_dependencies: Vec<BlockId>,
+}
+
+/// Discarded block, with the reason why it was discarded
+#[derive(Debug, Clone)]
+pub struct DiscardedBlockSummary {
+    /// Id of the block.
+    pub block_id: BlockId,
+    /// Slot of the block.
+    pub slot: Slot,
+    /// Address of the creator of the block.
+    pub creator: Address,
+    /// Ids of the parents of the block.
+    pub parents: Vec<BlockId>,
+    /// Why the block was discarded.
+    pub reason: DiscardReason,
+}
@@ -1,3 +1,4 @@
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_channel::sender::MassaSender;
 use massa_execution_exports::ExecutionController;
 use massa_models::block::{FilledBlock, SecureShareBlock};
@@ -31,9 +32,9 @@ pub struct ConsensusChannels {
 #[derive(Clone)]
 pub struct ConsensusBroadcasts {
     /// Channel used for Websocket broadcast (if enabled) of new blocks being integrated in the graph
-    pub block_sender: tokio::sync::broadcast::Sender<SecureShareBlock>,
+    pub block_sender: DedupBroadcaster<SecureShareBlock>,
     /// Channel used for Websocket broadcast (if enabled) of new block headers being integrated in the graph
     pub block_header_sender: tokio::sync::broadcast::Sender<SecureShare<BlockHeader, BlockId>>,
     /// Channel use by Websocket (if they are enable) to broadcast a new block integrated
-    pub filled_block_sender: tokio::sync::broadcast::Sender<FilledBlock>,
+    pub filled_block_sender: DedupBroadcaster<FilledBlock>,
 }
@@ -0,0 +1,54 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Processing time of the commands executed by the consensus worker.
+//!
+//! When the metrics are enabled, the worker measures for each command the time spent waiting for
+//! the graph lock and the time spent processing it, and keeps the timings of the last
+//! `ConsensusConfig::max_command_timings` commands.
+
+use massa_models::{block_id::BlockId, slot::Slot};
+use serde::{Deserialize, Serialize};
+
+/// Kind of a command executed by the consensus worker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConsensusCommandKind {
+    /// registration of a block
+    RegisterBlock,
+    /// registration of several blocks with a single graph update
+    RegisterBlocksBatch,
+    /// registration of a block header
+    RegisterBlockHeader,
+    /// invalidation of a block
+    MarkInvalidBlock,
+}
+
+impl ConsensusCommandKind {
+    /// Name of the command, used as metrics label
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConsensusCommandKind::RegisterBlock => "register_block",
+            ConsensusCommandKind::RegisterBlocksBatch => "register_blocks_batch",
+            ConsensusCommandKind::RegisterBlockHeader => "register_block_header",
+            ConsensusCommandKind::MarkInvalidBlock => "mark_invalid_block",
+        }
+    }
+}
+
+/// Timing of a command executed by the consensus worker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsensusCommandTiming {
+    /// kind of the command
+    pub command: ConsensusCommandKind,
+    /// id of the block concerned by the command, `None` for a batch
+    pub block_id: Option<BlockId>,
+    /// number of blocks concerned by the command
+    pub block_count: usize,
+    /// highest slot of the blocks concerned by the command, `None` for an empty batch
+    pub slot: Option<Slot>,
+    /// latest slot reached by the worker when it executed the command
+    pub current_slot: Option<Slot>,
+    /// time spent waiting for the graph lock, in microseconds
+    pub lock_wait_micros: u64,
+    /// time spent processing the command once the lock was acquired, in microseconds
+    pub processing_micros: u64,
+}
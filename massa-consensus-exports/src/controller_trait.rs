@@ -1,10 +1,16 @@
-use crate::block_graph_export::BlockGraphExport;
+use crate::block_graph_export::{BlockGraphExport, BlockGraphSummary};
+use crate::command_timing::ConsensusCommandTiming;
 use crate::{bootstrapable_graph::BootstrapableGraph, error::ConsensusError};
 use massa_models::prehash::PreHashSet;
 use massa_models::streaming_step::StreamingStep;
 use massa_models::{
-    block::BlockGraphStatus, block_header::BlockHeader, block_id::BlockId, clique::Clique,
-    secure_share::SecureShare, slot::Slot, stats::ConsensusStats,
+    block::{BlockGraphStatus, ConfirmationInfo},
+    block_header::BlockHeader,
+    block_id::BlockId,
+    clique::{Clique, CliqueInfo},
+    secure_share::SecureShare,
+    slot::Slot,
+    stats::ConsensusStats,
 };
 use massa_storage::Storage;
 
@@ -28,6 +34,15 @@ pub trait ConsensusController: Send + Sync {
         end_slot: Option<Slot>,
     ) -> Result<BlockGraphExport, ConsensusError>;
 
+    /// Get a summary of the graph, without copying the blocks
+    ///
+    /// # Arguments
+    /// * `max_items`: the maximum number of waiting blocks and of discarded blocks listed
+    ///
+    /// # Returns
+    /// The block counts by status, and the blocks waiting for dependencies and discarded
+    fn get_block_graph_summary(&self, max_items: usize) -> BlockGraphSummary;
+
     /// Get statuses of a list of blocks
     ///
     /// # Arguments
@@ -37,12 +52,27 @@ pub trait ConsensusController: Send + Sync {
     /// The statuses of the blocks sorted by the order of the input list
     fn get_block_statuses(&self, ids: &[BlockId]) -> Vec<BlockGraphStatus>;
 
+    /// Get the confirmation of a list of blocks
+    ///
+    /// # Arguments
+    /// * `ids`: the list of block ids to get the confirmation of
+    ///
+    /// # Returns
+    /// The confirmations of the blocks sorted by the order of the input list, None for the blocks that are not active
+    fn get_confirmation_info(&self, ids: &[BlockId]) -> Vec<Option<ConfirmationInfo>>;
+
     /// Get all the cliques of the graph
     ///
     /// # Returns
     /// The list of cliques
     fn get_cliques(&self) -> Vec<Clique>;
 
+    /// Get all the cliques of the graph with their block count and tip blocks
+    ///
+    /// # Returns
+    /// The list of cliques information
+    fn get_cliques_info(&self) -> Vec<CliqueInfo>;
+
     /// Get a part of the graph to send to a node for it to setup its graph.
     /// Used for bootstrap.
     ///
@@ -98,6 +128,10 @@ pub trait ConsensusController: Send + Sync {
     /// The block id of the latest block in the thread of the given slot and before this slot
     fn get_latest_blockclique_block_at_slot(&self, slot: Slot) -> BlockId;
 
+    /// Get the timings of the last commands executed by the graph worker, oldest first.
+    /// They are only recorded when the metrics are enabled.
+    fn get_command_timings(&self) -> Vec<ConsensusCommandTiming>;
+
     /// Register a block in the graph
     ///
     /// # Arguments
@@ -107,6 +141,15 @@ pub trait ConsensusController: Send + Sync {
     /// * `created`: is the block created by our node ?
     fn register_block(&self, block_id: BlockId, slot: Slot, block_storage: Storage, created: bool);
 
+    /// Register several blocks in the graph at once.
+    /// The graph is updated a single time after all the blocks are registered,
+    /// which is much cheaper than registering them one by one when catching up.
+    ///
+    /// # Arguments
+    /// * `blocks`: for each block, its id, its slot, the storage that contains all its objects
+    ///   and whether it was created by our node
+    fn register_blocks_batch(&self, blocks: Vec<(BlockId, Slot, Storage, bool)>);
+
     /// Register a block header in the graph
     ///
     /// # Arguments
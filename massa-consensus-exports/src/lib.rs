@@ -8,6 +8,7 @@ mod settings;
 pub mod block_graph_export;
 pub mod block_status;
 pub mod bootstrapable_graph;
+pub mod command_timing;
 pub mod error;
 pub mod events;
 pub mod export_active_block;
@@ -20,6 +20,12 @@ pub struct ConsensusConfig {
     pub max_dependency_blocks: usize,
     /// old blocks are pruned every `block_db_prune_interval`
     pub block_db_prune_interval: MassaTime,
+    /// max number of queued block registrations processed together with a single graph update
+    pub max_block_registration_batch_size: usize,
+    /// number of recently executed commands whose timing is kept, when the metrics are enabled
+    pub max_command_timings: usize,
+    /// a warning is logged for the commands taking longer than this to execute, when the metrics are enabled
+    pub slow_command_threshold: MassaTime,
     /// Max gas per block for the execution configuration
     pub max_gas_per_block: u64,
     /// Threshold for fitness.
@@ -50,6 +56,8 @@ pub struct ConsensusConfig {
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// number of recently broadcast block ids remembered to avoid broadcasting a block twice
+    pub broadcast_dedup_capacity: u32,
     /// last start period
     pub last_start_period: u64,
     /// chain id
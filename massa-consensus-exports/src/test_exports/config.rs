@@ -20,6 +20,9 @@ impl Default for ConsensusConfig {
             max_future_processing_blocks: 100,
             max_dependency_blocks: 2048,
             block_db_prune_interval: MassaTime::from_millis(5000),
+            max_block_registration_batch_size: 256,
+            max_command_timings: 1000,
+            slow_command_threshold: MassaTime::from_millis(500),
             max_gas_per_block: MAX_GAS_PER_BLOCK,
             delta_f0: DELTA_F0,
             operation_validity_periods: OPERATION_VALIDITY_PERIODS,
@@ -35,6 +38,7 @@ impl Default for ConsensusConfig {
             broadcast_blocks_headers_channel_capacity: 128,
             broadcast_blocks_channel_capacity: 128,
             broadcast_filled_blocks_channel_capacity: 128,
+            broadcast_dedup_capacity: 1024,
             last_start_period: 0,
             chain_id: *CHAINID,
         }
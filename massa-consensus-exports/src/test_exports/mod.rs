@@ -1,3 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 mod config;
+mod recording;
+
+pub use recording::{RecordedConsensusCall, RecordingConsensusController};
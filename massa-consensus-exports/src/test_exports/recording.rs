@@ -0,0 +1,269 @@
+use std::sync::{Arc, Mutex};
+
+use massa_models::{
+    block::{BlockGraphStatus, ConfirmationInfo},
+    block_header::BlockHeader,
+    block_id::BlockId,
+    clique::{Clique, CliqueInfo},
+    prehash::PreHashSet,
+    secure_share::SecureShare,
+    slot::Slot,
+    stats::ConsensusStats,
+    streaming_step::StreamingStep,
+};
+use massa_storage::Storage;
+
+use crate::{
+    block_graph_export::BlockGraphExport, bootstrapable_graph::BootstrapableGraph,
+    command_timing::ConsensusCommandTiming, error::ConsensusError, ConsensusController,
+};
+
+/// Call changing the block graph, recorded by a [`RecordingConsensusController`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordedConsensusCall {
+    /// `register_block`
+    RegisterBlock {
+        /// id of the registered block
+        block_id: BlockId,
+        /// slot of the registered block
+        slot: Slot,
+        /// whether the block was created by our node
+        created: bool,
+    },
+    /// `register_blocks_batch`
+    RegisterBlocksBatch {
+        /// id, slot and `created` flag of each block of the batch
+        blocks: Vec<(BlockId, Slot, bool)>,
+    },
+    /// `register_block_header`
+    RegisterBlockHeader {
+        /// id of the block whose header was registered
+        block_id: BlockId,
+    },
+    /// `mark_invalid_block`
+    MarkInvalidBlock {
+        /// id of the invalid block
+        block_id: BlockId,
+    },
+}
+
+/// Consensus controller recording the calls that change the block graph before forwarding every
+/// call to an inner controller, usually a [`MockConsensusController`](crate::MockConsensusController).
+///
+/// The clones returned by `clone_box` share the recorded calls and the inner controller, so the
+/// calls made by components cloning their controller are recorded as well.
+#[derive(Clone)]
+pub struct RecordingConsensusController {
+    inner: Arc<dyn ConsensusController>,
+    calls: Arc<Mutex<Vec<RecordedConsensusCall>>>,
+}
+
+impl RecordingConsensusController {
+    /// Record the calls made to `inner`
+    pub fn new(inner: Box<dyn ConsensusController>) -> Self {
+        RecordingConsensusController {
+            inner: Arc::from(inner),
+            calls: Default::default(),
+        }
+    }
+
+    /// Calls recorded so far, in order
+    pub fn calls(&self) -> Vec<RecordedConsensusCall> {
+        self.calls
+            .lock()
+            .expect("recorded calls lock poisoned")
+            .clone()
+    }
+
+    /// Return and forget the calls recorded so far
+    pub fn take_calls(&self) -> Vec<RecordedConsensusCall> {
+        std::mem::take(&mut *self.calls.lock().expect("recorded calls lock poisoned"))
+    }
+
+    /// Ids of the blocks registered so far, alone or in a batch, in order
+    pub fn registered_blocks(&self) -> Vec<BlockId> {
+        self.calls()
+            .into_iter()
+            .flat_map(|call| match call {
+                RecordedConsensusCall::RegisterBlock { block_id, .. } => vec![block_id],
+                RecordedConsensusCall::RegisterBlocksBatch { blocks } => {
+                    blocks.into_iter().map(|(block_id, ..)| block_id).collect()
+                }
+                _ => vec![],
+            })
+            .collect()
+    }
+
+    fn record(&self, call: RecordedConsensusCall) {
+        self.calls
+            .lock()
+            .expect("recorded calls lock poisoned")
+            .push(call);
+    }
+}
+
+impl ConsensusController for RecordingConsensusController {
+    fn get_block_graph_status(
+        &self,
+        start_slot: Option<Slot>,
+        end_slot: Option<Slot>,
+    ) -> Result<BlockGraphExport, ConsensusError> {
+        self.inner.get_block_graph_status(start_slot, end_slot)
+    }
+
+    fn get_block_statuses(&self, ids: &[BlockId]) -> Vec<BlockGraphStatus> {
+        self.inner.get_block_statuses(ids)
+    }
+
+    fn get_confirmation_info(&self, ids: &[BlockId]) -> Vec<Option<ConfirmationInfo>> {
+        self.inner.get_confirmation_info(ids)
+    }
+
+    fn get_cliques(&self) -> Vec<Clique> {
+        self.inner.get_cliques()
+    }
+
+    fn get_cliques_info(&self) -> Vec<CliqueInfo> {
+        self.inner.get_cliques_info()
+    }
+
+    fn get_bootstrap_part(
+        &self,
+        cursor: StreamingStep<PreHashSet<BlockId>>,
+        execution_cursor: StreamingStep<Slot>,
+    ) -> Result<
+        (
+            BootstrapableGraph,
+            PreHashSet<BlockId>,
+            StreamingStep<PreHashSet<BlockId>>,
+        ),
+        ConsensusError,
+    > {
+        self.inner.get_bootstrap_part(cursor, execution_cursor)
+    }
+
+    fn get_stats(&self) -> Result<ConsensusStats, ConsensusError> {
+        self.inner.get_stats()
+    }
+
+    fn get_best_parents(&self) -> Vec<(BlockId, u64)> {
+        self.inner.get_best_parents()
+    }
+
+    fn get_blockclique_block_at_slot(&self, slot: Slot) -> Option<BlockId> {
+        self.inner.get_blockclique_block_at_slot(slot)
+    }
+
+    fn get_latest_blockclique_block_at_slot(&self, slot: Slot) -> BlockId {
+        self.inner.get_latest_blockclique_block_at_slot(slot)
+    }
+
+    fn get_command_timings(&self) -> Vec<ConsensusCommandTiming> {
+        self.inner.get_command_timings()
+    }
+
+    fn register_block(&self, block_id: BlockId, slot: Slot, block_storage: Storage, created: bool) {
+        self.record(RecordedConsensusCall::RegisterBlock {
+            block_id,
+            slot,
+            created,
+        });
+        self.inner
+            .register_block(block_id, slot, block_storage, created)
+    }
+
+    fn register_blocks_batch(&self, blocks: Vec<(BlockId, Slot, Storage, bool)>) {
+        self.record(RecordedConsensusCall::RegisterBlocksBatch {
+            blocks: blocks
+                .iter()
+                .map(|(block_id, slot, _, created)| (*block_id, *slot, *created))
+                .collect(),
+        });
+        self.inner.register_blocks_batch(blocks)
+    }
+
+    fn register_block_header(&self, block_id: BlockId, header: SecureShare<BlockHeader, BlockId>) {
+        self.record(RecordedConsensusCall::RegisterBlockHeader { block_id });
+        self.inner.register_block_header(block_id, header)
+    }
+
+    fn mark_invalid_block(&self, block_id: BlockId, header: SecureShare<BlockHeader, BlockId>) {
+        self.record(RecordedConsensusCall::MarkInvalidBlock { block_id });
+        self.inner.mark_invalid_block(block_id, header)
+    }
+
+    fn clone_box(&self) -> Box<dyn ConsensusController> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockConsensusController;
+    use massa_hash::Hash;
+
+    fn block_id(seed: &str) -> BlockId {
+        BlockId::generate_from_hash(Hash::compute_from(seed.as_bytes()))
+    }
+
+    #[test]
+    fn test_recording_consensus_controller() {
+        let mut inner = MockConsensusController::new();
+        inner.expect_register_block().times(1).return_const(());
+        inner
+            .expect_register_blocks_batch()
+            .times(1)
+            .return_const(());
+        inner
+            .expect_get_blockclique_block_at_slot()
+            .times(1)
+            .returning(|_| None);
+        let controller = RecordingConsensusController::new(Box::new(inner));
+
+        // calls made through a clone are recorded as well
+        let clone = controller.clone_box();
+        clone.register_block(block_id("a"), Slot::new(1, 0), Storage::create_root(), true);
+        controller.register_blocks_batch(vec![
+            (
+                block_id("b"),
+                Slot::new(1, 1),
+                Storage::create_root(),
+                false,
+            ),
+            (
+                block_id("c"),
+                Slot::new(2, 0),
+                Storage::create_root(),
+                false,
+            ),
+        ]);
+        // queries are forwarded but not recorded
+        assert!(clone
+            .get_blockclique_block_at_slot(Slot::new(1, 0))
+            .is_none());
+
+        assert_eq!(
+            controller.calls(),
+            vec![
+                RecordedConsensusCall::RegisterBlock {
+                    block_id: block_id("a"),
+                    slot: Slot::new(1, 0),
+                    created: true,
+                },
+                RecordedConsensusCall::RegisterBlocksBatch {
+                    blocks: vec![
+                        (block_id("b"), Slot::new(1, 1), false),
+                        (block_id("c"), Slot::new(2, 0), false),
+                    ],
+                },
+            ]
+        );
+        assert_eq!(
+            controller.registered_blocks(),
+            vec![block_id("a"), block_id("b"), block_id("c")]
+        );
+        assert_eq!(controller.take_calls().len(), 2);
+        assert!(controller.calls().is_empty());
+    }
+}
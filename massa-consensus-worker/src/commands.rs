@@ -7,6 +7,7 @@ use massa_storage::Storage;
 #[derive(Clone)]
 pub enum ConsensusCommand {
     RegisterBlock(BlockId, Slot, Storage, bool),
+    RegisterBlocksBatch(Vec<(BlockId, Slot, Storage, bool)>),
     RegisterBlockHeader(BlockId, SecureShare<BlockHeader, BlockId>),
     MarkInvalidBlock(BlockId, SecureShare<BlockHeader, BlockId>),
 }
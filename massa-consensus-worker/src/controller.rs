@@ -1,29 +1,41 @@
 use massa_channel::sender::MassaSender;
 use massa_consensus_exports::ConsensusBroadcasts;
 use massa_consensus_exports::{
-    block_graph_export::BlockGraphExport, block_status::BlockStatus,
-    bootstrapable_graph::BootstrapableGraph, error::ConsensusError,
-    export_active_block::ExportActiveBlock, ConsensusController,
+    block_graph_export::{BlockGraphExport, BlockGraphSummary},
+    block_status::BlockStatus,
+    bootstrapable_graph::BootstrapableGraph,
+    command_timing::ConsensusCommandTiming,
+    error::ConsensusError,
+    export_active_block::ExportActiveBlock,
+    ConsensusController,
 };
 use massa_models::{
-    block::{BlockGraphStatus, FilledBlock},
+    block::{BlockGraphStatus, ConfirmationInfo, FilledBlock},
     block_header::BlockHeader,
     block_id::BlockId,
-    clique::Clique,
+    clique::{Clique, CliqueInfo},
     operation::{Operation, OperationId},
-    prehash::PreHashSet,
+    prehash::{PreHashMap, PreHashSet},
     secure_share::SecureShare,
     slot::Slot,
     stats::ConsensusStats,
     streaming_step::StreamingStep,
 };
 use massa_storage::Storage;
-use parking_lot::RwLock;
-use std::sync::Arc;
+use parking_lot::{Mutex, RwLock};
+use std::{collections::VecDeque, sync::Arc};
 use tracing::{debug, trace, warn};
 
 use crate::{commands::ConsensusCommand, state::ConsensusState};
 
+/// Confirmations computed since the last change of the graph
+#[derive(Default)]
+struct ConfirmationCache {
+    /// graph revision the confirmations were computed at
+    graph_revision: u64,
+    confirmations: PreHashMap<BlockId, ConfirmationInfo>,
+}
+
 /// The retrieval of data is made using a shared state and modifications are asked by sending message to a channel.
 /// This is done mostly to be able to:
 ///
@@ -38,6 +50,8 @@ pub struct ConsensusControllerImpl {
     shared_state: Arc<RwLock<ConsensusState>>,
     bootstrap_part_size: u64,
     broadcast_enabled: bool,
+    confirmation_cache: Arc<Mutex<ConfirmationCache>>,
+    command_timings: Arc<Mutex<VecDeque<ConsensusCommandTiming>>>,
 }
 
 impl ConsensusControllerImpl {
@@ -47,6 +61,7 @@ impl ConsensusControllerImpl {
         shared_state: Arc<RwLock<ConsensusState>>,
         bootstrap_part_size: u64,
         broadcast_enabled: bool,
+        command_timings: Arc<Mutex<VecDeque<ConsensusCommandTiming>>>,
     ) -> Self {
         Self {
             command_sender,
@@ -54,8 +69,55 @@ impl ConsensusControllerImpl {
             shared_state,
             bootstrap_part_size,
             broadcast_enabled,
+            confirmation_cache: Default::default(),
+            command_timings,
         }
     }
+
+    /// Send a registered block to the block and filled block broadcast channels
+    fn broadcast_block(&self, block_id: BlockId, block_storage: &Storage) {
+        if let Some(verifiable_block) = block_storage.read_blocks().get(&block_id) {
+            let operations: Vec<(OperationId, Option<SecureShare<Operation, OperationId>>)> =
+                verifiable_block
+                    .content
+                    .operations
+                    .iter()
+                    .map(|operation_id| {
+                        match block_storage.read_operations().get(operation_id).cloned() {
+                            Some(verifiable_operation) => {
+                                (*operation_id, Some(verifiable_operation))
+                            }
+                            None => (*operation_id, None),
+                        }
+                    })
+                    .collect();
+
+            if let Err(err) = self.broadcasts.block_sender.send(verifiable_block.clone()) {
+                trace!(
+                    "error, failed to broadcast block with id {} due to: {}",
+                    block_id,
+                    err
+                );
+            }
+
+            if let Err(err) = self.broadcasts.filled_block_sender.send(FilledBlock {
+                header: verifiable_block.content.header.clone(),
+                operations,
+                locally_produced: false,
+            }) {
+                trace!(
+                    "error, failed to broadcast filled block with id {} due to: {}",
+                    block_id,
+                    err
+                );
+            }
+        } else {
+            debug!(
+                "error, no broadcast event sent, block with id {} not found",
+                block_id
+            );
+        };
+    }
 }
 
 impl ConsensusController for ConsensusControllerImpl {
@@ -77,6 +139,11 @@ impl ConsensusController for ConsensusControllerImpl {
             .extract_block_graph_part(start_slot, end_slot)
     }
 
+    /// Get a summary of the block graph, listing at most `max_items` waiting and discarded blocks
+    fn get_block_graph_summary(&self, max_items: usize) -> BlockGraphSummary {
+        self.shared_state.read().get_block_graph_summary(max_items)
+    }
+
     /// Get statuses of blocks present in the graph
     ///
     /// # Arguments:
@@ -91,6 +158,33 @@ impl ConsensusController for ConsensusControllerImpl {
             .collect()
     }
 
+    /// Get the confirmation of blocks present in the graph.
+    /// The confirmations are cached until the graph changes.
+    ///
+    /// # Arguments:
+    /// * `block_ids`: the block ids to get the confirmation of
+    ///
+    /// # Returns:
+    /// A vector of confirmations sorted by the order of the block ids, None for the blocks that are not active
+    fn get_confirmation_info(&self, ids: &[BlockId]) -> Vec<Option<ConfirmationInfo>> {
+        let read_shared_state = self.shared_state.read();
+        let mut cache = self.confirmation_cache.lock();
+        if cache.graph_revision != read_shared_state.graph_revision {
+            cache.graph_revision = read_shared_state.graph_revision;
+            cache.confirmations.clear();
+        }
+        ids.iter()
+            .map(|id| {
+                if let Some(confirmation) = cache.confirmations.get(id) {
+                    return Some(*confirmation);
+                }
+                let confirmation = read_shared_state.get_confirmation_info(id)?;
+                cache.confirmations.insert(*id, confirmation);
+                Some(confirmation)
+            })
+            .collect()
+    }
+
     /// Get all the cliques possible in the block graph.
     ///
     /// # Returns:
@@ -99,6 +193,14 @@ impl ConsensusController for ConsensusControllerImpl {
         self.shared_state.read().max_cliques.clone()
     }
 
+    /// Get all the cliques of the graph with their block count and tip blocks.
+    ///
+    /// # Returns:
+    /// A vector of cliques information
+    fn get_cliques_info(&self) -> Vec<CliqueInfo> {
+        self.shared_state.read().get_cliques_info()
+    }
+
     /// Get a part of the graph to send to a node so that he can setup his graph.
     /// Used for bootstrap.
     ///
@@ -230,48 +332,13 @@ impl ConsensusController for ConsensusControllerImpl {
             .get_latest_blockclique_block_at_slot(&slot)
     }
 
+    fn get_command_timings(&self) -> Vec<ConsensusCommandTiming> {
+        self.command_timings.lock().iter().cloned().collect()
+    }
+
     fn register_block(&self, block_id: BlockId, slot: Slot, block_storage: Storage, created: bool) {
         if self.broadcast_enabled {
-            if let Some(verifiable_block) = block_storage.read_blocks().get(&block_id) {
-                let operations: Vec<(OperationId, Option<SecureShare<Operation, OperationId>>)> =
-                    verifiable_block
-                        .content
-                        .operations
-                        .iter()
-                        .map(|operation_id| {
-                            match block_storage.read_operations().get(operation_id).cloned() {
-                                Some(verifiable_operation) => {
-                                    (*operation_id, Some(verifiable_operation))
-                                }
-                                None => (*operation_id, None),
-                            }
-                        })
-                        .collect();
-
-                if let Err(err) = self.broadcasts.block_sender.send(verifiable_block.clone()) {
-                    trace!(
-                        "error, failed to broadcast block with id {} due to: {}",
-                        block_id,
-                        err
-                    );
-                }
-
-                if let Err(err) = self.broadcasts.filled_block_sender.send(FilledBlock {
-                    header: verifiable_block.content.header.clone(),
-                    operations,
-                }) {
-                    trace!(
-                        "error, failed to broadcast filled block with id {} due to: {}",
-                        block_id,
-                        err
-                    );
-                }
-            } else {
-                debug!(
-                    "error, no broadcast event sent, block with id {} not found",
-                    block_id
-                );
-            };
+            self.broadcast_block(block_id, &block_storage);
         }
 
         if let Err(err) = self
@@ -287,6 +354,21 @@ impl ConsensusController for ConsensusControllerImpl {
         }
     }
 
+    fn register_blocks_batch(&self, blocks: Vec<(BlockId, Slot, Storage, bool)>) {
+        if self.broadcast_enabled {
+            for (block_id, _, block_storage, _) in &blocks {
+                self.broadcast_block(*block_id, block_storage);
+            }
+        }
+
+        if let Err(err) = self
+            .command_sender
+            .try_send(ConsensusCommand::RegisterBlocksBatch(blocks))
+        {
+            warn!("error trying to register a batch of blocks: {}", err);
+        }
+    }
+
     fn register_block_header(&self, block_id: BlockId, header: SecureShare<BlockHeader, BlockId>) {
         if self.broadcast_enabled {
             if let Err(err) = self.broadcasts.block_header_sender.send(header.clone()) {
@@ -32,7 +32,7 @@ mod manager;
 mod state;
 mod worker;
 
-pub use worker::start_consensus_worker;
+pub use worker::{start_consensus_worker, start_consensus_worker_with_clock};
 
 #[cfg(test)]
 pub mod tests;
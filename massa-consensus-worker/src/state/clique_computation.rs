@@ -3,7 +3,9 @@
 //! This file is responsible for clique computation
 
 use massa_models::{
+    active_block::ActiveBlock,
     block_id::BlockId,
+    clique::{Clique, CliqueInfo},
     prehash::{PreHashMap, PreHashSet},
 };
 
@@ -56,16 +58,48 @@ pub fn compute_max_cliques(
     max_cliques
 }
 
+/// Computes the information of each clique, with its tip blocks:
+/// the active blocks of the clique that have no child in that same clique
+pub fn compute_cliques_info<'a>(
+    max_cliques: &[Clique],
+    active_block_of: impl Fn(&BlockId) -> Option<&'a ActiveBlock>,
+) -> Vec<CliqueInfo> {
+    max_cliques
+        .iter()
+        .map(|clique| {
+            let tip_block_ids = clique
+                .block_ids
+                .iter()
+                .filter(|block_id| match active_block_of(block_id) {
+                    Some(a_block) => !a_block.children.iter().any(|thread_children| {
+                        thread_children
+                            .keys()
+                            .any(|child| clique.block_ids.contains(child))
+                    }),
+                    None => false,
+                })
+                .copied()
+                .collect();
+            CliqueInfo::new(clique, tip_block_ids)
+        })
+        .collect()
+}
+
 /// Tests
 
 #[cfg(test)]
 mod tests {
-    use crate::state::clique_computation::compute_max_cliques;
+    use crate::state::clique_computation::{compute_cliques_info, compute_max_cliques};
     use itertools::Itertools;
     use massa_models::{
+        active_block::ActiveBlock,
+        address::Address,
         block_id::BlockId,
+        clique::Clique,
         prehash::{PreHashMap, PreHashSet},
+        slot::Slot,
     };
+    use massa_signature::KeyPair;
     use rand::Rng;
 
     #[test]
@@ -150,4 +184,97 @@ mod tests {
 
         // All cliques are valid, unique and maximal
     }
+
+    fn block_id(seed: &str) -> BlockId {
+        BlockId::generate_from_hash(massa_hash::Hash::compute_from(seed.as_bytes()))
+    }
+
+    /// active block of `slot` with its children, by thread
+    fn active_block(block_id: BlockId, slot: Slot, children: &[(BlockId, Slot)]) -> ActiveBlock {
+        let mut block_children = vec![PreHashMap::default(); 2];
+        for (child, child_slot) in children {
+            block_children[child_slot.thread as usize].insert(*child, child_slot.period);
+        }
+        ActiveBlock {
+            creator_address: Address::from_public_key(
+                &KeyPair::generate(0).unwrap().get_public_key(),
+            ),
+            block_id,
+            parents: vec![],
+            children: block_children,
+            descendants: PreHashSet::default(),
+            is_final: false,
+            slot,
+            fitness: 1,
+            same_thread_parent_creator: None,
+        }
+    }
+
+    #[test]
+    fn test_compute_cliques_info() {
+        // genesis <- a1 <- a2 in thread 0, genesis <- b1 <- b2 in thread 1,
+        // and c1 in thread 0 competing with a1
+        let (genesis, a1, a2, b1, b2, c1) = (
+            block_id("genesis"),
+            block_id("a1"),
+            block_id("a2"),
+            block_id("b1"),
+            block_id("b2"),
+            block_id("c1"),
+        );
+        let (genesis_slot, a1_slot, a2_slot, b1_slot, b2_slot, c1_slot) = (
+            Slot::new(0, 0),
+            Slot::new(1, 0),
+            Slot::new(2, 0),
+            Slot::new(1, 1),
+            Slot::new(2, 1),
+            Slot::new(1, 0),
+        );
+        let blocks: PreHashMap<BlockId, ActiveBlock> = [
+            active_block(
+                genesis,
+                genesis_slot,
+                &[(a1, a1_slot), (b1, b1_slot), (c1, c1_slot)],
+            ),
+            active_block(a1, a1_slot, &[(a2, a2_slot)]),
+            active_block(a2, a2_slot, &[]),
+            active_block(b1, b1_slot, &[(b2, b2_slot)]),
+            active_block(b2, b2_slot, &[]),
+            active_block(c1, c1_slot, &[]),
+        ]
+        .into_iter()
+        .map(|block| (block.block_id, block))
+        .collect();
+
+        let clique = |block_ids: &[BlockId], is_blockclique: bool| Clique {
+            block_ids: block_ids.iter().copied().collect(),
+            fitness: block_ids.len() as u64,
+            is_blockclique,
+        };
+        let cliques = vec![
+            // the blockclique has one tip per thread
+            clique(&[genesis, a1, a2, b1, b2], true),
+            // b2 is incompatible with c1: the tip of thread 1 is b1 in this clique
+            clique(&[genesis, c1, b1], false),
+            // a clique with an unknown block: it is not a tip
+            clique(&[genesis, a1, block_id("unknown")], false),
+        ];
+
+        let infos = compute_cliques_info(&cliques, |id| blocks.get(id));
+        assert_eq!(infos.len(), 3);
+
+        let mut blockclique_tips = vec![a2, b2];
+        blockclique_tips.sort_unstable();
+        assert!(infos[0].is_blockclique);
+        assert_eq!(infos[0].block_count, 5);
+        assert_eq!(infos[0].tip_block_ids, blockclique_tips);
+
+        let mut alternative_tips = vec![c1, b1];
+        alternative_tips.sort_unstable();
+        assert!(!infos[1].is_blockclique);
+        assert_eq!(infos[1].fitness, 3);
+        assert_eq!(infos[1].tip_block_ids, alternative_tips);
+
+        assert_eq!(infos[2].tip_block_ids, vec![a1]);
+    }
 }
@@ -0,0 +1,275 @@
+use massa_consensus_exports::block_status::BlockStatus;
+use massa_models::{
+    active_block::ActiveBlock, block::ConfirmationInfo, block_id::BlockId, clique::Clique,
+};
+
+use super::ConsensusState;
+
+/// score of the final blocks, the other blocks are capped just below it
+const FINAL_CONFIRMATION_SCORE: u8 = 100;
+
+impl ConsensusState {
+    /// Get the confirmation of an active block, None if the block is not active
+    pub fn get_confirmation_info(&self, block_id: &BlockId) -> Option<ConfirmationInfo> {
+        let (a_block, _) = self.get_full_active_block(block_id)?;
+        Some(compute_confirmation_info(
+            block_id,
+            a_block,
+            &self.max_cliques,
+            |id| match self.blocks_state.get(id) {
+                Some(BlockStatus::Active { a_block, .. }) => a_block.fitness,
+                _ => 0,
+            },
+            self.config.delta_f0,
+        ))
+    }
+}
+
+/// Compute the confirmation of an active block in O(descendants + cliques).
+///
+/// A block becomes final once its descendants weigh more than `delta_f0` in its cliques, and
+/// the cliques excluding it are dropped once they are more than `delta_f0` behind the
+/// blockclique: the score measures the progress of the slowest of both.
+fn compute_confirmation_info(
+    block_id: &BlockId,
+    a_block: &ActiveBlock,
+    max_cliques: &[Clique],
+    fitness_of: impl Fn(&BlockId) -> u64,
+    delta_f0: u64,
+) -> ConfirmationInfo {
+    let blockclique = max_cliques.iter().find(|clique| clique.is_blockclique);
+    let in_blockclique =
+        a_block.is_final || blockclique.is_some_and(|clique| clique.block_ids.contains(block_id));
+
+    // final blocks are removed from the cliques, only their non-final descendants are counted
+    let descendants_fitness = blockclique.map_or(0, |clique| {
+        a_block
+            .descendants
+            .iter()
+            .filter(|id| clique.block_ids.contains(*id))
+            .map(&fitness_of)
+            .fold(0u64, |total, fitness| total.saturating_add(fitness))
+    });
+
+    if a_block.is_final {
+        return ConfirmationInfo {
+            is_final: true,
+            in_blockclique,
+            descendants_fitness,
+            fitness_gap: None,
+            confirmation_score: FINAL_CONFIRMATION_SCORE,
+        };
+    }
+
+    let (fitness_gap, progress) = match blockclique {
+        Some(blockclique) if in_blockclique => {
+            let fitness_gap = max_cliques
+                .iter()
+                .filter(|clique| !clique.block_ids.contains(block_id))
+                .map(|clique| clique.fitness)
+                .max()
+                .map(|best_alternative| blockclique.fitness.saturating_sub(best_alternative));
+            (
+                fitness_gap,
+                fitness_gap.map_or(descendants_fitness, |gap| gap.min(descendants_fitness)),
+            )
+        }
+        _ => (Some(0), 0),
+    };
+
+    let confirmation_score = if delta_f0 == 0 {
+        FINAL_CONFIRMATION_SCORE - 1
+    } else {
+        (u128::from(progress.min(delta_f0)) * u128::from(FINAL_CONFIRMATION_SCORE - 1)
+            / u128::from(delta_f0)) as u8
+    };
+
+    ConfirmationInfo {
+        is_final: false,
+        in_blockclique,
+        descendants_fitness,
+        fitness_gap,
+        confirmation_score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::{
+        address::Address,
+        prehash::{PreHashMap, PreHashSet},
+        slot::Slot,
+    };
+    use massa_signature::KeyPair;
+
+    const DELTA_F0: u64 = 100;
+    const BLOCK_FITNESS: u64 = 10;
+
+    fn block_id(seed: &str) -> BlockId {
+        BlockId::generate_from_hash(massa_hash::Hash::compute_from(seed.as_bytes()))
+    }
+
+    fn active_block(block_id: BlockId, descendants: PreHashSet<BlockId>) -> ActiveBlock {
+        ActiveBlock {
+            creator_address: Address::from_public_key(
+                &KeyPair::generate(0).unwrap().get_public_key(),
+            ),
+            block_id,
+            parents: vec![],
+            children: vec![PreHashMap::default()],
+            descendants,
+            is_final: false,
+            slot: Slot::new(1, 0),
+            fitness: BLOCK_FITNESS,
+            same_thread_parent_creator: None,
+        }
+    }
+
+    /// two cliques sharing `common`, each with its own chain of blocks on top of it
+    struct TwoCliqueGraph {
+        common: BlockId,
+        target: ActiveBlock,
+        alternative: Vec<BlockId>,
+        main: Vec<BlockId>,
+    }
+
+    impl TwoCliqueGraph {
+        fn new(alternative_len: usize) -> Self {
+            let target_id = block_id("target");
+            TwoCliqueGraph {
+                common: block_id("common"),
+                target: active_block(target_id, PreHashSet::default()),
+                alternative: (0..alternative_len)
+                    .map(|i| block_id(&format!("alternative {}", i)))
+                    .collect(),
+                main: vec![],
+            }
+        }
+
+        /// add a block descending from the target to its clique
+        fn extend_main(&mut self) {
+            let id = block_id(&format!("main {}", self.main.len()));
+            self.main.push(id);
+            self.target.descendants.insert(id);
+        }
+
+        fn cliques(&self) -> Vec<Clique> {
+            let main: PreHashSet<BlockId> = [self.common, self.target.block_id]
+                .into_iter()
+                .chain(self.main.iter().copied())
+                .collect();
+            let alternative: PreHashSet<BlockId> = std::iter::once(self.common)
+                .chain(self.alternative.iter().copied())
+                .collect();
+            let main_fitness = main.len() as u64 * BLOCK_FITNESS;
+            let alternative_fitness = alternative.len() as u64 * BLOCK_FITNESS;
+            vec![
+                Clique {
+                    block_ids: alternative,
+                    fitness: alternative_fitness,
+                    is_blockclique: alternative_fitness > main_fitness,
+                },
+                Clique {
+                    block_ids: main,
+                    fitness: main_fitness,
+                    is_blockclique: main_fitness >= alternative_fitness,
+                },
+            ]
+        }
+
+        fn confirmation(&self) -> ConfirmationInfo {
+            compute_confirmation_info(
+                &self.target.block_id,
+                &self.target,
+                &self.cliques(),
+                |_| BLOCK_FITNESS,
+                DELTA_F0,
+            )
+        }
+    }
+
+    #[test]
+    fn test_confirmation_two_cliques() {
+        // the alternative clique is ahead: the target is not in the blockclique
+        let mut graph = TwoCliqueGraph::new(3);
+        let confirmation = graph.confirmation();
+        assert!(!confirmation.is_final);
+        assert!(!confirmation.in_blockclique);
+        assert_eq!(confirmation.fitness_gap, Some(0));
+        assert_eq!(confirmation.confirmation_score, 0);
+
+        // extending the clique of the target makes it the blockclique
+        for _ in 0..3 {
+            graph.extend_main();
+        }
+        let confirmation = graph.confirmation();
+        assert!(confirmation.in_blockclique);
+        assert_eq!(confirmation.descendants_fitness, 3 * BLOCK_FITNESS);
+        assert_eq!(confirmation.fitness_gap, Some(BLOCK_FITNESS));
+        assert_eq!(confirmation.confirmation_score, 9);
+
+        // the score follows the gap while it is the lowest of both
+        let mut previous = confirmation;
+        for _ in 0..5 {
+            graph.extend_main();
+            let confirmation = graph.confirmation();
+            assert_eq!(
+                confirmation.fitness_gap,
+                Some(previous.fitness_gap.unwrap() + BLOCK_FITNESS)
+            );
+            assert!(confirmation.confirmation_score > previous.confirmation_score);
+            previous = confirmation;
+        }
+        assert_eq!(previous.fitness_gap, Some(6 * BLOCK_FITNESS));
+        assert_eq!(previous.confirmation_score, 59);
+
+        // the score is capped below the score of the final blocks
+        for _ in 0..20 {
+            graph.extend_main();
+        }
+        assert_eq!(graph.confirmation().confirmation_score, 99);
+
+        // a block of the alternative clique is not confirmed
+        let alternative = active_block(graph.alternative[0], PreHashSet::default());
+        let confirmation = compute_confirmation_info(
+            &alternative.block_id,
+            &alternative,
+            &graph.cliques(),
+            |_| BLOCK_FITNESS,
+            DELTA_F0,
+        );
+        assert!(!confirmation.in_blockclique);
+        assert_eq!(confirmation.confirmation_score, 0);
+    }
+
+    #[test]
+    fn test_confirmation_single_clique_and_final() {
+        // without competing clique, only the descendants count
+        let mut graph = TwoCliqueGraph::new(0);
+        graph.extend_main();
+        let cliques: Vec<Clique> = graph
+            .cliques()
+            .into_iter()
+            .filter(|clique| clique.is_blockclique)
+            .collect();
+        let confirmation = compute_confirmation_info(
+            &graph.target.block_id,
+            &graph.target,
+            &cliques,
+            |_| BLOCK_FITNESS,
+            DELTA_F0,
+        );
+        assert_eq!(confirmation.fitness_gap, None);
+        assert_eq!(confirmation.descendants_fitness, BLOCK_FITNESS);
+        assert_eq!(confirmation.confirmation_score, 9);
+
+        // final blocks report final, whatever their descendants
+        graph.target.is_final = true;
+        let confirmation = graph.confirmation();
+        assert!(confirmation.is_final);
+        assert!(confirmation.in_blockclique);
+        assert_eq!(confirmation.fitness_gap, None);
+        assert_eq!(confirmation.confirmation_score, FINAL_CONFIRMATION_SCORE);
+    }
+}
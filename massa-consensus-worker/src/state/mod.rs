@@ -4,7 +4,9 @@ use std::{
 };
 
 use massa_consensus_exports::{
-    block_graph_export::BlockGraphExport,
+    block_graph_export::{
+        BlockGraphExport, BlockGraphSummary, DiscardedBlockSummary, WaitingBlockSummary,
+    },
     block_status::{BlockStatus, ExportCompiledBlock, HeaderOrBlock, StorageOrBlock},
     error::ConsensusError,
     ConsensusChannels, ConsensusConfig,
@@ -17,7 +19,7 @@ use massa_models::{
     block::BlockGraphStatus,
     block_header::SecuredHeader,
     block_id::BlockId,
-    clique::Clique,
+    clique::{Clique, CliqueInfo},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     slot::Slot,
 };
@@ -26,9 +28,11 @@ use massa_time::MassaTime;
 use tracing::debug;
 
 use self::blocks_state::BlocksState;
+use self::clique_computation::compute_cliques_info;
 
 pub mod blocks_state;
 mod clique_computation;
+mod confirmation;
 mod graph;
 mod process;
 mod process_commands;
@@ -92,6 +96,8 @@ pub struct ConsensusState {
     pub nonfinal_active_blocks_per_slot: HashMap<Slot, PreHashSet<BlockId>>,
     /// massa metrics
     pub(crate) massa_metrics: MassaMetrics,
+    /// incremented each time the graph may have changed, to invalidate what was computed from it
+    pub graph_revision: u64,
 }
 
 impl ConsensusState {
@@ -125,6 +131,17 @@ impl ConsensusState {
         self.max_cliques.len()
     }
 
+    /// Get all the cliques along with their tip blocks,
+    /// i.e. the blocks of each clique that have no child in that same clique
+    pub fn get_cliques_info(&self) -> Vec<CliqueInfo> {
+        compute_cliques_info(&self.max_cliques, |block_id| {
+            match self.blocks_state.get(block_id) {
+                Some(BlockStatus::Active { a_block, .. }) => Some(a_block.as_ref()),
+                _ => None,
+            }
+        })
+    }
+
     /// get the blockclique (or final) block ID at a given slot, if any
     pub fn get_blockclique_block_at_slot(&self, slot: &Slot) -> Option<BlockId> {
         // List all blocks at this slot.
@@ -455,6 +472,79 @@ impl ConsensusState {
         Ok(export)
     }
 
+    /// Summarize the block graph without copying the blocks.
+    /// At most `max_items` blocks waiting for dependencies, and as many discarded blocks, are listed.
+    pub fn get_block_graph_summary(&self, max_items: usize) -> BlockGraphSummary {
+        let mut waiting_for_dependencies = Vec::new();
+        for block_id in self.blocks_state.waiting_for_dependencies_blocks() {
+            if let Some(BlockStatus::WaitingForDependencies {
+                header_or_block,
+                unsatisfied_dependencies,
+                sequence_number,
+            }) = self.blocks_state.get(block_id)
+            {
+                waiting_for_dependencies.push((
+                    *sequence_number,
+                    WaitingBlockSummary {
+                        block_id: *block_id,
+                        slot: header_or_block.get_slot(),
+                        missing_dependencies: unsatisfied_dependencies.iter().copied().collect(),
+                    },
+                ));
+            }
+        }
+        // the oldest waiting blocks are the most likely to be stuck
+        waiting_for_dependencies.sort_unstable_by_key(|(sequence_number, _)| *sequence_number);
+        waiting_for_dependencies.truncate(max_items);
+
+        let mut discarded_blocks = Vec::new();
+        for block_id in self.blocks_state.discarded_blocks() {
+            if let Some(BlockStatus::Discarded {
+                slot,
+                creator,
+                parents,
+                reason,
+                sequence_number,
+            }) = self.blocks_state.get(block_id)
+            {
+                discarded_blocks.push((
+                    *sequence_number,
+                    DiscardedBlockSummary {
+                        block_id: *block_id,
+                        slot: *slot,
+                        creator: *creator,
+                        parents: parents.clone(),
+                        reason: reason.clone(),
+                    },
+                ));
+            }
+        }
+        discarded_blocks.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+        discarded_blocks.truncate(max_items);
+
+        BlockGraphSummary {
+            incoming_block_count: self.blocks_state.incoming_blocks().len(),
+            waiting_for_slot_block_count: self.blocks_state.waiting_for_slot_blocks().len(),
+            waiting_for_dependencies_block_count: self
+                .blocks_state
+                .waiting_for_dependencies_blocks()
+                .len(),
+            active_block_count: self.blocks_state.active_blocks().len(),
+            discarded_block_count: self.blocks_state.discarded_blocks().len(),
+            best_parents: self.best_parents.clone(),
+            latest_final_blocks_periods: self.latest_final_blocks_periods.clone(),
+            clique_count: self.max_cliques.len(),
+            waiting_for_dependencies: waiting_for_dependencies
+                .into_iter()
+                .map(|(_, block)| block)
+                .collect(),
+            discarded_blocks: discarded_blocks
+                .into_iter()
+                .map(|(_, block)| block)
+                .collect(),
+        }
+    }
+
     /// Gets all stored final blocks, not only the still-useful ones
     /// This is used when initializing Execution from Consensus.
     /// Since the Execution bootstrap snapshot is older than the Consensus snapshot,
@@ -693,6 +693,7 @@ impl ConsensusState {
     /// 10. note new latest final periods (prune graph if changed)
     /// 11. add stale blocks to stats
     pub fn block_db_changed(&mut self) -> Result<(), ConsensusError> {
+        self.graph_revision = self.graph_revision.wrapping_add(1);
         let final_block_slots = {
             massa_trace!("consensus.consensus_worker.block_db_changed", {});
 
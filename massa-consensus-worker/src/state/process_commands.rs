@@ -171,5 +171,6 @@ impl ConsensusState {
                 sequence_number,
             })
         });
+        self.graph_revision = self.graph_revision.wrapping_add(1);
     }
 }
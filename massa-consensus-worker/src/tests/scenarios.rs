@@ -1,15 +1,20 @@
 use std::{
     collections::{HashSet, VecDeque},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 
 use super::{
-    tools::{consensus_test, register_block},
+    tools::{consensus_test, consensus_test_with_metrics, register_block},
     universe::{ConsensusForeignControllers, ConsensusTestUniverse},
 };
 use crate::tests::tools::create_block;
-use massa_consensus_exports::ConsensusConfig;
+use massa_consensus_exports::{command_timing::ConsensusCommandKind, ConsensusConfig};
 use massa_execution_exports::MockExecutionController;
+use massa_metrics::MassaMetrics;
 use massa_models::{
     address::Address, block::BlockGraphStatus, block_id::BlockId, config::ENDORSEMENT_COUNT,
     slot::Slot,
@@ -19,7 +24,7 @@ use massa_pos_exports::{MockSelectorController, Selection};
 use massa_signature::KeyPair;
 use massa_storage::Storage;
 use massa_test_framework::TestUniverse;
-use massa_time::MassaTime;
+use massa_time::{ManualClock, MassaTime};
 use mockall::Sequence;
 
 #[test]
@@ -400,3 +405,429 @@ fn test_parent_in_the_future() {
         "wrong status"
     );
 }
+
+/// With a manual clock, a block waits for its slot until the clock is moved to it,
+/// even though the system time passed it long ago
+#[test]
+fn test_manual_clock_slot_tick() {
+    let staking_key: KeyPair = KeyPair::generate(0).unwrap();
+    let t0 = MassaTime::from_millis(1000);
+    let genesis_timestamp = MassaTime::now().saturating_sub(t0.checked_mul(10).unwrap());
+    let cfg = ConsensusConfig {
+        t0,
+        thread_count: 2,
+        genesis_timestamp,
+        force_keep_final_periods: 50,
+        force_keep_final_periods_without_ops: 128,
+        max_future_processing_blocks: 10,
+        genesis_key: staking_key.clone(),
+        ..ConsensusConfig::default()
+    };
+    let staking_address = Address::from_public_key(&staking_key.get_public_key());
+
+    let clock = ManualClock::new(genesis_timestamp);
+    let mut foreign_controllers = ConsensusForeignControllers::new_with_mocks();
+    foreign_controllers.clock = Arc::new(clock.clone());
+    let storage = foreign_controllers.storage.clone();
+
+    foreign_controllers
+        .execution_controller
+        .expect_update_blockclique_status()
+        .returning(|_, _, _| {});
+    foreign_controllers
+        .pool_controller
+        .expect_notify_final_cs_periods()
+        .returning(|_| {});
+    foreign_controllers
+        .pool_controller
+        .expect_add_denunciation_precursor()
+        .returning(|_| {});
+    foreign_controllers
+        .selector_controller
+        .expect_get_producer()
+        .returning(move |_| Ok(staking_address));
+    foreign_controllers
+        .selector_controller
+        .expect_get_selection()
+        .returning(move |_| {
+            Ok(Selection {
+                producer: staking_address,
+                endorsements: vec![staking_address; ENDORSEMENT_COUNT as usize],
+            })
+        });
+
+    let universe = ConsensusTestUniverse::new(foreign_controllers, cfg);
+    let genesis_hashes = universe
+        .module_controller
+        .get_block_graph_status(None, None)
+        .expect("could not get block graph status")
+        .genesis_blocks;
+    let block = create_block(Slot::new(1, 0), genesis_hashes, &staking_key);
+    register_block(&universe.module_controller, block.clone(), storage);
+
+    std::thread::sleep(Duration::from_millis(200));
+    assert_eq!(
+        universe.module_controller.get_block_statuses(&[block.id]),
+        vec![BlockGraphStatus::WaitingForSlot]
+    );
+
+    // the worker catches up with the clock at its next check
+    clock.advance(t0);
+    let mut status = BlockGraphStatus::WaitingForSlot;
+    for _ in 0..100 {
+        status = universe
+            .module_controller
+            .get_block_statuses(&[block.id])
+            .remove(0);
+        if status != BlockGraphStatus::WaitingForSlot {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert_eq!(status, BlockGraphStatus::ActiveInBlockclique);
+}
+
+/// State of the graph after registering a chain of blocks
+#[derive(Debug, PartialEq, Eq)]
+struct RegisteredChain {
+    statuses: Vec<BlockGraphStatus>,
+    best_parents: Vec<(BlockId, u64)>,
+    /// number of graph updates notified to execution while registering the chain
+    blockclique_updates: usize,
+}
+
+/// Register a chain of `block_count` blocks alternating between two threads,
+/// either one by one (without coalescing) or as a single batch
+fn register_chain(block_count: usize, staking_key: &KeyPair, as_batch: bool) -> RegisteredChain {
+    let t0 = MassaTime::from_millis(16000);
+    let periods = (block_count as u64).div_ceil(2);
+    let cfg = ConsensusConfig {
+        t0,
+        thread_count: 2,
+        // all the blocks are in the past so that they are processed right away
+        genesis_timestamp: MassaTime::now().saturating_sub(t0.saturating_mul(periods + 2)),
+        delta_f0: 32,
+        force_keep_final_periods: periods + 10,
+        force_keep_final_periods_without_ops: periods + 10,
+        block_db_prune_interval: MassaTime::from_millis(3_600_000),
+        max_block_registration_batch_size: if as_batch { 256 } else { 1 },
+        genesis_key: staking_key.clone(),
+        ..ConsensusConfig::default()
+    };
+    let staking_address = Address::from_public_key(&staking_key.get_public_key());
+    let storage = Storage::create_root();
+
+    let updates = Arc::new(AtomicUsize::new(0));
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    let updates_count = updates.clone();
+    execution_controller
+        .expect_update_blockclique_status()
+        .returning(move |_, _, _| {
+            updates_count.fetch_add(1, Ordering::SeqCst);
+        });
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_notify_final_cs_periods()
+        .returning(|_| {});
+    pool_controller
+        .expect_add_denunciation_precursor()
+        .returning(|_| {});
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .returning(move |_| Ok(staking_address));
+    selector_controller
+        .expect_get_selection()
+        .returning(move |_| {
+            Ok(Selection {
+                producer: staking_address,
+                endorsements: vec![staking_address; ENDORSEMENT_COUNT as usize],
+            })
+        });
+
+    let mut result = None;
+    consensus_test(
+        cfg,
+        execution_controller,
+        pool_controller,
+        selector_controller,
+        |consensus_controller| {
+            let mut parents = consensus_controller
+                .get_block_graph_status(None, None)
+                .expect("could not get block graph status")
+                .genesis_blocks;
+            let mut blocks = Vec::with_capacity(block_count);
+            for index in 0..block_count {
+                let slot = Slot::new(1 + (index / 2) as u64, (index % 2) as u8);
+                let block = create_block(slot, parents.clone(), staking_key);
+                parents[slot.thread as usize] = block.id;
+                blocks.push(block);
+            }
+            let block_ids: Vec<BlockId> = blocks.iter().map(|block| block.id).collect();
+
+            let updates_before = updates.load(Ordering::SeqCst);
+            if as_batch {
+                let mut storage = storage.clone();
+                let batch = blocks
+                    .into_iter()
+                    .map(|block| {
+                        let (block_id, slot) = (block.id, block.content.header.content.slot);
+                        storage.store_block(block);
+                        (block_id, slot, storage.clone(), false)
+                    })
+                    .collect();
+                consensus_controller.register_blocks_batch(batch);
+            } else {
+                for block in blocks {
+                    register_block(&consensus_controller, block, storage.clone());
+                }
+            }
+
+            // wait for the last block to be registered
+            let last_block_id = *block_ids.last().unwrap();
+            let start = std::time::Instant::now();
+            while consensus_controller.get_block_statuses(&[last_block_id])[0]
+                == BlockGraphStatus::NotFound
+            {
+                assert!(
+                    start.elapsed() < Duration::from_secs(30),
+                    "blocks were not registered in time"
+                );
+                std::thread::sleep(Duration::from_millis(50));
+            }
+
+            result = Some(RegisteredChain {
+                statuses: consensus_controller.get_block_statuses(&block_ids),
+                best_parents: consensus_controller.get_best_parents(),
+                blockclique_updates: updates.load(Ordering::SeqCst) - updates_before,
+            });
+        },
+    );
+    result.unwrap()
+}
+
+#[test]
+fn test_register_blocks_batch() {
+    let block_count = 500;
+    let staking_key: KeyPair = KeyPair::generate(0).unwrap();
+
+    let one_by_one = register_chain(block_count, &staking_key, false);
+    let batch = register_chain(block_count, &staking_key, true);
+
+    assert_eq!(one_by_one.statuses, batch.statuses);
+    assert_eq!(one_by_one.best_parents, batch.best_parents);
+    assert_eq!(
+        batch.statuses.last(),
+        Some(&BlockGraphStatus::ActiveInBlockclique)
+    );
+    assert_eq!(batch.statuses.first(), Some(&BlockGraphStatus::Final));
+    // every block extends the blockclique: one update per registration,
+    // against a single update for the whole batch
+    assert_eq!(one_by_one.blockclique_updates, block_count);
+    assert_eq!(batch.blockclique_updates, 1);
+}
+
+/// Mocks accepting the blocks produced by `staking_address`
+fn mocked_controllers(
+    staking_address: Address,
+) -> (
+    Box<MockExecutionController>,
+    Box<MockPoolController>,
+    Box<MockSelectorController>,
+) {
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    execution_controller
+        .expect_update_blockclique_status()
+        .returning(|_, _, _| {});
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_notify_final_cs_periods()
+        .returning(|_| {});
+    pool_controller
+        .expect_add_denunciation_precursor()
+        .returning(|_| {});
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .returning(move |_| Ok(staking_address));
+    selector_controller
+        .expect_get_selection()
+        .returning(move |_| {
+            Ok(Selection {
+                producer: staking_address,
+                endorsements: vec![staking_address; ENDORSEMENT_COUNT as usize],
+            })
+        });
+    (execution_controller, pool_controller, selector_controller)
+}
+
+/// Config executing the block registrations one by one, with 26 slots in the past
+fn command_timings_config(staking_key: &KeyPair) -> ConsensusConfig {
+    let t0 = MassaTime::from_millis(16000);
+    ConsensusConfig {
+        t0,
+        thread_count: 2,
+        // all the blocks are in the past so that they are processed right away
+        genesis_timestamp: MassaTime::now().saturating_sub(t0.saturating_mul(20)),
+        delta_f0: 32,
+        force_keep_final_periods: 30,
+        force_keep_final_periods_without_ops: 30,
+        block_db_prune_interval: MassaTime::from_millis(3_600_000),
+        max_block_registration_batch_size: 1,
+        max_command_timings: 4,
+        genesis_key: staking_key.clone(),
+        ..ConsensusConfig::default()
+    }
+}
+
+#[test]
+fn test_command_timings() {
+    let staking_key: KeyPair = KeyPair::generate(0).unwrap();
+    let cfg = command_timings_config(&staking_key);
+    let storage = Storage::create_root();
+    let (execution_controller, pool_controller, selector_controller) =
+        mocked_controllers(Address::from_public_key(&staking_key.get_public_key()));
+    let massa_metrics = MassaMetrics::new(
+        true,
+        "0.0.0.0:9898".parse().unwrap(),
+        cfg.thread_count,
+        Duration::from_secs(1),
+    )
+    .0;
+
+    consensus_test_with_metrics(
+        cfg,
+        execution_controller,
+        pool_controller,
+        selector_controller,
+        massa_metrics.clone(),
+        |consensus_controller| {
+            let mut parents = consensus_controller
+                .get_block_graph_status(None, None)
+                .expect("could not get block graph status")
+                .genesis_blocks;
+            let mut blocks = Vec::new();
+            for index in 0..26 {
+                let slot = Slot::new(1 + index / 2, (index % 2) as u8);
+                let block = create_block(slot, parents.clone(), &staking_key);
+                parents[slot.thread as usize] = block.id;
+                blocks.push(block);
+            }
+            let mut blocks = blocks.into_iter();
+
+            // three cheap registrations of a single block
+            for block in blocks.by_ref().take(3) {
+                register_block(&consensus_controller, block, storage.clone());
+            }
+            // a costly registration of twenty blocks
+            let mut batch_storage = storage.clone();
+            let batch: Vec<_> = blocks
+                .by_ref()
+                .take(20)
+                .map(|block| {
+                    let (block_id, slot) = (block.id, block.content.header.content.slot);
+                    batch_storage.store_block(block);
+                    (block_id, slot, batch_storage.clone(), false)
+                })
+                .collect();
+            let batch_last_slot = batch.last().unwrap().1;
+            consensus_controller.register_blocks_batch(batch);
+            // a header, and an invalid block
+            let header = blocks.next().unwrap();
+            consensus_controller.register_block_header(header.id, header.content.header.clone());
+            let invalid = blocks.next().unwrap();
+            consensus_controller.mark_invalid_block(invalid.id, invalid.content.header.clone());
+
+            // wait for the last command to be executed
+            let start = std::time::Instant::now();
+            while consensus_controller
+                .get_command_timings()
+                .last()
+                .map(|timing| timing.command)
+                != Some(ConsensusCommandKind::MarkInvalidBlock)
+            {
+                assert!(
+                    start.elapsed() < Duration::from_secs(30),
+                    "commands were not executed in time"
+                );
+                std::thread::sleep(Duration::from_millis(50));
+            }
+
+            // each command is counted under its own label
+            for (command, count) in [
+                (ConsensusCommandKind::RegisterBlock, 3),
+                (ConsensusCommandKind::RegisterBlocksBatch, 1),
+                (ConsensusCommandKind::RegisterBlockHeader, 1),
+                (ConsensusCommandKind::MarkInvalidBlock, 1),
+            ] {
+                assert_eq!(
+                    massa_metrics
+                        .get_consensus_command_processing(command.name())
+                        .0,
+                    count,
+                    "wrong count of {}",
+                    command.name()
+                );
+            }
+
+            // only the last four commands are kept, oldest first
+            let timings = consensus_controller.get_command_timings();
+            assert_eq!(
+                timings
+                    .iter()
+                    .map(|timing| timing.command)
+                    .collect::<Vec<_>>(),
+                vec![
+                    ConsensusCommandKind::RegisterBlock,
+                    ConsensusCommandKind::RegisterBlocksBatch,
+                    ConsensusCommandKind::RegisterBlockHeader,
+                    ConsensusCommandKind::MarkInvalidBlock,
+                ]
+            );
+            assert_eq!(timings[0].block_count, 1);
+            assert_eq!(timings[1].block_id, None);
+            assert_eq!(timings[1].block_count, 20);
+            assert_eq!(timings[1].slot, Some(batch_last_slot));
+            assert_eq!(timings[2].block_id, Some(header.id));
+            assert_eq!(timings[2].slot, Some(header.content.header.content.slot));
+            assert_eq!(timings[3].block_id, Some(invalid.id));
+            assert!(timings.iter().all(|timing| timing.current_slot.is_some()));
+        },
+    );
+}
+
+#[test]
+fn test_command_timings_disabled_without_metrics() {
+    let staking_key: KeyPair = KeyPair::generate(0).unwrap();
+    let storage = Storage::create_root();
+    let (execution_controller, pool_controller, selector_controller) =
+        mocked_controllers(Address::from_public_key(&staking_key.get_public_key()));
+
+    consensus_test(
+        command_timings_config(&staking_key),
+        execution_controller,
+        pool_controller,
+        selector_controller,
+        |consensus_controller| {
+            let parents = consensus_controller
+                .get_block_graph_status(None, None)
+                .expect("could not get block graph status")
+                .genesis_blocks;
+            let block = create_block(Slot::new(1, 0), parents, &staking_key);
+            let block_id = block.id;
+            register_block(&consensus_controller, block, storage.clone());
+
+            let start = std::time::Instant::now();
+            while consensus_controller.get_block_statuses(&[block_id])[0]
+                == BlockGraphStatus::NotFound
+            {
+                assert!(
+                    start.elapsed() < Duration::from_secs(30),
+                    "block was not registered in time"
+                );
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            assert!(consensus_controller.get_command_timings().is_empty());
+        },
+    );
+}
@@ -1,6 +1,7 @@
 use std::{time::Duration, vec};
 
 use crate::start_consensus_worker;
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_channel::MassaChannel;
 use massa_consensus_exports::{
     ConsensusBroadcasts, ConsensusChannels, ConsensusConfig, ConsensusController,
@@ -31,6 +32,33 @@ pub fn consensus_test<F>(
     test: F,
 ) where
     F: FnOnce(Box<dyn ConsensusController>),
+{
+    consensus_test_with_metrics(
+        cfg,
+        execution_controller,
+        pool_controller,
+        selector_controller,
+        MassaMetrics::new(
+            false,
+            "0.0.0.0:9898".parse().unwrap(),
+            THREAD_COUNT,
+            Duration::from_secs(1),
+        )
+        .0,
+        test,
+    )
+}
+
+/// Same as `consensus_test`, the consensus worker using `massa_metrics`
+pub fn consensus_test_with_metrics<F>(
+    cfg: ConsensusConfig,
+    execution_controller: Box<MockExecutionController>,
+    pool_controller: Box<MockPoolController>,
+    selector_controller: Box<MockSelectorController>,
+    massa_metrics: MassaMetrics,
+    test: F,
+) where
+    F: FnOnce(Box<dyn ConsensusController>),
 {
     let storage: Storage = Storage::create_root();
     // mock protocol
@@ -49,9 +77,11 @@ pub fn consensus_test<F>(
     let (consensus_event_sender, _) = MassaChannel::new(String::from("consensus_event"), Some(10));
 
     // All API channels
-    let (block_sender, _block_receiver) = tokio::sync::broadcast::channel(10);
+    let block_sender = DedupBroadcaster::new("test_blocks".to_string(), 10, 10);
+    let _block_receiver = block_sender.subscribe();
     let (block_header_sender, _block_header_receiver) = tokio::sync::broadcast::channel(10);
-    let (filled_block_sender, _filled_block_receiver) = tokio::sync::broadcast::channel(10);
+    let filled_block_sender = DedupBroadcaster::new("test_filled_blocks".to_string(), 10, 10);
+    let _filled_block_receiver = filled_block_sender.subscribe();
     let (consensus_controller, mut consensus_manager) = start_consensus_worker(
         cfg.clone(),
         ConsensusChannels {
@@ -68,13 +98,7 @@ pub fn consensus_test<F>(
         },
         None,
         storage.clone(),
-        MassaMetrics::new(
-            false,
-            "0.0.0.0:9898".parse().unwrap(),
-            THREAD_COUNT,
-            Duration::from_secs(1),
-        )
-        .0,
+        massa_metrics,
     );
 
     // Call test func.
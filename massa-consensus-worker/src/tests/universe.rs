@@ -1,5 +1,7 @@
+use std::sync::Arc;
 use std::time::Duration;
 
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_channel::MassaChannel;
 use massa_consensus_exports::{
     ConsensusBroadcasts, ConsensusChannels, ConsensusConfig, ConsensusController,
@@ -12,8 +14,9 @@ use massa_pos_exports::MockSelectorController;
 use massa_protocol_exports::MockProtocolController;
 use massa_storage::Storage;
 use massa_test_framework::TestUniverse;
+use massa_time::{Clock, RealClock};
 
-use crate::start_consensus_worker;
+use crate::start_consensus_worker_with_clock;
 
 pub struct ConsensusForeignControllers {
     pub execution_controller: Box<MockExecutionController>,
@@ -21,6 +24,7 @@ pub struct ConsensusForeignControllers {
     pub pool_controller: Box<MockPoolController>,
     pub selector_controller: Box<MockSelectorController>,
     pub storage: Storage,
+    pub clock: Arc<dyn Clock>,
 }
 
 impl ConsensusForeignControllers {
@@ -31,6 +35,7 @@ impl ConsensusForeignControllers {
             pool_controller: Box::new(MockPoolController::new()),
             selector_controller: Box::new(MockSelectorController::new()),
             storage: Storage::create_root(),
+            clock: Arc::new(RealClock),
         }
     }
 }
@@ -60,10 +65,12 @@ impl TestUniverse for ConsensusTestUniverse {
             MassaChannel::new(String::from("consensus_event"), Some(10));
 
         // All API channels
-        let (block_sender, _block_receiver) = tokio::sync::broadcast::channel(10);
+        let block_sender = DedupBroadcaster::new("test_blocks".to_string(), 10, 10);
+        let _block_receiver = block_sender.subscribe();
         let (block_header_sender, _block_header_receiver) = tokio::sync::broadcast::channel(10);
-        let (filled_block_sender, _filled_block_receiver) = tokio::sync::broadcast::channel(10);
-        let (consensus_controller, _) = start_consensus_worker(
+        let filled_block_sender = DedupBroadcaster::new("test_filled_blocks".to_string(), 10, 10);
+        let _filled_block_receiver = filled_block_sender.subscribe();
+        let (consensus_controller, _) = start_consensus_worker_with_clock(
             config,
             ConsensusChannels {
                 broadcasts: ConsensusBroadcasts {
@@ -86,6 +93,7 @@ impl TestUniverse for ConsensusTestUniverse {
                 Duration::from_secs(1),
             )
             .0,
+            foreign_controllers.clock,
         );
         let universe = Self {
             module_controller: consensus_controller,
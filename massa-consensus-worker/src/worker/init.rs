@@ -2,11 +2,13 @@ use massa_channel::receiver::MassaReceiver;
 use massa_consensus_exports::{
     block_status::{BlockStatus, StorageOrBlock},
     bootstrapable_graph::BootstrapableGraph,
+    command_timing::ConsensusCommandTiming,
     error::ConsensusError,
     ConsensusConfig,
 };
 use massa_execution_exports::ExecutionBlockMetadata;
 use massa_hash::Hash;
+use massa_metrics::MassaMetrics;
 use massa_models::{
     active_block::ActiveBlock,
     address::Address,
@@ -16,11 +18,11 @@ use massa_models::{
     prehash::PreHashMap,
     secure_share::SecureShareContent,
     slot::Slot,
-    timeslots::{get_block_slot_timestamp, get_latest_block_slot_at_timestamp},
+    timeslots::{get_block_slot_timestamp, get_latest_block_slot_at_timestamp, SlotClock},
 };
 use massa_storage::Storage;
-use massa_time::MassaTime;
-use parking_lot::RwLock;
+use massa_time::Clock;
+use parking_lot::{Mutex, RwLock};
 use std::{
     collections::{HashMap, VecDeque},
     sync::Arc,
@@ -80,6 +82,9 @@ impl ConsensusWorker {
     /// * `shared_state`: shared state with the controller
     /// * `init_graph`: Optional graph of blocks to initiate the worker
     /// * `storage`: shared storage
+    /// * `massa_metrics`: metrics of the node
+    /// * `command_timings`: timings of the last commands executed, shared with the controller
+    /// * `clock`: source of the time followed by the slots
     ///
     /// # Returns:
     /// A `ConsensusWorker`, to interact with it use the `ConsensusController`
@@ -89,8 +94,13 @@ impl ConsensusWorker {
         shared_state: Arc<RwLock<ConsensusState>>,
         init_graph: Option<BootstrapableGraph>,
         storage: Storage,
+        massa_metrics: MassaMetrics,
+        command_timings: Arc<Mutex<VecDeque<ConsensusCommandTiming>>>,
+        clock: Arc<dyn Clock>,
     ) -> Result<Self, ConsensusError> {
-        let now = MassaTime::now();
+        let slot_clock = SlotClock::new(config.thread_count, config.t0, config.genesis_timestamp)
+            .with_clock(clock);
+        let now = slot_clock.now();
         let previous_slot = get_latest_block_slot_at_timestamp(
             config.thread_count,
             config.t0,
@@ -131,13 +141,7 @@ impl ConsensusWorker {
         let next_slot = previous_slot.map_or(Ok(Slot::new(0u64, 0u8)), |s| {
             s.get_next_slot(config.thread_count)
         })?;
-        let next_instant = get_block_slot_timestamp(
-            config.thread_count,
-            config.t0,
-            config.genesis_timestamp,
-            next_slot,
-        )?
-        .estimate_instant()?;
+        let next_instant = slot_clock.slot_instant(next_slot)?;
 
         info!(
             "Started node at time {}, cycle {}, period {}, thread {}",
@@ -195,9 +199,13 @@ impl ConsensusWorker {
             config: config.clone(),
             command_receiver,
             shared_state,
+            slot_clock,
             previous_slot,
             next_slot,
             next_instant,
+            pending_command: None,
+            massa_metrics,
+            command_timings,
         };
 
         // If the node starts after the genesis timestamp then it has to initialize its graph
@@ -1,14 +1,14 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use massa_consensus_exports::{error::ConsensusError, events::ConsensusEvent};
-use massa_models::{
-    slot::Slot,
-    timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
+use massa_consensus_exports::{
+    command_timing::{ConsensusCommandKind, ConsensusCommandTiming},
+    error::ConsensusError,
+    events::ConsensusEvent,
 };
-use massa_time::MassaTime;
-use tracing::{info, warn};
+use massa_models::{block_id::BlockId, slot::Slot};
+use tracing::{debug, info, warn};
 
-use crate::commands::ConsensusCommand;
+use crate::{commands::ConsensusCommand, state::ConsensusState};
 
 use super::ConsensusWorker;
 
@@ -18,8 +18,44 @@ enum WaitingStatus {
     Disconnected,
 }
 
+/// Blocks concerned by a command, recorded with its timing
+struct CommandContext {
+    kind: ConsensusCommandKind,
+    block_ids: Vec<BlockId>,
+    /// highest slot of the blocks
+    slot: Option<Slot>,
+}
+
+impl CommandContext {
+    fn new(command: &ConsensusCommand) -> Self {
+        match command {
+            ConsensusCommand::RegisterBlock(block_id, slot, _, _) => CommandContext {
+                kind: ConsensusCommandKind::RegisterBlock,
+                block_ids: vec![*block_id],
+                slot: Some(*slot),
+            },
+            ConsensusCommand::RegisterBlocksBatch(blocks) => CommandContext {
+                kind: ConsensusCommandKind::RegisterBlocksBatch,
+                block_ids: blocks.iter().map(|(block_id, _, _, _)| *block_id).collect(),
+                slot: blocks.iter().map(|(_, slot, _, _)| *slot).max(),
+            },
+            ConsensusCommand::RegisterBlockHeader(block_id, header) => CommandContext {
+                kind: ConsensusCommandKind::RegisterBlockHeader,
+                block_ids: vec![*block_id],
+                slot: Some(header.content.slot),
+            },
+            ConsensusCommand::MarkInvalidBlock(block_id, header) => CommandContext {
+                kind: ConsensusCommandKind::MarkInvalidBlock,
+                block_ids: vec![*block_id],
+                slot: Some(header.content.slot),
+            },
+        }
+    }
+}
+
 impl ConsensusWorker {
     /// Execute a command received from the controller also run an update of the graph after processing the command.
+    /// When the metrics are enabled, the time spent waiting for the graph lock and processing the command is recorded.
     ///
     /// # Arguments:
     /// * `command`: the command to execute
@@ -27,7 +63,26 @@ impl ConsensusWorker {
     /// # Returns:
     /// An error if the command failed
     fn manage_command(&mut self, command: ConsensusCommand) -> Result<(), ConsensusError> {
+        if !self.massa_metrics.is_enabled() {
+            return self.execute_command(&mut self.shared_state.write(), command);
+        }
+        let context = CommandContext::new(&command);
+        let start = Instant::now();
         let mut write_shared_state = self.shared_state.write();
+        let locked = Instant::now();
+        let result = self.execute_command(&mut write_shared_state, command);
+        let processing = locked.elapsed();
+        drop(write_shared_state);
+        self.record_command_timing(context, locked.duration_since(start), processing);
+        result
+    }
+
+    /// Execute a command with the graph locked
+    fn execute_command(
+        &self,
+        write_shared_state: &mut ConsensusState,
+        command: ConsensusCommand,
+    ) -> Result<(), ConsensusError> {
         match command {
             ConsensusCommand::RegisterBlockHeader(block_id, header) => {
                 write_shared_state.register_block_header(block_id, header, self.previous_slot)?;
@@ -43,6 +98,27 @@ impl ConsensusWorker {
                 )?;
                 write_shared_state.block_db_changed()
             }
+            ConsensusCommand::RegisterBlocksBatch(mut blocks) => {
+                // register parents before their children
+                blocks.sort_by_key(|(_, slot, _, _)| *slot);
+                let count = blocks.len();
+                let mut failed = 0;
+                for (block_id, slot, block_storage, created) in blocks {
+                    // a faulty block must not prevent the registration of the others
+                    if let Err(err) = write_shared_state.register_block(
+                        block_id,
+                        slot,
+                        self.previous_slot,
+                        block_storage,
+                        created,
+                    ) {
+                        warn!("Error registering block {} of a batch: {}", block_id, err);
+                        failed += 1;
+                    }
+                }
+                debug!("registered a batch of {} blocks ({} failed)", count, failed);
+                write_shared_state.block_db_changed()
+            }
             ConsensusCommand::MarkInvalidBlock(block_id, header) => {
                 write_shared_state.mark_invalid_block(&block_id, header);
                 Ok(())
@@ -50,6 +126,51 @@ impl ConsensusWorker {
         }
     }
 
+    /// Feed the timing of a command to the metrics and to the window of the last command timings,
+    /// and warn if the command was slow
+    fn record_command_timing(
+        &self,
+        context: CommandContext,
+        lock_wait: Duration,
+        processing: Duration,
+    ) {
+        self.massa_metrics
+            .observe_consensus_command(context.kind.name(), lock_wait, processing);
+        if lock_wait + processing > self.config.slow_command_threshold.to_duration() {
+            let block_ids: Vec<String> =
+                context.block_ids.iter().map(|id| id.to_string()).collect();
+            warn!(
+                "slow consensus command {} for block(s) {} at slot {}: {:?} waiting for the graph lock, {:?} processing",
+                context.kind.name(),
+                block_ids.join(", "),
+                context
+                    .slot
+                    .map_or_else(|| "none".to_string(), |slot| slot.to_string()),
+                lock_wait,
+                processing
+            );
+        }
+        if self.config.max_command_timings == 0 {
+            return;
+        }
+        let mut command_timings = self.command_timings.lock();
+        while command_timings.len() >= self.config.max_command_timings {
+            command_timings.pop_front();
+        }
+        command_timings.push_back(ConsensusCommandTiming {
+            command: context.kind,
+            block_id: match context.kind {
+                ConsensusCommandKind::RegisterBlocksBatch => None,
+                _ => context.block_ids.first().copied(),
+            },
+            block_count: context.block_ids.len(),
+            slot: context.slot,
+            current_slot: self.previous_slot,
+            lock_wait_micros: lock_wait.as_micros() as u64,
+            processing_micros: processing.as_micros() as u64,
+        });
+    }
+
     /// Wait and interrupt if we receive a command, a stop signal or we reach the `instant`
     ///
     /// # Return:
@@ -57,9 +178,14 @@ impl ConsensusWorker {
     /// WaitingStatus::Ended => if we reached the `instant`
     /// WaitingStatus::Disconnected => if we received a stop signal
     fn wait_slot_or_command(&mut self, deadline: Instant) -> WaitingStatus {
-        match self.command_receiver.recv_deadline(deadline) {
+        let received = match self.pending_command.take() {
+            Some(command) => Ok(command),
+            None => self.command_receiver.recv_deadline(deadline),
+        };
+        match received {
             // message received => manage it
             Ok(command) => {
+                let command = self.coalesce_block_registrations(command);
                 if let Err(err) = self.manage_command(command) {
                     warn!("Error in consensus: {}", err);
                 }
@@ -72,40 +198,45 @@ impl ConsensusWorker {
         }
     }
 
+    /// Merge the block registrations already queued behind `command` into a single batch,
+    /// so that the graph is updated once for all of them.
+    /// Stops at the first other command, which is kept to be executed next.
+    fn coalesce_block_registrations(&mut self, command: ConsensusCommand) -> ConsensusCommand {
+        let ConsensusCommand::RegisterBlock(block_id, slot, block_storage, created) = command
+        else {
+            return command;
+        };
+        let mut batch = vec![(block_id, slot, block_storage, created)];
+        while batch.len() < self.config.max_block_registration_batch_size {
+            match self.command_receiver.try_recv() {
+                Ok(ConsensusCommand::RegisterBlock(block_id, slot, block_storage, created)) => {
+                    batch.push((block_id, slot, block_storage, created));
+                }
+                Ok(command) => {
+                    self.pending_command = Some(command);
+                    break;
+                }
+                // nothing queued, or disconnected which will be noticed by the next wait
+                Err(_) => break,
+            }
+        }
+        if batch.len() == 1 {
+            let (block_id, slot, block_storage, created) = batch.remove(0);
+            ConsensusCommand::RegisterBlock(block_id, slot, block_storage, created)
+        } else {
+            ConsensusCommand::RegisterBlocksBatch(batch)
+        }
+    }
+
     /// Gets the next slot and the instant when it will happen.
     /// Slots can be skipped if we waited too much in-between.
     /// Extra safety against double-production caused by clock adjustments (this is the role of the `previous_slot` parameter).
     fn get_next_slot(&self, previous_slot: Option<Slot>) -> (Slot, Instant) {
-        // get current absolute time
-        let now = MassaTime::now();
-
-        // get closest slot according to the current absolute time
-        let mut next_slot = get_closest_slot_to_timestamp(
-            self.config.thread_count,
-            self.config.t0,
-            self.config.genesis_timestamp,
-            now,
-        );
-
-        // protection against double-production on unexpected system clock adjustment
-        if let Some(prev_slot) = previous_slot {
-            if next_slot <= prev_slot {
-                next_slot = prev_slot
-                    .get_next_slot(self.config.thread_count)
-                    .expect("could not compute next slot");
-            }
-        }
-
-        // get the timestamp of the target slot
-        let next_instant = get_block_slot_timestamp(
-            self.config.thread_count,
-            self.config.t0,
-            self.config.genesis_timestamp,
-            next_slot,
-        )
-        .expect("could not get block slot timestamp")
-        .estimate_instant()
-        .expect("could not estimate block slot instant");
+        let next_slot = self.slot_clock.next_slot_after(previous_slot);
+        let next_instant = self
+            .slot_clock
+            .slot_instant(next_slot)
+            .expect("could not estimate block slot instant");
 
         (next_slot, next_instant)
     }
@@ -118,6 +249,14 @@ impl ConsensusWorker {
             match self.wait_slot_or_command(self.next_instant) {
                 // When we reached the instant of the next slot
                 WaitingStatus::Ended => {
+                    // the clock may not have reached the slot yet if it does not follow the system time
+                    if !self.slot_clock.is_slot_reached(self.next_slot) {
+                        self.next_instant = self
+                            .slot_clock
+                            .slot_instant(self.next_slot)
+                            .expect("could not estimate block slot instant");
+                        continue;
+                    }
                     if let Some(end) = self.config.end_timestamp {
                         // The testnet has ended. Will be removed for mainnet.
                         if self.next_instant > end.estimate_instant().unwrap() {
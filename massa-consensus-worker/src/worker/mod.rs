@@ -1,7 +1,7 @@
 use massa_channel::{receiver::MassaReceiver, MassaChannel};
 use massa_consensus_exports::{
-    bootstrapable_graph::BootstrapableGraph, ConsensusChannels, ConsensusConfig,
-    ConsensusController, ConsensusManager,
+    bootstrapable_graph::BootstrapableGraph, command_timing::ConsensusCommandTiming,
+    ConsensusChannels, ConsensusConfig, ConsensusController, ConsensusManager,
 };
 use massa_metrics::MassaMetrics;
 use massa_models::block_id::BlockId;
@@ -9,9 +9,11 @@ use massa_models::clique::Clique;
 use massa_models::config::CHANNEL_SIZE;
 use massa_models::prehash::PreHashSet;
 use massa_models::slot::Slot;
+use massa_models::timeslots::SlotClock;
 use massa_storage::Storage;
-use massa_time::MassaTime;
-use parking_lot::RwLock;
+use massa_time::{Clock, MassaTime, RealClock};
+use parking_lot::{Mutex, RwLock};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::thread;
 use std::time::Instant;
@@ -29,12 +31,20 @@ pub struct ConsensusWorker {
     config: ConsensusConfig,
     /// State shared with the controller
     shared_state: Arc<RwLock<ConsensusState>>,
+    /// Conversions between the current time and the slots
+    slot_clock: SlotClock,
     /// Previous slot.
     previous_slot: Option<Slot>,
     /// Next slot
     next_slot: Slot,
     /// Next slot instant
     next_instant: Instant,
+    /// Command received while coalescing block registrations, to execute before receiving new ones
+    pending_command: Option<ConsensusCommand>,
+    /// Metrics of the node, the commands are only timed when they are enabled
+    massa_metrics: MassaMetrics,
+    /// Timings of the last commands executed, shared with the controller
+    command_timings: Arc<Mutex<VecDeque<ConsensusCommandTiming>>>,
 }
 
 mod init;
@@ -57,6 +67,26 @@ pub fn start_consensus_worker(
     init_graph: Option<BootstrapableGraph>,
     storage: Storage,
     massa_metrics: MassaMetrics,
+) -> (Box<dyn ConsensusController>, Box<dyn ConsensusManager>) {
+    start_consensus_worker_with_clock(
+        config,
+        channels,
+        init_graph,
+        storage,
+        massa_metrics,
+        Arc::new(RealClock),
+    )
+}
+
+/// Create a new consensus worker thread that follows the slots with `clock` instead of the system time.
+/// See `start_consensus_worker` for the other arguments and the return value.
+pub fn start_consensus_worker_with_clock(
+    config: ConsensusConfig,
+    channels: ConsensusChannels,
+    init_graph: Option<BootstrapableGraph>,
+    storage: Storage,
+    massa_metrics: MassaMetrics,
+    clock: Arc<dyn Clock>,
 ) -> (Box<dyn ConsensusController>, Box<dyn ConsensusManager>) {
     let (tx, rx) = MassaChannel::new("consensus_command".to_string(), Some(CHANNEL_SIZE));
     // desync detection timespan
@@ -64,6 +94,7 @@ pub fn start_consensus_worker(
     let stats_desync_detection_timespan =
         config.t0.checked_mul(config.periods_per_cycle * 2).unwrap();
     let broadcasts = channels.broadcasts.clone();
+    let command_timings = Arc::new(Mutex::new(VecDeque::new()));
     let shared_state = Arc::new(RwLock::new(ConsensusState {
         storage: storage.clone(),
         config: config.clone(),
@@ -96,12 +127,22 @@ pub fn start_consensus_worker(
         ),
         prev_blockclique: Default::default(),
         nonfinal_active_blocks_per_slot: Default::default(),
-        massa_metrics,
+        massa_metrics: massa_metrics.clone(),
+        graph_revision: 0,
     }));
 
     let shared_state_cloned = shared_state.clone();
-    let mut consensus_worker =
-        ConsensusWorker::new(config.clone(), rx, shared_state_cloned, init_graph, storage).unwrap();
+    let mut consensus_worker = ConsensusWorker::new(
+        config.clone(),
+        rx,
+        shared_state_cloned,
+        init_graph,
+        storage,
+        massa_metrics,
+        command_timings.clone(),
+        clock,
+    )
+    .unwrap();
 
     let consensus_thread = thread::Builder::new()
         .name("consensus worker".into())
@@ -118,6 +159,7 @@ pub fn start_consensus_worker(
         shared_state,
         bootstrap_part_size,
         config.broadcast_enabled,
+        command_timings,
     );
 
     (Box::new(controller), Box::new(manager))
@@ -7,6 +7,8 @@ pub const VERSIONING_CF: &str = "versioning";
 pub const STATE_HASH_BYTES_LEN: usize = 512;
 pub const STATE_HASH_KEY: &[u8; 1] = b"h";
 pub const STATE_HASH_INITIAL_BYTES: &[u8; STATE_HASH_BYTES_LEN] = &[0; STATE_HASH_BYTES_LEN];
+// The hash of each component is stored under this key prefix followed by the component key prefix
+pub const STATE_COMPONENT_HASH_KEY_PREFIX: &[u8; 2] = b"h/";
 
 // Change_id
 pub const CHANGE_ID_KEY: &[u8; 1] = b"c";
@@ -29,6 +31,20 @@ pub const LEDGER_PREFIX: &str = "ledger/";
 pub const MIP_STORE_PREFIX: &str = "versioning/";
 pub const MIP_STORE_STATS_PREFIX: &str = "versioning_stats/";
 pub const EXECUTION_TRAIL_HASH_PREFIX: &str = "execution_trail_hash/";
+pub const SLASHING_STATS_PREFIX: &str = "slashing_stats/";
+// Components of the state column hashed on their own, by key prefix.
+// The slashing stats are left out: they are only stored in the versioning column.
+pub const STATE_HASH_COMPONENT_PREFIXES: [&str; 9] = [
+    LEDGER_PREFIX,
+    ASYNC_POOL_PREFIX,
+    CYCLE_HISTORY_PREFIX,
+    DEFERRED_CREDITS_PREFIX,
+    EXECUTED_OPS_PREFIX,
+    EXECUTED_DENUNCIATIONS_PREFIX,
+    EXECUTION_TRAIL_HASH_PREFIX,
+    MIP_STORE_PREFIX,
+    MIP_STORE_STATS_PREFIX,
+];
 
 // Async Pool
 pub const MESSAGE_DESER_ERROR: &str = "critical: message deserialization failed";
@@ -51,6 +67,8 @@ pub const EXECUTED_DENUNCIATIONS_INDEX_DESER_ERROR: &str =
     "critical: executed_denunciations_index deserialization failed";
 pub const EXECUTED_DENUNCIATIONS_INDEX_SER_ERROR: &str =
     "critical: executed_denunciations_index serialization failed";
+pub const SLASHING_STATS_DESER_ERROR: &str = "critical: slashing_stats deserialization failed";
+pub const SLASHING_STATS_SER_ERROR: &str = "critical: slashing_stats serialization failed";
 
 // Ledger
 pub const KEY_DESER_ERROR: &str = "critical: key deserialization failed";
@@ -2,14 +2,16 @@ use crate::{DBBatch, Key, MassaDBError, StreamBatch, Value};
 use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::{error::ModelsError, slot::Slot, streaming_step::StreamingStep};
 use parking_lot::RwLock;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::{fmt::Debug, sync::Arc};
 
-#[cfg(feature = "test-exports")]
-use std::collections::BTreeMap;
-
 pub type ShareableMassaDBController = Arc<RwLock<Box<dyn MassaDBController>>>;
 
+/// Compacts a whole column family of the DB, given its handle name.
+/// It shares the underlying DB and holds no lock on its controller.
+pub type MassaDBCompactor = Box<dyn Fn(&str) -> Result<(), MassaDBError> + Send + Sync>;
+
 /// Controller trait for the MassaDB
 /// TODO: MOCK IT WITH MOCKALL. HAVING LIFETIMES ERRORS WITH AUTO MOCK
 pub trait MassaDBController: Send + Sync + Debug {
@@ -60,9 +62,18 @@ pub trait MassaDBController: Send + Sync + Debug {
     /// Get the current extended state hash of the database
     fn get_xof_db_hash(&self) -> HashXof<HASH_XOF_SIZE_BYTES>;
 
+    /// Get the current extended hash of each component of the state, by key prefix
+    /// (see `STATE_HASH_COMPONENT_PREFIXES`)
+    fn get_xof_component_hashes(&self) -> BTreeMap<&'static str, HashXof<HASH_XOF_SIZE_BYTES>>;
+
     /// Flushes the underlying db.
     fn flush(&self) -> Result<(), MassaDBError>;
 
+    /// Get a compactor exposing RocksDB's "compact_range_cf" function over a whole column family.
+    /// Compaction purges deleted entries from the files on disk, and is I/O intensive:
+    /// run the compactor after releasing the lock on the DB, so that the writes are not held back.
+    fn get_compactor(&self) -> MassaDBCompactor;
+
     /// Write a stream_batch of database entries received from a bootstrap server
     fn write_batch_bootstrap_client(
         &mut self,
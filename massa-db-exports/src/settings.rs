@@ -16,3 +16,18 @@ pub struct MassaDBConfig {
     /// Maximum number of ledger backups to keep
     pub max_ledger_backups: u64,
 }
+
+/// Block cache sizes of the column families of a `MassaDBRaw`, in bytes.
+/// A size of 0 keeps the default block cache of RocksDB.
+///
+/// Each cache is allocated in addition to the memory already used by the node,
+/// and is only filled as blocks of the column family are read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MassaDBCacheSizes {
+    /// cache of the state column family: ledger, async pool, PoS state, executed operations and denunciations
+    pub state_cf: usize,
+    /// cache of the metadata column family: change id and state hash
+    pub metadata_cf: usize,
+    /// cache of the versioning column family: MIP store and the other data kept out of the state hash
+    pub versioning_cf: usize,
+}
@@ -1,7 +1,8 @@
 use massa_db_exports::{
-    DBBatch, Key, MassaDBConfig, MassaDBController, MassaDBError, MassaDirection,
-    MassaIteratorMode, StreamBatch, Value, CF_ERROR, CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY,
-    CHANGE_ID_SER_ERROR, CRUD_ERROR, METADATA_CF, OPEN_ERROR, STATE_CF, STATE_HASH_ERROR,
+    DBBatch, Key, MassaDBCacheSizes, MassaDBCompactor, MassaDBConfig, MassaDBController,
+    MassaDBError, MassaDirection, MassaIteratorMode, StreamBatch, Value, CF_ERROR,
+    CHANGE_ID_DESER_ERROR, CHANGE_ID_KEY, CHANGE_ID_SER_ERROR, CRUD_ERROR, METADATA_CF, OPEN_ERROR,
+    STATE_CF, STATE_COMPONENT_HASH_KEY_PREFIX, STATE_HASH_COMPONENT_PREFIXES, STATE_HASH_ERROR,
     STATE_HASH_INITIAL_BYTES, STATE_HASH_KEY, VERSIONING_CF,
 };
 use massa_hash::{HashXof, HASH_XOF_SIZE_BYTES};
@@ -13,8 +14,8 @@ use massa_models::{
 use massa_serialization::{DeserializeError, Deserializer, Serializer, U64VarIntSerializer};
 use parking_lot::Mutex;
 use rocksdb::{
-    checkpoint::Checkpoint, ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch,
-    DB,
+    checkpoint::Checkpoint, BlockBasedOptions, Cache, ColumnFamilyDescriptor, Direction,
+    IteratorMode, Options, WriteBatch, DB,
 };
 use std::path::PathBuf;
 use std::{
@@ -362,10 +363,12 @@ where
         let handle_versioning = self.db.cf_handle(VERSIONING_CF).expect(CF_ERROR);
 
         let mut current_xor_hash = self.get_xof_db_hash();
+        let mut component_xor_hashes = self.get_xof_component_hashes();
 
         *self.current_batch.lock() = WriteBatch::default();
 
         for (key, value) in changes.iter() {
+            let mut xor_hash_change = HashXof(*STATE_HASH_INITIAL_BYTES);
             if let Some(value) = value {
                 self.current_batch.lock().put_cf(handle_state, key, value);
 
@@ -373,10 +376,10 @@ where
                 if let Ok(Some(prev_value)) = self.db.get_cf(handle_state, key) {
                     let prev_hash =
                         HashXof::compute_from_tuple(&[key.as_slice(), prev_value.as_slice()]);
-                    current_xor_hash ^= prev_hash;
+                    xor_hash_change ^= prev_hash;
                 };
                 let new_hash = HashXof::compute_from_tuple(&[key.as_slice(), value.as_slice()]);
-                current_xor_hash ^= new_hash;
+                xor_hash_change ^= new_hash;
             } else {
                 self.current_batch.lock().delete_cf(handle_state, key);
 
@@ -384,9 +387,19 @@ where
                 if let Ok(Some(prev_value)) = self.db.get_cf(handle_state, key) {
                     let prev_hash =
                         HashXof::compute_from_tuple(&[key.as_slice(), prev_value.as_slice()]);
-                    current_xor_hash ^= prev_hash;
+                    xor_hash_change ^= prev_hash;
                 };
             }
+
+            // The same change applies to the hash of the component of the key
+            current_xor_hash ^= xor_hash_change;
+            if let Some(component_xor_hash) = component_xor_hashes
+                .iter_mut()
+                .find(|(prefix, _)| key.starts_with(prefix.as_bytes()))
+                .map(|(_, component_xor_hash)| component_xor_hash)
+            {
+                *component_xor_hash ^= xor_hash_change;
+            }
         }
 
         // in versioning_changes, we have the data that we do not want to include in hash
@@ -405,10 +418,17 @@ where
             self.set_change_id_to_batch(change_id);
         }
 
-        // Update the hash entry
+        // Update the hash entries
         self.current_batch
             .lock()
             .put_cf(handle_metadata, STATE_HASH_KEY, current_xor_hash.0);
+        for (prefix, component_xor_hash) in component_xor_hashes {
+            self.current_batch.lock().put_cf(
+                handle_metadata,
+                component_hash_key(prefix),
+                component_xor_hash.0,
+            );
+        }
 
         {
             let mut current_batch_guard = self.current_batch.lock();
@@ -571,13 +591,76 @@ where
             .as_deref()
             .map(|state_hash_bytes| HashXof(state_hash_bytes.try_into().expect(STATE_HASH_ERROR)))
     }
+
+    /// Get the current XOF hash of each component of the state, by key prefix
+    pub fn get_xof_component_hashes(&self) -> BTreeMap<&'static str, HashXof<HASH_XOF_SIZE_BYTES>> {
+        let db = &self.db;
+        let handle = db.cf_handle(METADATA_CF).expect(CF_ERROR);
+
+        STATE_HASH_COMPONENT_PREFIXES
+            .iter()
+            .map(|prefix| {
+                let component_hash = db
+                    .get_cf(handle, component_hash_key(prefix))
+                    .expect(CRUD_ERROR)
+                    .as_deref()
+                    .map(|hash_bytes| HashXof(hash_bytes.try_into().expect(STATE_HASH_ERROR)))
+                    .unwrap_or(HashXof(*STATE_HASH_INITIAL_BYTES));
+                (*prefix, component_hash)
+            })
+            .collect()
+    }
+
+    /// Compute the hashes of the components that a database written by a previous version lacks.
+    /// They are then updated along with the state hash by each write.
+    fn init_xof_component_hashes(&self) {
+        // an empty database starts with the initial hashes
+        if self.get_xof_db_hash_opt().is_none() {
+            return;
+        }
+
+        let db = &self.db;
+        let handle_state = db.cf_handle(STATE_CF).expect(CF_ERROR);
+        let handle_metadata = db.cf_handle(METADATA_CF).expect(CF_ERROR);
+
+        for prefix in STATE_HASH_COMPONENT_PREFIXES {
+            let hash_key = component_hash_key(prefix);
+            if db
+                .get_cf(handle_metadata, &hash_key)
+                .expect(CRUD_ERROR)
+                .is_some()
+            {
+                continue;
+            }
+
+            let mut component_xor_hash = HashXof(*STATE_HASH_INITIAL_BYTES);
+            for (key, value) in db.prefix_iterator_cf(handle_state, prefix).flatten() {
+                if !key.starts_with(prefix.as_bytes()) {
+                    break;
+                }
+                component_xor_hash ^= HashXof::compute_from_tuple(&[&key, &value]);
+            }
+            db.put_cf(handle_metadata, hash_key, component_xor_hash.0)
+                .expect(CRUD_ERROR);
+        }
+    }
+}
+
+/// Key of the hash of a state component in the metadata column
+fn component_hash_key(prefix: &str) -> Vec<u8> {
+    [&STATE_COMPONENT_HASH_KEY_PREFIX[..], prefix.as_bytes()].concat()
 }
 
 impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
     /// Returns a new `MassaDB` instance
     pub fn new(config: MassaDBConfig) -> Self {
+        Self::new_with_cache_sizes(config, MassaDBCacheSizes::default())
+    }
+
+    /// Returns a new `MassaDB` instance whose column families use block caches of the given sizes
+    pub fn new_with_cache_sizes(config: MassaDBConfig, cache_sizes: MassaDBCacheSizes) -> Self {
         let db_opts = Self::default_db_opts();
-        Self::new_with_options(config, db_opts).expect(OPEN_ERROR)
+        Self::new_with_options_and_cache_sizes(config, db_opts, cache_sizes).expect(OPEN_ERROR)
     }
 
     pub fn default_db_opts() -> Options {
@@ -588,15 +671,38 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         db_opts
     }
 
+    /// Options of a column family, using a dedicated block cache of `cache_size` bytes if not 0
+    fn cf_opts(cache_size: usize) -> Options {
+        let mut cf_opts = Options::default();
+        if cache_size > 0 {
+            let mut table_opts = BlockBasedOptions::default();
+            table_opts.set_block_cache(&Cache::new_lru_cache(cache_size));
+            cf_opts.set_block_based_table_factory(&table_opts);
+        }
+        cf_opts
+    }
+
     /// Returns a new `MassaDB` instance given a config and RocksDB options
     fn new_with_options(config: MassaDBConfig, db_opts: Options) -> Result<Self, rocksdb::Error> {
+        Self::new_with_options_and_cache_sizes(config, db_opts, MassaDBCacheSizes::default())
+    }
+
+    /// Returns a new `MassaDB` instance given a config, RocksDB options and column family cache sizes
+    fn new_with_options_and_cache_sizes(
+        config: MassaDBConfig,
+        db_opts: Options,
+        cache_sizes: MassaDBCacheSizes,
+    ) -> Result<Self, rocksdb::Error> {
         let db = DB::open_cf_descriptors(
             &db_opts,
             &config.path,
             vec![
-                ColumnFamilyDescriptor::new(STATE_CF, Options::default()),
-                ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
-                ColumnFamilyDescriptor::new(VERSIONING_CF, Options::default()),
+                ColumnFamilyDescriptor::new(STATE_CF, Self::cf_opts(cache_sizes.state_cf)),
+                ColumnFamilyDescriptor::new(METADATA_CF, Self::cf_opts(cache_sizes.metadata_cf)),
+                ColumnFamilyDescriptor::new(
+                    VERSIONING_CF,
+                    Self::cf_opts(cache_sizes.versioning_cf),
+                ),
             ],
         )?;
 
@@ -624,6 +730,7 @@ impl RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
                 thread: 0,
             });
         }
+        massa_db.init_xof_component_hashes();
 
         Ok(massa_db)
     }
@@ -795,6 +902,11 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
         self.get_xof_db_hash()
     }
 
+    /// Get the current extended hash of each component of the state, by key prefix
+    fn get_xof_component_hashes(&self) -> BTreeMap<&'static str, HashXof<HASH_XOF_SIZE_BYTES>> {
+        self.get_xof_component_hashes()
+    }
+
     /// Get the current change_id attached to the database.
     fn get_change_id(&self) -> Result<Slot, ModelsError> {
         self.get_change_id()
@@ -812,6 +924,19 @@ impl MassaDBController for RawMassaDB<Slot, SlotSerializer, SlotDeserializer> {
             .map_err(|e| MassaDBError::RocksDBError(format!("{:?}", e)))
     }
 
+    /// Get a compactor exposing RocksDB's "compact_range_cf" function over a whole column family.
+    fn get_compactor(&self) -> MassaDBCompactor {
+        let db = self.db.clone();
+        Box::new(move |handle_cf| {
+            let handle = db.cf_handle(handle_cf).ok_or_else(|| {
+                MassaDBError::RocksDBError(format!("unknown column family: {}", handle_cf))
+            })?;
+
+            db.compact_range_cf(handle, None::<&[u8]>, None::<&[u8]>);
+            Ok(())
+        })
+    }
+
     /// Write a stream_batch of database entries received from a bootstrap server
     fn write_batch_bootstrap_client(
         &mut self,
@@ -877,6 +1002,7 @@ mod test {
 
     use assert_matches::assert_matches;
     use massa_db_exports::MassaDBError::TimeError;
+    use massa_db_exports::{ASYNC_POOL_PREFIX, EXECUTED_OPS_PREFIX, LEDGER_PREFIX};
     use parking_lot::RwLock;
     use tempfile::tempdir;
 
@@ -938,6 +1064,46 @@ mod test {
         assert!(db2.err().unwrap().into_string().contains("IO error"));
     }
 
+    #[test]
+    fn test_cache_sizes() {
+        // Data written with dedicated column family caches is read back after a reopening
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+            max_ledger_backups: 10,
+        };
+        let cache_sizes = MassaDBCacheSizes {
+            state_cf: 1 << 20,
+            metadata_cf: 0,
+            versioning_cf: 1 << 16,
+        };
+        let db = Arc::new(RwLock::new(Box::new(MassaDB::new_with_cache_sizes(
+            db_config.clone(),
+            cache_sizes,
+        ))
+            as Box<(dyn MassaDBController + 'static)>));
+
+        let mut batch = DBBatch::new();
+        batch.insert(vec![1, 2, 3], Some(vec![4, 5, 6]));
+        let mut versioning_batch = DBBatch::new();
+        versioning_batch.insert(vec![10, 20, 30], Some(vec![40, 50, 60]));
+        db.write()
+            .write_batch(batch.clone(), versioning_batch.clone(), None);
+        assert_eq!(dump_column_opt(db.clone(), "state"), batch);
+        drop(db);
+
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        assert_eq!(dump_column_opt(db.clone(), "state"), batch);
+        assert_eq!(dump_column_opt(db, "versioning"), versioning_batch);
+    }
+
     #[test]
     fn test_basics_1() {
         // 1- Init a db + check initial hash
@@ -1022,6 +1188,66 @@ mod test {
         assert!(dump_column(db.clone(), "versioning").is_empty());
     }
 
+    #[test]
+    fn test_component_hashes() {
+        // 1- Write keys of two components, and check their hashes
+        // 2- Check that the component hashes make up the state hash
+        // 3- Drop the component hashes as in a database of a previous version,
+        //    and check that they are computed again when opening it
+
+        let temp_dir_db = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir_db.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count: THREAD_COUNT,
+            max_ledger_backups: 10,
+        };
+        let mut db = MassaDB::new(db_config.clone());
+
+        let ledger_key = [LEDGER_PREFIX.as_bytes(), b"key"].concat();
+        let async_pool_key = [ASYNC_POOL_PREFIX.as_bytes(), b"key"].concat();
+        let mut batch = DBBatch::new();
+        batch.insert(ledger_key.clone(), Some(vec![1]));
+        batch.insert(async_pool_key.clone(), Some(vec![2]));
+        db.write_batch(batch, DBBatch::new(), None);
+        let mut batch = DBBatch::new();
+        batch.insert(ledger_key.clone(), Some(vec![3]));
+        db.write_batch(batch, DBBatch::new(), None);
+
+        let component_hashes = db.get_xof_component_hashes();
+        assert_eq!(
+            component_hashes[LEDGER_PREFIX],
+            HashXof::compute_from_tuple(&[ledger_key.as_slice(), &[3]])
+        );
+        assert_eq!(
+            component_hashes[ASYNC_POOL_PREFIX],
+            HashXof::compute_from_tuple(&[async_pool_key.as_slice(), &[2]])
+        );
+        assert_eq!(
+            component_hashes[EXECUTED_OPS_PREFIX],
+            HashXof(*STATE_HASH_INITIAL_BYTES)
+        );
+
+        let combined_hash = component_hashes.values().fold(
+            HashXof(*STATE_HASH_INITIAL_BYTES),
+            |hash, component_hash| hash ^ *component_hash,
+        );
+        assert_eq!(combined_hash, db.get_xof_db_hash());
+
+        let handle_metadata = db.db.cf_handle(METADATA_CF).expect(CF_ERROR);
+        for prefix in STATE_HASH_COMPONENT_PREFIXES {
+            db.db
+                .delete_cf(handle_metadata, component_hash_key(prefix))
+                .expect(CRUD_ERROR);
+        }
+        drop(db);
+
+        let db = MassaDB::new(db_config);
+        assert_eq!(db.get_xof_component_hashes(), component_hashes);
+    }
+
     #[test]
     fn test_basics_2() {
         // 1- Init a db + check initial hash
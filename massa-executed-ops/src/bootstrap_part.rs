@@ -0,0 +1,115 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Checks of the executed operations and denunciations streamed by a bootstrap server,
+//! before they are written to the final state.
+
+use displaydoc::Display;
+use massa_db_exports::{Key, StreamBatch, Value};
+use massa_models::{
+    denunciation::DenunciationIndex, operation::OperationId, slot::Slot,
+    streaming_step::StreamingStep,
+};
+use thiserror::Error;
+
+/// Invalid entry in a part of the final state streamed by a bootstrap server
+#[non_exhaustive]
+#[derive(Display, Error, Debug, Clone, PartialEq, Eq)]
+pub enum BootstrapPartError {
+    /// entry {key:?} cannot be deserialized
+    InvalidEntry {
+        /// key of the entry
+        key: Key,
+    },
+    /// executed operation {op_id} is kept until slot {slot}, out of the range {min}..={max} allowed at final slot {final_slot}
+    OperationSlotOutOfRange {
+        /// operation of the entry
+        op_id: OperationId,
+        /// slot until which the operation is kept
+        slot: Slot,
+        /// lowest slot not pruned at `final_slot`
+        min: Slot,
+        /// highest slot an operation executed at `final_slot` can be kept until
+        max: Slot,
+        /// final slot declared by the server
+        final_slot: Slot,
+    },
+    /// executed denunciation {de_idx:?} targets slot {slot}, out of the range {min}..={max} allowed at final slot {final_slot}
+    DenunciationSlotOutOfRange {
+        /// denunciation of the entry
+        de_idx: DenunciationIndex,
+        /// slot targeted by the denunciation
+        slot: Slot,
+        /// lowest slot not pruned at `final_slot`
+        min: Slot,
+        /// latest slot a denunciation can target, `final_slot` itself
+        max: Slot,
+        /// final slot declared by the server
+        final_slot: Slot,
+    },
+    /// new entry {key:?} does not come after the streaming cursor {cursor:?}
+    KeyBeforeCursor {
+        /// key of the entry
+        key: Key,
+        /// streaming cursor of the client
+        cursor: StreamingStep<Key>,
+    },
+    /// update of entry {key:?} was never streamed, the streaming cursor is {cursor:?}
+    UpdateAfterCursor {
+        /// key of the entry
+        key: Key,
+        /// streaming cursor of the client
+        cursor: StreamingStep<Key>,
+    },
+}
+
+/// Entries of `part` whose key starts with `prefix`, deletions excepted.
+///
+/// The new elements must come after `cursor`, and the updates must be on keys already streamed.
+pub(crate) fn prefixed_part_entries<'a>(
+    part: &'a StreamBatch<Slot>,
+    cursor: &StreamingStep<Key>,
+    prefix: &str,
+) -> Result<Vec<(&'a Key, &'a Value)>, BootstrapPartError> {
+    let prefix = prefix.as_bytes();
+    let mut entries = Vec::new();
+    for (key, value) in part
+        .new_elements
+        .range(prefix.to_vec()..)
+        .take_while(|(key, _)| key.starts_with(prefix))
+    {
+        let after_cursor = match cursor {
+            StreamingStep::Started => true,
+            StreamingStep::Ongoing(last_key) => key > last_key,
+            StreamingStep::Finished(_) => false,
+        };
+        if !after_cursor {
+            return Err(BootstrapPartError::KeyBeforeCursor {
+                key: key.clone(),
+                cursor: cursor.clone(),
+            });
+        }
+        entries.push((key, value));
+    }
+    for (key, value) in part
+        .updates_on_previous_elements
+        .range(prefix.to_vec()..)
+        .take_while(|(key, _)| key.starts_with(prefix))
+    {
+        let streamed = match cursor {
+            StreamingStep::Started => false,
+            StreamingStep::Ongoing(last_key) => key <= last_key,
+            StreamingStep::Finished(_) => true,
+        };
+        if !streamed {
+            return Err(BootstrapPartError::UpdateAfterCursor {
+                key: key.clone(),
+                cursor: cursor.clone(),
+            });
+        }
+        // deletions carry no slot to check
+        if let Some(value) = value {
+            entries.push((key, value));
+        }
+    }
+    Ok(entries)
+}
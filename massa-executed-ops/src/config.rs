@@ -6,6 +6,8 @@ pub struct ExecutedOpsConfig {
     pub thread_count: u8,
     /// Number of extra periods to keep executed denunciations
     pub keep_executed_history_extra_periods: u64,
+    /// Number of periods during which an operation can be executed
+    pub operation_validity_periods: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -18,4 +20,6 @@ pub struct ExecutedDenunciationsConfig {
     pub endorsement_count: u32,
     /// Number of extra periods to keep executed denunciations
     pub keep_executed_history_extra_periods: u64,
+    /// Number of periods per cycle, to aggregate the slashing statistics
+    pub periods_per_cycle: u64,
 }
@@ -2,19 +2,31 @@
 
 //! This file defines a structure to list and prune previously executed denunciations.
 //! Used to detect denunciation reuse.
-
-use crate::{ExecutedDenunciationsChanges, ExecutedDenunciationsConfig};
+//!
+//! It also aggregates the slashing of the executed denunciations by cycle of their target slot.
+//! The aggregates are derived from the execution rather than from the final state, so they are
+//! stored in the versioning column, out of the final state hash.
+
+use crate::{
+    bootstrap_part::prefixed_part_entries, BootstrapPartError, CycleSlashingStats,
+    CycleSlashingStatsDeserializer, CycleSlashingStatsSerializer, ExecutedDenunciationsChanges,
+    ExecutedDenunciationsConfig, ExecutedDenunciationsSlashings,
+};
 use massa_db_exports::{
-    DBBatch, ShareableMassaDBController, CRUD_ERROR, EXECUTED_DENUNCIATIONS_INDEX_DESER_ERROR,
-    EXECUTED_DENUNCIATIONS_INDEX_SER_ERROR, EXECUTED_DENUNCIATIONS_PREFIX, STATE_CF,
+    DBBatch, Key, ShareableMassaDBController, StreamBatch, CRUD_ERROR,
+    EXECUTED_DENUNCIATIONS_INDEX_DESER_ERROR, EXECUTED_DENUNCIATIONS_INDEX_SER_ERROR,
+    EXECUTED_DENUNCIATIONS_PREFIX, SLASHING_STATS_DESER_ERROR, SLASHING_STATS_PREFIX,
+    SLASHING_STATS_SER_ERROR, STATE_CF, VERSIONING_CF,
 };
 use massa_models::denunciation::Denunciation;
 use massa_models::{
     denunciation::{DenunciationIndex, DenunciationIndexDeserializer, DenunciationIndexSerializer},
     slot::Slot,
+    streaming_step::StreamingStep,
 };
 use massa_serialization::{DeserializeError, Deserializer, Serializer};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ops::RangeInclusive;
 
 /// Denunciation index key formatting macro
 #[macro_export]
@@ -24,6 +36,11 @@ macro_rules! denunciation_index_key {
     };
 }
 
+/// Slashing statistics key of a cycle
+fn slashing_stats_key(cycle: u64) -> Vec<u8> {
+    [SLASHING_STATS_PREFIX.as_bytes(), &cycle.to_be_bytes()].concat()
+}
+
 /// A structure to list and prune previously executed denunciations
 #[derive(Clone)]
 pub struct ExecutedDenunciations {
@@ -37,6 +54,12 @@ pub struct ExecutedDenunciations {
     denunciation_index_serializer: DenunciationIndexSerializer,
     /// for rocksdb deserialization
     denunciation_index_deserializer: DenunciationIndexDeserializer,
+    /// slashing of the executed denunciations, by cycle of their target slot
+    pub cycle_slashing_stats: BTreeMap<u64, CycleSlashingStats>,
+    /// for rocksdb serialization of the slashing statistics
+    slashing_stats_serializer: CycleSlashingStatsSerializer,
+    /// for rocksdb deserialization of the slashing statistics
+    slashing_stats_deserializer: CycleSlashingStatsDeserializer,
 }
 
 impl ExecutedDenunciations {
@@ -44,12 +67,22 @@ impl ExecutedDenunciations {
     pub fn new(config: ExecutedDenunciationsConfig, db: ShareableMassaDBController) -> Self {
         let denunciation_index_deserializer =
             DenunciationIndexDeserializer::new(config.thread_count, config.endorsement_count);
+        // one block header and one endorsement denunciation per endorsement per slot
+        let max_denunciations_per_cycle = config
+            .periods_per_cycle
+            .saturating_mul(config.thread_count as u64)
+            .saturating_mul(1 + config.endorsement_count as u64);
         Self {
             config,
             db,
             sorted_denunciations: Default::default(),
             denunciation_index_serializer: DenunciationIndexSerializer::new(),
             denunciation_index_deserializer,
+            cycle_slashing_stats: Default::default(),
+            slashing_stats_serializer: CycleSlashingStatsSerializer::new(),
+            slashing_stats_deserializer: CycleSlashingStatsDeserializer::new(
+                max_denunciations_per_cycle,
+            ),
         }
     }
 
@@ -83,6 +116,25 @@ impl ExecutedDenunciations {
                     new
                 });
         }
+
+        self.cycle_slashing_stats.clear();
+        for (serialized_key, serialized_stats) in
+            db.prefix_iterator_cf(VERSIONING_CF, SLASHING_STATS_PREFIX.as_bytes())
+        {
+            if !serialized_key.starts_with(SLASHING_STATS_PREFIX.as_bytes()) {
+                break;
+            }
+            let cycle = u64::from_be_bytes(
+                serialized_key[SLASHING_STATS_PREFIX.len()..]
+                    .try_into()
+                    .expect(SLASHING_STATS_DESER_ERROR),
+            );
+            let (_, stats) = self
+                .slashing_stats_deserializer
+                .deserialize::<DeserializeError>(&serialized_stats)
+                .expect(SLASHING_STATS_DESER_ERROR);
+            self.cycle_slashing_stats.insert(cycle, stats);
+        }
     }
 
     /// Reset the executed denunciations
@@ -92,6 +144,7 @@ impl ExecutedDenunciations {
         {
             let mut db = self.db.write();
             db.delete_prefix(EXECUTED_DENUNCIATIONS_PREFIX, STATE_CF, None);
+            db.delete_prefix(SLASHING_STATS_PREFIX, VERSIONING_CF, None);
         }
 
         self.recompute_sorted_denunciations();
@@ -111,14 +164,52 @@ impl ExecutedDenunciations {
             .is_some()
     }
 
+    /// Number of executed denunciations recorded, the expired ones being pruned
+    pub fn count(&self) -> usize {
+        self.sorted_denunciations.values().map(HashSet::len).sum()
+    }
+
+    /// Slashing statistics of the cycles of `cycles` having executed denunciations
+    pub fn get_cycle_slashing_stats(
+        &self,
+        cycles: RangeInclusive<u64>,
+    ) -> BTreeMap<u64, CycleSlashingStats> {
+        if cycles.is_empty() {
+            return BTreeMap::new();
+        }
+        self.cycle_slashing_stats
+            .range(cycles)
+            .map(|(cycle, stats)| (*cycle, stats.clone()))
+            .collect()
+    }
+
     /// Apply speculative operations changes to the final executed denunciations state
+    ///
+    /// # Arguments
+    /// * `changes`: the executed denunciations
+    /// * `slashings`: the slashing caused by the executed denunciations, for the statistics
+    /// * `slot`: the final slot
+    /// * `batch`: the operation batch of the final state
+    /// * `versioning_batch`: the operation batch of the data kept out of the final state hash
     pub fn apply_changes_to_batch(
         &mut self,
         changes: ExecutedDenunciationsChanges,
+        slashings: &ExecutedDenunciationsSlashings,
         slot: Slot,
         batch: &mut DBBatch,
+        versioning_batch: &mut DBBatch,
     ) {
+        let mut updated_cycles = BTreeSet::new();
         for de_idx in changes {
+            let cycle = de_idx.get_slot().get_cycle(self.config.periods_per_cycle);
+            let stats = self.cycle_slashing_stats.entry(cycle).or_default();
+            stats.denunciation_count = stats.denunciation_count.saturating_add(1);
+            if let Some(slashing) = slashings.get(&de_idx) {
+                stats.slashed_rolls = stats.slashed_rolls.saturating_add(slashing.slashed_rolls);
+                stats.slashed_addresses.insert(slashing.address);
+            }
+            updated_cycles.insert(cycle);
+
             self.put_entry(&de_idx, batch);
             self.sorted_denunciations
                 .entry(*de_idx.get_slot())
@@ -132,16 +223,27 @@ impl ExecutedDenunciations {
                 });
         }
 
-        self.prune_to_batch(slot, batch);
+        self.prune_to_batch(slot, batch, versioning_batch);
+
+        for cycle in updated_cycles {
+            if let Some(stats) = self.cycle_slashing_stats.get(&cycle) {
+                self.put_slashing_stats(cycle, stats, versioning_batch);
+            }
+        }
     }
 
-    /// Prune all denunciations that have expired, assuming the given slot is final
-    fn prune_to_batch(&mut self, slot: Slot, batch: &mut DBBatch) {
+    /// Number of periods after which an executed denunciation is pruned
+    fn effective_expiry_periods(&self) -> u64 {
         // Force-keep `keep_executed_history_extra_periods` for API polling safety
-        let effective_expiry_periods = self
-            .config
+        self.config
             .denunciation_expire_periods
-            .saturating_add(self.config.keep_executed_history_extra_periods);
+            .saturating_add(self.config.keep_executed_history_extra_periods)
+    }
+
+    /// Prune all denunciations that have expired, assuming the given slot is final.
+    /// The slashing statistics of a cycle are pruned along with its last denunciation.
+    fn prune_to_batch(&mut self, slot: Slot, batch: &mut DBBatch, versioning_batch: &mut DBBatch) {
+        let effective_expiry_periods = self.effective_expiry_periods();
         let mut drained: HashSet<DenunciationIndex> = Default::default();
         self.sorted_denunciations.retain(|de_idx_slot, de_idx| {
             if Denunciation::is_expired(
@@ -154,9 +256,55 @@ impl ExecutedDenunciations {
             }
             true
         });
+        let mut drained_cycles = BTreeSet::new();
         for de_idx in drained {
+            drained_cycles.insert(de_idx.get_slot().get_cycle(self.config.periods_per_cycle));
             self.delete_entry(&de_idx, batch);
         }
+        for cycle in drained_cycles {
+            let cycle_slots = Slot::new(cycle.saturating_mul(self.config.periods_per_cycle), 0)
+                ..Slot::new(
+                    cycle
+                        .saturating_add(1)
+                        .saturating_mul(self.config.periods_per_cycle),
+                    0,
+                );
+            if self
+                .sorted_denunciations
+                .range(cycle_slots)
+                .next()
+                .is_none()
+                && self.cycle_slashing_stats.remove(&cycle).is_some()
+            {
+                self.db
+                    .read()
+                    .delete_key(versioning_batch, slashing_stats_key(cycle));
+            }
+        }
+    }
+
+    /// Add the slashing statistics of a cycle to the DB
+    ///
+    /// # Arguments
+    /// * `cycle`
+    /// * `stats`
+    /// * `versioning_batch`: the given operation batch to update
+    fn put_slashing_stats(
+        &self,
+        cycle: u64,
+        stats: &CycleSlashingStats,
+        versioning_batch: &mut DBBatch,
+    ) {
+        let mut serialized_stats = Vec::new();
+        self.slashing_stats_serializer
+            .serialize(stats, &mut serialized_stats)
+            .expect(SLASHING_STATS_SER_ERROR);
+
+        self.db.read().put_or_update_entry_value(
+            versioning_batch,
+            slashing_stats_key(cycle),
+            &serialized_stats,
+        );
     }
 
     /// Add a denunciation_index to the DB
@@ -191,42 +339,101 @@ impl ExecutedDenunciations {
         db.delete_key(batch, denunciation_index_key!(serialized_de_idx));
     }
 
-    /// Deserializes the key and value, useful after bootstrap
-    pub fn is_key_value_valid(&self, serialized_key: &[u8], serialized_value: &[u8]) -> bool {
-        if !serialized_key.starts_with(EXECUTED_DENUNCIATIONS_PREFIX.as_bytes()) {
-            return false;
+    /// Checks the executed denunciations of a part streamed by a bootstrap server, before applying it.
+    ///
+    /// # Arguments
+    /// * `part`: the streamed part of the final state
+    /// * `cursor`: the streaming cursor before the part
+    /// * `final_slot`: the final slot declared by the server for the part
+    ///
+    /// The denunciations must target a slot that is not after `final_slot` and not pruned at `final_slot`.
+    pub fn check_bootstrap_part(
+        &self,
+        part: &StreamBatch<Slot>,
+        cursor: &StreamingStep<Key>,
+        final_slot: Slot,
+    ) -> Result<(), BootstrapPartError> {
+        // denunciations are pruned by period, whatever their thread
+        let min = Slot::new(
+            final_slot
+                .period
+                .saturating_sub(self.effective_expiry_periods()),
+            0,
+        );
+        for (key, value) in prefixed_part_entries(part, cursor, EXECUTED_DENUNCIATIONS_PREFIX)? {
+            let de_idx = self
+                .deserialize_entry(key, value)
+                .ok_or_else(|| BootstrapPartError::InvalidEntry { key: key.clone() })?;
+            let slot = *de_idx.get_slot();
+            if slot < min || slot > final_slot {
+                return Err(BootstrapPartError::DenunciationSlotOutOfRange {
+                    de_idx,
+                    slot,
+                    min,
+                    max: final_slot,
+                    final_slot,
+                });
+            }
         }
+        Ok(())
+    }
 
-        let Ok((rest, _idx)) = self
+    /// Deserializes the denunciation index, if the entry is valid
+    fn deserialize_entry(
+        &self,
+        serialized_key: &[u8],
+        serialized_value: &[u8],
+    ) -> Option<DenunciationIndex> {
+        let (rest, de_idx) = self
             .denunciation_index_deserializer
             .deserialize::<DeserializeError>(
-                &serialized_key[EXECUTED_DENUNCIATIONS_PREFIX.len()..],
+                serialized_key.strip_prefix(EXECUTED_DENUNCIATIONS_PREFIX.as_bytes())?,
             )
-        else {
-            return false;
-        };
-        if !rest.is_empty() {
-            return false;
-        }
+            .ok()?;
+        (rest.is_empty() && serialized_value.is_empty()).then_some(de_idx)
+    }
+
+    /// Deserializes the key and value, useful after bootstrap
+    pub fn is_key_value_valid(&self, serialized_key: &[u8], serialized_value: &[u8]) -> bool {
+        self.deserialize_entry(serialized_key, serialized_value)
+            .is_some()
+    }
 
-        if !serialized_value.is_empty() {
+    /// Deserializes the key and value of slashing statistics, useful after bootstrap
+    pub fn is_slashing_stats_key_value_valid(
+        &self,
+        serialized_key: &[u8],
+        serialized_value: &[u8],
+    ) -> bool {
+        if !serialized_key.starts_with(SLASHING_STATS_PREFIX.as_bytes())
+            || serialized_key.len() != SLASHING_STATS_PREFIX.len() + std::mem::size_of::<u64>()
+        {
             return false;
         }
 
-        true
+        let Ok((rest, _stats)) = self
+            .slashing_stats_deserializer
+            .deserialize::<DeserializeError>(serialized_value)
+        else {
+            return false;
+        };
+        rest.is_empty()
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::DenunciationSlashing;
     use massa_db_exports::{MassaDBConfig, MassaDBController};
     use massa_db_worker::MassaDB;
+    use massa_models::address::Address;
     use massa_models::config::{
         DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
-        THREAD_COUNT,
+        PERIODS_PER_CYCLE, THREAD_COUNT,
     };
     use parking_lot::RwLock;
+    use std::str::FromStr;
     use std::sync::Arc;
     use tempfile::tempdir;
 
@@ -239,6 +446,7 @@ mod test {
             thread_count: THREAD_COUNT,
             endorsement_count: ENDORSEMENT_COUNT,
             keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+            periods_per_cycle: PERIODS_PER_CYCLE,
         };
         // Db init
         let temp_dir = tempdir().expect("Unable to create a temp folder");
@@ -275,13 +483,21 @@ mod test {
         changes.insert(de_idx_1);
         changes.insert(de_idx_2);
         let mut batch = DBBatch::new();
-        exec_de.apply_changes_to_batch(changes, slot_2, &mut batch);
+        let mut versioning_batch = DBBatch::new();
+        exec_de.apply_changes_to_batch(
+            changes,
+            &Default::default(),
+            slot_2,
+            &mut batch,
+            &mut versioning_batch,
+        );
         exec_de
             .db
             .write()
-            .write_batch(batch.clone(), DBBatch::new(), Some(slot_2));
+            .write_batch(batch.clone(), versioning_batch, Some(slot_2));
 
         assert_eq!(exec_de.sorted_denunciations.len(), 1);
+        assert_eq!(exec_de.count(), 1);
         assert_eq!(
             exec_de.sorted_denunciations.get(&slot_2),
             Some(&HashSet::from([de_idx_2]))
@@ -306,5 +522,218 @@ mod test {
         // Reset cache
         exec_de2.reset();
         assert_eq!(exec_de2.sorted_denunciations.len(), 0);
+        assert_eq!(exec_de2.count(), 0);
+    }
+
+    #[test]
+    fn test_exec_de_slashing_stats() {
+        // Check the slashing statistics aggregation / pruning / reload
+
+        let config = ExecutedDenunciationsConfig {
+            denunciation_expire_periods: 2,
+            thread_count: THREAD_COUNT,
+            endorsement_count: ENDORSEMENT_COUNT,
+            keep_executed_history_extra_periods: 0,
+            periods_per_cycle: 4,
+        };
+        let temp_dir = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            max_ledger_backups: 10,
+            thread_count: THREAD_COUNT,
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config.clone())) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let mut exec_de = ExecutedDenunciations::new(config.clone(), db);
+
+        let addr_a =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let addr_b =
+            Address::from_str("AU12htxRWiEm8jDJpJptr6cwEhWNcCSFWstN1MLSa96DDkVM9Y42G").unwrap();
+        let apply = |exec_de: &mut ExecutedDenunciations,
+                     denunciations: Vec<(DenunciationIndex, Address, u64)>,
+                     slot: Slot| {
+            let changes = denunciations.iter().map(|(de_idx, ..)| *de_idx).collect();
+            let slashings = denunciations
+                .iter()
+                .map(|(de_idx, address, slashed_rolls)| {
+                    (
+                        *de_idx,
+                        DenunciationSlashing {
+                            address: *address,
+                            slashed_rolls: *slashed_rolls,
+                        },
+                    )
+                })
+                .collect();
+            let mut batch = DBBatch::new();
+            let mut versioning_batch = DBBatch::new();
+            exec_de.apply_changes_to_batch(
+                changes,
+                &slashings,
+                slot,
+                &mut batch,
+                &mut versioning_batch,
+            );
+            exec_de
+                .db
+                .write()
+                .write_batch(batch, versioning_batch, Some(slot));
+        };
+
+        // cycle 0: 3 denunciations, one of them against an address without rolls left
+        apply(
+            &mut exec_de,
+            vec![
+                (
+                    DenunciationIndex::Endorsement {
+                        slot: Slot::new(1, 0),
+                        index: 0,
+                    },
+                    addr_a,
+                    1,
+                ),
+                (
+                    DenunciationIndex::BlockHeader {
+                        slot: Slot::new(2, 1),
+                    },
+                    addr_a,
+                    2,
+                ),
+                (
+                    DenunciationIndex::Endorsement {
+                        slot: Slot::new(3, 0),
+                        index: 1,
+                    },
+                    addr_b,
+                    0,
+                ),
+            ],
+            Slot::new(3, 0),
+        );
+        // cycle 1: the denunciations of periods 1 and 2 expire, cycle 0 still has one left
+        apply(
+            &mut exec_de,
+            vec![(
+                DenunciationIndex::Endorsement {
+                    slot: Slot::new(5, 0),
+                    index: 0,
+                },
+                addr_b,
+                1,
+            )],
+            Slot::new(5, 0),
+        );
+
+        let stats = exec_de.get_cycle_slashing_stats(0..=1);
+        assert_eq!(
+            stats.get(&0),
+            Some(&CycleSlashingStats {
+                denunciation_count: 3,
+                slashed_rolls: 3,
+                slashed_addresses: BTreeSet::from([addr_a, addr_b]),
+            })
+        );
+        assert_eq!(
+            stats.get(&1),
+            Some(&CycleSlashingStats {
+                denunciation_count: 1,
+                slashed_rolls: 1,
+                slashed_addresses: BTreeSet::from([addr_b]),
+            })
+        );
+        assert!(exec_de.get_cycle_slashing_stats(1..=1).get(&0).is_none());
+
+        // the last denunciation of cycle 0 expires
+        apply(&mut exec_de, vec![], Slot::new(6, 0));
+        let stats = exec_de.get_cycle_slashing_stats(0..=1);
+        assert_eq!(stats.len(), 1);
+        assert!(stats.contains_key(&1));
+
+        let cycle_slashing_stats = exec_de.cycle_slashing_stats.clone();
+        drop(exec_de);
+
+        // the statistics are reloaded from disk
+        let db2 = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let mut exec_de2 = ExecutedDenunciations::new(config, db2);
+        exec_de2.recompute_sorted_denunciations();
+        assert_eq!(exec_de2.cycle_slashing_stats, cycle_slashing_stats);
+
+        exec_de2.reset();
+        assert!(exec_de2.cycle_slashing_stats.is_empty());
+    }
+
+    #[test]
+    fn test_exec_de_bootstrap_part_checks() {
+        let config = ExecutedDenunciationsConfig {
+            denunciation_expire_periods: 2,
+            thread_count: THREAD_COUNT,
+            endorsement_count: ENDORSEMENT_COUNT,
+            keep_executed_history_extra_periods: 1,
+            periods_per_cycle: PERIODS_PER_CYCLE,
+        };
+        let temp_dir = tempdir().expect("Unable to create a temp folder");
+        let db_config = MassaDBConfig {
+            path: temp_dir.path().to_path_buf(),
+            max_history_length: 100,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            max_ledger_backups: 10,
+            thread_count: THREAD_COUNT,
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let exec_de = ExecutedDenunciations::new(config, db);
+
+        // targets from period 7 to slot (10, 1) are kept at final slot (10, 1)
+        let final_slot = Slot::new(10, 1);
+        let part_of = |slot: Slot| {
+            let mut batch = DBBatch::new();
+            exec_de.put_entry(&DenunciationIndex::BlockHeader { slot }, &mut batch);
+            StreamBatch {
+                new_elements: batch
+                    .into_iter()
+                    .map(|(key, value)| (key, value.unwrap()))
+                    .collect(),
+                updates_on_previous_elements: Default::default(),
+                change_id: final_slot,
+            }
+        };
+        for slot in [Slot::new(7, 0), final_slot] {
+            exec_de
+                .check_bootstrap_part(&part_of(slot), &StreamingStep::Started, final_slot)
+                .unwrap();
+        }
+        for slot in [Slot::new(6, THREAD_COUNT - 1), Slot::new(11, 0)] {
+            assert_eq!(
+                exec_de.check_bootstrap_part(&part_of(slot), &StreamingStep::Started, final_slot),
+                Err(BootstrapPartError::DenunciationSlotOutOfRange {
+                    de_idx: DenunciationIndex::BlockHeader { slot },
+                    slot,
+                    min: Slot::new(7, 0),
+                    max: final_slot,
+                    final_slot,
+                })
+            );
+        }
+
+        // new entries must come after the cursor
+        let part = part_of(final_slot);
+        let (key, _) = part.new_elements.first_key_value().unwrap();
+        let cursor = StreamingStep::Finished(Some(key.clone()));
+        assert_eq!(
+            exec_de.check_bootstrap_part(&part, &cursor, final_slot),
+            Err(BootstrapPartError::KeyBeforeCursor {
+                key: key.clone(),
+                cursor: cursor.clone(),
+            })
+        );
     }
 }
@@ -3,15 +3,19 @@
 //! This file defines a structure to list and prune previously executed operations.
 //! Used to detect operation reuse.
 
-use crate::{ops_changes::ExecutedOpsChanges, ExecutedOpsConfig};
+use crate::{
+    bootstrap_part::prefixed_part_entries, ops_changes::ExecutedOpsChanges, BootstrapPartError,
+    ExecutedOpsConfig,
+};
 use massa_db_exports::{
-    DBBatch, ShareableMassaDBController, CRUD_ERROR, EXECUTED_OPS_ID_DESER_ERROR,
+    DBBatch, Key, ShareableMassaDBController, StreamBatch, CRUD_ERROR, EXECUTED_OPS_ID_DESER_ERROR,
     EXECUTED_OPS_ID_SER_ERROR, EXECUTED_OPS_PREFIX, STATE_CF,
 };
 use massa_models::{
     operation::{OperationId, OperationIdDeserializer, OperationIdSerializer},
     prehash::PreHashSet,
     slot::{Slot, SlotDeserializer, SlotSerializer},
+    streaming_step::StreamingStep,
 };
 use massa_serialization::{
     BoolDeserializer, BoolSerializer, DeserializeError, Deserializer, Serializer,
@@ -174,15 +178,18 @@ impl ExecutedOps {
             .is_some()
     }
 
-    /// Prune all expired operations
-    fn prune_to_batch(&mut self, slot: Slot, batch: &mut DBBatch) {
+    /// Lowest slot of the operations kept once `slot` is final, if any are pruned
+    fn prune_cutoff_slot(&self, slot: Slot) -> Option<Slot> {
         // Force-keep `keep_executed_history_extra_periods` for API polling safety
-        let cutoff_slot = match slot
-            .period
+        slot.period
             .checked_sub(self.config.keep_executed_history_extra_periods)
-        {
-            Some(cutoff_slot) => Slot::new(cutoff_slot, slot.thread),
-            None => return,
+            .map(|cutoff_period| Slot::new(cutoff_period, slot.thread))
+    }
+
+    /// Prune all expired operations
+    fn prune_to_batch(&mut self, slot: Slot, batch: &mut DBBatch) {
+        let Some(cutoff_slot) = self.prune_cutoff_slot(slot) else {
+            return;
         };
 
         let kept = self.sorted_ops.split_off(&cutoff_slot);
@@ -236,36 +243,75 @@ impl ExecutedOps {
         db.delete_key(batch, op_id_key!(serialized_op_id));
     }
 
-    /// Deserializes the key and value, useful after bootstrap
-    pub fn is_key_value_valid(&self, serialized_key: &[u8], serialized_value: &[u8]) -> bool {
-        if !serialized_key.starts_with(EXECUTED_OPS_PREFIX.as_bytes()) {
-            return false;
+    /// Checks the executed operations of a part streamed by a bootstrap server, before applying it.
+    ///
+    /// # Arguments
+    /// * `part`: the streamed part of the final state
+    /// * `cursor`: the streaming cursor before the part
+    /// * `final_slot`: the final slot declared by the server for the part
+    ///
+    /// The operations must be kept until a slot that is not pruned at `final_slot`,
+    /// and that an operation executed at or before `final_slot` can be kept until.
+    pub fn check_bootstrap_part(
+        &self,
+        part: &StreamBatch<Slot>,
+        cursor: &StreamingStep<Key>,
+        final_slot: Slot,
+    ) -> Result<(), BootstrapPartError> {
+        let min = self.prune_cutoff_slot(final_slot).unwrap_or_else(Slot::min);
+        let max = Slot::new(
+            final_slot
+                .period
+                .saturating_add(self.config.operation_validity_periods),
+            self.config.thread_count.saturating_sub(1),
+        );
+        for (key, value) in prefixed_part_entries(part, cursor, EXECUTED_OPS_PREFIX)? {
+            let (op_id, slot) = self
+                .deserialize_entry(key, value)
+                .ok_or_else(|| BootstrapPartError::InvalidEntry { key: key.clone() })?;
+            if slot < min || slot > max {
+                return Err(BootstrapPartError::OperationSlotOutOfRange {
+                    op_id,
+                    slot,
+                    min,
+                    max,
+                    final_slot,
+                });
+            }
         }
+        Ok(())
+    }
 
-        let Ok((rest, _id)): Result<(&[u8], OperationId), nom::Err<DeserializeError>> = self
+    /// Deserializes the operation id and the slot until which it is kept, if the entry is valid
+    fn deserialize_entry(
+        &self,
+        serialized_key: &[u8],
+        serialized_value: &[u8],
+    ) -> Option<(OperationId, Slot)> {
+        let (rest, op_id) = self
             .operation_id_deserializer
-            .deserialize::<DeserializeError>(&serialized_key[EXECUTED_OPS_PREFIX.len()..])
-        else {
-            return false;
-        };
+            .deserialize::<DeserializeError>(
+                serialized_key.strip_prefix(EXECUTED_OPS_PREFIX.as_bytes())?,
+            )
+            .ok()?;
         if !rest.is_empty() {
-            return false;
+            return None;
         }
-
-        let Ok((rest, _bool)) = self
+        let (rest, _op_exec_status) = self
             .bool_deserializer
             .deserialize::<DeserializeError>(serialized_value)
-        else {
-            return false;
-        };
-        let Ok((rest, _slot)) = self.slot_deserializer.deserialize::<DeserializeError>(rest) else {
-            return false;
-        };
-        if !rest.is_empty() {
-            return false;
-        }
+            .ok()?;
+        let (rest, slot) = self
+            .slot_deserializer
+            .deserialize::<DeserializeError>(rest)
+            .ok()?;
+        rest.is_empty().then_some((op_id, slot))
+    }
 
-        true
+    /// Deserializes the key and value, useful after bootstrap
+    pub fn is_key_value_valid(&self, serialized_key: &[u8], serialized_value: &[u8]) -> bool {
+        self.deserialize_entry(serialized_key, serialized_value)
+            .is_some()
     }
 }
 #[cfg(test)]
@@ -278,7 +324,9 @@ mod test {
     use massa_db_exports::{MassaDBConfig, MassaDBController, STATE_HASH_INITIAL_BYTES};
     use massa_db_worker::MassaDB;
     use massa_hash::{Hash, HashXof};
-    use massa_models::config::{KEEP_EXECUTED_HISTORY_EXTRA_PERIODS, THREAD_COUNT};
+    use massa_models::config::{
+        KEEP_EXECUTED_HISTORY_EXTRA_PERIODS, OPERATION_VALIDITY_PERIODS, THREAD_COUNT,
+    };
     use massa_models::prehash::PreHashMap;
     use massa_models::secure_share::Id;
 
@@ -291,6 +339,7 @@ mod test {
         let config = ExecutedOpsConfig {
             thread_count: THREAD_COUNT,
             keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+            operation_validity_periods: OPERATION_VALIDITY_PERIODS,
         };
 
         // Db init
@@ -345,6 +394,58 @@ mod test {
         assert_eq!(exec_ops2.sorted_ops.len(), 0);
     }
 
+    #[test]
+    fn test_executed_ops_pruning_boundary() {
+        let thread_count = 2;
+        let tempdir = TempDir::new().expect("cannot create temp directory");
+        let db_config = MassaDBConfig {
+            path: tempdir.path().to_path_buf(),
+            max_history_length: 10,
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count,
+            max_ledger_backups: 10,
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+
+        // the entry is kept through the last slot of validity of the operation
+        // extended by `keep_executed_history_extra_periods`, and pruned right after
+        let valid_until_slot = Slot::new(5, 1);
+        for extra_periods in [0, 2] {
+            let config = ExecutedOpsConfig {
+                thread_count,
+                keep_executed_history_extra_periods: extra_periods,
+                operation_validity_periods: OPERATION_VALIDITY_PERIODS,
+            };
+            let mut exec_ops = ExecutedOps::new(config, db.clone());
+            let op_id = OperationId::new(Hash::compute_from(&[extra_periods as u8]));
+            let mut changes = PreHashMap::default();
+            changes.insert(op_id, (true, valid_until_slot));
+
+            let mut batch = DBBatch::new();
+            exec_ops.apply_changes_to_batch(changes, Slot::new(3, 0), &mut batch);
+            db.write().write_batch(batch, Default::default(), None);
+
+            let last_kept_slot = Slot::new(valid_until_slot.period + extra_periods, 1);
+            let mut slot = Slot::new(3, 0);
+            while slot <= last_kept_slot {
+                let mut batch = DBBatch::new();
+                exec_ops.apply_changes_to_batch(PreHashMap::default(), slot, &mut batch);
+                db.write().write_batch(batch, Default::default(), None);
+                assert!(exec_ops.contains(&op_id), "pruned too early at {}", slot);
+                slot = slot.get_next_slot(thread_count).unwrap();
+            }
+
+            let mut batch = DBBatch::new();
+            exec_ops.apply_changes_to_batch(PreHashMap::default(), slot, &mut batch);
+            db.write().write_batch(batch, Default::default(), None);
+            assert!(!exec_ops.contains(&op_id), "not pruned at {}", slot);
+            assert!(exec_ops.get_ops_exec_status(&[op_id])[0].is_none());
+        }
+    }
+
     #[test]
     fn test_executed_ops_hash_computing() {
         // initialize the executed ops config
@@ -352,6 +453,7 @@ mod test {
         let config = ExecutedOpsConfig {
             thread_count,
             keep_executed_history_extra_periods: 2,
+            operation_validity_periods: OPERATION_VALIDITY_PERIODS,
         };
         let tempdir_a = TempDir::new().expect("cannot create temp directory");
         let tempdir_c = TempDir::new().expect("cannot create temp directory");
@@ -453,4 +555,147 @@ mod test {
             "'a' was not reset to its initial value"
         );
     }
+
+    /// New elements of a streamed part, from the entries put in `batch`
+    fn stream_part(batch: DBBatch, final_slot: Slot) -> StreamBatch<Slot> {
+        StreamBatch {
+            new_elements: batch
+                .into_iter()
+                .map(|(key, value)| (key, value.unwrap()))
+                .collect(),
+            updates_on_previous_elements: Default::default(),
+            change_id: final_slot,
+        }
+    }
+
+    #[test]
+    fn test_executed_ops_bootstrap_part_checks() {
+        let thread_count = 2;
+        let config = ExecutedOpsConfig {
+            thread_count,
+            keep_executed_history_extra_periods: 2,
+            operation_validity_periods: 10,
+        };
+        let tempdir = TempDir::new().expect("cannot create temp directory");
+        let db_config = MassaDBConfig {
+            path: tempdir.path().to_path_buf(),
+            max_history_length: 10,
+            // small parts, so that the state is streamed in several of them
+            max_final_state_elements_size: 100,
+            max_versioning_elements_size: 100,
+            thread_count,
+            max_ledger_backups: 10,
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let mut exec_ops = ExecutedOps::new(config, db.clone());
+
+        // kept from slot (7, 0) to slot (19, 1) at final slot (9, 0)
+        let final_slot = Slot::new(9, 0);
+        let mut changes = PreHashMap::default();
+        for (i, valid_until) in [(7, 0), (9, 1), (12, 0), (15, 1), (19, 1)]
+            .into_iter()
+            .enumerate()
+        {
+            let op_id = OperationId::new(Hash::compute_from(&[i as u8]));
+            changes.insert(op_id, (true, Slot::new(valid_until.0, valid_until.1)));
+        }
+        let mut batch = DBBatch::new();
+        exec_ops.apply_changes_to_batch(changes, final_slot, &mut batch);
+        db.write()
+            .write_batch(batch, Default::default(), Some(final_slot));
+
+        // a valid state is accepted part by part
+        let mut cursor = StreamingStep::Started;
+        let mut last_change_id = None;
+        let mut part_count = 0;
+        loop {
+            let part = db
+                .read()
+                .get_batch_to_stream(&cursor, last_change_id)
+                .unwrap();
+            let Some((last_key, _)) = part.new_elements.last_key_value() else {
+                break;
+            };
+            exec_ops
+                .check_bootstrap_part(&part, &cursor, final_slot)
+                .unwrap();
+            cursor = StreamingStep::Ongoing(last_key.clone());
+            last_change_id = Some(part.change_id);
+            part_count += 1;
+        }
+        assert!(
+            part_count > 1,
+            "the state should be streamed in several parts"
+        );
+
+        // operations that would have been pruned or that cannot have been executed yet are rejected
+        for (valid_until, expected_min, expected_max) in [
+            (Slot::new(6, 1), Slot::new(7, 0), Slot::new(19, 1)),
+            (Slot::new(20, 0), Slot::new(7, 0), Slot::new(19, 1)),
+        ] {
+            let op_id = OperationId::new(Hash::compute_from(b"out of range"));
+            let mut batch = DBBatch::new();
+            exec_ops.put_entry(&op_id, &(false, valid_until), &mut batch);
+            let part = stream_part(batch, final_slot);
+            assert_eq!(
+                exec_ops.check_bootstrap_part(&part, &StreamingStep::Started, final_slot),
+                Err(BootstrapPartError::OperationSlotOutOfRange {
+                    op_id,
+                    slot: valid_until,
+                    min: expected_min,
+                    max: expected_max,
+                    final_slot,
+                })
+            );
+        }
+
+        // new entries must come after the cursor
+        let op_id = OperationId::new(Hash::compute_from(b"out of order"));
+        let mut batch = DBBatch::new();
+        exec_ops.put_entry(&op_id, &(true, final_slot), &mut batch);
+        let part = stream_part(batch, final_slot);
+        let (key, _) = part.new_elements.first_key_value().unwrap();
+        let cursor = StreamingStep::Ongoing(key.clone());
+        assert_eq!(
+            exec_ops.check_bootstrap_part(&part, &cursor, final_slot),
+            Err(BootstrapPartError::KeyBeforeCursor {
+                key: key.clone(),
+                cursor: cursor.clone(),
+            })
+        );
+
+        // updates must be on entries already streamed
+        let part = StreamBatch {
+            new_elements: Default::default(),
+            updates_on_previous_elements: part
+                .new_elements
+                .iter()
+                .map(|(key, value)| (key.clone(), Some(value.clone())))
+                .collect(),
+            change_id: final_slot,
+        };
+        exec_ops
+            .check_bootstrap_part(&part, &cursor, final_slot)
+            .unwrap();
+        assert_eq!(
+            exec_ops.check_bootstrap_part(&part, &StreamingStep::Started, final_slot),
+            Err(BootstrapPartError::UpdateAfterCursor {
+                key: key.clone(),
+                cursor: StreamingStep::Started,
+            })
+        );
+
+        // undecodable entries are rejected
+        let mut batch = DBBatch::new();
+        batch.insert(op_id_key!(b"garbage".to_vec()), Some(vec![1]));
+        let part = stream_part(batch, final_slot);
+        assert_eq!(
+            exec_ops.check_bootstrap_part(&part, &StreamingStep::Started, final_slot),
+            Err(BootstrapPartError::InvalidEntry {
+                key: op_id_key!(b"garbage".to_vec()),
+            })
+        );
+    }
 }
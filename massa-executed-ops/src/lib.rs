@@ -2,14 +2,18 @@
 
 #![warn(unused_crate_dependencies)]
 
+mod bootstrap_part;
 mod config;
 mod denunciations_changes;
 mod executed_denunciations;
 mod executed_ops;
 mod ops_changes;
+mod slashing_stats;
 
+pub use bootstrap_part::BootstrapPartError;
 pub use config::*;
 pub use denunciations_changes::*;
 pub use executed_denunciations::*;
 pub use executed_ops::*;
 pub use ops_changes::*;
+pub use slashing_stats::*;
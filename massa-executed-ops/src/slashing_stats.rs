@@ -0,0 +1,141 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Per-cycle aggregates of the slashing caused by executed denunciations
+
+use massa_models::{
+    address::{Address, AddressDeserializer, AddressSerializer},
+    denunciation::DenunciationIndex,
+};
+use massa_serialization::{
+    Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use nom::{
+    error::{context, ContextError, ParseError},
+    multi::length_count,
+    sequence::tuple,
+    IResult, Parser,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::ops::Bound::Included;
+
+/// Slashing caused by an executed denunciation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DenunciationSlashing {
+    /// denounced address
+    pub address: Address,
+    /// number of rolls slashed from the denounced address, 0 if it had none left
+    pub slashed_rolls: u64,
+}
+
+/// Slashing caused by the denunciations executed in a slot
+pub type ExecutedDenunciationsSlashings = HashMap<DenunciationIndex, DenunciationSlashing>;
+
+/// Aggregated slashing of the executed denunciations targeting the slots of a cycle
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CycleSlashingStats {
+    /// number of executed denunciations
+    pub denunciation_count: u64,
+    /// number of slashed rolls
+    pub slashed_rolls: u64,
+    /// denounced addresses
+    pub slashed_addresses: BTreeSet<Address>,
+}
+
+/// `CycleSlashingStats` serializer
+pub struct CycleSlashingStatsSerializer {
+    u64_serializer: U64VarIntSerializer,
+    address_serializer: AddressSerializer,
+}
+
+impl Default for CycleSlashingStatsSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CycleSlashingStatsSerializer {
+    /// Create a new `CycleSlashingStats` serializer
+    pub fn new() -> Self {
+        Self {
+            u64_serializer: U64VarIntSerializer::new(),
+            address_serializer: AddressSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<CycleSlashingStats> for CycleSlashingStatsSerializer {
+    fn serialize(
+        &self,
+        value: &CycleSlashingStats,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.u64_serializer
+            .serialize(&value.denunciation_count, buffer)?;
+        self.u64_serializer
+            .serialize(&value.slashed_rolls, buffer)?;
+        self.u64_serializer
+            .serialize(&(value.slashed_addresses.len() as u64), buffer)?;
+        for address in &value.slashed_addresses {
+            self.address_serializer.serialize(address, buffer)?;
+        }
+        Ok(())
+    }
+}
+
+/// `CycleSlashingStats` deserializer
+pub struct CycleSlashingStatsDeserializer {
+    u64_deserializer: U64VarIntDeserializer,
+    address_count_deserializer: U64VarIntDeserializer,
+    address_deserializer: AddressDeserializer,
+}
+
+impl CycleSlashingStatsDeserializer {
+    /// Create a new `CycleSlashingStats` deserializer
+    ///
+    /// # Arguments
+    /// * `max_denunciations_per_cycle`: max number of denunciations targeting the slots of a cycle
+    pub fn new(max_denunciations_per_cycle: u64) -> Self {
+        Self {
+            u64_deserializer: U64VarIntDeserializer::new(Included(u64::MIN), Included(u64::MAX)),
+            address_count_deserializer: U64VarIntDeserializer::new(
+                Included(u64::MIN),
+                Included(max_denunciations_per_cycle),
+            ),
+            address_deserializer: AddressDeserializer::new(),
+        }
+    }
+}
+
+impl Deserializer<CycleSlashingStats> for CycleSlashingStatsDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], CycleSlashingStats, E> {
+        context(
+            "CycleSlashingStats",
+            tuple((
+                context("denunciation count", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context("slashed rolls", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context(
+                    "slashed addresses",
+                    length_count(
+                        |input| self.address_count_deserializer.deserialize(input),
+                        |input| self.address_deserializer.deserialize(input),
+                    ),
+                ),
+            )),
+        )
+        .map(
+            |(denunciation_count, slashed_rolls, slashed_addresses)| CycleSlashingStats {
+                denunciation_count,
+                slashed_rolls,
+                slashed_addresses: slashed_addresses.into_iter().collect(),
+            },
+        )
+        .parse(buffer)
+    }
+}
@@ -1,6 +1,10 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
-use crate::types::SlotExecutionOutput;
+use crate::types::{ExecutionOutput, SlotExecutionOutput};
+use tokio::sync::broadcast::{
+    error::{RecvError, TryRecvError},
+    Receiver,
+};
 
 #[cfg(feature = "execution-trace")]
 use crate::types_trace_info::SlotAbiCallStack;
@@ -14,3 +18,104 @@ pub struct ExecutionChannels {
     /// Broadcast channel for execution traces (abi call stacks, boolean true if the slot is finalized, false otherwise)
     pub slot_execution_traces_sender: tokio::sync::broadcast::Sender<(SlotAbiCallStack, bool)>,
 }
+
+impl ExecutionChannels {
+    /// Subscribe to the outputs of the finalized slots only, skipping the speculative ones
+    pub fn subscribe_final_slot_outputs(&self) -> FinalSlotExecutionOutputReceiver {
+        FinalSlotExecutionOutputReceiver {
+            receiver: self.slot_execution_output_sender.subscribe(),
+        }
+    }
+}
+
+/// Receiver of the outputs of the finalized slots, see [`ExecutionChannels::subscribe_final_slot_outputs`]
+pub struct FinalSlotExecutionOutputReceiver {
+    receiver: Receiver<SlotExecutionOutput>,
+}
+
+impl FinalSlotExecutionOutputReceiver {
+    /// Wait for the output of the next finalized slot.
+    ///
+    /// A `Lagged` error counts the skipped outputs, speculative ones included.
+    pub async fn recv(&mut self) -> Result<ExecutionOutput, RecvError> {
+        loop {
+            if let SlotExecutionOutput::FinalizedSlot(output) = self.receiver.recv().await? {
+                return Ok(output);
+            }
+        }
+    }
+
+    /// Return the output of the next finalized slot if one was already broadcast
+    pub fn try_recv(&mut self) -> Result<ExecutionOutput, TryRecvError> {
+        loop {
+            if let SlotExecutionOutput::FinalizedSlot(output) = self.receiver.try_recv()? {
+                return Ok(output);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_store::EventStore;
+    use massa_final_state::StateChanges;
+    use massa_models::slot::Slot;
+
+    fn execution_output(slot: Slot) -> ExecutionOutput {
+        ExecutionOutput {
+            slot,
+            block_info: None,
+            state_changes: StateChanges::default(),
+            events: EventStore::default(),
+            #[cfg(feature = "execution-trace")]
+            slot_trace: None,
+            #[cfg(feature = "dump-block")]
+            storage: None,
+            deferred_credits_execution: vec![],
+            cancel_async_message_execution: vec![],
+            auto_sell_execution: vec![],
+            digest: Default::default(),
+        }
+    }
+
+    #[test]
+    fn final_slot_outputs_only() {
+        let (slot_execution_output_sender, mut all_outputs) = tokio::sync::broadcast::channel(10);
+        #[cfg(feature = "execution-trace")]
+        let (slot_execution_traces_sender, _) = tokio::sync::broadcast::channel(10);
+        let channels = ExecutionChannels {
+            slot_execution_output_sender,
+            #[cfg(feature = "execution-trace")]
+            slot_execution_traces_sender,
+        };
+        let mut final_outputs = channels.subscribe_final_slot_outputs();
+
+        let speculative_slot = Slot::new(2, 0);
+        let final_slot = Slot::new(1, 0);
+        channels
+            .slot_execution_output_sender
+            .send(SlotExecutionOutput::ExecutedSlot(execution_output(
+                speculative_slot,
+            )))
+            .unwrap();
+        channels
+            .slot_execution_output_sender
+            .send(SlotExecutionOutput::FinalizedSlot(execution_output(
+                final_slot,
+            )))
+            .unwrap();
+
+        // every output carries its finality and slot
+        let output = all_outputs.try_recv().unwrap();
+        assert!(!output.is_final());
+        assert_eq!(output.slot(), speculative_slot);
+        let output = all_outputs.try_recv().unwrap();
+        assert!(output.is_final());
+        assert_eq!(output.slot(), final_slot);
+
+        // the filtered subscription skips the speculative output
+        assert_eq!(final_outputs.try_recv().unwrap().slot, final_slot);
+        assert!(matches!(final_outputs.try_recv(), Err(TryRecvError::Empty)));
+    }
+}
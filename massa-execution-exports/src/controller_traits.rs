@@ -3,11 +3,12 @@
 //! This module exports generic traits representing interfaces for interacting with the Execution worker
 
 use crate::types::{
-    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse, ReadOnlyExecutionRequest,
+    ExecutionBlockMetadata, ExecutionQueryRequest, ExecutionQueryResponse,
+    OperationExecutionStatus, ReadOnlyExecutionRequest, SlashingStats, SlotExecutionBudget,
 };
 
 use crate::ExecutionError;
-use crate::{ExecutionAddressInfo, ReadOnlyExecutionOutput};
+use crate::{AsyncMessageInfo, ExecutionAddressInfo, ReadOnlyExecutionOutput};
 use massa_models::address::Address;
 use massa_models::amount::Amount;
 use massa_models::block_id::BlockId;
@@ -17,7 +18,7 @@ use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashMap;
 use massa_models::slot::Slot;
-use massa_models::stats::ExecutionStats;
+use massa_models::stats::{ExecutionBacklog, ExecutionStats};
 use std::collections::BTreeMap;
 use std::collections::HashMap;
 
@@ -69,6 +70,20 @@ pub trait ExecutionController: Send + Sync {
     /// Otherwise, the status is a boolean indicating whether the execution was successful (true) or if there was an error (false.)
     fn get_ops_exec_status(&self, batch: &[OperationId]) -> Vec<(Option<bool>, Option<bool>)>;
 
+    /// Get the execution status of a single operation.
+    ///
+    /// Only final executions are reported as `ExecutedSuccess` or `ExecutedFailed`:
+    /// an operation executed in a candidate slot is `Pending` until that slot becomes final,
+    /// as a change of blockclique may still revert or change its execution.
+    /// Use `get_ops_exec_status` to get the candidate outcome of a pending operation.
+    fn get_operation_execution_status(&self, op_id: &OperationId) -> OperationExecutionStatus;
+
+    /// Get the candidate asynchronous messages sent to an address that are still waiting to be executed.
+    ///
+    /// The messages are sorted by validity start slot, then by priority (highest fee per gas first),
+    /// then by emission slot and index: the order in which they are considered for execution once valid.
+    fn get_async_messages_for(&self, addr: &Address) -> Vec<AsyncMessageInfo>;
+
     /// Get a copy of a single datastore entry with its final and active values
     ///
     /// # Return value
@@ -92,6 +107,8 @@ pub trait ExecutionController: Send + Sync {
     ///
     /// # returns
     /// An instance of `ExecutionOutput` containing a summary of the effects of the execution,
+    /// along with the gas consumed by each section of the execution if `req.profile` is set
+    /// and the tree of the calls it made if `req.include_trace` is set,
     /// or an error if the execution failed.
     fn execute_readonly_request(
         &self,
@@ -115,6 +132,27 @@ pub trait ExecutionController: Send + Sync {
     /// Get execution statistics
     fn get_stats(&self) -> ExecutionStats;
 
+    /// Get the inputs waiting to be processed by the execution thread
+    fn get_backlog(&self) -> ExecutionBacklog;
+
+    /// Get the final deferred credits between `min_slot` and `max_slot` (both included),
+    /// sorted by slot then address.
+    /// If `addresses` is given, only the credits of these addresses are returned.
+    fn get_deferred_credits_range(
+        &self,
+        min_slot: Slot,
+        max_slot: Slot,
+        addresses: Option<Vec<Address>>,
+    ) -> BTreeMap<Slot, Vec<(Address, Amount)>>;
+
+    /// Get the final slashing statistics of the cycles between `min_cycle` and `max_cycle`
+    /// (both included), sorted by cycle.
+    /// Only the cycles whose executed denunciations are not pruned yet are returned.
+    fn get_cycle_slashing_stats(&self, min_cycle: u64, max_cycle: u64) -> Vec<SlashingStats>;
+
+    /// Get the gas budget of the execution of a slot, projected from the candidate asynchronous pool
+    fn get_slot_budget(&self, slot: Slot) -> SlotExecutionBudget;
+
     #[cfg(feature = "execution-trace")]
     /// Get the abi call stack for a given operation id
     fn get_operation_abi_call_stack(&self, operation_id: OperationId) -> Option<Vec<AbiTrace>>;
@@ -2,10 +2,12 @@
 
 //! this file defines all possible execution error categories
 
+use crate::types::ReadOnlyCallTrace;
 use displaydoc::Display;
 use massa_module_cache::error::CacheError;
 use massa_sc_runtime::VMError;
 use massa_versioning::versioning_factory::FactoryError;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 /// Errors of the execution component.
@@ -67,6 +69,49 @@ pub enum ExecutionError {
 
     /// Factory error: {0}
     FactoryError(#[from] FactoryError),
+
+    /// Read-only execution error ({kind}): {message}
+    ReadOnlyError {
+        /// cause of the failure
+        kind: ReadOnlyErrorKind,
+        /// error message
+        message: String,
+        /// calls made up to the failure, `Some` only if the trace was requested
+        call_trace: Option<Box<ReadOnlyCallTrace>>,
+    },
+}
+
+impl ExecutionError {
+    /// Cause of the failure of a read-only execution, `None` for the other errors
+    pub fn readonly_error_kind(&self) -> Option<ReadOnlyErrorKind> {
+        match self {
+            ExecutionError::ReadOnlyError { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Calls made by a failed read-only execution up to its failure, if they were traced
+    pub fn readonly_call_trace(&self) -> Option<&ReadOnlyCallTrace> {
+        match self {
+            ExecutionError::ReadOnlyError { call_trace, .. } => call_trace.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Cause of the failure of a read-only execution.
+///
+/// Gas estimators retry with more gas on `OutOfGas`, while the other kinds fail whatever the gas.
+#[derive(Clone, Copy, Display, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReadOnlyErrorKind {
+    /// out of gas
+    OutOfGas,
+    /// target not found
+    TargetNotFound,
+    /// bytecode trap
+    BytecodeTrap,
+    /// datastore access denied
+    DatastoreAccessDenied,
 }
 
 /// Execution query errors
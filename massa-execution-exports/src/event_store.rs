@@ -54,47 +54,12 @@ impl EventStore {
     /// * original caller address
     /// * operation id
     /// * is final
+    /// * is error
+    /// * substrings of the data
     pub fn get_filtered_sc_output_events(&self, filter: &EventFilter) -> VecDeque<SCOutputEvent> {
         self.0
             .iter()
-            .filter(|x| {
-                if let Some(start) = filter.start {
-                    if x.context.slot < start {
-                        return false;
-                    }
-                }
-                if let Some(end) = filter.end {
-                    if x.context.slot >= end {
-                        return false;
-                    }
-                }
-                if let Some(is_final) = filter.is_final {
-                    if x.context.is_final != is_final {
-                        return false;
-                    }
-                }
-                if let Some(is_error) = filter.is_error {
-                    if x.context.is_error != is_error {
-                        return false;
-                    }
-                }
-                match (filter.original_caller_address, x.context.call_stack.front()) {
-                    (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                match (filter.emitter_address, x.context.call_stack.back()) {
-                    (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                match (filter.original_operation_id, x.context.origin_operation_id) {
-                    (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
-                    (Some(_), None) => return false,
-                    _ => (),
-                }
-                true
-            })
+            .filter(|x| filter.matches(x))
             .cloned()
             .collect()
     }
@@ -128,3 +93,43 @@ fn test_prune() {
     assert_eq!(store.0[1].data, "8");
     assert_eq!(store.0[0].data, "7");
 }
+
+#[test]
+fn test_filter_by_data() {
+    use massa_models::output_event::{EventExecutionContext, SCOutputEvent};
+    use massa_models::slot::Slot;
+
+    let mut store = EventStore(VecDeque::new());
+    for (period, recipient) in ["AU1a", "AU1b", "AU1a"].iter().enumerate() {
+        store.push(SCOutputEvent {
+            context: EventExecutionContext {
+                slot: Slot::new(period as u64, 0),
+                block: None,
+                read_only: false,
+                index_in_slot: 1,
+                call_stack: VecDeque::new(),
+                origin_operation_id: None,
+                is_final: false,
+                is_error: false,
+            },
+            data: format!("transfer:recipient={}:amount=10", recipient),
+        });
+    }
+    let filter = |data_contains: &[&str]| EventFilter {
+        start: Some(Slot::new(1, 0)),
+        data_contains: data_contains.iter().map(|s| s.to_string()).collect(),
+        ..Default::default()
+    };
+
+    let events = store.get_filtered_sc_output_events(&filter(&["recipient=AU1a"]));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].context.slot, Slot::new(2, 0));
+    let events = store.get_filtered_sc_output_events(&filter(&["transfer:", ":amount=10"]));
+    assert_eq!(events.len(), 2);
+    // all the patterns must match
+    let events = store.get_filtered_sc_output_events(&filter(&["AU1b", ":amount=20"]));
+    assert!(events.is_empty());
+    let events = store.get_filtered_sc_output_events(&filter(&["AU1b", ":amount=10"]));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].context.slot, Slot::new(1, 0));
+}
@@ -53,20 +53,22 @@ pub mod mapping_grpc;
 mod settings;
 mod types;
 
-pub use channels::ExecutionChannels;
+pub use channels::{ExecutionChannels, FinalSlotExecutionOutputReceiver};
 #[cfg(feature = "test-exports")]
 pub use controller_traits::MockExecutionController;
 pub use controller_traits::{ExecutionController, ExecutionManager};
-pub use error::{ExecutionError, ExecutionQueryError};
+pub use error::{ExecutionError, ExecutionQueryError, ReadOnlyErrorKind};
 pub use event_store::EventStore;
 pub use massa_sc_runtime::GasCosts;
 pub use settings::{ExecutionConfig, StorageCostsConstants};
 pub use types::{
-    ExecutedBlockInfo, ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionOutput,
-    ExecutionQueryCycleInfos, ExecutionQueryExecutionStatus, ExecutionQueryRequest,
-    ExecutionQueryRequestItem, ExecutionQueryResponse, ExecutionQueryResponseItem,
-    ExecutionQueryStakerInfo, ExecutionStackElement, ReadOnlyCallRequest, ReadOnlyExecutionOutput,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlotExecutionOutput,
+    AsyncMessageInfo, ExecutedBlockInfo, ExecutionAddressInfo, ExecutionBlockMetadata,
+    ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryExecutionStatus,
+    ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
+    ExecutionQueryResponseItem, ExecutionQueryStakerInfo, ExecutionStackElement, FinalStateDigest,
+    GasProfileSection, OperationExecutionStatus, ReadOnlyCallRequest, ReadOnlyCallTrace,
+    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlashingStats,
+    SlotExecutionBudget, SlotExecutionDigest, SlotExecutionOutput,
 };
 
 #[cfg(any(feature = "test-exports", feature = "gas_calibration"))]
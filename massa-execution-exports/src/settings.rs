@@ -68,6 +68,10 @@ pub struct ExecutionConfig {
     pub storage_costs_constants: StorageCostsConstants,
     /// Max gas for read only executions
     pub max_read_only_gas: u64,
+    /// Max depth of the calls recorded in the trace of a read only execution
+    pub max_read_only_trace_depth: usize,
+    /// Max number of calls recorded in the trace of a read only execution
+    pub max_read_only_trace_calls: usize,
     /// Gas costs
     pub gas_costs: GasCosts,
     /// Gas used by a transaction, a roll buy or a roll sell)
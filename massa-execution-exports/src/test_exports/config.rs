@@ -50,6 +50,8 @@ impl Default for ExecutionConfig {
             max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
             storage_costs_constants,
             max_read_only_gas: 1_000_000_000,
+            max_read_only_trace_depth: 16,
+            max_read_only_trace_calls: 256,
             gas_costs: GasCosts::new(
                 concat!(
                     env!("CARGO_MANIFEST_DIR"),
@@ -4,6 +4,7 @@
 
 use crate::error::ExecutionQueryError;
 use crate::event_store::EventStore;
+use massa_async_pool::AsyncMessageTrigger;
 use massa_final_state::StateChanges;
 use massa_hash::Hash;
 use massa_models::block_id::BlockId;
@@ -19,7 +20,7 @@ use massa_models::{
 };
 use massa_pos_exports::ProductionStats;
 use massa_storage::Storage;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet};
 
 #[cfg(feature = "execution-trace")]
@@ -164,6 +165,21 @@ pub enum ExecutionQueryExecutionStatus {
     ExecutableOrExpired,
 }
 
+/// Execution status of a single operation, see `ExecutionController::get_operation_execution_status`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OperationExecutionStatus {
+    /// The operation was executed in a candidate slot that is not final yet.
+    /// Its outcome may still change if the blockclique changes before the slot becomes final.
+    Pending,
+    /// The operation was executed successfully in a final slot
+    ExecutedSuccess,
+    /// The operation was executed with an error in a final slot, with the message of its error event
+    ExecutedFailed(String),
+    /// No execution of the operation was found: it was not executed yet,
+    /// or its execution was forgotten after it expired
+    Unknown,
+}
+
 /// Information about cycles
 pub struct ExecutionQueryCycleInfos {
     /// cycle number
@@ -207,6 +223,76 @@ pub struct ExecutionAddressInfo {
     pub cycle_infos: Vec<ExecutionAddressCycleInfo>,
 }
 
+/// Final slashing of the executed denunciations targeting the slots of a cycle,
+/// see `ExecutionController::get_cycle_slashing_stats`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlashingStats {
+    /// cycle of the denounced slots
+    pub cycle: u64,
+    /// number of executed denunciations
+    pub denunciation_count: u64,
+    /// number of slashed rolls
+    pub slashed_rolls: u64,
+    /// number of distinct denounced addresses
+    pub slashed_address_count: u64,
+}
+
+/// Gas budget of the execution of a slot, see `ExecutionController::get_slot_budget`.
+/// The asynchronous messages are projected from the candidate pool, assuming none of them is executed before the slot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlotExecutionBudget {
+    /// slot
+    pub slot: Slot,
+    /// maximum gas of the asynchronous messages executed at the slot
+    pub max_async_gas: u64,
+    /// number of asynchronous messages that can be executed at the slot
+    pub ready_async_message_count: u64,
+    /// gas reserved by the asynchronous messages that can be executed at the slot, can exceed `max_async_gas`
+    pub ready_async_gas: u64,
+    /// number of asynchronous messages that would be executed at the slot
+    pub projected_async_message_count: u64,
+    /// gas reserved by the asynchronous messages that would be executed at the slot
+    pub projected_async_gas: u64,
+    /// maximum gas of the operations of the block of the slot
+    pub max_block_gas: u64,
+}
+
+impl SlotExecutionBudget {
+    /// Gas still available to the asynchronous messages at the slot
+    pub fn async_gas_headroom(&self) -> u64 {
+        self.max_async_gas.saturating_sub(self.projected_async_gas)
+    }
+}
+
+/// Pending asynchronous message, see `ExecutionController::get_async_messages_for`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsyncMessageInfo {
+    /// slot at which the message was emitted
+    pub emission_slot: Slot,
+    /// index of the message among the ones emitted at `emission_slot`
+    pub emission_index: u64,
+    /// address that sent the message
+    pub sender: Address,
+    /// address to which the message is sent
+    pub destination: Address,
+    /// function of the destination called with the message
+    pub function: String,
+    /// max gas used to execute the message
+    pub max_gas: u64,
+    /// fee paid by the sender when the message is executed
+    pub fee: Amount,
+    /// coins sent to the destination with the message
+    pub coins: Amount,
+    /// first slot at which the message can be executed (included)
+    pub validity_start: Slot,
+    /// slot at which the message stops being valid (excluded)
+    pub validity_end: Slot,
+    /// trigger that the message waits for before being executable
+    pub trigger: Option<AsyncMessageTrigger>,
+    /// whether the message can be executed, that is if it has no trigger or its trigger was matched
+    pub can_be_executed: bool,
+}
+
 /// structure describing the output of the execution of a slot
 #[derive(Debug, Clone)]
 pub enum SlotExecutionOutput {
@@ -217,6 +303,26 @@ pub enum SlotExecutionOutput {
     FinalizedSlot(ExecutionOutput),
 }
 
+impl SlotExecutionOutput {
+    /// Whether the output is final, a speculative output may be reverted by a blockclique change
+    pub fn is_final(&self) -> bool {
+        matches!(self, SlotExecutionOutput::FinalizedSlot(_))
+    }
+
+    /// Executed slot
+    pub fn slot(&self) -> Slot {
+        self.output().slot
+    }
+
+    /// Output of the execution, final or not
+    pub fn output(&self) -> &ExecutionOutput {
+        match self {
+            SlotExecutionOutput::ExecutedSlot(output)
+            | SlotExecutionOutput::FinalizedSlot(output) => output,
+        }
+    }
+}
+
 /// structure storing a block id + network versions (from a block header)
 #[derive(Debug, Clone, Serialize)]
 pub struct ExecutedBlockInfo {
@@ -252,6 +358,32 @@ pub struct ExecutionOutput {
     pub cancel_async_message_execution: Vec<(Address, Result<Amount, String>)>,
     /// Auto sell roll execution (empty if execution-info feature is NOT enabled)
     pub auto_sell_execution: Vec<(Address, Amount)>,
+    /// digest of the state changes, for subscribers to check that they applied them correctly
+    pub digest: SlotExecutionDigest,
+}
+
+/// Digest of the state changes of an executed slot
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SlotExecutionDigest {
+    /// number of ledger entries changed
+    pub ledger_changes_count: usize,
+    /// number of asynchronous messages emitted
+    pub async_messages_emitted: usize,
+    /// number of asynchronous messages taken out of the pool, executed or not
+    pub async_messages_taken: usize,
+    /// digest of the final state after applying the slot, set once the slot is finalized
+    pub final_state: Option<FinalStateDigest>,
+}
+
+/// Digest of the final state after applying a finalized slot
+#[derive(Debug, Clone, Serialize)]
+pub struct FinalStateDigest {
+    /// change id of the final state, which is the finalized slot
+    pub change_id: Slot,
+    /// fingerprint of the final state, derived from its incremental hash
+    pub fingerprint: Hash,
+    /// fingerprint of each component of the final state by name, derived from their incremental hashes
+    pub component_fingerprints: BTreeMap<String, Hash>,
 }
 
 /// structure describing the output of a read only execution
@@ -263,6 +395,56 @@ pub struct ReadOnlyExecutionOutput {
     pub gas_cost: u64,
     /// Returned value from the module call
     pub call_result: Vec<u8>,
+    /// Gas consumed by each section of the execution, `Some` only if profiling was requested
+    pub gas_profile: Option<BTreeMap<GasProfileSection, u64>>,
+    /// Tree of the calls made by the execution, `Some` only if the trace was requested
+    pub call_trace: Option<ReadOnlyCallTrace>,
+}
+
+/// Call made during a read-only execution, with the calls it made in turn.
+///
+/// The root is the request target itself. The VM only hands the address and the coins of the
+/// calls a smart contract makes to the node, so the function and the parameter are known for
+/// the root only. The gas is only observed when a module is loaded: a call is deemed to end at
+/// the first module load that follows its return, or at the end of the execution, so its
+/// `gas_used` may include some code run by its caller after it returned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadOnlyCallTrace {
+    /// called address
+    pub target: Address,
+    /// called function, `None` for the calls made by a smart contract
+    pub function: Option<String>,
+    /// size of the parameter in bytes, `None` for the calls made by a smart contract
+    pub parameter_size: Option<u64>,
+    /// coins transferred to the called address
+    pub coins: Amount,
+    /// gas consumed by the call and the calls it made,
+    /// `None` if the execution failed before the end of the call could be observed
+    pub gas_used: Option<u64>,
+    /// error that stopped the call, `None` if it succeeded
+    pub error: Option<String>,
+    /// calls made by this call, in call order
+    pub sub_calls: Vec<ReadOnlyCallTrace>,
+    /// number of calls made within this call that were left out of the trace
+    /// because it reached its maximum depth or number of calls
+    pub omitted_calls: u64,
+}
+
+/// Section of a read-only execution whose gas consumption is reported by its profile.
+///
+/// The gas is metered inside the VM, which only reports the instantiation cost of the
+/// executed module and the gas remaining at each call to another smart contract: the
+/// sections follow those boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasProfileSection {
+    /// compilation and instantiation of the executed module
+    Instantiation,
+    /// code of the executed module run before its first call to another smart contract
+    TopLevel,
+    /// everything run from the first call to another smart contract: the called contracts,
+    /// their own instantiation and the code of the executed module run after that call
+    SubExecutions,
 }
 
 /// structure describing different types of read-only execution request
@@ -278,6 +460,15 @@ pub struct ReadOnlyExecutionRequest {
     pub coins: Option<Amount>,
     /// Fee
     pub fee: Option<Amount>,
+    /// Whether to report the gas consumed by each section of the execution.
+    /// Profiling may slightly change the execution time, never its results.
+    pub profile: bool,
+    /// Seed of the unsafe RNG available to the executed bytecode.
+    /// If `None`, it is derived from the execution slot as for any other execution:
+    /// only requests executed on the same slot draw the same numbers.
+    pub rng_seed: Option<[u8; 32]>,
+    /// Whether to record the tree of the calls made by the execution
+    pub include_trace: bool,
 }
 
 /// structure describing different possible targets of a read-only execution request
@@ -8,6 +8,7 @@
 //! and does not write anything persistent to the consensus state.
 
 use crate::active_history::HistorySearchResult;
+use crate::readonly_call_trace::CallTraceRecorder;
 use crate::speculative_async_pool::SpeculativeAsyncPool;
 use crate::speculative_executed_denunciations::SpeculativeExecutedDenunciations;
 use crate::speculative_executed_ops::SpeculativeExecutedOps;
@@ -15,14 +16,17 @@ use crate::speculative_ledger::SpeculativeLedger;
 use crate::{active_history::ActiveHistory, speculative_roll_state::SpeculativeRollState};
 use massa_async_pool::{AsyncMessage, AsyncPoolChanges};
 use massa_async_pool::{AsyncMessageId, AsyncMessageInfo};
-use massa_executed_ops::{ExecutedDenunciationsChanges, ExecutedOpsChanges};
+use massa_executed_ops::{
+    DenunciationSlashing, ExecutedDenunciationsChanges, ExecutedDenunciationsSlashings,
+    ExecutedOpsChanges,
+};
 use massa_execution_exports::{
     EventStore, ExecutedBlockInfo, ExecutionConfig, ExecutionError, ExecutionOutput,
-    ExecutionStackElement,
+    ExecutionStackElement, SlotExecutionDigest,
 };
 use massa_final_state::{FinalStateController, StateChanges};
 use massa_hash::Hash;
-use massa_ledger_exports::{LedgerChanges, SetOrKeep};
+use massa_ledger_exports::{LedgerChanges, SetOrKeep, SetUpdateOrDelete};
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::block_id::BlockIdSerializer;
 use massa_models::bytecode::Bytecode;
@@ -131,6 +135,9 @@ pub struct ExecutionContext {
     /// speculative list of executed denunciations
     speculative_executed_denunciations: SpeculativeExecutedDenunciations,
 
+    /// slashing caused by the denunciations executed in the slot
+    executed_denunciations_slashings: ExecutedDenunciationsSlashings,
+
     /// minimal balance allowed for the creator of the operation after its execution
     pub creator_min_balance: Option<Amount>,
 
@@ -179,6 +186,16 @@ pub struct ExecutionContext {
     /// The gas remaining before the last subexecution.
     /// so *excluding* the gas used by the last sc call.
     pub gas_remaining_before_subexecution: Option<u64>,
+
+    /// The gas remaining before the first subexecution, used to profile read-only executions
+    pub gas_remaining_before_first_subexecution: Option<u64>,
+
+    /// Recorder of the calls made by a read-only execution, `Some` only if its trace was requested
+    pub readonly_call_trace: Option<CallTraceRecorder>,
+
+    /// Whether a datastore change was refused for lack of write rights,
+    /// to tell the failure of a read-only execution apart from the other traps
+    pub datastore_access_denied: bool,
 }
 
 impl ExecutionContext {
@@ -227,6 +244,7 @@ impl ExecutionContext {
                 final_state,
                 active_history,
             ),
+            executed_denunciations_slashings: Default::default(),
             creator_min_balance: Default::default(),
             slot: Slot::new(0, 0),
             created_addr_index: Default::default(),
@@ -244,6 +262,9 @@ impl ExecutionContext {
             address_factory: AddressFactory { mip_store },
             execution_trail_hash,
             gas_remaining_before_subexecution: None,
+            gas_remaining_before_first_subexecution: None,
+            readonly_call_trace: None,
+            datastore_access_denied: false,
         }
     }
 
@@ -313,6 +334,7 @@ impl ExecutionContext {
     /// # arguments
     /// * `slot`: slot at which the execution will happen
     /// * `req`: parameters of the read only execution
+    /// * `rng_seed`: seed of the unsafe RNG, derived from the execution trail hash if `None`
     /// * `final_state`: thread-safe access to the final state. Note that this will be used only for reading, never for writing
     ///
     /// # returns
@@ -322,6 +344,7 @@ impl ExecutionContext {
         config: ExecutionConfig,
         slot: Slot,
         call_stack: Vec<ExecutionStackElement>,
+        rng_seed: Option<[u8; 32]>,
         final_state: Arc<RwLock<dyn FinalStateController>>,
         active_history: Arc<RwLock<ActiveHistory>>,
         module_cache: Arc<RwLock<ModuleCache>>,
@@ -337,7 +360,7 @@ impl ExecutionContext {
             generate_execution_trail_hash(&prev_execution_trail_hash, &slot, None, true);
 
         // return readonly context
-        ExecutionContext {
+        let mut context = ExecutionContext {
             slot,
             stack: call_stack,
             read_only: true,
@@ -349,7 +372,11 @@ impl ExecutionContext {
                 mip_store,
                 execution_trail_hash,
             )
+        };
+        if let Some(seed) = rng_seed {
+            context.unsafe_rng = Xoshiro256PlusPlus::from_seed(seed);
         }
+        context
     }
 
     /// This function takes a batch of asynchronous operations to execute, removing them from the speculative pool.
@@ -577,6 +604,7 @@ impl ExecutionContext {
     ) -> Result<(), ExecutionError> {
         // check access right
         if !self.has_write_rights_on(address) {
+            self.datastore_access_denied = true;
             return Err(ExecutionError::RuntimeError(format!(
                 "writing in the datastore of address {} is not allowed in this context",
                 address
@@ -604,6 +632,7 @@ impl ExecutionContext {
     ) -> Result<(), ExecutionError> {
         // check access right
         if !self.has_write_rights_on(address) {
+            self.datastore_access_denied = true;
             return Err(ExecutionError::RuntimeError(format!(
                 "appending to the datastore of address {} is not allowed in this context",
                 address
@@ -642,6 +671,7 @@ impl ExecutionContext {
     ) -> Result<(), ExecutionError> {
         // check access right
         if !self.has_write_rights_on(address) {
+            self.datastore_access_denied = true;
             return Err(ExecutionError::RuntimeError(format!(
                 "deleting from the datastore of address {} is not allowed in this context",
                 address
@@ -765,7 +795,7 @@ impl ExecutionContext {
     }
 
     /// Try to slash `roll_count` rolls from the denounced address. If not enough rolls,
-    /// slash the available amount and return the result
+    /// slash the available amount and return the number of slashed rolls and the total slashed amount
     ///
     /// # Arguments
     /// * `denounced_addr`: address to sell the rolls from
@@ -774,17 +804,18 @@ impl ExecutionContext {
         &mut self,
         denounced_addr: &Address,
         roll_count: u64,
-    ) -> Result<Amount, ExecutionError> {
+    ) -> Result<(u64, Amount), ExecutionError> {
         // try to slash as many roll as available
         let slashed_rolls = self
             .speculative_roll_state
-            .try_slash_rolls(denounced_addr, roll_count);
+            .try_slash_rolls(denounced_addr, roll_count)
+            .unwrap_or_default();
 
         // convert slashed rolls to coins (as deferred credits => coins)
         let mut slashed_coins = self
             .config
             .roll_price
-            .checked_mul_u64(slashed_rolls.unwrap_or_default())
+            .checked_mul_u64(slashed_rolls)
             .ok_or_else(|| {
                 ExecutionError::RuntimeError(format!(
                     "Cannot multiply roll price by {}",
@@ -824,7 +855,7 @@ impl ExecutionContext {
             }
         }
 
-        Ok(slashed_coins)
+        Ok((slashed_rolls, slashed_coins))
     }
 
     /// Update production statistics of an address.
@@ -941,8 +972,12 @@ impl ExecutionContext {
             pos_changes: self.speculative_roll_state.take(),
             executed_ops_changes: self.speculative_executed_ops.take(),
             executed_denunciations_changes: self.speculative_executed_denunciations.take(),
+            executed_denunciations_slashings: std::mem::take(
+                &mut self.executed_denunciations_slashings,
+            ),
             execution_trail_hash_change: SetOrKeep::Set(self.execution_trail_hash),
         };
+        let digest = slot_execution_digest(&state_changes);
 
         std::mem::take(&mut self.opt_block_id);
         ExecutionOutput {
@@ -957,6 +992,7 @@ impl ExecutionContext {
             deferred_credits_execution: deferred_credits_transfers,
             cancel_async_message_execution: cancel_async_message_transfers,
             auto_sell_execution: auto_sell_rolls,
+            digest,
         }
     }
 
@@ -1063,6 +1099,16 @@ impl ExecutionContext {
             .insert_executed_denunciation(*denunciation_idx);
     }
 
+    /// Record the slashing caused by an executed denunciation, for the slashing statistics
+    pub fn insert_denunciation_slashing(
+        &mut self,
+        denunciation_idx: &DenunciationIndex,
+        slashing: DenunciationSlashing,
+    ) {
+        self.executed_denunciations_slashings
+            .insert(*denunciation_idx, slashing);
+    }
+
     /// gets the cycle information for an address
     pub fn get_address_cycle_infos(
         &self,
@@ -1162,3 +1208,20 @@ fn init_prng(execution_trail_hash: &massa_hash::Hash) -> Xoshiro256PlusPlus {
     // but not cryptographically secure (and that's ok because the internal state is exposed anyway)
     Xoshiro256PlusPlus::from_seed(seed)
 }
+
+/// Summarizes the changes of a slot for the subscribers of the execution outputs.
+/// The final state part is filled once the slot is finalized.
+fn slot_execution_digest(state_changes: &StateChanges) -> SlotExecutionDigest {
+    let mut digest = SlotExecutionDigest {
+        ledger_changes_count: state_changes.ledger_changes.0.len(),
+        ..Default::default()
+    };
+    for change in state_changes.async_pool_changes.0.values() {
+        match change {
+            SetUpdateOrDelete::Set(_) => digest.async_messages_emitted += 1,
+            SetUpdateOrDelete::Delete => digest.async_messages_taken += 1,
+            SetUpdateOrDelete::Update(_) => {}
+        }
+    }
+    digest
+}
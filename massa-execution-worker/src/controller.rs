@@ -7,16 +7,17 @@ use crate::execution::ExecutionState;
 use crate::request_queue::{RequestQueue, RequestWithResponseSender};
 use massa_channel::MassaChannel;
 use massa_execution_exports::{
-    ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionConfig, ExecutionController,
-    ExecutionError, ExecutionManager, ExecutionQueryError, ExecutionQueryExecutionStatus,
-    ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionQueryResponse,
-    ExecutionQueryResponseItem, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    AsyncMessageInfo, ExecutionAddressInfo, ExecutionBlockMetadata, ExecutionConfig,
+    ExecutionController, ExecutionError, ExecutionManager, ExecutionQueryError,
+    ExecutionQueryExecutionStatus, ExecutionQueryRequest, ExecutionQueryRequestItem,
+    ExecutionQueryResponse, ExecutionQueryResponseItem, OperationExecutionStatus,
+    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest, SlashingStats, SlotExecutionBudget,
 };
 use massa_models::denunciation::DenunciationIndex;
 use massa_models::execution::EventFilter;
 use massa_models::output_event::SCOutputEvent;
-use massa_models::prehash::PreHashMap;
-use massa_models::stats::ExecutionStats;
+use massa_models::prehash::{PreHashMap, PreHashSet};
+use massa_models::stats::{ExecutionBacklog, ExecutionStats};
 use massa_models::{address::Address, amount::Amount, operation::OperationId};
 use massa_models::{block_id::BlockId, slot::Slot};
 use parking_lot::{Condvar, Mutex, RwLock};
@@ -473,6 +474,48 @@ impl ExecutionController for ExecutionControllerImpl {
         self.execution_state.read().get_stats()
     }
 
+    /// Get the inputs waiting to be processed by the execution thread
+    fn get_backlog(&self) -> ExecutionBacklog {
+        let mut backlog = {
+            let input_data = self.input_data.1.lock();
+            ExecutionBacklog {
+                pending_finalized_block_count: input_data.finalized_blocks.len(),
+                pending_blockclique_update: input_data.new_blockclique.is_some(),
+                pending_block_metadata_count: input_data.block_metadata.len(),
+                pending_readonly_request_count: input_data.readonly_requests.len(),
+                active_history_length: 0,
+            }
+        };
+        backlog.active_history_length = self.execution_state.read().get_active_history_length();
+        backlog
+    }
+
+    /// Get the final deferred credits of a slot range, optionally filtered by address
+    fn get_deferred_credits_range(
+        &self,
+        min_slot: Slot,
+        max_slot: Slot,
+        addresses: Option<Vec<Address>>,
+    ) -> BTreeMap<Slot, Vec<(Address, Amount)>> {
+        let addresses: Option<PreHashSet<Address>> =
+            addresses.map(|addresses| addresses.into_iter().collect());
+        self.execution_state
+            .read()
+            .get_deferred_credits_range(min_slot..=max_slot, addresses.as_ref())
+    }
+
+    /// Get the final slashing statistics of a cycle range
+    fn get_cycle_slashing_stats(&self, min_cycle: u64, max_cycle: u64) -> Vec<SlashingStats> {
+        self.execution_state
+            .read()
+            .get_cycle_slashing_stats(min_cycle..=max_cycle)
+    }
+
+    /// Get the gas budget of the execution of a slot
+    fn get_slot_budget(&self, slot: Slot) -> SlotExecutionBudget {
+        self.execution_state.read().get_slot_budget(slot)
+    }
+
     #[cfg(feature = "execution-trace")]
     fn get_operation_abi_call_stack(&self, operation_id: OperationId) -> Option<Vec<AbiTrace>> {
         self.execution_state
@@ -520,6 +563,16 @@ impl ExecutionController for ExecutionControllerImpl {
     fn get_ops_exec_status(&self, batch: &[OperationId]) -> Vec<(Option<bool>, Option<bool>)> {
         self.execution_state.read().get_ops_exec_status(batch)
     }
+
+    fn get_async_messages_for(&self, addr: &Address) -> Vec<AsyncMessageInfo> {
+        self.execution_state.read().get_async_messages_for(addr)
+    }
+
+    fn get_operation_execution_status(&self, op_id: &OperationId) -> OperationExecutionStatus {
+        self.execution_state
+            .read()
+            .get_operation_execution_status(op_id)
+    }
 }
 
 /// Execution manager
@@ -11,26 +11,31 @@
 use crate::active_history::{ActiveHistory, HistorySearchResult};
 use crate::context::{ExecutionContext, ExecutionContextSnapshot};
 use crate::interface_impl::InterfaceImpl;
+use crate::readonly_call_trace::CallTraceRecorder;
+use crate::speculative_async_pool::SpeculativeAsyncPool;
 use crate::stats::ExecutionStatsCounter;
 #[cfg(feature = "dump-block")]
 use crate::storage_backend::StorageBackend;
-use massa_async_pool::AsyncMessage;
+use massa_async_pool::{AsyncMessage, AsyncMessageId};
+use massa_executed_ops::DenunciationSlashing;
 use massa_execution_exports::{
-    EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels, ExecutionConfig,
-    ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos, ExecutionQueryStakerInfo,
-    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
-    ReadOnlyExecutionTarget, SlotExecutionOutput,
+    AsyncMessageInfo, EventStore, ExecutedBlockInfo, ExecutionBlockMetadata, ExecutionChannels,
+    ExecutionConfig, ExecutionError, ExecutionOutput, ExecutionQueryCycleInfos,
+    ExecutionQueryStakerInfo, ExecutionStackElement, FinalStateDigest, GasProfileSection,
+    OperationExecutionStatus, ReadOnlyCallTrace, ReadOnlyErrorKind, ReadOnlyExecutionOutput,
+    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget, SlashingStats, SlotExecutionBudget,
+    SlotExecutionOutput,
 };
 use massa_final_state::FinalStateController;
-use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
+use massa_ledger_exports::{Applicable, SetOrDelete, SetUpdateOrDelete};
 use massa_metrics::MassaMetrics;
 use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::bytecode::Bytecode;
-
+use massa_models::config::SLASHING_EVENT_EMITTER_ADDRESS;
 use massa_models::datastore::get_prefix_bounds;
 use massa_models::denunciation::{Denunciation, DenunciationIndex};
 use massa_models::execution::EventFilter;
-use massa_models::output_event::SCOutputEvent;
+use massa_models::output_event::{SCOutputEvent, SlashingEventData};
 use massa_models::prehash::PreHashSet;
 use massa_models::stats::ExecutionStats;
 use massa_models::timeslots::get_block_slot_timestamp;
@@ -42,6 +47,7 @@ use massa_models::{
 use massa_models::{amount::Amount, slot::Slot};
 use massa_module_cache::config::ModuleCacheConfig;
 use massa_module_cache::controller::ModuleCache;
+use massa_module_cache::error::CacheError;
 use massa_pos_exports::SelectorController;
 use massa_sc_runtime::{Interface, Response, VMError};
 use massa_versioning::versioning::MipStore;
@@ -245,6 +251,11 @@ impl ExecutionState {
             .get_stats(self.active_cursor, self.final_cursor)
     }
 
+    /// Get the number of executed slots kept in the active history
+    pub fn get_active_history_length(&self) -> usize {
+        self.active_history.read().0.len()
+    }
+
     /// Applies the output of an execution to the final execution state.
     /// The newly applied final output should be from the slot just after the last executed final slot
     ///
@@ -271,7 +282,7 @@ impl ExecutionState {
         // as it will also write the MIP store on disk
         self.update_versioning_stats(&exec_out.block_info, &exec_out.slot);
 
-        let exec_out_2 = exec_out.clone();
+        let mut exec_out_2 = exec_out.clone();
         #[cfg(feature = "slot-replayer")]
         {
             println!(">>> Execution changes");
@@ -318,6 +329,8 @@ impl ExecutionState {
                 .message_info_cache
                 .len(),
         );
+        self.massa_metrics
+            .set_executed_denunciations(self.final_state.read().executed_denunciations_count());
 
         self.massa_metrics.inc_executed_final_slot();
         if exec_out.block_info.is_some() {
@@ -326,6 +339,13 @@ impl ExecutionState {
 
         // Broadcast a final slot execution output to active channel subscribers.
         if self.config.broadcast_enabled {
+            let final_state = self.final_state.read();
+            exec_out_2.digest.final_state = Some(FinalStateDigest {
+                change_id: exec_out_2.slot,
+                fingerprint: final_state.get_fingerprint(),
+                component_fingerprints: final_state.get_component_fingerprints(),
+            });
+            drop(final_state);
             let slot_exec_out = SlotExecutionOutput::FinalizedSlot(exec_out_2);
             if let Err(err) = self
                 .channels
@@ -388,6 +408,7 @@ impl ExecutionState {
                 let filled_block = FilledBlock {
                     header: secured_block.content.header.clone(),
                     operations,
+                    locally_produced: false,
                 };
 
                 let grpc_filled_block = grpc_model::FilledBlock::from(filled_block);
@@ -745,9 +766,18 @@ impl ExecutionState {
             &addr_denounced,
             self.config.roll_count_to_slash_on_denunciation,
         );
+        context.insert_denunciation_slashing(
+            &de_idx,
+            DenunciationSlashing {
+                address: addr_denounced,
+                slashed_rolls: slashed
+                    .as_ref()
+                    .map_or(0, |(slashed_rolls, _)| *slashed_rolls),
+            },
+        );
 
         match slashed.as_ref() {
-            Ok(slashed_amount) => {
+            Ok((slashed_rolls, slashed_amount)) => {
                 // Add slashed amount / 2 to block reward
                 let amount = slashed_amount.checked_div_u64(2).ok_or_else(|| {
                     ExecutionError::RuntimeError(format!(
@@ -756,6 +786,29 @@ impl ExecutionState {
                     ))
                 })?;
                 *block_credits = block_credits.saturating_add(amount);
+
+                // emit the slashing event on behalf of the system emitter.
+                // The denunciation is now marked as executed so this happens only once.
+                context.origin_operation_id = None;
+                context.stack = vec![ExecutionStackElement {
+                    address: *SLASHING_EVENT_EMITTER_ADDRESS,
+                    coins: Amount::zero(),
+                    owned_addresses: vec![],
+                    operation_datastore: None,
+                }];
+                let data = SlashingEventData {
+                    denounced_address: addr_denounced,
+                    denunciation: de_idx,
+                    slashed_rolls: *slashed_rolls,
+                    slashed_amount: *slashed_amount,
+                    credited_amount: amount,
+                    burned_amount: slashed_amount.saturating_sub(amount),
+                };
+                let event = context.event_create(
+                    serde_json::json!({ "massa_slashing": data }).to_string(),
+                    false,
+                );
+                context.event_emit(event);
             }
             Err(e) => {
                 warn!("Unable to slash rolls or deferred credits: {}", e);
@@ -777,7 +830,7 @@ impl ExecutionState {
         Ok(DenunciationResult {
             address_denounced: addr_denounced,
             slot: *de_slot,
-            slashed: slashed.unwrap_or_default(),
+            slashed: slashed.map(|(_, amount)| amount).unwrap_or_default(),
         })
     }
 
@@ -1757,16 +1810,32 @@ impl ExecutionState {
             .get_next_slot(self.config.thread_count)
             .expect("slot overflow in readonly execution from active slot");
 
+        // the trace starts with the request target itself
+        let call_trace = req
+            .include_trace
+            .then(|| readonly_call_trace_root(&req))
+            .flatten()
+            .map(|root| {
+                CallTraceRecorder::new(
+                    self.config.max_read_only_trace_depth,
+                    self.config.max_read_only_trace_calls,
+                    root,
+                    req.max_gas,
+                )
+            });
+
         // create a readonly execution context
-        let execution_context = ExecutionContext::readonly(
+        let mut execution_context = ExecutionContext::readonly(
             self.config.clone(),
             slot,
             req.call_stack,
+            req.rng_seed,
             self.final_state.clone(),
             self.active_history.clone(),
             self.module_cache.clone(),
             self.mip_store.clone(),
         );
+        execution_context.readonly_call_trace = call_trace;
 
         // run the interpreter according to the target type
         let exec_response = match req.target {
@@ -1787,7 +1856,8 @@ impl ExecutionState {
                 let module = self
                     .module_cache
                     .read()
-                    .load_tmp_module(&bytecode, req.max_gas)?;
+                    .load_tmp_module(&bytecode, req.max_gas)
+                    .map_err(readonly_load_error)?;
 
                 // run the VM
                 massa_sc_runtime::run_main(
@@ -1796,10 +1866,7 @@ impl ExecutionState {
                     req.max_gas,
                     self.config.gas_costs.clone(),
                 )
-                .map_err(|error| ExecutionError::VMError {
-                    context: "ReadOnlyExecutionTarget::BytecodeExecution".to_string(),
-                    error,
-                })?
+                .map_err(|error| readonly_vm_error(error, &mut context_guard!(self)))?
             }
 
             ReadOnlyExecutionTarget::FunctionCall {
@@ -1818,7 +1885,13 @@ impl ExecutionState {
                     *context = execution_context;
 
                     // Ensure that the target address is an SC address and exists
-                    context.check_target_sc_address(target_addr)?;
+                    context
+                        .check_target_sc_address(target_addr)
+                        .map_err(|err| ExecutionError::ReadOnlyError {
+                            kind: ReadOnlyErrorKind::TargetNotFound,
+                            message: err.to_string(),
+                            call_trace: None,
+                        })?;
 
                     let call_stack_addr = context.get_call_stack();
 
@@ -1840,7 +1913,8 @@ impl ExecutionState {
                 let module = self
                     .module_cache
                     .write()
-                    .load_module(&bytecode, req.max_gas)?;
+                    .load_module(&bytecode, req.max_gas)
+                    .map_err(readonly_load_error)?;
 
                 let response = massa_sc_runtime::run_function(
                     &*self.execution_interface,
@@ -1861,16 +1935,26 @@ impl ExecutionState {
                     _ => (),
                 }
 
-                response.map_err(|error| ExecutionError::VMError {
-                    context: "ReadOnlyExecutionTarget::FunctionCall".to_string(),
-                    error,
-                })?
+                response.map_err(|error| readonly_vm_error(error, &mut context_guard!(self)))?
             }
         };
 
         // return the execution output
         let execution_output = context_guard!(self).settle_slot(None);
         let exact_exec_cost = req.max_gas.saturating_sub(exec_response.remaining_gas);
+        let call_trace = context_guard!(self)
+            .readonly_call_trace
+            .take()
+            .map(|call_trace| call_trace.finish(exec_response.remaining_gas));
+
+        // split the exact cost at the boundaries reported by the VM
+        let gas_profile = req.profile.then(|| {
+            readonly_gas_profile(
+                exact_exec_cost,
+                &exec_response,
+                context_guard!(self).gas_remaining_before_first_subexecution,
+            )
+        });
 
         // compute a gas cost, estimating the gas of the last SC call to be max_instance_cost
         let corrected_cost = match (context_guard!(self)).gas_remaining_before_subexecution {
@@ -1896,6 +1980,8 @@ impl ExecutionState {
             out: execution_output,
             gas_cost: estimated_cost,
             call_result: exec_response.ret,
+            gas_profile,
+            call_trace,
         })
     }
 
@@ -2232,6 +2318,63 @@ impl ExecutionState {
         (res_speculative, res_final)
     }
 
+    /// Get the final deferred credits of a slot range, sorted by slot then address
+    pub fn get_deferred_credits_range(
+        &self,
+        range: std::ops::RangeInclusive<Slot>,
+        addresses: Option<&PreHashSet<Address>>,
+    ) -> BTreeMap<Slot, Vec<(Address, Amount)>> {
+        self.final_state
+            .read()
+            .get_pos_state()
+            .get_deferred_credits_by_slot(range, addresses)
+    }
+
+    /// Get the final slashing statistics of a cycle range, sorted by cycle
+    pub fn get_cycle_slashing_stats(
+        &self,
+        cycles: std::ops::RangeInclusive<u64>,
+    ) -> Vec<SlashingStats> {
+        self.final_state
+            .read()
+            .get_executed_denunciations()
+            .get_cycle_slashing_stats(cycles)
+            .into_iter()
+            .map(|(cycle, stats)| SlashingStats {
+                cycle,
+                denunciation_count: stats.denunciation_count,
+                slashed_rolls: stats.slashed_rolls,
+                slashed_address_count: stats.slashed_addresses.len() as u64,
+            })
+            .collect()
+    }
+
+    /// Get the gas budget of the execution of a slot, see `ExecutionController::get_slot_budget`
+    pub fn get_slot_budget(&self, slot: Slot) -> SlotExecutionBudget {
+        // the candidate pool, as seen by the execution of the next slot
+        let async_pool =
+            SpeculativeAsyncPool::new(self.final_state.clone(), self.active_history.clone());
+        let (ready_async_message_count, ready_async_gas) = async_pool
+            .ready_messages(slot, self.config.async_msg_cst_gas_cost)
+            .fold((0u64, 0u64), |(count, gas), (_, message_gas)| {
+                (count + 1, gas.saturating_add(message_gas))
+            });
+        let batch = async_pool.choose_batch(
+            slot,
+            self.config.max_async_gas,
+            self.config.async_msg_cst_gas_cost,
+        );
+        SlotExecutionBudget {
+            slot,
+            max_async_gas: self.config.max_async_gas,
+            ready_async_message_count,
+            ready_async_gas,
+            projected_async_message_count: batch.len() as u64,
+            projected_async_gas: batch.iter().map(|(_, gas)| gas).sum(),
+            max_block_gas: self.config.max_gas_per_block,
+        }
+    }
+
     /// Get the execution status of a batch of operations.
     ///
     ///  Return value: vector of
@@ -2254,6 +2397,83 @@ impl ExecutionState {
             .collect()
     }
 
+    /// Get the execution status of a single operation, see
+    /// `ExecutionController::get_operation_execution_status`
+    pub fn get_operation_execution_status(&self, op_id: &OperationId) -> OperationExecutionStatus {
+        match self.final_state.read().get_ops_exec_status(&[*op_id])[0] {
+            Some(true) => return OperationExecutionStatus::ExecutedSuccess,
+            Some(false) => {
+                // the error of a failed execution is the last error event it emitted
+                let reason = self
+                    .final_events
+                    .get_filtered_sc_output_events(&EventFilter {
+                        original_operation_id: Some(*op_id),
+                        is_final: Some(true),
+                        is_error: Some(true),
+                        ..Default::default()
+                    })
+                    .pop_back()
+                    .map_or_else(|| "unknown error".to_string(), |event| event.data);
+                return OperationExecutionStatus::ExecutedFailed(reason);
+            }
+            None => {}
+        }
+        match self.active_history.read().get_ops_exec_status(&[*op_id])[0] {
+            Some(_) => OperationExecutionStatus::Pending,
+            None => OperationExecutionStatus::Unknown,
+        }
+    }
+
+    /// Get the candidate asynchronous messages sent to an address, see
+    /// `ExecutionController::get_async_messages_for`
+    pub fn get_async_messages_for(&self, addr: &Address) -> Vec<AsyncMessageInfo> {
+        let mut messages = self
+            .final_state
+            .read()
+            .get_async_pool()
+            .fetch_messages_to(addr);
+
+        // apply the changes of the active history, from the oldest to the most recent slot
+        for output in self.active_history.read().0.iter() {
+            for (message_id, change) in output.state_changes.async_pool_changes.0.iter() {
+                match change {
+                    SetUpdateOrDelete::Set(message) if &message.destination == addr => {
+                        messages.insert(*message_id, message.clone());
+                    }
+                    SetUpdateOrDelete::Set(_) | SetUpdateOrDelete::Delete => {
+                        messages.remove(message_id);
+                    }
+                    SetUpdateOrDelete::Update(update) => {
+                        if let Some(message) = messages.get_mut(message_id) {
+                            message.apply(update.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        // the message ids are sorted by priority
+        let mut messages: Vec<(AsyncMessageId, AsyncMessage)> = messages.into_iter().collect();
+        messages.sort_by_key(|(message_id, message)| (message.validity_start, *message_id));
+        messages
+            .into_iter()
+            .map(|(_, message)| AsyncMessageInfo {
+                emission_slot: message.emission_slot,
+                emission_index: message.emission_index,
+                sender: message.sender,
+                destination: message.destination,
+                function: message.function,
+                max_gas: message.max_gas,
+                fee: message.fee,
+                coins: message.coins,
+                validity_start: message.validity_start,
+                validity_end: message.validity_end,
+                trigger: message.trigger,
+                can_be_executed: message.can_be_executed,
+            })
+            .collect()
+    }
+
     /// Update MipStore with block header stats
     pub fn update_versioning_stats(&mut self, block_info: &Option<ExecutedBlockInfo>, slot: &Slot) {
         let slot_ts = get_block_slot_timestamp(
@@ -2272,3 +2492,98 @@ impl ExecutionState {
         );
     }
 }
+
+/// Classify a module loading failure of a read-only execution.
+/// Only the gas checks get a kind: an invalid module stays a cache error.
+fn readonly_load_error(error: CacheError) -> ExecutionError {
+    match error {
+        CacheError::InsufficientGas { .. } => ExecutionError::ReadOnlyError {
+            kind: ReadOnlyErrorKind::OutOfGas,
+            message: error.to_string(),
+            call_trace: None,
+        },
+        error => error.into(),
+    }
+}
+
+/// Classify a VM failure of a read-only execution,
+/// and end the trace of the calls made up to the failure if one was recorded.
+///
+/// The datastore rights checks are recorded by the context. The gas metering and the missing
+/// exports are traps of the VM itself, which only reports them by their message.
+fn readonly_vm_error(error: VMError, context: &mut ExecutionContext) -> ExecutionError {
+    let message = error.to_string();
+    let lowercase = message.to_lowercase();
+    let kind = if context.datastore_access_denied {
+        ReadOnlyErrorKind::DatastoreAccessDenied
+    } else if lowercase.contains("not enough gas") {
+        ReadOnlyErrorKind::OutOfGas
+    } else if lowercase.contains("missing export") {
+        ReadOnlyErrorKind::TargetNotFound
+    } else {
+        ReadOnlyErrorKind::BytecodeTrap
+    };
+    let call_trace = context
+        .readonly_call_trace
+        .take()
+        .map(|call_trace| Box::new(call_trace.fail(&message)));
+    ExecutionError::ReadOnlyError {
+        kind,
+        message,
+        call_trace,
+    }
+}
+
+/// Trace of the target of a read-only request, root of the trace of its calls.
+/// A bytecode execution runs as the last address of the call stack: it has no root if the stack is empty.
+fn readonly_call_trace_root(req: &ReadOnlyExecutionRequest) -> Option<ReadOnlyCallTrace> {
+    let (target, function, parameter_size, coins) = match &req.target {
+        ReadOnlyExecutionTarget::BytecodeExecution(_) => {
+            let element = req.call_stack.last()?;
+            (element.address, "main".to_string(), 0, element.coins)
+        }
+        ReadOnlyExecutionTarget::FunctionCall {
+            target_addr,
+            target_func,
+            parameter,
+        } => (
+            *target_addr,
+            target_func.clone(),
+            parameter.len() as u64,
+            req.coins.unwrap_or_default(),
+        ),
+    };
+    Some(ReadOnlyCallTrace {
+        target,
+        function: Some(function),
+        parameter_size: Some(parameter_size),
+        coins,
+        gas_used: None,
+        error: None,
+        sub_calls: Vec::new(),
+        omitted_calls: 0,
+    })
+}
+
+/// Split the gas consumed by a read-only execution into the sections of its profile.
+/// The sections always add up to `exec_cost`, the exact cost of the execution.
+fn readonly_gas_profile(
+    exec_cost: u64,
+    response: &Response,
+    gas_remaining_before_first_subexecution: Option<u64>,
+) -> BTreeMap<GasProfileSection, u64> {
+    let instantiation = response.init_gas_cost.min(exec_cost);
+    let sub_executions = gas_remaining_before_first_subexecution
+        .map_or(0, |gas_remaining| {
+            gas_remaining.saturating_sub(response.remaining_gas)
+        })
+        .min(exec_cost - instantiation);
+    BTreeMap::from([
+        (GasProfileSection::Instantiation, instantiation),
+        (
+            GasProfileSection::TopLevel,
+            exec_cost - instantiation - sub_executions,
+        ),
+        (GasProfileSection::SubExecutions, sub_executions),
+    ])
+}
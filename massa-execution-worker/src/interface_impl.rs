@@ -244,6 +244,12 @@ impl Interface for InterfaceImpl {
         // write-lock context
         let mut context = context_guard!(self);
 
+        // trace the call before any check so that its failure is traced as well
+        let coins = Amount::from_raw(raw_coins);
+        if let Some(call_trace) = context.readonly_call_trace.as_mut() {
+            call_trace.enter(to_address, coins);
+        }
+
         // check that the target address is a SC address and if it exists
         context.check_target_sc_address(to_address)?;
 
@@ -260,7 +266,6 @@ impl Interface for InterfaceImpl {
         };
 
         // transfer coins from caller to target address
-        let coins = Amount::from_raw(raw_coins);
         // note: rights are not checked here we checked that to_address is an SC address above
         // and we know that the sender is at the top of the call stack
         if let Err(err) = context.transfer_coins(Some(from_address), Some(to_address), coins, false)
@@ -295,6 +300,10 @@ impl Interface for InterfaceImpl {
             bail!("call stack out of bounds")
         }
 
+        if let Some(call_trace) = context.readonly_call_trace.as_mut() {
+            call_trace.exit();
+        }
+
         Ok(())
     }
 
@@ -303,7 +312,11 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// A `massa-sc-runtime` CL compiled module & the remaining gas after loading the module
     fn get_module(&self, bytecode: &[u8], gas_limit: u64) -> Result<RuntimeModule> {
-        Ok((context_guard!(self))
+        let mut context = context_guard!(self);
+        if let Some(call_trace) = context.readonly_call_trace.as_mut() {
+            call_trace.observe_gas(gas_limit);
+        }
+        Ok(context
             .module_cache
             .write()
             .load_module(bytecode, gas_limit)?)
@@ -314,7 +327,11 @@ impl Interface for InterfaceImpl {
     /// # Returns
     /// A `massa-sc-runtime` SP compiled module & the remaining gas after loading the module
     fn get_tmp_module(&self, bytecode: &[u8], gas_limit: u64) -> Result<RuntimeModule> {
-        Ok((context_guard!(self))
+        let mut context = context_guard!(self);
+        if let Some(call_trace) = context.readonly_call_trace.as_mut() {
+            call_trace.observe_gas(gas_limit);
+        }
+        Ok(context
             .module_cache
             .write()
             .load_tmp_module(bytecode, gas_limit)?)
@@ -1334,15 +1351,21 @@ impl Interface for InterfaceImpl {
     fn init_call_wasmv1(&self, address: &str, raw_coins: NativeAmount) -> Result<Vec<u8>> {
         // get target address
         let to_address = Address::from_str(address)?;
+        let coins = amount_from_native_amount(&raw_coins)?;
+
+        // write-lock context
+        let mut context = context_guard!(self);
+
+        // trace the call before the checks, as in `init_call`
+        if let Some(call_trace) = context.readonly_call_trace.as_mut() {
+            call_trace.enter(to_address, coins);
+        }
 
         // check that the target address is an SC address
         if !matches!(to_address, Address::SC(..)) {
             bail!("called address {} is not an SC address", to_address);
         }
 
-        // write-lock context
-        let mut context = context_guard!(self);
-
         // get target bytecode
         let bytecode = match context.get_bytecode(&to_address) {
             Some(bytecode) => bytecode,
@@ -1356,7 +1379,6 @@ impl Interface for InterfaceImpl {
         };
 
         // transfer coins from caller to target address
-        let coins = amount_from_native_amount(&raw_coins)?;
         // note: rights are not checked here we checked that to_address is an SC address above
         // and we know that the sender is at the top of the call stack
         if let Err(err) = context.transfer_coins(Some(from_address), Some(to_address), coins, false)
@@ -1674,13 +1696,17 @@ impl Interface for InterfaceImpl {
     }
 
     /// Try to get a write lock on the execution context then set the
-    /// gas_used_until_the_last_subexecution field to the given `gas_remaining` value.
+    /// gas_used_until_the_last_subexecution field to the given `gas_remaining` value,
+    /// and the gas_remaining_before_first_subexecution field if it is not set yet.
     ///
     /// If the context is locked, this function does nothing but log a warning.
     fn save_gas_remaining_before_subexecution(&self, gas_remaining: u64) {
         match self.context.try_lock() {
             Some(mut context) => {
                 context.gas_remaining_before_subexecution = Some(gas_remaining);
+                context
+                    .gas_remaining_before_first_subexecution
+                    .get_or_insert(gas_remaining);
             }
             None => {
                 warn!("Context is locked, cannot save gas remaining before subexecution");
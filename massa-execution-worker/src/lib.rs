@@ -69,6 +69,9 @@
 //! ## `speculative_executed_ops.rs`
 //! A speculative (non-final) list of previously executed operations to prevent reuse.
 //!
+//! ## `readonly_call_trace.rs`
+//! Records the tree of the calls made by a read-only execution, when its trace is requested.
+//!
 //! ## `request_queue.rs`
 //! This module contains the implementation of a generic finite-size execution request queue.
 //! It handles requests that come with an MPSC to send back the result of their execution once it's done.
@@ -84,6 +87,7 @@ mod context;
 mod controller;
 mod execution;
 mod interface_impl;
+mod readonly_call_trace;
 mod request_queue;
 mod slot_sequencer;
 mod speculative_async_pool;
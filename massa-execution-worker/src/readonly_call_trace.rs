@@ -0,0 +1,143 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use massa_execution_exports::ReadOnlyCallTrace;
+use massa_models::{address::Address, amount::Amount};
+
+/// Call recorded by a `CallTraceRecorder`
+struct RecordedCall {
+    /// trace of the call, without its sub-calls nor its gas
+    trace: ReadOnlyCallTrace,
+    /// index of the calling call, `None` for the root
+    parent: Option<usize>,
+    /// gas remaining when the call started
+    entry_gas: Option<u64>,
+    /// gas remaining at the first observation after the call returned
+    exit_gas: Option<u64>,
+}
+
+/// Records the tree of the calls made by a read-only execution
+pub struct CallTraceRecorder {
+    /// maximum depth of the recorded calls, the root being at depth 0
+    max_depth: usize,
+    /// maximum number of recorded calls, root included
+    max_calls: usize,
+    /// recorded calls, in call order
+    calls: Vec<RecordedCall>,
+    /// calls in progress, innermost last: index of the recorded call, `None` if it was omitted
+    open: Vec<Option<usize>>,
+    /// calls that returned and wait for the next gas observation to end
+    returned: Vec<usize>,
+}
+
+impl CallTraceRecorder {
+    /// Start recording the calls made by a read-only execution
+    ///
+    /// # arguments
+    /// * `max_depth`: maximum depth of the recorded calls
+    /// * `max_calls`: maximum number of recorded calls
+    /// * `root`: trace of the request target, without its gas
+    /// * `max_gas`: gas given to the execution
+    pub fn new(max_depth: usize, max_calls: usize, root: ReadOnlyCallTrace, max_gas: u64) -> Self {
+        CallTraceRecorder {
+            max_depth,
+            max_calls: max_calls.max(1),
+            calls: vec![RecordedCall {
+                trace: root,
+                parent: None,
+                entry_gas: Some(max_gas),
+                exit_gas: None,
+            }],
+            open: vec![Some(0)],
+            returned: Vec::new(),
+        }
+    }
+
+    /// Record the start of a call made by the smart contract currently running.
+    /// Past the maximum depth or number of calls, it is only counted by its closest recorded caller.
+    pub fn enter(&mut self, target: Address, coins: Amount) {
+        let parent = self.open.iter().rev().flatten().next().copied();
+        if self.open.len() > self.max_depth || self.calls.len() >= self.max_calls {
+            if let Some(parent) = parent {
+                self.calls[parent].trace.omitted_calls += 1;
+            }
+            self.open.push(None);
+            return;
+        }
+        self.open.push(Some(self.calls.len()));
+        self.calls.push(RecordedCall {
+            trace: ReadOnlyCallTrace {
+                target,
+                function: None,
+                parameter_size: None,
+                coins,
+                gas_used: None,
+                error: None,
+                sub_calls: Vec::new(),
+                omitted_calls: 0,
+            },
+            parent,
+            entry_gas: None,
+            exit_gas: None,
+        });
+    }
+
+    /// Record the successful return of the innermost call in progress
+    pub fn exit(&mut self) {
+        // the root only ends with the execution
+        if self.open.len() > 1 {
+            if let Some(Some(index)) = self.open.pop() {
+                self.returned.push(index);
+            }
+        }
+    }
+
+    /// Record the gas remaining when a module is loaded: it ends the calls that returned
+    /// since the previous observation, and starts the innermost call if it was just entered
+    pub fn observe_gas(&mut self, gas_remaining: u64) {
+        for index in self.returned.drain(..) {
+            self.calls[index].exit_gas = Some(gas_remaining);
+        }
+        if let Some(Some(index)) = self.open.last() {
+            self.calls[*index].entry_gas.get_or_insert(gas_remaining);
+        }
+    }
+
+    /// Build the trace of an execution that succeeded with `remaining_gas` left
+    pub fn finish(mut self, remaining_gas: u64) -> ReadOnlyCallTrace {
+        for index in self.returned.drain(..).chain(self.open.drain(..).flatten()) {
+            self.calls[index].exit_gas = Some(remaining_gas);
+        }
+        self.build()
+    }
+
+    /// Build the trace of an execution that failed with `error`.
+    /// The calls in progress failed with it, and the VM does not report the gas left at failure.
+    pub fn fail(mut self, error: &str) -> ReadOnlyCallTrace {
+        for index in self.open.drain(..).flatten() {
+            self.calls[index].trace.error = Some(error.to_string());
+        }
+        self.build()
+    }
+
+    /// Nest the recorded calls into the tree of the root
+    fn build(self) -> ReadOnlyCallTrace {
+        let mut parents = Vec::with_capacity(self.calls.len());
+        let mut traces = Vec::with_capacity(self.calls.len());
+        for call in self.calls {
+            let mut trace = call.trace;
+            trace.gas_used = call
+                .entry_gas
+                .zip(call.exit_gas)
+                .map(|(entry_gas, exit_gas)| entry_gas.saturating_sub(exit_gas));
+            parents.push(call.parent);
+            traces.push(trace);
+        }
+        // a call is always recorded after its parent: nest them from the last one
+        while traces.len() > 1 {
+            let trace = traces.pop().expect("missing call");
+            let parent = parents[traces.len()].expect("only the root has no parent");
+            traces[parent].sub_calls.insert(0, trace);
+        }
+        traces.pop().expect("missing root call")
+    }
+}
@@ -75,6 +75,11 @@ impl<T, R> RequestQueue<T, R> {
         self.max_items
     }
 
+    /// Returns the number of items in the queue
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
     /// Extends Self with the contents of another `RequestQueue`.
     /// The contents of the incoming queue are appended last.
     /// Excess items with respect to `self.max_items` are canceled and dropped.
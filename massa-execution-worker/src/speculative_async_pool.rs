@@ -96,6 +96,48 @@ impl SpeculativeAsyncPool {
         self.message_infos.insert(msg.compute_id(), msg.into());
     }
 
+    /// Messages that can be executed at `slot`, in execution order,
+    /// with the gas each one reserves (its max gas plus the constant cost of an asynchronous message).
+    /// All messages are considered: finals, in active_history and in speculative.
+    pub fn ready_messages(
+        &self,
+        slot: Slot,
+        async_msg_cst_gas_cost: u64,
+    ) -> impl Iterator<Item = (AsyncMessageId, u64)> + '_ {
+        self.message_infos
+            .iter()
+            .filter(move |(_, message_info)| {
+                slot >= message_info.validity_start
+                    && slot < message_info.validity_end
+                    && message_info.can_be_executed
+            })
+            .map(move |(message_id, message_info)| {
+                (
+                    *message_id,
+                    message_info.max_gas.saturating_add(async_msg_cst_gas_cost),
+                )
+            })
+    }
+
+    /// Chooses the ready messages executed at `slot` with `max_gas` available, in execution order:
+    /// a message that does not fit in the remaining gas is skipped.
+    pub fn choose_batch(
+        &self,
+        slot: Slot,
+        max_gas: u64,
+        async_msg_cst_gas_cost: u64,
+    ) -> Vec<(AsyncMessageId, u64)> {
+        let mut available_gas = max_gas;
+        let mut batch = Vec::new();
+        for (message_id, corrected_max_gas) in self.ready_messages(slot, async_msg_cst_gas_cost) {
+            if available_gas >= corrected_max_gas {
+                available_gas -= corrected_max_gas;
+                batch.push((message_id, corrected_max_gas));
+            }
+        }
+        batch
+    }
+
     /// Takes a batch of asynchronous messages to execute,
     /// removing them from the speculative asynchronous pool and settling their deletion from it in the changes accumulator.
     ///
@@ -111,29 +153,13 @@ impl SpeculativeAsyncPool {
         max_gas: u64,
         async_msg_cst_gas_cost: u64,
     ) -> Vec<(AsyncMessageId, AsyncMessage)> {
-        let mut available_gas = max_gas;
-
-        // Choose which messages to take based on self.message_infos
-        // (all messages are considered: finals, in active_history and in speculative)
-
-        let mut wanted_messages = Vec::new();
-
-        let message_infos = self.message_infos.clone();
-
-        for (message_id, message_info) in message_infos.iter() {
-            let corrected_max_gas = message_info.max_gas.saturating_add(async_msg_cst_gas_cost);
-            if available_gas >= corrected_max_gas
-                && slot >= message_info.validity_start
-                && slot < message_info.validity_end
-                && message_info.can_be_executed
-            {
-                available_gas -= corrected_max_gas;
-
-                wanted_messages.push(message_id);
-            }
-        }
+        let wanted_messages: Vec<AsyncMessageId> = self
+            .choose_batch(slot, max_gas, async_msg_cst_gas_cost)
+            .into_iter()
+            .map(|(message_id, _)| message_id)
+            .collect();
 
-        let taken = self.fetch_msgs(wanted_messages, true);
+        let taken = self.fetch_msgs(wanted_messages.iter().collect(), true);
 
         for (message_id, _) in taken.iter() {
             self.message_infos.remove(message_id);
@@ -9,4 +9,7 @@ mod universe;
 #[cfg(test)]
 mod tests_active_history;
 
+#[cfg(test)]
+mod tests_readonly_call_trace;
+
 mod interface;
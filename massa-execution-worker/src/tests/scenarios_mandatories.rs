@@ -1,11 +1,16 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_async_pool::{AsyncMessage, AsyncPool, AsyncPoolChanges, AsyncPoolConfig};
-use massa_db_exports::{DBBatch, ShareableMassaDBController};
-use massa_executed_ops::{ExecutedDenunciations, ExecutedDenunciationsConfig};
+use massa_db_exports::{DBBatch, MassaDBConfig, ShareableMassaDBController};
+use massa_db_worker::MassaDB;
+use massa_executed_ops::{
+    ExecutedDenunciations, ExecutedDenunciationsConfig, ExecutedOps, ExecutedOpsChanges,
+    ExecutedOpsConfig,
+};
 use massa_execution_exports::{
     ExecutionConfig, ExecutionQueryRequest, ExecutionQueryRequestItem, ExecutionStackElement,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    GasProfileSection, OperationExecutionStatus, ReadOnlyErrorKind, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget, SlotExecutionOutput,
 };
 use massa_final_state::test_exports::get_initials;
 use massa_final_state::MockFinalStateController;
@@ -15,15 +20,17 @@ use massa_ledger_exports::{
 };
 use massa_models::bytecode::Bytecode;
 use massa_models::config::{
-    CHAINID, ENDORSEMENT_COUNT, LEDGER_ENTRY_DATASTORE_BASE_SIZE, THREAD_COUNT,
+    CHAINID, ENDORSEMENT_COUNT, LEDGER_ENTRY_DATASTORE_BASE_SIZE, PERIODS_PER_CYCLE,
+    SLASHING_EVENT_EMITTER_ADDRESS, THREAD_COUNT,
 };
 use massa_models::prehash::PreHashMap;
 use massa_models::test_exports::gen_endorsements_for_denunciation;
 use massa_models::{address::Address, amount::Amount, slot::Slot};
 use massa_models::{
-    denunciation::Denunciation,
+    denunciation::{Denunciation, DenunciationIndex},
     execution::EventFilter,
     operation::{Operation, OperationSerializer, OperationType},
+    output_event::SlashingEventData,
     secure_share::SecureShareContent,
 };
 use massa_pos_exports::{
@@ -33,9 +40,10 @@ use massa_signature::KeyPair;
 use massa_test_framework::{TestUniverse, WaitPoint};
 use mockall::predicate;
 use num::rational::Ratio;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::sync::Arc;
 use std::{cmp::Reverse, collections::BTreeMap, str::FromStr, time::Duration};
+use tempfile::TempDir;
 
 use super::universe::{ExecutionForeignControllers, ExecutionTestUniverse};
 
@@ -79,6 +87,16 @@ fn final_state_boilerplate(
         .expect_get_execution_trail_hash()
         .returning(|| Hash::compute_from("Genesis".as_bytes()));
 
+    mock_final_state
+        .write()
+        .expect_get_fingerprint()
+        .returning(|| Hash::compute_from("Genesis".as_bytes()));
+
+    mock_final_state
+        .write()
+        .expect_get_component_fingerprints()
+        .returning(BTreeMap::new);
+
     let pos_final_state = custom_pos_state.unwrap_or_else(|| {
         let (rolls_path, _) = get_initials();
         let mut batch = DBBatch::default();
@@ -137,9 +155,14 @@ fn final_state_boilerplate(
                 thread_count: THREAD_COUNT,
                 endorsement_count: ENDORSEMENT_COUNT,
                 keep_executed_history_extra_periods: 10,
+                periods_per_cycle: PERIODS_PER_CYCLE,
             },
             db.clone(),
         ));
+    mock_final_state
+        .write()
+        .expect_executed_denunciations_count()
+        .return_const(0usize);
 }
 
 fn expect_finalize_deploy_and_call_blocks(
@@ -288,10 +311,15 @@ fn test_readonly_execution() {
             ),
             coins: None,
             fee: Some(Amount::from_str("40").unwrap()),
+            profile: false,
+            rng_seed: None,
+            include_trace: false,
         })
         .expect("readonly execution failed");
 
     assert!(res.gas_cost > 0);
+    assert!(res.gas_profile.is_none());
+    assert!(res.call_trace.is_none());
     assert_eq!(res.out.events.take().len(), 1, "wrong number of events");
     assert_eq!(
         res.out.state_changes.ledger_changes.0.get(&addr).unwrap(),
@@ -333,11 +361,18 @@ fn test_readonly_execution() {
             },
             coins: Some(Amount::from_str("20").unwrap()),
             fee: Some(Amount::from_str("30").unwrap()),
+            profile: true,
+            rng_seed: None,
+            include_trace: false,
         })
         .expect("readonly execution failed");
 
     //assert_eq!(res2.out.slot, Slot::new(0, 1));
     assert!(res2.gas_cost > 0);
+    // the profile splits the exact cost, which the estimated cost can only exceed
+    let gas_profile = res2.gas_profile.take().expect("missing gas profile");
+    assert!(gas_profile[&GasProfileSection::Instantiation] > 0);
+    assert!(gas_profile.values().sum::<u64>() <= res2.gas_cost);
     assert_eq!(res2.out.events.take().len(), 1, "wrong number of events");
     assert_eq!(
         res2.out.state_changes.ledger_changes.0.get(&addr).unwrap(),
@@ -349,6 +384,310 @@ fn test_readonly_execution() {
     );
 }
 
+#[test]
+fn test_readonly_execution_rng_seed() {
+    let exec_cfg = ExecutionConfig::default();
+    let t0 = exec_cfg.t0;
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    foreign_controllers
+        .ledger_controller
+        .set_expectations(|ledger_controller| {
+            ledger_controller
+                .expect_get_balance()
+                .returning(move |_| Some(Amount::from_str("100").unwrap()));
+            ledger_controller
+                .expect_entry_exists()
+                .returning(move |_| true);
+        });
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    let universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg);
+
+    let addr = Address::from_str("AU1LQrXPJ3DVL8SFRqACk31E9MVxBcmCATFiRdpEmgztGxWAx48D").unwrap();
+    // the bytecode emits the first number drawn from the unsafe RNG, as 16 hexadecimal digits
+    let request = |rng_seed: Option<[u8; 32]>| ReadOnlyExecutionRequest {
+        max_gas: 100_000_000,
+        call_stack: vec![ExecutionStackElement {
+            address: addr,
+            coins: Amount::zero(),
+            owned_addresses: vec![],
+            operation_datastore: None,
+        }],
+        target: ReadOnlyExecutionTarget::BytecodeExecution(
+            include_bytes!("./wasm/unsafe_random.wasm").to_vec(),
+        ),
+        coins: None,
+        fee: None,
+        profile: false,
+        rng_seed,
+        include_trace: false,
+    };
+    // returns the slot of the execution and the random number it drew
+    let draw = |rng_seed: Option<[u8; 32]>| {
+        let mut res = universe
+            .module_controller
+            .execute_readonly_request(request(rng_seed))
+            .expect("readonly execution failed");
+        let events = res.out.events.take();
+        assert_eq!(events.len(), 1, "wrong number of events");
+        let random = events.into_iter().next().unwrap().data;
+        assert_eq!(random.len(), 16, "unexpected random number {}", random);
+        (res.out.slot, random)
+    };
+    // read-only requests run after the latest executed slot, which moves every t0
+    let draw_at_next_slot = |previous_slot: Slot, rng_seed: Option<[u8; 32]>| loop {
+        std::thread::sleep(t0.to_duration());
+        let (slot, random) = draw(rng_seed);
+        if slot != previous_slot {
+            return (slot, random);
+        }
+    };
+
+    // without a seed, the RNG is seeded from the execution trail hash of the slot
+    let (first_slot, first_random) = draw(None);
+    let (second_slot, second_random) = draw_at_next_slot(first_slot, None);
+    assert_ne!(first_slot, second_slot);
+    assert_ne!(first_random, second_random);
+
+    // with a seed, the RNG draws the same numbers whatever the slot
+    let (first_slot, first_random) = draw(Some([42; 32]));
+    let (second_slot, second_random) = draw_at_next_slot(first_slot, Some([42; 32]));
+    assert_ne!(first_slot, second_slot);
+    assert_eq!(first_random, second_random);
+
+    // and other numbers with another seed
+    let (_, other_random) = draw(Some([43; 32]));
+    assert_ne!(first_random, other_random);
+}
+
+#[test]
+fn test_readonly_execution_call_trace() {
+    let exec_cfg = ExecutionConfig::default();
+    let max_gas = exec_cfg.max_read_only_gas;
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    foreign_controllers
+        .ledger_controller
+        .set_expectations(|ledger_controller| {
+            ledger_controller
+                .expect_get_bytecode()
+                .returning(move |_| Some(Bytecode(include_bytes!("./wasm/test.wasm").to_vec())));
+            ledger_controller
+                .expect_get_balance()
+                .returning(move |_| Some(Amount::from_str("100").unwrap()));
+            ledger_controller
+                .expect_entry_exists()
+                .returning(move |_| true);
+        });
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    let universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg);
+
+    let caller = Address::from_str("AU1LQrXPJ3DVL8SFRqACk31E9MVxBcmCATFiRdpEmgztGxWAx48D").unwrap();
+    let target =
+        Address::from_str("AS12mzL2UWroPV7zzHpwHnnF74op9Gtw7H55fAmXMnCuVZTFSjZCA").unwrap();
+    // the `test` function calls the `receive` function of the address given as parameter
+    let request = |callee: Address, include_trace: bool| ReadOnlyExecutionRequest {
+        max_gas,
+        call_stack: vec![
+            ExecutionStackElement {
+                address: caller,
+                coins: Amount::zero(),
+                owned_addresses: vec![],
+                operation_datastore: None,
+            },
+            ExecutionStackElement {
+                address: target,
+                coins: Amount::zero(),
+                owned_addresses: vec![],
+                operation_datastore: None,
+            },
+        ],
+        target: ReadOnlyExecutionTarget::FunctionCall {
+            target_addr: target,
+            target_func: "test".to_string(),
+            parameter: callee.to_string().into_bytes(),
+        },
+        coins: None,
+        fee: None,
+        profile: false,
+        rng_seed: None,
+        include_trace,
+    };
+
+    // the trace is only recorded on request
+    let res = universe
+        .module_controller
+        .execute_readonly_request(request(target, false))
+        .expect("readonly execution failed");
+    assert!(res.call_trace.is_none());
+
+    // the root is the called function, its sub-call is only known by its address
+    let res = universe
+        .module_controller
+        .execute_readonly_request(request(target, true))
+        .expect("readonly execution failed");
+    let call_trace = res.call_trace.expect("missing call trace");
+    assert_eq!(call_trace.target, target);
+    assert_eq!(call_trace.function.as_deref(), Some("test"));
+    assert_eq!(
+        call_trace.parameter_size,
+        Some(target.to_string().len() as u64)
+    );
+    assert_eq!(call_trace.error, None);
+    assert_eq!(call_trace.sub_calls.len(), 1);
+    let sub_call = &call_trace.sub_calls[0];
+    assert_eq!(sub_call.target, target);
+    assert_eq!(sub_call.function, None);
+    assert_eq!(sub_call.error, None);
+    assert!(sub_call.sub_calls.is_empty());
+    // the exact cost of the root includes the one of its sub-call, the estimated cost exceeds it
+    let root_gas = call_trace.gas_used.expect("missing root gas");
+    let sub_call_gas = sub_call.gas_used.expect("missing sub-call gas");
+    assert!(sub_call_gas > 0);
+    assert!(sub_call_gas < root_gas);
+    assert!(root_gas <= res.gas_cost);
+
+    // a failed sub-call is traced with the error that stopped the execution
+    let err = universe
+        .module_controller
+        .execute_readonly_request(request(caller, true))
+        .expect_err("calling a user address should fail");
+    let call_trace = err.readonly_call_trace().expect("missing call trace");
+    assert!(call_trace.error.is_some());
+    assert_eq!(call_trace.gas_used, None);
+    assert_eq!(call_trace.sub_calls.len(), 1);
+    let sub_call = &call_trace.sub_calls[0];
+    assert_eq!(sub_call.target, caller);
+    assert_eq!(sub_call.error, call_trace.error);
+    assert_eq!(sub_call.gas_used, None);
+}
+
+#[test]
+fn test_readonly_execution_error_kinds() {
+    let exec_cfg = ExecutionConfig::default();
+    let max_gas = exec_cfg.max_read_only_gas;
+    let max_instance_cost = exec_cfg.gas_costs.max_instance_cost;
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    foreign_controllers
+        .ledger_controller
+        .set_expectations(|ledger_controller| {
+            ledger_controller
+                .expect_get_bytecode()
+                .returning(move |_| Some(Bytecode(include_bytes!("./wasm/test.wasm").to_vec())));
+            ledger_controller
+                .expect_get_balance()
+                .returning(move |_| Some(Amount::from_str("100").unwrap()));
+            ledger_controller
+                .expect_entry_exists()
+                .returning(move |_| true);
+            ledger_controller
+                .expect_get_data_entry()
+                .returning(move |_, _| None);
+            ledger_controller
+                .expect_get_datastore_keys()
+                .returning(move |_, _| None);
+        });
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    let universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg);
+
+    let caller = Address::from_str("AU1LQrXPJ3DVL8SFRqACk31E9MVxBcmCATFiRdpEmgztGxWAx48D").unwrap();
+    let target =
+        Address::from_str("AS12mzL2UWroPV7zzHpwHnnF74op9Gtw7H55fAmXMnCuVZTFSjZCA").unwrap();
+    let call_stack = vec![
+        ExecutionStackElement {
+            address: caller,
+            coins: Amount::zero(),
+            owned_addresses: vec![],
+            operation_datastore: None,
+        },
+        ExecutionStackElement {
+            address: target,
+            coins: Amount::zero(),
+            owned_addresses: vec![],
+            operation_datastore: None,
+        },
+    ];
+    let request = |target: ReadOnlyExecutionTarget, max_gas: u64| ReadOnlyExecutionRequest {
+        max_gas,
+        call_stack: call_stack.clone(),
+        target,
+        coins: None,
+        fee: None,
+        profile: false,
+        rng_seed: None,
+        include_trace: false,
+    };
+    // the `test` function calls the `receive` function of the address given as parameter
+    let call = |function: &str, callee: Address| ReadOnlyExecutionTarget::FunctionCall {
+        target_addr: target,
+        target_func: function.to_string(),
+        parameter: callee.to_string().into_bytes(),
+    };
+    let error_kind = |request: ReadOnlyExecutionRequest| {
+        universe
+            .module_controller
+            .execute_readonly_request(request)
+            .expect_err("readonly execution should fail")
+            .readonly_error_kind()
+    };
+
+    // the VM runs out of gas once the instance is created
+    assert_eq!(
+        error_kind(request(call("test", target), max_instance_cost)),
+        Some(ReadOnlyErrorKind::OutOfGas)
+    );
+    // the module is not even loaded without the gas to create its instance
+    assert_eq!(
+        error_kind(request(call("test", target), max_instance_cost - 1)),
+        Some(ReadOnlyErrorKind::OutOfGas)
+    );
+    // the module has no such export
+    assert_eq!(
+        error_kind(request(call("missing_function", target), max_gas)),
+        Some(ReadOnlyErrorKind::TargetNotFound)
+    );
+    // calling a user address traps
+    assert_eq!(
+        error_kind(request(call("test", caller), max_gas)),
+        Some(ReadOnlyErrorKind::BytecodeTrap)
+    );
+    // the executed bytecode writes in the datastore of an address it does not own
+    assert_eq!(
+        error_kind(request(
+            ReadOnlyExecutionTarget::BytecodeExecution(
+                include_bytes!("./wasm/datastore_manipulations.wasm").to_vec()
+            ),
+            max_gas
+        )),
+        Some(ReadOnlyErrorKind::DatastoreAccessDenied)
+    );
+}
+
 /// Test the gas usage in nested calls using call SC operation
 ///
 /// Create a smart contract and send it in the blockclique.
@@ -549,6 +888,12 @@ fn test_get_call_coins() {
         op_candidate == Some(true) && op_final == Some(true),
         "Expected operation not found or not successfully executed"
     );
+    assert_eq!(
+        universe
+            .module_controller
+            .get_operation_execution_status(&operation.id),
+        OperationExecutionStatus::ExecutedSuccess
+    );
 }
 
 /// # Context
@@ -919,6 +1264,91 @@ fn cancel_async_message() {
     assert!(events[0].data.contains(" is not a smart contract address"));
 }
 
+#[test]
+fn slot_budget_with_ready_async_messages() {
+    let exec_cfg = ExecutionConfig::default();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+
+    let message =
+        |emission_index: u64, fee: u64, max_gas: u64, validity_start: Slot| AsyncMessage {
+            emission_slot: Slot::new(1, 0),
+            emission_index,
+            sender: Address::from_str("AU1TyzwHarZMQSVJgxku8co7xjrRLnH74nFbNpoqNd98YhJkWgi")
+                .unwrap(),
+            destination: Address::from_str("AU12mzL2UWroPV7zzHpwHnnF74op9Gtw7H55fAmXMnCuVZTFSjZCA")
+                .unwrap(),
+            function: String::from("receive"),
+            max_gas,
+            fee: Amount::from_raw(fee),
+            coins: Amount::from_raw(100),
+            validity_start,
+            validity_end: Slot::new(2000, 0),
+            function_params: vec![],
+            trigger: None,
+            can_be_executed: true,
+        };
+    // the messages are valid far enough for the candidate execution of empty slots
+    // not to reach them during the test. The first one has the highest priority and takes all the gas of a slot, so the others do not fit.
+    let filling_gas = exec_cfg.max_async_gas - exec_cfg.async_msg_cst_gas_cost;
+    let messages = vec![
+        message(0, 1_000_000_000, filling_gas, Slot::new(1000, 0)),
+        message(1, 1, 1000, Slot::new(1000, 0)),
+        message(2, 2, 1000, Slot::new(1500, 0)),
+    ];
+    let mut async_pool = AsyncPool::new(AsyncPoolConfig::default(), foreign_controllers.db.clone());
+    let changes = messages
+        .into_iter()
+        .map(|message| (message.compute_id(), SetUpdateOrDelete::Set(message)))
+        .collect();
+    let mut db_batch = DBBatch::default();
+    async_pool.apply_changes_to_batch(&AsyncPoolChanges(changes), &mut db_batch);
+    foreign_controllers
+        .db
+        .write()
+        .write_batch(db_batch, DBBatch::default(), Some(Slot::new(1, 0)));
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        Some(async_pool),
+        None,
+    );
+    let universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg.clone());
+
+    let budget = universe
+        .module_controller
+        .get_slot_budget(Slot::new(1000, 0));
+    assert_eq!(budget.slot, Slot::new(1000, 0));
+    assert_eq!(budget.max_async_gas, exec_cfg.max_async_gas);
+    assert_eq!(budget.max_block_gas, exec_cfg.max_gas_per_block);
+    assert_eq!(budget.ready_async_message_count, 2);
+    assert_eq!(
+        budget.ready_async_gas,
+        exec_cfg.max_async_gas + 1000 + exec_cfg.async_msg_cst_gas_cost
+    );
+    assert_eq!(budget.projected_async_message_count, 1);
+    assert_eq!(budget.projected_async_gas, exec_cfg.max_async_gas);
+    assert_eq!(budget.async_gas_headroom(), 0);
+
+    // the last message is only ready later
+    let budget = universe
+        .module_controller
+        .get_slot_budget(Slot::new(1500, 0));
+    assert_eq!(budget.ready_async_message_count, 3);
+    assert_eq!(budget.projected_async_message_count, 1);
+
+    // no message is ready after their validity
+    let budget = universe
+        .module_controller
+        .get_slot_budget(Slot::new(2000, 0));
+    assert_eq!(budget.ready_async_message_count, 0);
+    assert_eq!(budget.projected_async_gas, 0);
+    assert_eq!(budget.async_gas_headroom(), exec_cfg.max_async_gas);
+}
+
 /// Context
 ///
 /// Functional test for local smart-contract execution
@@ -1295,6 +1725,208 @@ fn send_and_receive_transaction() {
     finalized_waitpoint.wait();
 }
 
+/// Apply the balance changes of a slot to a copy of the balances
+/// Executed operations stored in `db`, as in the final state
+fn new_executed_ops(db: ShareableMassaDBController) -> ExecutedOps {
+    ExecutedOps::new(
+        ExecutedOpsConfig {
+            thread_count: THREAD_COUNT,
+            keep_executed_history_extra_periods: 10,
+            operation_validity_periods: 10,
+        },
+        db,
+    )
+}
+
+/// Apply the executed operations changes of the final `slot` to the executed operations stored in `db`
+fn apply_executed_ops_changes(
+    executed_ops: &mut ExecutedOps,
+    db: &ShareableMassaDBController,
+    changes: ExecutedOpsChanges,
+    slot: Slot,
+) {
+    let mut batch = DBBatch::default();
+    executed_ops.apply_changes_to_batch(changes, slot, &mut batch);
+    db.write()
+        .write_batch(batch, DBBatch::default(), Some(slot));
+}
+
+/// Fingerprints of the components stored in `db`, as computed by the final state
+fn component_fingerprints(db: &ShareableMassaDBController) -> BTreeMap<String, Hash> {
+    db.read()
+        .get_xof_component_hashes()
+        .into_iter()
+        .map(|(prefix, internal_hash)| {
+            (
+                prefix.trim_end_matches('/').to_string(),
+                Hash::compute_from(internal_hash.to_bytes()),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn slot_execution_digest() {
+    let exec_cfg = ExecutionConfig::default();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    let finalized_waitpoint = WaitPoint::new();
+    let finalized_waitpoint_trigger_handle = finalized_waitpoint.get_trigger_handle();
+    let recipient_address =
+        Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+    // the node stores the executed operations of the final slots in its database, and
+    // fingerprints it as the final state does
+    let node_db = foreign_controllers.db.clone();
+    let node_executed_ops = Arc::new(Mutex::new(new_executed_ops(node_db.clone())));
+    let node_db_fingerprint = node_db.clone();
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_get_fingerprint()
+        .returning(move || {
+            Hash::compute_from(node_db_fingerprint.read().get_xof_db_hash().to_bytes())
+        });
+    let node_db_component_fingerprints = node_db.clone();
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_get_component_fingerprints()
+        .returning(move || component_fingerprints(&node_db_component_fingerprints));
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    foreign_controllers
+        .final_state
+        .write()
+        .expect_finalize()
+        .times(1)
+        .with(predicate::eq(Slot::new(1, 0)), predicate::always())
+        .returning(move |slot, changes| {
+            apply_executed_ops_changes(
+                &mut node_executed_ops.lock(),
+                &node_db,
+                changes.executed_ops_changes,
+                slot,
+            );
+            finalized_waitpoint_trigger_handle.trigger();
+        });
+    let mut universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg);
+    let mut receiver = universe.broadcast_channel_receiver.take().unwrap();
+
+    let operation = Operation::new_verifiable(
+        Operation {
+            fee: Amount::from_str("10").unwrap(),
+            expire_period: 10,
+            op: OperationType::Transaction {
+                recipient_address,
+                amount: Amount::from_str("90").unwrap(),
+            },
+        },
+        OperationSerializer::new(),
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        *CHAINID,
+    )
+    .unwrap();
+    universe.storage.store_operations(vec![operation.clone()]);
+    let block = ExecutionTestUniverse::create_block(
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        Slot::new(1, 0),
+        vec![operation.clone()],
+        vec![],
+        vec![],
+    );
+    universe.send_and_finalize(&KeyPair::from_str(TEST_SK_1).unwrap(), block);
+    finalized_waitpoint.wait();
+
+    let exec_out = loop {
+        match receiver
+            .blocking_recv()
+            .expect("execution outputs channel closed")
+        {
+            SlotExecutionOutput::FinalizedSlot(exec_out) if exec_out.slot == Slot::new(1, 0) => {
+                break exec_out
+            }
+            _ => continue,
+        }
+    };
+    let digest = exec_out.digest;
+    assert_eq!(
+        digest.ledger_changes_count,
+        exec_out.state_changes.ledger_changes.0.len()
+    );
+    assert_eq!(digest.async_messages_emitted, 0);
+    assert_eq!(digest.async_messages_taken, 0);
+    let final_state_digest = digest.final_state.expect("missing final state digest");
+    assert_eq!(final_state_digest.change_id, Slot::new(1, 0));
+    let executed_ops_fingerprint = *final_state_digest
+        .component_fingerprints
+        .get("executed_ops")
+        .expect("missing executed operations fingerprint");
+    assert!(exec_out
+        .state_changes
+        .executed_ops_changes
+        .contains_key(&operation.id));
+
+    // a subscriber mirroring the executed operations gets the same fingerprint by applying the
+    // published changes to its own database
+    let shadow_dir = TempDir::new().expect("cannot create temp directory");
+    let shadow_db: ShareableMassaDBController =
+        Arc::new(RwLock::new(Box::new(MassaDB::new(MassaDBConfig {
+            path: shadow_dir.path().to_path_buf(),
+            max_history_length: 10,
+            max_final_state_elements_size: 100_000,
+            max_versioning_elements_size: 100_000,
+            thread_count: THREAD_COUNT,
+            max_ledger_backups: 10,
+        }))));
+    let mut shadow_executed_ops = new_executed_ops(shadow_db.clone());
+    apply_executed_ops_changes(
+        &mut shadow_executed_ops,
+        &shadow_db,
+        exec_out.state_changes.executed_ops_changes.clone(),
+        exec_out.slot,
+    );
+    assert_eq!(
+        component_fingerprints(&shadow_db).get("executed_ops"),
+        Some(&executed_ops_fingerprint)
+    );
+
+    // a diverging copy is detected
+    let not_executed_operation = Operation::new_verifiable(
+        Operation {
+            fee: Amount::from_str("10").unwrap(),
+            expire_period: 10,
+            op: OperationType::Transaction {
+                recipient_address,
+                amount: Amount::from_str("1").unwrap(),
+            },
+        },
+        OperationSerializer::new(),
+        &KeyPair::from_str(TEST_SK_1).unwrap(),
+        *CHAINID,
+    )
+    .unwrap();
+    let mut diverging_changes = ExecutedOpsChanges::default();
+    diverging_changes.insert(not_executed_operation.id, (true, Slot::new(10, 0)));
+    apply_executed_ops_changes(
+        &mut shadow_executed_ops,
+        &shadow_db,
+        diverging_changes,
+        Slot::new(1, 1),
+    );
+    assert_ne!(
+        component_fingerprints(&shadow_db).get("executed_ops"),
+        Some(&executed_ops_fingerprint)
+    );
+}
+
 #[test]
 fn roll_buy() {
     // setup
@@ -1849,6 +2481,114 @@ fn roll_slash_2() {
     waitpoint.wait();
 }
 
+#[test]
+fn roll_slash_event() {
+    // Execute a block carrying a denunciation, then a block carrying the same denunciation again.
+    // Check that exactly one slashing event is emitted
+    let exec_cfg = ExecutionConfig {
+        periods_per_cycle: 2,
+        thread_count: 2,
+        last_start_period: 0,
+        roll_count_to_slash_on_denunciation: 3,
+        max_miss_ratio: Ratio::new(1, 1),
+        ..Default::default()
+    };
+    let keypair = KeyPair::from_str(TEST_SK_1).unwrap();
+    let address = Address::from_public_key(&keypair.get_public_key());
+    let finalized_waitpoint = WaitPoint::new();
+    let mut foreign_controllers = ExecutionForeignControllers::new_with_mocks();
+    foreign_controllers
+        .selector_controller
+        .set_expectations(|selector_controller| {
+            selector_controller
+                .expect_get_selection()
+                .returning(move |_| {
+                    Ok(Selection {
+                        endorsements: vec![address; ENDORSEMENT_COUNT as usize],
+                        producer: address,
+                    })
+                });
+        });
+    selector_boilerplate(&mut foreign_controllers.selector_controller);
+    expect_finalize_deploy_and_call_blocks(
+        Slot::new(1, 0),
+        Some(Slot::new(1, 1)),
+        finalized_waitpoint.get_trigger_handle(),
+        &mut foreign_controllers.final_state,
+    );
+    final_state_boilerplate(
+        &mut foreign_controllers.final_state,
+        foreign_controllers.db.clone(),
+        &foreign_controllers.selector_controller,
+        &mut foreign_controllers.ledger_controller,
+        None,
+        None,
+        None,
+    );
+    let mut universe = ExecutionTestUniverse::new(foreign_controllers, exec_cfg);
+
+    let (_slot, _keypair, s_endorsement_1, s_endorsement_2, _) =
+        gen_endorsements_for_denunciation(Some(Slot::new(1, 0)), Some(keypair.clone()));
+    let denunciation = Denunciation::try_from((&s_endorsement_1, &s_endorsement_2)).unwrap();
+    let slashing_filter = EventFilter {
+        emitter_address: Some(*SLASHING_EVENT_EMITTER_ADDRESS),
+        ..Default::default()
+    };
+
+    let block = ExecutionTestUniverse::create_block(
+        &keypair,
+        Slot::new(1, 0),
+        vec![],
+        vec![],
+        vec![denunciation.clone()],
+    );
+    universe.send_and_finalize(&keypair, block);
+    finalized_waitpoint.wait();
+
+    let events = universe
+        .module_controller
+        .get_filtered_sc_output_event(slashing_filter.clone());
+    assert_eq!(events.len(), 1, "exactly one slashing event was expected");
+    assert_eq!(events[0].context.slot, Slot::new(1, 0));
+    assert!(!events[0].context.is_error);
+    assert_eq!(events[0].context.origin_operation_id, None);
+    let data: serde_json::Value = serde_json::from_str(&events[0].data).unwrap();
+    let data: SlashingEventData = serde_json::from_value(data["massa_slashing"].clone()).unwrap();
+    // 3 rolls slashed at 100 coins each, half for the block producer
+    assert_eq!(
+        data,
+        SlashingEventData {
+            denounced_address: address,
+            denunciation: DenunciationIndex::from(&denunciation),
+            slashed_rolls: 3,
+            slashed_amount: Amount::from_mantissa_scale(300, 0).unwrap(),
+            credited_amount: Amount::from_mantissa_scale(150, 0).unwrap(),
+            burned_amount: Amount::from_mantissa_scale(150, 0).unwrap(),
+        }
+    );
+
+    // the denunciation is already executed: including it again does not emit anything
+    let block = ExecutionTestUniverse::create_block(
+        &keypair,
+        Slot::new(1, 1),
+        vec![],
+        vec![],
+        vec![denunciation],
+    );
+    universe.send_and_finalize(&keypair, block);
+    finalized_waitpoint.wait();
+
+    let events = universe
+        .module_controller
+        .get_filtered_sc_output_event(slashing_filter);
+    assert_eq!(
+        events.len(),
+        1,
+        "the replayed denunciation emitted an event"
+    );
+    assert_eq!(events[0].context.slot, Slot::new(1, 0));
+}
+
 #[test]
 fn sc_execution_error() {
     let exec_cfg = ExecutionConfig::default();
@@ -2332,7 +3072,7 @@ fn not_enough_instance_gas() {
         .get_filtered_sc_output_event(EventFilter::default());
     assert!(events[0]
         .data
-        .contains("is lower than the base instance creation gas"));
+        .contains("is lower than the instance creation gas cost"));
 }
 
 #[test]
@@ -51,6 +51,7 @@ fn test_active_history_deferred_credits() {
             },
             executed_ops_changes: Default::default(),
             executed_denunciations_changes: Default::default(),
+            executed_denunciations_slashings: Default::default(),
             execution_trail_hash_change: Default::default(),
         },
         events: Default::default(),
@@ -61,6 +62,7 @@ fn test_active_history_deferred_credits() {
         deferred_credits_execution: Default::default(),
         cancel_async_message_execution: Default::default(),
         auto_sell_execution: Default::default(),
+        digest: Default::default(),
     };
 
     let active_history = ActiveHistory(VecDeque::from([exec_output_1]));
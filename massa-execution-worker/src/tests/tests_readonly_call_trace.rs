@@ -0,0 +1,84 @@
+use crate::readonly_call_trace::CallTraceRecorder;
+use massa_execution_exports::ReadOnlyCallTrace;
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use std::str::FromStr;
+
+fn root_call(target: Address) -> ReadOnlyCallTrace {
+    ReadOnlyCallTrace {
+        target,
+        function: Some("main".to_string()),
+        parameter_size: Some(0),
+        coins: Amount::zero(),
+        gas_used: None,
+        error: None,
+        sub_calls: vec![],
+        omitted_calls: 0,
+    }
+}
+
+#[test]
+fn test_readonly_call_trace_gas() {
+    let target =
+        Address::from_str("AS12mzL2UWroPV7zzHpwHnnF74op9Gtw7H55fAmXMnCuVZTFSjZCA").unwrap();
+    let mut recorder = CallTraceRecorder::new(16, 256, root_call(target), 1000);
+
+    // root -> first -> nested, then root -> second
+    recorder.enter(target, Amount::from_raw(1));
+    recorder.observe_gas(900);
+    recorder.enter(target, Amount::zero());
+    recorder.observe_gas(800);
+    recorder.exit();
+    recorder.exit();
+    recorder.enter(target, Amount::zero());
+    recorder.observe_gas(600);
+    recorder.exit();
+    let call_trace = recorder.finish(500);
+
+    assert_eq!(call_trace.gas_used, Some(500));
+    assert_eq!(call_trace.sub_calls.len(), 2);
+    let first = &call_trace.sub_calls[0];
+    assert_eq!(first.coins, Amount::from_raw(1));
+    // the first call and its nested call both end when the second call starts
+    assert_eq!(first.gas_used, Some(300));
+    assert_eq!(first.sub_calls.len(), 1);
+    assert_eq!(first.sub_calls[0].gas_used, Some(200));
+    assert_eq!(call_trace.sub_calls[1].gas_used, Some(100));
+}
+
+#[test]
+fn test_readonly_call_trace_limits_and_failure() {
+    let target =
+        Address::from_str("AS12mzL2UWroPV7zzHpwHnnF74op9Gtw7H55fAmXMnCuVZTFSjZCA").unwrap();
+    // at most one level of sub-calls and three calls, root included
+    let mut recorder = CallTraceRecorder::new(1, 3, root_call(target), 1000);
+
+    recorder.enter(target, Amount::zero());
+    recorder.observe_gas(900);
+    // too deep
+    recorder.enter(target, Amount::zero());
+    recorder.observe_gas(850);
+    recorder.exit();
+    recorder.exit();
+    recorder.enter(target, Amount::zero());
+    recorder.exit();
+    // too many calls
+    recorder.enter(target, Amount::zero());
+    recorder.exit();
+    // also too many calls, then the execution fails
+    recorder.enter(target, Amount::zero());
+    let call_trace = recorder.fail("boom");
+
+    assert_eq!(call_trace.error.as_deref(), Some("boom"));
+    assert_eq!(call_trace.gas_used, None);
+    assert_eq!(call_trace.omitted_calls, 2);
+    assert_eq!(call_trace.sub_calls.len(), 2);
+    let first = &call_trace.sub_calls[0];
+    assert!(first.sub_calls.is_empty());
+    assert_eq!(first.omitted_calls, 1);
+    assert_eq!(first.error, None);
+    assert_eq!(first.gas_used, None);
+    // returned without any later observation
+    assert_eq!(call_trace.sub_calls[1].error, None);
+    assert_eq!(call_trace.sub_calls[1].gas_used, None);
+}
@@ -2,7 +2,34 @@
 
 //! This file defines the factory settings
 
+use crate::FactoryConfigError;
+use massa_models::{address::Address, amount::Amount, prehash::PreHashSet};
 use massa_time::MassaTime;
+use serde::Deserialize;
+
+/// Restriction of the blocks endorsed by the factory, according to their producer
+///
+/// Endorsements count in the fitness of the endorsed block, and its producer is rewarded for including them:
+/// withholding them weakens the blocks of the filtered-out producers in the blockclique.
+/// The endorsements that are not produced are lost, along with their rewards.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndorsementProducerFilter {
+    /// endorse only the blocks produced by these addresses
+    Allow(PreHashSet<Address>),
+    /// endorse every block except the ones produced by these addresses
+    Deny(PreHashSet<Address>),
+}
+
+impl EndorsementProducerFilter {
+    /// Whether the blocks of `producer` can be endorsed
+    pub fn allows(&self, producer: &Address) -> bool {
+        match self {
+            EndorsementProducerFilter::Allow(addresses) => addresses.contains(producer),
+            EndorsementProducerFilter::Deny(addresses) => !addresses.contains(producer),
+        }
+    }
+}
 
 /// Structure defining the settings of the factory
 #[derive(Debug, Clone)]
@@ -21,14 +48,55 @@ pub struct FactoryConfig {
     pub max_block_gas: u64,
     /// maximum number of operation ids in block
     pub max_operations_per_block: u32,
+    /// number of block production reports kept by the factory
+    pub production_history_length: usize,
+    /// minimal fee of the operations included in the produced blocks, the cheaper ones stay in the pool
+    pub min_operation_fee: Amount,
     /// last start period, to deduce genesis blocks
     pub last_start_period: u64,
     /// cycle duration in periods
     pub periods_per_cycle: u64,
     /// denunciation expiration as periods
     pub denunciation_expire_periods: u64,
+    /// maximum number of denunciations in a block header
+    pub max_denunciations_per_block_header: u32,
+    /// number of slots before the produced one whose denunciations can be included in the block
+    pub denunciation_lookback_slots: u64,
     /// choose whether to stop production when zero connections on protocol
     pub stop_production_when_zero_connections: bool,
+    /// whether to start the block factory worker
+    pub enable_block_production: bool,
+    /// whether to start the endorsement factory worker
+    pub enable_endorsement_production: bool,
+    /// whether the produced block headers include the denunciations proposed by the pool
+    pub enable_denunciation: bool,
+    /// whether the produced blocks and endorsements are published to the broadcast channels
+    pub broadcast_enabled: bool,
     /// chain id
     pub chain_id: u64,
+    /// initial set of addresses whose operations are left out of the produced blocks
+    pub production_blocklist: PreHashSet<Address>,
+    /// producers whose blocks are endorsed, all of them if `None`
+    pub endorsement_producer_filter: Option<EndorsementProducerFilter>,
+}
+
+impl FactoryConfig {
+    /// Number of slots over which a denunciation stays valid: a lookback beyond it would only
+    /// propose expired denunciations.
+    pub fn max_denunciation_lookback_slots(&self) -> u64 {
+        self.denunciation_expire_periods
+            .saturating_mul(self.thread_count as u64)
+    }
+
+    /// Checks the consistency of the configuration before starting the factory.
+    pub fn validate(&self) -> Result<(), FactoryConfigError> {
+        let max = self.max_denunciation_lookback_slots();
+        if self.denunciation_lookback_slots > max {
+            return Err(FactoryConfigError::DenunciationLookbackTooLong {
+                lookback: self.denunciation_lookback_slots,
+                max,
+            });
+        }
+        Ok(())
+    }
 }
@@ -3,11 +3,31 @@
 //! This module exports generic traits representing interfaces for interacting
 //! with the factory worker.
 
+use std::sync::Arc;
+
+use massa_models::{address::Address, prehash::PreHashSet};
+use parking_lot::RwLock;
+
+use crate::{BlockProductionReport, ProductionHistory};
+
 /// Factory manager used to stop the factory thread
 pub trait FactoryManager {
+    /// Get a shared handle on the block production history of the factory
+    fn get_production_history(&self) -> Arc<RwLock<ProductionHistory>>;
+
     /// Stop the factory thread
     /// Note that we do not take self by value to consume it
     /// because it is not allowed to move out of `Box<dyn FactoryManager>`
     /// This will improve if the `unsized_fn_params` feature stabilizes enough to be safely usable.
     fn stop(&mut self);
+
+    /// Replace the set of addresses whose operations are left out of the blocks produced by the node.
+    /// Block validation, propagation and endorsement production are not affected.
+    fn set_production_blocklist(&self, blocklist: PreHashSet<Address>);
+
+    /// Get the production blocklist shared with the block factory, so that it can be updated at runtime
+    fn get_production_blocklist(&self) -> Arc<RwLock<PreHashSet<Address>>>;
+
+    /// Get the report of the latest block produced by the node, if any
+    fn get_last_block_production_report(&self) -> Option<BlockProductionReport>;
 }
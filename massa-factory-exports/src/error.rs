@@ -11,3 +11,16 @@ pub enum FactoryError {
     /// Generic error: {0}
     GenericError(String),
 }
+
+/// Inconsistencies of a factory configuration, detected before starting the factory.
+#[non_exhaustive]
+#[derive(Display, Error, Debug)]
+pub enum FactoryConfigError {
+    /// `denunciation_lookback_slots` is {lookback} but denunciations expire after {max} slots
+    DenunciationLookbackTooLong {
+        /// configured lookback
+        lookback: u64,
+        /// denunciation validity window, in slots
+        max: u64,
+    },
+}
@@ -1,6 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::FactoryConfig;
+use massa_models::amount::Amount;
 use massa_time::MassaTime;
 
 impl Default for FactoryConfig {
@@ -14,11 +15,21 @@ impl Default for FactoryConfig {
             max_block_size: MAX_BLOCK_SIZE as u64,
             max_block_gas: MAX_GAS_PER_BLOCK,
             max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+            production_history_length: PRODUCTION_HISTORY_LENGTH,
+            min_operation_fee: Amount::zero(),
             last_start_period: 0,
             periods_per_cycle: PERIODS_PER_CYCLE,
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+            max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+            denunciation_lookback_slots: DENUNCIATION_EXPIRE_PERIODS * THREAD_COUNT as u64,
             stop_production_when_zero_connections: false,
+            enable_block_production: true,
+            enable_endorsement_production: true,
+            enable_denunciation: true,
+            broadcast_enabled: true,
             chain_id: *CHAINID,
+            production_blocklist: Default::default(),
+            endorsement_producer_filter: None,
         }
     }
 }
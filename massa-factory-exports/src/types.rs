@@ -1,13 +1,113 @@
+use std::collections::VecDeque;
+
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_consensus_exports::ConsensusController;
-use massa_models::block::Block;
+use massa_execution_exports::ExecutionController;
+use massa_models::{
+    address::Address,
+    block::{FilledBlock, SecureShareBlock},
+    block_id::BlockId,
+    endorsement::SecureShareEndorsement,
+    slot::Slot,
+};
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
 use massa_protocol_exports::ProtocolController;
 use massa_storage::Storage;
 
-/// History of block production from latest to oldest
-/// todo: redesign type (maybe add slots, draws...)
-pub type ProductionHistory = Vec<Block>;
+/// Outcome of a slot for which an address of the node was drawn to produce a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SlotProductionOutcome {
+    /// a block was produced and sent to consensus
+    Produced {
+        /// id of the produced block
+        block_id: BlockId,
+        /// number of operations included in the block
+        operation_count: usize,
+        /// number of endorsements included in the block
+        endorsement_count: usize,
+        /// number of denunciations included in the block
+        denunciation_count: usize,
+    },
+    /// no block was produced
+    Skipped {
+        /// why the block was not produced
+        reason: String,
+    },
+}
+
+/// Report of the block production at a slot for which an address of the node was drawn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotProductionReport {
+    /// slot of the block
+    pub slot: Slot,
+    /// drawn block producer
+    pub producer: Address,
+    /// outcome of the production
+    pub outcome: SlotProductionOutcome,
+}
+
+/// History of block production from latest to oldest, bounded to the last reports
+#[derive(Debug, Clone)]
+pub struct ProductionHistory {
+    /// maximal number of kept reports
+    max_length: usize,
+    /// reports, the latest first
+    reports: VecDeque<SlotProductionReport>,
+}
+
+impl ProductionHistory {
+    /// Creates an empty history keeping at most `max_length` reports
+    pub fn new(max_length: usize) -> Self {
+        ProductionHistory {
+            max_length,
+            reports: VecDeque::with_capacity(max_length),
+        }
+    }
+
+    /// Adds the report of the latest processed slot, forgetting the oldest report when full
+    pub fn push(&mut self, report: SlotProductionReport) {
+        if self.max_length == 0 {
+            return;
+        }
+        if self.reports.len() >= self.max_length {
+            self.reports.pop_back();
+        }
+        self.reports.push_front(report);
+    }
+
+    /// Iterates over the reports, the latest first
+    pub fn iter(&self) -> impl Iterator<Item = &SlotProductionReport> {
+        self.reports.iter()
+    }
+
+    /// Number of kept reports
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    /// Whether no report was kept
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+}
+
+/// Report of the production of a block by the factory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockProductionReport {
+    /// slot of the block
+    pub slot: Slot,
+    /// id of the block
+    pub block_id: BlockId,
+    /// number of operations included in the block
+    pub operation_count: usize,
+    /// number of operations left out because their creator is in the production blocklist
+    pub excluded_operation_count: usize,
+    /// number of denunciations included in the block header
+    pub denunciation_count: usize,
+    /// number of denunciations the pool proposed for the block
+    pub available_denunciation_count: usize,
+}
 
 /// List of channels the factory will send commands to
 #[derive(Clone)]
@@ -18,8 +118,25 @@ pub struct FactoryChannels {
     pub consensus: Box<dyn ConsensusController>,
     /// pool controller
     pub pool: Box<dyn PoolController>,
+    /// execution controller, to check that denunciations were not executed yet
+    pub execution: Box<dyn ExecutionController>,
     /// protocol controller
     pub protocol: Box<dyn ProtocolController>,
     /// storage instance
     pub storage: Storage,
+    /// broadcasts of the produced blocks and endorsements
+    pub broadcasts: FactoryBroadcasts,
+}
+
+/// Broadcast channels shared with consensus and pool, on which the factory publishes what it produces
+/// as soon as it is signed. The later announcement of the same object by consensus or pool is suppressed
+/// by the deduplication of the channels.
+#[derive(Clone)]
+pub struct FactoryBroadcasts {
+    /// blocks broadcast channel of consensus
+    pub block_sender: DedupBroadcaster<SecureShareBlock>,
+    /// filled blocks broadcast channel of consensus
+    pub filled_block_sender: DedupBroadcaster<FilledBlock>,
+    /// endorsements broadcast channel of pool
+    pub endorsement_sender: DedupBroadcaster<SecureShareEndorsement>,
 }
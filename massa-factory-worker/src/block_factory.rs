@@ -1,32 +1,45 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_channel::receiver::MassaReceiver;
-use massa_factory_exports::{FactoryChannels, FactoryConfig};
+use massa_factory_exports::{
+    BlockProductionReport, FactoryChannels, FactoryConfig, ProductionHistory,
+    SlotProductionOutcome, SlotProductionReport,
+};
 use massa_models::{
-    block::{Block, BlockSerializer},
-    block_header::{BlockHeader, BlockHeaderSerializer, SecuredHeader},
+    address::Address,
+    block::{Block, BlockSerializer, FilledBlock},
+    block_header::{BlockHeader, SecuredHeader},
+    block_header_versioned::{BlockHeaderFormats, VersionedBlockHeaderSerializer},
     block_id::BlockId,
+    config::ENDORSEMENT_COUNT,
+    denunciation::{Denunciation, DenunciationIndex},
     endorsement::SecureShareEndorsement,
     operation::{compute_operations_hash, OperationIdSerializer},
+    prehash::PreHashSet,
     secure_share::SecureShareContent,
     slot::Slot,
-    timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
+    timeslots::{get_block_slot_timestamp, SlotClock},
 };
-use massa_time::MassaTime;
+use massa_storage::Storage;
 use massa_versioning::versioning::MipStore;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::{sync::Arc, thread, time::Instant};
-use tracing::{info, warn};
+use tracing::{info, trace, warn};
 
 /// Structure gathering all elements needed by the factory thread
 pub(crate) struct BlockFactoryWorker {
     cfg: FactoryConfig,
+    slot_clock: SlotClock,
     wallet: Arc<RwLock<Wallet>>,
     channels: FactoryChannels,
     factory_receiver: MassaReceiver<()>,
     mip_store: MipStore,
     op_id_serializer: OperationIdSerializer,
+    header_serializer: VersionedBlockHeaderSerializer,
+    production_blocklist: Arc<RwLock<PreHashSet<Address>>>,
+    last_block_production_report: Arc<RwLock<Option<BlockProductionReport>>>,
+    production_history: Arc<RwLock<ProductionHistory>>,
 }
 
 impl BlockFactoryWorker {
@@ -38,17 +51,33 @@ impl BlockFactoryWorker {
         channels: FactoryChannels,
         factory_receiver: MassaReceiver<()>,
         mip_store: MipStore,
+        production_blocklist: Arc<RwLock<PreHashSet<Address>>>,
+        last_block_production_report: Arc<RwLock<Option<BlockProductionReport>>>,
+        production_history: Arc<RwLock<ProductionHistory>>,
     ) -> thread::JoinHandle<()> {
         thread::Builder::new()
             .name("block-factory".into())
             .spawn(|| {
+                let header_serializer =
+                    VersionedBlockHeaderSerializer::new(Arc::new(BlockHeaderFormats::new(
+                        cfg.thread_count,
+                        ENDORSEMENT_COUNT,
+                        cfg.max_denunciations_per_block_header,
+                        Some(cfg.last_start_period),
+                        cfg.chain_id,
+                    )));
                 let mut this = Self {
+                    slot_clock: SlotClock::new(cfg.thread_count, cfg.t0, cfg.genesis_timestamp),
                     cfg,
                     wallet,
                     channels,
                     factory_receiver,
                     mip_store,
                     op_id_serializer: OperationIdSerializer::new(),
+                    header_serializer,
+                    production_blocklist,
+                    last_block_production_report,
+                    production_history,
                 };
                 this.run();
             })
@@ -59,48 +88,23 @@ impl BlockFactoryWorker {
     /// Slots can be skipped if we waited too much in-between.
     /// Extra safety against double-production caused by clock adjustments (this is the role of the `previous_slot` parameter).
     fn get_next_slot(&self, previous_slot: Option<Slot>) -> (Slot, Instant) {
-        // get current absolute time
-        let now = MassaTime::now();
-
-        // if it's the first computed slot, add a time shift to prevent double-production on node restart with clock skew
-        let base_time = if previous_slot.is_none() {
-            now.saturating_add(self.cfg.initial_delay)
-        } else {
-            now
+        let mut next_slot = match previous_slot {
+            // if it's the first computed slot, add a time shift to prevent double-production on node restart with clock skew
+            None => self
+                .slot_clock
+                .closest_slot_at(self.slot_clock.now().saturating_add(self.cfg.initial_delay)),
+            Some(_) => self.slot_clock.next_slot_after(previous_slot),
         };
 
-        // get closest slot according to the current absolute time
-        let mut next_slot = get_closest_slot_to_timestamp(
-            self.cfg.thread_count,
-            self.cfg.t0,
-            self.cfg.genesis_timestamp,
-            base_time,
-        );
-
         // ignore genesis
         if next_slot.period <= self.cfg.last_start_period {
             next_slot = Slot::new(self.cfg.last_start_period + 1, 0);
         }
 
-        // protection against double-production on unexpected system clock adjustment
-        if let Some(prev_slot) = previous_slot {
-            if next_slot <= prev_slot {
-                next_slot = prev_slot
-                    .get_next_slot(self.cfg.thread_count)
-                    .expect("could not compute next slot");
-            }
-        }
-
-        // get the timestamp of the target slot
-        let next_instant = get_block_slot_timestamp(
-            self.cfg.thread_count,
-            self.cfg.t0,
-            self.cfg.genesis_timestamp,
-            next_slot,
-        )
-        .expect("could not get block slot timestamp")
-        .estimate_instant()
-        .expect("could not estimate block slot instant");
+        let next_instant = self
+            .slot_clock
+            .slot_instant(next_slot)
+            .expect("could not estimate block slot instant");
 
         (next_slot, next_instant)
     }
@@ -120,6 +124,56 @@ impl BlockFactoryWorker {
         }
     }
 
+    /// Record the outcome of a slot for which one of the managed keys was drawn
+    fn report_production(&self, slot: Slot, producer: Address, outcome: SlotProductionOutcome) {
+        self.production_history.write().push(SlotProductionReport {
+            slot,
+            producer,
+            outcome,
+        });
+    }
+
+    /// Gets the denunciations to include in the header of the block at `slot`, leaving out the ones
+    /// executed since the pool last checked them and the ones older than the configured lookback.
+    /// None are included if denunciations are disabled.
+    ///
+    /// # Return value
+    /// Returns the denunciations and the number of denunciations proposed by the pool.
+    fn get_block_denunciations(&self, slot: &Slot) -> (Vec<Denunciation>, usize) {
+        if !self.cfg.enable_denunciation {
+            return (Vec::new(), 0);
+        }
+        // the pool leaves out the denunciations older than the lookback before filling the header
+        let available = self
+            .channels
+            .pool
+            .get_block_denunciations(slot, &self.denunciation_lookback_start(slot));
+        let available_count = available.len();
+        let denunciations = available
+            .into_iter()
+            .filter(|denunciation| {
+                !self
+                    .channels
+                    .execution
+                    .get_denunciation_execution_status(&DenunciationIndex::from(denunciation))
+                    .0
+            })
+            .take(self.cfg.max_denunciations_per_block_header as usize)
+            .collect();
+        (denunciations, available_count)
+    }
+
+    /// First slot within the denunciation lookback of the block at `slot`
+    fn denunciation_lookback_start(&self, slot: &Slot) -> Slot {
+        let thread_count = self.cfg.thread_count as u64;
+        let index = slot
+            .period
+            .saturating_mul(thread_count)
+            .saturating_add(slot.thread as u64)
+            .saturating_sub(self.cfg.denunciation_lookback_slots);
+        Slot::new(index / thread_count, (index % thread_count) as u8)
+    }
+
     /// Process a slot: produce a block at that slot if one of the managed keys is drawn.
     fn process_slot(&mut self, slot: Slot) {
         // get block producer address for that slot
@@ -165,6 +219,13 @@ impl BlockFactoryWorker {
             if let Ok(stats) = self.channels.protocol.get_stats() {
                 if stats.1.is_empty() {
                     warn!("block factory could not produce block for slot {} because there are no connections", slot);
+                    self.report_production(
+                        slot,
+                        block_producer_addr,
+                        SlotProductionOutcome::Skipped {
+                            reason: "no connections".to_string(),
+                        },
+                    );
                     return;
                 }
             }
@@ -178,6 +239,10 @@ impl BlockFactoryWorker {
         // will not panic because the thread is validated before the call
         let (same_thread_parent_id, _) = parents[slot.thread as usize];
 
+        // gather denunciations before the operations: they are part of the header so they don't
+        // compete with the operations for the block space
+        let (denunciations, available_denunciation_count) = self.get_block_denunciations(&slot);
+
         // gather endorsements
         let (endorsements_ids, endo_storage) = self
             .channels
@@ -195,23 +260,64 @@ impl BlockFactoryWorker {
                         .expect("could not retrieve endorsement")
                         .clone()
                 })
+                .filter(|endo| {
+                    let valid = endo.is_valid_for(slot, &same_thread_parent_id);
+                    if !valid {
+                        warn!(
+                            "pool returned endorsement {} which does not endorse {} at slot {}",
+                            endo.id, same_thread_parent_id, slot
+                        );
+                    }
+                    valid
+                })
                 .collect()
         };
         block_storage.extend(endo_storage);
 
-        // gather operations and compute global operations hash
-        let (op_ids, op_storage) = self.channels.pool.get_block_operations(&slot);
+        // gather operations, leaving out the ones created by blocklisted addresses
+        // or paying less than the fee floor, and compute global operations hash
+        let production_blocklist = self.production_blocklist.read().clone();
+        let (op_ids, op_storage, excluded_operation_count) = self
+            .channels
+            .pool
+            .get_block_operations(&slot, &production_blocklist, self.cfg.min_operation_fee);
         if op_ids.len() > self.cfg.max_operations_per_block as usize {
             warn!("Too many operations returned");
+            self.report_production(
+                slot,
+                block_producer_addr,
+                SlotProductionOutcome::Skipped {
+                    reason: format!("too many operations returned by the pool: {}", op_ids.len()),
+                },
+            );
             return;
         }
 
         block_storage.extend(op_storage);
 
-        // create header
-        let current_version = self.mip_store.get_network_version_current();
+        let operation_count = op_ids.len();
+        let endorsement_count = endorsements.len();
+        let denunciation_count = denunciations.len();
+
+        // create header, in the format of the network version active at the slot
+        let slot_timestamp = match get_block_slot_timestamp(
+            self.cfg.thread_count,
+            self.cfg.t0,
+            self.cfg.genesis_timestamp,
+            slot,
+        ) {
+            Ok(timestamp) => timestamp,
+            Err(err) => {
+                warn!(
+                    "block factory could not get the timestamp of slot {}: {}",
+                    slot, err
+                );
+                return;
+            }
+        };
+        let current_version = self.mip_store.get_network_version_active_at(slot_timestamp);
         let announced_version = self.mip_store.get_network_version_to_announce();
-        let header: SecuredHeader = BlockHeader::new_verifiable::<BlockHeaderSerializer, BlockId>(
+        let header = BlockHeader::new_verifiable::<VersionedBlockHeaderSerializer, BlockId>(
             BlockHeader {
                 current_version,
                 announced_version,
@@ -219,13 +325,22 @@ impl BlockFactoryWorker {
                 parents: parents.into_iter().map(|(id, _period)| id).collect(),
                 operation_merkle_root: compute_operations_hash(&op_ids, &self.op_id_serializer),
                 endorsements,
-                denunciations: self.channels.pool.get_block_denunciations(&slot),
+                denunciations,
             },
-            BlockHeaderSerializer::new(), // TODO reuse self.block_header_serializer
+            self.header_serializer.clone(),
             block_producer_keypair,
             self.cfg.chain_id,
-        )
-        .expect("error while producing block header");
+        );
+        let header: SecuredHeader = match header {
+            Ok(header) => header,
+            Err(err) => {
+                warn!(
+                    "block factory could not produce the header of slot {} for network version {}: {}",
+                    slot, current_version, err
+                );
+                return;
+            }
+        };
         // create block
         let block_ = Block {
             header,
@@ -243,11 +358,41 @@ impl BlockFactoryWorker {
         // store block in storage
         block_storage.store_block(block);
 
+        // publish the block without waiting for consensus, which announces it again later
+        if self.cfg.broadcast_enabled {
+            self.broadcast_block(block_id, &block_storage);
+        }
+
         // log block creation
         info!(
             "block {} created at slot {} by address {}",
             block_id, slot, block_producer_addr
         );
+        if excluded_operation_count > 0 {
+            info!(
+                "{} operations left out of block {} by the production blocklist",
+                excluded_operation_count, block_id
+            );
+        }
+        *self.last_block_production_report.write() = Some(BlockProductionReport {
+            slot,
+            block_id,
+            operation_count,
+            excluded_operation_count,
+            denunciation_count,
+            available_denunciation_count,
+        });
+
+        self.report_production(
+            slot,
+            block_producer_addr,
+            SlotProductionOutcome::Produced {
+                block_id,
+                operation_count,
+                endorsement_count,
+                denunciation_count,
+            },
+        );
 
         // send full block to consensus
         self.channels
@@ -255,6 +400,46 @@ impl BlockFactoryWorker {
             .register_block(block_id, slot, block_storage, true);
     }
 
+    /// Send a produced block to the block and filled block broadcast channels, flagged as locally produced
+    fn broadcast_block(&self, block_id: BlockId, block_storage: &Storage) {
+        let Some(block) = block_storage.read_blocks().get(&block_id).cloned() else {
+            return;
+        };
+        let operations = {
+            let stored_operations = block_storage.read_operations();
+            block
+                .content
+                .operations
+                .iter()
+                .map(|op_id| (*op_id, stored_operations.get(op_id).cloned()))
+                .collect()
+        };
+        let filled_block = FilledBlock {
+            header: block.content.header.clone(),
+            operations,
+            locally_produced: true,
+        };
+        if let Err(err) = self.channels.broadcasts.block_sender.send(block) {
+            trace!(
+                "error, failed to broadcast produced block {}: {}",
+                block_id,
+                err
+            );
+        }
+        if let Err(err) = self
+            .channels
+            .broadcasts
+            .filled_block_sender
+            .send(filled_block)
+        {
+            trace!(
+                "error, failed to broadcast produced filled block {}: {}",
+                block_id,
+                err
+            );
+        }
+    }
+
     /// main run loop of the block creator thread
     fn run(&mut self) {
         let mut prev_slot = None;
@@ -7,14 +7,14 @@ use massa_models::{
     endorsement::{Endorsement, EndorsementSerializer, SecureShareEndorsement},
     secure_share::SecureShareContent,
     slot::Slot,
-    timeslots::{get_block_slot_timestamp, get_closest_slot_to_timestamp},
+    timeslots::SlotClock,
 };
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::{sync::Arc, thread, time::Instant};
-use tracing::{debug, warn};
+use tracing::{debug, trace, warn};
 
 /// Structure gathering all elements needed by the factory thread
 pub(crate) struct EndorsementFactoryWorker {
@@ -22,6 +22,7 @@ pub(crate) struct EndorsementFactoryWorker {
     wallet: Arc<RwLock<Wallet>>,
     channels: FactoryChannels,
     factory_receiver: MassaReceiver<()>,
+    slot_clock: SlotClock,
     half_t0: MassaTime,
     endorsement_serializer: EndorsementSerializer,
 }
@@ -39,6 +40,7 @@ impl EndorsementFactoryWorker {
             .name("endorsement-factory".into())
             .spawn(|| {
                 let mut this = Self {
+                    slot_clock: SlotClock::new(cfg.thread_count, cfg.t0, cfg.genesis_timestamp),
                     half_t0: cfg
                         .t0
                         .checked_div_u64(2)
@@ -58,49 +60,29 @@ impl EndorsementFactoryWorker {
     /// Slots can be skipped if we waited too much in-between.
     /// Extra safety against double-production caused by clock adjustments (this is the role of the `previous_slot` parameter).
     fn get_next_slot(&self, previous_slot: Option<Slot>) -> (Slot, Instant) {
-        // get delayed time
-        let now = MassaTime::now();
-
-        // if it's the first computed slot, add a time shift to prevent double-production on node restart with clock skew
-        let base_time = if previous_slot.is_none() {
-            now.saturating_add(self.cfg.initial_delay)
-        } else {
-            now
+        let mut next_slot = match previous_slot {
+            // if it's the first computed slot, add a time shift to prevent double-production on node restart with clock skew
+            None => self
+                .slot_clock
+                .closest_slot_at(self.slot_clock.now().saturating_add(self.cfg.initial_delay)),
+            Some(_) => self.slot_clock.next_slot_after(previous_slot),
         };
 
-        // get closest slot according to the current absolute time
-        let mut next_slot = get_closest_slot_to_timestamp(
-            self.cfg.thread_count,
-            self.cfg.t0,
-            self.cfg.genesis_timestamp,
-            base_time,
-        );
-
-        // protection against double-production on unexpected system clock adjustment
-        if let Some(prev_slot) = previous_slot {
-            if next_slot <= prev_slot {
-                next_slot = prev_slot
-                    .get_next_slot(self.cfg.thread_count)
-                    .expect("could not compute next slot");
-            }
-        }
-
         // ignore genesis
         if next_slot.period <= self.cfg.last_start_period {
             next_slot = Slot::new(self.cfg.last_start_period + 1, 0);
         }
 
-        // get the timestamp of the target slot
-        let next_instant = get_block_slot_timestamp(
-            self.cfg.thread_count,
-            self.cfg.t0,
-            self.cfg.genesis_timestamp,
-            next_slot,
-        )
-        .expect("could not get block slot timestamp")
-        .saturating_sub(self.half_t0)
-        .estimate_instant()
-        .expect("could not estimate block slot instant");
+        // endorsements are made half a period before the slot
+        let endorsement_timestamp = self
+            .slot_clock
+            .slot_timestamp(next_slot)
+            .expect("could not get block slot timestamp")
+            .saturating_sub(self.half_t0);
+        let next_instant = self
+            .slot_clock
+            .instant_at(endorsement_timestamp)
+            .expect("could not estimate endorsement instant");
 
         (next_slot, next_instant)
     }
@@ -174,6 +156,23 @@ impl EndorsementFactoryWorker {
             .consensus
             .get_latest_blockclique_block_at_slot(slot);
 
+        // skip the blocks whose producer is filtered out, or unknown
+        if let Some(filter) = &self.cfg.endorsement_producer_filter {
+            let endorsed_producer = self
+                .channels
+                .storage
+                .read_blocks()
+                .get(&endorsed_block)
+                .map(|block| block.content_creator_address);
+            if !endorsed_producer.is_some_and(|producer| filter.allows(&producer)) {
+                debug!(
+                    "endorsement factory skipped slot {}: the producer {:?} of block {} is not endorsed",
+                    slot, endorsed_producer, endorsed_block
+                );
+                return;
+            }
+        }
+
         // produce endorsements
         let mut endorsements: Vec<SecureShareEndorsement> =
             Vec::with_capacity(producers_indices.len());
@@ -199,6 +198,24 @@ impl EndorsementFactoryWorker {
             endorsements.push(endorsement);
         }
 
+        // publish the endorsements without waiting for the pool, which announces them again later
+        if self.cfg.broadcast_enabled {
+            for endorsement in &endorsements {
+                if let Err(err) = self
+                    .channels
+                    .broadcasts
+                    .endorsement_sender
+                    .send(endorsement.clone())
+                {
+                    trace!(
+                        "error, failed to broadcast produced endorsement {}: {}",
+                        endorsement.id,
+                        err
+                    );
+                }
+            }
+        }
+
         // store endorsements
         let mut endo_storage = self.channels.storage.clone_without_refs();
         endo_storage.store_endorsements(endorsements);
@@ -3,10 +3,12 @@
 //! This module implements a factory manager.
 //! See `massa-factory-exports/manager_traits.rs` for functional details.
 
-use std::thread::JoinHandle;
+use std::{sync::Arc, thread::JoinHandle};
 
 use massa_channel::sender::MassaSender;
-use massa_factory_exports::FactoryManager;
+use massa_factory_exports::{BlockProductionReport, FactoryManager, ProductionHistory};
+use massa_models::{address::Address, prehash::PreHashSet};
+use parking_lot::RwLock;
 use tracing::{info, warn};
 
 /// Implementation of the factory manager
@@ -17,9 +19,23 @@ pub struct FactoryManagerImpl {
 
     /// endorsement worker message sender and join handle
     pub(crate) endorsement_worker: Option<(MassaSender<()>, JoinHandle<()>)>,
+
+    /// addresses whose operations are left out of the produced blocks, shared with the block worker
+    pub(crate) production_blocklist: Arc<RwLock<PreHashSet<Address>>>,
+
+    /// report of the latest block produced by the block worker
+    pub(crate) last_block_production_report: Arc<RwLock<Option<BlockProductionReport>>>,
+
+    /// block production history, filled by the block worker
+    pub(crate) production_history: Arc<RwLock<ProductionHistory>>,
 }
 
 impl FactoryManager for FactoryManagerImpl {
+    /// get a shared handle on the block production history
+    fn get_production_history(&self) -> Arc<RwLock<ProductionHistory>> {
+        self.production_history.clone()
+    }
+
     /// stops the worker
     fn stop(&mut self) {
         info!("stopping factory...");
@@ -37,4 +53,20 @@ impl FactoryManager for FactoryManagerImpl {
         }
         info!("factory stopped");
     }
+
+    fn set_production_blocklist(&self, blocklist: PreHashSet<Address>) {
+        info!(
+            "block production blocklist updated: {} addresses",
+            blocklist.len()
+        );
+        *self.production_blocklist.write() = blocklist;
+    }
+
+    fn get_production_blocklist(&self) -> Arc<RwLock<PreHashSet<Address>>> {
+        self.production_blocklist.clone()
+    }
+
+    fn get_last_block_production_report(&self) -> Option<BlockProductionReport> {
+        self.last_block_production_report.read().clone()
+    }
 }
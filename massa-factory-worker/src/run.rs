@@ -4,12 +4,13 @@ use massa_channel::MassaChannel;
 use massa_versioning::versioning::MipStore;
 use parking_lot::RwLock;
 use std::sync::Arc;
+use tracing::info;
 
 use crate::{
     block_factory::BlockFactoryWorker, endorsement_factory::EndorsementFactoryWorker,
     manager::FactoryManagerImpl,
 };
-use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
+use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager, ProductionHistory};
 use massa_wallet::Wallet;
 
 /// Start factory
@@ -21,37 +22,61 @@ use massa_wallet::Wallet;
 ///
 /// # Return value
 /// Returns a factory manager allowing to stop the workers cleanly.
+/// The workers disabled in `cfg` are not started.
 pub fn start_factory(
     cfg: FactoryConfig,
     wallet: Arc<RwLock<Wallet>>,
     channels: FactoryChannels,
     mip_store: MipStore,
 ) -> Box<dyn FactoryManager> {
-    // create block factory channel
-    let (block_worker_tx, block_worker_rx) =
-        MassaChannel::new("factory_block_worker".to_string(), None);
+    // shared with the manager, so that the blocklist can be changed while the factory runs
+    let production_blocklist = Arc::new(RwLock::new(cfg.production_blocklist.clone()));
+    let last_block_production_report = Arc::new(RwLock::new(None));
 
-    // create endorsement factory channel
-    let (endorsement_worker_tx, endorsement_worker_rx) =
-        MassaChannel::new("factory_endorsement_worker".to_string(), None);
+    // create the block production history shared with the manager
+    let production_history = Arc::new(RwLock::new(ProductionHistory::new(
+        cfg.production_history_length,
+    )));
 
     // start block factory worker
-    let block_worker_handle = BlockFactoryWorker::spawn(
-        cfg.clone(),
-        wallet.clone(),
-        channels.clone(),
-        block_worker_rx,
-        mip_store,
-    );
+    let block_worker = if cfg.enable_block_production {
+        let (block_worker_tx, block_worker_rx) =
+            MassaChannel::new("factory_block_worker".to_string(), None);
+        let block_worker_handle = BlockFactoryWorker::spawn(
+            cfg.clone(),
+            wallet.clone(),
+            channels.clone(),
+            block_worker_rx,
+            mip_store,
+            production_blocklist.clone(),
+            last_block_production_report.clone(),
+            production_history.clone(),
+        );
+        Some((block_worker_tx, block_worker_handle))
+    } else {
+        info!("block production is disabled");
+        None
+    };
 
     // start endorsement factory worker
-    let endorsement_worker_handle =
-        EndorsementFactoryWorker::spawn(cfg, wallet, channels, endorsement_worker_rx);
+    let endorsement_worker = if cfg.enable_endorsement_production {
+        let (endorsement_worker_tx, endorsement_worker_rx) =
+            MassaChannel::new("factory_endorsement_worker".to_string(), None);
+        let endorsement_worker_handle =
+            EndorsementFactoryWorker::spawn(cfg, wallet, channels, endorsement_worker_rx);
+        Some((endorsement_worker_tx, endorsement_worker_handle))
+    } else {
+        info!("endorsement production is disabled");
+        None
+    };
 
     // create factory manager
     let manager = FactoryManagerImpl {
-        block_worker: Some((block_worker_tx, block_worker_handle)),
-        endorsement_worker: Some((endorsement_worker_tx, endorsement_worker_handle)),
+        block_worker,
+        endorsement_worker,
+        production_blocklist,
+        last_block_production_report,
+        production_history,
     };
 
     Box::new(manager)
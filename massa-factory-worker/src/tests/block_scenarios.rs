@@ -1,17 +1,26 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    str::FromStr,
+    sync::{mpsc, Arc},
+};
 
 use super::BlockTestFactory;
 use massa_consensus_exports::MockConsensusController;
+use massa_execution_exports::MockExecutionController;
+use massa_factory_exports::{FactoryConfig, SlotProductionOutcome};
 use massa_hash::Hash;
 use massa_models::config::CHAINID;
 use massa_models::{
     address::Address,
     amount::Amount,
+    block::FilledBlock,
     block_id::BlockId,
     config::THREAD_COUNT,
-    operation::{Operation, OperationSerializer, OperationType},
+    denunciation::{Denunciation, DenunciationIndex},
+    operation::{Operation, OperationId, OperationSerializer, OperationType},
+    prehash::PreHashSet,
     secure_share::SecureShareContent,
     slot::Slot,
+    test_exports::gen_endorsements_for_denunciation,
 };
 use massa_pool_exports::MockPoolController;
 use massa_pos_exports::MockSelectorController;
@@ -64,15 +73,15 @@ fn basic_creation() {
     let mut pool_controller = Box::new(MockPoolController::new());
     pool_controller
         .expect_get_block_denunciations()
-        .returning(|slot| {
+        .returning(|slot, _| {
             assert_eq!(*slot, Slot::new(1, 0));
             vec![]
         });
     pool_controller
         .expect_get_block_operations()
-        .returning(|slot| {
+        .returning(|slot, _, _| {
             assert_eq!(*slot, Slot::new(1, 0));
-            (vec![], Storage::create_root())
+            (vec![], Storage::create_root(), 0)
         });
     pool_controller
         .expect_get_block_endorsements()
@@ -86,6 +95,7 @@ fn basic_creation() {
         consensus_controller,
         selector_controller,
         pool_controller,
+        Box::new(MockExecutionController::new()),
     );
     let (ref lock, ref cvar) = *pair;
     let mut started = lock.lock();
@@ -93,6 +103,22 @@ fn basic_creation() {
         cvar.wait(&mut started);
     }
     test_factory.stop();
+
+    // the produced block is reported in the production history
+    let history = test_factory.production_history.read();
+    assert_eq!(history.len(), 1);
+    let report = history.iter().next().unwrap();
+    assert_eq!(report.slot, Slot::new(1, 0));
+    assert_eq!(report.producer, staking_address);
+    assert!(matches!(
+        report.outcome,
+        SlotProductionOutcome::Produced {
+            operation_count: 0,
+            endorsement_count: 0,
+            denunciation_count: 0,
+            ..
+        }
+    ));
 }
 
 /// Creates a block with a roll buy operation in it.
@@ -119,7 +145,7 @@ fn basic_creation_with_operation() {
     let mut pool_controller = Box::new(MockPoolController::new());
     pool_controller
         .expect_get_block_denunciations()
-        .returning(|slot| {
+        .returning(|slot, _| {
             assert_eq!(*slot, Slot::new(1, 0));
             vec![]
         });
@@ -128,7 +154,7 @@ fn basic_creation_with_operation() {
     let mut pool_storage = storage.clone_without_refs();
     pool_controller
         .expect_get_block_operations()
-        .returning(move |slot| {
+        .returning(move |slot, _, _| {
             assert_eq!(*slot, Slot::new(1, 0));
             let content = Operation {
                 fee: Amount::from_str("0.01").unwrap(),
@@ -143,7 +169,7 @@ fn basic_creation_with_operation() {
             )
             .unwrap();
             pool_storage.store_operations(vec![operation.clone()]);
-            (vec![operation.id], pool_storage.clone())
+            (vec![operation.id], pool_storage.clone(), 0)
         });
     let pool_storage_2 = storage.clone_without_refs();
     pool_controller
@@ -179,6 +205,7 @@ fn basic_creation_with_operation() {
         consensus_controller,
         selector_controller,
         pool_controller,
+        Box::new(MockExecutionController::new()),
     );
     let (lock, cvar) = &*pair;
     let mut started = lock.lock();
@@ -187,3 +214,418 @@ fn basic_creation_with_operation() {
     }
     test_factory.stop();
 }
+
+/// Creates blocks while the production blocklist contains one of three operation creators,
+/// then clears the blocklist at runtime.
+#[test]
+#[serial]
+fn creation_with_production_blocklist() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+    let keypair = KeyPair::generate(0).unwrap();
+    let staking_address = Address::from_public_key(&keypair.get_public_key());
+    let parent = BlockId::generate_from_hash(Hash::compute_from("test".as_bytes()));
+    let mut parents = Vec::new();
+    for i in 0..THREAD_COUNT as u64 {
+        parents.push((parent, i));
+    }
+    let storage = Storage::create_root();
+
+    // one operation for each of the three creators
+    let mut pool_storage = storage.clone_without_refs();
+    let operations: Vec<_> = (0..3)
+        .map(|_| {
+            let content = Operation {
+                fee: Amount::from_str("0.01").unwrap(),
+                expire_period: 2,
+                op: OperationType::RollBuy { roll_count: 1 },
+            };
+            Operation::new_verifiable(
+                content,
+                OperationSerializer::new(),
+                &KeyPair::generate(0).unwrap(),
+                *CHAINID,
+            )
+            .unwrap()
+        })
+        .collect();
+    pool_storage.store_operations(operations.clone());
+    let blocklisted_creator = operations[1].content_creator_address;
+    let all_op_ids: PreHashSet<OperationId> = operations.iter().map(|op| op.id).collect();
+    let allowed_op_ids: PreHashSet<OperationId> =
+        PreHashSet::from_iter([operations[0].id, operations[2].id]);
+
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .times(2)
+        .returning(move |_| Ok(staking_address));
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_get_block_denunciations()
+        .returning(|_, _| vec![]);
+    pool_controller
+        .expect_get_block_endorsements()
+        .returning(|_, _| (vec![], Storage::create_root()));
+    pool_controller
+        .expect_get_block_operations()
+        .times(2)
+        .returning(move |_, excluded_creators, _| {
+            let (op_ids, excluded): (Vec<_>, Vec<_>) = operations
+                .iter()
+                .partition(|op| !excluded_creators.contains(&op.content_creator_address));
+            let op_ids: Vec<OperationId> = op_ids.into_iter().map(|op| op.id).collect();
+            let mut res_storage = pool_storage.clone_without_refs();
+            res_storage.claim_operation_refs(&op_ids.iter().copied().collect());
+            (op_ids, res_storage, excluded.len())
+        });
+
+    // each produced block is reported to the test, which resumes the factory once it checked it
+    let (block_tx, block_rx) = mpsc::channel();
+    let (resume_tx, resume_rx) = mpsc::channel();
+    let mut consensus_controller = Box::new(MockConsensusController::new());
+    consensus_controller
+        .expect_get_best_parents()
+        .times(2)
+        .returning(move || parents.clone());
+    consensus_controller
+        .expect_register_block()
+        .times(2)
+        .returning(move |_, _, storage, created| {
+            assert!(created);
+            assert_eq!(storage.get_block_refs().len(), 1);
+            block_tx.send(storage.get_op_refs().clone()).unwrap();
+            resume_rx.recv().unwrap();
+        });
+
+    let mut test_factory = BlockTestFactory::new(
+        &keypair,
+        storage,
+        consensus_controller,
+        selector_controller,
+        pool_controller,
+        Box::new(MockExecutionController::new()),
+    );
+    *test_factory.production_blocklist.write() = PreHashSet::from_iter([blocklisted_creator]);
+
+    // the operation of the blocklisted creator is left out and counted
+    let block_op_ids = block_rx.recv().unwrap();
+    assert_eq!(block_op_ids, allowed_op_ids);
+    let report = test_factory
+        .last_block_production_report
+        .read()
+        .clone()
+        .unwrap();
+    assert_eq!(report.operation_count, 2);
+    assert_eq!(report.excluded_operation_count, 1);
+
+    // clearing the blocklist at runtime includes the operation again
+    test_factory.production_blocklist.write().clear();
+    resume_tx.send(()).unwrap();
+    let block_op_ids = block_rx.recv().unwrap();
+    assert_eq!(block_op_ids, all_op_ids);
+    let report = test_factory
+        .last_block_production_report
+        .read()
+        .clone()
+        .unwrap();
+    assert_eq!(report.operation_count, 3);
+    assert_eq!(report.excluded_operation_count, 0);
+
+    resume_tx.send(()).unwrap();
+    test_factory.stop();
+}
+
+/// Creates a block while the pool proposes four denunciations, one of which was executed
+/// since the pool checked it.
+#[test]
+#[serial]
+fn creation_with_denunciations() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+    let keypair = KeyPair::generate(0).unwrap();
+    let staking_address = Address::from_public_key(&keypair.get_public_key());
+    let parent = BlockId::generate_from_hash(Hash::compute_from("test".as_bytes()));
+    let mut parents = Vec::new();
+    for i in 0..THREAD_COUNT as u64 {
+        parents.push((parent, i));
+    }
+
+    // one denunciation of a double endorsement for each of four slots
+    let denunciations: Vec<Denunciation> = (0..4)
+        .map(|thread| {
+            let (_, _, endorsement_1, endorsement_2, _) =
+                gen_endorsements_for_denunciation(Some(Slot::new(1, thread)), None);
+            Denunciation::try_from((&endorsement_1, &endorsement_2)).unwrap()
+        })
+        .collect();
+    let executed = DenunciationIndex::from(&denunciations[2]);
+    let expected: Vec<Denunciation> = denunciations
+        .iter()
+        .filter(|denunciation| DenunciationIndex::from(*denunciation) != executed)
+        .cloned()
+        .collect();
+
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .times(1)
+        .return_once(move |_| Ok(staking_address));
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_get_block_denunciations()
+        .times(1)
+        .return_once(move |_, _| denunciations);
+    pool_controller
+        .expect_get_block_endorsements()
+        .returning(|_, _| (vec![], Storage::create_root()));
+    pool_controller
+        .expect_get_block_operations()
+        .returning(|_, _, _| (vec![], Storage::create_root(), 0));
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    execution_controller
+        .expect_get_denunciation_execution_status()
+        .times(4)
+        .returning(move |index| (*index == executed, false));
+
+    let (block_tx, block_rx) = mpsc::channel();
+    let mut consensus_controller = Box::new(MockConsensusController::new());
+    consensus_controller
+        .expect_get_best_parents()
+        .times(1)
+        .return_once(move || parents);
+    consensus_controller
+        .expect_register_block()
+        .times(1)
+        .return_once(move |block_id, _, storage, created| {
+            assert!(created);
+            let header_denunciations = storage
+                .read_blocks()
+                .get(&block_id)
+                .unwrap()
+                .content
+                .header
+                .content
+                .denunciations
+                .clone();
+            block_tx.send(header_denunciations).unwrap();
+        });
+
+    let mut test_factory = BlockTestFactory::new(
+        &keypair,
+        Storage::create_root(),
+        consensus_controller,
+        selector_controller,
+        pool_controller,
+        execution_controller,
+    );
+
+    // only the three denunciations not executed yet land in the header
+    assert_eq!(block_rx.recv().unwrap(), expected);
+    let report = test_factory
+        .last_block_production_report
+        .read()
+        .clone()
+        .unwrap();
+    assert_eq!(report.denunciation_count, 3);
+    assert_eq!(report.available_denunciation_count, 4);
+    test_factory.stop();
+}
+
+/// Creates a block at slot (1, 0) with a lookback of two slots: of the denunciations of the last
+/// three slots of the genesis period, only the two within the lookback land in the header.
+#[test]
+#[serial]
+fn creation_with_denunciation_lookback() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+    let keypair = KeyPair::generate(0).unwrap();
+    let staking_address = Address::from_public_key(&keypair.get_public_key());
+    let parent = BlockId::generate_from_hash(Hash::compute_from("test".as_bytes()));
+    let mut parents = Vec::new();
+    for i in 0..THREAD_COUNT as u64 {
+        parents.push((parent, i));
+    }
+    let factory_config = FactoryConfig {
+        denunciation_lookback_slots: 2,
+        ..Default::default()
+    };
+
+    // denunciations made 3, 2 and 1 slots before the produced block
+    let denunciations: Vec<Denunciation> = (THREAD_COUNT - 3..THREAD_COUNT)
+        .map(|thread| {
+            let (_, _, endorsement_1, endorsement_2, _) =
+                gen_endorsements_for_denunciation(Some(Slot::new(0, thread)), None);
+            Denunciation::try_from((&endorsement_1, &endorsement_2)).unwrap()
+        })
+        .collect();
+    let expected = denunciations[1..].to_vec();
+
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .times(1)
+        .return_once(move |_| Ok(staking_address));
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_get_block_denunciations()
+        .times(1)
+        .return_once(move |_, min_slot| {
+            // the pool only proposes the denunciations within the lookback
+            assert_eq!(*min_slot, Slot::new(0, THREAD_COUNT - 2));
+            denunciations
+                .into_iter()
+                .filter(|denunciation| denunciation.get_slot() >= min_slot)
+                .collect()
+        });
+    pool_controller
+        .expect_get_block_endorsements()
+        .returning(|_, _| (vec![], Storage::create_root()));
+    pool_controller
+        .expect_get_block_operations()
+        .returning(|_, _, _| (vec![], Storage::create_root(), 0));
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    execution_controller
+        .expect_get_denunciation_execution_status()
+        .times(2)
+        .returning(|_| (false, false));
+
+    let (block_tx, block_rx) = mpsc::channel();
+    let mut consensus_controller = Box::new(MockConsensusController::new());
+    consensus_controller
+        .expect_get_best_parents()
+        .times(1)
+        .return_once(move || parents);
+    consensus_controller
+        .expect_register_block()
+        .times(1)
+        .return_once(move |block_id, slot, storage, created| {
+            assert!(created);
+            assert_eq!(slot, Slot::new(1, 0));
+            let header_denunciations = storage
+                .read_blocks()
+                .get(&block_id)
+                .unwrap()
+                .content
+                .header
+                .content
+                .denunciations
+                .clone();
+            block_tx.send(header_denunciations).unwrap();
+        });
+
+    let mut test_factory = BlockTestFactory::with_config(
+        factory_config,
+        &keypair,
+        Storage::create_root(),
+        consensus_controller,
+        selector_controller,
+        pool_controller,
+        execution_controller,
+    );
+
+    assert_eq!(block_rx.recv().unwrap(), expected);
+    let report = test_factory
+        .last_block_production_report
+        .read()
+        .clone()
+        .unwrap();
+    assert_eq!(report.denunciation_count, 2);
+    assert_eq!(report.available_denunciation_count, 2);
+    test_factory.stop();
+}
+
+/// Creates a block and checks that it is published to the broadcast channels before it is
+/// registered to consensus, and that the announcement of consensus is then suppressed.
+#[test]
+#[serial]
+fn creation_broadcast_before_registration() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+    let keypair = KeyPair::generate(0).unwrap();
+    let storage = Storage::create_root();
+    let staking_address = Address::from_public_key(&keypair.get_public_key());
+    let parent = BlockId::generate_from_hash(Hash::compute_from("test".as_bytes()));
+    let mut parents = Vec::new();
+    for i in 0..THREAD_COUNT as u64 {
+        parents.push((parent, i));
+    }
+    let (registered_tx, registered_rx) = mpsc::channel();
+    let mut consensus_controller = Box::new(MockConsensusController::new());
+    consensus_controller
+        .expect_get_best_parents()
+        .times(1)
+        .return_once(move || parents);
+    consensus_controller
+        .expect_register_block()
+        .times(1)
+        .return_once(move |block_id, _, storage, created| {
+            assert!(created);
+            registered_tx.send((block_id, storage)).unwrap();
+        });
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_producer()
+        .times(1)
+        .return_once(move |_| Ok(staking_address));
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_get_block_denunciations()
+        .returning(|_, _| vec![]);
+    pool_controller
+        .expect_get_block_operations()
+        .returning(|_, _, _| (vec![], Storage::create_root(), 0));
+    pool_controller
+        .expect_get_block_endorsements()
+        .returning(|_, _| (vec![], Storage::create_root()));
+    let mut test_factory = BlockTestFactory::new(
+        &keypair,
+        storage,
+        consensus_controller,
+        selector_controller,
+        pool_controller,
+        Box::new(MockExecutionController::new()),
+    );
+    let mut block_receiver = test_factory.broadcasts.block_sender.subscribe();
+    let mut filled_block_receiver = test_factory.broadcasts.filled_block_sender.subscribe();
+
+    // the block was published, flagged, before reaching consensus
+    let (block_id, block_storage) = registered_rx.recv().unwrap();
+    assert_eq!(block_receiver.try_recv().unwrap().id, block_id);
+    let filled_block = filled_block_receiver.try_recv().unwrap();
+    assert_eq!(filled_block.header.id, block_id);
+    assert!(filled_block.locally_produced);
+
+    // consensus announcing the registered block again does not reach the subscribers
+    let block = block_storage.read_blocks().get(&block_id).unwrap().clone();
+    let consensus_filled_block = FilledBlock {
+        header: block.content.header.clone(),
+        operations: vec![],
+        locally_produced: false,
+    };
+    assert_eq!(test_factory.broadcasts.block_sender.send(block).unwrap(), 0);
+    assert_eq!(
+        test_factory
+            .broadcasts
+            .filled_block_sender
+            .send(consensus_filled_block)
+            .unwrap(),
+        0
+    );
+    assert!(block_receiver.try_recv().is_err());
+    assert!(filled_block_receiver.try_recv().is_err());
+
+    test_factory.stop();
+}
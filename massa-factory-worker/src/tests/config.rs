@@ -0,0 +1,32 @@
+use massa_factory_exports::{FactoryConfig, FactoryConfigError};
+
+#[test]
+fn denunciation_lookback_within_validity_window() {
+    let mut config = FactoryConfig::default();
+    let max = config.denunciation_expire_periods * config.thread_count as u64;
+    assert_eq!(config.max_denunciation_lookback_slots(), max);
+
+    // the whole window is allowed
+    config.denunciation_lookback_slots = max;
+    config.validate().unwrap();
+
+    config.denunciation_lookback_slots = 0;
+    config.validate().unwrap();
+}
+
+#[test]
+fn denunciation_lookback_beyond_validity_window() {
+    let mut config = FactoryConfig::default();
+    let max = config.max_denunciation_lookback_slots();
+    config.denunciation_lookback_slots = max + 1;
+    match config.validate() {
+        Err(FactoryConfigError::DenunciationLookbackTooLong {
+            lookback,
+            max: window,
+        }) => {
+            assert_eq!(lookback, max + 1);
+            assert_eq!(window, max);
+        }
+        other => panic!("expected a too long lookback error, got {:?}", other),
+    }
+}
@@ -1,12 +1,16 @@
-use std::sync::Arc;
+use std::sync::{mpsc, Arc};
 
 use super::EndorsementTestFactory;
 use massa_consensus_exports::MockConsensusController;
+use massa_factory_exports::{
+    test_exports::create_empty_block, EndorsementProducerFilter, FactoryConfig,
+};
 use massa_hash::Hash;
 use massa_models::{
     address::Address,
     block_id::BlockId,
     config::{ENDORSEMENT_COUNT, THREAD_COUNT},
+    prehash::PreHashSet,
     slot::Slot,
 };
 use massa_pool_exports::MockPoolController;
@@ -94,3 +98,148 @@ fn basic_creation() {
     }
     test_factory.stop();
 }
+
+/// The blocks of a producer that is not allowed by the filter are not endorsed.
+#[test]
+#[serial]
+fn filtered_out_producer_not_endorsed() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+    let keypair = KeyPair::generate(0).unwrap();
+    let staking_address = Address::from_public_key(&keypair.get_public_key());
+    let allowed_address = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+
+    // the latest block is produced by our staking address, which is not allowed
+    let mut storage = Storage::create_root();
+    let latest_block = create_empty_block(&keypair, &Slot::new(0, 0));
+    let latest_block_id = latest_block.id;
+    storage.store_block(latest_block);
+
+    let pair = Arc::new((Mutex::new(false), Condvar::new()));
+    let pair2 = pair.clone();
+    let mut consensus_controller = Box::new(MockConsensusController::new());
+    consensus_controller
+        .expect_get_latest_blockclique_block_at_slot()
+        .returning(move |_| latest_block_id);
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_selection()
+        .times(2..)
+        .returning(move |slot| {
+            // the first slot was processed once the next one is
+            if slot > Slot::new(1, 0) {
+                let (lock, cvar) = &*pair2;
+                *lock.lock() = true;
+                cvar.notify_one();
+            }
+            Ok(Selection {
+                producer: staking_address,
+                endorsements: vec![staking_address; ENDORSEMENT_COUNT as usize],
+            })
+        });
+
+    // the pool and protocol are never given endorsements
+    let mut factory_config = FactoryConfig::default();
+    let mut allowed = PreHashSet::default();
+    allowed.insert(allowed_address);
+    factory_config.endorsement_producer_filter = Some(EndorsementProducerFilter::Allow(allowed));
+    let mut test_factory = EndorsementTestFactory::with_config(
+        factory_config,
+        &keypair,
+        storage,
+        consensus_controller,
+        selector_controller,
+        Box::new(MockPoolController::new()),
+        Box::new(MockProtocolController::new()),
+    );
+    let (lock, cvar) = &*pair;
+    let mut processed = lock.lock();
+    if !*processed {
+        cvar.wait(&mut processed);
+    }
+    drop(processed);
+    test_factory.stop();
+}
+
+/// The produced endorsements are published to the broadcast channel before they are sent to the
+/// pool, and the announcement of the pool is then suppressed.
+#[test]
+#[serial]
+fn creation_broadcast_before_pool() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+    let keypair = KeyPair::generate(0).unwrap();
+    let storage = Storage::create_root();
+    let staking_address = Address::from_public_key(&keypair.get_public_key());
+    let parent = BlockId::generate_from_hash(Hash::compute_from("test".as_bytes()));
+    let mut consensus_controller = Box::new(MockConsensusController::new());
+    consensus_controller
+        .expect_get_latest_blockclique_block_at_slot()
+        .times(1)
+        .returning(move |_| parent);
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller
+        .expect_get_selection()
+        .times(1)
+        .returning(move |_| {
+            Ok(Selection {
+                producer: staking_address,
+                endorsements: vec![staking_address; ENDORSEMENT_COUNT as usize],
+            })
+        });
+    let (pool_tx, pool_rx) = mpsc::channel();
+    let mut pool_controller = Box::new(MockPoolController::new());
+    pool_controller
+        .expect_add_endorsements()
+        .times(1)
+        .return_once(move |storage| pool_tx.send(storage).unwrap());
+    let mut protocol_controller = Box::new(MockProtocolController::new());
+    protocol_controller
+        .expect_propagate_endorsements()
+        .times(1)
+        .returning(|_| Ok(()));
+    let mut test_factory = EndorsementTestFactory::new(
+        &keypair,
+        storage,
+        consensus_controller,
+        selector_controller,
+        pool_controller,
+        protocol_controller,
+    );
+    let mut endorsement_receiver = test_factory.broadcasts.endorsement_sender.subscribe();
+
+    // every endorsement was published before reaching the pool
+    let endo_storage = pool_rx.recv().unwrap();
+    let endorsement_ids = endo_storage.get_endorsement_refs().clone();
+    let mut received_ids = PreHashSet::default();
+    while let Ok(endorsement) = endorsement_receiver.try_recv() {
+        received_ids.insert(endorsement.id);
+    }
+    assert_eq!(received_ids, endorsement_ids);
+
+    // the pool announcing them again does not reach the subscribers
+    for endorsement_id in &endorsement_ids {
+        let endorsement = endo_storage
+            .read_endorsements()
+            .get(endorsement_id)
+            .unwrap()
+            .clone();
+        assert_eq!(
+            test_factory
+                .broadcasts
+                .endorsement_sender
+                .send(endorsement)
+                .unwrap(),
+            0
+        );
+    }
+    assert!(endorsement_receiver.try_recv().is_err());
+
+    test_factory.stop();
+}
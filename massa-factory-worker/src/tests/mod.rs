@@ -1,5 +1,7 @@
 mod block_scenarios;
+mod config;
 mod endorsement_scenarios;
+mod run_scenarios;
 mod tools;
 
 pub use tools::*;
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use massa_consensus_exports::MockConsensusController;
+use massa_execution_exports::MockExecutionController;
+use massa_factory_exports::{FactoryChannels, FactoryConfig};
+use massa_models::{address::Address, config::MIP_STORE_STATS_BLOCK_CONSIDERED};
+use massa_pool_exports::MockPoolController;
+use massa_pos_exports::MockSelectorController;
+use massa_protocol_exports::MockProtocolController;
+use massa_signature::KeyPair;
+use massa_storage::Storage;
+use massa_versioning::versioning::{MipStatsConfig, MipStore};
+use massa_wallet::test_exports::create_test_wallet;
+use num::rational::Ratio;
+use parking_lot::{Condvar, Mutex, RwLock};
+use serial_test::serial;
+
+use super::create_test_broadcasts;
+use crate::start_factory;
+
+fn empty_mip_store() -> MipStore {
+    let mip_stats_config = MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        warn_announced_version_ratio: Ratio::new_raw(30, 100),
+    };
+    MipStore::try_from(([], mip_stats_config)).expect("Cannot create an empty MIP store")
+}
+
+/// A factory with all its workers disabled starts nothing and stops cleanly.
+#[test]
+#[serial]
+fn all_workers_disabled() {
+    let mut config = FactoryConfig::default();
+    config.enable_block_production = false;
+    config.enable_endorsement_production = false;
+
+    // the mocks have no expectations: any call to them fails the test
+    let channels = FactoryChannels {
+        selector: Box::new(MockSelectorController::new()),
+        consensus: Box::new(MockConsensusController::new()),
+        pool: Box::new(MockPoolController::new()),
+        execution: Box::new(MockExecutionController::new()),
+        protocol: Box::new(MockProtocolController::new()),
+        storage: Storage::create_root(),
+        broadcasts: create_test_broadcasts(),
+    };
+    let mut manager = start_factory(
+        config,
+        Arc::new(RwLock::new(create_test_wallet(None))),
+        channels,
+        empty_mip_store(),
+    );
+    manager.stop();
+}
+
+/// Only the enabled block worker asks the selector for draws.
+#[test]
+#[serial]
+fn endorsement_worker_disabled() {
+    let default_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_panic(info);
+        std::process::exit(1);
+    }));
+    let mut config = FactoryConfig::default();
+    config.enable_endorsement_production = false;
+    // the first block slot is (1, 0)
+    config.genesis_timestamp = config
+        .genesis_timestamp
+        .checked_sub(config.t0.checked_div_u64(2).unwrap())
+        .unwrap();
+
+    // the block producer is not in the wallet, so that nothing is produced
+    let producer = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    let pair = Arc::new((Mutex::new(false), Condvar::new()));
+    let pair2 = pair.clone();
+    let mut block_selector = Box::new(MockSelectorController::new());
+    block_selector
+        .expect_get_producer()
+        .times(1..)
+        .returning(move |_| {
+            let (lock, cvar) = &*pair2;
+            *lock.lock() = true;
+            cvar.notify_one();
+            Ok(producer)
+        });
+    // the endorsement worker would ask the original selector for its draws
+    let mut selector = Box::new(MockSelectorController::new());
+    selector
+        .expect_clone_box()
+        .return_once(move || block_selector);
+
+    let mut consensus = Box::new(MockConsensusController::new());
+    consensus
+        .expect_clone_box()
+        .return_once(|| Box::new(MockConsensusController::new()));
+    let mut pool = Box::new(MockPoolController::new());
+    pool.expect_clone_box()
+        .return_once(|| Box::new(MockPoolController::new()));
+    let mut execution = Box::new(MockExecutionController::new());
+    execution
+        .expect_clone_box()
+        .return_once(|| Box::new(MockExecutionController::new()));
+    let mut protocol = Box::new(MockProtocolController::new());
+    protocol
+        .expect_clone_box()
+        .return_once(|| Box::new(MockProtocolController::new()));
+
+    let channels = FactoryChannels {
+        selector,
+        consensus,
+        pool,
+        execution,
+        protocol,
+        storage: Storage::create_root(),
+        broadcasts: create_test_broadcasts(),
+    };
+    let mut manager = start_factory(
+        config,
+        Arc::new(RwLock::new(create_test_wallet(None))),
+        channels,
+        empty_mip_store(),
+    );
+
+    let (ref lock, ref cvar) = *pair;
+    let mut asked = lock.lock();
+    if !*asked {
+        cvar.wait(&mut asked);
+    }
+    drop(asked);
+    manager.stop();
+}
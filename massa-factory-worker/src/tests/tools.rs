@@ -1,6 +1,7 @@
 use massa_channel::sender::MassaSender;
 use massa_channel::MassaChannel;
 use massa_consensus_exports::MockConsensusController;
+use massa_execution_exports::MockExecutionController;
 use massa_models::config::MIP_STORE_STATS_BLOCK_CONSIDERED;
 use massa_versioning::versioning::MipStatsConfig;
 use massa_versioning::versioning::MipStore;
@@ -9,8 +10,17 @@ use parking_lot::RwLock;
 use std::sync::Arc;
 use std::thread::JoinHandle;
 
-use massa_factory_exports::{test_exports::create_empty_block, FactoryChannels, FactoryConfig};
-use massa_models::{address::Address, block_id::BlockId, prehash::PreHashMap, slot::Slot};
+use massa_channel::broadcast::DedupBroadcaster;
+use massa_factory_exports::{
+    test_exports::create_empty_block, BlockProductionReport, FactoryBroadcasts, FactoryChannels,
+    FactoryConfig, ProductionHistory,
+};
+use massa_models::{
+    address::Address,
+    block_id::BlockId,
+    prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
+};
 use massa_pool_exports::MockPoolController;
 use massa_pos_exports::MockSelectorController;
 use massa_protocol_exports::MockProtocolController;
@@ -21,6 +31,15 @@ use crate::block_factory::BlockFactoryWorker;
 use crate::endorsement_factory::EndorsementFactoryWorker;
 use massa_wallet::test_exports::create_test_wallet;
 
+/// Create broadcast channels for the factory, suppressing duplicates like the ones of the node
+pub fn create_test_broadcasts() -> FactoryBroadcasts {
+    FactoryBroadcasts {
+        block_sender: DedupBroadcaster::new("test_blocks".to_string(), 10, 10),
+        filled_block_sender: DedupBroadcaster::new("test_filled_blocks".to_string(), 10, 10),
+        endorsement_sender: DedupBroadcaster::new("test_endorsements".to_string(), 100, 100),
+    }
+}
+
 /// This structure store all information and links to creates tests for the factory.
 pub struct BlockTestFactory {
     _factory_config: FactoryConfig,
@@ -28,6 +47,10 @@ pub struct BlockTestFactory {
     _genesis_blocks: Vec<(BlockId, u64)>,
     pub(crate) _storage: Storage,
     _keypair: KeyPair,
+    pub(crate) production_blocklist: Arc<RwLock<PreHashSet<Address>>>,
+    pub(crate) last_block_production_report: Arc<RwLock<Option<BlockProductionReport>>>,
+    pub(crate) production_history: Arc<RwLock<ProductionHistory>>,
+    pub(crate) broadcasts: FactoryBroadcasts,
 }
 
 impl BlockTestFactory {
@@ -38,18 +61,40 @@ impl BlockTestFactory {
     /// Returns
     /// - `TestFactory`: the structure that will be used to manage the tests
     pub fn new(
+        default_keypair: &KeyPair,
+        storage: Storage,
+        consensus_controller: Box<MockConsensusController>,
+        selector_controller: Box<MockSelectorController>,
+        pool_controller: Box<MockPoolController>,
+        execution_controller: Box<MockExecutionController>,
+    ) -> BlockTestFactory {
+        Self::with_config(
+            FactoryConfig::default(),
+            default_keypair,
+            storage,
+            consensus_controller,
+            selector_controller,
+            pool_controller,
+            execution_controller,
+        )
+    }
+
+    /// Same as `new`, with a custom factory configuration.
+    /// Its genesis timestamp is moved half a period back, so that the first block is produced at slot (1, 0).
+    pub fn with_config(
+        mut factory_config: FactoryConfig,
         default_keypair: &KeyPair,
         mut storage: Storage,
         consensus_controller: Box<MockConsensusController>,
         selector_controller: Box<MockSelectorController>,
         pool_controller: Box<MockPoolController>,
+        execution_controller: Box<MockExecutionController>,
     ) -> BlockTestFactory {
         let mut protocol_controller = Box::new(MockProtocolController::new());
         let block_protocol_controller = Box::new(MockProtocolController::new());
         protocol_controller
             .expect_clone_box()
             .return_once(move || block_protocol_controller);
-        let mut factory_config = FactoryConfig::default();
         factory_config.genesis_timestamp = factory_config
             .genesis_timestamp
             .checked_sub(factory_config.t0.checked_div_u64(2).unwrap())
@@ -76,7 +121,14 @@ impl BlockTestFactory {
             MipStore::try_from(([], mip_stats_config)).expect("Cannot create an empty MIP store");
 
         let wallet = create_test_wallet(Some(accounts));
+        let production_blocklist =
+            Arc::new(RwLock::new(factory_config.production_blocklist.clone()));
+        let last_block_production_report = Arc::new(RwLock::new(None));
+        let broadcasts = create_test_broadcasts();
         let (tx, rx) = MassaChannel::new(String::from("test_block_factory"), None);
+        let production_history = Arc::new(RwLock::new(ProductionHistory::new(
+            factory_config.production_history_length,
+        )));
         let join_handle = BlockFactoryWorker::spawn(
             factory_config.clone(),
             Arc::new(RwLock::new(wallet)),
@@ -84,11 +136,16 @@ impl BlockTestFactory {
                 selector: selector_controller,
                 consensus: consensus_controller,
                 pool: pool_controller,
+                execution: execution_controller,
                 protocol: protocol_controller,
                 storage: storage.clone_without_refs(),
+                broadcasts: broadcasts.clone(),
             },
             rx,
             mip_store,
+            production_blocklist.clone(),
+            last_block_production_report.clone(),
+            production_history.clone(),
         );
 
         BlockTestFactory {
@@ -97,6 +154,10 @@ impl BlockTestFactory {
             _genesis_blocks: genesis_blocks,
             _storage: storage,
             _keypair: default_keypair.clone(),
+            production_blocklist,
+            last_block_production_report,
+            production_history,
+            broadcasts,
         }
     }
 
@@ -114,6 +175,7 @@ pub struct EndorsementTestFactory {
     _genesis_blocks: Vec<(BlockId, u64)>,
     pub(crate) _storage: Storage,
     _keypair: KeyPair,
+    pub(crate) broadcasts: FactoryBroadcasts,
 }
 
 impl EndorsementTestFactory {
@@ -124,6 +186,28 @@ impl EndorsementTestFactory {
     /// Returns
     /// - `TestFactory`: the structure that will be used to manage the tests
     pub fn new(
+        default_keypair: &KeyPair,
+        storage: Storage,
+        consensus_controller: Box<MockConsensusController>,
+        selector_controller: Box<MockSelectorController>,
+        pool_controller: Box<MockPoolController>,
+        protocol_controller: Box<MockProtocolController>,
+    ) -> EndorsementTestFactory {
+        Self::with_config(
+            FactoryConfig::default(),
+            default_keypair,
+            storage,
+            consensus_controller,
+            selector_controller,
+            pool_controller,
+            protocol_controller,
+        )
+    }
+
+    /// Same as `new`, with a custom factory configuration.
+    /// Its genesis timestamp is moved half a period back, so that the first endorsements are produced for slot (1, 0).
+    pub fn with_config(
+        mut factory_config: FactoryConfig,
         default_keypair: &KeyPair,
         mut storage: Storage,
         consensus_controller: Box<MockConsensusController>,
@@ -131,7 +215,6 @@ impl EndorsementTestFactory {
         pool_controller: Box<MockPoolController>,
         protocol_controller: Box<MockProtocolController>,
     ) -> EndorsementTestFactory {
-        let mut factory_config = FactoryConfig::default();
         factory_config.genesis_timestamp = factory_config
             .genesis_timestamp
             .checked_sub(factory_config.t0.checked_div_u64(2).unwrap())
@@ -150,6 +233,7 @@ impl EndorsementTestFactory {
         accounts.insert(producer_address, producer_keypair.clone());
 
         let wallet = create_test_wallet(Some(accounts));
+        let broadcasts = create_test_broadcasts();
         let (tx, rx) = MassaChannel::new(String::from("test_block_factory"), None);
         let join_handle = EndorsementFactoryWorker::spawn(
             factory_config.clone(),
@@ -158,8 +242,10 @@ impl EndorsementTestFactory {
                 selector: selector_controller,
                 consensus: consensus_controller,
                 pool: pool_controller,
+                execution: Box::new(MockExecutionController::new()),
                 protocol: protocol_controller,
                 storage: storage.clone_without_refs(),
+                broadcasts: broadcasts.clone(),
             },
             rx,
         );
@@ -170,6 +256,7 @@ impl EndorsementTestFactory {
             _genesis_blocks: genesis_blocks,
             _storage: storage,
             _keypair: default_keypair.clone(),
+            broadcasts,
         }
     }
 
@@ -3,6 +3,7 @@
 //! This file defines a configuration structure containing all settings for final state management
 
 use massa_async_pool::AsyncPoolConfig;
+use massa_db_exports::MassaDBCacheSizes;
 use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
 use massa_ledger_exports::LedgerConfig;
 use massa_pos_exports::PoSConfig;
@@ -45,4 +46,7 @@ pub struct FinalStateConfig {
     pub genesis_timestamp: MassaTime,
     /// Interval of periods between creation of each ledger backup
     pub ledger_backup_periods_interval: u64,
+    /// block cache sizes of the column families of the final state database,
+    /// applied when the database is opened
+    pub db_cache_sizes: MassaDBCacheSizes,
 }
@@ -1,14 +1,18 @@
 use massa_async_pool::AsyncPool;
-use massa_db_exports::{DBBatch, ShareableMassaDBController};
+use massa_db_exports::{DBBatch, Key, ShareableMassaDBController, StreamBatch};
 use massa_executed_ops::ExecutedDenunciations;
 use massa_hash::Hash;
 use massa_ledger_exports::LedgerController;
-use massa_models::{operation::OperationId, slot::Slot};
+use massa_models::{operation::OperationId, slot::Slot, streaming_step::StreamingStep};
 use massa_pos_exports::PoSFinalState;
 use massa_versioning::versioning::MipStore;
+use std::collections::BTreeMap;
 
 use crate::{FinalStateError, StateChanges};
 
+/// Compaction of the final state DB, see [`FinalStateController::get_compaction_job`]
+pub type FinalStateCompactionJob = Box<dyn FnOnce() -> Result<(), FinalStateError> + Send>;
+
 /// Trait for final state controller.
 #[cfg_attr(feature = "test-exports", mockall::automock)]
 pub trait FinalStateController: Send + Sync {
@@ -16,6 +20,11 @@ pub trait FinalStateController: Send + Sync {
     /// Note that only one atomic write per final slot occurs, so this can be safely queried at any time.
     fn get_fingerprint(&self) -> Hash;
 
+    /// Get the fingerprint (hash) of each component of the final state, by component name
+    /// (the prefix of its keys, without the trailing slash).
+    /// Together, they are derived from the same content as the fingerprint of the whole final state.
+    fn get_component_fingerprints(&self) -> BTreeMap<String, Hash>;
+
     /// Get the slot at the end of which the final state is attached
     fn get_slot(&self) -> Slot;
 
@@ -36,12 +45,38 @@ pub trait FinalStateController: Send + Sync {
     /// Panics if the new slot is not the one coming just after the current one.
     fn finalize(&mut self, slot: Slot, changes: StateChanges);
 
+    /// Same as `finalize`, but returns an error instead of panicking.
+    /// A `NonContiguousChange` error is returned, without touching the state,
+    /// if the new slot is not the one coming just after the current one.
+    ///
+    /// `allow_gap` is meant for bootstrap and initial loads only, where the state is attached
+    /// to a slot it has not executed: the new slot then only needs to come after the current one.
+    fn try_finalize(
+        &mut self,
+        slot: Slot,
+        changes: StateChanges,
+        allow_gap: bool,
+    ) -> Result<(), FinalStateError>;
+
     /// After bootstrap or load from disk, recompute all the caches.
     fn recompute_caches(&mut self);
 
     /// Deserialize the entire DB and check the data. Useful to check after bootstrap.
     fn is_db_valid(&self) -> bool;
 
+    /// Get a job compacting the state and metadata column families of the DB on disk,
+    /// purging the entries deleted since their last compaction to speed up reads.
+    ///
+    /// The job is I/O intensive. It holds no lock on the final state nor on the DB:
+    /// run it after releasing them, so that the final state keeps being written meanwhile.
+    fn get_compaction_job(&self) -> FinalStateCompactionJob;
+
+    /// Compact the state and metadata column families of the DB on disk, returning once it is over.
+    ///
+    /// The caller holds the final state for the whole compaction, which blocks its writes:
+    /// use [`get_compaction_job`](Self::get_compaction_job) to compact without holding it.
+    fn compact(&self) -> Result<(), FinalStateError>;
+
     /// Initialize the execution trail hash to zero.
     fn init_execution_trail_hash_to_batch(&mut self, batch: &mut DBBatch);
 
@@ -70,6 +105,18 @@ pub trait FinalStateController: Send + Sync {
     /// Get executed denunciations
     fn get_executed_denunciations(&self) -> &ExecutedDenunciations;
 
+    /// Get the number of executed denunciations recorded in the state, the expired ones being pruned
+    fn executed_denunciations_count(&self) -> usize;
+
+    /// Check the executed operations and denunciations of a part streamed by a bootstrap server,
+    /// given the streaming cursor before the part and the final slot declared by the server
+    fn check_bootstrap_part(
+        &self,
+        part: &StreamBatch<Slot>,
+        cursor: &StreamingStep<Key>,
+        final_slot: Slot,
+    ) -> Result<(), FinalStateError>;
+
     /// Get the database
     fn get_database(&self) -> &ShareableMassaDBController;
 
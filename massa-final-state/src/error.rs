@@ -5,6 +5,8 @@
 use displaydoc::Display;
 use thiserror::Error;
 
+use massa_executed_ops::BootstrapPartError;
+use massa_models::slot::Slot;
 use massa_versioning::versioning::{ExtendFromDbError, IsConsistentWithShutdownPeriodError};
 
 /// Final state error
@@ -19,6 +21,19 @@ pub enum FinalStateError {
     PosError(String),
     /// Snapshot error: {0}
     SnapshotError(String),
+    /// database error: {0}
+    DatabaseError(String),
+    /// non-contiguous change: the state is attached at slot {current}, a change at slot {got} can't be applied
+    NonContiguousChange {
+        /// slot at the end of which the state is attached
+        current: Slot,
+        /// slot of the rejected change
+        got: Slot,
+    },
+    /// could not apply the changes: {0}
+    ApplyError(String),
+    /// invalid part streamed by the bootstrap server: {0}
+    BootstrapPartError(#[from] BootstrapPartError),
     /// ExtendFromDbError
     ExtendFromDbError(#[from] ExtendFromDbError),
     /// IsConsistentWithShutdownPeriodError
@@ -5,7 +5,7 @@
 //! the output of a given final slot (the latest executed final slot),
 //! and need to be bootstrapped by nodes joining the network.
 
-use crate::controller_trait::FinalStateController;
+use crate::controller_trait::{FinalStateCompactionJob, FinalStateController};
 use crate::{config::FinalStateConfig, error::FinalStateError, state_changes::StateChanges};
 
 use anyhow::{anyhow, Result as AnyResult};
@@ -13,9 +13,12 @@ use massa_async_pool::AsyncPool;
 use massa_db_exports::{
     DBBatch, MassaIteratorMode, ShareableMassaDBController, ASYNC_POOL_PREFIX,
     CYCLE_HISTORY_PREFIX, DEFERRED_CREDITS_PREFIX, EXECUTED_DENUNCIATIONS_PREFIX,
-    EXECUTED_OPS_PREFIX, LEDGER_PREFIX, MIP_STORE_PREFIX, STATE_CF,
+    EXECUTED_OPS_PREFIX, LEDGER_PREFIX, METADATA_CF, MIP_STORE_PREFIX, STATE_CF,
+};
+use massa_db_exports::{
+    Key, StreamBatch, EXECUTION_TRAIL_HASH_PREFIX, MIP_STORE_STATS_PREFIX, SLASHING_STATS_PREFIX,
+    VERSIONING_CF,
 };
-use massa_db_exports::{EXECUTION_TRAIL_HASH_PREFIX, MIP_STORE_STATS_PREFIX, VERSIONING_CF};
 use massa_executed_ops::ExecutedDenunciations;
 use massa_executed_ops::ExecutedOps;
 use massa_hash::Hash;
@@ -23,9 +26,11 @@ use massa_ledger_exports::LedgerController;
 use massa_ledger_exports::SetOrKeep;
 use massa_models::operation::OperationId;
 use massa_models::slot::Slot;
+use massa_models::streaming_step::StreamingStep;
 use massa_models::timeslots::get_block_slot_timestamp;
 use massa_pos_exports::{PoSFinalState, SelectorController};
 use massa_versioning::versioning::MipStore;
+use std::collections::BTreeMap;
 use tracing::{debug, info, warn};
 
 /// Represents a final state `(ledger, async pool, executed_ops, executed_de and the state of the PoS)`
@@ -423,20 +428,28 @@ impl FinalState {
         Ok(())
     }
 
-    fn _finalize(&mut self, slot: Slot, changes: StateChanges) -> AnyResult<()> {
-        let cur_slot = self.db.read().get_change_id()?;
-        // check slot consistency
-        let next_slot = cur_slot.get_next_slot(self.config.thread_count)?;
-
-        // .expect("overflow in execution state slot");
-
-        if slot != next_slot {
-            return Err(anyhow!(
-                "attempting to apply execution state changes at slot {} while the current slot is {}",
-                slot, cur_slot
-            ));
+    /// Check that changes at `slot` can be applied to the state: `slot` must come just after
+    /// the current slot of the state, or anywhere after it if `allow_gap` is set
+    fn check_change_slot(&self, slot: Slot, allow_gap: bool) -> Result<(), FinalStateError> {
+        let current =
+            self.db.read().get_change_id().map_err(|_| {
+                FinalStateError::InvalidSlot(String::from("Could not get slot in db"))
+            })?;
+        let next_slot = current
+            .get_next_slot(self.config.thread_count)
+            .map_err(|err| FinalStateError::InvalidSlot(err.to_string()))?;
+        let contiguous = if allow_gap {
+            slot >= next_slot
+        } else {
+            slot == next_slot
+        };
+        if !contiguous {
+            return Err(FinalStateError::NonContiguousChange { current, got: slot });
         }
+        Ok(())
+    }
 
+    fn _finalize(&mut self, slot: Slot, changes: StateChanges) -> AnyResult<()> {
         let mut db_batch = DBBatch::new();
         let mut db_versioning_batch = DBBatch::new();
 
@@ -456,8 +469,10 @@ impl FinalState {
 
         self.executed_denunciations.apply_changes_to_batch(
             changes.executed_denunciations_changes,
+            &changes.executed_denunciations_slashings,
             slot,
             &mut db_batch,
+            &mut db_versioning_batch,
         );
 
         let slot_ts = get_block_slot_timestamp(
@@ -675,6 +690,17 @@ impl FinalState {
                         serialized_key, serialized_value
                     ));
                 }
+            } else if serialized_key.starts_with(SLASHING_STATS_PREFIX.as_bytes()) {
+                if !self
+                    .executed_denunciations
+                    .is_slashing_stats_key_value_valid(&serialized_key, &serialized_value)
+                {
+                    warn!("Wrong key/value for slashing stats");
+                    return Err(anyhow!(
+                        "Wrong key/value for slashing stats serialized_key: {:?}, serialized_value: {:?}",
+                        serialized_key, serialized_value
+                    ));
+                }
             } else {
                 warn!(
                     "Key/value does not correspond to any prefix: serialized_key: {:?}, serialized_value: {:?}",
@@ -795,7 +821,19 @@ impl FinalStateController for FinalState {
     }
 
     fn finalize(&mut self, slot: Slot, changes: StateChanges) {
-        self._finalize(slot, changes).unwrap()
+        self.try_finalize(slot, changes, false)
+            .unwrap_or_else(|err| panic!("could not finalize slot {}: {}", slot, err))
+    }
+
+    fn try_finalize(
+        &mut self,
+        slot: Slot,
+        changes: StateChanges,
+        allow_gap: bool,
+    ) -> Result<(), FinalStateError> {
+        self.check_change_slot(slot, allow_gap)?;
+        self._finalize(slot, changes)
+            .map_err(|err| FinalStateError::ApplyError(err.to_string()))
     }
 
     fn get_execution_trail_hash(&self) -> Hash {
@@ -818,6 +856,20 @@ impl FinalStateController for FinalState {
         Hash::compute_from(internal_hash.to_bytes())
     }
 
+    fn get_component_fingerprints(&self) -> BTreeMap<String, Hash> {
+        self.db
+            .read()
+            .get_xof_component_hashes()
+            .into_iter()
+            .map(|(prefix, internal_hash)| {
+                (
+                    prefix.trim_end_matches('/').to_string(),
+                    Hash::compute_from(internal_hash.to_bytes()),
+                )
+            })
+            .collect()
+    }
+
     fn get_slot(&self) -> Slot {
         self.db
             .read()
@@ -836,6 +888,21 @@ impl FinalStateController for FinalState {
         self._is_db_valid().is_ok()
     }
 
+    fn get_compaction_job(&self) -> FinalStateCompactionJob {
+        let compactor = self.db.read().get_compactor();
+        Box::new(move || {
+            for handle_cf in [STATE_CF, METADATA_CF] {
+                compactor(handle_cf)
+                    .map_err(|err| FinalStateError::DatabaseError(err.to_string()))?;
+            }
+            Ok(())
+        })
+    }
+
+    fn compact(&self) -> Result<(), FinalStateError> {
+        self.get_compaction_job()()
+    }
+
     fn recompute_caches(&mut self) {
         self.async_pool.recompute_message_info_cache();
         self.executed_ops.recompute_sorted_ops_and_op_exec_status();
@@ -890,6 +957,23 @@ impl FinalStateController for FinalState {
         &self.executed_denunciations
     }
 
+    fn executed_denunciations_count(&self) -> usize {
+        self.executed_denunciations.count()
+    }
+
+    fn check_bootstrap_part(
+        &self,
+        part: &StreamBatch<Slot>,
+        cursor: &StreamingStep<Key>,
+        final_slot: Slot,
+    ) -> Result<(), FinalStateError> {
+        self.executed_ops
+            .check_bootstrap_part(part, cursor, final_slot)?;
+        self.executed_denunciations
+            .check_bootstrap_part(part, cursor, final_slot)?;
+        Ok(())
+    }
+
     fn get_database(&self) -> &ShareableMassaDBController {
         &self.db
     }
@@ -947,7 +1031,7 @@ mod test {
         MAX_DEFERRED_CREDITS_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         MAX_DENUNCIATION_CHANGES_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_PARAMETERS_SIZE,
         MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, MIP_STORE_STATS_BLOCK_CONSIDERED,
-        PERIODS_PER_CYCLE, POS_SAVED_CYCLES, T0, THREAD_COUNT,
+        OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, POS_SAVED_CYCLES, T0, THREAD_COUNT,
     };
     use massa_pos_exports::MockSelectorController;
     use massa_pos_exports::{PoSChanges, PoSConfig, PosError};
@@ -987,12 +1071,14 @@ mod test {
         let executed_ops_config = ExecutedOpsConfig {
             thread_count: THREAD_COUNT,
             keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+            operation_validity_periods: OPERATION_VALIDITY_PERIODS,
         };
         let executed_denunciations_config = ExecutedDenunciationsConfig {
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
             thread_count: THREAD_COUNT,
             endorsement_count: ENDORSEMENT_COUNT,
             keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+            periods_per_cycle: PERIODS_PER_CYCLE,
         };
 
         let final_state_config = FinalStateConfig {
@@ -1011,6 +1097,7 @@ mod test {
             max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
             t0: T0,
             ledger_backup_periods_interval: 10,
+            db_cache_sizes: Default::default(),
             genesis_timestamp,
         };
 
@@ -1117,12 +1204,12 @@ mod test {
         let ok_next_slot = Slot::new(0, 1);
         let changes = get_state_changes();
 
-        let res = fstate._finalize(wrong_next_slot, changes.clone());
-        assert!(res
-            .err()
-            .unwrap()
-            .to_string()
-            .contains("while the current slot is"));
+        let res = fstate.try_finalize(wrong_next_slot, changes.clone(), false);
+        assert!(matches!(
+            res,
+            Err(FinalStateError::NonContiguousChange { current, got })
+                if current == initial_slot && got == wrong_next_slot
+        ));
 
         assert_eq!(fstate.get_slot(), initial_slot);
 
@@ -1304,6 +1391,17 @@ mod test {
         let db_valid = fstate._is_db_valid();
         assert!(db_valid.is_ok());
 
+        // Only the fingerprint of the execution trail hash component changed
+        let component_fingerprints = fstate.get_component_fingerprints();
+        assert_ne!(
+            component_fingerprints["execution_trail_hash"],
+            Hash::compute_from(STATE_HASH_INITIAL_BYTES)
+        );
+        assert_eq!(
+            component_fingerprints["ledger"],
+            Hash::compute_from(STATE_HASH_INITIAL_BYTES)
+        );
+
         // Check final state fingerprint before reset
         assert_ne!(
             fstate.get_fingerprint(),
@@ -95,7 +95,7 @@ mod mapping_grpc;
 mod state_changes;
 
 pub use config::FinalStateConfig;
-pub use controller_trait::FinalStateController;
+pub use controller_trait::{FinalStateCompactionJob, FinalStateController};
 pub use error::FinalStateError;
 pub use final_state::FinalState;
 use num as _;
@@ -7,8 +7,8 @@ use massa_async_pool::{
 };
 use massa_executed_ops::{
     ExecutedDenunciationsChanges, ExecutedDenunciationsChangesDeserializer,
-    ExecutedDenunciationsChangesSerializer, ExecutedOpsChanges, ExecutedOpsChangesDeserializer,
-    ExecutedOpsChangesSerializer,
+    ExecutedDenunciationsChangesSerializer, ExecutedDenunciationsSlashings, ExecutedOpsChanges,
+    ExecutedOpsChangesDeserializer, ExecutedOpsChangesSerializer,
 };
 use massa_hash::{HashDeserializer, HashSerializer};
 use massa_ledger_exports::{
@@ -37,6 +37,10 @@ pub struct StateChanges {
     pub executed_ops_changes: ExecutedOpsChanges,
     /// executed denunciations changes
     pub executed_denunciations_changes: ExecutedDenunciationsChanges,
+    /// slashing caused by the executed denunciations, only used for the slashing statistics:
+    /// it is not part of the final state, so it is neither serialized nor hashed
+    #[serde(skip)]
+    pub executed_denunciations_slashings: ExecutedDenunciationsSlashings,
     /// execution trail hash change
     pub execution_trail_hash_change: SetOrKeep<massa_hash::Hash>,
 }
@@ -201,6 +205,7 @@ impl Deserializer<StateChanges> for StateChangesDeserializer {
                 pos_changes,
                 executed_ops_changes,
                 executed_denunciations_changes,
+                executed_denunciations_slashings: Default::default(),
                 execution_trail_hash_change,
             },
         )
@@ -217,6 +222,8 @@ impl StateChanges {
         self.pos_changes.extend(changes.pos_changes);
         self.executed_ops_changes
             .extend(changes.executed_ops_changes);
+        self.executed_denunciations_slashings
+            .extend(changes.executed_denunciations_slashings);
         self.execution_trail_hash_change
             .apply(changes.execution_trail_hash_change);
     }
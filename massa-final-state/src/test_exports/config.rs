@@ -18,7 +18,7 @@ use massa_models::config::{
     DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, GENESIS_TIMESTAMP,
     KEEP_EXECUTED_HISTORY_EXTRA_PERIODS, MAX_DEFERRED_CREDITS_LENGTH,
     MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_DENUNCIATION_CHANGES_LENGTH,
-    MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, T0,
+    MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, OPERATION_VALIDITY_PERIODS, T0,
 };
 use massa_models::config::{PERIODS_PER_CYCLE, POS_SAVED_CYCLES, THREAD_COUNT};
 use massa_pos_exports::{PoSConfig, PoSFinalState};
@@ -65,12 +65,14 @@ impl Default for FinalStateConfig {
             executed_ops_config: ExecutedOpsConfig {
                 thread_count: THREAD_COUNT,
                 keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+                operation_validity_periods: OPERATION_VALIDITY_PERIODS,
             },
             executed_denunciations_config: ExecutedDenunciationsConfig {
                 denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
                 thread_count: THREAD_COUNT,
                 endorsement_count: ENDORSEMENT_COUNT,
                 keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+                periods_per_cycle: PERIODS_PER_CYCLE,
             },
             pos_config: PoSConfig {
                 periods_per_cycle: PERIODS_PER_CYCLE,
@@ -92,6 +94,7 @@ impl Default for FinalStateConfig {
             t0: T0,
             genesis_timestamp: *GENESIS_TIMESTAMP,
             ledger_backup_periods_interval: 100,
+            db_cache_sizes: Default::default(),
         }
     }
 }
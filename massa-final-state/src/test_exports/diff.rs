@@ -0,0 +1,429 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! This file defines a structural comparison between two final states.
+//!
+//! Instead of comparing raw column families, every component is walked through its
+//! public iteration API (and its in-memory caches), and each mismatch is reported
+//! as a typed `FinalStateDifference`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Debug, Display};
+
+use massa_db_exports::{
+    ShareableMassaDBController, ASYNC_POOL_PREFIX, CYCLE_HISTORY_PREFIX, DEFERRED_CREDITS_PREFIX,
+    EXECUTED_DENUNCIATIONS_PREFIX, EXECUTED_OPS_PREFIX, EXECUTION_TRAIL_HASH_PREFIX, LEDGER_PREFIX,
+    METADATA_CF, MIP_STORE_PREFIX, MIP_STORE_STATS_PREFIX, STATE_CF,
+    STATE_COMPONENT_HASH_KEY_PREFIX, STATE_HASH_KEY, VERSIONING_CF,
+};
+use massa_models::{address::Address, amount::Amount, slot::Slot};
+
+use crate::FinalState;
+
+/// Maximum number of differences printed when an equivalence assertion fails
+pub const MAX_PRINTED_DIFFERENCES: usize = 10;
+
+/// Component of the final state in which a difference was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FinalStateComponent {
+    /// change id, start period, downtime and execution trail hash
+    Metadata,
+    /// ledger entries (balance, bytecode and datastore)
+    Ledger,
+    /// asynchronous messages and the message info cache
+    AsyncPool,
+    /// executed operations
+    ExecutedOps,
+    /// executed denunciations
+    ExecutedDenunciations,
+    /// PoS cycle history and its caches
+    PosCycleHistory,
+    /// PoS deferred credits
+    PosDeferredCredits,
+    /// MIP store
+    Versioning,
+    /// keys that do not belong to any known component
+    Unknown,
+}
+
+impl FinalStateComponent {
+    /// Get the component owning a serialized key of the state column
+    pub fn from_state_key(key: &[u8]) -> Self {
+        let prefixes = [
+            (LEDGER_PREFIX, FinalStateComponent::Ledger),
+            (ASYNC_POOL_PREFIX, FinalStateComponent::AsyncPool),
+            (EXECUTED_OPS_PREFIX, FinalStateComponent::ExecutedOps),
+            (
+                EXECUTED_DENUNCIATIONS_PREFIX,
+                FinalStateComponent::ExecutedDenunciations,
+            ),
+            (CYCLE_HISTORY_PREFIX, FinalStateComponent::PosCycleHistory),
+            (
+                DEFERRED_CREDITS_PREFIX,
+                FinalStateComponent::PosDeferredCredits,
+            ),
+            (MIP_STORE_PREFIX, FinalStateComponent::Versioning),
+            (MIP_STORE_STATS_PREFIX, FinalStateComponent::Versioning),
+            (EXECUTION_TRAIL_HASH_PREFIX, FinalStateComponent::Metadata),
+        ];
+        prefixes
+            .iter()
+            .find(|(prefix, _)| key.starts_with(prefix.as_bytes()))
+            .map(|(_, component)| *component)
+            .unwrap_or(FinalStateComponent::Unknown)
+    }
+}
+
+/// A single difference between two final states
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalStateDifference {
+    /// component in which the difference was found
+    pub component: FinalStateComponent,
+    /// key of the differing element
+    pub key: String,
+    /// summary of the value in the left state, `None` if missing
+    pub left: Option<String>,
+    /// summary of the value in the right state, `None` if missing
+    pub right: Option<String>,
+}
+
+impl Display for FinalStateDifference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{:?}] {}: left = {}, right = {}",
+            self.component,
+            self.key,
+            self.left.as_deref().unwrap_or("<missing>"),
+            self.right.as_deref().unwrap_or("<missing>")
+        )
+    }
+}
+
+/// List of differences between two final states
+#[derive(Debug, Clone, Default)]
+pub struct FinalStateDiff {
+    /// differences, grouped by component
+    pub differences: Vec<FinalStateDifference>,
+}
+
+impl FinalStateDiff {
+    /// Compare two final states component by component, including their caches
+    pub fn compute(left: &FinalState, right: &FinalState) -> Self {
+        let mut diff = FinalStateDiff::default();
+        diff.compare(
+            FinalStateComponent::Metadata,
+            metadata_entries(left),
+            metadata_entries(right),
+        );
+        diff.compare(
+            FinalStateComponent::Ledger,
+            ledger_entries(left),
+            ledger_entries(right),
+        );
+        diff.compare(
+            FinalStateComponent::AsyncPool,
+            async_pool_entries(left),
+            async_pool_entries(right),
+        );
+        diff.compare(
+            FinalStateComponent::ExecutedOps,
+            executed_ops_entries(left),
+            executed_ops_entries(right),
+        );
+        diff.compare(
+            FinalStateComponent::ExecutedDenunciations,
+            executed_denunciations_entries(left),
+            executed_denunciations_entries(right),
+        );
+        diff.compare(
+            FinalStateComponent::PosCycleHistory,
+            pos_cycle_entries(left),
+            pos_cycle_entries(right),
+        );
+        diff.compare(
+            FinalStateComponent::PosDeferredCredits,
+            deferred_credits_entries(left),
+            deferred_credits_entries(right),
+        );
+        let left_db = left.db.read().get_entire_database();
+        let right_db = right.db.read().get_entire_database();
+        diff.compare(
+            FinalStateComponent::Versioning,
+            versioning_column(&left_db),
+            versioning_column(&right_db),
+        );
+        diff
+    }
+
+    /// Compare two final state databases key by key, attributing each difference to a component
+    pub fn compute_databases(
+        left: &ShareableMassaDBController,
+        right: &ShareableMassaDBController,
+    ) -> Self {
+        let left_db = left.read().get_entire_database();
+        let right_db = right.read().get_entire_database();
+        let mut diff = FinalStateDiff::default();
+        for (column, component) in [
+            (STATE_CF, None),
+            (METADATA_CF, Some(FinalStateComponent::Metadata)),
+            (VERSIONING_CF, Some(FinalStateComponent::Versioning)),
+        ] {
+            let empty = BTreeMap::new();
+            let left_column = column_entries(&left_db, column).unwrap_or(&empty);
+            let right_column = column_entries(&right_db, column).unwrap_or(&empty);
+            let keys: BTreeSet<&Vec<u8>> = left_column.keys().chain(right_column.keys()).collect();
+            for key in keys {
+                let left_value = left_column.get(key);
+                let right_value = right_column.get(key);
+                if left_value != right_value {
+                    diff.differences.push(FinalStateDifference {
+                        component: component
+                            .unwrap_or_else(|| FinalStateComponent::from_state_key(key)),
+                        key: key.escape_ascii().to_string(),
+                        left: left_value.map(|v| v.escape_ascii().to_string()),
+                        right: right_value.map(|v| v.escape_ascii().to_string()),
+                    });
+                }
+            }
+        }
+        diff.differences.sort_by_key(|d| d.component);
+        diff
+    }
+
+    /// true if no difference was found
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Panics if any difference was found, printing the first `MAX_PRINTED_DIFFERENCES` of them
+    pub fn assert_empty(&self) {
+        if self.is_empty() {
+            return;
+        }
+        let printed: Vec<String> = self
+            .differences
+            .iter()
+            .take(MAX_PRINTED_DIFFERENCES)
+            .map(|d| d.to_string())
+            .collect();
+        panic!(
+            "final states are not equivalent, {} difference(s) found, first {}:\n{}",
+            self.differences.len(),
+            printed.len(),
+            printed.join("\n")
+        );
+    }
+
+    fn compare<K: Ord + Debug, V: PartialEq + Debug>(
+        &mut self,
+        component: FinalStateComponent,
+        left: BTreeMap<K, V>,
+        right: BTreeMap<K, V>,
+    ) {
+        let keys: BTreeSet<&K> = left.keys().chain(right.keys()).collect();
+        for key in keys {
+            let left_value = left.get(key);
+            let right_value = right.get(key);
+            if left_value != right_value {
+                self.differences.push(FinalStateDifference {
+                    component,
+                    key: format!("{:?}", key),
+                    left: left_value.map(|v| format!("{:?}", v)),
+                    right: right_value.map(|v| format!("{:?}", v)),
+                });
+            }
+        }
+    }
+}
+
+/// asserts that two `FinalState` are equivalent, component by component
+pub fn assert_final_states_equivalent(left: &FinalState, right: &FinalState) {
+    FinalStateDiff::compute(left, right).assert_empty();
+}
+
+/// asserts that two final state databases are equivalent, key by key
+pub fn assert_final_state_databases_equivalent(
+    left: &ShareableMassaDBController,
+    right: &ShareableMassaDBController,
+) {
+    FinalStateDiff::compute_databases(left, right).assert_empty();
+}
+
+fn column_entries<'a>(
+    database: &'a [BTreeMap<Vec<u8>, Vec<u8>>],
+    column: &str,
+) -> Option<&'a BTreeMap<Vec<u8>, Vec<u8>>> {
+    // same order as `MassaDBController::get_entire_database`
+    let index = match column {
+        STATE_CF => 0,
+        METADATA_CF => 1,
+        VERSIONING_CF => 2,
+        _ => return None,
+    };
+    database.get(index)
+}
+
+fn metadata_entries(state: &FinalState) -> BTreeMap<String, String> {
+    let db = state.db.read();
+    let mut entries = BTreeMap::new();
+    entries.insert(
+        "change_id".to_string(),
+        format!("{:?}", db.get_change_id().ok()),
+    );
+    entries.insert(
+        "last_start_period".to_string(),
+        state.last_start_period.to_string(),
+    );
+    entries.insert(
+        "last_slot_before_downtime".to_string(),
+        format!("{:?}", state.last_slot_before_downtime),
+    );
+    entries.insert(
+        "execution_trail_hash".to_string(),
+        format!(
+            "{:?}",
+            db.get_cf(STATE_CF, EXECUTION_TRAIL_HASH_PREFIX.as_bytes().to_vec())
+                .ok()
+                .flatten()
+        ),
+    );
+    // the state hashes are not compared, as they are derived from the compared content
+    if let Some(metadata) = column_entries(&db.get_entire_database(), METADATA_CF) {
+        for (key, value) in metadata {
+            if key.as_slice() != STATE_HASH_KEY.as_slice()
+                && !key.starts_with(STATE_COMPONENT_HASH_KEY_PREFIX)
+            {
+                entries.insert(
+                    key.escape_ascii().to_string(),
+                    value.escape_ascii().to_string(),
+                );
+            }
+        }
+    }
+    entries
+}
+
+fn ledger_entries(state: &FinalState) -> BTreeMap<String, String> {
+    let mut entries = BTreeMap::new();
+    for (addr, balance) in state.ledger.get_every_address() {
+        entries.insert(format!("{}/balance", addr), balance.to_string());
+        entries.insert(
+            format!("{}/bytecode", addr),
+            format!("{:?}", state.ledger.get_bytecode(&addr)),
+        );
+        for (key, value) in state.ledger.get_entire_datastore(&addr) {
+            entries.insert(
+                format!("{}/datastore/{}", addr, key.escape_ascii()),
+                value.escape_ascii().to_string(),
+            );
+        }
+    }
+    entries
+}
+
+fn async_pool_entries(state: &FinalState) -> BTreeMap<String, String> {
+    let mut entries = BTreeMap::new();
+    for (id, info) in state.async_pool.message_info_cache.iter() {
+        entries.insert(format!("{:?}/info", id), format!("{:?}", info));
+        entries.insert(
+            format!("{:?}/message", id),
+            format!("{:?}", state.async_pool.fetch_message(id)),
+        );
+    }
+    entries
+}
+
+fn executed_ops_entries(state: &FinalState) -> BTreeMap<String, (Option<Slot>, Option<bool>)> {
+    let executed_ops = &state.executed_ops;
+    let mut entries = BTreeMap::new();
+    for (slot, ids) in executed_ops.sorted_ops.iter() {
+        for id in ids {
+            entries.insert(
+                id.to_string(),
+                (Some(*slot), executed_ops.op_exec_status.get(id).copied()),
+            );
+        }
+    }
+    // statuses that are not referenced by `sorted_ops` are reported too
+    for (id, status) in executed_ops.op_exec_status.iter() {
+        entries
+            .entry(id.to_string())
+            .or_insert((None, Some(*status)));
+    }
+    entries
+}
+
+fn executed_denunciations_entries(state: &FinalState) -> BTreeMap<String, Slot> {
+    let mut entries = BTreeMap::new();
+    for (slot, indexes) in state.executed_denunciations.sorted_denunciations.iter() {
+        for index in indexes {
+            entries.insert(format!("{:?}", index), *slot);
+        }
+    }
+    entries
+}
+
+fn pos_cycle_entries(state: &FinalState) -> BTreeMap<String, String> {
+    let pos_state = &state.pos_state;
+    let mut entries = BTreeMap::new();
+    entries.insert(
+        "cycle_history_cache".to_string(),
+        format!("{:?}", pos_state.cycle_history_cache),
+    );
+    entries.insert(
+        "rng_seed_cache".to_string(),
+        format!("{:?}", pos_state.rng_seed_cache),
+    );
+    for (cycle, _) in pos_state.cycle_history_cache.iter() {
+        if let Some(cycle_info) = pos_state.get_cycle_info(*cycle) {
+            entries.insert(
+                format!("cycle {}/complete", cycle),
+                cycle_info.complete.to_string(),
+            );
+            entries.insert(
+                format!("cycle {}/rng_seed", cycle),
+                format!("{:?}", cycle_info.rng_seed),
+            );
+            entries.insert(
+                format!("cycle {}/final_state_hash_snapshot", cycle),
+                format!("{:?}", cycle_info.final_state_hash_snapshot),
+            );
+            for (addr, rolls) in cycle_info.roll_counts.iter() {
+                entries.insert(format!("cycle {}/rolls/{}", cycle, addr), rolls.to_string());
+            }
+            for (addr, stats) in cycle_info.production_stats.iter() {
+                entries.insert(
+                    format!("cycle {}/production_stats/{}", cycle, addr),
+                    format!("{:?}", stats),
+                );
+            }
+        }
+    }
+    entries
+}
+
+fn deferred_credits_entries(state: &FinalState) -> BTreeMap<(Slot, Address), Amount> {
+    let mut entries = BTreeMap::new();
+    for (slot, credits) in state.pos_state.get_deferred_credits().credits {
+        for (addr, amount) in credits {
+            entries.insert((slot, addr), amount);
+        }
+    }
+    entries
+}
+
+fn versioning_column(database: &[BTreeMap<Vec<u8>, Vec<u8>>]) -> BTreeMap<String, String> {
+    column_entries(database, VERSIONING_CF)
+        .map(|column| {
+            column
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.escape_ascii().to_string(),
+                        value.escape_ascii().to_string(),
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
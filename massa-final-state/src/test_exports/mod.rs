@@ -3,6 +3,8 @@
 //! This file exports testing utilities
 
 mod config;
+mod diff;
 mod mock;
 
+pub use diff::*;
 pub use mock::*;
@@ -2,25 +2,27 @@
 
 use crate::controller_trait::FinalStateController;
 use crate::{
-    /*test_exports::{assert_eq_final_state, assert_eq_final_state_hash},*/
-    FinalState, FinalStateConfig, StateChanges,
+    /*test_exports::{assert_final_states_equivalent, assert_eq_final_state_hash},*/
+    FinalState, FinalStateConfig, FinalStateError, StateChanges,
 };
 use massa_async_pool::{AsyncMessage, AsyncPoolChanges, AsyncPoolConfig};
 use massa_db_exports::{DBBatch, MassaDBConfig, MassaDBController};
 use massa_db_worker::MassaDB;
 use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
+use massa_hash::Hash;
 use massa_ledger_exports::{
-    LedgerChanges, LedgerConfig, LedgerEntryUpdate, SetOrKeep, SetUpdateOrDelete,
+    LedgerChanges, LedgerConfig, LedgerEntry, LedgerEntryUpdate, SetOrKeep, SetUpdateOrDelete,
 };
 use massa_ledger_worker::FinalLedger;
-use massa_models::address::Address;
+use massa_models::address::{Address, UserAddress, UserAddressV0};
 use massa_models::amount::Amount;
 use massa_models::bytecode::Bytecode;
 use massa_models::config::{
     DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, GENESIS_TIMESTAMP,
     KEEP_EXECUTED_HISTORY_EXTRA_PERIODS, MAX_ASYNC_POOL_LENGTH, MAX_DATASTORE_KEY_LENGTH,
     MAX_DEFERRED_CREDITS_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_FUNCTION_NAME_LENGTH,
-    MAX_PARAMETERS_SIZE, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH, POS_SAVED_CYCLES, T0,
+    MAX_PARAMETERS_SIZE, MAX_PRODUCTION_STATS_LENGTH, MAX_ROLLS_COUNT_LENGTH,
+    OPERATION_VALIDITY_PERIODS, POS_SAVED_CYCLES, T0,
 };
 use massa_models::{config::MAX_DATASTORE_VALUE_LENGTH, slot::Slot};
 use massa_pos_exports::{PoSConfig, SelectorConfig};
@@ -76,12 +78,14 @@ fn create_final_state(temp_dir: &TempDir, reset_final_state: bool) -> Arc<RwLock
         executed_ops_config: ExecutedOpsConfig {
             thread_count,
             keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+            operation_validity_periods: OPERATION_VALIDITY_PERIODS,
         },
         executed_denunciations_config: ExecutedDenunciationsConfig {
             denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
             thread_count,
             endorsement_count: ENDORSEMENT_COUNT,
             keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+            periods_per_cycle,
         },
         final_history_length: 100,
         initial_seed_string: "".into(),
@@ -94,6 +98,7 @@ fn create_final_state(temp_dir: &TempDir, reset_final_state: bool) -> Arc<RwLock
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
         ledger_backup_periods_interval: 10,
+        db_cache_sizes: Default::default(),
     };
 
     // setup selector local config
@@ -224,3 +229,137 @@ fn test_final_state() {
 
     assert_eq!(hash, hash2);
 }
+
+#[test]
+fn test_final_state_compaction() {
+    let temp_dir = TempDir::new().unwrap();
+    let fs = create_final_state(&temp_dir, true);
+
+    let mut batch = DBBatch::new();
+    fs.write().pos_state.create_initial_cycle(&mut batch);
+    let slot = fs.read().db.read().get_change_id().unwrap();
+    fs.write()
+        .db
+        .write()
+        .write_batch(batch, DBBatch::new(), Some(slot));
+
+    // populate the ledger, then delete half of it to leave deleted entries on disk
+    let addresses: Vec<Address> = (0..100u32)
+        .map(|index| {
+            Address::User(UserAddress::UserAddressV0(UserAddressV0(
+                Hash::compute_from(&index.to_be_bytes()),
+            )))
+        })
+        .collect();
+    let amount = Amount::from_str("1").unwrap();
+    let mut state_changes = StateChanges::default();
+    for address in addresses.iter() {
+        state_changes.ledger_changes.0.insert(
+            *address,
+            SetUpdateOrDelete::Set(LedgerEntry {
+                balance: amount,
+                ..Default::default()
+            }),
+        );
+    }
+    fs.write().finalize(Slot::new(1, 0), state_changes);
+    let mut state_changes = StateChanges::default();
+    for address in addresses.iter().step_by(2) {
+        state_changes
+            .ledger_changes
+            .0
+            .insert(*address, SetUpdateOrDelete::Delete);
+    }
+    fs.write().finalize(Slot::new(1, 1), state_changes);
+    fs.read().db.read().flush().unwrap();
+
+    let hash = fs.read().db.read().get_xof_db_hash();
+    let compaction_job = fs.read().get_compaction_job();
+    {
+        // the job holds no lock: it runs while the final state and its DB are locked for writing
+        let final_state = fs.write();
+        let _db = final_state.db.write();
+        compaction_job().unwrap();
+    }
+
+    // compaction leaves the content of the state untouched
+    assert_eq!(fs.read().db.read().get_xof_db_hash(), hash);
+    assert_eq!(fs.read().get_ledger().get_balance(&addresses[0]), None);
+    assert_eq!(
+        fs.read().get_ledger().get_balance(&addresses[1]),
+        Some(amount)
+    );
+
+    // compacting in place does too
+    fs.read().compact().unwrap();
+    assert_eq!(fs.read().db.read().get_xof_db_hash(), hash);
+    assert_eq!(
+        fs.read().get_ledger().get_balance(&addresses[1]),
+        Some(amount)
+    );
+}
+
+/// Final state attached at the last genesis slot, with its initial cycle
+fn create_initial_final_state(temp_dir: &TempDir) -> Arc<RwLock<FinalState>> {
+    let fs = create_final_state(temp_dir, true);
+    let mut batch = DBBatch::new();
+    fs.write().pos_state.create_initial_cycle(&mut batch);
+    let slot = fs.read().db.read().get_change_id().unwrap();
+    fs.write()
+        .db
+        .write()
+        .write_batch(batch, DBBatch::new(), Some(slot));
+    fs
+}
+
+#[test]
+fn test_final_state_contiguous_changes() {
+    let temp_dir = TempDir::new().unwrap();
+    let fs = create_initial_final_state(&temp_dir);
+    assert_eq!(fs.read().get_slot(), Slot::new(0, 1));
+
+    fs.write()
+        .try_finalize(Slot::new(1, 0), StateChanges::default(), false)
+        .unwrap();
+    fs.write()
+        .try_finalize(Slot::new(1, 1), StateChanges::default(), false)
+        .unwrap();
+    assert_eq!(fs.read().get_slot(), Slot::new(1, 1));
+}
+
+#[test]
+fn test_final_state_change_gap() {
+    let temp_dir = TempDir::new().unwrap();
+    let fs = create_initial_final_state(&temp_dir);
+    let hash = fs.read().db.read().get_xof_db_hash();
+
+    // skipping a slot, or applying the current one again, is rejected without touching the state
+    for slot in [Slot::new(1, 1), Slot::new(0, 1)] {
+        let err = fs
+            .write()
+            .try_finalize(slot, StateChanges::default(), false)
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                FinalStateError::NonContiguousChange { current, got }
+                    if current == Slot::new(0, 1) && got == slot
+            ),
+            "unexpected error: {}",
+            err
+        );
+    }
+    assert_eq!(fs.read().get_slot(), Slot::new(0, 1));
+    assert_eq!(fs.read().db.read().get_xof_db_hash(), hash);
+
+    // the override accepts a gap, but the state still can't go back
+    assert!(matches!(
+        fs.write()
+            .try_finalize(Slot::new(0, 1), StateChanges::default(), true),
+        Err(FinalStateError::NonContiguousChange { .. })
+    ));
+    fs.write()
+        .try_finalize(Slot::new(1, 1), StateChanges::default(), true)
+        .unwrap();
+    assert_eq!(fs.read().get_slot(), Slot::new(1, 1));
+}
@@ -0,0 +1,66 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use flate2::{write::GzEncoder, Compression};
+use massa_proto_rs::massa::{api::v1 as grpc_api, model::v1 as grpc_model};
+use prost::Message;
+use std::io::Write;
+
+/// threshold of the selective compression, in bytes
+const COMPRESS_THRESHOLD_BYTES: usize = 1024;
+
+/// Encoded responses of a mixed workload: mostly small responses with a few large ones
+fn mixed_responses() -> Vec<Vec<u8>> {
+    let datastore_entries = |count: usize, value_length: usize| {
+        grpc_api::GetDatastoreEntriesResponse {
+            datastore_entries: (0..count)
+                .map(|index| grpc_model::DatastoreEntry {
+                    final_value: vec![index as u8; value_length],
+                    candidate_value: vec![index as u8; value_length],
+                })
+                .collect(),
+        }
+        .encode_to_vec()
+    };
+    (0..100)
+        .map(|index| {
+            if index % 10 == 0 {
+                datastore_entries(500, 256)
+            } else {
+                datastore_entries(1, 64)
+            }
+        })
+        .collect()
+}
+
+/// Gzip a response the way the server compresses it
+fn gzip(response: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(response.len()), Compression::new(6));
+    encoder.write_all(response).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn compression(c: &mut Criterion) {
+    let responses = mixed_responses();
+    let mut group = c.benchmark_group("mixed_responses");
+    group.bench_function("compress_all", |b| {
+        b.iter(|| {
+            for response in &responses {
+                black_box(gzip(response));
+            }
+        })
+    });
+    group.bench_function("compress_above_threshold", |b| {
+        b.iter(|| {
+            for response in &responses {
+                if response.len() > COMPRESS_THRESHOLD_BYTES {
+                    black_box(gzip(response));
+                } else {
+                    black_box(response);
+                }
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, compression);
+criterion_main!(benches);
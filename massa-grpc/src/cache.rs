@@ -0,0 +1,231 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Cache of the responses of the expensive idempotent queries of the public service.
+//!
+//! A response is keyed by its method and the hash of the encoded request, and is served again
+//! until the execution reports a new candidate or final slot, until the slot clock reaches a new
+//! slot, or until `response_cache_ttl` elapses. The slot clock keeps the responses fresh when the
+//! execution does not broadcast its slots. Only the methods listed in `response_cache_methods`
+//! are cached, and they must be part of [`CACHEABLE_METHODS`]: streams and methods changing the
+//! node state are never cached. The handlers recompute the fields depending on the current time,
+//! such as the times of `get_status`, on each call.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use massa_execution_exports::SlotExecutionOutput;
+use massa_hash::Hash;
+use massa_models::slot::Slot;
+use massa_models::timeslots::get_latest_block_slot_at_timestamp;
+use massa_time::MassaTime;
+use parking_lot::Mutex;
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tracing::debug;
+
+use crate::config::GrpcConfig;
+
+/// methods of the public service whose responses can be cached
+pub const CACHEABLE_METHODS: [&str; 4] = [
+    "get_selector_draws",
+    "get_stakers",
+    "get_status",
+    "get_transactions_throughput",
+];
+
+/// Cached response
+struct CacheEntry {
+    /// latest executed slot when the response was computed
+    slot: Option<Slot>,
+    /// number of slots reported to the cache when the response was computed
+    slot_sequence: u64,
+    /// latest slot of the slot clock when the response was computed
+    clock_slot: Option<Slot>,
+    /// when the response was computed
+    computed_at: Instant,
+    /// the response itself
+    response: Arc<dyn Any + Send + Sync>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// latest executed slot reported by the execution
+    slot: Option<Slot>,
+    /// latest final slot reported by the execution
+    final_slot: Option<Slot>,
+    /// number of slots reported, a slot may be executed again after a blockclique change
+    slot_sequence: u64,
+    /// cached responses, by method and hash of the encoded request
+    entries: HashMap<(&'static str, Hash), CacheEntry>,
+}
+
+impl CacheState {
+    fn is_valid(&self, entry: &CacheEntry, ttl: Duration, clock_slot: Option<Slot>) -> bool {
+        entry.slot_sequence == self.slot_sequence
+            && entry.slot == self.slot
+            && entry.clock_slot == clock_slot
+            && entry.computed_at.elapsed() < ttl
+    }
+
+    fn new_slot(&mut self, slot: Slot, is_final: bool) {
+        if is_final {
+            self.final_slot = Some(slot);
+        } else {
+            self.slot = Some(slot);
+        }
+        self.slot_sequence += 1;
+        self.entries.clear();
+    }
+}
+
+/// Response cache of the public service, shared by all its connections
+#[derive(Clone)]
+pub struct ResponseCache {
+    /// methods whose responses are cached
+    methods: Arc<Vec<&'static str>>,
+    /// max age of a cached response
+    ttl: Duration,
+    /// max number of cached responses
+    max_entries: usize,
+    /// slot clock: thread count, t0 and genesis timestamp
+    clock: (u8, MassaTime, MassaTime),
+    state: Arc<Mutex<CacheState>>,
+}
+
+impl ResponseCache {
+    /// Create a cache for the methods listed in `response_cache_methods`.
+    ///
+    /// The methods that can't be cached are ignored here, they are rejected by [`GrpcConfig::validate`].
+    pub fn new(config: &GrpcConfig) -> Self {
+        let methods = CACHEABLE_METHODS
+            .into_iter()
+            .filter(|method| config.response_cache_methods.iter().any(|m| m == method))
+            .collect();
+        ResponseCache {
+            methods: Arc::new(methods),
+            ttl: config.response_cache_ttl,
+            max_entries: config.response_cache_max_entries,
+            clock: (config.thread_count, config.t0, config.genesis_timestamp),
+            state: Default::default(),
+        }
+    }
+
+    /// Latest slot of the slot clock, `None` before genesis
+    fn clock_slot(&self) -> Option<Slot> {
+        let (thread_count, t0, genesis_timestamp) = self.clock;
+        get_latest_block_slot_at_timestamp(thread_count, t0, genesis_timestamp, MassaTime::now())
+            .ok()
+            .flatten()
+    }
+
+    /// Whether at least one method is cached
+    pub fn is_enabled(&self) -> bool {
+        !self.methods.is_empty() && self.max_entries > 0 && !self.ttl.is_zero()
+    }
+
+    /// Latest executed and final slots reported to the cache
+    pub fn slots(&self) -> (Option<Slot>, Option<Slot>) {
+        let state = self.state.lock();
+        (state.slot, state.final_slot)
+    }
+
+    /// Return the cached response of `request` if it is still valid, or compute and cache it.
+    ///
+    /// The response is only cached if no new slot was reported or reached by the slot clock
+    /// while it was computed.
+    pub fn get_or_compute<Req, Resp, E>(
+        &self,
+        method: &'static str,
+        request: tonic::Request<Req>,
+        compute: impl FnOnce(tonic::Request<Req>) -> Result<Resp, E>,
+    ) -> Result<Resp, E>
+    where
+        Req: prost::Message,
+        Resp: Clone + Send + Sync + 'static,
+    {
+        if !self.is_enabled() || !self.methods.contains(&method) {
+            return compute(request);
+        }
+
+        let key = (
+            method,
+            Hash::compute_from(&request.get_ref().encode_to_vec()),
+        );
+        let clock_slot = self.clock_slot();
+        let (slot, slot_sequence) = {
+            let state = self.state.lock();
+            if let Some(response) = state
+                .entries
+                .get(&key)
+                .filter(|entry| state.is_valid(entry, self.ttl, clock_slot))
+                .and_then(|entry| entry.response.downcast_ref::<Resp>())
+            {
+                massa_metrics::inc_grpc_response_cache_hits(method);
+                return Ok(response.clone());
+            }
+            (state.slot, state.slot_sequence)
+        };
+        massa_metrics::inc_grpc_response_cache_misses(method);
+
+        let response = compute(request)?;
+
+        let mut state = self.state.lock();
+        if state.slot_sequence == slot_sequence && self.clock_slot() == clock_slot {
+            if state.entries.len() >= self.max_entries && !state.entries.contains_key(&key) {
+                let ttl = self.ttl;
+                state
+                    .entries
+                    .retain(|_, entry| entry.computed_at.elapsed() < ttl);
+                if state.entries.len() >= self.max_entries {
+                    let oldest = state
+                        .entries
+                        .iter()
+                        .min_by_key(|(_, entry)| entry.computed_at)
+                        .map(|(key, _)| *key);
+                    if let Some(oldest) = oldest {
+                        state.entries.remove(&oldest);
+                    }
+                }
+            }
+            state.entries.insert(
+                key,
+                CacheEntry {
+                    slot,
+                    slot_sequence,
+                    clock_slot,
+                    computed_at: Instant::now(),
+                    response: Arc::new(response.clone()),
+                },
+            );
+        }
+        Ok(response)
+    }
+
+    /// Drop the cached responses each time the execution reports a new candidate or final slot.
+    /// The reports are only sent if the execution broadcasts are enabled: the slot clock
+    /// invalidates the responses otherwise.
+    pub fn spawn_invalidation(&self, mut receiver: Receiver<SlotExecutionOutput>) {
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(SlotExecutionOutput::ExecutedSlot(output)) => {
+                        state.lock().new_slot(output.slot, false)
+                    }
+                    Ok(SlotExecutionOutput::FinalizedSlot(output)) => {
+                        state.lock().new_slot(output.slot, true)
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        // the skipped slots are unknown, the cached responses may be outdated
+                        debug!("response cache missed {} slot notifications", skipped);
+                        let mut state = state.lock();
+                        state.slot_sequence += 1;
+                        state.entries.clear();
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
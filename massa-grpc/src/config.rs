@@ -1,11 +1,16 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::cache::CACHEABLE_METHODS;
+use crate::error::GrpcConfigError;
 use massa_models::amount::Amount;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use serde::Deserialize;
 use std::{net::SocketAddr, path::PathBuf, time::Duration};
 
+/// max number of concurrent streams per connection of the public service, if not configured
+pub const DEFAULT_PUBLIC_MAX_CONCURRENT_STREAMS: u32 = 100;
+
 /// gRPC configuration.
 /// the gRPC configuration
 #[derive(Debug, Deserialize, Clone)]
@@ -36,6 +41,8 @@ pub struct GrpcConfig {
     pub accept_compressed: Option<String>,
     /// which compression encodings might the server use for responses
     pub send_compressed: Option<String>,
+    /// only compress the unary responses larger than this size in bytes. If `None`, every response is compressed according to `send_compressed`
+    pub compress_threshold_bytes: Option<usize>,
     /// limits the maximum size of a decoded message. Defaults to 4MB
     pub max_decoding_message_size: usize,
     /// limits the maximum size of an encoded message. Defaults to 4MB
@@ -48,7 +55,7 @@ pub struct GrpcConfig {
     pub initial_stream_window_size: Option<u32>,
     /// sets the max connection-level flow control for HTTP2. Default is 65,535
     pub initial_connection_window_size: Option<u32>,
-    /// sets the SETTINGS_MAX_CONCURRENT_STREAMS spec option for HTTP2 connections. Default is no limit (`None`)
+    /// sets the SETTINGS_MAX_CONCURRENT_STREAMS spec option for HTTP2 connections. `None` means no limit
     pub max_concurrent_streams: Option<u32>,
     /// max number of arguments per gRPC request
     pub max_arguments: u64,
@@ -100,6 +107,9 @@ pub struct GrpcConfig {
     pub keypair: KeyPair,
     /// limits the maximum size of streaming channel
     pub max_channel_size: usize,
+    /// drop a stream whose client hasn't read anything for this duration while its channel is full,
+    /// `None` to keep the streams of stalled clients open
+    pub stream_client_idle_timeout: Option<Duration>,
     /// when looking for next draw we want to look at max `draw_lookahead_period_count`
     pub draw_lookahead_period_count: u64,
     /// last_start_period of the network, used to deserialize blocks
@@ -132,12 +142,81 @@ pub struct GrpcConfig {
     pub client_certificate_path: PathBuf,
     /// client private key path
     pub client_private_key_path: PathBuf,
+    /// interval at which the TLS certificate files are checked for changes and reloaded,
+    /// `None` to load them only at startup
+    pub certificate_reload_interval: Option<Duration>,
+    /// methods of the public service whose responses are cached until a new slot is executed, finalized
+    /// or reached by the slot clock.
+    /// Only the methods of `cache::CACHEABLE_METHODS` are accepted, the cache is disabled if empty
+    pub response_cache_methods: Vec<String>,
+    /// max age of a cached response, in case no new slot is executed meanwhile
+    pub response_cache_ttl: Duration,
+    /// max number of cached responses
+    pub response_cache_max_entries: usize,
     /// chain id
     pub chain_id: u64,
     /// minimal fees
     pub minimal_fees: Amount,
 }
 
+impl GrpcConfig {
+    /// Check the consistency of the configuration before starting the server.
+    ///
+    /// `bind` is not checked here: it is already parsed as a socket address when the
+    /// configuration is loaded. HTTP/2 is always served, `accept_http1` only adds HTTP/1.1.
+    pub fn validate(&self) -> Result<(), GrpcConfigError> {
+        if self.enable_cors && !self.accept_http1 {
+            return Err(GrpcConfigError::CorsWithoutHttp1);
+        }
+        if let Some(method) = self
+            .response_cache_methods
+            .iter()
+            .find(|method| !CACHEABLE_METHODS.contains(&method.as_str()))
+        {
+            return Err(GrpcConfigError::UncacheableMethod(method.clone()));
+        }
+        if !self.enable_tls {
+            // mTLS and the certificate files are ignored without TLS
+            return Ok(());
+        }
+        if self.certificate_reload_interval == Some(Duration::ZERO) {
+            return Err(GrpcConfigError::ZeroCertificateReloadInterval);
+        }
+        // self-signed certificates are generated at startup if the CA root does not exist yet
+        if self.generate_self_signed_certificates && !self.certificate_authority_root_path.exists()
+        {
+            return Ok(());
+        }
+        let mut files = vec![
+            ("server_certificate_path", &self.server_certificate_path),
+            ("server_private_key_path", &self.server_private_key_path),
+        ];
+        if self.enable_mtls {
+            files.push((
+                "client_certificate_authority_root_path",
+                &self.client_certificate_authority_root_path,
+            ));
+        }
+        for (name, path) in files {
+            if !path.is_file() {
+                return Err(GrpcConfigError::MissingTlsFile {
+                    name,
+                    path: path.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a unary response of `encoded_len` bytes is sent compressed
+    pub fn compress_response(&self, encoded_len: usize) -> bool {
+        self.send_compressed.is_some()
+            && self
+                .compress_threshold_bytes
+                .map_or(true, |threshold| encoded_len > threshold)
+    }
+}
+
 /// gRPC API configuration.
 #[derive(Debug, Deserialize, Clone)]
 pub struct GrpcApiConfig {
@@ -155,3 +234,24 @@ pub enum ServiceName {
     /// Private service name
     Private,
 }
+
+impl ServiceName {
+    /// Max number of concurrent streams per connection used if none is configured.
+    ///
+    /// The public service is bounded so that a single connection can't open unlimited streams,
+    /// the private one is left unbounded.
+    pub fn default_max_concurrent_streams(&self) -> Option<u32> {
+        match self {
+            ServiceName::Public => Some(DEFAULT_PUBLIC_MAX_CONCURRENT_STREAMS),
+            ServiceName::Private => None,
+        }
+    }
+
+    /// Prefix of the metrics of the service, so that both services can run in the same process
+    pub fn metrics_namespace(&self) -> &'static str {
+        match self {
+            ServiceName::Public => "grpc_public",
+            ServiceName::Private => "grpc_private",
+        }
+    }
+}
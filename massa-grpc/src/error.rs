@@ -1,11 +1,12 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
 use std::error::Error;
+use std::path::PathBuf;
 
 use displaydoc::Display;
 
 use massa_consensus_exports::error::ConsensusError;
-use massa_execution_exports::ExecutionError;
+use massa_execution_exports::{ExecutionError, ReadOnlyErrorKind};
 use massa_hash::MassaHashError;
 use massa_models::error::ModelsError;
 use massa_protocol_exports::ProtocolError;
@@ -45,6 +46,27 @@ pub enum GrpcError {
     InvalidArgument(String),
     /// Not implemented error: {0}
     Unimplemented(String),
+    /// Invalid gRPC configuration: {0}
+    InvalidConfig(#[from] GrpcConfigError),
+}
+
+/// Inconsistencies of a gRPC configuration, detected before starting the server.
+#[non_exhaustive]
+#[derive(Display, thiserror::Error, Debug)]
+pub enum GrpcConfigError {
+    /// TLS is enabled but the {name} file does not exist: {path:?}
+    MissingTlsFile {
+        /// configuration field of the missing file
+        name: &'static str,
+        /// configured path
+        path: PathBuf,
+    },
+    /// CORS is enabled but requires `accept_http1` to be true
+    CorsWithoutHttp1,
+    /// `certificate_reload_interval` must not be zero
+    ZeroCertificateReloadInterval,
+    /// `{0}` is not a method whose responses can be cached
+    UncacheableMethod(String),
 }
 
 impl From<GrpcError> for tonic::Status {
@@ -54,7 +76,17 @@ impl From<GrpcError> for tonic::Status {
             GrpcError::MassaHashError(e) => tonic::Status::internal(e.to_string()),
             GrpcError::MassaSignatureError(e) => tonic::Status::internal(e.to_string()),
             GrpcError::ConsensusError(e) => tonic::Status::internal(e.to_string()),
-            GrpcError::ExecutionError(e) => tonic::Status::internal(e.to_string()),
+            GrpcError::ExecutionError(e) => match e.readonly_error_kind() {
+                Some(ReadOnlyErrorKind::OutOfGas) => {
+                    tonic::Status::resource_exhausted(e.to_string())
+                }
+                Some(ReadOnlyErrorKind::TargetNotFound) => tonic::Status::not_found(e.to_string()),
+                Some(ReadOnlyErrorKind::BytecodeTrap) => tonic::Status::aborted(e.to_string()),
+                Some(ReadOnlyErrorKind::DatastoreAccessDenied) => {
+                    tonic::Status::permission_denied(e.to_string())
+                }
+                None => tonic::Status::internal(e.to_string()),
+            },
             GrpcError::ProtocolError(e) => tonic::Status::internal(e.to_string()),
             GrpcError::ModelsError(e) => tonic::Status::internal(e.to_string()),
             GrpcError::TimeError(e) => tonic::Status::internal(e.to_string()),
@@ -64,6 +96,7 @@ impl From<GrpcError> for tonic::Status {
             GrpcError::ReflectionError(e) => tonic::Status::internal(e.to_string()),
             GrpcError::InvalidArgument(e) => tonic::Status::invalid_argument(e),
             GrpcError::Unimplemented(e) => tonic::Status::unimplemented(e),
+            GrpcError::InvalidConfig(e) => tonic::Status::internal(e.to_string()),
         }
     }
 }
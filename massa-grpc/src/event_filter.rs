@@ -0,0 +1,55 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Conversion of the smart contract execution events filters received by the gRPC API.
+
+use crate::config::GrpcConfig;
+use crate::error::GrpcError;
+use crate::SlotRange;
+use massa_execution_exports::mapping_grpc::to_event_filter;
+use massa_models::execution::EventFilter;
+use massa_proto_rs::massa::api::v1 as grpc_api;
+
+/// Smart contract execution events filters of a request, along with the limit they must respect
+#[derive(Clone, Debug)]
+pub struct ScExecutionEventsFilters {
+    /// filters of the request
+    pub filters: Vec<grpc_api::ScExecutionEventsFilter>,
+    /// maximum number of filters accepted
+    pub max_filters: u32,
+}
+
+impl ScExecutionEventsFilters {
+    /// Wrap the filters of a request with the limits of the gRPC configuration
+    pub fn new(filters: Vec<grpc_api::ScExecutionEventsFilter>, config: &GrpcConfig) -> Self {
+        ScExecutionEventsFilters {
+            filters,
+            max_filters: config.max_filters_per_request,
+        }
+    }
+}
+
+impl TryFrom<ScExecutionEventsFilters> for EventFilter {
+    type Error = GrpcError;
+
+    fn try_from(value: ScExecutionEventsFilters) -> Result<Self, Self::Error> {
+        if value.filters.len() as u32 > value.max_filters {
+            return Err(GrpcError::InvalidArgument(format!(
+                "too many filters received. Only a maximum of {} filters are accepted per request",
+                value.max_filters
+            )));
+        }
+
+        let event_filter = to_event_filter(value.filters)
+            .map_err(|e| GrpcError::InvalidArgument(e.to_string()))?;
+        // the end slot is excluded, so an empty range is rejected as well
+        if event_filter.start.is_some() || event_filter.end.is_some() {
+            SlotRange {
+                start_slot: event_filter.start,
+                end_slot: event_filter.end,
+            }
+            .check()?;
+        }
+
+        Ok(event_filter)
+    }
+}
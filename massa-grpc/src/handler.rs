@@ -14,7 +14,7 @@ use crate::public::{
     execute_read_only_call, get_blocks, get_datastore_entries, get_endorsements,
     get_next_block_best_parents, get_operations, get_sc_execution_events, get_selector_draws,
     get_stakers, get_status, get_transactions_throughput, query_state, search_blocks,
-    search_endorsements, search_operations,
+    search_endorsements, search_operations, set_status_times,
 };
 
 #[cfg(feature = "execution-trace")]
@@ -22,6 +22,7 @@ use crate::public::{get_operation_abi_call_stacks, get_slot_abi_call_stacks, get
 #[cfg(feature = "execution-trace")]
 use crate::stream::new_slot_transfers::new_slot_transfers;
 
+use crate::config::GrpcConfig;
 use crate::server::{MassaPrivateGrpc, MassaPublicGrpc};
 use crate::stream::{
     new_blocks::{new_blocks, NewBlocksStreamType},
@@ -37,6 +38,16 @@ use crate::stream::{
     tx_throughput::{transactions_throughput, TransactionsThroughputStreamType},
 };
 
+/// Wrap the message of a unary response, left uncompressed if it is not larger than `compress_threshold_bytes`
+fn unary_response<T: prost::Message>(config: &GrpcConfig, message: T) -> tonic::Response<T> {
+    let compress = config.compress_response(message.encoded_len());
+    let mut response = tonic::Response::new(message);
+    if !compress {
+        response.disable_compression();
+    }
+    response
+}
+
 #[tonic::async_trait]
 impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
     /// Execute read only call
@@ -45,7 +56,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         request: tonic::Request<grpc_api::ExecuteReadOnlyCallRequest>,
     ) -> std::result::Result<tonic::Response<grpc_api::ExecuteReadOnlyCallResponse>, tonic::Status>
     {
-        Ok(tonic::Response::new(execute_read_only_call(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            execute_read_only_call(self, request)?,
+        ))
     }
 
     #[cfg(feature = "execution-trace")]
@@ -56,9 +70,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         tonic::Response<grpc_api::GetOperationAbiCallStacksResponse>,
         tonic::Status,
     > {
-        Ok(tonic::Response::new(get_operation_abi_call_stacks(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_operation_abi_call_stacks(self, request)?,
+        ))
     }
 
     #[cfg(not(feature = "execution-trace"))]
@@ -78,9 +93,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         request: tonic::Request<grpc_api::GetSlotAbiCallStacksRequest>,
     ) -> std::result::Result<tonic::Response<grpc_api::GetSlotAbiCallStacksResponse>, tonic::Status>
     {
-        Ok(tonic::Response::new(get_slot_abi_call_stacks(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_slot_abi_call_stacks(self, request)?,
+        ))
     }
 
     #[cfg(not(feature = "execution-trace"))]
@@ -98,7 +114,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         request: tonic::Request<grpc_api::GetSlotTransfersRequest>,
     ) -> std::result::Result<tonic::Response<grpc_api::GetSlotTransfersResponse>, tonic::Status>
     {
-        Ok(tonic::Response::new(get_slot_transfers(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_slot_transfers(self, request)?,
+        ))
     }
 
     #[cfg(not(feature = "execution-trace"))]
@@ -115,7 +134,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::GetBlocksRequest>,
     ) -> Result<tonic::Response<grpc_api::GetBlocksResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_blocks(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_blocks(self, request)?,
+        ))
     }
 
     /// handler for get multiple datastore entries
@@ -123,7 +145,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::GetDatastoreEntriesRequest>,
     ) -> Result<tonic::Response<grpc_api::GetDatastoreEntriesResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_datastore_entries(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_datastore_entries(self, request)?,
+        ))
     }
 
     /// handler for get endorsements
@@ -131,7 +156,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::GetEndorsementsRequest>,
     ) -> Result<tonic::Response<grpc_api::GetEndorsementsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_endorsements(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_endorsements(self, request)?,
+        ))
     }
 
     /// handler for get largest stakers
@@ -139,7 +167,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::GetStakersRequest>,
     ) -> Result<tonic::Response<grpc_api::GetStakersResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_stakers(self, request)?))
+        let response = self
+            .response_cache
+            .get_or_compute("get_stakers", request, |request| get_stakers(self, request))?;
+        Ok(unary_response(&self.grpc_config, response))
     }
 
     /// handler for get satatus
@@ -147,7 +178,14 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::GetStatusRequest>,
     ) -> Result<tonic::Response<grpc_api::GetStatusResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_status(self, request)?))
+        let mut response =
+            self.response_cache
+                .get_or_compute("get_status", request, |request| get_status(self, request))?;
+        // the times are never served from the cache
+        if let Some(status) = response.status.as_mut() {
+            set_status_times(self, status)?;
+        }
+        Ok(unary_response(&self.grpc_config, response))
     }
 
     /// handler for get next block best parents
@@ -155,9 +193,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::GetNextBlockBestParentsRequest>,
     ) -> Result<tonic::Response<grpc_api::GetNextBlockBestParentsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_next_block_best_parents(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_next_block_best_parents(self, request)?,
+        ))
     }
 
     /// handler for get operations
@@ -165,7 +204,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::GetOperationsRequest>,
     ) -> Result<tonic::Response<grpc_api::GetOperationsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_operations(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_operations(self, request)?,
+        ))
     }
 
     /// handler for get smart contract execution events
@@ -173,9 +215,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::GetScExecutionEventsRequest>,
     ) -> Result<tonic::Response<grpc_api::GetScExecutionEventsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_sc_execution_events(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_sc_execution_events(self, request)?,
+        ))
     }
 
     /// handler for get selector draws
@@ -183,7 +226,12 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::GetSelectorDrawsRequest>,
     ) -> Result<tonic::Response<grpc_api::GetSelectorDrawsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_selector_draws(self, request)?))
+        let response =
+            self.response_cache
+                .get_or_compute("get_selector_draws", request, |request| {
+                    get_selector_draws(self, request)
+                })?;
+        Ok(unary_response(&self.grpc_config, response))
     }
 
     /// handler for get transactions throughput
@@ -191,9 +239,12 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::GetTransactionsThroughputRequest>,
     ) -> Result<tonic::Response<grpc_api::GetTransactionsThroughputResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_transactions_throughput(
-            self, request,
-        )?))
+        let response = self.response_cache.get_or_compute(
+            "get_transactions_throughput",
+            request,
+            |request| get_transactions_throughput(self, request),
+        )?;
+        Ok(unary_response(&self.grpc_config, response))
     }
 
     /// handler for get version
@@ -201,7 +252,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::QueryStateRequest>,
     ) -> Result<tonic::Response<grpc_api::QueryStateResponse>, tonic::Status> {
-        Ok(tonic::Response::new(query_state(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            query_state(self, request)?,
+        ))
     }
 
     /// handler for search blocks
@@ -209,7 +263,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::SearchBlocksRequest>,
     ) -> Result<tonic::Response<grpc_api::SearchBlocksResponse>, tonic::Status> {
-        Ok(tonic::Response::new(search_blocks(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            search_blocks(self, request)?,
+        ))
     }
 
     /// handler for search endorsemets
@@ -217,7 +274,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::SearchEndorsementsRequest>,
     ) -> Result<tonic::Response<grpc_api::SearchEndorsementsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(search_endorsements(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            search_endorsements(self, request)?,
+        ))
     }
 
     /// handler for search operations
@@ -225,7 +285,10 @@ impl grpc_api::public_service_server::PublicService for MassaPublicGrpc {
         &self,
         request: tonic::Request<grpc_api::SearchOperationsRequest>,
     ) -> Result<tonic::Response<grpc_api::SearchOperationsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(search_operations(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            search_operations(self, request)?,
+        ))
     }
 
     // ███████╗████████╗██████╗ ███████╗ █████╗ ███╗   ███╗
@@ -379,18 +442,20 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
         &self,
         request: tonic::Request<grpc_api::AddToBootstrapBlacklistRequest>,
     ) -> Result<tonic::Response<grpc_api::AddToBootstrapBlacklistResponse>, tonic::Status> {
-        Ok(tonic::Response::new(add_to_bootstrap_blacklist(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            add_to_bootstrap_blacklist(self, request)?,
+        ))
     }
     /// Add IP addresses to node bootstrap whitelist
     async fn add_to_bootstrap_whitelist(
         &self,
         request: tonic::Request<grpc_api::AddToBootstrapWhitelistRequest>,
     ) -> Result<tonic::Response<grpc_api::AddToBootstrapWhitelistResponse>, tonic::Status> {
-        Ok(tonic::Response::new(add_to_bootstrap_whitelist(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            add_to_bootstrap_whitelist(self, request)?,
+        ))
     }
     /// Add IP addresses to node peers whitelist. No confirmation to expect.
     /// Note: If the ip was unknown it adds it to the known peers, otherwise it updates the peer type
@@ -398,23 +463,30 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
         &self,
         request: tonic::Request<grpc_api::AddToPeersWhitelistRequest>,
     ) -> Result<tonic::Response<grpc_api::AddToPeersWhitelistResponse>, tonic::Status> {
-        Ok(tonic::Response::new(add_to_peers_whitelist(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            add_to_peers_whitelist(self, request)?,
+        ))
     }
     /// Add staking secret keys to wallet
     async fn add_staking_secret_keys(
         &self,
         request: tonic::Request<grpc_api::AddStakingSecretKeysRequest>,
     ) -> Result<tonic::Response<grpc_api::AddStakingSecretKeysResponse>, tonic::Status> {
-        Ok(tonic::Response::new(add_staking_secret_keys(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            add_staking_secret_keys(self, request)?,
+        ))
     }
     /// Ban multiple nodes by their individual ids
     async fn ban_nodes_by_ids(
         &self,
         request: tonic::Request<grpc_api::BanNodesByIdsRequest>,
     ) -> Result<tonic::Response<grpc_api::BanNodesByIdsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(ban_nodes_by_ids(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            ban_nodes_by_ids(self, request)?,
+        ))
     }
 
     /// Ban multiple nodes by their individual IP addresses
@@ -422,7 +494,10 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
         &self,
         request: tonic::Request<grpc_api::BanNodesByIpsRequest>,
     ) -> Result<tonic::Response<grpc_api::BanNodesByIpsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(ban_nodes_by_ips(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            ban_nodes_by_ips(self, request)?,
+        ))
     }
 
     /// Get node bootstrap blacklist IP addresses
@@ -430,48 +505,60 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
         &self,
         request: tonic::Request<grpc_api::GetBootstrapBlacklistRequest>,
     ) -> Result<tonic::Response<grpc_api::GetBootstrapBlacklistResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_bootstrap_blacklist(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_bootstrap_blacklist(self, request)?,
+        ))
     }
     /// Get node bootstrap whitelist IP addresses
     async fn get_bootstrap_whitelist(
         &self,
         request: tonic::Request<grpc_api::GetBootstrapWhitelistRequest>,
     ) -> Result<tonic::Response<grpc_api::GetBootstrapWhitelistResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_bootstrap_whitelist(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_bootstrap_whitelist(self, request)?,
+        ))
     }
     /// handler for get mip status (versioning)
     async fn get_mip_status(
         &self,
         request: tonic::Request<grpc_api::GetMipStatusRequest>,
     ) -> Result<tonic::Response<grpc_api::GetMipStatusResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_mip_status(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_mip_status(self, request)?,
+        ))
     }
     /// Allow everyone to bootstrap from the node by removing bootstrap whitelist configuration file
     async fn allow_everyone_to_bootstrap(
         &self,
         request: tonic::Request<grpc_api::AllowEveryoneToBootstrapRequest>,
     ) -> Result<tonic::Response<grpc_api::AllowEveryoneToBootstrapResponse>, tonic::Status> {
-        Ok(tonic::Response::new(allow_everyone_to_bootstrap(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            allow_everyone_to_bootstrap(self, request)?,
+        ))
     }
     /// Get node status
     async fn get_node_status(
         &self,
         request: tonic::Request<grpc_api::GetNodeStatusRequest>,
     ) -> Result<tonic::Response<grpc_api::GetNodeStatusResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_node_status(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_node_status(self, request)?,
+        ))
     }
     /// Get node peers whitelist IP addresses
     async fn get_peers_whitelist(
         &self,
         request: tonic::Request<grpc_api::GetPeersWhitelistRequest>,
     ) -> Result<tonic::Response<grpc_api::GetPeersWhitelistResponse>, tonic::Status> {
-        Ok(tonic::Response::new(get_peers_whitelist(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            get_peers_whitelist(self, request)?,
+        ))
     }
     /// Remove from bootstrap blacklist given IP addresses
     async fn remove_from_bootstrap_blacklist(
@@ -479,9 +566,10 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
         request: tonic::Request<grpc_api::RemoveFromBootstrapBlacklistRequest>,
     ) -> Result<tonic::Response<grpc_api::RemoveFromBootstrapBlacklistResponse>, tonic::Status>
     {
-        Ok(tonic::Response::new(remove_from_bootstrap_blacklist(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            remove_from_bootstrap_blacklist(self, request)?,
+        ))
     }
     /// Remove from bootstrap whitelist given IP addresses
     async fn remove_from_bootstrap_whitelist(
@@ -489,41 +577,50 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
         request: tonic::Request<grpc_api::RemoveFromBootstrapWhitelistRequest>,
     ) -> Result<tonic::Response<grpc_api::RemoveFromBootstrapWhitelistResponse>, tonic::Status>
     {
-        Ok(tonic::Response::new(remove_from_bootstrap_whitelist(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            remove_from_bootstrap_whitelist(self, request)?,
+        ))
     }
     /// Remove from peers whitelist given IP addresses
     async fn remove_from_peers_whitelist(
         &self,
         request: tonic::Request<grpc_api::RemoveFromPeersWhitelistRequest>,
     ) -> Result<tonic::Response<grpc_api::RemoveFromPeersWhitelistResponse>, tonic::Status> {
-        Ok(tonic::Response::new(remove_from_peers_whitelist(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            remove_from_peers_whitelist(self, request)?,
+        ))
     }
     /// Remove addresses from staking
     async fn remove_staking_addresses(
         &self,
         request: tonic::Request<grpc_api::RemoveStakingAddressesRequest>,
     ) -> Result<tonic::Response<grpc_api::RemoveStakingAddressesResponse>, tonic::Status> {
-        Ok(tonic::Response::new(remove_staking_addresses(
-            self, request,
-        )?))
+        Ok(unary_response(
+            &self.grpc_config,
+            remove_staking_addresses(self, request)?,
+        ))
     }
     /// Sign messages with node's key
     async fn sign_messages(
         &self,
         request: tonic::Request<grpc_api::SignMessagesRequest>,
     ) -> Result<tonic::Response<grpc_api::SignMessagesResponse>, tonic::Status> {
-        Ok(tonic::Response::new(sign_messages(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            sign_messages(self, request)?,
+        ))
     }
     /// Shutdown the node gracefully
     async fn shutdown_gracefully(
         &self,
         request: tonic::Request<grpc_api::ShutdownGracefullyRequest>,
     ) -> Result<tonic::Response<grpc_api::ShutdownGracefullyResponse>, tonic::Status> {
-        Ok(tonic::Response::new(shutdown_gracefully(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            shutdown_gracefully(self, request)?,
+        ))
     }
 
     /// Unban multiple nodes by their individual ids
@@ -531,7 +628,10 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
         &self,
         request: tonic::Request<grpc_api::UnbanNodesByIdsRequest>,
     ) -> Result<tonic::Response<grpc_api::UnbanNodesByIdsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(unban_nodes_by_ids(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            unban_nodes_by_ids(self, request)?,
+        ))
     }
 
     /// Unban multiple nodes by their individual IP addresses
@@ -539,6 +639,9 @@ impl grpc_api::private_service_server::PrivateService for MassaPrivateGrpc {
         &self,
         request: tonic::Request<grpc_api::UnbanNodesByIpsRequest>,
     ) -> Result<tonic::Response<grpc_api::UnbanNodesByIpsResponse>, tonic::Status> {
-        Ok(tonic::Response::new(unban_nodes_by_ips(self, request)?))
+        Ok(unary_response(
+            &self.grpc_config,
+            unban_nodes_by_ips(self, request)?,
+        ))
     }
 }
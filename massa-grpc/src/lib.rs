@@ -26,20 +26,38 @@ use tonic_web as _;
 #[cfg(feature = "execution-trace")]
 use serde_json as _;
 
+// used by the benchmarks
+#[cfg(test)]
+use criterion as _;
+#[cfg(test)]
+use flate2 as _;
+
+/// cache of the responses of idempotent queries
+pub mod cache;
 /// gRPC configuration
 pub mod config;
 /// models error
 pub mod error;
+/// conversion of the execution events filters
+pub mod event_filter;
 /// gRPC API implementation
 pub mod handler;
+/// request metrics of the services
+pub mod metrics;
 /// business code for node management methods
 pub mod private;
 /// business code for non stream methods
 pub mod public;
+/// correlation id of the requests
+pub mod request_id;
 /// gRPC service initialization and serve
 pub mod server;
 /// business code for stream methods
 pub mod stream;
+/// TLS termination with hot reload of the certificates
+pub mod tls;
+/// status of the certificate served over TLS
+pub mod tls_status;
 
 #[cfg(test)]
 /// gRPC tests
@@ -0,0 +1,98 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Request metrics of the gRPC services.
+//!
+//! Each service records its requests under its own namespace (see
+//! [`ServiceName::metrics_namespace`](crate::config::ServiceName::metrics_namespace)), labelled by
+//! method. The duration of a stream only covers its opening, not the messages sent afterwards.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use hyper::{Request, Response};
+use massa_metrics::GrpcServiceMetrics;
+use tower::{Layer, Service};
+
+/// label of the requests to a method the service doesn't implement, so that the paths sent by
+/// clients don't create new series
+const UNKNOWN_METHOD: &str = "unknown";
+
+/// `grpc-status` of the requests to a method the service doesn't implement
+const UNIMPLEMENTED_STATUS: &str = "12";
+
+/// Layer recording the metrics of every request
+#[derive(Clone)]
+pub(crate) struct MetricsLayer {
+    metrics: GrpcServiceMetrics,
+}
+
+impl MetricsLayer {
+    /// Record the requests under the metrics of `namespace`
+    pub(crate) fn new(namespace: &str) -> Self {
+        MetricsLayer {
+            metrics: GrpcServiceMetrics::new(namespace),
+        }
+    }
+}
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MetricsService {
+            inner,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+/// Service recording the metrics of the requests it handles
+#[derive(Clone)]
+pub(crate) struct MetricsService<S> {
+    inner: S,
+    metrics: GrpcServiceMetrics,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for MetricsService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<ReqBody>) -> Self::Future {
+        // the path of a gRPC request is `/{package}.{service}/{method}`
+        let method = request
+            .uri()
+            .path()
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let metrics = self.metrics.clone();
+        let start = Instant::now();
+        let response = self.inner.call(request);
+        Box::pin(async move {
+            let response = response.await?;
+            let implemented = response
+                .headers()
+                .get("grpc-status")
+                .map_or(true, |status| {
+                    status.as_bytes() != UNIMPLEMENTED_STATUS.as_bytes()
+                });
+            metrics.observe_request(
+                if implemented { &method } else { UNKNOWN_METHOD },
+                start.elapsed(),
+            );
+            Ok(response)
+        })
+    }
+}
@@ -1,13 +1,12 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
 use crate::error::GrpcError;
+use crate::event_filter::ScExecutionEventsFilters;
 use crate::server::MassaPublicGrpc;
 use crate::{EndorsementDraw, SlotDraw, SlotRange};
 
 use itertools::{izip, Itertools};
-use massa_execution_exports::mapping_grpc::{
-    to_event_filter, to_execution_query_response, to_querystate_filter,
-};
+use massa_execution_exports::mapping_grpc::{to_execution_query_response, to_querystate_filter};
 use massa_execution_exports::{
     ExecutionQueryRequest, ExecutionStackElement, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
 };
@@ -18,16 +17,18 @@ use massa_models::block_id::BlockId;
 use massa_models::config::CompactConfig;
 use massa_models::datastore::DatastoreDeserializer;
 use massa_models::endorsement::{EndorsementId, SecureShareEndorsement};
+use massa_models::execution::EventFilter;
 use massa_models::operation::{OperationId, SecureShareOperation};
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_latest_block_slot_at_timestamp;
 use massa_proto_rs::massa::api::v1::{self as grpc_api};
 use massa_proto_rs::massa::model::v1::{self as grpc_model, read_only_execution_call};
-use massa_serialization::{DeserializeError, Deserializer};
+use massa_serialization::{DeserializeError, Deserializer, U64VarIntDeserializer};
 use massa_time::MassaTime;
 use massa_versioning::versioning_factory::{FactoryStrategy, VersioningFactory};
 use std::collections::HashSet;
+use std::ops::Bound::Included;
 use std::str::FromStr;
 
 #[cfg(feature = "execution-trace")]
@@ -68,6 +69,18 @@ pub(crate) fn execute_read_only_call(
                 let op_datastore = if value.operation_datastore.is_empty() {
                     None
                 } else {
+                    // check the number of entries first, so that the limit is reported as is
+                    if let Ok((_, entry_count)) =
+                        U64VarIntDeserializer::new(Included(0), Included(u64::MAX))
+                            .deserialize::<DeserializeError>(&value.operation_datastore)
+                    {
+                        if entry_count > grpc.grpc_config.max_arguments {
+                            return Err(GrpcError::InvalidArgument(format!(
+                                "too many datastore entries received. Only a maximum of {} datastore entries are accepted per read-only call",
+                                grpc.grpc_config.max_arguments
+                            )));
+                        }
+                    }
                     let deserializer = DatastoreDeserializer::new(
                         grpc.grpc_config.max_op_datastore_entry_count,
                         grpc.grpc_config.max_op_datastore_key_length,
@@ -141,6 +154,11 @@ pub(crate) fn execute_read_only_call(
                     .map_err(|_| GrpcError::InvalidArgument("invalid amount".to_string()))
             })
             .transpose()?,
+        // the gRPC read-only call has no field to report a gas profile, to seed the RNG
+        // or to trace the calls
+        profile: false,
+        rng_seed: None,
+        include_trace: false,
     };
 
     if read_only_call
@@ -708,6 +726,9 @@ pub(crate) fn get_next_block_best_parents(
 }
 
 /// Get operations
+///
+/// The ids of operations missing from storage (never seen or already pruned) are omitted from the
+/// response instead of failing the request. The other operations are returned in the requested order.
 pub(crate) fn get_operations(
     grpc: &MassaPublicGrpc,
     request: tonic::Request<grpc_api::GetOperationsRequest>,
@@ -782,7 +803,10 @@ pub(crate) fn get_sc_execution_events(
     grpc: &MassaPublicGrpc,
     request: tonic::Request<grpc_api::GetScExecutionEventsRequest>,
 ) -> Result<grpc_api::GetScExecutionEventsResponse, GrpcError> {
-    let event_filter = to_event_filter(request.into_inner().filters)?;
+    let event_filter = EventFilter::try_from(ScExecutionEventsFilters::new(
+        request.into_inner().filters,
+        &grpc.grpc_config,
+    ))?;
     let events: Vec<grpc_model::ScExecutionEvent> = grpc
         .execution_controller
         .get_filtered_sc_output_event(event_filter)
@@ -916,6 +940,37 @@ pub(crate) fn get_status(
     _request: tonic::Request<grpc_api::GetStatusRequest>,
 ) -> Result<grpc_api::GetStatusResponse, GrpcError> {
     let config = CompactConfig::default();
+    //TODO to be enhanced
+    let empty_request = ExecutionQueryRequest { requests: vec![] };
+    let state = grpc.execution_controller.query_state(empty_request);
+
+    let mut status = grpc_model::PublicStatus {
+        node_id: grpc.node_id.to_string(),
+        version: grpc.version.to_string(),
+        current_time: None,
+        current_cycle: 0,
+        current_cycle_time: None,
+        next_cycle_time: None,
+        last_executed_final_slot: Some(state.final_cursor.into()),
+        last_executed_speculative_slot: Some(state.candidate_cursor.into()),
+        final_state_fingerprint: state.final_state_fingerprint.to_string(),
+        config: Some(config.into()),
+        chain_id: grpc.grpc_config.chain_id,
+        minimal_fees: Some(grpc.grpc_config.minimal_fees.into()),
+    };
+    set_status_times(grpc, &mut status)?;
+
+    Ok(grpc_api::GetStatusResponse {
+        status: Some(status),
+    })
+}
+
+/// Set the fields of a status depending on the current time.
+/// They are set again on each call, even when the status is served from the response cache.
+pub(crate) fn set_status_times(
+    grpc: &MassaPublicGrpc,
+    status: &mut grpc_model::PublicStatus,
+) -> Result<(), GrpcError> {
     let now = MassaTime::now();
     let last_slot = get_latest_block_slot_at_timestamp(
         grpc.grpc_config.thread_count,
@@ -943,28 +998,12 @@ pub(crate) fn get_status(
             })?
     };
     let next_cycle_time = current_cycle_time.checked_add(cycle_duration)?;
-    //TODO to be enhanced
-    let empty_request = ExecutionQueryRequest { requests: vec![] };
-    let state = grpc.execution_controller.query_state(empty_request);
 
-    let status = grpc_model::PublicStatus {
-        node_id: grpc.node_id.to_string(),
-        version: grpc.version.to_string(),
-        current_time: Some(now.into()),
-        current_cycle,
-        current_cycle_time: Some(current_cycle_time.into()),
-        next_cycle_time: Some(next_cycle_time.into()),
-        last_executed_final_slot: Some(state.final_cursor.into()),
-        last_executed_speculative_slot: Some(state.candidate_cursor.into()),
-        final_state_fingerprint: state.final_state_fingerprint.to_string(),
-        config: Some(config.into()),
-        chain_id: grpc.grpc_config.chain_id,
-        minimal_fees: Some(grpc.grpc_config.minimal_fees.into()),
-    };
-
-    Ok(grpc_api::GetStatusResponse {
-        status: Some(status),
-    })
+    status.current_time = Some(now.into());
+    status.current_cycle = current_cycle;
+    status.current_cycle_time = Some(current_cycle_time.into());
+    status.next_cycle_time = Some(next_cycle_time.into());
+    Ok(())
 }
 
 /// Get transactions throughput
@@ -1118,9 +1157,7 @@ pub(crate) fn search_blocks(
             let read_lock = grpc.storage.read_blocks();
             let mut b_ids: PreHashSet<BlockId> = PreHashSet::default();
             for addr in addrs {
-                if let Some(addr_b_ids) = read_lock.get_blocks_created_by(&addr) {
-                    b_ids.extend(addr_b_ids.clone());
-                }
+                b_ids.extend(read_lock.get_blocks_created_by(&addr, None, usize::MAX));
             }
 
             b_ids
@@ -0,0 +1,104 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Correlation id of the gRPC requests.
+//!
+//! Every request is handled within a `grpc_request` tracing span carrying its id, taken from
+//! the `x-request-id` header if the client sent a valid one and generated otherwise.
+//! The id is also forwarded to the handler in the request metadata and echoed back in the
+//! response headers.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::{Request, Response};
+use massa_time::MassaTime;
+use tower::{Layer, Service};
+use tracing::Instrument;
+
+/// header carrying the correlation id of a request
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// max length of a correlation id provided by a client
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+/// number of ids generated so far, to keep them unique within a millisecond
+static GENERATED_ID_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// Layer attaching a correlation id to every request
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestIdLayer;
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestIdService { inner }
+    }
+}
+
+/// Service handling a request within a span carrying its correlation id
+#[derive(Debug, Clone)]
+pub(crate) struct RequestIdService<S> {
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for RequestIdService<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<ReqBody>) -> Self::Future {
+        let request_id = request
+            .headers()
+            .get(&REQUEST_ID_HEADER)
+            .filter(|value| is_valid_request_id(value))
+            .cloned()
+            .unwrap_or_else(generate_request_id);
+        request
+            .headers_mut()
+            .insert(REQUEST_ID_HEADER, request_id.clone());
+
+        let span = tracing::info_span!(
+            "grpc_request",
+            request_id = request_id.to_str().unwrap_or_default(),
+            method = request.uri().path()
+        );
+        let response = span.in_scope(|| self.inner.call(request));
+        Box::pin(
+            async move {
+                let mut response = response.await?;
+                response.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Whether a client provided id can be logged and echoed as is
+fn is_valid_request_id(value: &HeaderValue) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LENGTH
+        && value
+            .as_bytes()
+            .iter()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"-_.:".contains(byte))
+}
+
+/// Generate an id unique within this node
+fn generate_request_id() -> HeaderValue {
+    let count = GENERATED_ID_COUNT.fetch_add(1, Ordering::Relaxed);
+    HeaderValue::from_str(&format!("{:x}-{:x}", MassaTime::now().as_millis(), count))
+        .expect("generated request id is a valid header value")
+}
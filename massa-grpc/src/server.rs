@@ -9,8 +9,17 @@ use std::convert::Infallible;
 use std::path::Path;
 use std::sync::{Arc, Condvar, Mutex};
 
+use crate::cache::ResponseCache;
 use crate::config::{GrpcConfig, ServiceName};
 use crate::error::GrpcError;
+use crate::metrics::MetricsLayer;
+use crate::request_id::{RequestIdLayer, REQUEST_ID_HEADER};
+use crate::stream::new_slot_execution_digests::SlotExecutionDigestServiceServer;
+use crate::tls::{
+    load_certificate_info, spawn_certificate_reload, tls_incoming, CertificateInfo, ReloadableTls,
+    ServedCertificate,
+};
+use crate::tls_status::TlsStatusServiceServer;
 use futures_util::FutureExt;
 use hyper::service::Service;
 use hyper::{Body, Method, Request, Response};
@@ -87,7 +96,7 @@ impl MassaPrivateGrpc {
             };
         }
 
-        serve(service, config).await
+        serve(service, None, config).await
     }
 }
 
@@ -122,11 +131,26 @@ pub struct MassaPublicGrpc {
     pub version: massa_models::version::Version,
     /// keypair factory
     pub keypair_factory: KeyPairFactory,
+    /// cache of the responses of idempotent queries
+    pub response_cache: ResponseCache,
 }
 
 impl MassaPublicGrpc {
     /// Start the gRPC PUBLIC API
     pub async fn serve(self, config: &GrpcConfig) -> Result<StopHandle, GrpcError> {
+        if self.response_cache.is_enabled() {
+            self.response_cache.spawn_invalidation(
+                self.execution_channels
+                    .slot_execution_output_sender
+                    .subscribe(),
+            );
+        }
+
+        // the slot execution digests have no message in the public API definitions, they are
+        // streamed by a service of their own
+        let digest_service =
+            SlotExecutionDigestServiceServer::new(self.execution_channels.clone(), config.clone());
+
         let mut service = PublicServiceServer::new(self)
             .max_decoding_message_size(config.max_decoding_message_size)
             .max_encoding_message_size(config.max_encoding_message_size);
@@ -142,16 +166,25 @@ impl MassaPublicGrpc {
                 service = service.send_compressed(CompressionEncoding::Gzip);
             };
         }
-        serve(service, config).await
+        serve(service, Some(digest_service), config).await
     }
 }
 
 /// Used to be able to stop the gRPC API
 pub struct StopHandle {
     stop_cmd_sender: oneshot::Sender<()>,
+    /// certificate served to the new connections, if TLS is enabled
+    served_certificate: Option<ServedCertificate>,
 }
 
 impl StopHandle {
+    /// Certificate used for the new connections, if TLS is enabled
+    pub fn tls_certificate(&self) -> Option<CertificateInfo> {
+        self.served_certificate
+            .as_ref()
+            .map(|certificate| certificate.info())
+    }
+
     /// stop the gRPC API gracefully
     pub fn stop(self) {
         if let Err(e) = self.stop_cmd_sender.send(()) {
@@ -170,8 +203,12 @@ async fn massa_service_status(mut reporter: HealthReporter) {
         .await;
 }
 
-// Configure and start the gRPC API with the given service
-async fn serve<S>(service: S, config: &GrpcConfig) -> Result<StopHandle, GrpcError>
+// Configure and start the gRPC API with the given service, and the digests service if any
+async fn serve<S>(
+    service: S,
+    digest_service_opt: Option<SlotExecutionDigestServiceServer>,
+    config: &GrpcConfig,
+) -> Result<StopHandle, GrpcError>
 where
     S: Service<Request<Body>, Response = Response<BoxBody>, Error = Infallible>
         + NamedService
@@ -180,6 +217,8 @@ where
         + 'static,
     S::Future: Send + 'static,
 {
+    config.validate()?;
+
     let (shutdown_send, shutdown_recv) = oneshot::channel::<()>();
 
     let mut server_builder = tonic::transport::Server::builder()
@@ -195,6 +234,8 @@ where
         .http2_adaptive_window(config.http2_adaptive_window)
         .max_frame_size(config.max_frame_size);
 
+    let mut reloadable_tls = None;
+    let mut served_certificate = None;
     if config.enable_tls {
         if config.generate_self_signed_certificates {
             if Path::new(&config.certificate_authority_root_path).exists() {
@@ -204,6 +245,30 @@ where
                 generate_self_signed_certificates(config);
             }
         }
+    }
+
+    if let (true, Some(reload_interval)) = (config.enable_tls, config.certificate_reload_interval) {
+        // TLS is terminated by our own acceptor to be able to swap its certificate
+        let tls = Arc::new(ReloadableTls::load(config)?);
+        let certificate = tls.certificate_info();
+        info!(
+            "gRPC {}TLS enabled with certificate reload every {:?}: fingerprint {}, valid until {}",
+            if config.enable_mtls { "m" } else { "" },
+            reload_interval,
+            certificate.fingerprint,
+            certificate.not_after.format_instant()
+        );
+        spawn_certificate_reload(Arc::downgrade(&tls), reload_interval);
+        served_certificate = Some(ServedCertificate::Reloadable(tls.clone()));
+        reloadable_tls = Some(tls);
+    } else if config.enable_tls {
+        let certificate = load_certificate_info(config)?;
+        info!(
+            "gRPC TLS certificate: fingerprint {}, valid until {}",
+            certificate.fingerprint,
+            certificate.not_after.format_instant()
+        );
+        served_certificate = Some(ServedCertificate::Static(certificate));
 
         let cert = std::fs::read_to_string(config.server_certificate_path.clone())
             .expect("error, failed to read server certificat");
@@ -234,6 +299,29 @@ where
         }
     }
 
+    // every request is handled in a span carrying its correlation id, and recorded in the
+    // metrics of the service
+    let server_builder = server_builder
+        .layer(RequestIdLayer)
+        .layer(MetricsLayer::new(config.name.metrics_namespace()));
+
+    // serve on the configured address, through the reloadable TLS acceptor if any
+    macro_rules! spawn_router {
+        ($router:expr) => {
+            match &reloadable_tls {
+                Some(tls) => {
+                    let incoming = tls_incoming(config, tls.clone())?;
+                    tokio::spawn(
+                        $router.serve_with_incoming_shutdown(incoming, shutdown_recv.map(drop)),
+                    );
+                }
+                None => {
+                    tokio::spawn($router.serve_with_shutdown(config.bind, shutdown_recv.map(drop)));
+                }
+            }
+        };
+    }
+
     let reflection_service_opt = if config.enable_reflection {
         let file_descriptor_set = match config.name {
             ServiceName::Public => FILE_DESCRIPTOR_SET_PUBLIC,
@@ -260,6 +348,9 @@ where
         None
     };
 
+    // the served certificate is described next to the health service
+    let tls_status_service_opt = served_certificate.clone().map(TlsStatusServiceServer::new);
+
     if config.accept_http1 {
         if config.enable_cors {
             let cors = CorsLayer::new()
@@ -267,7 +358,9 @@ where
                 .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
                 // Allow requests from any origin
                 .allow_origin(Any)
-                .allow_headers(Any);
+                .allow_headers(Any)
+                // let browsers read the correlation id of their requests
+                .expose_headers([REQUEST_ID_HEADER]);
 
             let router_with_http1 = server_builder
                 .accept_http1(true)
@@ -275,34 +368,37 @@ where
                 .layer(GrpcWebLayer::new())
                 .add_optional_service(reflection_service_opt)
                 .add_optional_service(health_service_opt)
+                .add_optional_service(digest_service_opt)
+                .add_optional_service(tls_status_service_opt)
                 .add_service(service);
 
-            tokio::spawn(
-                router_with_http1.serve_with_shutdown(config.bind, shutdown_recv.map(drop)),
-            );
+            spawn_router!(router_with_http1);
         } else {
             let router_with_http1 = server_builder
                 .accept_http1(true)
                 .layer(GrpcWebLayer::new())
                 .add_optional_service(reflection_service_opt)
                 .add_optional_service(health_service_opt)
+                .add_optional_service(digest_service_opt)
+                .add_optional_service(tls_status_service_opt)
                 .add_service(service);
 
-            tokio::spawn(
-                router_with_http1.serve_with_shutdown(config.bind, shutdown_recv.map(drop)),
-            );
+            spawn_router!(router_with_http1);
         }
     } else {
         let router = server_builder
             .add_optional_service(reflection_service_opt)
             .add_optional_service(health_service_opt)
+            .add_optional_service(digest_service_opt)
+            .add_optional_service(tls_status_service_opt)
             .add_service(service);
 
-        tokio::spawn(router.serve_with_shutdown(config.bind, shutdown_recv.map(drop)));
+        spawn_router!(router);
     }
 
     Ok(StopHandle {
         stop_cmd_sender: shutdown_send,
+        served_certificate,
     })
 }
 
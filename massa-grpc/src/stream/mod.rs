@@ -10,6 +10,8 @@ pub mod new_filled_blocks;
 pub mod new_operations;
 /// subscribe new slot abi call stacks
 pub mod new_slot_abi_call_stacks;
+/// subscribe new slot execution digests
+pub mod new_slot_execution_digests;
 /// subscribe new slot execution outputs
 pub mod new_slot_execution_outputs;
 /// subscribe new slot transfers
@@ -20,5 +22,7 @@ pub mod send_blocks;
 pub mod send_endorsements;
 /// send operations
 pub mod send_operations;
+/// sender of the stream messages, dropping the stalled clients
+pub(crate) mod sender;
 /// subscribe tx througput
 pub mod tx_throughput;
@@ -3,6 +3,7 @@
 use crate::config::GrpcConfig;
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
+use crate::stream::sender::stream_channel;
 use crate::SlotRange;
 use futures_util::StreamExt;
 use massa_models::address::Address;
@@ -45,7 +46,7 @@ pub(crate) async fn new_filled_blocks(
     request: Request<Streaming<grpc_api::NewFilledBlocksRequest>>,
 ) -> Result<NewFilledBlocksStreamType, GrpcError> {
     // Create a channel to handle communication with the client
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(&grpc.grpc_config, request.remote_addr());
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
     // Subscribe to the new filled blocks channel
@@ -6,6 +6,7 @@ use tonic::{Request, Streaming};
 use tracing::{error, warn};
 
 use crate::error::match_for_io_error;
+use crate::stream::sender::stream_channel;
 use crate::{error::GrpcError, server::MassaPublicGrpc};
 
 #[cfg(feature = "execution-trace")]
@@ -36,7 +37,7 @@ pub(crate) async fn new_slot_abi_call_stacks(
     grpc: &MassaPublicGrpc,
     request: Request<Streaming<grpc_api::NewSlotAbiCallStacksRequest>>,
 ) -> Result<NewSlotABICallStacksStreamType, GrpcError> {
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(&grpc.grpc_config, request.remote_addr());
     // Extract the incoming stream of abi call stacks messages
     let mut in_stream = request.into_inner();
 
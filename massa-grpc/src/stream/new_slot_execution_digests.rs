@@ -0,0 +1,303 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Stream of the digests of the executed slots.
+//!
+//! The messages of the public API are generated from the massa-proto-rs definitions, which have
+//! no digest message. This service is declared by hand, with the messages and the routing that
+//! tonic-build would generate, under `massa.api.v1.SlotExecutionDigestService`.
+
+use crate::config::GrpcConfig;
+use crate::stream::sender::stream_channel;
+use massa_execution_exports::{ExecutionChannels, SlotExecutionOutput};
+use massa_proto_rs::massa::model::v1::{self as grpc_model};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::broadcast::error::RecvError;
+use tonic::body::BoxBody;
+use tonic::codec::{CompressionEncoding, EnabledCompressionEncodings, ProstCodec};
+use tonic::codegen::{empty_body, http, BoxFuture, Service};
+use tonic::transport::{Body, NamedService};
+use tracing::warn;
+
+/// Name of the digests service
+pub const SLOT_EXECUTION_DIGEST_SERVICE_NAME: &str = "massa.api.v1.SlotExecutionDigestService";
+
+/// Path of the method streaming the digests
+const NEW_SLOT_EXECUTION_DIGESTS_PATH: &str =
+    "/massa.api.v1.SlotExecutionDigestService/NewSlotExecutionDigests";
+
+/// Request of the digests of the new executed slots
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct NewSlotExecutionDigestsRequest {}
+
+/// Digest of a new executed slot
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct NewSlotExecutionDigestsResponse {
+    /// Digest of the executed slot
+    #[prost(message, optional, tag = "1")]
+    pub digest: Option<SlotExecutionDigest>,
+}
+
+/// Digest of the state changes of an executed slot
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SlotExecutionDigest {
+    /// Executed slot
+    #[prost(message, optional, tag = "1")]
+    pub slot: Option<grpc_model::Slot>,
+    /// Block executed at the slot, if any
+    #[prost(string, optional, tag = "2")]
+    pub block_id: Option<String>,
+    /// Whether the slot is final
+    #[prost(bool, tag = "3")]
+    pub is_final: bool,
+    /// Number of ledger entries changed
+    #[prost(uint64, tag = "4")]
+    pub ledger_changes_count: u64,
+    /// Number of asynchronous messages emitted
+    #[prost(uint64, tag = "5")]
+    pub async_messages_emitted: u64,
+    /// Number of asynchronous messages taken out of the pool
+    #[prost(uint64, tag = "6")]
+    pub async_messages_taken: u64,
+    /// Digest of the final state after applying the slot, set once the slot is final
+    #[prost(message, optional, tag = "7")]
+    pub final_state: Option<FinalStateDigest>,
+}
+
+/// Digest of the final state after applying a final slot
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FinalStateDigest {
+    /// Change id of the final state
+    #[prost(message, optional, tag = "1")]
+    pub change_id: Option<grpc_model::Slot>,
+    /// Fingerprint of the final state
+    #[prost(string, tag = "2")]
+    pub fingerprint: String,
+    /// Fingerprint of each component of the final state, by name
+    #[prost(btree_map = "string, string", tag = "3")]
+    pub component_fingerprints: BTreeMap<String, String>,
+}
+
+impl From<&SlotExecutionOutput> for SlotExecutionDigest {
+    fn from(slot_output: &SlotExecutionOutput) -> Self {
+        let output = slot_output.output();
+        let digest = &output.digest;
+        SlotExecutionDigest {
+            slot: Some(output.slot.into()),
+            block_id: output
+                .block_info
+                .as_ref()
+                .map(|info| info.block_id.to_string()),
+            is_final: slot_output.is_final(),
+            ledger_changes_count: digest.ledger_changes_count as u64,
+            async_messages_emitted: digest.async_messages_emitted as u64,
+            async_messages_taken: digest.async_messages_taken as u64,
+            final_state: digest
+                .final_state
+                .as_ref()
+                .map(|final_state| FinalStateDigest {
+                    change_id: Some(final_state.change_id.into()),
+                    fingerprint: final_state.fingerprint.to_string(),
+                    component_fingerprints: final_state
+                        .component_fingerprints
+                        .iter()
+                        .map(|(name, fingerprint)| (name.clone(), fingerprint.to_string()))
+                        .collect(),
+                }),
+        }
+    }
+}
+
+/// Type declaration for NewSlotExecutionDigests
+pub type NewSlotExecutionDigestsStreamType = Pin<
+    Box<
+        dyn futures_util::Stream<Item = Result<NewSlotExecutionDigestsResponse, tonic::Status>>
+            + Send
+            + 'static,
+    >,
+>;
+
+/// Server of the digests service, served next to the public API
+#[derive(Clone)]
+pub struct SlotExecutionDigestServiceServer {
+    execution_channels: ExecutionChannels,
+    grpc_config: GrpcConfig,
+    accept_compression_encodings: EnabledCompressionEncodings,
+    send_compression_encodings: EnabledCompressionEncodings,
+}
+
+impl SlotExecutionDigestServiceServer {
+    /// Create the server streaming the digests broadcast on `execution_channels`
+    pub fn new(execution_channels: ExecutionChannels, grpc_config: GrpcConfig) -> Self {
+        let mut accept_compression_encodings = EnabledCompressionEncodings::default();
+        if let Some(encoding) = &grpc_config.accept_compressed {
+            if encoding.eq_ignore_ascii_case("Gzip") {
+                accept_compression_encodings.enable(CompressionEncoding::Gzip);
+            }
+        }
+        let mut send_compression_encodings = EnabledCompressionEncodings::default();
+        if let Some(encoding) = &grpc_config.send_compressed {
+            if encoding.eq_ignore_ascii_case("Gzip") {
+                send_compression_encodings.enable(CompressionEncoding::Gzip);
+            }
+        }
+        SlotExecutionDigestServiceServer {
+            execution_channels,
+            grpc_config,
+            accept_compression_encodings,
+            send_compression_encodings,
+        }
+    }
+}
+
+/// Handler of the streaming method
+struct NewSlotExecutionDigestsSvc(SlotExecutionDigestServiceServer);
+
+impl tonic::server::ServerStreamingService<NewSlotExecutionDigestsRequest>
+    for NewSlotExecutionDigestsSvc
+{
+    type Response = NewSlotExecutionDigestsResponse;
+    type ResponseStream = NewSlotExecutionDigestsStreamType;
+    type Future = BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+
+    fn call(&mut self, request: tonic::Request<NewSlotExecutionDigestsRequest>) -> Self::Future {
+        let stream = new_slot_execution_digests(&self.0, request);
+        Box::pin(async move { Ok(tonic::Response::new(stream)) })
+    }
+}
+
+impl Service<http::Request<Body>> for SlotExecutionDigestServiceServer {
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<Body>) -> Self::Future {
+        match req.uri().path() {
+            NEW_SLOT_EXECUTION_DIGESTS_PATH => {
+                let server = self.clone();
+                Box::pin(async move {
+                    let mut grpc = tonic::server::Grpc::new(ProstCodec::default())
+                        .apply_compression_config(
+                            server.accept_compression_encodings,
+                            server.send_compression_encodings,
+                        )
+                        .apply_max_message_size_config(
+                            Some(server.grpc_config.max_decoding_message_size),
+                            Some(server.grpc_config.max_encoding_message_size),
+                        );
+                    Ok(grpc
+                        .server_streaming(NewSlotExecutionDigestsSvc(server), req)
+                        .await)
+                })
+            }
+            _ => Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(200)
+                    .header(
+                        "grpc-status",
+                        (tonic::Code::Unimplemented as i32).to_string(),
+                    )
+                    .header("content-type", "application/grpc")
+                    .body(empty_body())
+                    .expect("failed to build the unimplemented response"))
+            }),
+        }
+    }
+}
+
+impl NamedService for SlotExecutionDigestServiceServer {
+    const NAME: &'static str = SLOT_EXECUTION_DIGEST_SERVICE_NAME;
+}
+
+/// Creates a new stream of the digests of the executed slots
+fn new_slot_execution_digests(
+    server: &SlotExecutionDigestServiceServer,
+    request: tonic::Request<NewSlotExecutionDigestsRequest>,
+) -> NewSlotExecutionDigestsStreamType {
+    // Create a channel to handle communication with the client
+    let (tx, rx) = stream_channel(&server.grpc_config, request.remote_addr());
+    // Subscribe to the new slot execution events channel
+    let mut subscriber = server
+        .execution_channels
+        .slot_execution_output_sender
+        .subscribe();
+
+    tokio::spawn(async move {
+        loop {
+            match subscriber.recv().await {
+                Ok(slot_output) => {
+                    let response = NewSlotExecutionDigestsResponse {
+                        digest: Some(SlotExecutionDigest::from(&slot_output)),
+                    };
+                    if tx.send(Ok(response)).await.is_err() {
+                        // the client disconnected or stalled
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    // a mirror missing a slot can't check the next digests: let it resubscribe
+                    warn!(
+                        "new slot execution digests stream lagged, {} digests skipped",
+                        skipped
+                    );
+                    let _ = tx
+                        .send(Err(tonic::Status::data_loss(format!(
+                            "stream lagged behind: {} slot execution digests were skipped, resubscribe to continue",
+                            skipped
+                        ))))
+                        .await;
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    let out_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    Box::pin(out_stream) as NewSlotExecutionDigestsStreamType
+}
+
+/// Client of the digests service
+#[derive(Debug, Clone)]
+pub struct SlotExecutionDigestServiceClient {
+    inner: tonic::client::Grpc<tonic::transport::Channel>,
+}
+
+impl SlotExecutionDigestServiceClient {
+    /// Connect to the public gRPC API at `dst`
+    pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+    where
+        D: TryInto<tonic::transport::Endpoint>,
+        D::Error: Into<tonic::codegen::StdError>,
+    {
+        let channel = tonic::transport::Endpoint::new(dst)?.connect().await?;
+        Ok(SlotExecutionDigestServiceClient {
+            inner: tonic::client::Grpc::new(channel),
+        })
+    }
+
+    /// Subscribe to the digests of the new executed slots
+    pub async fn new_slot_execution_digests(
+        &mut self,
+        request: impl tonic::IntoRequest<NewSlotExecutionDigestsRequest>,
+    ) -> Result<tonic::Response<tonic::Streaming<NewSlotExecutionDigestsResponse>>, tonic::Status>
+    {
+        self.inner
+            .ready()
+            .await
+            .map_err(|e| tonic::Status::unknown(format!("Service was not ready: {}", e)))?;
+        self.inner
+            .server_streaming(
+                request.into_request(),
+                http::uri::PathAndQuery::from_static(NEW_SLOT_EXECUTION_DIGESTS_PATH),
+                ProstCodec::default(),
+            )
+            .await
+    }
+}
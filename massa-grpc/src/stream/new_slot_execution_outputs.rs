@@ -3,16 +3,22 @@
 use crate::config::GrpcConfig;
 use crate::error::{match_for_io_error, GrpcError};
 use crate::server::MassaPublicGrpc;
+use crate::stream::sender::stream_channel;
 use crate::SlotRange;
 use futures_util::StreamExt;
 use massa_execution_exports::{ExecutionOutput, SlotExecutionOutput};
+use massa_models::address::Address;
+use massa_models::execution::EventFilter;
+use massa_models::operation::OperationId;
 use massa_models::slot::Slot;
 use massa_proto_rs::massa::api::v1::{self as grpc_api, NewSlotExecutionOutputsRequest};
 use massa_proto_rs::massa::model::v1::{self as grpc_model};
 use std::collections::HashSet;
 use std::io::ErrorKind;
 use std::pin::Pin;
+use std::str::FromStr;
 use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
 use tonic::{Request, Streaming};
 use tracing::{error, warn};
 
@@ -60,8 +66,10 @@ struct ExecutedDenounciationFilter {
 
 #[derive(Clone, Debug, Default)]
 struct ExecutionEventFilter {
-    // Do not return any message
+    // Do not return any event
     none: Option<()>,
+    // Criteria that the returned events must all satisfy
+    event_filter: EventFilter,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -82,7 +90,7 @@ pub(crate) async fn new_slot_execution_outputs(
     request: Request<Streaming<grpc_api::NewSlotExecutionOutputsRequest>>,
 ) -> Result<NewSlotExecutionOutputsStreamType, GrpcError> {
     // Create a channel to handle communication with the client
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(&grpc.grpc_config, request.remote_addr());
     // Get the inner stream from the request
     let mut in_stream = request.into_inner();
     // Subscribe to the new slot execution events channel
@@ -125,7 +133,20 @@ pub(crate) async fn new_slot_execution_outputs(
                                 }
                             },
 
-                            Err(e) => error!("error on receive new slot execution output : {}", e)
+                            Err(RecvError::Lagged(skipped)) => {
+                                // the client missed state changes: end the stream with a lag marker
+                                // so that it can resynchronize instead of silently skipping them
+                                warn!("new slot execution outputs stream lagged, {} outputs skipped", skipped);
+                                let _ = tx.send(Err(tonic::Status::data_loss(format!(
+                                    "stream lagged behind: {} slot execution outputs were skipped, resubscribe to continue",
+                                    skipped
+                                )))).await;
+                                break;
+                            },
+                            Err(RecvError::Closed) => {
+                                error!("new slot execution outputs channel closed");
+                                break;
+                            }
                         }
                     },
                     // Receive a new message from the in_stream
@@ -266,19 +287,36 @@ fn get_filter(
                 }},
                 grpc_api::new_slot_execution_outputs_filter::Filter::EventFilter(filter) => {
                     if let Some(filter) = filter.filter {
+                        let execution_event_filter =
+                            execution_event_filter.get_or_insert_with(ExecutionEventFilter::default);
                         match filter {
                             grpc_api::execution_event_filter::Filter::None(_) => {
-                                execution_event_filter = Some(ExecutionEventFilter {
-                                    none: Some(()),
-                                });
+                                execution_event_filter.none = Some(());
+                            },
+                            grpc_api::execution_event_filter::Filter::CallerAddress(address) => {
+                                execution_event_filter.event_filter.original_caller_address =
+                                    Some(Address::from_str(&address).map_err(|_| {
+                                        GrpcError::InvalidArgument(format!("invalid address: {}", address))
+                                    })?);
+                            },
+                            grpc_api::execution_event_filter::Filter::EmitterAddress(address) => {
+                                execution_event_filter.event_filter.emitter_address =
+                                    Some(Address::from_str(&address).map_err(|_| {
+                                        GrpcError::InvalidArgument(format!("invalid address: {}", address))
+                                    })?);
+                            },
+                            grpc_api::execution_event_filter::Filter::OriginalOperationId(id) => {
+                                execution_event_filter.event_filter.original_operation_id =
+                                    Some(OperationId::from_str(&id).map_err(|_| {
+                                        GrpcError::InvalidArgument(format!("invalid operation id: {}", id))
+                                    })?);
+                            },
+                            grpc_api::execution_event_filter::Filter::IsFailure(is_failure) => {
+                                execution_event_filter.event_filter.is_error = Some(is_failure);
                             },
-                            _ => {
-                                execution_event_filter = Some(ExecutionEventFilter {
-                                none: None,
-                            })
                         }
-                }
-                }},
+                    }
+                },
                 grpc_api::new_slot_execution_outputs_filter::Filter::ExecutedOpsChangesFilter(filter) => {
                     if let Some(filter) = filter.filter {
                         match filter {
@@ -400,6 +438,12 @@ fn filter_map_exec_output(
     if let Some(execution_event_filter) = &filters.execution_event_filter {
         if execution_event_filter.none.is_some() {
             exec_output.events.clear();
+        } else {
+            // same matching as the events queried from the event store
+            exec_output
+                .events
+                .0
+                .retain(|event| execution_event_filter.event_filter.matches(event));
         }
     }
 
@@ -25,12 +25,13 @@ pub(crate) async fn new_slot_transfers(
     request: Request<Streaming<massa_proto_rs::massa::api::v1::NewSlotTransfersRequest>>,
 ) -> Result<NewSlotTransfersStreamType, GrpcError> {
     use crate::error::match_for_io_error;
+    use crate::stream::sender::stream_channel;
     use futures_util::StreamExt;
     use massa_proto_rs::massa::api::v1::{self as grpc_api, FinalityLevel, TransferInfo};
     use tokio::select;
     use tracing::{error, warn};
 
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(&grpc.grpc_config, request.remote_addr());
     // Extract the incoming stream of abi call stacks messages
     let mut in_stream = request.into_inner();
 
@@ -71,11 +71,19 @@ pub(crate) async fn send_operations(
                             continue;
                         };
                         // If there are too many operations in the incoming message, send an error message back to the client
-                        if req_content.operations.len() as u32 > config.max_operations_per_message {
+                        // (a message is bounded by both the network limit and the gRPC request limit)
+                        let max_operations = std::cmp::min(
+                            config.max_operations_per_message as u64,
+                            config.max_arguments,
+                        );
+                        if req_content.operations.len() as u64 > max_operations {
                             report_error(
                                 tx.clone(),
                                 tonic::Code::InvalidArgument,
-                                "too many operations per message".to_owned(),
+                                format!(
+                                    "too many operations received. Only a maximum of {} operations are accepted per message",
+                                    max_operations
+                                ),
                             )
                             .await;
                         } else {
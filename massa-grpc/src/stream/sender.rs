@@ -0,0 +1,73 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use displaydoc::Display;
+use tokio::sync::mpsc::{self, error::SendTimeoutError};
+use tracing::warn;
+
+use crate::config::GrpcConfig;
+
+/// Error returned when a message can't be queued for the client of a stream
+#[derive(Display, thiserror::Error, Debug)]
+pub(crate) enum StreamSendError {
+    /// the stream is closed
+    Closed,
+    /// the client has not read the stream for {0:?}
+    Stalled(Duration),
+}
+
+/// Sender of the messages of a stream to its client.
+///
+/// The messages are queued in a channel of `max_channel_size` messages. If the client stops
+/// reading, sending fails once the channel has been full for `stream_client_idle_timeout`,
+/// so that the task feeding the stream stops and releases its subscriptions.
+pub(crate) struct StreamSender<T> {
+    sender: mpsc::Sender<T>,
+    idle_timeout: Option<Duration>,
+    /// address of the client, for logging
+    peer_addr: Option<SocketAddr>,
+}
+
+/// Create the channel of a stream to the client at `peer_addr`
+pub(crate) fn stream_channel<T>(
+    config: &GrpcConfig,
+    peer_addr: Option<SocketAddr>,
+) -> (StreamSender<T>, mpsc::Receiver<T>) {
+    let (sender, receiver) = mpsc::channel(config.max_channel_size);
+    (
+        StreamSender {
+            sender,
+            idle_timeout: config.stream_client_idle_timeout,
+            peer_addr,
+        },
+        receiver,
+    )
+}
+
+impl<T> StreamSender<T> {
+    /// Queue a message for the client, waiting for room in the channel
+    pub(crate) async fn send(&self, message: T) -> Result<(), StreamSendError> {
+        let Some(idle_timeout) = self.idle_timeout else {
+            return self
+                .sender
+                .send(message)
+                .await
+                .map_err(|_| StreamSendError::Closed);
+        };
+        match self.sender.send_timeout(message, idle_timeout).await {
+            Ok(()) => Ok(()),
+            Err(SendTimeoutError::Closed(_)) => Err(StreamSendError::Closed),
+            Err(SendTimeoutError::Timeout(_)) => {
+                warn!(
+                    "dropping the stream of client {}: it has not read anything for {:?}",
+                    self.peer_addr
+                        .map_or_else(|| "unknown".to_string(), |addr| addr.to_string()),
+                    idle_timeout
+                );
+                Err(StreamSendError::Stalled(idle_timeout))
+            }
+        }
+    }
+}
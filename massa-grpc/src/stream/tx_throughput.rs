@@ -1,5 +1,6 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::stream::sender::stream_channel;
 use crate::{error::GrpcError, server::MassaPublicGrpc};
 use futures_util::StreamExt;
 use massa_proto_rs::massa::api::v1 as grpc_api;
@@ -33,7 +34,7 @@ pub(crate) async fn transactions_throughput(
     let execution_controller = grpc.execution_controller.clone();
 
     // Create a channel for sending responses to the client
-    let (tx, rx) = tokio::sync::mpsc::channel(grpc.grpc_config.max_channel_size);
+    let (tx, rx) = stream_channel(&grpc.grpc_config, request.remote_addr());
     // Extract the incoming stream of operations messages
     let mut in_stream = request.into_inner();
 
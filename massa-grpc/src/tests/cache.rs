@@ -0,0 +1,293 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::cache::ResponseCache;
+use crate::server::MassaPublicGrpc;
+use crate::tests::mock::grpc_public_service;
+use massa_execution_exports::{
+    EventStore, ExecutionOutput, ExecutionQueryResponse, MockExecutionController,
+    SlotExecutionOutput,
+};
+use massa_models::address::Address;
+use massa_models::slot::Slot;
+use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
+use massa_proto_rs::massa::api::v1::{
+    stakers_filter, GetStakersRequest, GetStatusRequest, StakersFilter,
+};
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// public service caching `methods`, counting the computations of the stakers and of the status
+fn public_server(
+    addr: &SocketAddr,
+    methods: &[&str],
+    ttl: Duration,
+    computations: Arc<AtomicUsize>,
+) -> MassaPublicGrpc {
+    let mut public_server = grpc_public_service(addr);
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    let stakers_computations = computations.clone();
+    exec_ctrl
+        .expect_get_cycle_active_rolls()
+        .returning(move |_| {
+            stakers_computations.fetch_add(1, Ordering::SeqCst);
+            BTreeMap::from([(
+                Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key()),
+                30_u64,
+            )])
+        });
+    exec_ctrl.expect_query_state().returning(move |_| {
+        computations.fetch_add(1, Ordering::SeqCst);
+        ExecutionQueryResponse {
+            responses: vec![],
+            candidate_cursor: Slot::new(0, 2),
+            final_cursor: Slot::new(0, 0),
+            final_state_fingerprint: massa_hash::Hash::compute_from(&Vec::new()),
+        }
+    });
+    public_server.execution_controller = exec_ctrl;
+    public_server.grpc_config.response_cache_methods =
+        methods.iter().map(|method| method.to_string()).collect();
+    public_server.grpc_config.response_cache_ttl = ttl;
+    // slots of 100 seconds: the slot clock does not invalidate the responses during the tests
+    public_server.grpc_config.t0 = MassaTime::from_millis(3_200_000);
+    public_server.response_cache = ResponseCache::new(&public_server.grpc_config);
+    public_server
+}
+
+fn stakers_request(min_rolls: u64) -> GetStakersRequest {
+    GetStakersRequest {
+        filters: vec![StakersFilter {
+            filter: Some(stakers_filter::Filter::MinRolls(min_rolls)),
+        }],
+    }
+}
+
+fn execution_output(slot: Slot) -> ExecutionOutput {
+    ExecutionOutput {
+        slot,
+        block_info: None,
+        state_changes: massa_final_state::StateChanges::default(),
+        events: EventStore::default(),
+        #[cfg(feature = "execution-trace")]
+        slot_trace: None,
+        #[cfg(feature = "dump-block")]
+        storage: None,
+        deferred_credits_execution: vec![],
+        cancel_async_message_execution: vec![],
+        auto_sell_execution: vec![],
+        digest: Default::default(),
+    }
+}
+
+/// wait for the cache to be notified of the given slots
+async fn wait_for_slots(cache: &ResponseCache, slots: (Option<Slot>, Option<Slot>)) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while cache.slots() != slots {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the response cache was not notified of the new slot");
+}
+
+#[tokio::test]
+async fn cached_until_new_slot() {
+    let addr: SocketAddr = "[::]:4049".parse().unwrap();
+    let computations = Arc::new(AtomicUsize::new(0));
+    let public_server = public_server(
+        &addr,
+        &["get_stakers"],
+        Duration::from_secs(60),
+        computations.clone(),
+    );
+    let slot_tx = public_server
+        .execution_channels
+        .slot_execution_output_sender
+        .clone();
+    let cache = public_server.response_cache.clone();
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    // the same request within a slot is only computed once
+    let first = public_client
+        .get_stakers(stakers_request(20))
+        .await
+        .unwrap()
+        .into_inner();
+    let second = public_client
+        .get_stakers(stakers_request(20))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(first, second);
+    assert_eq!(computations.load(Ordering::SeqCst), 1);
+
+    // another request is cached separately
+    public_client
+        .get_stakers(stakers_request(10))
+        .await
+        .unwrap();
+    assert_eq!(computations.load(Ordering::SeqCst), 2);
+
+    // a new candidate slot invalidates the cached responses
+    let slot = Slot::new(1, 0);
+    slot_tx
+        .send(SlotExecutionOutput::ExecutedSlot(execution_output(slot)))
+        .unwrap();
+    wait_for_slots(&cache, (Some(slot), None)).await;
+    public_client
+        .get_stakers(stakers_request(20))
+        .await
+        .unwrap();
+    public_client
+        .get_stakers(stakers_request(20))
+        .await
+        .unwrap();
+    assert_eq!(computations.load(Ordering::SeqCst), 3);
+
+    // and so does a new final slot
+    slot_tx
+        .send(SlotExecutionOutput::FinalizedSlot(execution_output(slot)))
+        .unwrap();
+    wait_for_slots(&cache, (Some(slot), Some(slot))).await;
+    public_client
+        .get_stakers(stakers_request(20))
+        .await
+        .unwrap();
+    assert_eq!(computations.load(Ordering::SeqCst), 4);
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn cache_opt_in_and_ttl() {
+    let addr: SocketAddr = "[::]:4050".parse().unwrap();
+    let computations = Arc::new(AtomicUsize::new(0));
+    let public_server = public_server(
+        &addr,
+        &["get_status"],
+        Duration::from_secs(60),
+        computations.clone(),
+    );
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    // the stakers are not cached if the method is not listed
+    for _ in 0..2 {
+        public_client
+            .get_stakers(stakers_request(20))
+            .await
+            .unwrap();
+    }
+    assert_eq!(computations.load(Ordering::SeqCst), 2);
+    stop_handle.stop();
+
+    // a cached response expires after the TTL even if no new slot is executed
+    let addr: SocketAddr = "[::]:4051".parse().unwrap();
+    let computations = Arc::new(AtomicUsize::new(0));
+    let public_server = public_server(
+        &addr,
+        &["get_stakers"],
+        Duration::from_millis(200),
+        computations.clone(),
+    );
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    for _ in 0..2 {
+        public_client
+            .get_stakers(stakers_request(20))
+            .await
+            .unwrap();
+    }
+    assert_eq!(computations.load(Ordering::SeqCst), 1);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    public_client
+        .get_stakers(stakers_request(20))
+        .await
+        .unwrap();
+    assert_eq!(computations.load(Ordering::SeqCst), 2);
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn cached_until_clock_slot_with_fresh_times() {
+    let addr: SocketAddr = "[::]:4060".parse().unwrap();
+    let computations = Arc::new(AtomicUsize::new(0));
+    let mut public_server = public_server(
+        &addr,
+        &["get_status"],
+        Duration::from_secs(60),
+        computations.clone(),
+    );
+    // slots of 500 ms from now, without any slot broadcast by the execution
+    public_server.grpc_config.t0 = MassaTime::from_millis(16_000);
+    public_server.grpc_config.genesis_timestamp = MassaTime::now();
+    public_server.response_cache = ResponseCache::new(&public_server.grpc_config);
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    // the cached status is served with the current time
+    let first = public_client
+        .get_status(GetStatusRequest {})
+        .await
+        .unwrap()
+        .into_inner()
+        .status
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let second = public_client
+        .get_status(GetStatusRequest {})
+        .await
+        .unwrap()
+        .into_inner()
+        .status
+        .unwrap();
+    assert_eq!(computations.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        first.final_state_fingerprint,
+        second.final_state_fingerprint
+    );
+    assert!(second.current_time.unwrap().milliseconds > first.current_time.unwrap().milliseconds);
+
+    // the next slot of the clock invalidates the cached status
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    public_client.get_status(GetStatusRequest {}).await.unwrap();
+    assert_eq!(computations.load(Ordering::SeqCst), 2);
+
+    stop_handle.stop();
+}
@@ -0,0 +1,168 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::config::GrpcConfig;
+use crate::error::{GrpcConfigError, GrpcError};
+use crate::tests::mock::grpc_public_service;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+
+/// configuration of the mock public service with TLS enabled on the files of `dir`
+fn tls_config(dir: &Path) -> GrpcConfig {
+    let addr: SocketAddr = "[::]:4042".parse().unwrap();
+    let mut config = grpc_public_service(&addr).grpc_config;
+    config.enable_tls = true;
+    config.certificate_authority_root_path = dir.join("ca.pem");
+    config.server_certificate_path = dir.join("server.pem");
+    config.server_private_key_path = dir.join("server.key");
+    config.client_certificate_authority_root_path = dir.join("client_ca.pem");
+    std::fs::write(&config.certificate_authority_root_path, "").unwrap();
+    std::fs::write(&config.server_certificate_path, "").unwrap();
+    std::fs::write(&config.server_private_key_path, "").unwrap();
+    std::fs::write(&config.client_certificate_authority_root_path, "").unwrap();
+    config
+}
+
+fn assert_missing_file(config: &GrpcConfig, expected_name: &str) {
+    match config.validate() {
+        Err(GrpcConfigError::MissingTlsFile { name, .. }) => assert_eq!(name, expected_name),
+        other => panic!(
+            "expected a missing {} error, got {:?}",
+            expected_name, other
+        ),
+    }
+}
+
+#[test]
+fn valid_configs() {
+    let addr: SocketAddr = "[::]:4042".parse().unwrap();
+    let mut config = grpc_public_service(&addr).grpc_config;
+    config.validate().unwrap();
+
+    // mTLS is ignored without TLS
+    config.enable_mtls = true;
+    config.validate().unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = tls_config(dir.path());
+    config.enable_mtls = true;
+    config.certificate_reload_interval = Some(Duration::from_secs(60));
+    config.validate().unwrap();
+}
+
+#[test]
+fn tls_missing_server_certificate() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = tls_config(dir.path());
+    std::fs::remove_file(&config.server_certificate_path).unwrap();
+    assert_missing_file(&config, "server_certificate_path");
+}
+
+#[test]
+fn tls_missing_server_private_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = tls_config(dir.path());
+    std::fs::remove_file(&config.server_private_key_path).unwrap();
+    assert_missing_file(&config, "server_private_key_path");
+}
+
+#[test]
+fn mtls_missing_client_certificate_authority_root() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = tls_config(dir.path());
+    std::fs::remove_file(&config.client_certificate_authority_root_path).unwrap();
+    // only required with mTLS
+    config.validate().unwrap();
+
+    config.enable_mtls = true;
+    assert_missing_file(&config, "client_certificate_authority_root_path");
+}
+
+#[test]
+fn tls_files_generated_at_startup() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = tls_config(dir.path());
+    std::fs::remove_file(&config.server_certificate_path).unwrap();
+    config.generate_self_signed_certificates = true;
+    // the certificates are not generated again if the CA root exists
+    assert_missing_file(&config, "server_certificate_path");
+
+    std::fs::remove_file(&config.certificate_authority_root_path).unwrap();
+    config.validate().unwrap();
+}
+
+#[test]
+fn cors_without_http1() {
+    let addr: SocketAddr = "[::]:4042".parse().unwrap();
+    let mut config = grpc_public_service(&addr).grpc_config;
+    config.enable_cors = true;
+    config.accept_http1 = false;
+    assert!(matches!(
+        config.validate(),
+        Err(GrpcConfigError::CorsWithoutHttp1)
+    ));
+}
+
+#[test]
+fn uncacheable_response_cache_method() {
+    let addr: SocketAddr = "[::]:4042".parse().unwrap();
+    let mut config = grpc_public_service(&addr).grpc_config;
+    config.response_cache_methods = vec!["get_stakers".to_string(), "get_status".to_string()];
+    config.validate().unwrap();
+
+    // streams and methods changing the node state are never cached
+    config
+        .response_cache_methods
+        .push("send_operations".to_string());
+    match config.validate() {
+        Err(GrpcConfigError::UncacheableMethod(method)) => assert_eq!(method, "send_operations"),
+        other => panic!("expected an uncacheable method error, got {:?}", other),
+    }
+}
+
+#[test]
+fn zero_certificate_reload_interval() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut config = tls_config(dir.path());
+    config.certificate_reload_interval = Some(Duration::ZERO);
+    assert!(matches!(
+        config.validate(),
+        Err(GrpcConfigError::ZeroCertificateReloadInterval)
+    ));
+}
+
+#[test]
+fn compress_threshold() {
+    let addr: SocketAddr = "[::]:4042".parse().unwrap();
+    let mut config = grpc_public_service(&addr).grpc_config;
+    assert!(!config.compress_response(1 << 20));
+
+    config.send_compressed = Some("Gzip".to_string());
+    assert!(config.compress_response(0));
+    assert!(config.compress_response(1 << 20));
+
+    config.compress_threshold_bytes = Some(1024);
+    assert!(!config.compress_response(0));
+    assert!(!config.compress_response(1024));
+    assert!(config.compress_response(1025));
+
+    config.send_compressed = None;
+    assert!(!config.compress_response(1025));
+}
+
+#[tokio::test]
+async fn serve_rejects_invalid_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut public_server = grpc_public_service(&"[::]:4042".parse().unwrap());
+    public_server.grpc_config = tls_config(dir.path());
+    std::fs::remove_file(&public_server.grpc_config.server_private_key_path).unwrap();
+    let config = public_server.grpc_config.clone();
+
+    match public_server.serve(&config).await {
+        Err(GrpcError::InvalidConfig(GrpcConfigError::MissingTlsFile { name, .. })) => {
+            assert_eq!(name, "server_private_key_path")
+        }
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("the server started with an invalid configuration"),
+    }
+}
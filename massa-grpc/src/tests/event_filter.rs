@@ -0,0 +1,92 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::error::GrpcError;
+use crate::event_filter::ScExecutionEventsFilters;
+use massa_models::execution::EventFilter;
+use massa_models::slot::Slot;
+use massa_proto_rs::massa::api::v1::{sc_execution_events_filter::Filter, ScExecutionEventsFilter};
+use massa_proto_rs::massa::model::v1 as grpc_model;
+
+const ADDRESS: &str = "AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x";
+const OPERATION_ID: &str = "O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC";
+
+fn filter(filter: Filter) -> ScExecutionEventsFilter {
+    ScExecutionEventsFilter {
+        filter: Some(filter),
+    }
+}
+
+fn slot_range(start: Option<(u64, u32)>, end: Option<(u64, u32)>) -> ScExecutionEventsFilter {
+    let slot = |(period, thread)| grpc_model::Slot { period, thread };
+    filter(Filter::SlotRange(grpc_model::SlotRange {
+        start_slot: start.map(slot),
+        end_slot: end.map(slot),
+    }))
+}
+
+fn convert(filters: Vec<ScExecutionEventsFilter>) -> Result<EventFilter, GrpcError> {
+    EventFilter::try_from(ScExecutionEventsFilters {
+        filters,
+        max_filters: 5,
+    })
+}
+
+fn assert_invalid_argument(result: Result<EventFilter, GrpcError>) {
+    match result {
+        Err(GrpcError::InvalidArgument(_)) => {}
+        other => panic!("expected an invalid argument error, got {:?}", other),
+    }
+}
+
+#[test]
+fn valid_event_filter() {
+    let event_filter = convert(vec![
+        slot_range(Some((1, 2)), Some((3, 0))),
+        filter(Filter::CallerAddress(ADDRESS.to_string())),
+        filter(Filter::EmitterAddress(ADDRESS.to_string())),
+        filter(Filter::OriginalOperationId(OPERATION_ID.to_string())),
+        filter(Filter::IsFailure(true)),
+    ])
+    .unwrap();
+    assert_eq!(event_filter.start, Some(Slot::new(1, 2)));
+    assert_eq!(event_filter.end, Some(Slot::new(3, 0)));
+    assert_eq!(
+        event_filter.original_caller_address.unwrap().to_string(),
+        ADDRESS
+    );
+    assert_eq!(event_filter.emitter_address.unwrap().to_string(), ADDRESS);
+    assert_eq!(
+        event_filter.original_operation_id.unwrap().to_string(),
+        OPERATION_ID
+    );
+    assert_eq!(event_filter.is_error, Some(true));
+    assert_eq!(event_filter.is_final, None);
+
+    // open ranges and no filters at all are accepted
+    let event_filter = convert(vec![slot_range(Some((1, 2)), None)]).unwrap();
+    assert_eq!(event_filter.start, Some(Slot::new(1, 2)));
+    assert_eq!(event_filter.end, None);
+    let event_filter = convert(vec![]).unwrap();
+    assert!(event_filter.start.is_none() && event_filter.end.is_none());
+}
+
+#[test]
+fn too_many_filters() {
+    assert_invalid_argument(convert(vec![filter(Filter::IsFailure(false)); 6]));
+}
+
+#[test]
+fn unordered_slot_range() {
+    assert_invalid_argument(convert(vec![slot_range(Some((3, 0)), Some((1, 2)))]));
+    assert_invalid_argument(convert(vec![slot_range(Some((1, 2)), Some((1, 2)))]));
+}
+
+#[test]
+fn invalid_filter_value() {
+    assert_invalid_argument(convert(vec![filter(Filter::CallerAddress(
+        "invalid".to_string(),
+    ))]));
+    assert_invalid_argument(convert(vec![filter(Filter::OriginalOperationId(
+        "invalid".to_string(),
+    ))]));
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::tests::mock::{grpc_private_service, grpc_public_service};
+use massa_execution_exports::MockExecutionController;
+use massa_models::slot::Slot;
+use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
+use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
+use massa_proto_rs::massa::api::v1::{GetMipStatusRequest, GetStatusRequest};
+use prometheus::proto::MetricFamily;
+use std::net::SocketAddr;
+
+/// methods of the requests recorded by the metric `name` of the default registry
+fn recorded_methods(families: &[MetricFamily], name: &str) -> Vec<String> {
+    families
+        .iter()
+        .filter(|family| family.get_name() == name)
+        .flat_map(|family| family.get_metric())
+        .flat_map(|metric| metric.get_label())
+        .filter(|label| label.get_name() == "method")
+        .map(|label| label.get_value().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn services_metrics_namespaces() {
+    let public_addr: SocketAddr = "[::]:4052".parse().unwrap();
+    let mut public_server = grpc_public_service(&public_addr);
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_query_state()
+        .returning(|_| massa_execution_exports::ExecutionQueryResponse {
+            responses: vec![],
+            candidate_cursor: Slot::new(0, 2),
+            final_cursor: Slot::new(0, 0),
+            final_state_fingerprint: massa_hash::Hash::compute_from(&Vec::new()),
+        });
+    public_server.execution_controller = exec_ctrl;
+    let public_config = public_server.grpc_config.clone();
+    let public_stop_handle = public_server.serve(&public_config).await.unwrap();
+
+    let private_addr: SocketAddr = "[::]:4053".parse().unwrap();
+    let private_server = grpc_private_service(&private_addr);
+    let private_config = private_server.grpc_config.clone();
+    let private_stop_handle = private_server.serve(&private_config).await.unwrap();
+
+    let mut public_client =
+        PublicServiceClient::connect(format!("grpc://localhost:{}", public_addr.port()))
+            .await
+            .unwrap();
+    public_client.get_status(GetStatusRequest {}).await.unwrap();
+
+    let mut private_client =
+        PrivateServiceClient::connect(format!("grpc://localhost:{}", private_addr.port()))
+            .await
+            .unwrap();
+    private_client
+        .get_mip_status(GetMipStatusRequest {})
+        .await
+        .unwrap();
+
+    // each service records its requests under its own prefix
+    let families = prometheus::gather();
+    for name in [
+        "grpc_public_requests",
+        "grpc_public_request_duration_seconds",
+    ] {
+        let methods = recorded_methods(&families, name);
+        assert!(methods.contains(&"GetStatus".to_string()), "{}", name);
+        assert!(!methods.contains(&"GetMipStatus".to_string()), "{}", name);
+    }
+    for name in [
+        "grpc_private_requests",
+        "grpc_private_request_duration_seconds",
+    ] {
+        let methods = recorded_methods(&families, name);
+        assert!(methods.contains(&"GetMipStatus".to_string()), "{}", name);
+        assert!(!methods.contains(&"GetStatus".to_string()), "{}", name);
+    }
+
+    public_stop_handle.stop();
+    private_stop_handle.stop();
+}
@@ -1,11 +1,16 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 use std::net::SocketAddr;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
 
-use crate::config::{GrpcConfig, ServiceName};
-use crate::server::MassaPublicGrpc;
+use crate::cache::ResponseCache;
+use crate::config::{GrpcConfig, ServiceName, DEFAULT_PUBLIC_MAX_CONCURRENT_STREAMS};
+use crate::server::{MassaPrivateGrpc, MassaPublicGrpc};
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_consensus_exports::{ConsensusBroadcasts, MockConsensusController};
 use massa_execution_exports::{ExecutionChannels, MockExecutionController};
 use massa_models::amount::Amount;
+use massa_models::config::CHAINID;
 use massa_models::{
     config::{
         ENDORSEMENT_COUNT, MAX_DATASTORE_VALUE_LENGTH, MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
@@ -23,9 +28,9 @@ use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_versioning::versioning::{MipStatsConfig, MipStore};
-// use massa_wallet::test_exports::create_test_wallet;
-use massa_models::config::CHAINID;
+use massa_wallet::test_exports::create_test_wallet;
 use num::rational::Ratio;
+use parking_lot::RwLock;
 use std::path::PathBuf;
 
 /// generate a grpc public service
@@ -41,8 +46,8 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
     let execution_ctrl = Box::new(MockExecutionController::new());
     let protocol_ctrl = Box::new(MockProtocolController::new());
 
-    let endorsement_sender = tokio::sync::broadcast::channel(2000).0;
-    let operation_sender = tokio::sync::broadcast::channel(5000).0;
+    let endorsement_sender = DedupBroadcaster::new("test_endorsements".to_string(), 2000, 2000);
+    let operation_sender = DedupBroadcaster::new("test_operations".to_string(), 5000, 5000);
     let slot_execution_output_sender = tokio::sync::broadcast::channel(5000).0;
     let keypair = KeyPair::generate(0).unwrap();
     let grpc_config = GrpcConfig {
@@ -61,6 +66,7 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         // bind: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8888),
         accept_compressed: None,
         send_compressed: None,
+        compress_threshold_bytes: None,
         max_decoding_message_size: 4194304,
         max_encoding_message_size: 4194304,
         max_gas_per_block: u32::MAX as u64,
@@ -68,7 +74,7 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         timeout: Default::default(),
         initial_stream_window_size: None,
         initial_connection_window_size: None,
-        max_concurrent_streams: None,
+        max_concurrent_streams: Some(DEFAULT_PUBLIC_MAX_CONCURRENT_STREAMS),
         max_arguments: 128,
         tcp_keepalive: None,
         tcp_nodelay: false,
@@ -93,6 +99,7 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         periods_per_cycle: PERIODS_PER_CYCLE,
         keypair: keypair.clone(),
         max_channel_size: 128,
+        stream_client_idle_timeout: None,
         draw_lookahead_period_count: 10,
         last_start_period: 0,
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
@@ -108,7 +115,11 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         client_certificate_authority_root_path: PathBuf::default(),
         client_certificate_path: PathBuf::default(),
         client_private_key_path: PathBuf::default(),
+        certificate_reload_interval: None,
         max_query_items_per_request: 50,
+        response_cache_methods: Vec::new(),
+        response_cache_ttl: Duration::from_secs(10),
+        response_cache_max_entries: 1000,
         chain_id: *CHAINID,
         minimal_fees: Amount::zero(),
     };
@@ -122,9 +133,9 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
 
     MassaPublicGrpc {
         consensus_broadcasts: ConsensusBroadcasts {
-            block_sender: tokio::sync::broadcast::channel(100).0,
+            block_sender: DedupBroadcaster::new("test_blocks".to_string(), 100, 100),
             block_header_sender: tokio::sync::broadcast::channel(100).0,
-            filled_block_sender: tokio::sync::broadcast::channel(100).0,
+            filled_block_sender: DedupBroadcaster::new("test_filled_blocks".to_string(), 100, 100),
         },
         consensus_controller: consensus_ctrl,
         execution_controller: execution_ctrl,
@@ -136,6 +147,8 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         pool_broadcasts: PoolBroadcasts {
             endorsement_sender,
             operation_sender,
+            expired_operation_sender: tokio::sync::broadcast::channel(5000).0,
+            pool_event_sender: DedupBroadcaster::new("test_pool_events".to_string(), 5000, 0),
         },
         pool_controller: pool_ctrl,
         protocol_controller: protocol_ctrl,
@@ -148,5 +161,33 @@ pub(crate) fn grpc_public_service(addr: &SocketAddr) -> MassaPublicGrpc {
         keypair_factory: KeyPairFactory {
             mip_store: mip_store.clone(),
         },
+        response_cache: ResponseCache::new(&grpc_config),
+    }
+}
+
+/// generate a grpc private service, sharing the mocks of the public one
+/// # Arguments
+/// * `addr` - the address to bind to
+/// # Returns
+/// * `MassaPrivateGrpc` - the grpc private service
+pub(crate) fn grpc_private_service(addr: &SocketAddr) -> MassaPrivateGrpc {
+    let public_service = grpc_public_service(addr);
+    MassaPrivateGrpc {
+        consensus_controller: public_service.consensus_controller,
+        execution_controller: public_service.execution_controller,
+        pool_controller: public_service.pool_controller,
+        protocol_controller: public_service.protocol_controller,
+        stop_cv: Arc::new((Mutex::new(false), Condvar::new())),
+        node_wallet: Arc::new(RwLock::new(create_test_wallet(None))),
+        grpc_config: GrpcConfig {
+            name: ServiceName::Private,
+            max_concurrent_streams: ServiceName::Private.default_max_concurrent_streams(),
+            ..public_service.grpc_config
+        },
+        protocol_config: public_service.protocol_config,
+        node_id: public_service.node_id,
+        mip_store: public_service.keypair_factory.mip_store,
+        version: public_service.version,
+        bs_white_black_list: None,
     }
 }
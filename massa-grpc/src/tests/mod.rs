@@ -3,7 +3,19 @@
 #[cfg(test)]
 pub mod mock;
 
+#[cfg(test)]
+mod cache;
+#[cfg(test)]
+mod config;
+#[cfg(test)]
+mod event_filter;
+#[cfg(test)]
+mod metrics;
+#[cfg(test)]
+mod private;
 #[cfg(test)]
 mod public;
 #[cfg(test)]
 mod stream;
+#[cfg(test)]
+mod tls;
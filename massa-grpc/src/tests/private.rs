@@ -0,0 +1,65 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::tests::mock::grpc_private_service;
+use massa_models::config::MIP_STORE_STATS_BLOCK_CONSIDERED;
+use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
+use massa_proto_rs::massa::api::v1::GetMipStatusRequest;
+use massa_proto_rs::massa::model::v1::{ComponentStateId, MipComponent as GrpcMipComponent};
+use massa_time::MassaTime;
+use massa_versioning::versioning::{MipComponent, MipInfo, MipState, MipStatsConfig, MipStore};
+use num::rational::Ratio;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+
+#[tokio::test]
+async fn get_mip_status() {
+    let addr: SocketAddr = "[::]:4046".parse().unwrap();
+    let mut private_server = grpc_private_service(&addr);
+
+    let now = MassaTime::now();
+    let mip_info = MipInfo {
+        name: "MIP-0001".to_string(),
+        version: 1,
+        components: BTreeMap::from([(MipComponent::KeyPair, 1)]),
+        start: now.saturating_add(MassaTime::from_millis(60_000)),
+        timeout: now.saturating_add(MassaTime::from_millis(600_000)),
+        activation_delay: MassaTime::from_millis(60_000),
+    };
+    let mip_stats_config = MipStatsConfig {
+        block_count_considered: MIP_STORE_STATS_BLOCK_CONSIDERED,
+        warn_announced_version_ratio: Ratio::new_raw(30, 100),
+    };
+    private_server.mip_store =
+        MipStore::try_from(([(mip_info.clone(), MipState::new(now))], mip_stats_config)).unwrap();
+
+    let config = private_server.grpc_config.clone();
+    let stop_handle = private_server.serve(&config).await.unwrap();
+    let mut private_client = PrivateServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let response = private_client
+        .get_mip_status(GetMipStatusRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert_eq!(response.mipstatus_entries.len(), 1);
+    let entry = &response.mipstatus_entries[0];
+    // the MIP is not started yet
+    assert_eq!(entry.state_id, ComponentStateId::Defined as i32);
+    let grpc_mip_info = entry.mip_info.as_ref().unwrap();
+    assert_eq!(grpc_mip_info.name, mip_info.name);
+    assert_eq!(grpc_mip_info.version, 1);
+    assert_eq!(grpc_mip_info.components.len(), 1);
+    assert_eq!(
+        grpc_mip_info.components[0].kind,
+        GrpcMipComponent::Keypair as i32
+    );
+    assert_eq!(grpc_mip_info.components[0].version, 1);
+
+    stop_handle.stop();
+}
@@ -7,6 +7,7 @@ use massa_models::address::Address;
 use massa_models::block::BlockGraphStatus;
 use massa_models::block_id::BlockId;
 use massa_models::config::VERSION;
+use massa_models::datastore::DatastoreSerializer;
 use massa_models::slot::Slot;
 use massa_models::stats::ExecutionStats;
 use massa_pool_exports::MockPoolController;
@@ -30,6 +31,7 @@ use massa_protocol_exports::test_exports::tools::{
     create_block, create_block_with_endorsements, create_block_with_operations, create_endorsement,
     create_operation_with_expire_period,
 };
+use massa_serialization::Serializer;
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use std::collections::{BTreeMap, VecDeque};
@@ -160,6 +162,60 @@ async fn get_operations() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn get_operations_unknown_ids() {
+    let addr: SocketAddr = "[::]:4054".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let first_op = create_operation_with_expire_period(&KeyPair::generate(0).unwrap(), 0);
+    let second_op = create_operation_with_expire_period(&KeyPair::generate(0).unwrap(), 0);
+    let unknown_op = create_operation_with_expire_period(&KeyPair::generate(0).unwrap(), 0);
+    public_server
+        .storage
+        .store_operations(vec![first_op.clone(), second_op.clone()]);
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    // the unknown operation is omitted, the others keep the requested order
+    let response = public_client
+        .get_operations(GetOperationsRequest {
+            operation_ids: vec![
+                second_op.id.to_string(),
+                unknown_op.id.to_string(),
+                first_op.id.to_string(),
+            ],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let ids: Vec<String> = response
+        .wrapped_operations
+        .into_iter()
+        .map(|wrapper| wrapper.operation.unwrap().secure_hash)
+        .collect();
+    assert_eq!(ids, vec![second_op.id.to_string(), first_op.id.to_string()]);
+
+    // requesting only unknown operations is not an error either
+    let response = public_client
+        .get_operations(GetOperationsRequest {
+            operation_ids: vec![unknown_op.id.to_string()],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(response.wrapped_operations.is_empty());
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn get_blocks() {
     let addr: SocketAddr = "[::]:4004".parse().unwrap();
@@ -362,9 +418,12 @@ async fn execute_read_only_call() {
                     deferred_credits_execution: vec![],
                     cancel_async_message_execution: vec![],
                     auto_sell_execution: vec![],
+                    digest: Default::default(),
                 },
                 gas_cost: 100,
                 call_result: "toto".as_bytes().to_vec(),
+                gas_profile: None,
+                call_trace: None,
             })
         });
 
@@ -459,6 +518,99 @@ async fn execute_read_only_call() {
     stop_handle.stop();
 }
 
+#[tokio::test]
+async fn execute_read_only_call_max_arguments() {
+    let addr: SocketAddr = "[::]:4048".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    public_server.grpc_config.max_arguments = 2;
+    let config = public_server.grpc_config.clone();
+
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_execute_readonly_request()
+        .times(1)
+        .returning(|req| {
+            assert_eq!(
+                req.call_stack[0]
+                    .operation_datastore
+                    .as_ref()
+                    .map(|datastore| datastore.len()),
+                Some(2)
+            );
+            Ok(massa_execution_exports::ReadOnlyExecutionOutput {
+                out: massa_execution_exports::ExecutionOutput {
+                    slot: Slot::new(1, 5),
+                    block_info: None,
+                    state_changes: massa_final_state::StateChanges::default(),
+                    events: EventStore::default(),
+                    #[cfg(feature = "execution-trace")]
+                    slot_trace: None,
+                    #[cfg(feature = "dump-block")]
+                    storage: None,
+                    deferred_credits_execution: vec![],
+                    cancel_async_message_execution: vec![],
+                    auto_sell_execution: vec![],
+                    digest: Default::default(),
+                },
+                gas_cost: 100,
+                call_result: vec![],
+                gas_profile: None,
+                call_trace: None,
+            })
+        });
+    public_server.execution_controller = exec_ctrl;
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    // start grpc client and connect to the server
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let bytecode_call = |entry_count: u8| {
+        let datastore: BTreeMap<Vec<u8>, Vec<u8>> =
+            (0..entry_count).map(|i| (vec![i], vec![i])).collect();
+        let mut operation_datastore = Vec::new();
+        DatastoreSerializer::new()
+            .serialize(&datastore, &mut operation_datastore)
+            .unwrap();
+        ExecuteReadOnlyCallRequest {
+            call: Some(ReadOnlyExecutionCall {
+                max_gas: u64::MAX,
+                call_stack: vec![],
+                caller_address: None,
+                target: Some(Target::BytecodeCall(
+                    massa_proto_rs::massa::model::v1::BytecodeExecution {
+                        bytecode: vec![],
+                        operation_datastore,
+                    },
+                )),
+                fee: None,
+            }),
+        }
+    };
+
+    // exactly at the limit
+    public_client
+        .execute_read_only_call(bytecode_call(2))
+        .await
+        .unwrap();
+
+    // one entry above the limit, rejected before reaching the execution
+    let status = public_client
+        .execute_read_only_call(bytecode_call(3))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    assert!(status
+        .message()
+        .contains("Only a maximum of 2 datastore entries are accepted per read-only call"));
+
+    stop_handle.stop();
+}
+
 #[tokio::test]
 async fn get_endorsements() {
     let addr: SocketAddr = "[::]:4008".parse().unwrap();
@@ -1535,3 +1687,54 @@ async fn search_operations() {
 
     stop_handle.stop();
 }
+
+#[tokio::test]
+async fn request_id() {
+    let addr: SocketAddr = "[::]:4043".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+
+    let mut exec_ctrl = Box::new(MockExecutionController::new());
+    exec_ctrl
+        .expect_query_state()
+        .returning(|_| massa_execution_exports::ExecutionQueryResponse {
+            responses: vec![],
+            candidate_cursor: massa_models::slot::Slot::new(0, 2),
+            final_cursor: Slot::new(0, 0),
+            final_state_fingerprint: massa_hash::Hash::compute_from(&Vec::new()),
+        });
+    public_server.execution_controller = exec_ctrl;
+
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    // the id provided by the client is echoed back
+    let mut request = tonic::Request::new(GetStatusRequest {});
+    request
+        .metadata_mut()
+        .insert("x-request-id", "client-id-42".parse().unwrap());
+    let response = public_client.get_status(request).await.unwrap();
+    assert_eq!(
+        response.metadata().get("x-request-id").unwrap(),
+        "client-id-42"
+    );
+
+    // an id is generated if none is provided, or if the provided one is not valid
+    let response = public_client.get_status(GetStatusRequest {}).await.unwrap();
+    let first_id = response.metadata().get("x-request-id").unwrap().clone();
+    let mut request = tonic::Request::new(GetStatusRequest {});
+    request
+        .metadata_mut()
+        .insert("x-request-id", "invalid id".parse().unwrap());
+    let response = public_client.get_status(request).await.unwrap();
+    let second_id = response.metadata().get("x-request-id").unwrap().clone();
+    assert_ne!(second_id, "invalid id");
+    assert_ne!(first_id, second_id);
+
+    stop_handle.stop();
+}
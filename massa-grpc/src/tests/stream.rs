@@ -1,12 +1,22 @@
 // Copyright (c) 2023 MASSA LABS <info@massa.net>
 
+use crate::stream::new_slot_execution_digests::{
+    NewSlotExecutionDigestsRequest, SlotExecutionDigestServiceClient,
+};
 use crate::tests::mock::grpc_public_service;
 use core::panic;
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_consensus_exports::MockConsensusController;
 use massa_execution_exports::{ExecutionOutput, MockExecutionController, SlotExecutionOutput};
+use massa_hash::Hash;
 use massa_models::{
-    address::Address, amount::Amount, block::FilledBlock, secure_share::SecureShareSerializer,
-    slot::Slot, stats::ExecutionStats,
+    address::Address,
+    amount::Amount,
+    block::FilledBlock,
+    output_event::{EventExecutionContext, SCOutputEvent},
+    secure_share::SecureShareSerializer,
+    slot::Slot,
+    stats::ExecutionStats,
 };
 use massa_pool_exports::MockPoolController;
 use massa_proto_rs::massa::{
@@ -159,7 +169,9 @@ async fn new_operations() {
     let addr: SocketAddr = "[::]:4018".parse().unwrap();
     let mut public_server = grpc_public_service(&addr);
     let config = public_server.grpc_config.clone();
-    let (op_tx, _op_rx) = tokio::sync::broadcast::channel(10);
+    // dedup disabled: the same object is sent again for each tested filter
+    let op_tx = DedupBroadcaster::new("test_operations".to_string(), 10, 0);
+    let _op_rx = op_tx.subscribe();
     let keypair = massa_signature::KeyPair::generate(0).unwrap();
     let address = Address::from_public_key(&keypair.get_public_key());
     public_server.pool_broadcasts.operation_sender = op_tx.clone();
@@ -384,7 +396,9 @@ async fn new_blocks() {
     let addr: SocketAddr = "[::]:4019".parse().unwrap();
     let mut public_server = grpc_public_service(&addr);
     let config = public_server.grpc_config.clone();
-    let (block_tx, _block_rx) = tokio::sync::broadcast::channel(10);
+    // dedup disabled: the same object is sent again for each tested filter
+    let block_tx = DedupBroadcaster::new("test_blocks".to_string(), 10, 0);
+    let _block_rx = block_tx.subscribe();
 
     public_server.consensus_broadcasts.block_sender = block_tx.clone();
 
@@ -653,7 +667,9 @@ async fn new_endorsements() {
     let mut public_server = grpc_public_service(&addr);
     let config = public_server.grpc_config.clone();
 
-    let (endorsement_tx, _endorsement_rx) = tokio::sync::broadcast::channel(10);
+    // dedup disabled: the same object is sent again for each tested filter
+    let endorsement_tx = DedupBroadcaster::new("test_endorsements".to_string(), 10, 0);
+    let _endorsement_rx = endorsement_tx.subscribe();
 
     public_server.pool_broadcasts.endorsement_sender = endorsement_tx.clone();
 
@@ -852,7 +868,9 @@ async fn new_filled_blocks() {
     let mut public_server = grpc_public_service(&addr);
     let config = public_server.grpc_config.clone();
 
-    let (filled_block_tx, _filled_block_rx) = tokio::sync::broadcast::channel(10);
+    // dedup disabled: the same object is sent again for each tested filter
+    let filled_block_tx = DedupBroadcaster::new("test_filled_blocks".to_string(), 10, 0);
+    let _filled_block_rx = filled_block_tx.subscribe();
 
     public_server.consensus_broadcasts.filled_block_sender = filled_block_tx.clone();
 
@@ -867,6 +885,7 @@ async fn new_filled_blocks() {
     let filled_block = FilledBlock {
         header: block.content.header.clone(),
         operations: vec![],
+        locally_produced: false,
     };
 
     let mut public_client = PublicServiceClient::connect(format!(
@@ -1075,12 +1094,13 @@ async fn new_slot_execution_outputs() {
         deferred_credits_execution: vec![],
         cancel_async_message_execution: vec![],
         auto_sell_execution: vec![],
+        digest: Default::default(),
     };
 
     let (tx_request, rx) = tokio::sync::mpsc::channel(10);
     let request_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
     let keypair = KeyPair::generate(0).unwrap();
-    let _address = Address::from_public_key(&keypair.get_public_key());
+    let address = Address::from_public_key(&keypair.get_public_key());
 
     let mut public_client = PublicServiceClient::connect(format!(
         "grpc://localhost:{}",
@@ -1196,15 +1216,33 @@ async fn new_slot_execution_outputs() {
     // start slot is after block slot
     assert!(result.is_err());
 
-    // TODO add test when filter is updated
+    // only the events matching the event filter are sent
+    let other_keypair = KeyPair::generate(0).unwrap();
+    let other_address = Address::from_public_key(&other_keypair.get_public_key());
+    let mut exec_output_2 = exec_output_1.clone();
+    for caller in [address, other_address] {
+        exec_output_2.events.push(SCOutputEvent {
+            context: EventExecutionContext {
+                slot: Slot::new(1, 5),
+                block: None,
+                read_only: false,
+                index_in_slot: 0,
+                call_stack: vec![caller].into(),
+                origin_operation_id: None,
+                is_final: false,
+                is_error: false,
+            },
+            data: caller.to_string(),
+        });
+    }
 
-    /*     filter = massa_proto_rs::massa::api::v1::NewSlotExecutionOutputsFilter {
+    filter = massa_proto_rs::massa::api::v1::NewSlotExecutionOutputsFilter {
         filter: Some(
             massa_proto_rs::massa::api::v1::new_slot_execution_outputs_filter::Filter::EventFilter(
                 massa_proto_rs::massa::api::v1::ExecutionEventFilter {
                     filter: Some(
-                        massa_proto_rs::massa::api::v1::execution_event_filter::Filter::OriginalOperationId( "O1q4CBcuYo8YANEV34W4JRWVHrzcYns19VJfyAB7jT4qfitAnMC"
-                                    .to_string()
+                        massa_proto_rs::massa::api::v1::execution_event_filter::Filter::CallerAddress(
+                            address.to_string(),
                         ),
                     ),
                 },
@@ -1221,12 +1259,83 @@ async fn new_slot_execution_outputs() {
     tokio::time::sleep(Duration::from_millis(50)).await;
 
     slot_tx
-        .send(SlotExecutionOutput::ExecutedSlot(exec_output_1.clone()))
+        .send(SlotExecutionOutput::ExecutedSlot(exec_output_2))
         .unwrap();
 
-    let result = tokio::time::timeout(Duration::from_secs(2), resp_stream.next()).await;
-    dbg!(&result);
-    assert!(result.is_err()); */
+    let result = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let events = result.output.unwrap().execution_output.unwrap().events;
+    assert_eq!(events.len(), 1);
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn new_slot_execution_outputs_lagged() {
+    let addr: SocketAddr = "[::]:4041".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    public_server.grpc_config.max_channel_size = 1;
+    let config = public_server.grpc_config.clone();
+
+    let (slot_tx, _slot_rx) = tokio::sync::broadcast::channel(2);
+    public_server
+        .execution_channels
+        .slot_execution_output_sender = slot_tx.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let (tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let request_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+    let mut resp_stream = public_client
+        .new_slot_execution_outputs(request_stream)
+        .await
+        .unwrap()
+        .into_inner();
+    tx_request
+        .send(NewSlotExecutionOutputsRequest { filters: vec![] })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    // emit more outputs than the broadcast channel can hold before the stream can forward them
+    for period in 1..=10 {
+        slot_tx
+            .send(SlotExecutionOutput::ExecutedSlot(ExecutionOutput {
+                slot: Slot::new(period, 0),
+                block_info: None,
+                state_changes: massa_final_state::StateChanges::default(),
+                events: Default::default(),
+                #[cfg(feature = "execution-trace")]
+                slot_trace: None,
+                #[cfg(feature = "dump-block")]
+                storage: None,
+                deferred_credits_execution: vec![],
+                cancel_async_message_execution: vec![],
+                auto_sell_execution: vec![],
+                digest: Default::default(),
+            }))
+            .unwrap();
+    }
+
+    // the client is told that outputs were skipped
+    let status = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::DataLoss);
+    assert!(status
+        .message()
+        .contains("8 slot execution outputs were skipped"));
 
     stop_handle.stop();
 }
@@ -1456,7 +1565,124 @@ async fn send_operations() {
 
     match result.result.unwrap() {
         massa_proto_rs::massa::api::v1::send_operations_response::Result::Error(err) => {
-            assert_eq!(err.message, "too many operations per message");
+            assert_eq!(
+                err.message,
+                "too many operations received. Only a maximum of 2 operations are accepted per message"
+            );
+        }
+        _ => {
+            panic!("should be error");
+        }
+    }
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn send_operations_max_arguments() {
+    let addr: SocketAddr = "[::]:4047".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+
+    let mut pool_ctrl = Box::new(MockPoolController::new());
+    pool_ctrl.expect_clone_box().returning(|| {
+        let mut pool_ctrl = Box::new(MockPoolController::new());
+
+        pool_ctrl.expect_add_operations().returning(|_| ());
+
+        pool_ctrl
+    });
+
+    let mut protocol_ctrl = Box::new(MockProtocolController::new());
+    protocol_ctrl.expect_clone_box().returning(|| {
+        let mut ctrl = Box::new(MockProtocolController::new());
+
+        ctrl.expect_propagate_operations().returning(|_| Ok(()));
+
+        ctrl
+    });
+
+    public_server.pool_controller = pool_ctrl;
+    public_server.protocol_controller = protocol_ctrl;
+    // the request limit is lower than the network limit
+    public_server.grpc_config.max_operations_per_message = 10;
+    public_server.grpc_config.max_arguments = 2;
+
+    let config = public_server.grpc_config.clone();
+
+    let (tx, rx) = tokio::sync::mpsc::channel(10);
+    let request_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    let mut resp_stream = public_client
+        .send_operations(request_stream)
+        .await
+        .unwrap()
+        .into_inner();
+
+    let ops: Vec<_> = (0..3)
+        .map(|_| create_operation_with_expire_period(&KeyPair::generate(0).unwrap(), 11950000))
+        .collect();
+    let buffers: Vec<Vec<u8>> = ops
+        .iter()
+        .map(|op| {
+            let mut buffer: Vec<u8> = Vec::new();
+            SecureShareSerializer::new()
+                .serialize(op, &mut buffer)
+                .unwrap();
+            buffer
+        })
+        .collect();
+
+    // exactly at the limit
+    tx.send(SendOperationsRequest {
+        operations: buffers[..2].to_vec(),
+    })
+    .await
+    .unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+
+    match result.result.unwrap() {
+        massa_proto_rs::massa::api::v1::send_operations_response::Result::OperationIds(ope_id) => {
+            assert_eq!(ope_id.operation_ids.len(), 2);
+        }
+        massa_proto_rs::massa::api::v1::send_operations_response::Result::Error(e) => {
+            panic!("Send operations error: {:?}", e);
+        }
+    }
+
+    // one operation above the limit
+    tx.send(SendOperationsRequest {
+        operations: buffers.clone(),
+    })
+    .await
+    .unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+
+    match result.result.unwrap() {
+        massa_proto_rs::massa::api::v1::send_operations_response::Result::Error(err) => {
+            assert_eq!(err.code, tonic::Code::InvalidArgument as i32);
+            assert_eq!(
+                err.message,
+                "too many operations received. Only a maximum of 2 operations are accepted per message"
+            );
         }
         _ => {
             panic!("should be error");
@@ -1668,3 +1894,206 @@ async fn send_blocks() {
 
     stop_handle.stop();
 }
+
+#[tokio::test]
+async fn stalled_client_dropped() {
+    let addr: SocketAddr = "[::]:4044".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    public_server.grpc_config.max_channel_size = 1;
+    public_server.grpc_config.stream_client_idle_timeout = Some(Duration::from_millis(200));
+    let config = public_server.grpc_config.clone();
+    let block_tx = DedupBroadcaster::new("test_blocks".to_string(), 10, 0);
+    let _block_rx = block_tx.subscribe();
+    public_server.consensus_broadcasts.block_sender = block_tx.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let operations = (0..100)
+        .map(|expire_period| create_operation_with_expire_period(&keypair, expire_period))
+        .collect();
+    let block = create_block_with_operations(&keypair, Slot::new(1, 0), operations);
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+    let (tx_request, rx) = tokio::sync::mpsc::channel(10);
+    let request_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    // the response stream is kept open but never read
+    let _resp_stream = public_client
+        .new_blocks(request_stream)
+        .await
+        .unwrap()
+        .into_inner();
+    tx_request
+        .send(NewBlocksRequest { filters: vec![] })
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(block_tx.receiver_count(), 2);
+
+    // broadcast blocks until the flow control windows and the channel are full,
+    // and the stream is dropped after the timeout
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(20);
+    while block_tx.receiver_count() > 1 && tokio::time::Instant::now() < deadline {
+        block_tx.send(block.clone()).unwrap();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    // the stream released its subscription while the client is still connected
+    assert_eq!(block_tx.receiver_count(), 1);
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn max_concurrent_streams() {
+    let addr: SocketAddr = "[::]:4045".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    public_server.grpc_config.max_concurrent_streams = Some(1);
+    let config = public_server.grpc_config.clone();
+    let block_tx = DedupBroadcaster::new("test_blocks".to_string(), 10, 0);
+    public_server.consensus_broadcasts.block_sender = block_tx.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut public_client = PublicServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+    // let the client receive the settings of the server
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let (first_tx, first_rx) = tokio::sync::mpsc::channel(10);
+    let first_stream = public_client
+        .new_blocks(tokio_stream::wrappers::ReceiverStream::new(first_rx))
+        .await
+        .unwrap()
+        .into_inner();
+    first_tx
+        .send(NewBlocksRequest { filters: vec![] })
+        .await
+        .unwrap();
+
+    // the connection is at its limit: a second stream is not opened
+    let (second_tx, second_rx) = tokio::sync::mpsc::channel(10);
+    let mut second_client = public_client.clone();
+    let second_call = tokio::spawn(async move {
+        second_client
+            .new_blocks(tokio_stream::wrappers::ReceiverStream::new(second_rx))
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(!second_call.is_finished());
+
+    // closing the first stream releases its slot for the second one
+    drop(first_stream);
+    drop(first_tx);
+    let second_stream = tokio::time::timeout(Duration::from_secs(5), second_call)
+        .await
+        .expect("second stream not opened after the first one was closed")
+        .unwrap();
+    assert!(second_stream.is_ok());
+    drop(second_tx);
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn new_slot_execution_digests() {
+    let addr: SocketAddr = "[::]:4055".parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    let config = public_server.grpc_config.clone();
+
+    let (slot_tx, _slot_rx) = tokio::sync::broadcast::channel(10);
+    public_server
+        .execution_channels
+        .slot_execution_output_sender = slot_tx.clone();
+
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let mut digest_client = SlotExecutionDigestServiceClient::connect(format!(
+        "grpc://localhost:{}",
+        addr.to_string().split(':').last().unwrap()
+    ))
+    .await
+    .unwrap();
+    let mut resp_stream = digest_client
+        .new_slot_execution_digests(NewSlotExecutionDigestsRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let slot = Slot::new(1, 5);
+    let fingerprint = Hash::compute_from(b"final state");
+    let ledger_fingerprint = Hash::compute_from(b"ledger");
+    let exec_output = ExecutionOutput {
+        slot,
+        block_info: None,
+        state_changes: massa_final_state::StateChanges::default(),
+        events: Default::default(),
+        #[cfg(feature = "execution-trace")]
+        slot_trace: None,
+        #[cfg(feature = "dump-block")]
+        storage: None,
+        deferred_credits_execution: vec![],
+        cancel_async_message_execution: vec![],
+        auto_sell_execution: vec![],
+        digest: massa_execution_exports::SlotExecutionDigest {
+            ledger_changes_count: 2,
+            async_messages_emitted: 1,
+            async_messages_taken: 0,
+            final_state: None,
+        },
+    };
+    slot_tx
+        .send(SlotExecutionOutput::ExecutedSlot(exec_output.clone()))
+        .unwrap();
+
+    let digest = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap()
+        .digest
+        .unwrap();
+    assert_eq!(digest.slot, Some(slot.into()));
+    assert!(!digest.is_final);
+    assert_eq!(digest.ledger_changes_count, 2);
+    assert_eq!(digest.async_messages_emitted, 1);
+    assert!(digest.final_state.is_none());
+
+    // once final, the digest carries the fingerprints of the final state
+    let mut final_output = exec_output;
+    final_output.digest.final_state = Some(massa_execution_exports::FinalStateDigest {
+        change_id: slot,
+        fingerprint,
+        component_fingerprints: [("ledger".to_string(), ledger_fingerprint)].into(),
+    });
+    slot_tx
+        .send(SlotExecutionOutput::FinalizedSlot(final_output))
+        .unwrap();
+
+    let digest = tokio::time::timeout(Duration::from_secs(5), resp_stream.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap()
+        .digest
+        .unwrap();
+    assert!(digest.is_final);
+    let final_state = digest.final_state.unwrap();
+    assert_eq!(final_state.change_id, Some(slot.into()));
+    assert_eq!(final_state.fingerprint, fingerprint.to_string());
+    assert_eq!(
+        final_state.component_fingerprints.get("ledger"),
+        Some(&ledger_fingerprint.to_string())
+    );
+
+    stop_handle.stop();
+}
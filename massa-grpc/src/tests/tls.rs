@@ -0,0 +1,275 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use crate::tests::mock::grpc_public_service;
+use crate::tls::{der_element, DER_INTEGER, DER_OCTET_STRING, DER_SEQUENCE};
+use crate::tls_status::{GetTlsCertificateRequest, TlsStatusServiceClient};
+use base64::Engine;
+use massa_sdk::cert_manager::{gen_cert_for_ca, gen_signed_cert};
+use massa_time::MassaTime;
+use sha2::{Digest, Sha256};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::rustls::{Certificate, ClientConfig, RootCertStore, ServerName};
+use tokio_rustls::TlsConnector;
+use tonic::transport::{Channel, ClientTlsConfig};
+
+/// PKCS#8 RSA private key, which rcgen can't generate
+const RSA_PRIVATE_KEY_PEM: &str = include_str!("rsa_private_key.pem");
+
+/// write a new server certificate signed by `ca` and return its fingerprint
+fn write_server_certificate(ca: &rcgen::Certificate, cert_path: &Path, key_path: &Path) -> String {
+    let (cert_pem, key_pem) = gen_signed_cert(ca, vec![]).unwrap();
+    std::fs::write(cert_path, &cert_pem).unwrap();
+    std::fs::write(key_path, key_pem).unwrap();
+    let der = rustls_pemfile::certs(&mut cert_pem.as_bytes()).unwrap();
+    Sha256::digest(&der[0])
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// write a server certificate of `key_pair` signed by `ca` and return its fingerprint
+fn write_certificate_of(
+    ca: &rcgen::Certificate,
+    key_pair: rcgen::KeyPair,
+    cert_path: &Path,
+) -> String {
+    let mut params = rcgen::CertificateParams::new(vec!["localhost".to_string()]);
+    params.alg = key_pair.algorithm();
+    params.key_pair = Some(key_pair);
+    let cert_pem = rcgen::Certificate::from_params(params)
+        .unwrap()
+        .serialize_pem_with_signer(ca)
+        .unwrap();
+    std::fs::write(cert_path, &cert_pem).unwrap();
+    let der = rustls_pemfile::certs(&mut cert_pem.as_bytes()).unwrap();
+    Sha256::digest(&der[0])
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// private key wrapped in a PKCS#8 `PrivateKeyInfo`
+fn pkcs8_inner_key(pkcs8_der: &[u8]) -> Vec<u8> {
+    // PrivateKeyInfo ::= SEQUENCE { version, privateKeyAlgorithm, privateKey OCTET STRING }
+    let (_, fields, _) = der_element(pkcs8_der, DER_SEQUENCE).unwrap();
+    let (_, _, fields) = der_element(fields, DER_INTEGER).unwrap();
+    let (_, _, fields) = der_element(fields, DER_SEQUENCE).unwrap();
+    let (_, private_key, _) = der_element(fields, DER_OCTET_STRING).unwrap();
+    private_key.to_vec()
+}
+
+/// PEM encoding of `der` under `label`
+fn pem(label: &str, der: &[u8]) -> String {
+    format!(
+        "-----BEGIN {label}-----\n{}\n-----END {label}-----\n",
+        base64::engine::general_purpose::STANDARD.encode(der)
+    )
+}
+
+/// serve with the reloadable TLS configuration and return the fingerprint of the served certificate
+async fn served_fingerprint(
+    port: u16,
+    ca: &rcgen::Certificate,
+    cert_path: &Path,
+    key_path: &Path,
+) -> String {
+    let addr: SocketAddr = format!("[::]:{}", port).parse().unwrap();
+    let mut public_server = grpc_public_service(&addr);
+    public_server.grpc_config.enable_tls = true;
+    public_server.grpc_config.server_certificate_path = cert_path.to_path_buf();
+    public_server.grpc_config.server_private_key_path = key_path.to_path_buf();
+    public_server.grpc_config.certificate_reload_interval = Some(Duration::from_secs(60));
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    let (_connection, fingerprint) = connect(port, ca).await;
+    stop_handle.stop();
+    fingerprint
+}
+
+/// open a TLS connection to the server and return it with the fingerprint of the served certificate
+async fn connect(port: u16, ca: &rcgen::Certificate) -> (TlsStream<TcpStream>, String) {
+    let mut roots = RootCertStore::empty();
+    roots
+        .add(&Certificate(ca.serialize_der().unwrap()))
+        .unwrap();
+    let mut client_config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    client_config.alpn_protocols = vec![b"h2".to_vec()];
+    let tcp_stream = TcpStream::connect(("localhost", port)).await.unwrap();
+    let tls_stream = TlsConnector::from(Arc::new(client_config))
+        .connect(ServerName::try_from("localhost").unwrap(), tcp_stream)
+        .await
+        .unwrap();
+    let peer_certificate = tls_stream.get_ref().1.peer_certificates().unwrap()[0].clone();
+    let fingerprint = Sha256::digest(&peer_certificate.0)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    (tls_stream, fingerprint)
+}
+
+/// check that the server did not close the connection
+async fn assert_open(tls_stream: &mut TlsStream<TcpStream>) {
+    let mut buf = [0u8; 1024];
+    loop {
+        match tokio::time::timeout(Duration::from_millis(200), tls_stream.read(&mut buf)).await {
+            // nothing more to read, the connection is idle
+            Err(_) => return,
+            Ok(Ok(0)) => panic!("connection closed by the server"),
+            Ok(Ok(_)) => continue,
+            Ok(Err(e)) => panic!("connection error: {}", e),
+        }
+    }
+}
+
+#[tokio::test]
+async fn certificate_hot_reload() {
+    let addr: SocketAddr = "[::]:4040".parse().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let cert_path = dir.path().join("server.pem");
+    let key_path = dir.path().join("server.key");
+    let ca = gen_cert_for_ca().unwrap();
+    let first_fingerprint = write_server_certificate(&ca, &cert_path, &key_path);
+
+    let mut public_server = grpc_public_service(&addr);
+    public_server.grpc_config.enable_tls = true;
+    public_server.grpc_config.server_certificate_path = cert_path.clone();
+    public_server.grpc_config.server_private_key_path = key_path.clone();
+    public_server.grpc_config.certificate_reload_interval = Some(Duration::from_millis(50));
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+
+    let (mut old_connection, fingerprint) = connect(addr.port(), &ca).await;
+    assert_eq!(fingerprint, first_fingerprint);
+    assert_eq!(
+        stop_handle.tls_certificate().unwrap().fingerprint,
+        first_fingerprint
+    );
+
+    // rotate the certificate
+    let second_fingerprint = write_server_certificate(&ca, &cert_path, &key_path);
+    assert_ne!(second_fingerprint, first_fingerprint);
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let (_new_connection, fingerprint) = connect(addr.port(), &ca).await;
+    assert_eq!(fingerprint, second_fingerprint);
+    assert_eq!(
+        stop_handle.tls_certificate().unwrap().fingerprint,
+        second_fingerprint
+    );
+    assert_open(&mut old_connection).await;
+
+    // a key that doesn't match the certificate is rejected and the current certificate is kept
+    let (_, other_key_pem) = gen_signed_cert(&ca, vec![]).unwrap();
+    std::fs::write(&key_path, other_key_pem).unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let (_, fingerprint) = connect(addr.port(), &ca).await;
+    assert_eq!(fingerprint, second_fingerprint);
+    assert_eq!(
+        stop_handle.tls_certificate().unwrap().fingerprint,
+        second_fingerprint
+    );
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn tls_status_service() {
+    let addr: SocketAddr = "[::]:4056".parse().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let cert_path = dir.path().join("server.pem");
+    let key_path = dir.path().join("server.key");
+    let ca = gen_cert_for_ca().unwrap();
+    let fingerprint = write_server_certificate(&ca, &cert_path, &key_path);
+
+    // without certificate reload, the certificate is served by tonic
+    let mut public_server = grpc_public_service(&addr);
+    public_server.grpc_config.enable_tls = true;
+    public_server.grpc_config.server_certificate_path = cert_path;
+    public_server.grpc_config.server_private_key_path = key_path;
+    let config = public_server.grpc_config.clone();
+    let stop_handle = public_server.serve(&config).await.unwrap();
+    let certificate = stop_handle.tls_certificate().unwrap();
+    assert_eq!(certificate.fingerprint, fingerprint);
+    assert!(certificate.not_after > MassaTime::now());
+
+    let tls_config = ClientTlsConfig::new()
+        .ca_certificate(tonic::transport::Certificate::from_pem(
+            ca.serialize_pem().unwrap(),
+        ))
+        .domain_name("localhost");
+    let channel = Channel::from_static("https://localhost:4056")
+        .tls_config(tls_config)
+        .unwrap()
+        .connect()
+        .await
+        .unwrap();
+    let response = TlsStatusServiceClient::new(channel)
+        .get_tls_certificate(GetTlsCertificateRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(response.fingerprint, fingerprint);
+    assert_eq!(response.not_after, certificate.not_after.as_millis());
+
+    stop_handle.stop();
+}
+
+#[tokio::test]
+async fn rsa_and_sec1_private_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let cert_path = dir.path().join("server.pem");
+    let key_path = dir.path().join("server.key");
+    let ca = gen_cert_for_ca().unwrap();
+
+    // PKCS#1 RSA key
+    let key_pair = rcgen::KeyPair::from_pem(RSA_PRIVATE_KEY_PEM).unwrap();
+    let rsa_key = pkcs8_inner_key(&key_pair.serialize_der());
+    let fingerprint = write_certificate_of(&ca, key_pair, &cert_path);
+    std::fs::write(&key_path, pem("RSA PRIVATE KEY", &rsa_key)).unwrap();
+    assert_eq!(
+        served_fingerprint(4057, &ca, &cert_path, &key_path).await,
+        fingerprint
+    );
+
+    // SEC1 EC key
+    let key_pair = rcgen::KeyPair::generate(&rcgen::PKCS_ECDSA_P256_SHA256).unwrap();
+    let ec_key = pkcs8_inner_key(&key_pair.serialize_der());
+    let fingerprint = write_certificate_of(&ca, key_pair, &cert_path);
+    std::fs::write(&key_path, pem("EC PRIVATE KEY", &ec_key)).unwrap();
+    assert_eq!(
+        served_fingerprint(4058, &ca, &cert_path, &key_path).await,
+        fingerprint
+    );
+}
+
+#[tokio::test]
+async fn mismatched_private_key() {
+    let addr: SocketAddr = "[::]:4059".parse().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let cert_path = dir.path().join("server.pem");
+    let key_path = dir.path().join("server.key");
+    let ca = gen_cert_for_ca().unwrap();
+    write_server_certificate(&ca, &cert_path, &key_path);
+    let (_, other_key_pem) = gen_signed_cert(&ca, vec![]).unwrap();
+    std::fs::write(&key_path, other_key_pem).unwrap();
+
+    let mut public_server = grpc_public_service(&addr);
+    public_server.grpc_config.enable_tls = true;
+    public_server.grpc_config.server_certificate_path = cert_path;
+    public_server.grpc_config.server_private_key_path = key_path;
+    let config = public_server.grpc_config.clone();
+    let error = public_server.serve(&config).await.err().unwrap();
+    assert!(error
+        .to_string()
+        .contains("TLS private key does not match the certificate"));
+}
@@ -0,0 +1,414 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! TLS termination of the gRPC server with hot reload of the certificates.
+//!
+//! The certificate files are polled for modification. A modified set is validated
+//! (the key must parse and match the certificate) before being swapped in: new connections
+//! are then accepted with the new certificate while the established ones keep the old one.
+//! If the new files are invalid, the server keeps serving the previous certificate.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime};
+
+use futures_util::StreamExt;
+use hyper::server::conn::AddrStream;
+use massa_time::MassaTime;
+use parking_lot::{Mutex, RwLock};
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::server::TcpIncoming;
+use tracing::{debug, info, warn};
+
+use crate::config::GrpcConfig;
+use crate::error::GrpcError;
+
+/// max duration of a TLS handshake before the connection is dropped
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Certificate currently served by the gRPC server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateInfo {
+    /// hex encoded SHA-256 of the DER certificate
+    pub fingerprint: String,
+    /// end of validity of the certificate
+    pub not_after: MassaTime,
+}
+
+/// Source of the certificate served to the new connections
+#[derive(Clone)]
+pub(crate) enum ServedCertificate {
+    /// certificate of the reloadable TLS configuration, which changes with its files
+    Reloadable(Arc<ReloadableTls>),
+    /// certificate loaded once at startup
+    Static(CertificateInfo),
+}
+
+impl ServedCertificate {
+    /// Certificate currently used for new connections
+    pub(crate) fn info(&self) -> CertificateInfo {
+        match self {
+            ServedCertificate::Reloadable(tls) => tls.certificate_info(),
+            ServedCertificate::Static(info) => info.clone(),
+        }
+    }
+}
+
+/// Describe the certificate of the gRPC configuration, checking that the key belongs to it
+pub(crate) fn load_certificate_info(config: &GrpcConfig) -> Result<CertificateInfo, GrpcError> {
+    let certificates = read_certificates(&config.server_certificate_path)?;
+    let private_key = read_private_key(&config.server_private_key_path)?;
+    check_key_pair(&certificates[0], &private_key)
+}
+
+/// TLS configuration of the server, reloaded when its files change
+pub(crate) struct ReloadableTls {
+    /// server certificate, server private key and, with mTLS, client certificate authority root
+    paths: Vec<PathBuf>,
+    /// whether client certificates are required
+    mtls: bool,
+    /// ALPN protocols announced to the clients
+    alpn_protocols: Vec<Vec<u8>>,
+    /// configuration used to accept new connections, and its certificate
+    current: RwLock<(Arc<ServerConfig>, CertificateInfo)>,
+    /// last modification times of the files of the current configuration
+    modified: Mutex<Vec<Option<SystemTime>>>,
+}
+
+impl ReloadableTls {
+    /// Load the TLS configuration from the files of the gRPC configuration
+    pub(crate) fn load(config: &GrpcConfig) -> Result<Self, GrpcError> {
+        let mut paths = vec![
+            config.server_certificate_path.clone(),
+            config.server_private_key_path.clone(),
+        ];
+        if config.enable_mtls {
+            paths.push(config.client_certificate_authority_root_path.clone());
+        }
+        let mut alpn_protocols = vec![b"h2".to_vec()];
+        if config.accept_http1 {
+            alpn_protocols.push(b"http/1.1".to_vec());
+        }
+        let modified = get_modification_times(&paths);
+        let (server_config, info) =
+            load_server_config(&paths, config.enable_mtls, &alpn_protocols)?;
+        Ok(ReloadableTls {
+            paths,
+            mtls: config.enable_mtls,
+            alpn_protocols,
+            current: RwLock::new((Arc::new(server_config), info)),
+            modified: Mutex::new(modified),
+        })
+    }
+
+    /// Acceptor using the current certificate
+    fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.current.read().0.clone())
+    }
+
+    /// Certificate used for new connections
+    pub(crate) fn certificate_info(&self) -> CertificateInfo {
+        self.current.read().1.clone()
+    }
+
+    /// Reload the configuration if one of its files was modified since the last attempt
+    fn reload_if_modified(&self) {
+        let modified = get_modification_times(&self.paths);
+        {
+            let mut last_modified = self.modified.lock();
+            if *last_modified == modified {
+                return;
+            }
+            // don't retry invalid files until they are modified again
+            *last_modified = modified;
+        }
+        match load_server_config(&self.paths, self.mtls, &self.alpn_protocols) {
+            Ok((server_config, info)) => {
+                info!(
+                    "gRPC TLS certificate reloaded: fingerprint {}, valid until {}",
+                    info.fingerprint,
+                    info.not_after.format_instant()
+                );
+                *self.current.write() = (Arc::new(server_config), info);
+            }
+            Err(e) => warn!(
+                "gRPC TLS certificate files changed but could not be loaded, keeping the previous certificate: {}",
+                e
+            ),
+        }
+    }
+}
+
+/// Periodically reload the TLS configuration, until it is dropped
+pub(crate) fn spawn_certificate_reload(tls: Weak<ReloadableTls>, interval: Duration) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            match tls.upgrade() {
+                Some(tls) => tls.reload_if_modified(),
+                None => break,
+            }
+        }
+    });
+}
+
+/// Accept TCP connections on the configured address and perform their TLS handshake
+/// with the current certificate. The handshakes run concurrently so that a slow client
+/// doesn't delay the others.
+pub(crate) fn tls_incoming(
+    config: &GrpcConfig,
+    tls: Arc<ReloadableTls>,
+) -> Result<ReceiverStream<Result<TlsStream<AddrStream>, std::io::Error>>, GrpcError> {
+    let mut tcp_incoming = TcpIncoming::new(config.bind, config.tcp_nodelay, config.tcp_keepalive)
+        .map_err(|e| GrpcError::InternalServerError(format!("failed to bind gRPC: {}", e)))?;
+    let (sender, receiver) = mpsc::channel(config.max_channel_size);
+    tokio::spawn(async move {
+        loop {
+            let stream = tokio::select! {
+                // the server was stopped
+                _ = sender.closed() => break,
+                stream = tcp_incoming.next() => match stream {
+                    Some(Ok(stream)) => stream,
+                    Some(Err(e)) => {
+                        debug!("gRPC failed to accept a TCP connection: {}", e);
+                        continue;
+                    }
+                    None => break,
+                },
+            };
+            let acceptor = tls.acceptor();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(tls_stream)) => {
+                        let _ = sender.send(Ok(tls_stream)).await;
+                    }
+                    Ok(Err(e)) => debug!("gRPC TLS handshake failed: {}", e),
+                    Err(_) => debug!("gRPC TLS handshake timed out"),
+                }
+            });
+        }
+    });
+    Ok(ReceiverStream::new(receiver))
+}
+
+fn get_modification_times(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .collect()
+}
+
+/// Build a rustls configuration from the certificate, private key and optional client CA files
+fn load_server_config(
+    paths: &[PathBuf],
+    mtls: bool,
+    alpn_protocols: &[Vec<u8>],
+) -> Result<(ServerConfig, CertificateInfo), GrpcError> {
+    let certificates = read_certificates(&paths[0])?;
+    let private_key = read_private_key(&paths[1])?;
+    let info = check_key_pair(&certificates[0], &private_key)?;
+    let private_key = PrivateKey(private_key.der);
+
+    let builder = ServerConfig::builder().with_safe_defaults();
+    let builder = if mtls {
+        let mut roots = RootCertStore::empty();
+        for certificate in read_certificates(&paths[2])? {
+            roots
+                .add(&certificate)
+                .map_err(|e| tls_error(&paths[2], e))?;
+        }
+        builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+    } else {
+        builder.with_no_client_auth()
+    };
+    let mut server_config = builder
+        .with_single_cert(certificates, private_key)
+        .map_err(|e| tls_error(&paths[0], e))?;
+    server_config.alpn_protocols = alpn_protocols.to_vec();
+    Ok((server_config, info))
+}
+
+fn read_certificates(path: &Path) -> Result<Vec<Certificate>, GrpcError> {
+    let file = File::open(path).map_err(|e| tls_error(path, e))?;
+    let certificates: Vec<Certificate> = rustls_pemfile::certs(&mut BufReader::new(file))
+        .map_err(|e| tls_error(path, e))?
+        .into_iter()
+        .map(Certificate)
+        .collect();
+    if certificates.is_empty() {
+        return Err(tls_error(path, "no certificate found"));
+    }
+    Ok(certificates)
+}
+
+/// Encoding of a private key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PrivateKeyFormat {
+    /// PKCS#8 `PrivateKeyInfo`, of any algorithm
+    Pkcs8,
+    /// PKCS#1 `RSAPrivateKey`
+    Rsa,
+    /// SEC1 `ECPrivateKey`
+    Sec1,
+}
+
+/// DER private key read from a PEM file
+struct PemPrivateKey {
+    der: Vec<u8>,
+    format: PrivateKeyFormat,
+}
+
+fn read_private_key(path: &Path) -> Result<PemPrivateKey, GrpcError> {
+    let file = File::open(path).map_err(|e| tls_error(path, e))?;
+    rustls_pemfile::read_all(&mut BufReader::new(file))
+        .map_err(|e| tls_error(path, e))?
+        .into_iter()
+        .find_map(|item| match item {
+            rustls_pemfile::Item::PKCS8Key(der) => Some(PemPrivateKey {
+                der,
+                format: PrivateKeyFormat::Pkcs8,
+            }),
+            rustls_pemfile::Item::RSAKey(der) => Some(PemPrivateKey {
+                der,
+                format: PrivateKeyFormat::Rsa,
+            }),
+            rustls_pemfile::Item::ECKey(der) => Some(PemPrivateKey {
+                der,
+                format: PrivateKeyFormat::Sec1,
+            }),
+            _ => None,
+        })
+        .ok_or_else(|| tls_error(path, "no private key found"))
+}
+
+/// Check that the private key belongs to the certificate and describe the certificate.
+/// The public key of the certificate is compared with the one of the private key: a key whose
+/// public key can't be known is rejected.
+fn check_key_pair(
+    certificate: &Certificate,
+    private_key: &PemPrivateKey,
+) -> Result<CertificateInfo, GrpcError> {
+    let (_, parsed) = x509_parser::parse_x509_certificate(&certificate.0)
+        .map_err(|e| GrpcError::InternalServerError(format!("invalid TLS certificate: {}", e)))?;
+    let public_key = match private_key.format {
+        PrivateKeyFormat::Pkcs8 => rcgen::KeyPair::from_der(&private_key.der)
+            .map_err(|e| {
+                GrpcError::InternalServerError(format!("unsupported PKCS#8 TLS private key: {}", e))
+            })?
+            .public_key_raw()
+            .to_vec(),
+        PrivateKeyFormat::Rsa => rsa_public_key(&private_key.der).ok_or_else(|| {
+            GrpcError::InternalServerError("invalid PKCS#1 RSA TLS private key".to_string())
+        })?,
+        PrivateKeyFormat::Sec1 => sec1_public_key(&private_key.der).ok_or_else(|| {
+            GrpcError::InternalServerError(
+                "SEC1 EC TLS private key without its public key, convert it to PKCS#8".to_string(),
+            )
+        })?,
+    };
+    if *parsed.public_key().subject_public_key.data != *public_key {
+        return Err(GrpcError::InternalServerError(
+            "TLS private key does not match the certificate".to_string(),
+        ));
+    }
+    let not_after = u64::try_from(parsed.validity().not_after.timestamp())
+        .map(|seconds| MassaTime::from_millis(seconds.saturating_mul(1000)))
+        .unwrap_or_else(|_| MassaTime::from_millis(0));
+    Ok(CertificateInfo {
+        fingerprint: Sha256::digest(&certificate.0)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect(),
+        not_after,
+    })
+}
+
+/// DER `RSAPublicKey` of a PKCS#1 `RSAPrivateKey`, as found in the certificates
+fn rsa_public_key(der: &[u8]) -> Option<Vec<u8>> {
+    // RSAPrivateKey ::= SEQUENCE { version, modulus, publicExponent, ... }
+    let (_, fields, _) = der_element(der, DER_SEQUENCE)?;
+    let (_, _version, fields) = der_element(fields, DER_INTEGER)?;
+    let (modulus, _, fields) = der_element(fields, DER_INTEGER)?;
+    let (public_exponent, _, _) = der_element(fields, DER_INTEGER)?;
+    // RSAPublicKey ::= SEQUENCE { modulus, publicExponent }
+    let content = [modulus, public_exponent].concat();
+    let mut public_key = vec![DER_SEQUENCE];
+    match content.len() {
+        len @ 0..=0x7f => public_key.push(len as u8),
+        len => {
+            let len_bytes: Vec<u8> = len
+                .to_be_bytes()
+                .into_iter()
+                .skip_while(|byte| *byte == 0)
+                .collect();
+            public_key.push(0x80 | len_bytes.len() as u8);
+            public_key.extend(len_bytes);
+        }
+    }
+    public_key.extend(content);
+    Some(public_key)
+}
+
+/// Public key point of a SEC1 `ECPrivateKey`, if the key includes it
+fn sec1_public_key(der: &[u8]) -> Option<Vec<u8>> {
+    // ECPrivateKey ::= SEQUENCE { version, privateKey, [0] parameters OPTIONAL, [1] publicKey OPTIONAL }
+    let (_, fields, _) = der_element(der, DER_SEQUENCE)?;
+    let (_, _version, fields) = der_element(fields, DER_INTEGER)?;
+    let (_, _private_key, mut fields) = der_element(fields, DER_OCTET_STRING)?;
+    while let Some(&tag) = fields.first() {
+        let (_, content, rest) = der_element(fields, tag)?;
+        if tag == DER_PUBLIC_KEY_TAG {
+            // the public key is a BIT STRING whose first byte is the count of unused bits
+            let (_, bits, _) = der_element(content, DER_BIT_STRING)?;
+            return bits.split_first().map(|(_, point)| point.to_vec());
+        }
+        fields = rest;
+    }
+    None
+}
+
+pub(crate) const DER_INTEGER: u8 = 0x02;
+const DER_BIT_STRING: u8 = 0x03;
+pub(crate) const DER_OCTET_STRING: u8 = 0x04;
+pub(crate) const DER_SEQUENCE: u8 = 0x30;
+/// context specific tag `[1]` of the public key of a SEC1 key
+const DER_PUBLIC_KEY_TAG: u8 = 0xa1;
+
+/// Split the DER element of tag `tag` at the start of `input`.
+/// Returns the whole element, its content, and the rest of the input.
+pub(crate) fn der_element(input: &[u8], tag: u8) -> Option<(&[u8], &[u8], &[u8])> {
+    if *input.first()? != tag {
+        return None;
+    }
+    let (len, header_len) = match *input.get(1)? {
+        len @ 0..=0x7f => (len as usize, 2),
+        0x81..=0x84 => {
+            let len_bytes = (input[1] & 0x7f) as usize;
+            let len = input
+                .get(2..2 + len_bytes)?
+                .iter()
+                .fold(0usize, |len, byte| (len << 8) | *byte as usize);
+            (len, 2 + len_bytes)
+        }
+        _ => return None,
+    };
+    let end = header_len.checked_add(len)?;
+    let element = input.get(..end)?;
+    Some((element, &element[header_len..], &input[end..]))
+}
+
+fn tls_error(path: &Path, error: impl std::fmt::Display) -> GrpcError {
+    GrpcError::InternalServerError(format!("TLS file {}: {}", path.display(), error))
+}
@@ -0,0 +1,137 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Status of the TLS certificate served by the gRPC server.
+//!
+//! Served next to the health and reflection services when TLS is enabled, so that operators can
+//! check which certificate new connections get and when it expires, for instance after a reload.
+//! Like the digests stream, the service is declared by hand, under `massa.api.v1.TlsStatusService`.
+
+use crate::tls::ServedCertificate;
+use std::convert::Infallible;
+use std::task::{Context, Poll};
+use tonic::body::BoxBody;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{empty_body, http, BoxFuture, Service};
+use tonic::transport::{Body, NamedService};
+
+/// Name of the TLS status service
+pub const TLS_STATUS_SERVICE_NAME: &str = "massa.api.v1.TlsStatusService";
+
+/// Path of the method getting the served certificate
+const GET_TLS_CERTIFICATE_PATH: &str = "/massa.api.v1.TlsStatusService/GetTlsCertificate";
+
+/// Request of the certificate served to the new connections
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetTlsCertificateRequest {}
+
+/// Certificate served to the new connections
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct GetTlsCertificateResponse {
+    /// Hex encoded SHA-256 of the DER certificate
+    #[prost(string, tag = "1")]
+    pub fingerprint: String,
+    /// End of validity of the certificate, in milliseconds since the UNIX epoch
+    #[prost(uint64, tag = "2")]
+    pub not_after: u64,
+}
+
+/// Server of the TLS status service
+#[derive(Clone)]
+pub(crate) struct TlsStatusServiceServer {
+    certificate: ServedCertificate,
+}
+
+impl TlsStatusServiceServer {
+    /// Create the server describing the certificate of `certificate`
+    pub(crate) fn new(certificate: ServedCertificate) -> Self {
+        TlsStatusServiceServer { certificate }
+    }
+}
+
+/// Handler of the unary method
+struct GetTlsCertificateSvc(ServedCertificate);
+
+impl tonic::server::UnaryService<GetTlsCertificateRequest> for GetTlsCertificateSvc {
+    type Response = GetTlsCertificateResponse;
+    type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+
+    fn call(&mut self, _request: tonic::Request<GetTlsCertificateRequest>) -> Self::Future {
+        let info = self.0.info();
+        Box::pin(async move {
+            Ok(tonic::Response::new(GetTlsCertificateResponse {
+                fingerprint: info.fingerprint,
+                not_after: info.not_after.as_millis(),
+            }))
+        })
+    }
+}
+
+impl Service<http::Request<Body>> for TlsStatusServiceServer {
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<Body>) -> Self::Future {
+        match req.uri().path() {
+            GET_TLS_CERTIFICATE_PATH => {
+                let certificate = self.certificate.clone();
+                Box::pin(async move {
+                    let mut grpc = tonic::server::Grpc::new(ProstCodec::default());
+                    Ok(grpc.unary(GetTlsCertificateSvc(certificate), req).await)
+                })
+            }
+            _ => Box::pin(async move {
+                Ok(http::Response::builder()
+                    .status(200)
+                    .header(
+                        "grpc-status",
+                        (tonic::Code::Unimplemented as i32).to_string(),
+                    )
+                    .header("content-type", "application/grpc")
+                    .body(empty_body())
+                    .expect("failed to build the unimplemented response"))
+            }),
+        }
+    }
+}
+
+impl NamedService for TlsStatusServiceServer {
+    const NAME: &'static str = TLS_STATUS_SERVICE_NAME;
+}
+
+/// Client of the TLS status service
+#[derive(Debug, Clone)]
+pub struct TlsStatusServiceClient {
+    inner: tonic::client::Grpc<tonic::transport::Channel>,
+}
+
+impl TlsStatusServiceClient {
+    /// Create a client using `channel`, which must be configured for TLS
+    pub fn new(channel: tonic::transport::Channel) -> Self {
+        TlsStatusServiceClient {
+            inner: tonic::client::Grpc::new(channel),
+        }
+    }
+
+    /// Get the certificate served to the new connections
+    pub async fn get_tls_certificate(
+        &mut self,
+        request: impl tonic::IntoRequest<GetTlsCertificateRequest>,
+    ) -> Result<tonic::Response<GetTlsCertificateResponse>, tonic::Status> {
+        self.inner
+            .ready()
+            .await
+            .map_err(|e| tonic::Status::unknown(format!("Service was not ready: {}", e)))?;
+        self.inner
+            .unary(
+                request.into_request(),
+                http::uri::PathAndQuery::from_static(GET_TLS_CERTIFICATE_PATH),
+                ProstCodec::default(),
+            )
+            .await
+    }
+}
@@ -7,13 +7,16 @@
 use std::{
     collections::HashMap,
     net::SocketAddr,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
     thread::JoinHandle,
     time::Duration,
 };
 
 use lazy_static::lazy_static;
-use prometheus::{register_int_gauge, Gauge, Histogram, IntCounter, IntGauge};
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, Gauge, Histogram,
+    HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+};
 use tokio::sync::oneshot::Sender;
 use tracing::warn;
 
@@ -30,6 +33,22 @@ lazy_static! {
         register_int_gauge!("blocks_storage_counter", "blocks storage counter len").unwrap();
     static ref ENDORSEMENTS_COUNTER: IntGauge =
         register_int_gauge!("endorsements_storage_counter", "endorsements storage counter len").unwrap();
+    // labelled by gRPC method, the cached methods are chosen in the configuration
+    static ref GRPC_RESPONSE_CACHE_HITS: IntCounterVec = register_int_counter_vec!(
+        "grpc_response_cache_hits",
+        "gRPC responses served from the cache, per method",
+        &["method"]
+    )
+    .unwrap();
+    static ref GRPC_RESPONSE_CACHE_MISSES: IntCounterVec = register_int_counter_vec!(
+        "grpc_response_cache_misses",
+        "gRPC responses computed because they were not cached, per method",
+        &["method"]
+    )
+    .unwrap();
+    // request metrics of the gRPC services, by namespace
+    static ref GRPC_SERVICES_METRICS: Mutex<HashMap<String, GrpcServiceMetrics>> =
+        Default::default();
 }
 
 pub fn set_blocks_counter(val: usize) {
@@ -44,6 +63,63 @@ pub fn set_operations_counter(val: usize) {
     OPERATIONS_COUNTER.set(val as i64);
 }
 
+pub fn inc_grpc_response_cache_hits(method: &str) {
+    GRPC_RESPONSE_CACHE_HITS.with_label_values(&[method]).inc();
+}
+
+pub fn inc_grpc_response_cache_misses(method: &str) {
+    GRPC_RESPONSE_CACHE_MISSES
+        .with_label_values(&[method])
+        .inc();
+}
+
+/// Request metrics of a gRPC service.
+///
+/// The metrics names are prefixed by the namespace of the service, so that the requests of
+/// services running in the same process are counted separately.
+#[derive(Clone)]
+pub struct GrpcServiceMetrics {
+    requests: IntCounterVec,
+    request_duration: HistogramVec,
+}
+
+impl GrpcServiceMetrics {
+    /// Metrics of the service using `namespace`, registered the first time they are requested
+    pub fn new(namespace: &str) -> Self {
+        let mut services = GRPC_SERVICES_METRICS
+            .lock()
+            .expect("gRPC services metrics lock poisoned");
+        services
+            .entry(namespace.to_string())
+            .or_insert_with(|| GrpcServiceMetrics {
+                requests: register_int_counter_vec!(
+                    Opts::new("requests", "number of requests received, per method")
+                        .namespace(namespace),
+                    &["method"]
+                )
+                .expect("failed to register the gRPC requests counter"),
+                request_duration: register_histogram_vec!(
+                    HistogramOpts::new(
+                        "request_duration_seconds",
+                        "time to answer a request, per method"
+                    )
+                    .namespace(namespace),
+                    &["method"]
+                )
+                .expect("failed to register the gRPC request duration histogram"),
+            })
+            .clone()
+    }
+
+    /// Count a request to `method` answered after `duration`
+    pub fn observe_request(&self, method: &str, duration: Duration) {
+        self.requests.with_label_values(&[method]).inc();
+        self.request_duration
+            .with_label_values(&[method])
+            .observe(duration.as_secs_f64());
+    }
+}
+
 #[derive(Default)]
 pub struct MetricsStopper {
     pub(crate) stopper: Option<Sender<()>>,
@@ -95,12 +171,21 @@ pub struct MassaMetrics {
     operations_pool: IntGauge,
     /// number of endorsements in the endorsement pool
     endorsements_pool: IntGauge,
+    /// number of endorsements rejected by the endorsement pool because their creator was not drawn
+    endorsements_pool_rejected_creator: IntCounter,
+    /// number of operations left out of the produced blocks because they pay less than the fee floor
+    operations_pool_below_fee_floor: IntCounter,
+    /// number of discrepancies found by the audits of the operation pool storage references
+    operations_pool_ref_discrepancies: IntCounter,
     /// number of elements in the denunciation pool
     denunciations_pool: IntGauge,
 
     // number of autonomous SCs messages in pool
     async_message_pool_size: IntGauge,
 
+    /// number of executed denunciations recorded in the final state
+    executed_denunciations: IntGauge,
+
     // number of autonomous SC messages executed as final
     sc_messages_final: IntCounter,
 
@@ -134,6 +219,9 @@ pub struct MassaMetrics {
     /// block slot delay
     block_slot_delay: Histogram,
 
+    /// time spent by the consensus worker on its commands, per command and phase
+    consensus_command_duration: HistogramVec,
+
     /// active in connections peer
     active_in_connections: IntGauge,
     /// active out connections peer
@@ -244,6 +332,21 @@ impl MassaMetrics {
             "number of endorsements in the endorsement pool",
         )
         .unwrap();
+        let endorsements_pool_rejected_creator = IntCounter::new(
+            "endorsements_pool_rejected_creator",
+            "number of endorsements rejected by the endorsement pool because their creator was not drawn",
+        )
+        .unwrap();
+        let operations_pool_below_fee_floor = IntCounter::new(
+            "operations_pool_below_fee_floor",
+            "number of operations left out of the produced blocks because they pay less than the fee floor",
+        )
+        .unwrap();
+        let operations_pool_ref_discrepancies = IntCounter::new(
+            "operations_pool_ref_discrepancies",
+            "number of discrepancies found between the operation pool and the references of its storage",
+        )
+        .unwrap();
         let denunciations_pool = IntGauge::new(
             "denunciations_pool",
             "number of elements in the denunciation pool",
@@ -256,6 +359,12 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        let executed_denunciations = IntGauge::new(
+            "executed_denunciations",
+            "number of executed denunciations recorded in the final state",
+        )
+        .unwrap();
+
         let sc_messages_final = IntCounter::new(
             "sc_messages_final",
             "number of autonomous SC messages executed as final",
@@ -406,6 +515,18 @@ impl MassaMetrics {
         )
         .unwrap();
 
+        let consensus_command_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "consensus_command_duration_seconds",
+                "time spent by the consensus worker on a command, per command and phase (lock_wait or processing)",
+            )
+            .buckets(vec![
+                0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5,
+            ]),
+            &["command", "phase"],
+        )
+        .unwrap();
+
         let mut stopper = MetricsStopper::default();
 
         if enabled {
@@ -450,14 +571,19 @@ impl MassaMetrics {
                 let _ = prometheus::register(Box::new(process_available_processors.clone()));
                 let _ = prometheus::register(Box::new(operations_pool.clone()));
                 let _ = prometheus::register(Box::new(endorsements_pool.clone()));
+                let _ = prometheus::register(Box::new(endorsements_pool_rejected_creator.clone()));
+                let _ = prometheus::register(Box::new(operations_pool_below_fee_floor.clone()));
+                let _ = prometheus::register(Box::new(operations_pool_ref_discrepancies.clone()));
                 let _ = prometheus::register(Box::new(denunciations_pool.clone()));
                 let _ = prometheus::register(Box::new(protocol_tester_success.clone()));
                 let _ = prometheus::register(Box::new(protocol_tester_failed.clone()));
                 let _ = prometheus::register(Box::new(sc_messages_final.clone()));
                 let _ = prometheus::register(Box::new(async_message_pool_size.clone()));
+                let _ = prometheus::register(Box::new(executed_denunciations.clone()));
                 let _ = prometheus::register(Box::new(current_time_period.clone()));
                 let _ = prometheus::register(Box::new(current_time_thread.clone()));
                 let _ = prometheus::register(Box::new(block_slot_delay.clone()));
+                let _ = prometheus::register(Box::new(consensus_command_duration.clone()));
 
                 stopper = server::bind_metrics(addr);
             }
@@ -475,8 +601,12 @@ impl MassaMetrics {
                 active_history,
                 operations_pool,
                 endorsements_pool,
+                endorsements_pool_rejected_creator,
+                operations_pool_below_fee_floor,
+                operations_pool_ref_discrepancies,
                 denunciations_pool,
                 async_message_pool_size,
+                executed_denunciations,
                 sc_messages_final,
                 bootstrap_counter,
                 bootstrap_peers_success: bootstrap_success,
@@ -490,6 +620,7 @@ impl MassaMetrics {
                 peernet_total_bytes_received,
                 peernet_total_bytes_sent,
                 block_slot_delay,
+                consensus_command_duration,
                 active_in_connections,
                 active_out_connections,
                 operations_final_counter,
@@ -658,6 +789,28 @@ impl MassaMetrics {
         self.endorsements_pool.set(nb as i64);
     }
 
+    pub fn inc_endorsements_pool_rejected_creator(&self) {
+        self.endorsements_pool_rejected_creator.inc();
+    }
+
+    pub fn inc_operations_pool_below_fee_floor(&self) {
+        self.operations_pool_below_fee_floor.inc();
+    }
+
+    /// Number of operations left out of the produced blocks because of the fee floor
+    pub fn get_operations_pool_below_fee_floor(&self) -> u64 {
+        self.operations_pool_below_fee_floor.get()
+    }
+
+    pub fn inc_operations_pool_ref_discrepancies(&self, count: u64) {
+        self.operations_pool_ref_discrepancies.inc_by(count);
+    }
+
+    /// Number of discrepancies found by the operation pool reference audits
+    pub fn get_operations_pool_ref_discrepancies(&self) -> u64 {
+        self.operations_pool_ref_discrepancies.get()
+    }
+
     pub fn set_denunciations_pool(&self, nb: usize) {
         self.denunciations_pool.set(nb as i64);
     }
@@ -686,6 +839,10 @@ impl MassaMetrics {
         self.async_message_pool_size.set(nb as i64);
     }
 
+    pub fn set_executed_denunciations(&self, nb: usize) {
+        self.executed_denunciations.set(nb as i64);
+    }
+
     pub fn set_available_processors(&self, nb: usize) {
         self.process_available_processors.set(nb as i64);
     }
@@ -702,6 +859,29 @@ impl MassaMetrics {
         self.block_slot_delay.observe(delay);
     }
 
+    /// Record a consensus `command` that waited `lock_wait` for the graph lock and was then processed in `processing`
+    pub fn observe_consensus_command(
+        &self,
+        command: &str,
+        lock_wait: Duration,
+        processing: Duration,
+    ) {
+        self.consensus_command_duration
+            .with_label_values(&[command, "lock_wait"])
+            .observe(lock_wait.as_secs_f64());
+        self.consensus_command_duration
+            .with_label_values(&[command, "processing"])
+            .observe(processing.as_secs_f64());
+    }
+
+    /// Number of consensus `command` recorded, and their total processing time in seconds
+    pub fn get_consensus_command_processing(&self, command: &str) -> (u64, f64) {
+        let histogram = self
+            .consensus_command_duration
+            .with_label_values(&[command, "processing"]);
+        (histogram.get_sample_count(), histogram.get_sample_sum())
+    }
+
     /// Update the bandwidth metrics for all peers
     /// HashMap<peer_id, (tx, rx)>
     pub fn update_peers_tx_rx(&self, data: HashMap<String, (u64, u64)>) {
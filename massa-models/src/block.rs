@@ -3,6 +3,7 @@
 // use crate::config::THREAD_COUNT;
 // use crate::endorsement::{EndorsementId, EndorsementSerializer, EndorsementSerializerLW};
 // use crate::prehash::PreHashed;
+use crate::has_id::HasId;
 use crate::secure_share::{
     Id, SecureShare, SecureShareContent, SecureShareDeserializer, SecureShareSerializer,
 };
@@ -60,6 +61,9 @@ pub struct FilledBlock {
     pub header: SecuredHeader,
     /// operations
     pub operations: Vec<(OperationId, Option<SecureShareOperation>)>,
+    /// whether the block was produced by this node, and broadcast by its factory before being registered
+    #[serde(default)]
+    pub locally_produced: bool,
 }
 
 /// Block with assosciated meta-data and interfaces allowing trust of data in untrusted network
@@ -382,6 +386,14 @@ impl SecureShareBlock {
     }
 }
 
+impl HasId for FilledBlock {
+    type Id = BlockId;
+
+    fn get_id(&self) -> BlockId {
+        self.header.id
+    }
+}
+
 impl std::fmt::Display for Block {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{}", self.header)?;
@@ -431,6 +443,24 @@ pub enum BlockGraphStatus {
     NotFound,
 }
 
+/// Confirmation of an active block, taking the cliques of the graph into account
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Deserialize, Serialize)]
+pub struct ConfirmationInfo {
+    /// the block is final
+    pub is_final: bool,
+    /// the block is in the blockclique
+    pub in_blockclique: bool,
+    /// cumulative fitness of the non-final descendants of the block that are in the blockclique
+    pub descendants_fitness: u64,
+    /// fitness of the blockclique minus the fitness of the best clique not containing the block,
+    /// 0 if the block is not in the blockclique, None if the block is final or no clique excludes it
+    pub fitness_gap: Option<u64>,
+    /// from 0 to 100: 100 once the block is final, 0 if it is not in the blockclique.
+    /// Otherwise, the lowest of `descendants_fitness` and `fitness_gap` relative to the fitness
+    /// margin required for finality, capped at 99.
+    pub confirmation_score: u8,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
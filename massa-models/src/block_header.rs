@@ -74,7 +74,7 @@ impl BlockHeader {
 
             let parent_id = self.parents[self.slot.thread as usize];
             for endo in self.endorsements.iter() {
-                if endo.content.endorsed_block != parent_id {
+                if !endo.is_valid_for(self.slot, &parent_id) {
                     return Err("Invariant broken: endorsement doesn't match parent".into());
                 }
             }
@@ -494,7 +494,8 @@ impl Deserializer<BlockHeader> for BlockHeaderDeserializer {
                     let (rest, endo) = endorsement_deserializer
                         .deserialize_with(&self.endorsement_serializer, input)?;
 
-                    if endo.content.endorsed_block != parent_id {
+                    // the slot is given by the context to the LW deserializer, only the endorsed block can differ
+                    if !endo.is_valid_for(slot, &parent_id) {
                         return Err(nom::Err::Failure(ContextError::add_context(
                             rest,
                             "Endorsement does not match block parents",
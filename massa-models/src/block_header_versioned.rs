@@ -0,0 +1,415 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Serialization of the block headers in the format of their network version.
+//!
+//! Each range of network versions is bound to a format. A header is serialized and deserialized
+//! with the format of its `current_version`, so that a network upgrade can change the header
+//! format without breaking the blocks of the previous versions.
+
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use displaydoc::Display;
+use massa_serialization::{
+    DeserializeError, Deserializer, SerializeError, Serializer, U32VarIntDeserializer,
+};
+use nom::error::{context, ContextError, ErrorKind, ParseError};
+use nom::{IResult, Parser};
+use std::ops::Bound::Included;
+use thiserror::Error;
+
+use crate::block_header::{BlockHeader, BlockHeaderDeserializer, BlockHeaderSerializer};
+
+/// Error of the versioned block header serialization
+#[non_exhaustive]
+#[derive(Display, Error, Debug, Clone, PartialEq, Eq)]
+pub enum BlockHeaderVersionError {
+    /// no block header format is registered for network version {0}
+    UnknownVersion(u32),
+    /// the block header formats of network versions {0:?} and {1:?} overlap
+    OverlappingVersions(RangeInclusive<u32>, RangeInclusive<u32>),
+    /// field {field} is not supported by the block header format of network version {version}
+    UnsupportedField {
+        /// network version of the header
+        version: u32,
+        /// field that is not empty or default
+        field: &'static str,
+    },
+}
+
+/// Format of the block headers of a range of network versions
+pub trait BlockHeaderFormat: Send + Sync {
+    /// Name of a field of `header` that the format cannot encode and is not empty or default, if any
+    fn unsupported_field(&self, header: &BlockHeader) -> Option<&'static str>;
+
+    /// Serialize a header whose fields are all supported
+    fn serialize(&self, header: &BlockHeader, buffer: &mut Vec<u8>) -> Result<(), SerializeError>;
+
+    /// Deserialize a header, its network version included
+    fn deserialize<'a>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], BlockHeader, DeserializeError<'a>>;
+}
+
+/// Format of the block headers since the introduction of the network versions
+pub struct CurrentBlockHeaderFormat {
+    serializer: BlockHeaderSerializer,
+    deserializer: BlockHeaderDeserializer,
+}
+
+impl CurrentBlockHeaderFormat {
+    /// Creates a new `CurrentBlockHeaderFormat`, see `BlockHeaderDeserializer::new` for the arguments
+    pub fn new(
+        thread_count: u8,
+        endorsement_count: u32,
+        max_denunciations_in_block_header: u32,
+        last_start_period: Option<u64>,
+        chain_id: u64,
+    ) -> Self {
+        Self {
+            serializer: BlockHeaderSerializer::new(),
+            deserializer: BlockHeaderDeserializer::new(
+                thread_count,
+                endorsement_count,
+                max_denunciations_in_block_header,
+                last_start_period,
+                chain_id,
+            ),
+        }
+    }
+}
+
+impl BlockHeaderFormat for CurrentBlockHeaderFormat {
+    fn unsupported_field(&self, _header: &BlockHeader) -> Option<&'static str> {
+        None
+    }
+
+    fn serialize(&self, header: &BlockHeader, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.serializer.serialize(header, buffer)
+    }
+
+    fn deserialize<'a>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], BlockHeader, DeserializeError<'a>> {
+        self.deserializer.deserialize(buffer)
+    }
+}
+
+/// Block header formats, each bound to a range of network versions
+#[derive(Default)]
+pub struct BlockHeaderFormats {
+    formats: Vec<(RangeInclusive<u32>, Box<dyn BlockHeaderFormat>)>,
+}
+
+impl BlockHeaderFormats {
+    /// Formats known by this node: the current format, for network versions 0 and 1
+    pub fn new(
+        thread_count: u8,
+        endorsement_count: u32,
+        max_denunciations_in_block_header: u32,
+        last_start_period: Option<u64>,
+        chain_id: u64,
+    ) -> Self {
+        let mut formats = Self::default();
+        formats
+            .register(
+                0..=1,
+                Box::new(CurrentBlockHeaderFormat::new(
+                    thread_count,
+                    endorsement_count,
+                    max_denunciations_in_block_header,
+                    last_start_period,
+                    chain_id,
+                )),
+            )
+            .expect("the formats of a new registry cannot overlap");
+        formats
+    }
+
+    /// Bind `format` to the network versions of `versions`, which must not have a format yet
+    pub fn register(
+        &mut self,
+        versions: RangeInclusive<u32>,
+        format: Box<dyn BlockHeaderFormat>,
+    ) -> Result<(), BlockHeaderVersionError> {
+        if let Some((registered, _)) = self.formats.iter().find(|(registered, _)| {
+            registered.start() <= versions.end() && versions.start() <= registered.end()
+        }) {
+            return Err(BlockHeaderVersionError::OverlappingVersions(
+                registered.clone(),
+                versions,
+            ));
+        }
+        self.formats.push((versions, format));
+        Ok(())
+    }
+
+    /// Format of the headers of network version `version`
+    pub fn get(&self, version: u32) -> Result<&dyn BlockHeaderFormat, BlockHeaderVersionError> {
+        self.formats
+            .iter()
+            .find(|(versions, _)| versions.contains(&version))
+            .map(|(_, format)| format.as_ref())
+            .ok_or(BlockHeaderVersionError::UnknownVersion(version))
+    }
+}
+
+/// Serializer for `BlockHeader`, in the format of its `current_version`
+#[derive(Clone)]
+pub struct VersionedBlockHeaderSerializer {
+    formats: Arc<BlockHeaderFormats>,
+}
+
+impl VersionedBlockHeaderSerializer {
+    /// Creates a new `VersionedBlockHeaderSerializer`
+    pub fn new(formats: Arc<BlockHeaderFormats>) -> Self {
+        Self { formats }
+    }
+
+    /// Format `header` is serialized with, if it exists and supports all the fields of `header`
+    pub fn check(
+        &self,
+        header: &BlockHeader,
+    ) -> Result<&dyn BlockHeaderFormat, BlockHeaderVersionError> {
+        let format = self.formats.get(header.current_version)?;
+        if let Some(field) = format.unsupported_field(header) {
+            return Err(BlockHeaderVersionError::UnsupportedField {
+                version: header.current_version,
+                field,
+            });
+        }
+        Ok(format)
+    }
+}
+
+impl Serializer<BlockHeader> for VersionedBlockHeaderSerializer {
+    fn serialize(&self, value: &BlockHeader, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.check(value)
+            .map_err(|err| SerializeError::GeneralError(err.to_string()))?
+            .serialize(value, buffer)
+    }
+}
+
+/// Deserializer for `BlockHeader`, in the format of the network version it starts with
+#[derive(Clone)]
+pub struct VersionedBlockHeaderDeserializer {
+    formats: Arc<BlockHeaderFormats>,
+    version_deserializer: U32VarIntDeserializer,
+}
+
+impl VersionedBlockHeaderDeserializer {
+    /// Creates a new `VersionedBlockHeaderDeserializer`
+    pub fn new(formats: Arc<BlockHeaderFormats>) -> Self {
+        Self {
+            formats,
+            version_deserializer: U32VarIntDeserializer::new(Included(0), Included(u32::MAX)),
+        }
+    }
+}
+
+impl Deserializer<BlockHeader> for VersionedBlockHeaderDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], BlockHeader, E> {
+        // only peek at the version: the format deserializes it again
+        let (_, version) = context("Failed current_version deserialization", |input| {
+            self.version_deserializer.deserialize(input)
+        })
+        .parse(buffer)?;
+        let format = self.formats.get(version).map_err(|_| {
+            nom::Err::Failure(E::add_context(
+                buffer,
+                "Unknown block header network version",
+                E::from_error_kind(buffer, ErrorKind::Fail),
+            ))
+        })?;
+        // the errors of the format cannot be converted to `E`, only their kind is kept
+        format.deserialize(buffer).map_err(|err| {
+            err.map(|_| {
+                E::add_context(
+                    buffer,
+                    "Failed BlockHeader deserialization",
+                    E::from_error_kind(buffer, ErrorKind::Fail),
+                )
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block_id::BlockId;
+    use crate::config::{
+        CHAINID, ENDORSEMENT_COUNT, MAX_DENUNCIATIONS_PER_BLOCK_HEADER, THREAD_COUNT,
+    };
+    use crate::slot::Slot;
+    use massa_hash::Hash;
+    use massa_serialization::U32VarIntSerializer;
+
+    /// Next format of the tests: the current one followed by a new field, without the announced version
+    struct NextBlockHeaderFormat {
+        current: CurrentBlockHeaderFormat,
+        new_field_serializer: U32VarIntSerializer,
+        new_field_deserializer: U32VarIntDeserializer,
+    }
+
+    impl BlockHeaderFormat for NextBlockHeaderFormat {
+        fn unsupported_field(&self, header: &BlockHeader) -> Option<&'static str> {
+            header.announced_version.map(|_| "announced_version")
+        }
+
+        fn serialize(
+            &self,
+            header: &BlockHeader,
+            buffer: &mut Vec<u8>,
+        ) -> Result<(), SerializeError> {
+            self.current.serialize(header, buffer)?;
+            self.new_field_serializer.serialize(&0, buffer)
+        }
+
+        fn deserialize<'a>(
+            &self,
+            buffer: &'a [u8],
+        ) -> IResult<&'a [u8], BlockHeader, DeserializeError<'a>> {
+            let (rest, header) = self.current.deserialize(buffer)?;
+            let (rest, _) = self.new_field_deserializer.deserialize(rest)?;
+            Ok((rest, header))
+        }
+    }
+
+    fn current_format() -> Box<dyn BlockHeaderFormat> {
+        Box::new(CurrentBlockHeaderFormat::new(
+            THREAD_COUNT,
+            ENDORSEMENT_COUNT,
+            MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+            None,
+            *CHAINID,
+        ))
+    }
+
+    /// formats of the versions 0 and 1, and 2 if `with_next`
+    fn formats(with_next: bool) -> Arc<BlockHeaderFormats> {
+        let mut formats = BlockHeaderFormats::default();
+        formats.register(0..=1, current_format()).unwrap();
+        if with_next {
+            formats
+                .register(
+                    2..=2,
+                    Box::new(NextBlockHeaderFormat {
+                        current: CurrentBlockHeaderFormat::new(
+                            THREAD_COUNT,
+                            ENDORSEMENT_COUNT,
+                            MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+                            None,
+                            *CHAINID,
+                        ),
+                        new_field_serializer: U32VarIntSerializer::new(),
+                        new_field_deserializer: U32VarIntDeserializer::new(
+                            Included(0),
+                            Included(0),
+                        ),
+                    }),
+                )
+                .unwrap();
+        }
+        Arc::new(formats)
+    }
+
+    fn header(current_version: u32, announced_version: Option<u32>) -> BlockHeader {
+        BlockHeader {
+            current_version,
+            announced_version,
+            slot: Slot::new(1, 0),
+            parents: (0..THREAD_COUNT)
+                .map(|i| BlockId::generate_from_hash(Hash::compute_from(&[i])))
+                .collect(),
+            operation_merkle_root: Hash::compute_from("merkle".as_bytes()),
+            endorsements: Vec::new(),
+            denunciations: Vec::new(),
+        }
+    }
+
+    fn round_trip(formats: &Arc<BlockHeaderFormats>, header: &BlockHeader) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        VersionedBlockHeaderSerializer::new(formats.clone())
+            .serialize(header, &mut buffer)
+            .unwrap();
+        let (rest, deserialized) = VersionedBlockHeaderDeserializer::new(formats.clone())
+            .deserialize::<DeserializeError>(&buffer)
+            .unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(deserialized.current_version, header.current_version);
+        assert_eq!(deserialized.announced_version, header.announced_version);
+        assert_eq!(deserialized.slot, header.slot);
+        assert_eq!(deserialized.parents, header.parents);
+        assert_eq!(
+            deserialized.operation_merkle_root,
+            header.operation_merkle_root
+        );
+        buffer
+    }
+
+    #[test]
+    fn test_versioned_header_round_trip() {
+        let formats = formats(true);
+        let current = round_trip(&formats, &header(1, Some(2)));
+        round_trip(&formats, &header(2, None));
+
+        // the current format is the one of the plain serializer
+        let mut plain = Vec::new();
+        BlockHeaderSerializer::new()
+            .serialize(&header(1, Some(2)), &mut plain)
+            .unwrap();
+        assert_eq!(plain, current);
+    }
+
+    #[test]
+    fn test_versioned_header_rejections() {
+        let formats = formats(true);
+        let serializer = VersionedBlockHeaderSerializer::new(formats.clone());
+
+        // fields not supported by the version are rejected before serialization
+        assert_eq!(
+            serializer.check(&header(2, Some(3))).err(),
+            Some(BlockHeaderVersionError::UnsupportedField {
+                version: 2,
+                field: "announced_version",
+            })
+        );
+        assert!(serializer
+            .serialize(&header(2, Some(3)), &mut Vec::new())
+            .is_err());
+
+        // versions without a format are rejected
+        assert_eq!(
+            serializer.check(&header(3, None)).err(),
+            Some(BlockHeaderVersionError::UnknownVersion(3))
+        );
+        assert!(serializer
+            .serialize(&header(3, None), &mut Vec::new())
+            .is_err());
+
+        // a node that does not know the next version rejects its headers
+        let mut next = Vec::new();
+        serializer.serialize(&header(2, None), &mut next).unwrap();
+        assert!(VersionedBlockHeaderDeserializer::new(formats(false))
+            .deserialize::<DeserializeError>(&next)
+            .is_err());
+
+        // the current format does not read the new field
+        let (rest, _) = formats.get(1).unwrap().deserialize(&next).unwrap();
+        assert_eq!(rest, &[0]);
+
+        // a version cannot have two formats
+        let mut overlapping = BlockHeaderFormats::default();
+        overlapping.register(0..=1, current_format()).unwrap();
+        assert_eq!(
+            overlapping.register(1..=2, current_format()).err(),
+            Some(BlockHeaderVersionError::OverlappingVersions(0..=1, 1..=2))
+        );
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Canonical JSON serialization, for consumers hashing, signing or caching API responses.
+//!
+//! The output follows RFC 8785: no insignificant whitespace, object keys sorted by their UTF-16
+//! code units, strings escaped as ECMAScript does and floating point numbers formatted as
+//! ECMAScript `Number.prototype.toString` does.
+//! Integers are written as is, without the conversion to a double that RFC 8785 implies above
+//! 2^53, so that large periods and counters are not rounded. Non finite floats are written
+//! `null`, as serde_json does.
+
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+
+use crate::error::ModelsError;
+
+/// Types whose canonical JSON serialization is part of the API.
+///
+/// The implementation is opt-in, so that a type is only hashed or signed once its JSON
+/// format was checked to be deterministic (no floating point field serialized as a string, etc.).
+pub trait CanonicalJson: Serialize {
+    /// Serialize `self` to canonical JSON
+    fn to_canonical_json(&self) -> Result<String, ModelsError> {
+        to_canonical_json(self)
+    }
+}
+
+/// Serialize a value to canonical JSON
+pub fn to_canonical_json<T: Serialize + ?Sized>(value: &T) -> Result<String, ModelsError> {
+    let value =
+        serde_json::to_value(value).map_err(|err| ModelsError::SerializeError(err.to_string()))?;
+    let mut output = String::new();
+    write_value(&value, &mut output)?;
+    Ok(output)
+}
+
+fn write_value(value: &Value, output: &mut String) -> Result<(), ModelsError> {
+    match value {
+        Value::Null => output.push_str("null"),
+        Value::Bool(value) => output.push_str(if *value { "true" } else { "false" }),
+        Value::Number(number) => write_number(number, output)?,
+        Value::String(string) => write_string(string, output)?,
+        Value::Array(values) => {
+            output.push('[');
+            for (index, value) in values.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_value(value, output)?;
+            }
+            output.push(']');
+        }
+        Value::Object(map) => write_object(map, output)?,
+    }
+    Ok(())
+}
+
+fn write_object(map: &Map<String, Value>, output: &mut String) -> Result<(), ModelsError> {
+    let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+    output.push('{');
+    for (index, (key, value)) in entries.into_iter().enumerate() {
+        if index > 0 {
+            output.push(',');
+        }
+        write_string(key, output)?;
+        output.push(':');
+        write_value(value, output)?;
+    }
+    output.push('}');
+    Ok(())
+}
+
+fn write_string(string: &str, output: &mut String) -> Result<(), ModelsError> {
+    // serde_json escapes the same characters as ECMAScript, with lowercase hexadecimal digits
+    let escaped = serde_json::to_string(string)
+        .map_err(|err| ModelsError::SerializeError(err.to_string()))?;
+    output.push_str(&escaped);
+    Ok(())
+}
+
+fn write_number(number: &Number, output: &mut String) -> Result<(), ModelsError> {
+    if let Some(value) = number.as_u64() {
+        output.push_str(&value.to_string());
+    } else if let Some(value) = number.as_i64() {
+        output.push_str(&value.to_string());
+    } else {
+        let value = number.as_f64().ok_or_else(|| {
+            ModelsError::SerializeError(format!("number {} cannot be canonicalized", number))
+        })?;
+        write_float(value, output);
+    }
+    Ok(())
+}
+
+/// Format a finite float as ECMAScript `Number.prototype.toString` does
+fn write_float(value: f64, output: &mut String) {
+    if value == 0.0 {
+        // also covers -0
+        output.push('0');
+        return;
+    }
+    if value < 0.0 {
+        output.push('-');
+    }
+
+    // shortest digits that round-trip, and the position of the decimal point relative to them
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("scientific notation always has an exponent");
+    let digits = mantissa.replace('.', "");
+    let exponent: i32 = exponent
+        .parse()
+        .expect("scientific notation exponent is an integer");
+    let digit_count = digits.len() as i32;
+    let point = exponent + 1;
+
+    if digit_count <= point && point <= 21 {
+        output.push_str(&digits);
+        output.push_str(&"0".repeat((point - digit_count) as usize));
+    } else if 0 < point && point <= 21 {
+        let (integer, fraction) = digits.split_at(point as usize);
+        output.push_str(integer);
+        output.push('.');
+        output.push_str(fraction);
+    } else if -6 < point && point <= 0 {
+        output.push_str("0.");
+        output.push_str(&"0".repeat(-point as usize));
+        output.push_str(&digits);
+    } else {
+        let (first, rest) = digits.split_at(1);
+        output.push_str(first);
+        if !rest.is_empty() {
+            output.push('.');
+            output.push_str(rest);
+        }
+        output.push('e');
+        output.push(if exponent < 0 { '-' } else { '+' });
+        output.push_str(&exponent.abs().to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::Address;
+    use crate::output_event::{EventExecutionContext, SCOutputEvent};
+    use crate::slot::Slot;
+    use serde_json::json;
+    use std::collections::{BTreeMap, HashMap, VecDeque};
+    use std::str::FromStr;
+
+    fn output_event() -> SCOutputEvent {
+        SCOutputEvent {
+            context: EventExecutionContext {
+                slot: Slot::new(12, 3),
+                block: None,
+                read_only: false,
+                index_in_slot: 1,
+                call_stack: VecDeque::from([Address::from_str(
+                    "AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x",
+                )
+                .unwrap()]),
+                origin_operation_id: None,
+                is_final: true,
+                is_error: false,
+            },
+            data: "{\"value\":\"é\"}\n".to_string(),
+        }
+    }
+
+    #[test]
+    fn canonical_output_event() {
+        let event = output_event();
+        let canonical = event.to_canonical_json().unwrap();
+        assert_eq!(
+            canonical,
+            r#"{"context":{"block":null,"call_stack":["AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x"],"index_in_slot":1,"is_error":false,"is_final":true,"origin_operation_id":null,"read_only":false,"slot":{"period":12,"thread":3}},"data":"{\"value\":\"é\"}\n"}"#
+        );
+
+        // the canonical output of a parsed canonical output is unchanged
+        let parsed: SCOutputEvent = serde_json::from_str(&canonical).unwrap();
+        assert_eq!(parsed.to_canonical_json().unwrap(), canonical);
+    }
+
+    #[test]
+    fn canonical_values() {
+        let value = json!({
+            "b": [1, -2, true, null],
+            "a": {"z": "\u{1f}\t\"\\/", "y": {}},
+            "\u{e9}": "non ascii key",
+            "\u{1f600}": "key outside the BMP",
+            "\u{ff61}": "key sorted after the surrogate pair",
+        });
+        assert_eq!(
+            to_canonical_json(&value).unwrap(),
+            "{\"a\":{\"y\":{},\"z\":\"\\u001f\\t\\\"\\\\/\"},\"b\":[1,-2,true,null],\
+             \"\u{e9}\":\"non ascii key\",\"\u{1f600}\":\"key outside the BMP\",\
+             \"\u{ff61}\":\"key sorted after the surrogate pair\"}"
+        );
+        assert_eq!(
+            to_canonical_json(&u64::MAX).unwrap(),
+            "18446744073709551615"
+        );
+        assert_eq!(
+            to_canonical_json(&i64::MIN).unwrap(),
+            "-9223372036854775808"
+        );
+    }
+
+    #[test]
+    fn canonical_floats() {
+        for (value, expected) in [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1.0, "1"),
+            (-1.5, "-1.5"),
+            (0.1, "0.1"),
+            (123.456, "123.456"),
+            (0.000001, "0.000001"),
+            (1.5e-7, "1.5e-7"),
+            (1e20, "100000000000000000000"),
+            (1e21, "1e+21"),
+            (5e-324, "5e-324"),
+            (f64::MAX, "1.7976931348623157e+308"),
+        ] {
+            assert_eq!(to_canonical_json(&value).unwrap(), expected, "{}", value);
+        }
+    }
+
+    #[test]
+    fn canonical_output_does_not_depend_on_insertion_order() {
+        let entries: Vec<(String, u64)> = (0..32).map(|i| (format!("key {}", i), i)).collect();
+        let expected =
+            to_canonical_json(&entries.iter().cloned().collect::<BTreeMap<_, _>>()).unwrap();
+        for shift in 0..entries.len() {
+            // every map has its own hasher seed, so its iteration order differs as well
+            let mut rotated = entries.clone();
+            rotated.rotate_left(shift);
+            let map: HashMap<String, u64> = rotated.iter().cloned().collect();
+            assert_eq!(to_canonical_json(&map).unwrap(), expected);
+
+            let object: Map<String, Value> = rotated
+                .iter()
+                .rev()
+                .map(|(key, value)| (key.clone(), json!(value)))
+                .collect();
+            assert_eq!(to_canonical_json(&object).unwrap(), expected);
+        }
+    }
+}
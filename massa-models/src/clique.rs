@@ -40,6 +40,58 @@ impl Default for Clique {
     }
 }
 
+/// Clique enriched with information useful to API users.
+/// Keeps the `Clique` field names so that it stays compatible with it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CliqueInfo {
+    /// the block ids of the blocks in that clique
+    pub block_ids: PreHashSet<BlockId>,
+    /// Fitness used to compute finality
+    /// Depends on descendants and endorsement count
+    pub fitness: u64,
+    /// True if it is the clique of higher fitness
+    pub is_blockclique: bool,
+    /// number of blocks in that clique
+    pub block_count: usize,
+    /// ids of the blocks of that clique that have no child in it, sorted
+    pub tip_block_ids: Vec<BlockId>,
+}
+
+impl CliqueInfo {
+    /// Build the clique information from a clique and its tip blocks
+    pub fn new(clique: &Clique, mut tip_block_ids: Vec<BlockId>) -> Self {
+        tip_block_ids.sort_unstable();
+        CliqueInfo {
+            block_ids: clique.block_ids.clone(),
+            fitness: clique.fitness,
+            is_blockclique: clique.is_blockclique,
+            block_count: clique.block_ids.len(),
+            tip_block_ids,
+        }
+    }
+}
+
+/// Fitness difference between the blockclique and the best alternative clique.
+/// Returns `None` if there is no blockclique or no alternative clique.
+///
+/// ## Example
+/// ```rust
+/// # use massa_models::clique::{fitness_gap_to_second_clique, Clique, CliqueInfo};
+/// let blockclique = CliqueInfo::new(&Clique { block_ids: Default::default(), fitness: 100, is_blockclique: true }, vec![]);
+/// let alternative = CliqueInfo::new(&Clique { block_ids: Default::default(), fitness: 60, is_blockclique: false }, vec![]);
+/// assert_eq!(fitness_gap_to_second_clique(&[blockclique.clone()]), None);
+/// assert_eq!(fitness_gap_to_second_clique(&[alternative, blockclique]), Some(40));
+/// ```
+pub fn fitness_gap_to_second_clique(cliques: &[CliqueInfo]) -> Option<u64> {
+    let blockclique_fitness = cliques.iter().find(|c| c.is_blockclique)?.fitness;
+    let second_fitness = cliques
+        .iter()
+        .filter(|c| !c.is_blockclique)
+        .map(|c| c.fitness)
+        .max()?;
+    Some(blockclique_fitness.saturating_sub(second_fitness))
+}
+
 /// Basic serializer for `Clique`
 #[derive(Default)]
 pub struct CliqueSerializer {
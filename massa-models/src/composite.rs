@@ -1,7 +1,9 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::error::ModelsError;
 use crate::prehash::PreHashMap;
 use crate::{address::Address, block_id::BlockId, operation::SecureShareOperation};
+use massa_hash::Hash;
 use massa_signature::{PublicKey, Signature};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
@@ -81,3 +83,177 @@ impl Display for PubkeySig {
         writeln!(f, "Signature: {}", self.signature)
     }
 }
+
+/// Prefix prepended to every message signed by the node through the domain separated signing endpoint
+pub const NODE_SIGNED_MESSAGE_PREFIX: &str = "MASSA_NODE_SIGNED_MESSAGE:";
+
+/// Maximum length (in bytes) of a node signed message domain
+pub const NODE_SIGNED_MESSAGE_MAX_DOMAIN_LENGTH: usize = 64;
+
+/// Build the bytes that are actually hashed and signed for a domain separated node message:
+/// `NODE_SIGNED_MESSAGE_PREFIX || domain || ":" || message length (decimal) || ":" || message`.
+///
+/// The domain must be non-empty, at most `NODE_SIGNED_MESSAGE_MAX_DOMAIN_LENGTH` bytes long
+/// and only contain ASCII alphanumeric characters, `_`, `-` or `.` so that the encoding is unambiguous.
+pub fn node_signed_message_bytes(message: &[u8], domain: &str) -> Result<Vec<u8>, ModelsError> {
+    if domain.is_empty() {
+        return Err(ModelsError::InvalidSignedMessageDomain(
+            "domain cannot be empty".to_string(),
+        ));
+    }
+    if domain.len() > NODE_SIGNED_MESSAGE_MAX_DOMAIN_LENGTH {
+        return Err(ModelsError::InvalidSignedMessageDomain(format!(
+            "domain length {} exceeds the maximum of {}",
+            domain.len(),
+            NODE_SIGNED_MESSAGE_MAX_DOMAIN_LENGTH
+        )));
+    }
+    if !domain
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+    {
+        return Err(ModelsError::InvalidSignedMessageDomain(format!(
+            "domain {} contains forbidden characters",
+            domain
+        )));
+    }
+    let header = format!(
+        "{}{}:{}:",
+        NODE_SIGNED_MESSAGE_PREFIX,
+        domain,
+        message.len()
+    );
+    let mut bytes = Vec::with_capacity(header.len() + message.len());
+    bytes.extend(header.as_bytes());
+    bytes.extend(message);
+    Ok(bytes)
+}
+
+/// Compute the hash signed by the node for a domain separated message
+pub fn compute_node_signed_message_hash(message: &[u8], domain: &str) -> Result<Hash, ModelsError> {
+    Ok(Hash::compute_from(&node_signed_message_bytes(
+        message, domain,
+    )?))
+}
+
+/// Check that `signature` is a valid domain separated node signature of `message` by `public_key`
+pub fn verify_node_signed_message(
+    public_key: &PublicKey,
+    message: &[u8],
+    domain: &str,
+    signature: &Signature,
+) -> bool {
+    match compute_node_signed_message_hash(message, domain) {
+        Ok(hash) => public_key.verify_signature(&hash, signature).is_ok(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amount::Amount;
+    use crate::config::CHAINID;
+    use crate::operation::{Operation, OperationSerializer, OperationType};
+    use crate::secure_share::SecureShareContent;
+    use massa_serialization::Serializer;
+    use massa_signature::KeyPair;
+    use serial_test::serial;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_node_signed_message_prefix_vector() {
+        let bytes = node_signed_message_bytes(b"hello", "discord").unwrap();
+        assert_eq!(bytes, b"MASSA_NODE_SIGNED_MESSAGE:discord:5:hello".to_vec());
+
+        let bytes = node_signed_message_bytes(&[], "a.b-c_d").unwrap();
+        assert_eq!(bytes, b"MASSA_NODE_SIGNED_MESSAGE:a.b-c_d:0:".to_vec());
+    }
+
+    #[test]
+    fn test_node_signed_message_invalid_domain() {
+        assert!(node_signed_message_bytes(b"hello", "").is_err());
+        assert!(node_signed_message_bytes(b"hello", "with:colon").is_err());
+        assert!(node_signed_message_bytes(
+            b"hello",
+            &"a".repeat(NODE_SIGNED_MESSAGE_MAX_DOMAIN_LENGTH + 1)
+        )
+        .is_err());
+        assert!(node_signed_message_bytes(
+            b"hello",
+            &"a".repeat(NODE_SIGNED_MESSAGE_MAX_DOMAIN_LENGTH)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_node_signed_message_verify_round_trip() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let message = b"ownership proof".to_vec();
+        let signature = keypair
+            .sign(&compute_node_signed_message_hash(&message, "discord").unwrap())
+            .unwrap();
+
+        assert!(verify_node_signed_message(
+            &keypair.get_public_key(),
+            &message,
+            "discord",
+            &signature
+        ));
+        assert!(!verify_node_signed_message(
+            &keypair.get_public_key(),
+            &message,
+            "other",
+            &signature
+        ));
+        assert!(!verify_node_signed_message(
+            &keypair.get_public_key(),
+            b"another message",
+            "discord",
+            &signature
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test_node_signed_message_is_not_an_operation_signature() {
+        let keypair = KeyPair::generate(0).unwrap();
+        let content = Operation {
+            fee: Amount::from_str("1").unwrap(),
+            op: OperationType::RollBuy { roll_count: 1 },
+            expire_period: 10,
+        };
+        let mut op = Operation::new_verifiable(
+            content.clone(),
+            OperationSerializer::new(),
+            &keypair,
+            *CHAINID,
+        )
+        .unwrap();
+        op.verify_signature().unwrap();
+
+        // bytes covered by an operation signature
+        let mut serialized_content = Vec::new();
+        OperationSerializer::new()
+            .serialize(&content, &mut serialized_content)
+            .unwrap();
+        let mut signed_data = Vec::new();
+        signed_data.extend(CHAINID.to_be_bytes());
+        signed_data.extend(keypair.get_public_key().to_bytes());
+        signed_data.extend(&serialized_content);
+        assert_eq!(
+            content.compute_hash(&serialized_content, &keypair.get_public_key(), *CHAINID),
+            Hash::compute_from(&signed_data)
+        );
+
+        // a raw signature of those bytes (legacy `node_sign_message`) is a valid operation signature
+        op.signature = keypair.sign(&Hash::compute_from(&signed_data)).unwrap();
+        assert!(op.verify_signature().is_ok());
+
+        // a domain separated signature of those bytes must not be accepted for the operation
+        op.signature = keypair
+            .sign(&compute_node_signed_message_hash(&signed_data, "operation").unwrap())
+            .unwrap();
+        assert!(op.verify_signature().is_err());
+    }
+}
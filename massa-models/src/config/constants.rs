@@ -15,7 +15,13 @@
 //! (`default_testing.rs`) But as for the current file you shouldn't modify it.
 use std::str::FromStr;
 
-use crate::{amount::Amount, serialization::u32_be_bytes_min_length, version::Version};
+use crate::{
+    address::{Address, SCAddress, SCAddressV0},
+    amount::Amount,
+    serialization::u32_be_bytes_min_length,
+    version::Version,
+};
+use massa_hash::{Hash, HASH_SIZE_BYTES};
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use num::rational::Ratio;
@@ -100,6 +106,11 @@ lazy_static::lazy_static! {
             }
         }
     };
+    /// Emitter of the execution events describing the slashing of a denounced address.
+    /// It is the smart contract address of the zero hash, which no deployment can produce,
+    /// so that indexers can filter these events by emitter.
+    pub static ref SLASHING_EVENT_EMITTER_ADDRESS: Address =
+        Address::SC(SCAddress::SCAddressV0(SCAddressV0(Hash::from_bytes(&[0; HASH_SIZE_BYTES]))));
 }
 
 /// Helper function to parse args for lazy_static evaluations
@@ -246,6 +257,8 @@ pub const POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE: usize = 1024;
 pub const POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE: usize = 1024;
 /// Pool controller denunciations channel size
 pub const POOL_CONTROLLER_DENUNCIATIONS_CHANNEL_SIZE: usize = 1024;
+/// Max number of endorsements kept aside by the pool until the draws of their slot are available
+pub const POOL_MAX_PARKED_ENDORSEMENTS: usize = 256;
 
 // ***********************
 // Constants used for execution module (injected from ConsensusConfig)
@@ -338,6 +351,8 @@ pub const MAX_DENUNCIATIONS_PER_BLOCK_HEADER: u32 = 128;
 pub const ROLL_COUNT_TO_SLASH_ON_DENUNCIATION: u64 = 1;
 /// Maximum size of executed denunciations
 pub const MAX_DENUNCIATION_CHANGES_LENGTH: u64 = 1_000;
+/// Number of block production reports kept by the factory
+pub const PRODUCTION_HISTORY_LENGTH: usize = 100;
 
 // Some checks at compile time that should not be ignored!
 #[allow(clippy::assertions_on_constants)]
@@ -1,15 +1,19 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::serialization::{VecU8Deserializer, VecU8Serializer};
+use displaydoc::Display;
 use massa_serialization::{
     Deserializer, SerializeError, Serializer, U64VarIntDeserializer, U64VarIntSerializer,
 };
-use nom::error::{context, ContextError, ParseError};
+use nom::error::{context, ContextError, ErrorKind, ParseError};
 use nom::multi::length_count;
 use nom::sequence::tuple;
 use nom::{IResult, Parser};
+use serde::{Deserialize, Serialize};
+use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
 use std::ops::Bound::Included;
+use thiserror::Error;
 
 /// Datastore entry for Ledger & `ExecuteSC` Operation
 /// A Datastore is a Key Value store where
@@ -18,6 +22,107 @@ use std::ops::Bound::Included;
 /// What is stored can be arbitrary bytes but can often be smart contract bytecode (aka WASM binary)
 pub type Datastore = BTreeMap<Vec<u8>, Vec<u8>>;
 
+/// Builds a `Datastore` from a list of entries, rejecting duplicate keys
+pub fn datastore_from_entries(
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+) -> Result<Datastore, DatastoreError> {
+    let mut datastore = Datastore::new();
+    for (key, value) in entries {
+        match datastore.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+            Entry::Occupied(entry) => {
+                return Err(DatastoreError::DuplicateKey(entry.key().clone()))
+            }
+        }
+    }
+    Ok(datastore)
+}
+
+/// Limits that the datastore of an `ExecuteSC` operation must respect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatastoreLimits {
+    /// maximum number of entries
+    pub max_entry_count: u64,
+    /// maximum length of a key
+    pub max_key_length: u8,
+    /// maximum length of a value
+    pub max_value_length: u64,
+    /// maximum serialized size of the whole datastore
+    pub max_total_size: u64,
+}
+
+/// Datastore validation error
+#[non_exhaustive]
+#[derive(Display, Error, Debug, Clone, PartialEq, Eq)]
+pub enum DatastoreError {
+    /// too many datastore entries: {0}, maximum is {1}
+    TooManyEntries(u64, u64),
+    /// datastore key length {0} exceeds the maximum of {1}
+    KeyTooLong(u64, u8),
+    /// datastore value length {0} exceeds the maximum of {1}
+    ValueTooLong(u64, u64),
+    /// duplicate datastore key: {0:?}
+    DuplicateKey(Vec<u8>),
+    /// datastore serialized size {0} exceeds the maximum of {1}
+    TooLarge(u64, u64),
+}
+
+/// Datastore shipped with an `ExecuteSC` operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationDatastore<'a>(pub &'a Datastore);
+
+impl OperationDatastore<'_> {
+    /// Checks the datastore against the given limits
+    pub fn validate(&self, limits: &DatastoreLimits) -> Result<(), DatastoreError> {
+        let entry_count = self.0.len() as u64;
+        if entry_count > limits.max_entry_count {
+            return Err(DatastoreError::TooManyEntries(
+                entry_count,
+                limits.max_entry_count,
+            ));
+        }
+        for (key, value) in self.0.iter() {
+            if key.len() as u64 > limits.max_key_length as u64 {
+                return Err(DatastoreError::KeyTooLong(
+                    key.len() as u64,
+                    limits.max_key_length,
+                ));
+            }
+            if value.len() as u64 > limits.max_value_length {
+                return Err(DatastoreError::ValueTooLong(
+                    value.len() as u64,
+                    limits.max_value_length,
+                ));
+            }
+        }
+        let size = self.serialized_size() as u64;
+        if size > limits.max_total_size {
+            return Err(DatastoreError::TooLarge(size, limits.max_total_size));
+        }
+        Ok(())
+    }
+
+    /// Size of the datastore once serialized with `DatastoreSerializer`
+    pub fn serialized_size(&self) -> usize {
+        self.0
+            .iter()
+            .fold(u64_varint_size(self.0.len() as u64), |acc, (key, value)| {
+                acc.saturating_add(u64_varint_size(key.len() as u64))
+                    .saturating_add(key.len())
+                    .saturating_add(u64_varint_size(value.len() as u64))
+                    .saturating_add(value.len())
+            })
+    }
+}
+
+/// Number of bytes used by `U64VarIntSerializer` to serialize `value`
+fn u64_varint_size(value: u64) -> usize {
+    let bits = (u64::BITS - value.leading_zeros()) as usize;
+    std::cmp::max(1, (bits + 6) / 7)
+}
+
 /// Serializer for `Datastore`
 #[derive(Default)]
 pub struct DatastoreSerializer {
@@ -122,7 +227,7 @@ impl Deserializer<Datastore> for DatastoreDeserializer {
         &self,
         buffer: &'a [u8],
     ) -> IResult<&'a [u8], BTreeMap<Vec<u8>, Vec<u8>>, E> {
-        context(
+        let (rest, entries) = context(
             "Failed Datastore deserialization",
             length_count(
                 context("Failed length deserialization", |input| {
@@ -138,8 +243,15 @@ impl Deserializer<Datastore> for DatastoreDeserializer {
                 )),
             ),
         )
-        .map(|elements| elements.into_iter().collect())
-        .parse(buffer)
+        .parse(buffer)?;
+        let datastore = datastore_from_entries(entries).map_err(|_| {
+            nom::Err::Error(E::add_context(
+                buffer,
+                "Failed Datastore deserialization: duplicate key",
+                E::from_error_kind(buffer, ErrorKind::Verify),
+            ))
+        })?;
+        Ok((rest, datastore))
     }
 }
 
@@ -255,4 +367,121 @@ mod tests {
 
         assert_eq!(actual_wrapper.0, expected_datastore);
     }
+
+    fn test_limits() -> DatastoreLimits {
+        DatastoreLimits {
+            max_entry_count: 3,
+            max_key_length: 4,
+            max_value_length: 5,
+            max_total_size: 1000,
+        }
+    }
+
+    #[test]
+    fn test_operation_datastore_entry_count_limit() {
+        let mut datastore: Datastore = (0..3u8).map(|i| (vec![i], vec![i])).collect();
+        assert_eq!(
+            OperationDatastore(&datastore).validate(&test_limits()),
+            Ok(())
+        );
+        datastore.insert(vec![3], vec![3]);
+        assert_eq!(
+            OperationDatastore(&datastore).validate(&test_limits()),
+            Err(DatastoreError::TooManyEntries(4, 3))
+        );
+    }
+
+    #[test]
+    fn test_operation_datastore_key_length_limit() {
+        let datastore = BTreeMap::from([(vec![1; 4], vec![])]);
+        assert_eq!(
+            OperationDatastore(&datastore).validate(&test_limits()),
+            Ok(())
+        );
+        let datastore = BTreeMap::from([(vec![1; 5], vec![])]);
+        assert_eq!(
+            OperationDatastore(&datastore).validate(&test_limits()),
+            Err(DatastoreError::KeyTooLong(5, 4))
+        );
+    }
+
+    #[test]
+    fn test_operation_datastore_value_length_limit() {
+        let datastore = BTreeMap::from([(vec![1], vec![2; 5])]);
+        assert_eq!(
+            OperationDatastore(&datastore).validate(&test_limits()),
+            Ok(())
+        );
+        let datastore = BTreeMap::from([(vec![1], vec![2; 6])]);
+        assert_eq!(
+            OperationDatastore(&datastore).validate(&test_limits()),
+            Err(DatastoreError::ValueTooLong(6, 5))
+        );
+    }
+
+    #[test]
+    fn test_operation_datastore_total_size_limit() {
+        let datastore = BTreeMap::from([(vec![1, 2], vec![3, 4, 5]), (vec![6], vec![])]);
+        // 1 (entry count) + 1 + 2 + 1 + 3 + 1 + 1 + 1 + 0
+        let size = OperationDatastore(&datastore).serialized_size() as u64;
+        assert_eq!(size, 11);
+        let mut limits = test_limits();
+        limits.max_total_size = size;
+        assert_eq!(OperationDatastore(&datastore).validate(&limits), Ok(()));
+        limits.max_total_size = size - 1;
+        assert_eq!(
+            OperationDatastore(&datastore).validate(&limits),
+            Err(DatastoreError::TooLarge(size, size - 1))
+        );
+    }
+
+    #[test]
+    fn test_operation_datastore_serialized_size() {
+        let datastores = [
+            BTreeMap::new(),
+            BTreeMap::from([(vec![], vec![])]),
+            BTreeMap::from([
+                (vec![1; 127], vec![2; 128]),
+                (vec![3; 255], vec![4; 20_000]),
+            ]),
+            (0..200u8)
+                .map(|i| (vec![i; i as usize], vec![i; 3]))
+                .collect(),
+        ];
+        for datastore in datastores.iter() {
+            let mut buffer = Vec::new();
+            DatastoreSerializer::new()
+                .serialize(datastore, &mut buffer)
+                .unwrap();
+            assert_eq!(
+                OperationDatastore(datastore).serialized_size(),
+                buffer.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_der_duplicate_key() {
+        let serializer = U64VarIntSerializer::new();
+        let vec_serializer = VecU8Serializer::new();
+        let mut buffer = Vec::new();
+        serializer.serialize(&2, &mut buffer).unwrap();
+        for value in [vec![1], vec![2]] {
+            vec_serializer.serialize(&vec![7, 7], &mut buffer).unwrap();
+            vec_serializer.serialize(&value, &mut buffer).unwrap();
+        }
+
+        let datastore_deserializer = DatastoreDeserializer::new(
+            MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            MAX_OPERATION_DATASTORE_VALUE_LENGTH,
+        );
+        assert!(datastore_deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .is_err());
+        assert_eq!(
+            datastore_from_entries(vec![(vec![7, 7], vec![1]), (vec![7, 7], vec![2])]),
+            Err(DatastoreError::DuplicateKey(vec![7, 7]))
+        );
+    }
 }
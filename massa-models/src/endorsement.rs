@@ -254,6 +254,13 @@ impl SecureShareEndorsement {
 /// Wrapped endorsement
 pub type SecureShareEndorsement = SecureShare<Endorsement, EndorsementId>;
 
+impl SecureShareEndorsement {
+    /// Whether the endorsement can be included in the block of `slot` whose parent in the thread of `slot` is `endorsed_block`
+    pub fn is_valid_for(&self, slot: Slot, endorsed_block: &BlockId) -> bool {
+        self.content.slot == slot && self.content.endorsed_block == *endorsed_block
+    }
+}
+
 impl SecureShareContent for Endorsement {
     /// Compute the signed hash
     fn compute_signed_hash(&self, public_key: &PublicKey, content_hash: &Hash) -> Hash {
@@ -646,6 +653,32 @@ mod tests {
         verify_signature_batch(&batch_2).unwrap();
     }
 
+    #[test]
+    #[serial]
+    fn test_endorsement_is_valid_for() {
+        let slot = Slot::new(10, 1);
+        let endorsed_block = BlockId::generate_from_hash(Hash::compute_from("blk".as_bytes()));
+        let endorsement: SecureShareEndorsement = Endorsement::new_verifiable(
+            Endorsement {
+                slot,
+                index: 0,
+                endorsed_block,
+            },
+            EndorsementSerializer::new(),
+            &KeyPair::generate(0).unwrap(),
+            *CHAINID,
+        )
+        .unwrap();
+
+        assert!(endorsement.is_valid_for(slot, &endorsed_block));
+        // another slot of the same thread, or the same slot in another thread
+        assert!(!endorsement.is_valid_for(Slot::new(11, 1), &endorsed_block));
+        assert!(!endorsement.is_valid_for(Slot::new(10, 0), &endorsed_block));
+        // another endorsed block
+        let other_block = BlockId::generate_from_hash(Hash::compute_from("blk2".as_bytes()));
+        assert!(!endorsement.is_valid_for(slot, &other_block));
+    }
+
     #[test]
     #[serial]
     fn test_endorsement_id() {
@@ -62,6 +62,8 @@ pub enum ModelsError {
     OutdatedBootstrapCursor,
     /// Error raised {0}
     ErrorRaised(String),
+    /// Invalid signed message domain: {0}
+    InvalidSignedMessageDomain(String),
 }
 
 impl From<nom::Err<nom::error::Error<&[u8]>>> for ModelsError {
@@ -1,6 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::{address::Address, operation::OperationId, slot::Slot};
+use crate::{address::Address, operation::OperationId, output_event::SCOutputEvent, slot::Slot};
 use serde::{Deserialize, Serialize};
 
 /// filter used when retrieving SC output events
@@ -28,4 +28,54 @@ pub struct EventFilter {
     /// Some(false) means events coming from a succeeded sc execution
     /// None means both
     pub is_error: Option<bool>,
+    /// optional substrings that the event data must all contain
+    #[serde(default)]
+    pub data_contains: Vec<String>,
+}
+
+impl EventFilter {
+    /// Whether the event satisfies all the criteria of the filter
+    pub fn matches(&self, event: &SCOutputEvent) -> bool {
+        let context = &event.context;
+        if let Some(start) = self.start {
+            if context.slot < start {
+                return false;
+            }
+        }
+        if let Some(end) = self.end {
+            if context.slot >= end {
+                return false;
+            }
+        }
+        if let Some(is_final) = self.is_final {
+            if context.is_final != is_final {
+                return false;
+            }
+        }
+        if let Some(is_error) = self.is_error {
+            if context.is_error != is_error {
+                return false;
+            }
+        }
+        match (self.original_caller_address, context.call_stack.front()) {
+            (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        match (self.emitter_address, context.call_stack.back()) {
+            (Some(addr1), Some(addr2)) if addr1 != *addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        match (self.original_operation_id, context.origin_operation_id) {
+            (Some(addr1), Some(addr2)) if addr1 != addr2 => return false,
+            (Some(_), None) => return false,
+            _ => (),
+        }
+        // there is no index on the event data: the patterns are only matched
+        // against the events left by the cheaper criteria above
+        self.data_contains
+            .iter()
+            .all(|pattern| event.data.contains(pattern.as_str()))
+    }
 }
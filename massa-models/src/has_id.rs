@@ -0,0 +1,12 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::hash::Hash;
+
+/// Object carrying an id, used to recognize re-announcements of the same object
+pub trait HasId {
+    /// Type of the id
+    type Id: Hash + Eq + Clone + Send + 'static;
+
+    /// Get the id of the object
+    fn get_id(&self) -> Self::Id;
+}
@@ -16,10 +16,14 @@ pub mod amount;
 pub mod block;
 /// block-related structure: block_header
 pub mod block_header;
+/// block headers serialized in the format of their network version
+pub mod block_header_versioned;
 /// block-related structure: block_id
 pub mod block_id;
 /// bytecode structures
 pub mod bytecode;
+/// canonical JSON serialization
+pub mod canonical_json;
 /// clique
 pub mod clique;
 /// various structures
@@ -36,6 +40,8 @@ pub mod endorsement;
 pub mod error;
 /// execution related structures
 pub mod execution;
+/// trait of the objects carrying an id
+pub mod has_id;
 /// ledger related structures
 pub mod ledger;
 /// mapping grpc
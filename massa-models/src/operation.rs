@@ -1,7 +1,7 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::address::AddressSerializer;
-use crate::datastore::{Datastore, DatastoreDeserializer, DatastoreSerializer};
+use crate::datastore::{Datastore, DatastoreDeserializer, DatastoreSerializer, OperationDatastore};
 use crate::prehash::{PreHashSet, PreHashed};
 use crate::secure_share::{
     Id, SecureShare, SecureShareContent, SecureShareDeserializer, SecureShareSerializer,
@@ -1006,20 +1006,32 @@ impl SecureShareOperation {
         let mut res = PreHashSet::<Address>::default();
         let emitter_address = Address::from_public_key(&self.content_creator_pub_key);
         res.insert(emitter_address);
+        if let Some(target_address) = self.get_target_address() {
+            res.insert(target_address);
+        }
+        res
+    }
+
+    /// get the datastore shipped with this operation, if it is an `ExecuteSC` one
+    pub fn get_datastore(&self) -> Option<OperationDatastore<'_>> {
+        match &self.content.op {
+            OperationType::ExecuteSC { datastore, .. } => Some(OperationDatastore(datastore)),
+            _ => None,
+        }
+    }
+
+    /// get the address targeted by this operation, if any:
+    /// the recipient of a transaction or the called smart contract
+    pub fn get_target_address(&self) -> Option<Address> {
         match &self.content.op {
             OperationType::Transaction {
                 recipient_address, ..
-            } => {
-                res.insert(*recipient_address);
-            }
-            OperationType::RollBuy { .. } => {}
-            OperationType::RollSell { .. } => {}
-            OperationType::ExecuteSC { .. } => {}
-            OperationType::CallSC { target_addr, .. } => {
-                res.insert(*target_addr);
-            }
+            } => Some(*recipient_address),
+            OperationType::CallSC { target_addr, .. } => Some(*target_addr),
+            OperationType::RollBuy { .. }
+            | OperationType::RollSell { .. }
+            | OperationType::ExecuteSC { .. } => None,
         }
-        res
     }
 
     /// Gets the maximal amount of coins that may be spent by this operation (incl. fee)
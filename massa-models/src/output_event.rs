@@ -1,4 +1,7 @@
-use crate::{address::Address, block_id::BlockId, operation::OperationId, slot::Slot};
+use crate::{
+    address::Address, amount::Amount, block_id::BlockId, canonical_json::CanonicalJson,
+    denunciation::DenunciationIndex, operation::OperationId, slot::Slot,
+};
 use serde::{Deserialize, Serialize};
 use std::{collections::VecDeque, fmt::Display};
 
@@ -11,6 +14,8 @@ pub struct SCOutputEvent {
     pub data: String,
 }
 
+impl CanonicalJson for SCOutputEvent {}
+
 impl Display for SCOutputEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Context: {}", self.context)?;
@@ -18,6 +23,24 @@ impl Display for SCOutputEvent {
     }
 }
 
+/// Data of the event emitted by `SLASHING_EVENT_EMITTER_ADDRESS` when a denunciation is executed,
+/// serialized in JSON under the `massa_slashing` key of the event data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlashingEventData {
+    /// address that was denounced
+    pub denounced_address: Address,
+    /// type, slot and index of the denounced block header or endorsement
+    pub denunciation: DenunciationIndex,
+    /// number of rolls slashed
+    pub slashed_rolls: u64,
+    /// total amount slashed, from the rolls then from the deferred credits
+    pub slashed_amount: Amount,
+    /// part of the slashed amount credited to the block producer
+    pub credited_amount: Amount,
+    /// part of the slashed amount burned
+    pub burned_amount: Amount,
+}
+
 /// Context of the event (not generated by the user)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventExecutionContext {
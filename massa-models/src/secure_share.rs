@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crate::{address::Address, error::ModelsError};
+use crate::{address::Address, error::ModelsError, has_id::HasId};
 use massa_hash::Hash;
 use massa_serialization::{Deserializer, SerializeError, Serializer};
 use massa_signature::{
@@ -201,6 +201,18 @@ where
     }
 }
 
+impl<T, ID> HasId for SecureShare<T, ID>
+where
+    T: Display + SecureShareContent,
+    ID: Id + std::hash::Hash + Eq + Copy + Send + 'static,
+{
+    type Id = ID;
+
+    fn get_id(&self) -> ID {
+        self.id
+    }
+}
+
 impl<T, ID> SecureShare<T, ID>
 where
     T: Display + SecureShareContent,
@@ -51,6 +51,49 @@ impl std::fmt::Display for ExecutionStats {
     }
 }
 
+/// inputs waiting to be processed by the execution thread
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExecutionBacklog {
+    /// number of newly finalized blocks not yet taken by the execution thread
+    pub pending_finalized_block_count: usize,
+    /// whether a new blockclique is waiting to be taken by the execution thread
+    pub pending_blockclique_update: bool,
+    /// number of blocks whose metadata is waiting to be taken by the execution thread
+    pub pending_block_metadata_count: usize,
+    /// number of queued read-only execution requests
+    pub pending_readonly_request_count: usize,
+    /// number of executed slots kept in the active history
+    pub active_history_length: usize,
+}
+
+impl std::fmt::Display for ExecutionBacklog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Execution backlog:")?;
+        writeln!(
+            f,
+            "	Pending finalized blocks: {}",
+            self.pending_finalized_block_count
+        )?;
+        writeln!(
+            f,
+            "	Pending blockclique update: {}",
+            self.pending_blockclique_update
+        )?;
+        writeln!(
+            f,
+            "	Pending block metadata: {}",
+            self.pending_block_metadata_count
+        )?;
+        writeln!(
+            f,
+            "	Pending read-only requests: {}",
+            self.pending_readonly_request_count
+        )?;
+        writeln!(f, "	Active history length: {}", self.active_history_length)?;
+        Ok(())
+    }
+}
+
 /// stats produced by network module
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkStats {
@@ -78,6 +121,78 @@ impl std::fmt::Display for NetworkStats {
     }
 }
 
+/// bytes exchanged with peers for a category of messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageTrafficStats {
+    /// bytes sent to peers
+    pub bytes_sent: u64,
+    /// bytes received from peers
+    pub bytes_received: u64,
+}
+
+/// connectivity and traffic of the protocol layer.
+/// The counters are cumulative since the node started: they are kept across peer reconnections.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkTrafficStats {
+    /// in connections count
+    pub in_connection_count: u64,
+    /// out connections count
+    pub out_connection_count: u64,
+    /// banned node count
+    pub banned_peer_count: u64,
+    /// number of handshakes performed with peers
+    pub handshake_count: u64,
+    /// number of handshakes that failed
+    pub handshake_failure_count: u64,
+    /// block messages
+    pub blocks: MessageTrafficStats,
+    /// operation messages
+    pub operations: MessageTrafficStats,
+    /// endorsement messages
+    pub endorsements: MessageTrafficStats,
+    /// peer management messages
+    pub peers: MessageTrafficStats,
+}
+
+impl NetworkTrafficStats {
+    /// Fraction of the handshakes that failed, `None` if no handshake was performed
+    pub fn handshake_failure_rate(&self) -> Option<f64> {
+        (self.handshake_count > 0)
+            .then(|| self.handshake_failure_count as f64 / self.handshake_count as f64)
+    }
+}
+
+impl std::fmt::Display for NetworkTrafficStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Network traffic:")?;
+        writeln!(f, "\tIn connections: {}", self.in_connection_count)?;
+        writeln!(f, "\tOut connections: {}", self.out_connection_count)?;
+        writeln!(f, "\tBanned peers: {}", self.banned_peer_count)?;
+        write!(
+            f,
+            "\tHandshakes: {} ({} failed",
+            self.handshake_count, self.handshake_failure_count
+        )?;
+        if let Some(rate) = self.handshake_failure_rate() {
+            write!(f, ", {:.1}%", rate * 100.0)?;
+        }
+        writeln!(f, ")")?;
+        for (category, traffic) in [
+            ("Blocks", &self.blocks),
+            ("Operations", &self.operations),
+            ("Endorsements", &self.endorsements),
+            ("Peers", &self.peers),
+        ] {
+            writeln!(
+                f,
+                "\t{}: {} bytes sent, {} bytes received",
+                category, traffic.bytes_sent, traffic.bytes_received
+            )?;
+        }
+        Ok(())
+    }
+}
+
 /// stats produced by consensus module
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsensusStats {
@@ -2,8 +2,8 @@
 
 //! warning: assumes `thread_count >= 1, t0_millis >= 1, t0_millis % thread_count == 0`
 
-use massa_time::MassaTime;
-use std::convert::TryInto;
+use massa_time::{Clock, MassaTime, RealClock};
+use std::{convert::TryInto, sync::Arc, time::Instant};
 
 use crate::{error::ModelsError, slot::Slot};
 
@@ -200,9 +200,134 @@ pub fn get_closest_slot_to_timestamp(
     }
 }
 
+/// Conversions between the current time and the slots, shared by the workers that follow the slots
+#[derive(Debug, Clone)]
+pub struct SlotClock {
+    /// source of the local time
+    clock: Arc<dyn Clock>,
+    /// number of threads
+    thread_count: u8,
+    /// time between two periods in the same thread
+    t0: MassaTime,
+    /// when the blockclique first started
+    genesis_timestamp: MassaTime,
+    /// offset added to the local clock to get the network time, in milliseconds
+    clock_compensation_millis: i64,
+}
+
+impl SlotClock {
+    /// Creates a clock following the local time
+    ///
+    /// # Arguments
+    /// * `thread_count`: number of threads.
+    /// * `t0`: time in milliseconds between two periods in the same thread.
+    /// * `genesis_timestamp`: when the blockclique first started, in milliseconds.
+    pub fn new(thread_count: u8, t0: MassaTime, genesis_timestamp: MassaTime) -> Self {
+        SlotClock {
+            clock: Arc::new(RealClock),
+            thread_count,
+            t0,
+            genesis_timestamp,
+            clock_compensation_millis: 0,
+        }
+    }
+
+    /// Takes the local time from `clock` instead of the system time
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Shifts the local time by `clock_compensation_millis` to get the network time
+    pub fn with_clock_compensation(mut self, clock_compensation_millis: i64) -> Self {
+        self.clock_compensation_millis = clock_compensation_millis;
+        self
+    }
+
+    /// Converts a local timestamp to the network time
+    fn to_network_time(&self, local: MassaTime) -> MassaTime {
+        let compensation = MassaTime::from_millis(self.clock_compensation_millis.unsigned_abs());
+        if self.clock_compensation_millis >= 0 {
+            local.saturating_add(compensation)
+        } else {
+            local.saturating_sub(compensation)
+        }
+    }
+
+    /// Converts a network timestamp to the local time
+    fn to_local_time(&self, network: MassaTime) -> MassaTime {
+        let compensation = MassaTime::from_millis(self.clock_compensation_millis.unsigned_abs());
+        if self.clock_compensation_millis >= 0 {
+            network.saturating_sub(compensation)
+        } else {
+            network.saturating_add(compensation)
+        }
+    }
+
+    /// Current network time
+    pub fn now(&self) -> MassaTime {
+        self.to_network_time(self.clock.now())
+    }
+
+    /// Closest slot to a network timestamp, `Slot(0, 0)` before genesis
+    pub fn closest_slot_at(&self, timestamp: MassaTime) -> Slot {
+        get_closest_slot_to_timestamp(
+            self.thread_count,
+            self.t0,
+            self.genesis_timestamp,
+            timestamp,
+        )
+    }
+
+    /// Closest slot to the current time, `Slot(0, 0)` before genesis
+    pub fn current_slot(&self) -> Slot {
+        self.closest_slot_at(self.now())
+    }
+
+    /// Network timestamp of a slot
+    pub fn slot_timestamp(&self, slot: Slot) -> Result<MassaTime, ModelsError> {
+        get_block_slot_timestamp(self.thread_count, self.t0, self.genesis_timestamp, slot)
+    }
+
+    /// Local instant at which a network timestamp happens
+    pub fn instant_at(&self, timestamp: MassaTime) -> Result<Instant, ModelsError> {
+        Ok(self.clock.instant_at(self.to_local_time(timestamp))?)
+    }
+
+    /// Local instant at which a slot happens
+    pub fn slot_instant(&self, slot: Slot) -> Result<Instant, ModelsError> {
+        self.instant_at(self.slot_timestamp(slot)?)
+    }
+
+    /// Whether the current time reached the timestamp of a slot
+    pub fn is_slot_reached(&self, slot: Slot) -> bool {
+        self.slot_timestamp(slot)
+            .map_or(false, |timestamp| timestamp <= self.now())
+    }
+
+    /// Next slot to process after `previous_slot`: the closest slot to the current time.
+    /// Slots are skipped if the caller waited too much in-between,
+    /// and the result always comes after `previous_slot` even if the system clock went back.
+    pub fn next_slot_after(&self, previous_slot: Option<Slot>) -> Slot {
+        self.next_slot_after_at(previous_slot, self.now())
+    }
+
+    /// Next slot to process after `previous_slot` at a given network timestamp
+    fn next_slot_after_at(&self, previous_slot: Option<Slot>, timestamp: MassaTime) -> Slot {
+        let next_slot = self.closest_slot_at(timestamp);
+        match previous_slot {
+            Some(prev_slot) if next_slot <= prev_slot => prev_slot
+                .get_next_slot(self.thread_count)
+                .expect("could not compute next slot"),
+            _ => next_slot,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use massa_time::ManualClock;
     use serial_test::serial;
 
     #[test]
@@ -300,4 +425,121 @@ mod tests {
         );
         assert_eq!(out_slot, Slot::new(1, 2));
     }
+
+    #[test]
+    #[serial]
+    fn test_slot_clock_known_timestamps() {
+        /* slots:   (0, 0)  (0, 1)  (0, 2)  (1, 0)  (1, 1)  (1, 2)  (2, 0)  (2, 1)  (2, 2)
+            time:    100      110     120    130      140    150     160     170     180
+        */
+        let clock = SlotClock::new(3, MassaTime::from_millis(30), MassaTime::from_millis(100));
+
+        assert_eq!(
+            clock.slot_timestamp(Slot::new(2, 1)).unwrap(),
+            MassaTime::from_millis(170)
+        );
+        assert_eq!(
+            clock.closest_slot_at(MassaTime::from_millis(50)),
+            Slot::new(0, 0)
+        );
+        assert_eq!(
+            clock.closest_slot_at(MassaTime::from_millis(154)),
+            Slot::new(1, 2)
+        );
+
+        // first slot: the closest one
+        assert_eq!(
+            clock.next_slot_after_at(None, MassaTime::from_millis(156)),
+            Slot::new(2, 0)
+        );
+        // late: the slots in-between are skipped
+        assert_eq!(
+            clock.next_slot_after_at(Some(Slot::new(0, 1)), MassaTime::from_millis(150)),
+            Slot::new(1, 2)
+        );
+        // on time or clock adjusted backwards: the slot following the previous one
+        assert_eq!(
+            clock.next_slot_after_at(Some(Slot::new(1, 2)), MassaTime::from_millis(150)),
+            Slot::new(2, 0)
+        );
+        assert_eq!(
+            clock.next_slot_after_at(Some(Slot::new(2, 1)), MassaTime::from_millis(150)),
+            Slot::new(2, 2)
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_slot_clock_current_time() {
+        // one slot every hour
+        let t0 = MassaTime::from_millis(2 * 3_600_000);
+        let hour = MassaTime::from_millis(3_600_000);
+        let clock = SlotClock::new(2, t0, MassaTime::now().saturating_sub(t0));
+
+        assert_eq!(clock.current_slot(), Slot::new(1, 0));
+        assert_eq!(
+            clock
+                .clone()
+                .with_clock_compensation(3_600_000)
+                .current_slot(),
+            Slot::new(1, 1)
+        );
+        assert_eq!(
+            clock
+                .clone()
+                .with_clock_compensation(-3_600_000)
+                .current_slot(),
+            Slot::new(0, 1)
+        );
+
+        // the next slot happens in one hour, or right now one hour ahead of the local clock
+        let delay = clock
+            .slot_instant(Slot::new(1, 1))
+            .unwrap()
+            .saturating_duration_since(Instant::now());
+        assert!(delay <= hour.to_duration());
+        assert!(
+            delay
+                >= hour
+                    .saturating_sub(MassaTime::from_millis(1000))
+                    .to_duration()
+        );
+        let delay = clock
+            .with_clock_compensation(3_600_000)
+            .slot_instant(Slot::new(1, 1))
+            .unwrap()
+            .saturating_duration_since(Instant::now());
+        assert!(delay <= MassaTime::from_millis(1000).to_duration());
+    }
+
+    #[test]
+    #[serial]
+    fn test_slot_clock_manual_time() {
+        /* slots:   (0, 0)  (0, 1)  (0, 2)  (1, 0)  (1, 1)  (1, 2)  (2, 0)  (2, 1)  (2, 2)
+            time:    100      110     120    130      140    150     160     170     180
+        */
+        let time = ManualClock::new(MassaTime::from_millis(130));
+        let clock = SlotClock::new(3, MassaTime::from_millis(30), MassaTime::from_millis(100))
+            .with_clock(Arc::new(time.clone()));
+
+        assert_eq!(clock.current_slot(), Slot::new(1, 0));
+        assert!(clock.is_slot_reached(Slot::new(1, 0)));
+        assert!(!clock.is_slot_reached(Slot::new(1, 1)));
+        assert_eq!(
+            clock.next_slot_after(Some(Slot::new(1, 0))),
+            Slot::new(1, 1)
+        );
+
+        // the clock only moves when told to
+        time.advance(MassaTime::from_millis(20));
+        assert_eq!(clock.current_slot(), Slot::new(1, 2));
+        assert!(clock.is_slot_reached(Slot::new(1, 1)));
+        assert_eq!(
+            clock.next_slot_after(Some(Slot::new(1, 0))),
+            Slot::new(1, 2)
+        );
+        assert!(clock.slot_instant(Slot::new(1, 2)).unwrap() <= Instant::now());
+        // a time before genesis is reached as well
+        assert!(clock.instant_at(MassaTime::from_millis(85)).unwrap() <= Instant::now());
+    }
 }
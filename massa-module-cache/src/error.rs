@@ -9,6 +9,13 @@ pub enum CacheError {
     VMError(String),
     /// Load error: {0}
     LoadError(String),
+    /// Provided gas {provided} is lower than the instance creation gas cost {required}
+    InsufficientGas {
+        /// gas provided for the execution
+        provided: u64,
+        /// gas needed to create the instance
+        required: u64,
+    },
 }
 
 impl From<anyhow::Error> for CacheError {
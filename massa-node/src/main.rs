@@ -19,9 +19,10 @@ use massa_api_exports::config::APIConfig;
 use massa_async_pool::AsyncPoolConfig;
 use massa_bootstrap::BootstrapError;
 use massa_bootstrap::{
-    get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager, BootstrapTcpListener,
-    DefaultConnector,
+    get_state_with_import_progress, start_bootstrap_server, BootstrapConfig, BootstrapManager,
+    BootstrapTcpListener, DefaultConnector,
 };
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_channel::receiver::MassaReceiver;
 use massa_channel::MassaChannel;
 use massa_consensus_exports::events::ConsensusEvent;
@@ -29,7 +30,7 @@ use massa_consensus_exports::{
     ConsensusBroadcasts, ConsensusChannels, ConsensusConfig, ConsensusManager,
 };
 use massa_consensus_worker::start_consensus_worker;
-use massa_db_exports::{MassaDBConfig, MassaDBController};
+use massa_db_exports::{MassaDBCacheSizes, MassaDBConfig, MassaDBController};
 use massa_db_worker::MassaDB;
 use massa_executed_ops::{ExecutedDenunciationsConfig, ExecutedOpsConfig};
 use massa_execution_exports::{
@@ -45,9 +46,10 @@ use massa_execution_worker::storage_backend::FileStorageBackend;
 #[cfg(all(feature = "dump-block", feature = "db_storage_backend"))]
 use massa_execution_worker::storage_backend::RocksDBStorageBackend;
 
-use massa_factory_exports::{FactoryChannels, FactoryConfig, FactoryManager};
+use massa_factory_exports::{FactoryBroadcasts, FactoryChannels, FactoryConfig, FactoryManager};
 use massa_factory_worker::start_factory;
 use massa_final_state::{FinalState, FinalStateConfig, FinalStateController};
+use massa_grpc::cache::ResponseCache;
 use massa_grpc::config::{GrpcConfig, ServiceName};
 use massa_grpc::server::{MassaPrivateGrpc, MassaPublicGrpc};
 use massa_ledger_exports::LedgerConfig;
@@ -80,15 +82,16 @@ use massa_models::config::constants::{
     MAX_SIZE_CHANNEL_NETWORK_TO_ENDORSEMENT_HANDLER, MAX_SIZE_CHANNEL_NETWORK_TO_OPERATION_HANDLER,
     MAX_SIZE_CHANNEL_NETWORK_TO_PEER_HANDLER, MIP_STORE_STATS_BLOCK_CONSIDERED,
     OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, POS_MISS_RATE_DEACTIVATION_THRESHOLD,
-    POS_SAVED_CYCLES, PROTOCOL_CONTROLLER_CHANNEL_SIZE, PROTOCOL_EVENT_CHANNEL_SIZE,
-    ROLL_COUNT_TO_SLASH_ON_DENUNCIATION, ROLL_PRICE, SELECTOR_DRAW_CACHE_SIZE, T0, THREAD_COUNT,
-    VERSION,
+    POS_SAVED_CYCLES, PRODUCTION_HISTORY_LENGTH, PROTOCOL_CONTROLLER_CHANNEL_SIZE,
+    PROTOCOL_EVENT_CHANNEL_SIZE, ROLL_COUNT_TO_SLASH_ON_DENUNCIATION, ROLL_PRICE,
+    SELECTOR_DRAW_CACHE_SIZE, T0, THREAD_COUNT, VERSION,
 };
 use massa_models::config::{
     BASE_OPERATION_GAS_COST, CHAINID, KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
     MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, MAX_BOOTSTRAP_VERSIONING_ELEMENTS_SIZE,
     MAX_EVENT_DATA_SIZE, MAX_MESSAGE_SIZE, POOL_CONTROLLER_DENUNCIATIONS_CHANNEL_SIZE,
     POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE, POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE,
+    POOL_MAX_PARKED_ENDORSEMENTS,
 };
 use massa_models::slot::Slot;
 use massa_models::timeslots::get_block_slot_timestamp;
@@ -99,7 +102,7 @@ use massa_pos_worker::start_selector_worker;
 use massa_protocol_exports::{ProtocolConfig, ProtocolManager, TransportType};
 use massa_protocol_worker::{create_protocol_controller, start_protocol_controller};
 use massa_signature::KeyPair;
-use massa_storage::Storage;
+use massa_storage::{Storage, StorageCaps};
 use massa_time::MassaTime;
 use massa_versioning::keypair_factory::KeyPairFactory;
 use massa_versioning::mips::get_mip_list;
@@ -155,7 +158,15 @@ async fn launch(
     }
 
     // Storage shared by multiple components.
-    let shared_storage: Storage = Storage::create_root();
+    let shared_storage: Storage = Storage::create_root_with_caps(
+        SETTINGS.storage.orphan_removal_batch_size,
+        StorageCaps {
+            max_operations: SETTINGS.storage.max_operations,
+            max_endorsements: SETTINGS.storage.max_endorsements,
+            max_blocks: SETTINGS.storage.max_blocks,
+            max_bytes: SETTINGS.storage.max_bytes,
+        },
+    );
 
     // init final state
     let ledger_config = LedgerConfig {
@@ -183,12 +194,14 @@ async fn launch(
     let executed_ops_config = ExecutedOpsConfig {
         thread_count: THREAD_COUNT,
         keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+        operation_validity_periods: OPERATION_VALIDITY_PERIODS,
     };
     let executed_denunciations_config = ExecutedDenunciationsConfig {
         denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
         thread_count: THREAD_COUNT,
         endorsement_count: ENDORSEMENT_COUNT,
         keep_executed_history_extra_periods: KEEP_EXECUTED_HISTORY_EXTRA_PERIODS,
+        periods_per_cycle: PERIODS_PER_CYCLE,
     };
     let final_state_config = FinalStateConfig {
         ledger_config: ledger_config.clone(),
@@ -205,6 +218,11 @@ async fn launch(
         max_executed_denunciations_length: MAX_DENUNCIATION_CHANGES_LENGTH,
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
         ledger_backup_periods_interval: SETTINGS.ledger.ledger_backup_periods_interval,
+        db_cache_sizes: MassaDBCacheSizes {
+            state_cf: SETTINGS.ledger.state_cf_cache_size,
+            metadata_cf: SETTINGS.ledger.metadata_cf_cache_size,
+            versioning_cf: SETTINGS.ledger.versioning_cf_cache_size,
+        },
         t0: T0,
         genesis_timestamp: *GENESIS_TIMESTAMP,
     };
@@ -240,9 +258,11 @@ async fn launch(
         thread_count: THREAD_COUNT,
         max_ledger_backups: SETTINGS.ledger.max_ledger_backups,
     };
-    let db = Arc::new(RwLock::new(
-        Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
-    ));
+    let db = Arc::new(RwLock::new(Box::new(MassaDB::new_with_cache_sizes(
+        db_config,
+        final_state_config.db_cache_sizes,
+    ))
+        as Box<(dyn MassaDBController + 'static)>));
 
     // Create final ledger
     let ledger = FinalLedger::new(ledger_config.clone(), db.clone());
@@ -372,7 +392,7 @@ async fn launch(
         chain_id: *CHAINID,
     };
 
-    let bootstrap_state = match get_state(
+    let bootstrap_state = match get_state_with_import_progress(
         &bootstrap_config,
         final_state.clone(),
         DefaultConnector,
@@ -382,6 +402,9 @@ async fn launch(
         args.restart_from_snapshot_at_period,
         sig_int_toggled.clone(),
         massa_metrics.clone(),
+        Some(Arc::new(|imported_keys, _| {
+            debug!("Bootstrap: {} final state keys imported", imported_keys);
+        })),
     ) {
         Ok(vals) => vals,
         Err(BootstrapError::Interrupted(msg)) => {
@@ -492,6 +515,8 @@ async fn launch(
         max_datastore_value_size: MAX_DATASTORE_VALUE_LENGTH,
         storage_costs_constants,
         max_read_only_gas: SETTINGS.execution.max_read_only_gas,
+        max_read_only_trace_depth: SETTINGS.execution.max_read_only_trace_depth,
+        max_read_only_trace_calls: SETTINGS.execution.max_read_only_trace_calls,
         gas_costs: gas_costs.clone(),
         base_operation_gas_cost: BASE_OPERATION_GAS_COST,
         last_start_period: final_state.read().get_last_start_period(),
@@ -571,11 +596,17 @@ async fn launch(
         max_block_endorsement_count: ENDORSEMENT_COUNT,
         operation_validity_periods: OPERATION_VALIDITY_PERIODS,
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+        max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
         max_operation_pool_size: SETTINGS.pool.max_operation_pool_size,
         max_operation_pool_excess_items: SETTINGS.pool.max_operation_pool_excess_items,
+        max_operation_pool_bytes: SETTINGS.pool.max_operation_pool_bytes,
+        max_operation_pool_excess_bytes: SETTINGS.pool.max_operation_pool_excess_bytes,
         operation_pool_refresh_interval: SETTINGS.pool.operation_pool_refresh_interval,
         operation_max_future_start_delay: SETTINGS.pool.operation_max_future_start_delay,
         max_endorsements_pool_size_per_thread: SETTINGS.pool.max_endorsements_pool_size_per_thread,
+        max_parked_endorsements: POOL_MAX_PARKED_ENDORSEMENTS,
         operations_channel_size: POOL_CONTROLLER_OPERATIONS_CHANNEL_SIZE,
         endorsements_channel_size: POOL_CONTROLLER_ENDORSEMENTS_CHANNEL_SIZE,
         denunciations_channel_size: POOL_CONTROLLER_DENUNCIATIONS_CHANNEL_SIZE,
@@ -584,6 +615,15 @@ async fn launch(
             .pool
             .broadcast_endorsements_channel_capacity,
         broadcast_operations_channel_capacity: SETTINGS.pool.broadcast_operations_channel_capacity,
+        broadcast_dedup_capacity: SETTINGS.pool.broadcast_dedup_capacity,
+        broadcast_pool_events_channel_capacity: SETTINGS
+            .pool
+            .broadcast_pool_events_channel_capacity,
+        max_expired_operations: SETTINGS.pool.max_expired_operations,
+        expired_operations_retention: SETTINGS.pool.expired_operations_retention,
+        max_selection_audit_slots: SETTINGS.pool.max_selection_audit_slots,
+        operation_pool_ref_audit_interval: SETTINGS.pool.operation_pool_ref_audit_interval,
+        operation_pool_ref_audit_self_heal: SETTINGS.pool.operation_pool_ref_audit_self_heal,
         genesis_timestamp: *GENESIS_TIMESTAMP,
         t0: T0,
         periods_per_cycle: PERIODS_PER_CYCLE,
@@ -595,12 +635,26 @@ async fn launch(
 
     let pool_channels = PoolChannels {
         broadcasts: PoolBroadcasts {
-            endorsement_sender: broadcast::channel(
+            endorsement_sender: DedupBroadcaster::new(
+                "endorsements".to_string(),
                 pool_config.broadcast_endorsements_channel_capacity,
+                pool_config.broadcast_dedup_capacity,
+            ),
+            operation_sender: DedupBroadcaster::new(
+                "operations".to_string(),
+                pool_config.broadcast_operations_channel_capacity,
+                pool_config.broadcast_dedup_capacity,
+            ),
+            expired_operation_sender: broadcast::channel(
+                pool_config.broadcast_operations_channel_capacity,
             )
             .0,
-            operation_sender: broadcast::channel(pool_config.broadcast_operations_channel_capacity)
-                .0,
+            // an object may be admitted again after leaving the pool: its events are never suppressed
+            pool_event_sender: DedupBroadcaster::new(
+                "pool_events".to_string(),
+                pool_config.broadcast_pool_events_channel_capacity,
+                0,
+            ),
         },
         selector: selector_controller.clone(),
         execution_controller: execution_controller.clone(),
@@ -611,6 +665,7 @@ async fn launch(
         &shared_storage,
         pool_channels.clone(),
         node_wallet.clone(),
+        massa_metrics.clone(),
     );
 
     // launch protocol controller
@@ -728,6 +783,9 @@ async fn launch(
         force_keep_final_periods: SETTINGS.consensus.force_keep_final_periods,
         endorsement_count: ENDORSEMENT_COUNT,
         block_db_prune_interval: SETTINGS.consensus.block_db_prune_interval,
+        max_block_registration_batch_size: SETTINGS.consensus.max_block_registration_batch_size,
+        max_command_timings: SETTINGS.consensus.max_command_timings,
+        slow_command_threshold: SETTINGS.consensus.slow_command_threshold,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
         channel_size: CHANNEL_SIZE,
         bootstrap_part_size: CONSENSUS_BOOTSTRAP_PART_SIZE,
@@ -739,6 +797,7 @@ async fn launch(
         broadcast_filled_blocks_channel_capacity: SETTINGS
             .consensus
             .broadcast_filled_blocks_channel_capacity,
+        broadcast_dedup_capacity: SETTINGS.consensus.broadcast_dedup_capacity,
         last_start_period: final_state.read().get_last_start_period(),
         force_keep_final_periods_without_ops: SETTINGS
             .consensus
@@ -759,11 +818,16 @@ async fn launch(
                 consensus_config.broadcast_blocks_headers_channel_capacity,
             )
             .0,
-            block_sender: broadcast::channel(consensus_config.broadcast_blocks_channel_capacity).0,
-            filled_block_sender: broadcast::channel(
+            block_sender: DedupBroadcaster::new(
+                "blocks".to_string(),
+                consensus_config.broadcast_blocks_channel_capacity,
+                consensus_config.broadcast_dedup_capacity,
+            ),
+            filled_block_sender: DedupBroadcaster::new(
+                "filled_blocks".to_string(),
                 consensus_config.broadcast_filled_blocks_channel_capacity,
-            )
-            .0,
+                consensus_config.broadcast_dedup_capacity,
+            ),
         },
     };
 
@@ -797,20 +861,47 @@ async fn launch(
         max_block_size: MAX_BLOCK_SIZE as u64,
         max_block_gas: MAX_GAS_PER_BLOCK,
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+        production_history_length: PRODUCTION_HISTORY_LENGTH,
+        min_operation_fee: SETTINGS.factory.min_operation_fee,
         last_start_period: final_state.read().get_last_start_period(),
         periods_per_cycle: PERIODS_PER_CYCLE,
         denunciation_expire_periods: DENUNCIATION_EXPIRE_PERIODS,
+        max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
+        denunciation_lookback_slots: SETTINGS
+            .factory
+            .denunciation_lookback_slots
+            .unwrap_or(DENUNCIATION_EXPIRE_PERIODS * THREAD_COUNT as u64),
         stop_production_when_zero_connections: SETTINGS
             .factory
             .stop_production_when_zero_connections,
+        enable_block_production: SETTINGS.factory.enable_block_production,
+        enable_endorsement_production: SETTINGS.factory.enable_endorsement_production,
+        enable_denunciation: SETTINGS.factory.enable_denunciation,
+        broadcast_enabled: SETTINGS.api.enable_broadcast,
         chain_id: *CHAINID,
+        production_blocklist: SETTINGS
+            .factory
+            .production_blocklist
+            .iter()
+            .copied()
+            .collect(),
+        endorsement_producer_filter: SETTINGS.factory.endorsement_producer_filter.clone(),
     };
+    factory_config
+        .validate()
+        .expect("invalid factory configuration");
     let factory_channels = FactoryChannels {
         selector: selector_controller.clone(),
         consensus: consensus_controller.clone(),
         pool: pool_controller.clone(),
+        execution: execution_controller.clone(),
         protocol: protocol_controller.clone(),
         storage: shared_storage.clone(),
+        broadcasts: FactoryBroadcasts {
+            block_sender: consensus_channels.broadcasts.block_sender.clone(),
+            filled_block_sender: consensus_channels.broadcasts.filled_block_sender.clone(),
+            endorsement_sender: pool_channels.broadcasts.endorsement_sender.clone(),
+        },
     };
     let factory_manager = start_factory(
         factory_config,
@@ -847,6 +938,9 @@ async fn launch(
         bind_public: SETTINGS.api.bind_public,
         bind_api: SETTINGS.api.bind_api,
         draw_lookahead_period_count: SETTINGS.api.draw_lookahead_period_count,
+        endorsement_attribution_lookback_period_count: SETTINGS
+            .api
+            .endorsement_attribution_lookback_period_count,
         max_arguments: SETTINGS.api.max_arguments,
         openrpc_spec_path: SETTINGS.api.openrpc_spec_path.clone(),
         bootstrap_whitelist_path: SETTINGS.bootstrap.bootstrap_whitelist_path.clone(),
@@ -866,6 +960,7 @@ async fn launch(
         max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
         max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
+        max_block_size: MAX_BLOCK_SIZE,
         base_operation_gas_cost: BASE_OPERATION_GAS_COST,
         sp_compilation_cost: gas_costs.sp_compilation_cost,
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
@@ -878,7 +973,12 @@ async fn launch(
         last_start_period: final_state.read().get_last_start_period(),
         chain_id: *CHAINID,
         deferred_credits_delta: SETTINGS.api.deferred_credits_delta,
+        max_deferred_credits_slot_range: SETTINGS.api.max_deferred_credits_slot_range,
         minimal_fees: SETTINGS.pool.minimal_fees,
+        pool_snapshot_retention: SETTINGS.api.pool_snapshot_retention,
+        max_denunciation_evidences_per_second: SETTINGS.api.max_denunciation_evidences_per_second,
+        max_event_data_patterns: SETTINGS.api.max_event_data_patterns,
+        max_event_data_pattern_length: SETTINGS.api.max_event_data_pattern_length,
     };
 
     // spawn Massa API
@@ -886,6 +986,7 @@ async fn launch(
         consensus_controller.clone(),
         consensus_channels.broadcasts.clone(),
         execution_controller.clone(),
+        execution_channels.clone(),
         pool_channels.broadcasts.clone(),
         api_config.clone(),
         *VERSION,
@@ -931,6 +1032,7 @@ async fn launch(
             keypair_factory: KeyPairFactory {
                 mip_store: mip_store.clone(),
             },
+            response_cache: ResponseCache::new(&grpc_public_config),
         };
 
         // Spawn gRPC PUBLIC API
@@ -1002,10 +1104,16 @@ async fn launch(
     // spawn private API
     let api_private = API::<Private>::new(
         protocol_controller.clone(),
+        consensus_controller.clone(),
         execution_controller.clone(),
+        pool_controller.clone(),
         api_config.clone(),
         sig_int_toggled,
         node_wallet,
+        factory_manager.get_production_blocklist(),
+        final_state.clone(),
+        shared_storage.clone_without_refs(),
+        factory_manager.get_production_history(),
     );
     let api_private_handle = api_private
         .serve(&SETTINGS.api.bind_private, &api_config)
@@ -1109,6 +1217,7 @@ fn configure_grpc(
     final_state: &Arc<RwLock<dyn FinalStateController>>,
     minimal_fees: Amount,
 ) -> GrpcConfig {
+    let default_max_concurrent_streams = name.default_max_concurrent_streams();
     GrpcConfig {
         name,
         enabled: settings.enabled,
@@ -1123,13 +1232,16 @@ fn configure_grpc(
         bind: settings.bind,
         accept_compressed: settings.accept_compressed.clone(),
         send_compressed: settings.send_compressed.clone(),
+        compress_threshold_bytes: settings.compress_threshold_bytes,
         max_decoding_message_size: settings.max_decoding_message_size,
         max_encoding_message_size: settings.max_encoding_message_size,
         concurrency_limit_per_connection: settings.concurrency_limit_per_connection,
         timeout: settings.timeout.to_duration(),
         initial_stream_window_size: settings.initial_stream_window_size,
         initial_connection_window_size: settings.initial_connection_window_size,
-        max_concurrent_streams: settings.max_concurrent_streams,
+        max_concurrent_streams: settings
+            .max_concurrent_streams
+            .or(default_max_concurrent_streams),
         max_arguments: settings.max_arguments,
         tcp_keepalive: settings.tcp_keepalive.map(|t| t.to_duration()),
         tcp_nodelay: settings.tcp_nodelay,
@@ -1155,6 +1267,7 @@ fn configure_grpc(
         periods_per_cycle: PERIODS_PER_CYCLE,
         keypair,
         max_channel_size: settings.max_channel_size,
+        stream_client_idle_timeout: settings.stream_client_idle_timeout.map(|t| t.to_duration()),
         draw_lookahead_period_count: settings.draw_lookahead_period_count,
         last_start_period: final_state.read().get_last_start_period(),
         max_denunciations_per_block_header: MAX_DENUNCIATIONS_PER_BLOCK_HEADER,
@@ -1165,6 +1278,9 @@ fn configure_grpc(
         max_operation_ids_per_request: settings.max_operation_ids_per_request,
         max_filters_per_request: settings.max_filters_per_request,
         max_query_items_per_request: settings.max_query_items_per_request,
+        response_cache_methods: settings.response_cache_methods.clone(),
+        response_cache_ttl: settings.response_cache_ttl.to_duration(),
+        response_cache_max_entries: settings.response_cache_max_entries,
         certificate_authority_root_path: settings.certificate_authority_root_path.clone(),
         server_certificate_path: settings.server_certificate_path.clone(),
         server_private_key_path: settings.server_private_key_path.clone(),
@@ -1173,6 +1289,9 @@ fn configure_grpc(
             .clone(),
         client_certificate_path: settings.client_certificate_path.clone(),
         client_private_key_path: settings.client_private_key_path.clone(),
+        certificate_reload_interval: settings
+            .certificate_reload_interval
+            .map(|t| t.to_duration()),
         chain_id: *CHAINID,
         minimal_fees,
     }
@@ -4,7 +4,8 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use massa_bootstrap::IpType;
-use massa_models::{amount::Amount, config::build_massa_settings, node::NodeId};
+use massa_factory_exports::EndorsementProducerFilter;
+use massa_models::{address::Address, amount::Amount, config::build_massa_settings, node::NodeId};
 use massa_protocol_exports::PeerCategoryInfo;
 use massa_time::MassaTime;
 use serde::Deserialize;
@@ -26,6 +27,8 @@ pub struct ExecutionSettings {
     pub cursor_delay: MassaTime,
     pub stats_time_window_duration: MassaTime,
     pub max_read_only_gas: u64,
+    pub max_read_only_trace_depth: usize,
+    pub max_read_only_trace_calls: usize,
     pub abi_gas_costs_file: PathBuf,
     pub wasm_gas_costs_file: PathBuf,
     pub hd_cache_path: PathBuf,
@@ -52,6 +55,9 @@ pub struct LedgerSettings {
     pub initial_deferred_credits_path: Option<PathBuf>,
     pub ledger_backup_periods_interval: u64,
     pub max_ledger_backups: u64,
+    pub state_cf_cache_size: usize,
+    pub metadata_cf_cache_size: usize,
+    pub versioning_cf_cache_size: usize,
 }
 
 /// Bootstrap configuration.
@@ -88,6 +94,21 @@ pub struct FactorySettings {
     pub staking_wallet_path: PathBuf,
     /// stop the production in case we are not connected to anyone
     pub stop_production_when_zero_connections: bool,
+    /// produce blocks
+    pub enable_block_production: bool,
+    /// produce endorsements
+    pub enable_endorsement_production: bool,
+    /// include denunciations in the produced blocks
+    pub enable_denunciation: bool,
+    /// addresses whose operations are left out of the blocks produced by the node
+    pub production_blocklist: Vec<Address>,
+    /// minimal fee of the operations included in the blocks produced by the node
+    pub min_operation_fee: Amount,
+    /// number of slots before a produced block whose denunciations it can include.
+    /// Defaults to the whole denunciation validity window.
+    pub denunciation_lookback_slots: Option<u64>,
+    /// producers whose blocks are endorsed by the node, all of them if not set
+    pub endorsement_producer_filter: Option<EndorsementProducerFilter>,
 }
 
 /// Pool configuration, read from a file configuration
@@ -96,6 +117,8 @@ pub struct FactorySettings {
 pub struct PoolSettings {
     pub max_operation_pool_size: usize,
     pub max_operation_pool_excess_items: usize,
+    pub max_operation_pool_bytes: usize,
+    pub max_operation_pool_excess_bytes: usize,
     pub operation_max_future_start_delay: MassaTime,
     pub operation_pool_refresh_interval: MassaTime,
     pub max_endorsements_pool_size_per_thread: usize,
@@ -104,6 +127,20 @@ pub struct PoolSettings {
     pub broadcast_endorsements_channel_capacity: usize,
     /// operations channel capacity
     pub broadcast_operations_channel_capacity: usize,
+    /// number of recently broadcast operation and endorsement ids remembered to avoid broadcasting them twice
+    pub broadcast_dedup_capacity: u32,
+    /// pool events channel capacity
+    pub broadcast_pool_events_channel_capacity: usize,
+    /// max number of recently expired operations remembered
+    pub max_expired_operations: usize,
+    /// time during which an expired operation is remembered
+    pub expired_operations_retention: MassaTime,
+    /// number of recent slots whose operation selection is audited, 0 to disable the audit
+    pub max_selection_audit_slots: usize,
+    /// interval between the audits of the operation pool storage references, 0 to disable the audit
+    pub operation_pool_ref_audit_interval: MassaTime,
+    /// whether the audit repairs the discrepancies it finds
+    pub operation_pool_ref_audit_self_heal: bool,
     /// operations minimum fees for block creator
     pub minimal_fees: Amount,
 }
@@ -112,6 +149,7 @@ pub struct PoolSettings {
 #[derive(Debug, Deserialize, Clone)]
 pub struct APISettings {
     pub draw_lookahead_period_count: u64,
+    pub endorsement_attribution_lookback_period_count: u64,
     pub bind_private: SocketAddr,
     pub bind_public: SocketAddr,
     pub bind_api: SocketAddr,
@@ -130,6 +168,11 @@ pub struct APISettings {
     // whether to broadcast for blocks, endorsement and operations
     pub enable_broadcast: bool,
     pub deferred_credits_delta: MassaTime,
+    pub max_deferred_credits_slot_range: u64,
+    pub pool_snapshot_retention: MassaTime,
+    pub max_denunciation_evidences_per_second: u64,
+    pub max_event_data_patterns: u64,
+    pub max_event_data_pattern_length: u64,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -149,6 +192,7 @@ pub struct Settings {
     pub metrics: MetricsSettings,
     pub versioning: VersioningSettings,
     pub block_dump: BlockDumpSettings,
+    pub storage: StorageSettings,
 }
 
 /// Consensus configuration
@@ -169,12 +213,20 @@ pub struct ConsensusSettings {
     pub force_keep_final_periods_without_ops: u64,
     /// old blocks are pruned every `block_db_prune_interval`
     pub block_db_prune_interval: MassaTime,
+    /// max number of queued block registrations processed together with a single graph update
+    pub max_block_registration_batch_size: usize,
+    /// number of recently executed commands whose timing is kept, when the metrics are enabled
+    pub max_command_timings: usize,
+    /// a warning is logged for the commands taking longer than this to execute, when the metrics are enabled
+    pub slow_command_threshold: MassaTime,
     /// blocks headers channel capacity
     pub broadcast_blocks_headers_channel_capacity: usize,
     /// blocks channel capacity
     pub broadcast_blocks_channel_capacity: usize,
     /// filled blocks channel capacity
     pub broadcast_filled_blocks_channel_capacity: usize,
+    /// number of recently broadcast block ids remembered to avoid broadcasting a block twice
+    pub broadcast_dedup_capacity: u32,
 }
 
 // TODO: Remove one date. Kept for retro compatibility.
@@ -310,12 +362,16 @@ pub struct GrpcSettings {
     pub accept_compressed: Option<String>,
     /// which compression encodings might the server use for responses
     pub send_compressed: Option<String>,
+    /// only compress the unary responses larger than this size in bytes
+    pub compress_threshold_bytes: Option<usize>,
     /// limits the maximum size of a decoded message. Defaults to 4MB
     pub max_decoding_message_size: usize,
     /// limits the maximum size of an encoded message. Defaults to 4MB
     pub max_encoding_message_size: usize,
     /// limits the maximum size of streaming channel
     pub max_channel_size: usize,
+    /// drop a stream whose client hasn't read anything for this duration while its channel is full
+    pub stream_client_idle_timeout: Option<MassaTime>,
     /// set the concurrency limit applied to on requests inbound per connection. Defaults to 32
     pub concurrency_limit_per_connection: usize,
     /// set a timeout on for all request handlers
@@ -324,7 +380,7 @@ pub struct GrpcSettings {
     pub initial_stream_window_size: Option<u32>,
     /// sets the max connection-level flow control for HTTP2. Default is 65,535
     pub initial_connection_window_size: Option<u32>,
-    /// sets the SETTINGS_MAX_CONCURRENT_STREAMS spec option for HTTP2 connections. Default is no limit (`None`)
+    /// sets the SETTINGS_MAX_CONCURRENT_STREAMS spec option for HTTP2 connections. Defaults to 100 for the public service and no limit for the private one
     pub max_concurrent_streams: Option<u32>,
     /// max number of arguments per gRPC request
     pub max_arguments: u64,
@@ -358,6 +414,12 @@ pub struct GrpcSettings {
     pub max_filters_per_request: u32,
     /// max number of query items that can be included in a single request
     pub max_query_items_per_request: u32,
+    /// methods whose responses are cached until a new slot is executed or finalized (public service only)
+    pub response_cache_methods: Vec<String>,
+    /// max age of a cached response
+    pub response_cache_ttl: MassaTime,
+    /// max number of cached responses
+    pub response_cache_max_entries: usize,
     /// certificate authority root path
     pub certificate_authority_root_path: PathBuf,
     /// server certificate path
@@ -370,6 +432,8 @@ pub struct GrpcSettings {
     pub client_certificate_path: PathBuf,
     /// client private key path
     pub client_private_key_path: PathBuf,
+    /// interval at which the TLS certificate files are checked for changes and reloaded
+    pub certificate_reload_interval: Option<MassaTime>,
 }
 
 /// gRPC API settings.
@@ -396,6 +460,24 @@ pub struct BlockDumpSettings {
     pub(crate) max_blocks: u64,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct StorageSettings {
+    /// Max number of orphaned objects removed from a storage index before yielding its lock
+    pub orphan_removal_batch_size: usize,
+    /// Max number of stored operations, beyond which the operations gossiped by peers are dropped.
+    /// Unbounded if absent
+    pub max_operations: Option<usize>,
+    /// Max number of stored endorsements, beyond which the endorsements gossiped by peers are dropped.
+    /// Unbounded if absent
+    pub max_endorsements: Option<usize>,
+    /// Max number of stored blocks, beyond which the blocks retrieved from peers are put on hold.
+    /// Unbounded if absent
+    pub max_blocks: Option<usize>,
+    /// Max estimated size in bytes of the stored objects, beyond which the operations and endorsements
+    /// gossiped by peers are dropped and the blocks retrieved from peers put on hold. Unbounded if absent
+    pub max_bytes: Option<usize>,
+}
+
 #[cfg(test)]
 #[test]
 fn test_load_node_config() {
@@ -1,7 +1,10 @@
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_execution_exports::ExecutionController;
 use massa_models::{endorsement::SecureShareEndorsement, operation::SecureShareOperation};
 use massa_pos_exports::SelectorController;
 
+use crate::{ExpiredOperation, PoolEvent};
+
 /// channels used by the pool worker
 #[derive(Clone)]
 pub struct PoolChannels {
@@ -13,11 +16,17 @@ pub struct PoolChannels {
     pub broadcasts: PoolBroadcasts,
 }
 
-/// Broadcasts used by the pool worker to send new operations and endorsements
+/// Broadcasts used by the pool worker to send new operations and endorsements, expired operations
+/// and the changes of the pool content
 #[derive(Clone)]
 pub struct PoolBroadcasts {
     /// Broadcast channel for new endorsements
-    pub endorsement_sender: tokio::sync::broadcast::Sender<SecureShareEndorsement>,
+    pub endorsement_sender: DedupBroadcaster<SecureShareEndorsement>,
     /// Broadcast channel for new operations
-    pub operation_sender: tokio::sync::broadcast::Sender<SecureShareOperation>,
+    pub operation_sender: DedupBroadcaster<SecureShareOperation>,
+    /// Broadcast channel for the operations that expired without being executed
+    pub expired_operation_sender: tokio::sync::broadcast::Sender<ExpiredOperation>,
+    /// Broadcast channel for the admissions and removals of operations and endorsements.
+    /// Sending never waits: the events not yet received when the channel is full are lost.
+    pub pool_event_sender: DedupBroadcaster<PoolEvent>,
 }
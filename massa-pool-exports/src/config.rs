@@ -27,12 +27,24 @@ pub struct PoolConfig {
     pub operation_max_future_start_delay: MassaTime,
     /// max operations per block
     pub max_operations_per_block: u32,
+    /// max number of entries in the datastore of an operation
+    pub max_op_datastore_entry_count: u64,
+    /// max length of a key in the datastore of an operation
+    pub max_op_datastore_key_length: u8,
+    /// max length of a value in the datastore of an operation
+    pub max_op_datastore_value_length: u64,
     /// max operation pool size per thread (in number of operations)
     pub max_operation_pool_size: usize,
     /// max excess on pool size (in-between refreshes)
     pub max_operation_pool_excess_items: usize,
+    /// max total serialized size of the operations of the pool (in bytes)
+    pub max_operation_pool_bytes: usize,
+    /// max excess on the serialized size of the pool (in-between refreshes, in bytes)
+    pub max_operation_pool_excess_bytes: usize,
     /// max endorsement pool size per thread (in number of endorsements)
     pub max_endorsements_pool_size_per_thread: usize,
+    /// max number of endorsements waiting for the draws of their slot to be verified
+    pub max_parked_endorsements: usize,
     /// max number of endorsements per block
     pub max_block_endorsement_count: u32,
     /// operations channel capacity
@@ -47,6 +59,20 @@ pub struct PoolConfig {
     pub broadcast_endorsements_channel_capacity: usize,
     /// operations channel capacity
     pub broadcast_operations_channel_capacity: usize,
+    /// number of recently broadcast operation and endorsement ids remembered to avoid broadcasting them twice
+    pub broadcast_dedup_capacity: u32,
+    /// pool events channel capacity
+    pub broadcast_pool_events_channel_capacity: usize,
+    /// max number of recently expired operations remembered
+    pub max_expired_operations: usize,
+    /// time during which an expired operation is remembered
+    pub expired_operations_retention: MassaTime,
+    /// number of recent slots whose operation selection is audited, 0 to disable the audit
+    pub max_selection_audit_slots: usize,
+    /// interval between the audits of the operation pool storage references, 0 to disable the audit
+    pub operation_pool_ref_audit_interval: MassaTime,
+    /// whether the audit repairs the discrepancies it finds
+    pub operation_pool_ref_audit_self_heal: bool,
     /// genesis timestamp
     pub genesis_timestamp: MassaTime,
     /// period duration
@@ -1,14 +1,22 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use massa_models::{
+    address::Address,
+    amount::Amount,
     block_id::BlockId,
     denunciation::{Denunciation, DenunciationPrecursor},
     endorsement::EndorsementId,
     operation::OperationId,
+    prehash::PreHashSet,
     slot::Slot,
 };
 use massa_storage::Storage;
 
+use crate::{
+    ExpiredOperation, PoolSnapshot, PooledEndorsementInfo, PooledOperationInfo,
+    PooledOperationSummary, SelectionAudit,
+};
+
 #[cfg(feature = "test-exports")]
 use std::sync::{Arc, RwLock};
 
@@ -27,8 +35,19 @@ pub trait PoolController: Send + Sync {
     /// Asynchronously notify of new consensus final periods. Simply print a warning on failure.
     fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]);
 
-    /// Get operations for block creation.
-    fn get_block_operations(&self, slot: &Slot) -> (Vec<OperationId>, Storage);
+    /// Get operations for block creation, leaving out the operations created by `excluded_creators`
+    /// and the ones paying less than `min_fee`, which stay in the pool.
+    /// Also returns the number of operations that were left out because of their creator.
+    fn get_block_operations(
+        &self,
+        slot: &Slot,
+        excluded_creators: &PreHashSet<Address>,
+        min_fee: Amount,
+    ) -> (Vec<OperationId>, Storage, usize);
+
+    /// Get the audit of the operation selection for a recent slot, if the audit is enabled and
+    /// operations were selected for that slot
+    fn get_selection_audit(&self, slot: &Slot) -> Option<SelectionAudit>;
 
     /// Get endorsements for a block.
     fn get_block_endorsements(
@@ -37,8 +56,14 @@ pub trait PoolController: Send + Sync {
         slot: &Slot,
     ) -> (Vec<Option<EndorsementId>>, Storage);
 
-    /// Get denunciations for a block header.
-    fn get_block_denunciations(&self, target_slot: &Slot) -> Vec<Denunciation>;
+    /// Get denunciations for a block header, as many as it can hold, in the order of `get_denunciations_for_block`.
+    /// The denunciations of slots before `min_slot` are left out before filling the header.
+    fn get_block_denunciations(&self, target_slot: &Slot, min_slot: &Slot) -> Vec<Denunciation>;
+
+    /// Get at most `limit` denunciations for the block at `target_slot`, most valuable first:
+    /// block header denunciations come before endorsement denunciations, then the oldest denounced
+    /// slots, which are the first to expire, come first.
+    fn get_denunciations_for_block(&self, target_slot: &Slot, limit: usize) -> Vec<Denunciation>;
 
     /// Get the number of endorsements in the pool
     fn get_endorsement_count(&self) -> usize;
@@ -52,15 +77,30 @@ pub trait PoolController: Send + Sync {
     /// Check if the pool contains a list of operations. Returns one boolean per item.
     fn contains_operations(&self, operations: &[OperationId]) -> Vec<bool>;
 
+    /// Get the operations of a list that recently expired without being executed.
+    /// Returns one item per operation, `None` if it is not known to have expired.
+    fn get_expired_operations(&self, operations: &[OperationId]) -> Vec<Option<ExpiredOperation>>;
+
     /// Get the number of denunciations in the pool
     fn get_denunciation_count(&self) -> usize;
 
+    /// Take a snapshot of the operations in the pool.
+    /// The operations are not claimed: their ids may refer to operations that were since removed.
+    fn snapshot_operations(&self) -> PoolSnapshot<PooledOperationInfo>;
+
+    /// Take a snapshot of the endorsements in the pool.
+    /// The endorsements are not claimed: their ids may refer to endorsements that were since removed.
+    fn snapshot_endorsements(&self) -> PoolSnapshot<PooledEndorsementInfo>;
+
     /// Returns a boxed clone of self.
     /// Useful to allow cloning `Box<dyn PoolController>`.
     fn clone_box(&self) -> Box<dyn PoolController>;
 
     /// Get final cs periods (updated regularly from consensus)
     fn get_final_cs_periods(&self) -> Vec<u64>;
+
+    /// Get the metadata of at most `max_items` operations of the pool, the best ranked first
+    fn get_operations_sample(&self, max_items: usize) -> Vec<PooledOperationSummary>;
 }
 
 /// Allow cloning `Box<dyn PoolController>`
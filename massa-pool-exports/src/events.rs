@@ -0,0 +1,61 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Changes of the content of the operation and endorsement pools.
+//!
+//! Every operation and endorsement admitted in the pool produces one `Admitted` event, and one
+//! removal event when it leaves the pool. The objects that are rejected before being admitted
+//! produce no event.
+
+use massa_models::{
+    address::Address, endorsement::EndorsementId, has_id::HasId, operation::OperationId, slot::Slot,
+};
+use serde::{Deserialize, Serialize};
+
+/// Kind of change of the pool content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolEventKind {
+    /// the object entered the pool
+    Admitted,
+    /// the operation left the pool because it was executed in a block
+    Included,
+    /// the object left the pool because it can no longer be included in a block
+    Expired,
+    /// the object left the pool because the pool was full
+    Evicted,
+    /// the operation left the pool because it is not worth including anymore, see the reason of the event
+    Discarded,
+}
+
+/// Object of the pool an event is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolObjectId {
+    /// an operation
+    Operation(OperationId),
+    /// an endorsement
+    Endorsement(EndorsementId),
+}
+
+/// Change of the pool content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolEvent {
+    /// kind of change
+    pub kind: PoolEventKind,
+    /// operation or endorsement concerned
+    pub object: PoolObjectId,
+    /// creator of the object
+    pub creator: Address,
+    /// latest slot at the time of the event
+    pub slot_context: Option<Slot>,
+    /// details on why the object left the pool
+    pub reason: Option<String>,
+}
+
+impl HasId for PoolEvent {
+    type Id = (PoolObjectId, PoolEventKind);
+
+    fn get_id(&self) -> Self::Id {
+        (self.object, self.kind)
+    }
+}
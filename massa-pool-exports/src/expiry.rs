@@ -0,0 +1,21 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Operations whose validity period ended before they were executed.
+//!
+//! The pool watches the operations it admitted until the final periods of their thread pass
+//! their expire period, even if they left the pool meanwhile. The ones that were not executed
+//! are kept for a while, so that the API can tell them apart from the operations the node
+//! never saw.
+
+use massa_models::operation::SecureShareOperation;
+use massa_time::MassaTime;
+use serde::Serialize;
+
+/// Operation admitted in the pool that expired without being executed
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpiredOperation {
+    /// the operation itself, so that its creator can send it again with a new expire period
+    pub operation: SecureShareOperation,
+    /// time at which the pool noticed the expiry
+    pub expired_at: MassaTime,
+}
@@ -8,10 +8,22 @@
 mod channels;
 mod config;
 mod controller_traits;
+mod events;
+mod expiry;
+mod selection_audit;
+mod snapshot;
+mod types;
 
 pub use channels::{PoolBroadcasts, PoolChannels};
 pub use config::PoolConfig;
 pub use controller_traits::{PoolController, PoolManager};
+pub use events::{PoolEvent, PoolEventKind, PoolObjectId};
+pub use expiry::ExpiredOperation;
+pub use selection_audit::{
+    AuditedOperation, OperationPriority, RefreshFilterCounts, SelectionAudit, SelectionOutcome,
+};
+pub use snapshot::{PoolSnapshot, PooledEndorsementInfo, PooledOperationInfo};
+pub use types::PooledOperationSummary;
 
 #[cfg(feature = "test-exports")]
 pub use controller_traits::{MockPoolController, MockPoolControllerWrapper};
@@ -0,0 +1,119 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Audit of the operations selected by the pool for a block.
+//!
+//! When enabled (`PoolConfig::max_selection_audit_slots`), the pool records for each slot it
+//! selected operations for, the candidates it went through in order, why each of them was kept
+//! or left out, and where the selection stopped. Two nodes building different blocks from the
+//! same pool can compare their audits to find the first point where their selections diverge.
+
+use massa_models::{amount::Amount, operation::OperationId, slot::Slot};
+use serde::{Deserialize, Serialize};
+
+/// Components of the priority of an operation, computed at the last pool refresh.
+///
+/// The operations are sorted by decreasing score, the ties being broken by increasing operation id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationPriority {
+    /// fee of the operation, in raw units plus one so that zero-fee operations are still sorted
+    pub fee_factor: f32,
+    /// share of the block size and gas left by the operation: 1 for an empty operation, 0 for one filling the block
+    pub resource_factor: f32,
+    /// likelihood that the operation is still unincluded at our next production slot
+    pub inclusion_factor: f32,
+    /// product of the factors
+    pub score: f32,
+}
+
+/// Decision taken by the pool for an operation of the thread of the slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SelectionOutcome {
+    /// the operation was included in the block
+    Selected,
+    /// the slot is outside of the validity period range of the operation
+    OutsideValidityRange,
+    /// the creator of the operation is in the production blocklist
+    ExcludedCreator,
+    /// the operation pays less than the fee floor of the block producer
+    BelowFeeFloor,
+    /// the operation does not fit in the remaining block space
+    TooLarge,
+    /// the operation requires more than the remaining block gas
+    TooMuchGas,
+}
+
+/// Operation considered for a block, in the order of the pool
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditedOperation {
+    /// operation id
+    pub id: OperationId,
+    /// fee paid by the operation
+    pub fee: Amount,
+    /// serialized size of the operation
+    pub size: usize,
+    /// max gas usage of the operation
+    pub max_gas_usage: u64,
+    /// priority of the operation, `None` if it was added to the pool after the last refresh
+    pub priority: Option<OperationPriority>,
+    /// decision taken for the operation
+    pub outcome: SelectionOutcome,
+}
+
+/// Number of operations removed from the pool by each filter of the last refresh
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefreshFilterCounts {
+    /// operations using more gas or space than a whole block
+    pub too_many_resources: usize,
+    /// operations that can't be included in any of our production slots
+    pub no_production_slot: usize,
+    /// operations paying less than the minimal fees
+    pub insufficient_fee: usize,
+    /// operations already executed in a final or candidate slot
+    pub executed: usize,
+    /// operations spending more than the balance of their sender, or whose sender doesn't exist
+    pub insufficient_balance: usize,
+}
+
+/// Record of the selection of the operations of a block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectionAudit {
+    /// slot of the block
+    pub slot: Slot,
+    /// operations of the thread of the slot, in the order they were considered
+    pub candidates: Vec<AuditedOperation>,
+    /// number of operations of the other threads that were skipped
+    pub other_thread_count: usize,
+    /// number of candidates considered before the block reached its max operation count,
+    /// `None` if the whole pool was gone through
+    pub cut_point: Option<usize>,
+    /// operations removed from the pool by the last refresh
+    pub refresh_filters: RefreshFilterCounts,
+}
+
+impl SelectionAudit {
+    /// Ids of the selected operations, in block order
+    pub fn selected(&self) -> Vec<OperationId> {
+        self.candidates
+            .iter()
+            .filter(|candidate| candidate.outcome == SelectionOutcome::Selected)
+            .map(|candidate| candidate.id)
+            .collect()
+    }
+
+    /// Index of the first candidate differing from the one of `other`, `None` if both list the
+    /// same candidates with the same decisions
+    pub fn first_divergence(&self, other: &SelectionAudit) -> Option<usize> {
+        let divergence = self
+            .candidates
+            .iter()
+            .zip(&other.candidates)
+            .position(|(a, b)| a.id != b.id || a.outcome != b.outcome);
+        match divergence {
+            Some(index) => Some(index),
+            None if self.candidates.len() != other.candidates.len() => {
+                Some(self.candidates.len().min(other.candidates.len()))
+            }
+            None => None,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Snapshots of the pool contents, for external indexers.
+//!
+//! A snapshot only holds the ids and the metadata cached by the pool, taken in a single pass
+//! under the pool lock. It does not claim the items in the storage: once the pool moved on,
+//! the ids of a snapshot may refer to items that are no longer stored by the node.
+
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    block_id::BlockId,
+    endorsement::EndorsementId,
+    operation::{OperationId, SecureShareOperation},
+    slot::Slot,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Metadata of an operation, as cached by the pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PooledOperationInfo {
+    /// operation id
+    pub id: OperationId,
+    /// serialized size of the operation
+    pub size: usize,
+    /// The maximum amount of gas that can be used by an operation.
+    pub max_gas_usage: u64,
+    /// address of the creator of the operation
+    pub creator_address: Address,
+    /// thread of the creator of the operation
+    pub thread: u8,
+    /// fee paid by the operation
+    pub fee: Amount,
+    /// max amount that the op might spend from the sender's balance
+    pub max_spending: Amount,
+    /// periods in which the operation can be included
+    pub validity_period_range: RangeInclusive<u64>,
+}
+
+impl PooledOperationInfo {
+    /// Compute the metadata of an operation
+    pub fn from_op(
+        op: &SecureShareOperation,
+        operation_validity_periods: u64,
+        roll_price: Amount,
+        thread_count: u8,
+        base_operation_gas_cost: u64,
+        sp_compilation_cost: u64,
+    ) -> Self {
+        PooledOperationInfo {
+            id: op.id,
+            size: op.serialized_size(),
+            max_gas_usage: op.get_gas_usage(base_operation_gas_cost, sp_compilation_cost),
+            creator_address: op.content_creator_address,
+            fee: op.content.fee,
+            thread: op.content_creator_address.get_thread(thread_count),
+            validity_period_range: op.get_validity_range(operation_validity_periods),
+            max_spending: op.get_max_spending(roll_price),
+        }
+    }
+}
+
+/// Metadata of an endorsement, as cached by the pool
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PooledEndorsementInfo {
+    /// endorsement id
+    pub id: EndorsementId,
+    /// slot of the block in which the endorsement can be included
+    pub slot: Slot,
+    /// index of the endorsement in the block
+    pub index: u32,
+    /// block endorsed by the endorsement
+    pub endorsed_block: BlockId,
+}
+
+/// Consistent view of the pool items at a given time
+///
+/// Cloning a snapshot is cheap: the items are shared between the clones.
+#[derive(Debug, Clone)]
+pub struct PoolSnapshot<T> {
+    /// sequence number of the snapshot, increasing with each snapshot taken from a pool
+    pub sequence: u64,
+    /// latest slot at the time the snapshot was taken, if the network started
+    pub slot: Option<Slot>,
+    /// items of the pool, in the pool order
+    pub items: Arc<[T]>,
+}
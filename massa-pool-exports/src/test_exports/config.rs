@@ -5,7 +5,9 @@ use massa_models::{
     config::{
         BASE_OPERATION_GAS_COST, DENUNCIATION_EXPIRE_PERIODS, ENDORSEMENT_COUNT, MAX_BLOCK_SIZE,
         MAX_DENUNCIATIONS_PER_BLOCK_HEADER, MAX_GAS_PER_BLOCK, MAX_OPERATIONS_PER_BLOCK,
-        OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, ROLL_PRICE, T0, THREAD_COUNT,
+        MAX_OPERATION_DATASTORE_ENTRY_COUNT, MAX_OPERATION_DATASTORE_KEY_LENGTH,
+        MAX_OPERATION_DATASTORE_VALUE_LENGTH, OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE,
+        POOL_MAX_PARKED_ENDORSEMENTS, ROLL_PRICE, T0, THREAD_COUNT,
     },
 };
 use massa_time::MassaTime;
@@ -24,8 +26,14 @@ impl Default for PoolConfig {
             base_operation_gas_cost: BASE_OPERATION_GAS_COST,
             max_operation_pool_size: 32000,
             max_operation_pool_excess_items: 10000,
+            max_operation_pool_bytes: 32_000_000,
+            max_operation_pool_excess_bytes: 10_000_000,
             max_endorsements_pool_size_per_thread: 1000,
+            max_parked_endorsements: POOL_MAX_PARKED_ENDORSEMENTS,
             max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
+            max_op_datastore_entry_count: MAX_OPERATION_DATASTORE_ENTRY_COUNT,
+            max_op_datastore_key_length: MAX_OPERATION_DATASTORE_KEY_LENGTH,
+            max_op_datastore_value_length: MAX_OPERATION_DATASTORE_VALUE_LENGTH,
             max_block_endorsement_count: ENDORSEMENT_COUNT,
             operations_channel_size: 1024,
             endorsements_channel_size: 1024,
@@ -33,6 +41,13 @@ impl Default for PoolConfig {
             broadcast_enabled: false,
             broadcast_endorsements_channel_capacity: 2000,
             broadcast_operations_channel_capacity: 5000,
+            broadcast_dedup_capacity: 10000,
+            broadcast_pool_events_channel_capacity: 10000,
+            max_expired_operations: 1000,
+            expired_operations_retention: MassaTime::from_millis(600_000),
+            max_selection_audit_slots: 0,
+            operation_pool_ref_audit_interval: MassaTime::from_millis(0),
+            operation_pool_ref_audit_self_heal: false,
             genesis_timestamp: MassaTime::now(),
             t0: T0,
             periods_per_cycle: PERIODS_PER_CYCLE,
@@ -0,0 +1,27 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_models::{address::Address, amount::Amount, operation::OperationId};
+use serde::{Deserialize, Serialize};
+
+/// Metadata kept by the pool about one of its operations
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PooledOperationSummary {
+    /// id of the operation
+    pub id: OperationId,
+    /// address of the creator of the operation
+    pub creator_address: Address,
+    /// thread of the creator of the operation
+    pub thread: u8,
+    /// fee of the operation
+    pub fee: Amount,
+    /// serialized size of the operation
+    pub size: usize,
+    /// maximum amount of gas that the operation can use
+    pub max_gas_usage: u64,
+    /// maximum amount that the operation can spend from the balance of its creator
+    pub max_spending: Amount,
+    /// first period at which the operation can be included in a block
+    pub validity_start_period: u64,
+    /// last period at which the operation can be included in a block
+    pub validity_end_period: u64,
+}
@@ -3,12 +3,18 @@
 //! Pool controller implementation
 
 use massa_models::{
-    block_id::BlockId, denunciation::Denunciation, denunciation::DenunciationPrecursor,
-    endorsement::EndorsementId, operation::OperationId, slot::Slot,
+    address::Address, amount::Amount, block_id::BlockId, denunciation::Denunciation,
+    denunciation::DenunciationPrecursor, endorsement::EndorsementId, operation::OperationId,
+    prehash::PreHashSet, slot::Slot, timeslots::get_latest_block_slot_at_timestamp,
+};
+use massa_pool_exports::{
+    ExpiredOperation, PoolConfig, PoolController, PoolManager, PoolSnapshot, PooledEndorsementInfo,
+    PooledOperationInfo, PooledOperationSummary, SelectionAudit,
 };
-use massa_pool_exports::{PoolConfig, PoolController, PoolManager};
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use parking_lot::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::mpsc::TrySendError;
 use std::sync::{mpsc::SyncSender, Arc};
 use tracing::{info, warn};
@@ -35,7 +41,7 @@ pub enum Command {
 #[derive(Clone)]
 pub struct PoolControllerImpl {
     /// Config
-    pub(crate) _config: PoolConfig,
+    pub(crate) config: PoolConfig,
     /// Shared reference to the operation pool
     pub(crate) operation_pool: Arc<RwLock<OperationPool>>,
     /// Shared reference to the endorsement pool
@@ -50,6 +56,27 @@ pub struct PoolControllerImpl {
     pub(crate) denunciations_input_sender: SyncSender<Command>,
     /// Last final periods from Consensus
     pub last_cs_final_periods: Vec<u64>,
+    /// Sequence number of the last snapshot taken, shared by the operation and endorsement snapshots
+    pub(crate) snapshot_sequence: Arc<AtomicU64>,
+}
+
+impl PoolControllerImpl {
+    /// Build a snapshot of items read while holding the pool lock
+    fn take_snapshot<T>(&self, items: Arc<[T]>) -> PoolSnapshot<T> {
+        let slot = get_latest_block_slot_at_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            MassaTime::now(),
+        )
+        .ok()
+        .flatten();
+        PoolSnapshot {
+            sequence: self.snapshot_sequence.fetch_add(1, Ordering::Relaxed) + 1,
+            slot,
+            items,
+        }
+    }
 }
 
 impl PoolController for PoolControllerImpl {
@@ -172,8 +199,20 @@ impl PoolController for PoolControllerImpl {
     }
 
     /// get operations for block creation
-    fn get_block_operations(&self, slot: &Slot) -> (Vec<OperationId>, Storage) {
-        self.operation_pool.read().get_block_operations(slot)
+    fn get_block_operations(
+        &self,
+        slot: &Slot,
+        excluded_creators: &PreHashSet<Address>,
+        min_fee: Amount,
+    ) -> (Vec<OperationId>, Storage, usize) {
+        self.operation_pool
+            .read()
+            .get_block_operations(slot, excluded_creators, min_fee)
+    }
+
+    /// get the audit of the operation selection for a recent slot
+    fn get_selection_audit(&self, slot: &Slot) -> Option<SelectionAudit> {
+        self.operation_pool.read().get_selection_audit(slot)
     }
 
     /// get endorsements for a block
@@ -188,10 +227,17 @@ impl PoolController for PoolControllerImpl {
     }
 
     /// get denunciationsq for a block
-    fn get_block_denunciations(&self, target_slot: &Slot) -> Vec<Denunciation> {
+    fn get_block_denunciations(&self, target_slot: &Slot, min_slot: &Slot) -> Vec<Denunciation> {
+        self.denunciation_pool
+            .read()
+            .get_block_denunciations(target_slot, min_slot)
+    }
+
+    /// get prioritized denunciations for a block
+    fn get_denunciations_for_block(&self, target_slot: &Slot, limit: usize) -> Vec<Denunciation> {
         self.denunciation_pool
             .read()
-            .get_block_denunciations(target_slot)
+            .get_denunciations_for_block(target_slot, limit)
     }
 
     /// Get the number of endorsements in the pool
@@ -216,11 +262,30 @@ impl PoolController for PoolControllerImpl {
         operations.iter().map(|id| lck.contains(id)).collect()
     }
 
+    /// Get the operations of a list that recently expired without being executed
+    fn get_expired_operations(&self, operations: &[OperationId]) -> Vec<Option<ExpiredOperation>> {
+        self.operation_pool
+            .read()
+            .get_expired_operations(operations)
+    }
+
     /// Get the number of denunciations in the pool
     fn get_denunciation_count(&self) -> usize {
         self.denunciation_pool.read().len()
     }
 
+    /// Take a snapshot of the operations in the pool
+    fn snapshot_operations(&self) -> PoolSnapshot<PooledOperationInfo> {
+        let lck = self.operation_pool.read();
+        self.take_snapshot(lck.operation_infos())
+    }
+
+    /// Take a snapshot of the endorsements in the pool
+    fn snapshot_endorsements(&self) -> PoolSnapshot<PooledEndorsementInfo> {
+        let lck = self.endorsement_pool.read();
+        self.take_snapshot(lck.endorsement_infos())
+    }
+
     /// Returns a boxed clone of self.
     /// Allows cloning `Box<dyn PoolController>`,
     fn clone_box(&self) -> Box<dyn PoolController> {
@@ -231,6 +296,11 @@ impl PoolController for PoolControllerImpl {
     fn get_final_cs_periods(&self) -> Vec<u64> {
         self.last_cs_final_periods.clone()
     }
+
+    /// Get the metadata of at most `max_items` operations, the best ranked first
+    fn get_operations_sample(&self, max_items: usize) -> Vec<PooledOperationSummary> {
+        self.operation_pool.read().get_operations_sample(max_items)
+    }
 }
 
 /// Implementation of the pool manager.
@@ -193,37 +193,75 @@ impl DenunciationPool {
         );
     }
 
-    /// get denunciations for block creation
-    pub fn get_block_denunciations(&self, target_slot: &Slot) -> Vec<Denunciation> {
-        let mut res = Vec::with_capacity(self.config.max_denunciations_per_block_header as usize);
-        for (de_idx, de_status) in &self.denunciations_cache {
-            if let DenunciationStatus::DenunciationEmitted(de) = de_status {
-                // Checks
-                // 1. the denunciation has not been executed already
-                // 2. Denounced item slot is equal or before target slot of block header
-                // 3. Denounced item slot is not too old
+    /// get denunciations for block creation, filling the block header
+    /// with the denunciations of slots from `min_slot`
+    pub fn get_block_denunciations(
+        &self,
+        target_slot: &Slot,
+        min_slot: &Slot,
+    ) -> Vec<Denunciation> {
+        self.select_denunciations(
+            target_slot,
+            min_slot,
+            self.config.max_denunciations_per_block_header as usize,
+        )
+    }
+
+    /// get at most `limit` denunciations for the block at `target_slot`, most valuable first
+    ///
+    /// The denunciations are ordered by `inclusion_priority`.
+    pub fn get_denunciations_for_block(
+        &self,
+        target_slot: &Slot,
+        limit: usize,
+    ) -> Vec<Denunciation> {
+        self.select_denunciations(target_slot, &Slot::min(), limit)
+    }
+
+    /// get at most `limit` denunciations of slots from `min_slot` for the block at `target_slot`,
+    /// ordered by `inclusion_priority`
+    fn select_denunciations(
+        &self,
+        target_slot: &Slot,
+        min_slot: &Slot,
+        limit: usize,
+    ) -> Vec<Denunciation> {
+        // Checks
+        // 1. Denounced item slot is equal or before target slot of block header, and not before min slot
+        // 2. Denounced item slot is not too old
+        let mut candidates: Vec<(&DenunciationIndex, &Denunciation)> = self
+            .denunciations_cache
+            .iter()
+            .filter_map(|(de_idx, de_status)| match de_status {
+                DenunciationStatus::Accumulating(_) => None,
+                DenunciationStatus::DenunciationEmitted(de) => Some((de_idx, de)),
+            })
+            .filter(|(_, de)| {
                 let de_slot = de.get_slot();
-                if !self
-                    .channels
-                    .execution_controller
-                    .get_denunciation_execution_status(de_idx)
-                    .0
-                    && de_slot <= target_slot
+                de_slot <= target_slot
+                    && de_slot >= min_slot
                     && !Denunciation::is_expired(
                         &de_slot.period,
                         &target_slot.period,
                         &self.config.denunciation_expire_periods,
                     )
-                {
-                    res.push(de.clone());
-                }
-            }
-
-            if res.len() >= self.config.max_denunciations_per_block_header as usize {
-                break;
-            }
-        }
-        res
+            })
+            .collect();
+        candidates.sort_unstable_by_key(|(de_idx, _)| inclusion_priority(de_idx));
+
+        // 3. the denunciation has not been executed already, only checked for the best candidates
+        candidates
+            .into_iter()
+            .filter(|(de_idx, _)| {
+                !self
+                    .channels
+                    .execution_controller
+                    .get_denunciation_execution_status(de_idx)
+                    .0
+            })
+            .take(limit)
+            .map(|(_, de)| de.clone())
+            .collect()
     }
 
     /// Notify of final periods
@@ -257,6 +295,18 @@ impl DenunciationPool {
     }
 }
 
+/// Inclusion priority of a denunciation in a block header, the lowest key coming first:
+/// - block header denunciations come before endorsement denunciations, as producing several blocks
+///   for a slot splits the network on competing blocks, when multiple endorsements only weigh on fitness
+/// - then the oldest denounced slots come first, as they are the first to expire
+/// - then the lowest endorsement indexes, to keep the order deterministic
+fn inclusion_priority(de_idx: &DenunciationIndex) -> (u8, Slot, u32) {
+    match de_idx {
+        DenunciationIndex::BlockHeader { slot } => (0, *slot, 0),
+        DenunciationIndex::Endorsement { slot, index } => (1, *slot, *index),
+    }
+}
+
 /// Internal function to cleanup the denunciation cache
 fn cleanup_cache(
     cache: &mut BTreeMap<DenunciationIndex, DenunciationStatus>,
@@ -292,12 +342,19 @@ mod tests {
     use std::collections::Bound::Included;
     use std::ops::Bound::Unbounded;
 
+    use massa_channel::broadcast::DedupBroadcaster;
+    use massa_execution_exports::MockExecutionController;
     use massa_hash::Hash;
     use massa_models::block_header::{BlockHeader, BlockHeaderSerializer};
     use massa_models::block_id::BlockId;
     use massa_models::config::{CHAINID, ENDORSEMENT_COUNT};
     use massa_models::endorsement::{Endorsement, EndorsementSerializer};
     use massa_models::secure_share::SecureShareContent;
+    use massa_models::test_exports::{
+        gen_block_headers_for_denunciation, gen_endorsements_for_denunciation,
+    };
+    use massa_pool_exports::PoolBroadcasts;
+    use massa_pos_exports::MockSelectorController;
     use massa_signature::KeyPair;
 
     #[test]
@@ -411,4 +468,80 @@ mod tests {
                 .collect::<BTreeMap<DenunciationIndex, DenunciationStatus>>()
         );
     }
+
+    #[test]
+    fn test_denunciations_for_block_ordering() {
+        let header_denunciation = |slot| {
+            let (_, _, header_1, header_2, _) =
+                gen_block_headers_for_denunciation(Some(slot), None);
+            Denunciation::try_from((&header_1, &header_2)).unwrap()
+        };
+        let endorsement_denunciation = |slot| {
+            let (_, _, endorsement_1, endorsement_2, _) =
+                gen_endorsements_for_denunciation(Some(slot), None);
+            Denunciation::try_from((&endorsement_1, &endorsement_2)).unwrap()
+        };
+        let endorsement_de_1 = endorsement_denunciation(Slot::new(1, 0));
+        let endorsement_de_2 = endorsement_denunciation(Slot::new(2, 0));
+        let header_de_1 = header_denunciation(Slot::new(2, 5));
+        let header_de_2 = header_denunciation(Slot::new(3, 0));
+        let executed_idx = DenunciationIndex::from(&endorsement_de_1);
+
+        let mut execution_controller = Box::new(MockExecutionController::new());
+        execution_controller
+            .expect_get_denunciation_execution_status()
+            .returning(move |de_idx| (*de_idx == executed_idx, false));
+        let channels = PoolChannels {
+            execution_controller,
+            selector: Box::new(MockSelectorController::new()),
+            broadcasts: PoolBroadcasts {
+                endorsement_sender: DedupBroadcaster::new("test_endorsements".to_string(), 10, 10),
+                operation_sender: DedupBroadcaster::new("test_operations".to_string(), 10, 10),
+                expired_operation_sender: tokio::sync::broadcast::channel(10).0,
+                pool_event_sender: DedupBroadcaster::new("test_pool_events".to_string(), 10, 0),
+            },
+        };
+        let mut pool = DenunciationPool::init(PoolConfig::default(), channels);
+        for de in [
+            &endorsement_de_1,
+            &endorsement_de_2,
+            &header_de_1,
+            &header_de_2,
+        ] {
+            pool.denunciations_cache.insert(
+                DenunciationIndex::from(de),
+                DenunciationStatus::DenunciationEmitted(de.clone()),
+            );
+        }
+
+        // block header denunciations first, oldest first, leaving out the executed one
+        let target_slot = Slot::new(4, 0);
+        assert_eq!(
+            pool.get_denunciations_for_block(&target_slot, 10),
+            vec![
+                header_de_1.clone(),
+                header_de_2.clone(),
+                endorsement_de_2.clone()
+            ]
+        );
+        // the denunciations before the min slot are left out before keeping the best ones
+        assert_eq!(
+            pool.get_block_denunciations(&target_slot, &Slot::new(3, 0)),
+            vec![header_de_2.clone()]
+        );
+        assert_eq!(
+            pool.select_denunciations(&target_slot, &Slot::new(2, 1), 1),
+            vec![header_de_2.clone()]
+        );
+        assert_eq!(
+            pool.get_denunciations_for_block(&target_slot, 2),
+            vec![header_de_1.clone(), header_de_2]
+        );
+
+        // the denunciations of later slots can't be included yet
+        assert_eq!(
+            pool.get_denunciations_for_block(&Slot::new(2, 5), 10),
+            vec![header_de_1, endorsement_de_2]
+        );
+    }
 }
@@ -1,21 +1,63 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::events::{broadcast_pool_changes, PoolChange};
+use massa_metrics::MassaMetrics;
 use massa_models::{
     block_id::BlockId,
-    endorsement::EndorsementId,
+    endorsement::{EndorsementId, SecureShareEndorsement},
     prehash::{CapacityAllocator, PreHashSet},
     slot::Slot,
 };
-use massa_pool_exports::{PoolChannels, PoolConfig};
+use massa_pool_exports::{
+    PoolChannels, PoolConfig, PoolEventKind, PoolObjectId, PooledEndorsementInfo,
+};
+use massa_pos_exports::{PosError, Selection};
 use massa_storage::Storage;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::{
     collections::{hash_map::Entry, BTreeMap, HashMap},
+    fmt,
     sync::Arc,
 };
 use tracing::{trace, warn};
 
+/// Outcome of the PoS draw verification of an endorsement
+enum DrawVerification {
+    /// the creator of the endorsement was drawn, along with the selection of the endorsement slot
+    Drawn(Selection),
+    /// the draws of the endorsement slot are beyond the selector horizon
+    Pending,
+}
+
+/// Reason for which an endorsement is not admitted in the pool
+#[derive(Debug)]
+enum EndorsementRejection {
+    /// the endorsement slot is already final
+    Expired,
+    /// the creator of the endorsement was not drawn for its slot and index
+    UnexpectedCreator,
+    /// the draws of the endorsement slot could not be retrieved
+    DrawError(PosError),
+    /// the draws of the endorsement slot are not available yet, and too many endorsements are already waiting for them
+    TooManyParked,
+}
+
+impl fmt::Display for EndorsementRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndorsementRejection::Expired => write!(f, "slot is already final"),
+            EndorsementRejection::UnexpectedCreator => {
+                write!(f, "creator was not drawn for this slot and index")
+            }
+            EndorsementRejection::DrawError(err) => write!(f, "failed to get PoS draw: {}", err),
+            EndorsementRejection::TooManyParked => {
+                write!(f, "too many endorsements are waiting for their PoS draw")
+            }
+        }
+    }
+}
+
 pub struct EndorsementPool {
     /// configuration
     config: PoolConfig,
@@ -38,6 +80,13 @@ pub struct EndorsementPool {
 
     /// staking wallet, to know which addresses we are using to stake
     wallet: Arc<RwLock<Wallet>>,
+
+    /// endorsements whose slot draws were beyond the selector horizon when received.
+    /// They are verified again as final periods advance, and are never returned for block inclusion before.
+    parked_storage: Storage,
+
+    /// metrics
+    massa_metrics: MassaMetrics,
 }
 
 impl EndorsementPool {
@@ -46,6 +95,7 @@ impl EndorsementPool {
         storage: &Storage,
         channels: PoolChannels,
         wallet: Arc<RwLock<Wallet>>,
+        massa_metrics: MassaMetrics,
     ) -> Self {
         EndorsementPool {
             last_cs_final_periods: vec![0u64; config.thread_count as usize],
@@ -55,6 +105,8 @@ impl EndorsementPool {
             storage: storage.clone_without_refs(),
             channels,
             wallet,
+            parked_storage: storage.clone_without_refs(),
+            massa_metrics,
         }
     }
 
@@ -68,6 +120,22 @@ impl EndorsementPool {
         self.storage.get_endorsement_refs().contains(id)
     }
 
+    /// Get the metadata of the stored endorsements, sorted by thread then inclusion slot
+    pub fn endorsement_infos(&self) -> Arc<[PooledEndorsementInfo]> {
+        self.endorsements_sorted
+            .iter()
+            .flatten()
+            .map(
+                |(&(slot, index, endorsed_block), &id)| PooledEndorsementInfo {
+                    id,
+                    slot,
+                    index,
+                    endorsed_block,
+                },
+            )
+            .collect()
+    }
+
     /// notify of new final CS periods
     pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         // update internal final CS period counter
@@ -75,6 +143,7 @@ impl EndorsementPool {
 
         // remove all endorsements whose periods <= last_cs_final_periods[endorsement.thread]
         let mut removed: PreHashSet<EndorsementId> = Default::default();
+        let mut removed_order = Vec::new();
         for thread in 0..self.config.thread_count {
             while let Some((&(inclusion_slot, index, block_id), &endo_id)) =
                 self.endorsements_sorted[thread as usize].first_key_value()
@@ -85,12 +154,51 @@ impl EndorsementPool {
                         .remove(&(inclusion_slot, index, block_id))
                         .expect("endorsement should be in endorsements_indexed at this point");
                     removed.insert(endo_id);
+                    removed_order.push(endo_id);
                 } else {
                     break;
                 }
             }
         }
+        let changes = self.endorsement_changes(&removed_order, PoolEventKind::Expired);
+        broadcast_pool_changes(&self.config, &self.channels, changes);
         self.storage.drop_endorsement_refs(&removed);
+
+        // the selector horizon may have advanced: verify the parked endorsements again
+        let parked = self.parked_storage.get_endorsement_refs().clone();
+        if !parked.is_empty() {
+            let parked_storage =
+                self.parked_storage
+                    .split_off(&Default::default(), &Default::default(), &parked);
+            self.add_endorsements(parked_storage);
+        }
+    }
+
+    /// Check that the creator of an endorsement was drawn for its slot and index
+    fn verify_draw(
+        &self,
+        endo: &SecureShareEndorsement,
+    ) -> Result<DrawVerification, EndorsementRejection> {
+        // check endorsement expiry
+        if endo.content.slot.period <= self.last_cs_final_periods[endo.content.slot.thread as usize]
+        {
+            return Err(EndorsementRejection::Expired);
+        }
+
+        // check PoS draw
+        let selection = match self.channels.selector.get_selection(endo.content.slot) {
+            Ok(selection) => selection,
+            Err(PosError::CycleUnavailable(_) | PosError::CycleUnfinished(_)) => {
+                return Ok(DrawVerification::Pending)
+            }
+            Err(err) => return Err(EndorsementRejection::DrawError(err)),
+        };
+        if selection.endorsements.get(endo.content.index as usize)
+            != Some(&endo.content_creator_address)
+        {
+            return Err(EndorsementRejection::UnexpectedCreator);
+        }
+        Ok(DrawVerification::Drawn(selection))
     }
 
     /// Add a list of endorsements to the pool
@@ -102,7 +210,10 @@ impl EndorsementPool {
             .collect::<Vec<_>>();
 
         let mut added = PreHashSet::with_capacity(items.len());
+        let mut added_order = Vec::with_capacity(items.len());
         let mut removed = PreHashSet::with_capacity(items.len());
+        let mut removed_order = Vec::new();
+        let mut parked = PreHashSet::default();
 
         // add items to pool
         {
@@ -112,36 +223,34 @@ impl EndorsementPool {
                     .get(&endo_id)
                     .expect("attempting to add endorsement to pool, but it is absent from storage");
 
-                // check endorsement expiry
-                if endo.content.slot.period
-                    <= self.last_cs_final_periods[endo.content.slot.thread as usize]
-                {
-                    continue;
-                }
-
-                // check PoS draw
-                let pos_draws = match self.channels.selector.get_selection(endo.content.slot) {
-                    Ok(draw) => draw,
-                    Err(err) => {
+                let verification = match self.verify_draw(endo) {
+                    Ok(DrawVerification::Drawn(selection)) => Ok(selection),
+                    Ok(DrawVerification::Pending) => {
+                        // keep it aside until the draws of its slot are available
+                        if self.parked_storage.get_endorsement_refs().len() + parked.len()
+                            < self.config.max_parked_endorsements
+                        {
+                            parked.insert(endo.id);
+                            continue;
+                        }
+                        Err(EndorsementRejection::TooManyParked)
+                    }
+                    Err(rejection) => Err(rejection),
+                };
+                let pos_draws = match verification {
+                    Ok(selection) => selection,
+                    Err(EndorsementRejection::Expired) => continue,
+                    Err(rejection) => {
+                        if let EndorsementRejection::UnexpectedCreator = rejection {
+                            self.massa_metrics.inc_endorsements_pool_rejected_creator();
+                        }
                         warn!(
-                            "error, failed to get PoS draw for endorsement with id {} at slot {}: {}",
-                            endo.id.clone(), endo.content.slot, err
+                            "error, endorsement with id {} at slot {} rejected: {}",
+                            endo.id, endo.content.slot, rejection
                         );
                         continue;
                     }
                 };
-                if !pos_draws
-                    .endorsements
-                    .get(endo.content.index as usize)
-                    .map_or(false, |a| a == &endo.content_creator_address)
-                {
-                    warn!(
-                        "error, endorsement with id {} at slot {} is not selected for PoS draw",
-                        endo.id.clone(),
-                        endo.content.slot
-                    );
-                    continue;
-                }
 
                 // Broadcast endorsement to active channel subscribers.
                 if self.config.broadcast_enabled {
@@ -180,6 +289,7 @@ impl EndorsementPool {
                         panic!("endorsement is expected to be absent from endorsements_sorted at this point");
                     }
                     added.insert(endo.id);
+                    added_order.push(endo.id);
                 }
             }
         }
@@ -190,15 +300,24 @@ impl EndorsementPool {
                 > self.config.max_endorsements_pool_size_per_thread
             {
                 // won't panic because len was checked above
-                let (_key, endo_id) = self.endorsements_sorted[thread as usize]
+                let (key, endo_id) = self.endorsements_sorted[thread as usize]
                     .pop_last()
                     .unwrap();
+                self.endorsements_indexed.remove(&key);
                 if !added.remove(&endo_id) {
                     removed.insert(endo_id);
+                    removed_order.push(endo_id);
                 }
             }
         }
 
+        // keep the parked endorsements until they can be verified
+        self.parked_storage.extend(endorsement_storage.split_off(
+            &Default::default(),
+            &Default::default(),
+            &parked,
+        ));
+
         // take ownership on added endorsements
         self.storage.extend(endorsement_storage.split_off(
             &Default::default(),
@@ -206,11 +325,41 @@ impl EndorsementPool {
             &added,
         ));
 
+        // the endorsements pruned right away were never admitted
+        added_order.retain(|endo_id| added.contains(endo_id));
+        let mut changes = self.endorsement_changes(&added_order, PoolEventKind::Admitted);
+        changes.extend(self.endorsement_changes(&removed_order, PoolEventKind::Evicted));
+        broadcast_pool_changes(&self.config, &self.channels, changes);
+
         // drop removed endorsements from storage
         self.storage.drop_endorsement_refs(&removed);
     }
 
+    /// Changes of the pool content concerning endorsements held by the pool storage
+    fn endorsement_changes(
+        &self,
+        endo_ids: &[EndorsementId],
+        kind: PoolEventKind,
+    ) -> Vec<PoolChange> {
+        if !self.config.broadcast_enabled {
+            return Vec::new();
+        }
+        let endos = self.storage.read_endorsements();
+        endo_ids
+            .iter()
+            .filter_map(|endo_id| endos.get(endo_id))
+            .map(|endo| PoolChange {
+                kind,
+                object: PoolObjectId::Endorsement(endo.id),
+                creator: endo.content_creator_address,
+                reason: None,
+            })
+            .collect()
+    }
+
     /// get endorsements for block creation
+    ///
+    /// Only endorsements whose creator was verified against the PoS draws are returned.
     pub fn get_block_endorsements(
         &self,
         slot: &Slot, // slot of the block that will contain the endorsement
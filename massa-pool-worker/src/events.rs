@@ -0,0 +1,54 @@
+//! Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Broadcast of the admissions and removals of pool objects
+
+use massa_models::{address::Address, timeslots::get_latest_block_slot_at_timestamp};
+use massa_pool_exports::{PoolChannels, PoolConfig, PoolEvent, PoolEventKind, PoolObjectId};
+use massa_time::MassaTime;
+use tracing::trace;
+
+/// Change of the pool content, to be broadcast as a `PoolEvent`
+pub(crate) struct PoolChange {
+    pub kind: PoolEventKind,
+    pub object: PoolObjectId,
+    pub creator: Address,
+    pub reason: Option<&'static str>,
+}
+
+/// Broadcast changes of the pool content to the subscribers, in order.
+///
+/// Sending never waits for the subscribers: the ones lagging behind miss the oldest events.
+pub(crate) fn broadcast_pool_changes(
+    config: &PoolConfig,
+    channels: &PoolChannels,
+    changes: Vec<PoolChange>,
+) {
+    if !config.broadcast_enabled || changes.is_empty() {
+        return;
+    }
+    let slot_context = get_latest_block_slot_at_timestamp(
+        config.thread_count,
+        config.t0,
+        config.genesis_timestamp,
+        MassaTime::now(),
+    )
+    .ok()
+    .flatten();
+    for change in changes {
+        let event = PoolEvent {
+            kind: change.kind,
+            object: change.object,
+            creator: change.creator,
+            slot_context,
+            reason: change.reason.map(str::to_string),
+        };
+        if let Err(err) = channels.broadcasts.pool_event_sender.send(event) {
+            trace!(
+                "error, failed to broadcast pool event {:?} of {:?}: {}",
+                change.kind,
+                change.object,
+                err
+            );
+        }
+    }
+}
@@ -8,8 +8,8 @@
 mod controller_impl;
 mod denunciation_pool;
 mod endorsement_pool;
+mod events;
 mod operation_pool;
-mod types;
 mod worker;
 
 pub use worker::start_pool_controller;
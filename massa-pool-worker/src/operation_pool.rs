@@ -1,29 +1,43 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::events::{broadcast_pool_changes, PoolChange};
+use massa_metrics::MassaMetrics;
 use massa_models::{
     address::Address,
     amount::Amount,
+    datastore::DatastoreLimits,
     operation::OperationId,
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     slot::Slot,
     timeslots::get_latest_block_slot_at_timestamp,
 };
-use massa_pool_exports::{PoolChannels, PoolConfig};
-use massa_storage::Storage;
+use massa_pool_exports::{
+    AuditedOperation, ExpiredOperation, OperationPriority, PoolChannels, PoolConfig, PoolEventKind,
+    PoolObjectId, PooledOperationInfo, PooledOperationSummary, RefreshFilterCounts, SelectionAudit,
+    SelectionOutcome,
+};
+use massa_storage::{RefAuditReport, Storage};
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
-use parking_lot::RwLock;
-use std::{cmp::max, cmp::Ordering, cmp::PartialOrd, collections::BTreeSet, sync::Arc};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    cmp::max,
+    cmp::Ordering,
+    cmp::PartialOrd,
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    sync::Arc,
+};
 use tracing::{debug, trace, warn};
 
-use crate::types::OperationInfo;
-
 pub struct OperationPool {
     /// configuration
     config: PoolConfig,
 
     /// operations map
-    sorted_ops: Vec<OperationInfo>,
+    sorted_ops: Vec<PooledOperationInfo>,
+
+    /// total serialized size of the operations of `sorted_ops`
+    sorted_ops_bytes: usize,
 
     /// storage instance
     pub(crate) storage: Storage,
@@ -36,6 +50,31 @@ pub struct OperationPool {
 
     /// staking wallet, to know which addresses we are using to stake
     wallet: Arc<RwLock<Wallet>>,
+
+    /// admitted operations whose expire period is not final yet, per thread and expire period.
+    /// They are watched even once they left the pool, to detect the ones expiring without being executed
+    watched_ops: Vec<BTreeMap<u64, PreHashSet<OperationId>>>,
+
+    /// storage of the watched operations
+    watched_storage: Storage,
+
+    /// operations that recently expired without being executed
+    expired_ops: PreHashMap<OperationId, ExpiredOperation>,
+
+    /// ids of `expired_ops`, from the oldest to the most recent expiry
+    expired_order: VecDeque<OperationId>,
+
+    /// priorities of the operations computed at the last refresh
+    priorities: PreHashMap<OperationId, OperationPriority>,
+
+    /// number of operations removed by each filter of the last refresh
+    refresh_filters: RefreshFilterCounts,
+
+    /// audits of the last operation selections, from the oldest to the most recent slot
+    selection_audits: Mutex<VecDeque<SelectionAudit>>,
+
+    /// metrics
+    massa_metrics: MassaMetrics,
 }
 
 impl OperationPool {
@@ -44,6 +83,7 @@ impl OperationPool {
         storage: &Storage,
         channels: PoolChannels,
         wallet: Arc<RwLock<Wallet>>,
+        massa_metrics: MassaMetrics,
     ) -> Self {
         OperationPool {
             sorted_ops: Vec::with_capacity(
@@ -51,11 +91,20 @@ impl OperationPool {
                     .max_operation_pool_size
                     .saturating_add(config.max_operation_pool_excess_items),
             ),
+            sorted_ops_bytes: 0,
             last_cs_final_periods: vec![0u64; config.thread_count as usize],
+            watched_ops: vec![BTreeMap::new(); config.thread_count as usize],
+            watched_storage: storage.clone_without_refs(),
+            expired_ops: PreHashMap::default(),
+            expired_order: VecDeque::new(),
+            priorities: PreHashMap::default(),
+            refresh_filters: RefreshFilterCounts::default(),
+            selection_audits: Mutex::new(VecDeque::with_capacity(config.max_selection_audit_slots)),
             config,
             storage: storage.clone_without_refs(),
             channels,
             wallet,
+            massa_metrics,
         }
     }
 
@@ -152,44 +201,81 @@ impl OperationPool {
         sender_balances: &PreHashMap<Address, Amount>,
     ) {
         let mut removed = PreHashSet::default();
+        let mut changes = Vec::new();
+        let mut filter_counts = RefreshFilterCounts::default();
         self.sorted_ops.retain(|op_info| {
-            // filter out ops that use too much resources
-            let mut retain = (op_info.max_gas_usage <= self.config.max_block_gas)
-                && (op_info.size <= self.config.max_block_size as usize);
-
-            // filter out ops that are not valid during our PoS draws
-            if retain {
-                retain = pos_draws.iter().any(|slot| {
-                    op_info.thread == slot.thread
-                        && op_info.validity_period_range.contains(&slot.period)
-                });
-            }
-
-            if retain {
+            let filter = if op_info.max_gas_usage > self.config.max_block_gas
+                || op_info.size > self.config.max_block_size as usize
+            {
+                // filter out ops that use too much resources
+                Some((
+                    &mut filter_counts.too_many_resources,
+                    PoolEventKind::Discarded,
+                    Some("uses too many resources"),
+                ))
+            } else if !pos_draws.iter().any(|slot| {
+                op_info.thread == slot.thread
+                    && op_info.validity_period_range.contains(&slot.period)
+            }) {
+                // filter out ops that are not valid during our PoS draws
+                // (the ones whose last valid period is final can't be included by anyone anymore)
+                let (kind, reason) = if *op_info.validity_period_range.end()
+                    <= self.last_cs_final_periods[op_info.thread as usize]
+                {
+                    (PoolEventKind::Expired, None)
+                } else {
+                    (
+                        PoolEventKind::Discarded,
+                        Some("not valid during any of our production slots"),
+                    )
+                };
+                Some((&mut filter_counts.no_production_slot, kind, reason))
+            } else if op_info.fee.checked_sub(self.config.minimal_fees).is_none() {
                 // filter ops which doesn't have minimal fees
-                retain = op_info.fee.checked_sub(self.config.minimal_fees).is_some();
-            }
-
-            // filter out ops that have been executed in final or candidate slots
-            // TODO: in the re-execution followup, we should only filter out final-executed ops here (exec_status == Some(true))
-            if retain {
-                retain = !exec_statuses.contains_key(&op_info.id);
-            }
-
-            // filter out ops that spend more than the sender's balance
-            if retain {
-                retain = match sender_balances.get(&op_info.creator_address) {
-                    Some(v) => &op_info.max_spending <= v,
-                    None => false, // filter out ops for which the sender does not exist
+                Some((
+                    &mut filter_counts.insufficient_fee,
+                    PoolEventKind::Discarded,
+                    Some("fee below the minimal fees"),
+                ))
+            } else if let Some(is_final) = exec_statuses.get(&op_info.id) {
+                // filter out ops that have been executed in final or candidate slots
+                // TODO: in the re-execution followup, we should only filter out final-executed ops here (exec_status == Some(true))
+                let reason = if *is_final {
+                    "executed in a final slot"
+                } else {
+                    "executed in a candidate slot"
                 };
-            }
+                Some((
+                    &mut filter_counts.executed,
+                    PoolEventKind::Included,
+                    Some(reason),
+                ))
+            } else if !sender_balances
+                .get(&op_info.creator_address)
+                .is_some_and(|v| &op_info.max_spending <= v)
+            {
+                // filter out ops that spend more than the sender's balance,
+                // or for which the sender does not exist
+                Some((
+                    &mut filter_counts.insufficient_balance,
+                    PoolEventKind::Discarded,
+                    Some("spends more than the balance of its creator"),
+                ))
+            } else {
+                None
+            };
 
-            if !retain {
+            if let Some((filter_count, kind, reason)) = filter {
+                *filter_count += 1;
+                self.sorted_ops_bytes -= op_info.size;
                 removed.insert(op_info.id);
+                changes.push(operation_change(op_info, kind, reason));
                 return false;
             }
             true
         });
+        self.refresh_filters = filter_counts;
+        broadcast_pool_changes(&self.config, &self.channels, changes);
         // drop from storage
         self.storage.drop_operation_refs(&removed);
     }
@@ -199,6 +285,7 @@ impl OperationPool {
     fn eliminate_balance_overflows(&mut self, sender_balances: &PreHashMap<Address, Amount>) {
         let mut balance_cache = PreHashMap::default();
         let mut removed = PreHashSet::default();
+        let mut changes = Vec::new();
         self.sorted_ops.retain(|op_info| {
             let balance = balance_cache
                 .entry(op_info.creator_address)
@@ -214,28 +301,45 @@ impl OperationPool {
                     true
                 }
                 None => {
+                    self.sorted_ops_bytes -= op_info.size;
                     removed.insert(op_info.id);
+                    changes.push(operation_change(
+                        op_info,
+                        PoolEventKind::Discarded,
+                        Some("its creator can't afford it along with its better operations"),
+                    ));
                     false
                 }
             }
         });
+        broadcast_pool_changes(&self.config, &self.channels, changes);
         // drop from storage
         self.storage.drop_operation_refs(&removed);
     }
 
-    /// Truncates the container to the max allowed size
+    /// Truncates the container to the max allowed number of operations and serialized size
     fn truncate_container(&mut self) {
-        if self.sorted_ops.len() > self.config.max_operation_pool_size {
+        // keep the best operations as long as they fit in both limits
+        let mut kept_bytes: usize = 0;
+        let kept_count = self
+            .sorted_ops
+            .iter()
+            .take(self.config.max_operation_pool_size)
+            .take_while(|op_info| {
+                kept_bytes = kept_bytes.saturating_add(op_info.size);
+                kept_bytes <= self.config.max_operation_pool_bytes
+            })
+            .count();
+        if self.sorted_ops.len() > kept_count {
             let mut removed = PreHashSet::default();
-            for op_info in self
-                .sorted_ops
-                .iter()
-                .skip(self.config.max_operation_pool_size)
-            {
+            let mut changes = Vec::new();
+            for op_info in self.sorted_ops.iter().skip(kept_count) {
+                self.sorted_ops_bytes -= op_info.size;
                 removed.insert(op_info.id);
+                changes.push(operation_change(op_info, PoolEventKind::Evicted, None));
             }
-            self.sorted_ops
-                .truncate(self.config.max_operation_pool_size);
+            self.sorted_ops.truncate(kept_count);
+            broadcast_pool_changes(&self.config, &self.channels, changes);
             // drop from storage
             self.storage.drop_operation_refs(&removed);
         }
@@ -246,7 +350,7 @@ impl OperationPool {
         &self,
         _exec_statuses: &PreHashMap<OperationId, bool>,
         pos_draws: &BTreeSet<Slot>,
-    ) -> PreHashMap<OperationId, f32> {
+    ) -> PreHashMap<OperationId, OperationPriority> {
         let now = MassaTime::now();
         let now_period = get_latest_block_slot_at_timestamp(
             self.config.thread_count,
@@ -325,7 +429,15 @@ impl OperationPool {
             //  * reexecution_factor; // TODO: re-execution followup
 
             // store the score
-            scores.insert(op_info.id, score);
+            scores.insert(
+                op_info.id,
+                OperationPriority {
+                    fee_factor,
+                    resource_factor,
+                    inclusion_factor,
+                    score,
+                },
+            );
         }
         scores
     }
@@ -346,16 +458,20 @@ impl OperationPool {
         self.prefilter_ops(&exec_statuses, &pos_draws, &sender_balances);
 
         // score operations
-        let scores = self.score_operations(&exec_statuses, &pos_draws);
+        self.priorities = self.score_operations(&exec_statuses, &pos_draws);
 
         // sort by score
+        let priorities = &self.priorities;
         self.sorted_ops.sort_unstable_by(|op1, op2| {
             // note1: scores are float => we need to use partial_cmp.
             // note2: operands are reversed to sort from highest to lowest !
-            scores
+            // note3: ties are broken by operation id so that nodes with the same pool sort it the same way
+            priorities
                 .get(&op2.id)
-                .partial_cmp(&scores.get(&op1.id))
+                .map(|priority| priority.score)
+                .partial_cmp(&priorities.get(&op1.id).map(|priority| priority.score))
                 .unwrap_or(Ordering::Equal)
+                .then_with(|| op1.id.cmp(&op2.id))
         });
 
         // eliminate balance overflows in sorted ops
@@ -365,6 +481,65 @@ impl OperationPool {
         self.truncate_container();
     }
 
+    /// Check that the pool operations match the operation references held by its storage,
+    /// logging the discrepancies and repairing them if `operation_pool_ref_audit_self_heal` is set.
+    pub(crate) fn audit_refs(&mut self) -> RefAuditReport {
+        let claimed: PreHashSet<OperationId> =
+            self.sorted_ops.iter().map(|op_info| op_info.id).collect();
+        let report = self.storage.audit_refs_against(&claimed);
+        if report.is_empty() {
+            return report;
+        }
+        self.massa_metrics
+            .inc_operations_pool_ref_discrepancies(report.discrepancy_count() as u64);
+        warn!(
+            "operation pool of {} operations out of sync with its storage: {} missing refs, {} operations no longer stored, {} unclaimed refs, {} refs without owner (self-heal: {})",
+            claimed.len(),
+            report.missing_refs.len(),
+            report.missing_objects.len(),
+            report.unclaimed_refs.len(),
+            report.ownerless_refs.len(),
+            self.config.operation_pool_ref_audit_self_heal,
+        );
+        debug!("operation pool reference audit: {:?}", report);
+
+        if self.config.operation_pool_ref_audit_self_heal {
+            self.storage.heal_operation_refs(&report);
+            // the operations that are no longer stored can't be included in blocks anymore
+            let mut changes = Vec::new();
+            self.sorted_ops.retain(|op_info| {
+                if report.missing_objects.contains(&op_info.id) {
+                    self.sorted_ops_bytes -= op_info.size;
+                    changes.push(operation_change(
+                        op_info,
+                        PoolEventKind::Discarded,
+                        Some("no longer in storage"),
+                    ));
+                    return false;
+                }
+                true
+            });
+            for id in &report.missing_objects {
+                self.priorities.remove(id);
+            }
+            broadcast_pool_changes(&self.config, &self.channels, changes);
+        }
+        report
+    }
+
+    /// Remove an operation from the pool without releasing its storage reference.
+    /// Only meant to desynchronize the pool from its storage in tests.
+    #[cfg(test)]
+    pub(crate) fn forget_operation_unchecked(&mut self, id: &OperationId) {
+        self.sorted_ops.retain(|op_info| {
+            if &op_info.id == id {
+                self.sorted_ops_bytes -= op_info.size;
+                return false;
+            }
+            true
+        });
+    }
+
     /// Get the number of stored elements
     pub fn len(&self) -> usize {
         self.sorted_ops.len()
@@ -375,6 +550,44 @@ impl OperationPool {
         self.storage.get_op_refs().contains(id)
     }
 
+    /// Get the metadata of the `max_items` best ranked operations
+    pub fn get_operations_sample(&self, max_items: usize) -> Vec<PooledOperationSummary> {
+        self.sorted_ops
+            .iter()
+            .take(max_items)
+            .map(|op_info| PooledOperationSummary {
+                id: op_info.id,
+                creator_address: op_info.creator_address,
+                thread: op_info.thread,
+                fee: op_info.fee,
+                size: op_info.size,
+                max_gas_usage: op_info.max_gas_usage,
+                max_spending: op_info.max_spending,
+                validity_start_period: *op_info.validity_period_range.start(),
+                validity_end_period: *op_info.validity_period_range.end(),
+            })
+            .collect()
+    }
+
+    /// Get the metadata of the stored operations, in the pool order
+    pub fn operation_infos(&self) -> Arc<[PooledOperationInfo]> {
+        self.sorted_ops.as_slice().into()
+    }
+
+    /// Get the operations of a list that recently expired without being executed
+    pub fn get_expired_operations(&self, ids: &[OperationId]) -> Vec<Option<ExpiredOperation>> {
+        let min_expired_at =
+            MassaTime::now().saturating_sub(self.config.expired_operations_retention);
+        ids.iter()
+            .map(|id| {
+                self.expired_ops
+                    .get(id)
+                    .filter(|expired| expired.expired_at >= min_expired_at)
+                    .cloned()
+            })
+            .collect()
+    }
+
     /// notify of new final slot
     pub(crate) fn notify_final_cs_periods(&mut self, final_cs_periods: &[u64]) {
         // update internal final slot counter
@@ -383,6 +596,125 @@ impl OperationPool {
             "notified of new final consensus periods: {:?}",
             self.last_cs_final_periods
         );
+        self.process_expirations();
+    }
+
+    /// Watch new operations until their expire period is final
+    fn watch_operations(&mut self, ops_storage: &Storage, op_ids: &PreHashSet<OperationId>) {
+        // same bound as the pool itself, the operations that can't be watched are never reported as expired
+        let max_watched_ops = self
+            .config
+            .max_operation_pool_size
+            .saturating_add(self.config.max_operation_pool_excess_items);
+        let mut new_watched_ids = PreHashSet::default();
+        {
+            let ops = ops_storage.read_operations();
+            for op_id in op_ids {
+                if self.watched_storage.get_op_refs().len() + new_watched_ids.len()
+                    >= max_watched_ops
+                {
+                    break;
+                }
+                // an operation is reported as expired only once
+                if self.watched_storage.get_op_refs().contains(op_id)
+                    || self.expired_ops.contains_key(op_id)
+                {
+                    continue;
+                }
+                let Some(op) = ops.get(op_id) else {
+                    continue;
+                };
+                let thread = op
+                    .content_creator_address
+                    .get_thread(self.config.thread_count);
+                self.watched_ops[thread as usize]
+                    .entry(op.content.expire_period)
+                    .or_default()
+                    .insert(*op_id);
+                new_watched_ids.insert(*op_id);
+            }
+        }
+        // the operations are still referenced by `ops_storage`, they can be claimed
+        self.watched_storage.claim_operation_refs(&new_watched_ids);
+    }
+
+    /// Stop watching the operations whose expire period is final, and remember the ones that
+    /// were not executed as expired
+    fn process_expirations(&mut self) {
+        let mut ended_ids = PreHashSet::default();
+        for (thread, final_period) in self.last_cs_final_periods.iter().enumerate() {
+            // a block at the expire period may only just have become final: wait for one more
+            // final period so that the execution had time to execute it
+            let watched = &mut self.watched_ops[thread];
+            let still_watched = watched.split_off(final_period);
+            for (_, op_ids) in std::mem::replace(watched, still_watched) {
+                ended_ids.extend(op_ids);
+            }
+        }
+        if ended_ids.is_empty() {
+            return;
+        }
+
+        let ended_ids: Vec<OperationId> = ended_ids.into_iter().collect();
+        let exec_statuses = self
+            .channels
+            .execution_controller
+            .get_ops_exec_status(&ended_ids);
+        let expired_at = MassaTime::now();
+        let mut expired = Vec::new();
+        {
+            let ops = self.watched_storage.read_operations();
+            for (op_id, exec_status) in ended_ids.iter().zip(exec_statuses) {
+                if exec_status != (None, None) {
+                    continue;
+                }
+                if let Some(op) = ops.get(op_id) {
+                    expired.push(ExpiredOperation {
+                        operation: op.clone(),
+                        expired_at,
+                    });
+                }
+            }
+        }
+        self.watched_storage
+            .drop_operation_refs(&ended_ids.into_iter().collect());
+
+        for expired_op in expired {
+            debug!(
+                "operation {} expired without being executed",
+                expired_op.operation.id
+            );
+            if self.config.broadcast_enabled {
+                if let Err(err) = self
+                    .channels
+                    .broadcasts
+                    .expired_operation_sender
+                    .send(expired_op.clone())
+                {
+                    trace!(
+                        "error, failed to broadcast expired operation {}: {}",
+                        expired_op.operation.id,
+                        err
+                    );
+                }
+            }
+            self.expired_order.push_back(expired_op.operation.id);
+            self.expired_ops.insert(expired_op.operation.id, expired_op);
+        }
+
+        // forget the oldest expired operations
+        let min_expired_at = expired_at.saturating_sub(self.config.expired_operations_retention);
+        while let Some(op_id) = self.expired_order.front().copied() {
+            let outdated = self
+                .expired_ops
+                .get(&op_id)
+                .map_or(true, |expired| expired.expired_at < min_expired_at);
+            if !outdated && self.expired_order.len() <= self.config.max_expired_operations {
+                break;
+            }
+            self.expired_order.pop_front();
+            self.expired_ops.remove(&op_id);
+        }
     }
 
     /// Add a list of operations to the end of the pool.
@@ -391,6 +723,32 @@ impl OperationPool {
         // List all the new operations
         let mut new_op_ids = ops_storage.get_op_refs() - self.storage.get_op_refs();
 
+        // Drop the operations carrying an invalid datastore as they can never be included in a block.
+        {
+            let limits = DatastoreLimits {
+                max_entry_count: self.config.max_op_datastore_entry_count,
+                max_key_length: self.config.max_op_datastore_key_length,
+                max_value_length: self.config.max_op_datastore_value_length,
+                max_total_size: self.config.max_block_size as u64,
+            };
+            let ops = ops_storage.read_operations();
+            new_op_ids.retain(|op_id| {
+                let Some(datastore) = ops.get(op_id).and_then(|op| op.get_datastore()) else {
+                    return true;
+                };
+                match datastore.validate(&limits) {
+                    Ok(()) => true,
+                    Err(err) => {
+                        debug!(
+                            "dropping operation {} with invalid datastore: {}",
+                            op_id, err
+                        );
+                        false
+                    }
+                }
+            });
+        }
+
         // If there are too many extra operations,
         // we don't want the container to fill up too much in-between refreshes so we drop any excess.
         // This is because refreshing the container is very heavy and is only called periodically.
@@ -414,13 +772,48 @@ impl OperationPool {
             );
         }
 
+        // Same for the serialized size of the operations: the ones exceeding the excess on the
+        // pool bytes are dropped, by increasing id like they are added.
+        {
+            let max_bytes = self
+                .config
+                .max_operation_pool_bytes
+                .saturating_add(self.config.max_operation_pool_excess_bytes);
+            let ops = ops_storage.read_operations();
+            let mut sorted_new_op_ids: Vec<OperationId> = new_op_ids.iter().copied().collect();
+            sorted_new_op_ids.sort_unstable();
+            let mut pool_bytes = self.sorted_ops_bytes;
+            let mut dropped_bytes = 0;
+            for new_op_id in sorted_new_op_ids {
+                let size = ops
+                    .get(&new_op_id)
+                    .map(|op| op.serialized_size())
+                    .unwrap_or_default();
+                if pool_bytes.saturating_add(size) > max_bytes {
+                    new_op_ids.remove(&new_op_id);
+                    dropped_bytes += size;
+                } else {
+                    pool_bytes += size;
+                }
+            }
+            if dropped_bytes > 0 {
+                warn!(
+                    "Operation pool excess size limit reached. Dropping {} bytes of non-scored operations.",
+                    dropped_bytes
+                );
+            }
+        }
+
         // Add the new ops to the container.
-        // Note that the added items are put at the end of the sorted ops
+        // Note that the added items are put at the end of the sorted ops, by increasing id,
         // so that they can still be picked for block production before refresh but with low priority
         // because in that case we don't know anything about their quality.
+        let mut changes = Vec::with_capacity(new_op_ids.len());
         {
             let ops = ops_storage.read_operations();
-            for new_op_id in &new_op_ids {
+            let mut sorted_new_op_ids: Vec<&OperationId> = new_op_ids.iter().collect();
+            sorted_new_op_ids.sort_unstable();
+            for new_op_id in sorted_new_op_ids {
                 let op = ops
                     .get(new_op_id)
                     .expect("operation not found in storage but listed as owned");
@@ -432,16 +825,22 @@ impl OperationPool {
                     }
                 }
 
-                self.sorted_ops.push(OperationInfo::from_op(
+                let op_info = PooledOperationInfo::from_op(
                     op,
                     self.config.operation_validity_periods,
                     self.config.roll_price,
                     self.config.thread_count,
                     self.config.base_operation_gas_cost,
                     self.config.sp_compilation_cost,
-                ));
+                );
+                changes.push(operation_change(&op_info, PoolEventKind::Admitted, None));
+                self.sorted_ops_bytes += op_info.size;
+                self.sorted_ops.push(op_info);
             }
         }
+        broadcast_pool_changes(&self.config, &self.channels, changes);
+
+        self.watch_operations(&ops_storage, &new_op_ids);
 
         // This will add the new ops to the storage without taking locks.
         // It just take the local references from `ops_storage` if they are not in `self.storage` yet.
@@ -461,9 +860,20 @@ impl OperationPool {
     /// Searches the available operations, and selects the sub-set of operations that:
     /// - fit inside the block
     /// - is the most profitable for block producer
-    pub fn get_block_operations(&self, slot: &Slot) -> (Vec<OperationId>, Storage) {
+    /// - is not created by one of `excluded_creators`
+    /// - pays at least `min_fee`
+    ///
+    /// Also returns the number of operations that were left out because of their creator.
+    pub fn get_block_operations(
+        &self,
+        slot: &Slot,
+        excluded_creators: &PreHashSet<Address>,
+        min_fee: Amount,
+    ) -> (Vec<OperationId>, Storage, usize) {
         // init list of selected operation IDs
         let mut op_ids = Vec::new();
+        // init number of operations left out because of their creator
+        let mut excluded_count = 0;
 
         // init remaining space
         let mut remaining_space = self.config.max_block_size as usize;
@@ -472,30 +882,64 @@ impl OperationPool {
         // init remaining number of operations
         let mut remaining_ops = self.config.max_operations_per_block;
 
+        // init the audit of the selection, if enabled
+        let mut audit = (self.config.max_selection_audit_slots > 0).then(|| SelectionAudit {
+            slot: *slot,
+            candidates: Vec::new(),
+            other_thread_count: 0,
+            cut_point: None,
+            refresh_filters: self.refresh_filters,
+        });
+
         // iterate over pool operations in the right thread, from best to worst
         for op_info in &self.sorted_ops {
             // if we have reached the maximum number of operations, stop
             if remaining_ops == 0 {
+                if let Some(audit) = audit.as_mut() {
+                    audit.cut_point = Some(audit.candidates.len());
+                }
                 break;
             }
 
             // check thread
             if op_info.thread != slot.thread {
+                if let Some(audit) = audit.as_mut() {
+                    audit.other_thread_count += 1;
+                }
                 continue;
             }
 
-            // exclude ops for which the block slot is outside of their validity range
-            if !op_info.validity_period_range.contains(&slot.period) {
-                continue;
-            }
-
-            // exclude ops that are too large
-            if op_info.size > remaining_space {
-                continue;
+            let outcome = if !op_info.validity_period_range.contains(&slot.period) {
+                // exclude ops for which the block slot is outside of their validity range
+                SelectionOutcome::OutsideValidityRange
+            } else if excluded_creators.contains(&op_info.creator_address) {
+                // exclude ops created by the excluded addresses, before they take any block space
+                excluded_count += 1;
+                SelectionOutcome::ExcludedCreator
+            } else if op_info.fee < min_fee {
+                // exclude ops paying less than the fee floor of the producer, leaving them in the pool
+                self.massa_metrics.inc_operations_pool_below_fee_floor();
+                SelectionOutcome::BelowFeeFloor
+            } else if op_info.size > remaining_space {
+                // exclude ops that are too large
+                SelectionOutcome::TooLarge
+            } else if op_info.max_gas_usage > remaining_gas {
+                // exclude ops that require too much gas
+                SelectionOutcome::TooMuchGas
+            } else {
+                SelectionOutcome::Selected
+            };
+            if let Some(audit) = audit.as_mut() {
+                audit.candidates.push(AuditedOperation {
+                    id: op_info.id,
+                    fee: op_info.fee,
+                    size: op_info.size,
+                    max_gas_usage: op_info.max_gas_usage,
+                    priority: self.priorities.get(&op_info.id).cloned(),
+                    outcome,
+                });
             }
-
-            // exclude ops that require too much gas
-            if op_info.max_gas_usage > remaining_gas {
+            if outcome != SelectionOutcome::Selected {
                 continue;
             }
 
@@ -520,6 +964,43 @@ impl OperationPool {
             panic!("could not claim all operations from storage");
         }
 
-        (op_ids, res_storage)
+        if let Some(audit) = audit {
+            self.record_selection_audit(audit);
+        }
+
+        (op_ids, res_storage, excluded_count)
+    }
+
+    /// Keep the audit of a selection, replacing the previous audit of the same slot
+    fn record_selection_audit(&self, audit: SelectionAudit) {
+        let mut audits = self.selection_audits.lock();
+        audits.retain(|previous| previous.slot != audit.slot);
+        while audits.len() >= self.config.max_selection_audit_slots {
+            audits.pop_front();
+        }
+        audits.push_back(audit);
+    }
+
+    /// Get the audit of the operation selection for a recent slot
+    pub fn get_selection_audit(&self, slot: &Slot) -> Option<SelectionAudit> {
+        self.selection_audits
+            .lock()
+            .iter()
+            .find(|audit| &audit.slot == slot)
+            .cloned()
+    }
+}
+
+/// Change of the pool content concerning an operation
+fn operation_change(
+    op_info: &PooledOperationInfo,
+    kind: PoolEventKind,
+    reason: Option<&'static str>,
+) -> PoolChange {
+    PoolChange {
+        kind,
+        object: PoolObjectId::Operation(op_info.id),
+        creator: op_info.creator_address,
+        reason,
     }
 }
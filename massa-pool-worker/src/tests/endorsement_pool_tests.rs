@@ -1,8 +1,19 @@
-use std::{collections::BTreeMap, time::Duration};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
-use massa_models::{address::Address, config::THREAD_COUNT, slot::Slot};
+use massa_models::{
+    address::Address,
+    config::{PERIODS_PER_CYCLE, THREAD_COUNT},
+    slot::Slot,
+};
 use massa_pool_exports::PoolConfig;
-use massa_pos_exports::{MockSelectorController, Selection};
+use massa_pos_exports::{MockSelectorController, PosError, Selection};
 use massa_signature::KeyPair;
 
 use super::tools::{create_endorsement, default_mock_execution_controller, pool_test};
@@ -71,6 +82,45 @@ fn test_add_endorsements() {
     );
 }
 
+#[test]
+fn test_snapshot_endorsements() {
+    let sender_keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&sender_keypair.get_public_key());
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = default_mock_selector(address);
+    pool_test(
+        PoolConfig::default(),
+        execution_controller,
+        selector_controller,
+        Some((address, sender_keypair.clone())),
+        |mut pool, mut storage| {
+            let first = create_endorsement(&sender_keypair, 0, Slot::new(1, 2));
+            storage.store_endorsements(vec![first.clone()]);
+            pool.add_endorsements(storage.clone());
+            // Allow some time for the pool to add the endorsements
+            std::thread::sleep(Duration::from_secs(2));
+            let snapshot = pool.snapshot_endorsements();
+            assert_eq!(snapshot.items.len(), 1);
+            assert_eq!(snapshot.items[0].id, first.id);
+            assert_eq!(snapshot.items[0].slot, Slot::new(1, 2));
+
+            storage.store_endorsements(vec![create_endorsement(
+                &sender_keypair,
+                0,
+                Slot::new(1, 3),
+            )]);
+            pool.add_endorsements(storage.clone());
+            std::thread::sleep(Duration::from_secs(2));
+            let next_snapshot = pool.snapshot_endorsements();
+            assert_eq!(next_snapshot.sequence, snapshot.sequence + 1);
+            assert_eq!(next_snapshot.items.len(), 2);
+            // the previous snapshot is not affected by the pool changes
+            assert_eq!(snapshot.items.len(), 1);
+            assert_eq!(snapshot.items[0].id, first.id);
+        },
+    );
+}
+
 #[test]
 fn test_dont_add_endorsements_bad_pos() {
     let sender_keypair = KeyPair::generate(0).unwrap();
@@ -255,3 +305,73 @@ fn test_get_block_endorsements_works() {
         },
     );
 }
+
+#[test]
+fn test_park_endorsements_beyond_horizon() {
+    let sender_keypair = KeyPair::generate(0).unwrap();
+    let address = Address::from_public_key(&sender_keypair.get_public_key());
+    let impostor_keypair = KeyPair::generate(0).unwrap();
+    let execution_controller = default_mock_execution_controller();
+    // the draws are beyond the selector horizon until `draws_available` is set
+    let draws_available = Arc::new(AtomicBool::new(false));
+    let selector_controller = {
+        let draws_available = draws_available.clone();
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().returning(move || {
+            let draws_available = draws_available.clone();
+            let mut res = Box::new(MockSelectorController::new());
+            res.expect_get_selection().returning(move |slot| {
+                if !draws_available.load(Ordering::SeqCst) {
+                    return Err(PosError::CycleUnavailable(
+                        slot.get_cycle(PERIODS_PER_CYCLE),
+                    ));
+                }
+                Ok(Selection {
+                    producer: address,
+                    endorsements: vec![address; 16],
+                })
+            });
+            res.expect_get_available_selections_in_range()
+                .returning(|_, _| Ok(BTreeMap::default()));
+            res
+        });
+        res
+    };
+
+    pool_test(
+        PoolConfig::default(),
+        execution_controller,
+        selector_controller,
+        Some((address, sender_keypair.clone())),
+        |mut pool, mut storage| {
+            let endorsements = vec![
+                create_endorsement(&sender_keypair, 0, Slot::new(1, 2)),
+                create_endorsement(&impostor_keypair, 1, Slot::new(1, 2)),
+            ];
+            let endorsed_block = endorsements[0].content.endorsed_block;
+            storage.store_endorsements(endorsements.clone());
+            pool.add_endorsements(storage.clone());
+            // Allow some time for the pool to add the endorsements
+            std::thread::sleep(Duration::from_secs(2));
+            // the endorsements are parked: they can't be included in a block yet
+            assert_eq!(pool.get_endorsement_count(), 0);
+            let (endorsement_ids, _) =
+                pool.get_block_endorsements(&endorsed_block, &Slot::new(1, 2));
+            assert!(endorsement_ids.iter().all(|id| id.is_none()));
+
+            // the horizon advances: only the drawn creator is admitted
+            draws_available.store(true, Ordering::SeqCst);
+            pool.notify_final_cs_periods(&vec![0; THREAD_COUNT as usize]);
+            std::thread::sleep(Duration::from_secs(2));
+            assert_eq!(
+                pool.contains_endorsements(&[endorsements[0].id, endorsements[1].id]),
+                vec![true, false]
+            );
+            let (endorsement_ids, endorsements_storage) =
+                pool.get_block_endorsements(&endorsed_block, &Slot::new(1, 2));
+            assert_eq!(endorsement_ids[0], Some(endorsements[0].id));
+            assert_eq!(endorsement_ids[1], None);
+            assert_eq!(endorsements_storage.get_endorsement_refs().len(), 1);
+        },
+    );
+}
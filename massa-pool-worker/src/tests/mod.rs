@@ -2,5 +2,8 @@
 
 mod endorsement_pool_tests;
 mod operation_pool_tests;
+mod pool_events_tests;
+mod ref_audit_tests;
 mod scenario;
+mod selection_audit_tests;
 pub(crate) mod tools;
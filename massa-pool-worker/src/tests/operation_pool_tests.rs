@@ -22,7 +22,10 @@ use crate::tests::tools::OpGenerator;
 use super::tools::{
     create_some_operations, default_mock_execution_controller, pool_test, PoolTestBoilerPlate,
 };
-use massa_models::{amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, slot::Slot};
+use massa_models::{
+    amount::Amount, config::ENDORSEMENT_COUNT, operation::OperationId, prehash::PreHashSet,
+    slot::Slot,
+};
 use massa_pool_exports::PoolConfig;
 use massa_pos_exports::{MockSelectorController, Selection};
 use std::{collections::BTreeMap, time::Duration};
@@ -79,6 +82,79 @@ fn test_add_operation() {
     );
 }
 
+#[test]
+fn test_snapshot_operations() {
+    let execution_controller = default_mock_execution_controller();
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+    pool_test(
+        PoolConfig::default(),
+        execution_controller,
+        selector_controller,
+        None,
+        |mut operation_pool, mut storage| {
+            let empty_snapshot = operation_pool.snapshot_operations();
+            assert!(empty_snapshot.items.is_empty());
+
+            let op_gen = OpGenerator::default().expirery(2);
+            let ops = create_some_operations(10, &op_gen);
+            let op_ids: PreHashSet<OperationId> = ops.iter().map(|op| op.id).collect();
+            storage.store_operations(ops);
+            operation_pool.add_operations(storage);
+            // Allow some time for the pool to add the operations
+            std::thread::sleep(Duration::from_secs(3));
+
+            let snapshot = operation_pool.snapshot_operations();
+            assert!(snapshot.sequence > empty_snapshot.sequence);
+            assert_eq!(
+                snapshot
+                    .items
+                    .iter()
+                    .map(|info| info.id)
+                    .collect::<PreHashSet<_>>(),
+                op_ids
+            );
+            // the snapshots taken before are not affected by the pool changes
+            assert!(empty_snapshot.items.is_empty());
+
+            let next_snapshot = operation_pool.snapshot_operations();
+            assert_eq!(next_snapshot.sequence, snapshot.sequence + 1);
+            assert_eq!(next_snapshot.items, snapshot.items);
+        },
+    );
+}
+
 /// Test if adding irrelevant operations make simply skip the add.
 /// # Initialization
 #[test]
@@ -214,7 +290,8 @@ fn test_pool() {
     // // checks ops are the expected ones for thread 0 and 1 and various periods
     for thread in 0u8..pool_config.thread_count {
         let target_slot = Slot::new(0, thread);
-        let (ids, storage) = pool_controller.get_block_operations(&target_slot);
+        let (ids, storage, _) =
+            pool_controller.get_block_operations(&target_slot, &Default::default(), Amount::zero());
 
         assert_eq!(
             ids.iter()
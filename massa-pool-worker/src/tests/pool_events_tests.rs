@@ -0,0 +1,348 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! # Pool events tests
+//! The pools are driven directly, without their workers, so that the events of each step can be
+//! checked as soon as the step returns.
+
+use super::tools::{create_endorsement, test_metrics, OpGenerator};
+use crate::{endorsement_pool::EndorsementPool, operation_pool::OperationPool};
+use massa_channel::broadcast::DedupBroadcaster;
+use massa_execution_exports::MockExecutionController;
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    config::{ENDORSEMENT_COUNT, THREAD_COUNT},
+    operation::OperationId,
+    prehash::PreHashMap,
+    slot::Slot,
+};
+use massa_pool_exports::{
+    PoolBroadcasts, PoolChannels, PoolConfig, PoolEvent, PoolEventKind, PoolObjectId,
+};
+use massa_pos_exports::{MockSelectorController, Selection};
+use massa_signature::KeyPair;
+use massa_storage::Storage;
+use massa_wallet::{test_exports::create_test_wallet, Wallet};
+use parking_lot::RwLock;
+use std::{collections::BTreeMap, sync::Arc};
+use tokio::sync::broadcast::error::TryRecvError;
+
+/// Channels of a pool whose staker is drawn for every block and endorsement,
+/// `executed` being reported as executed in a candidate slot
+fn pool_channels(
+    staker: Address,
+    executed: Option<OperationId>,
+    pool_event_sender: DedupBroadcaster<PoolEvent>,
+) -> PoolChannels {
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    execution_controller
+        .expect_get_ops_exec_status()
+        .returning(move |ops| {
+            ops.iter()
+                .map(|id| match executed {
+                    Some(executed) if executed == *id => (Some(true), None),
+                    _ => (None, None),
+                })
+                .collect()
+        });
+    execution_controller
+        .expect_get_final_and_candidate_balance()
+        .returning(|addrs| {
+            vec![
+                (
+                    Some(Amount::const_init(1_000_000_000, 0)),
+                    Some(Amount::const_init(1_000_000_000, 0)),
+                );
+                addrs.len()
+            ]
+        });
+    let selection = Selection {
+        producer: staker,
+        endorsements: vec![staker; ENDORSEMENT_COUNT as usize],
+    };
+    let mut selector = Box::new(MockSelectorController::new());
+    let range_selection = selection.clone();
+    selector
+        .expect_get_available_selections_in_range()
+        .returning(move |slot_range, _| {
+            let mut all_slots = BTreeMap::new();
+            for period in 0..15 {
+                for thread in 0..THREAD_COUNT {
+                    let slot = Slot::new(period, thread);
+                    if slot_range.contains(&slot) {
+                        all_slots.insert(slot, range_selection.clone());
+                    }
+                }
+            }
+            Ok(all_slots)
+        });
+    selector
+        .expect_get_selection()
+        .returning(move |_| Ok(selection.clone()));
+    PoolChannels {
+        execution_controller,
+        selector,
+        broadcasts: PoolBroadcasts {
+            endorsement_sender: DedupBroadcaster::new("test_endorsements".to_string(), 10, 10),
+            operation_sender: DedupBroadcaster::new("test_operations".to_string(), 10, 10),
+            expired_operation_sender: tokio::sync::broadcast::channel(10).0,
+            pool_event_sender,
+        },
+    }
+}
+
+fn staking_wallet(staker: &KeyPair) -> Arc<RwLock<Wallet>> {
+    let mut addresses = PreHashMap::default();
+    addresses.insert(
+        Address::from_public_key(&staker.get_public_key()),
+        staker.clone(),
+    );
+    Arc::new(RwLock::new(create_test_wallet(Some(addresses))))
+}
+
+/// Kinds and objects of the events received so far
+fn received(
+    receiver: &mut tokio::sync::broadcast::Receiver<PoolEvent>,
+) -> Vec<(PoolEventKind, PoolObjectId)> {
+    std::iter::from_fn(|| receiver.try_recv().ok())
+        .map(|event| (event.kind, event.object))
+        .collect()
+}
+
+#[test]
+fn test_operation_pool_events() {
+    let config = PoolConfig {
+        broadcast_enabled: true,
+        ..Default::default()
+    };
+    let staker = KeyPair::generate(0).unwrap();
+    let staker_address = Address::from_public_key(&staker.get_public_key());
+    let expiring = OpGenerator::default()
+        .creator(staker.clone())
+        .expirery(1)
+        .generate();
+    let executed = OpGenerator::default()
+        .creator(staker.clone())
+        .expirery(10)
+        .generate();
+    let kept = OpGenerator::default()
+        .creator(staker.clone())
+        .expirery(10)
+        .generate();
+
+    let pool_event_sender = DedupBroadcaster::new("test_pool_events".to_string(), 10, 0);
+    let mut receiver = pool_event_sender.subscribe();
+    let storage = Storage::create_root();
+    let mut pool = OperationPool::init(
+        config,
+        &storage,
+        pool_channels(staker_address, Some(executed.id), pool_event_sender),
+        staking_wallet(&staker),
+        test_metrics(),
+    );
+
+    // the operations are admitted by increasing id
+    let mut ops_storage = storage.clone_without_refs();
+    ops_storage.store_operations(vec![expiring.clone(), executed.clone(), kept.clone()]);
+    pool.add_operations(ops_storage);
+    let mut admitted = vec![expiring.id, executed.id, kept.id];
+    admitted.sort_unstable();
+    let events: Vec<PoolEvent> = std::iter::from_fn(|| receiver.try_recv().ok()).collect();
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| (event.kind, event.object))
+            .collect::<Vec<_>>(),
+        admitted
+            .iter()
+            .map(|id| (PoolEventKind::Admitted, PoolObjectId::Operation(*id)))
+            .collect::<Vec<_>>()
+    );
+    assert!(events
+        .iter()
+        .all(|event| event.creator == staker_address && event.reason.is_none()));
+
+    // the expire period of `expiring` becomes final, and `executed` gets executed:
+    // both leave the pool at the next refresh, in the pool order
+    pool.notify_final_cs_periods(&vec![2; THREAD_COUNT as usize]);
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    pool.refresh();
+    let mut removed = vec![
+        (expiring.id, PoolEventKind::Expired, None),
+        (
+            executed.id,
+            PoolEventKind::Included,
+            Some("executed in a candidate slot".to_string()),
+        ),
+    ];
+    removed.sort_unstable_by_key(|(id, _, _)| *id);
+    let events: Vec<(PoolObjectId, PoolEventKind, Option<String>)> =
+        std::iter::from_fn(|| receiver.try_recv().ok())
+            .map(|event| (event.object, event.kind, event.reason))
+            .collect();
+    assert_eq!(
+        events,
+        removed
+            .into_iter()
+            .map(|(id, kind, reason)| (PoolObjectId::Operation(id), kind, reason))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(pool.len(), 1);
+    assert!(pool.contains(&kept.id));
+}
+
+#[test]
+fn test_endorsement_pool_events() {
+    let config = PoolConfig {
+        broadcast_enabled: true,
+        ..Default::default()
+    };
+    let staker = KeyPair::generate(0).unwrap();
+    let staker_address = Address::from_public_key(&staker.get_public_key());
+    let endorsement = create_endorsement(&staker, 0, Slot::new(1, 0));
+
+    let pool_event_sender = DedupBroadcaster::new("test_pool_events".to_string(), 10, 0);
+    let mut receiver = pool_event_sender.subscribe();
+    let storage = Storage::create_root();
+    let mut pool = EndorsementPool::init(
+        config,
+        &storage,
+        pool_channels(staker_address, None, pool_event_sender),
+        staking_wallet(&staker),
+        test_metrics(),
+    );
+
+    let mut endorsement_storage = storage.clone_without_refs();
+    endorsement_storage.store_endorsements(vec![endorsement.clone()]);
+    pool.add_endorsements(endorsement_storage);
+    assert!(pool.contains(&endorsement.id));
+
+    // the slot of the endorsement becomes final
+    pool.notify_final_cs_periods(&vec![1; THREAD_COUNT as usize]);
+    assert!(!pool.contains(&endorsement.id));
+    assert_eq!(
+        received(&mut receiver),
+        vec![
+            (
+                PoolEventKind::Admitted,
+                PoolObjectId::Endorsement(endorsement.id)
+            ),
+            (
+                PoolEventKind::Expired,
+                PoolObjectId::Endorsement(endorsement.id)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_full_pool_event_channel_does_not_stall_the_pool() {
+    let config = PoolConfig {
+        broadcast_enabled: true,
+        ..Default::default()
+    };
+    let staker = KeyPair::generate(0).unwrap();
+    let staker_address = Address::from_public_key(&staker.get_public_key());
+    let ops: Vec<_> = (0..4)
+        .map(|_| {
+            OpGenerator::default()
+                .creator(staker.clone())
+                .expirery(10)
+                .generate()
+        })
+        .collect();
+
+    // a subscriber that never reads, on a channel holding two events
+    let pool_event_sender = DedupBroadcaster::new("test_pool_events".to_string(), 2, 0);
+    let mut receiver = pool_event_sender.subscribe();
+    let storage = Storage::create_root();
+    let mut pool = OperationPool::init(
+        config,
+        &storage,
+        pool_channels(staker_address, None, pool_event_sender.clone()),
+        staking_wallet(&staker),
+        test_metrics(),
+    );
+
+    let mut ops_storage = storage.clone_without_refs();
+    ops_storage.store_operations(ops.clone());
+    pool.add_operations(ops_storage);
+    assert_eq!(pool.len(), 4);
+
+    // the two oldest events were dropped and counted
+    assert_eq!(pool_event_sender.get_stats().lag_events, 2);
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Lagged(2)));
+    let mut ids: Vec<OperationId> = ops.iter().map(|op| op.id).collect();
+    ids.sort_unstable();
+    assert_eq!(
+        received(&mut receiver),
+        ids[2..]
+            .iter()
+            .map(|id| (PoolEventKind::Admitted, PoolObjectId::Operation(*id)))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_operation_pool_serialized_size_limit() {
+    let staker = KeyPair::generate(0).unwrap();
+    let staker_address = Address::from_public_key(&staker.get_public_key());
+    let ops: Vec<_> = (0..4)
+        .map(|_| {
+            OpGenerator::default()
+                .creator(staker.clone())
+                .expirery(10)
+                .generate()
+        })
+        .collect();
+    let op_size = ops[0].serialized_size();
+    assert!(ops.iter().all(|op| op.serialized_size() == op_size));
+    let mut ids: Vec<OperationId> = ops.iter().map(|op| op.id).collect();
+    ids.sort_unstable();
+
+    // room for two operations, and one more in-between refreshes
+    let config = PoolConfig {
+        broadcast_enabled: true,
+        max_operation_pool_bytes: 2 * op_size,
+        max_operation_pool_excess_bytes: op_size,
+        ..Default::default()
+    };
+    let pool_event_sender = DedupBroadcaster::new("test_pool_events".to_string(), 10, 0);
+    let mut receiver = pool_event_sender.subscribe();
+    let storage = Storage::create_root();
+    let mut pool = OperationPool::init(
+        config,
+        &storage,
+        pool_channels(staker_address, None, pool_event_sender),
+        staking_wallet(&staker),
+        test_metrics(),
+    );
+
+    // the operation exceeding the excess is dropped before being admitted
+    let mut ops_storage = storage.clone_without_refs();
+    ops_storage.store_operations(ops);
+    pool.add_operations(ops_storage);
+    assert_eq!(pool.len(), 3);
+    assert!(!pool.contains(&ids[3]));
+    assert_eq!(
+        received(&mut receiver),
+        ids[..3]
+            .iter()
+            .map(|id| (PoolEventKind::Admitted, PoolObjectId::Operation(*id)))
+            .collect::<Vec<_>>()
+    );
+
+    // the refresh evicts the worst operation to fit in the pool size
+    pool.refresh();
+    assert_eq!(pool.len(), 2);
+    assert_eq!(
+        pool.operation_infos()
+            .iter()
+            .map(|op_info| op_info.size)
+            .sum::<usize>(),
+        2 * op_size
+    );
+    assert_eq!(
+        received(&mut receiver),
+        vec![(PoolEventKind::Evicted, PoolObjectId::Operation(ids[2]))]
+    );
+}
@@ -0,0 +1,99 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! # Reference audit tests
+//! The pool is desynchronized from its storage through test-only hooks,
+//! then audited directly, without its worker.
+
+use super::{
+    selection_audit_tests::{create_operations, refreshed_pool},
+    tools::test_metrics,
+};
+use crate::operation_pool::OperationPool;
+use massa_models::{operation::OperationId, prehash::PreHashSet};
+use massa_pool_exports::PoolConfig;
+use massa_signature::KeyPair;
+use massa_storage::{RefAuditReport, Storage};
+
+fn id_set(ids: &[OperationId]) -> PreHashSet<OperationId> {
+    ids.iter().copied().collect()
+}
+
+/// Desynchronize the pool in both directions, returning the instance keeping `ids[0]` in storage:
+/// * `ids[0]` loses its reference but stays in storage
+/// * `ids[1]` loses its reference and leaves the storage
+/// * `ids[2]` leaves the pool but keeps its reference
+fn desync(pool: &mut OperationPool, ids: &[OperationId]) -> Storage {
+    let mut other = pool.storage.clone_without_refs();
+    other.claim_operation_refs(&id_set(&ids[..1]));
+    pool.storage.drop_operation_refs(&id_set(&ids[..2]));
+    pool.forget_operation_unchecked(&ids[2]);
+    other
+}
+
+#[test]
+fn test_ref_audit_clean_pool() {
+    let massa_metrics = test_metrics();
+    let mut pool = refreshed_pool(
+        PoolConfig::default(),
+        create_operations(&KeyPair::generate(0).unwrap(), &[10, 20, 30]),
+        None,
+        massa_metrics.clone(),
+    );
+    assert_eq!(pool.audit_refs(), RefAuditReport::default());
+    assert_eq!(massa_metrics.get_operations_pool_ref_discrepancies(), 0);
+}
+
+#[test]
+fn test_ref_audit_detects_discrepancies() {
+    let massa_metrics = test_metrics();
+    let ops = create_operations(&KeyPair::generate(0).unwrap(), &[10, 20, 30, 40]);
+    let ids: Vec<_> = ops.iter().map(|op| op.id).collect();
+    let mut pool = refreshed_pool(PoolConfig::default(), ops, None, massa_metrics.clone());
+    let _other = desync(&mut pool, &ids);
+
+    let expected = RefAuditReport {
+        missing_refs: id_set(&ids[..1]),
+        missing_objects: id_set(&ids[1..2]),
+        unclaimed_refs: id_set(&ids[2..3]),
+        ownerless_refs: PreHashSet::default(),
+    };
+    assert_eq!(pool.audit_refs(), expected);
+    assert_eq!(massa_metrics.get_operations_pool_ref_discrepancies(), 3);
+
+    // without self-heal, nothing is repaired
+    assert_eq!(pool.len(), 3);
+    assert_eq!(pool.audit_refs(), expected);
+    assert_eq!(massa_metrics.get_operations_pool_ref_discrepancies(), 6);
+}
+
+#[test]
+fn test_ref_audit_self_heal() {
+    let massa_metrics = test_metrics();
+    let config = PoolConfig {
+        operation_pool_ref_audit_self_heal: true,
+        ..Default::default()
+    };
+    let ops = create_operations(&KeyPair::generate(0).unwrap(), &[10, 20, 30, 40]);
+    let ids: Vec<_> = ops.iter().map(|op| op.id).collect();
+    let mut pool = refreshed_pool(config, ops, None, massa_metrics.clone());
+    let other = desync(&mut pool, &ids);
+    // a reference to an operation that was never stored
+    let unknown = create_operations(&KeyPair::generate(0).unwrap(), &[50])[0].id;
+    pool.storage.insert_operation_ref_unchecked(unknown);
+
+    let report = pool.audit_refs();
+    assert_eq!(report.ownerless_refs, id_set(&[unknown]));
+    assert_eq!(report.discrepancy_count(), 4);
+    assert_eq!(massa_metrics.get_operations_pool_ref_discrepancies(), 4);
+
+    // the missing reference is claimed again, the operation no longer stored leaves the pool,
+    // and the references the pool does not use are released
+    assert!(pool.audit_refs().is_empty());
+    assert_eq!(massa_metrics.get_operations_pool_ref_discrepancies(), 4);
+    assert_eq!(pool.len(), 2);
+    assert!(pool.contains(&ids[0]) && pool.contains(&ids[3]));
+    assert_eq!(pool.storage.get_op_refs(), &id_set(&[ids[0], ids[3]]));
+    drop(other);
+    let stored = pool.storage.read_operations();
+    assert!(stored.contains(&ids[0]));
+    assert!(!stored.contains(&ids[2]));
+}
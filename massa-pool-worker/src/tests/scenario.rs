@@ -11,6 +11,16 @@
 //! Function: [`test_get_operations_overflow`]
 //! Same as the previous test with a low limit of size to check if
 //! configurations are taken into account.
+//!
+//! # Get operations with excluded creators
+//! Function: [`test_get_operations_excluded_creators`]
+//! Same as the first test with operations from several creators, one of them
+//! being excluded from the block.
+//!
+//! # Get operations with a fee floor
+//! Function: [`test_get_operations_min_fee`]
+//! Same as the first test with operations paying different fees, the cheapest
+//! ones being left out of the block but kept in the pool.
 
 use std::collections::BTreeMap;
 use std::time::Duration;
@@ -123,7 +133,11 @@ fn test_simple_get_operations() {
     };
     // This is what we are testing....
     let block_operations_storage = pool_controller
-        .get_block_operations(&Slot::new(1, creator_thread))
+        .get_block_operations(
+            &Slot::new(1, creator_thread),
+            &Default::default(),
+            Amount::zero(),
+        )
         .1;
 
     pool_manager.stop();
@@ -247,13 +261,349 @@ fn test_get_operations_overflow() {
 
     // This is what we are testing....
     let block_operations_storage = pool_controller
-        .get_block_operations(&Slot::new(1, creator_thread))
+        .get_block_operations(
+            &Slot::new(1, creator_thread),
+            &Default::default(),
+            Amount::zero(),
+        )
         .1;
     pool_manager.stop();
 
     assert_eq!(block_operations_storage.get_op_refs().len(), MAX_OP_LEN);
 }
 
+/// # Test get block operations with excluded creators
+/// Operations of three creators of the same thread are in the pool, and the
+/// block is assembled while excluding the second creator.
+///
+/// ## Expected result
+/// The block only contains the operations of the two other creators, and the
+/// excluded operation is counted. Without exclusion, all the operations are
+/// selected again.
+#[test]
+fn test_get_operations_excluded_creators() {
+    let config = PoolConfig::default();
+
+    // three creators in the same thread
+    let first_keypair = KeyPair::generate(0).unwrap();
+    let creator_thread =
+        Address::from_public_key(&first_keypair.get_public_key()).get_thread(config.thread_count);
+    let mut keypairs = vec![first_keypair];
+    while keypairs.len() < 3 {
+        let keypair = KeyPair::generate(0).unwrap();
+        if Address::from_public_key(&keypair.get_public_key()).get_thread(config.thread_count)
+            == creator_thread
+        {
+            keypairs.push(keypair);
+        }
+    }
+    let creators: Vec<Address> = keypairs
+        .iter()
+        .map(|keypair| Address::from_public_key(&keypair.get_public_key()))
+        .collect();
+    let operations: Vec<_> = keypairs
+        .iter()
+        .map(|keypair| {
+            OpGenerator::default()
+                .creator(keypair.clone())
+                .expirery(1)
+                .generate()
+        })
+        .collect();
+
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    let owned_ops = operations.iter().map(|op| op.id).collect();
+    execution_controller.expect_clone_box().returning(move || {
+        Box::new(create_basic_get_block_operation_execution_mock(
+            3,
+            creators[0],
+            vec![(Some(Amount::from_raw(1)), Some(Amount::from_raw(1)))],
+            &owned_ops,
+        ))
+    });
+
+    // Provide the selector boilderplate
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+
+    let PoolTestBoilerPlate {
+        mut pool_manager,
+        mut pool_controller,
+        mut storage,
+    } = PoolTestBoilerPlate::pool_test(config, execution_controller, selector_controller);
+
+    storage.store_operations(operations.clone());
+    pool_controller.add_operations(storage);
+    // Allow some time for the pool to add the operations
+    std::thread::sleep(Duration::from_millis(100));
+
+    // This is what we are testing....
+    let excluded_creators =
+        PreHashSet::from_iter([Address::from_public_key(&keypairs[1].get_public_key())]);
+    let (op_ids, _, excluded_count) = pool_controller.get_block_operations(
+        &Slot::new(1, creator_thread),
+        &excluded_creators,
+        Amount::zero(),
+    );
+    assert_eq!(excluded_count, 1);
+    assert_eq!(
+        op_ids.into_iter().collect::<PreHashSet<OperationId>>(),
+        PreHashSet::from_iter([operations[0].id, operations[2].id])
+    );
+
+    let (op_ids, _, excluded_count) = pool_controller.get_block_operations(
+        &Slot::new(1, creator_thread),
+        &Default::default(),
+        Amount::zero(),
+    );
+    pool_manager.stop();
+
+    assert_eq!(excluded_count, 0);
+    assert_eq!(
+        op_ids.into_iter().collect::<PreHashSet<OperationId>>(),
+        operations.iter().map(|op| op.id).collect()
+    );
+}
+
+/// # Test get block operations with a fee floor
+/// Operations paying 1, 2 and 3 raw units of fee are in the pool, and the block
+/// is assembled with a fee floor of 2 raw units.
+///
+/// ## Expected result
+/// The block only contains the two operations paying at least the floor, and
+/// the cheapest operation is not counted as excluded by its creator. It stays
+/// in the pool and is selected again without the floor.
+#[test]
+fn test_get_operations_min_fee() {
+    let config = PoolConfig::default();
+    let keypair = KeyPair::generate(0).unwrap();
+    let creator_address = Address::from_public_key(&keypair.get_public_key());
+    let creator_thread = creator_address.get_thread(config.thread_count);
+    let operations: Vec<_> = (1..=3)
+        .map(|fee| {
+            OpGenerator::default()
+                .creator(keypair.clone())
+                .fee(Amount::from_raw(fee))
+                .expirery(1)
+                .generate()
+        })
+        .collect();
+
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    let owned_ops = operations.iter().map(|op| op.id).collect();
+    execution_controller.expect_clone_box().returning(move || {
+        Box::new(create_basic_get_block_operation_execution_mock(
+            3,
+            creator_address,
+            vec![(Some(Amount::from_raw(1)), Some(Amount::from_raw(1)))],
+            &owned_ops,
+        ))
+    });
+
+    // Provide the selector boilderplate
+    let selector_controller = {
+        let mut res = Box::new(MockSelectorController::new());
+        res.expect_clone_box().times(2).returning(|| {
+            let mut story = MockSelectorController::new();
+            story
+                .expect_get_available_selections_in_range()
+                .returning(|slot_range, opt_addrs| {
+                    let mut all_slots = BTreeMap::new();
+                    let addr = *opt_addrs
+                        .expect("No addresses filter given")
+                        .iter()
+                        .next()
+                        .expect("No addresses given");
+                    for i in 0..15 {
+                        for j in 0..32 {
+                            let s = Slot::new(i, j);
+                            if slot_range.contains(&s) {
+                                all_slots.insert(
+                                    s,
+                                    Selection {
+                                        producer: addr,
+                                        endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    Ok(all_slots)
+                });
+            Box::new(story)
+        });
+        res
+    };
+
+    let PoolTestBoilerPlate {
+        mut pool_manager,
+        mut pool_controller,
+        mut storage,
+    } = PoolTestBoilerPlate::pool_test(config, execution_controller, selector_controller);
+
+    storage.store_operations(operations.clone());
+    pool_controller.add_operations(storage);
+    // Allow some time for the pool to add the operations
+    std::thread::sleep(Duration::from_millis(100));
+
+    let (op_ids, _, excluded_count) = pool_controller.get_block_operations(
+        &Slot::new(1, creator_thread),
+        &Default::default(),
+        Amount::from_raw(2),
+    );
+    assert_eq!(excluded_count, 0);
+    assert_eq!(
+        op_ids.into_iter().collect::<PreHashSet<OperationId>>(),
+        PreHashSet::from_iter([operations[1].id, operations[2].id])
+    );
+    assert!(pool_controller.contains_operations(&[operations[0].id])[0]);
+
+    let (op_ids, _, _) = pool_controller.get_block_operations(
+        &Slot::new(1, creator_thread),
+        &Default::default(),
+        Amount::zero(),
+    );
+    pool_manager.stop();
+
+    assert_eq!(
+        op_ids.into_iter().collect::<PreHashSet<OperationId>>(),
+        operations.iter().map(|op| op.id).collect()
+    );
+}
+
+/// # Test expired operations
+/// Check that the operations which were not executed before the end of their
+/// validity are reported, and only once.
+///
+/// ## Initialization
+/// Insert two operations expiring at period 1 in the pool, the execution
+/// reports one of them as executed.
+/// Notify the pool of final periods past their expire period.
+///
+/// ## Expected result
+/// Only the operation that was not executed is broadcast and returned by
+/// `get_expired_operations`. Notifying new final periods doesn't broadcast it again.
+#[test]
+fn test_expired_operations() {
+    let config = PoolConfig {
+        broadcast_enabled: true,
+        ..Default::default()
+    };
+    let op_gen = OpGenerator::default().expirery(1);
+    let operations = create_some_operations(2, &op_gen);
+    let executed_op_id = operations[1].id;
+
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    execution_controller.expect_clone_box().returning(move || {
+        let mut story = MockExecutionController::new();
+        story.expect_get_ops_exec_status().returning(move |ops| {
+            ops.iter()
+                .map(|id| {
+                    if *id == executed_op_id {
+                        (Some(true), Some(true))
+                    } else {
+                        (None, None)
+                    }
+                })
+                .collect()
+        });
+        story
+            .expect_get_final_and_candidate_balance()
+            .returning(|addrs| {
+                vec![
+                    (
+                        Some(Amount::const_init(1_000_000_000, 0)),
+                        Some(Amount::const_init(1_000_000_000, 0)),
+                    );
+                    addrs.len()
+                ]
+            });
+        Box::new(story)
+    });
+    let mut selector_controller = Box::new(MockSelectorController::new());
+    selector_controller.expect_clone_box().returning(|| {
+        let mut story = MockSelectorController::new();
+        story
+            .expect_get_available_selections_in_range()
+            .returning(|_, _| Ok(BTreeMap::new()));
+        Box::new(story)
+    });
+
+    let (expired_operation_sender, mut expired_operation_receiver) =
+        tokio::sync::broadcast::channel(10);
+    let PoolTestBoilerPlate {
+        mut pool_manager,
+        mut pool_controller,
+        mut storage,
+    } = PoolTestBoilerPlate::pool_test_with_expired_operation_sender(
+        config,
+        execution_controller,
+        selector_controller,
+        expired_operation_sender,
+    );
+
+    storage.store_operations(operations.clone());
+    pool_controller.add_operations(storage);
+    // Allow some time for the pool to add the operations
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(pool_controller.get_expired_operations(&[operations[0].id])[0].is_none());
+
+    // the expire period is final but may not be executed yet
+    pool_controller.notify_final_cs_periods(&vec![1; config.thread_count as usize]);
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(expired_operation_receiver.try_recv().is_err());
+
+    pool_controller.notify_final_cs_periods(&vec![2; config.thread_count as usize]);
+    std::thread::sleep(Duration::from_millis(100));
+    let expired = pool_controller.get_expired_operations(&[operations[0].id, executed_op_id]);
+    assert_eq!(
+        expired[0].as_ref().map(|expired| expired.operation.id),
+        Some(operations[0].id)
+    );
+    assert!(expired[1].is_none());
+    assert_eq!(
+        expired_operation_receiver.try_recv().unwrap().operation.id,
+        operations[0].id
+    );
+    assert!(expired_operation_receiver.try_recv().is_err());
+
+    pool_controller.notify_final_cs_periods(&vec![3; config.thread_count as usize]);
+    std::thread::sleep(Duration::from_millis(100));
+    pool_manager.stop();
+    assert!(expired_operation_receiver.try_recv().is_err());
+}
+
 //TODO: Readd
 // #[test]
 // fn test_block_header_denunciation_creation() {
@@ -0,0 +1,233 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+//! # Selection audit tests
+//! The pool is refreshed and queried directly, without its worker, so that every selection
+//! goes through the same pool state.
+
+use super::tools::{test_metrics, OpGenerator};
+use crate::operation_pool::OperationPool;
+use massa_channel::broadcast::DedupBroadcaster;
+use massa_execution_exports::MockExecutionController;
+use massa_metrics::MassaMetrics;
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    config::{ENDORSEMENT_COUNT, THREAD_COUNT},
+    operation::{OperationId, SecureShareOperation},
+    prehash::PreHashMap,
+    slot::Slot,
+};
+use massa_pool_exports::{PoolBroadcasts, PoolChannels, PoolConfig, SelectionOutcome};
+use massa_pos_exports::{MockSelectorController, Selection};
+use massa_signature::KeyPair;
+use massa_storage::Storage;
+use massa_wallet::test_exports::create_test_wallet;
+use parking_lot::RwLock;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Refreshed pool containing `ops`, with a staker drawn for every slot.
+/// `executed` is reported as executed in a candidate slot.
+pub(super) fn refreshed_pool(
+    config: PoolConfig,
+    ops: Vec<SecureShareOperation>,
+    executed: Option<OperationId>,
+    massa_metrics: MassaMetrics,
+) -> OperationPool {
+    let mut execution_controller = Box::new(MockExecutionController::new());
+    execution_controller
+        .expect_get_ops_exec_status()
+        .returning(move |ops| {
+            ops.iter()
+                .map(|id| match executed {
+                    Some(executed) if executed == *id => (Some(true), None),
+                    _ => (None, None),
+                })
+                .collect()
+        });
+    execution_controller
+        .expect_get_final_and_candidate_balance()
+        .returning(|addrs| {
+            vec![
+                (
+                    Some(Amount::const_init(1_000_000_000, 0)),
+                    Some(Amount::const_init(1_000_000_000, 0)),
+                );
+                addrs.len()
+            ]
+        });
+    let mut selector = Box::new(MockSelectorController::new());
+    selector
+        .expect_get_available_selections_in_range()
+        .returning(|slot_range, opt_addrs| {
+            let addr = *opt_addrs
+                .expect("No addresses filter given")
+                .iter()
+                .next()
+                .expect("No addresses given");
+            let mut all_slots = BTreeMap::new();
+            for period in 0..15 {
+                for thread in 0..THREAD_COUNT {
+                    let slot = Slot::new(period, thread);
+                    if slot_range.contains(&slot) {
+                        all_slots.insert(
+                            slot,
+                            Selection {
+                                producer: addr,
+                                endorsements: vec![addr; ENDORSEMENT_COUNT as usize],
+                            },
+                        );
+                    }
+                }
+            }
+            Ok(all_slots)
+        });
+
+    let keypair = KeyPair::generate(0).unwrap();
+    let mut addresses = PreHashMap::default();
+    addresses.insert(Address::from_public_key(&keypair.get_public_key()), keypair);
+    let wallet = Arc::new(RwLock::new(create_test_wallet(Some(addresses))));
+    let storage = Storage::create_root();
+    let mut pool = OperationPool::init(
+        config,
+        &storage,
+        PoolChannels {
+            execution_controller,
+            selector,
+            broadcasts: PoolBroadcasts {
+                endorsement_sender: DedupBroadcaster::new("test_endorsements".to_string(), 10, 10),
+                operation_sender: DedupBroadcaster::new("test_operations".to_string(), 10, 10),
+                expired_operation_sender: tokio::sync::broadcast::channel(10).0,
+                pool_event_sender: DedupBroadcaster::new("test_pool_events".to_string(), 10, 0),
+            },
+        },
+        wallet,
+        massa_metrics,
+    );
+    let mut ops_storage = storage.clone_without_refs();
+    ops_storage.store_operations(ops);
+    pool.add_operations(ops_storage);
+    pool.refresh();
+    pool
+}
+
+/// Operations of the same creator, valid until period 10, one per fee
+pub(super) fn create_operations(creator: &KeyPair, fees: &[u64]) -> Vec<SecureShareOperation> {
+    fees.iter()
+        .map(|fee| {
+            OpGenerator::default()
+                .creator(creator.clone())
+                .expirery(10)
+                .fee(Amount::from_raw(*fee))
+                .generate()
+        })
+        .collect()
+}
+
+#[test]
+fn test_selection_audit_is_deterministic() {
+    let config = PoolConfig {
+        max_operations_per_block: 3,
+        max_selection_audit_slots: 2,
+        ..Default::default()
+    };
+    let creator = KeyPair::generate(0).unwrap();
+    let thread = Address::from_public_key(&creator.get_public_key()).get_thread(THREAD_COUNT);
+    let ops = create_operations(&creator, &[10, 20, 20, 30, 40, 50]);
+    // the operation paying the most fees was already executed
+    let pool = refreshed_pool(config, ops.clone(), Some(ops[5].id), test_metrics());
+
+    let slot = Slot::new(1, thread);
+    let (op_ids, _, _) = pool.get_block_operations(&slot, &Default::default(), Amount::zero());
+    let audit = pool.get_selection_audit(&slot).unwrap();
+    let (op_ids_again, _, _) =
+        pool.get_block_operations(&slot, &Default::default(), Amount::zero());
+    assert_eq!(op_ids, op_ids_again);
+    assert_eq!(pool.get_selection_audit(&slot).unwrap(), audit);
+
+    // the operations with the same fee are sorted by id
+    let first_tie = ops[1].id.min(ops[2].id);
+    assert_eq!(op_ids, vec![ops[4].id, ops[3].id, first_tie]);
+    assert_eq!(audit.selected(), op_ids);
+    assert_eq!(audit.cut_point, Some(3));
+    assert_eq!(audit.other_thread_count, 0);
+    assert_eq!(audit.refresh_filters.executed, 1);
+    assert!(audit
+        .candidates
+        .iter()
+        .all(|candidate| candidate.priority.is_some()
+            && candidate.outcome == SelectionOutcome::Selected));
+
+    // only the audits of the last slots are kept
+    pool.get_block_operations(&Slot::new(2, thread), &Default::default(), Amount::zero());
+    pool.get_block_operations(&Slot::new(3, thread), &Default::default(), Amount::zero());
+    assert!(pool.get_selection_audit(&slot).is_none());
+    assert!(pool.get_selection_audit(&Slot::new(2, thread)).is_some());
+    assert!(pool.get_selection_audit(&Slot::new(3, thread)).is_some());
+}
+
+#[test]
+fn test_selection_audit_pinpoints_fee_change() {
+    let config = PoolConfig {
+        max_selection_audit_slots: 1,
+        ..Default::default()
+    };
+    let creator = KeyPair::generate(0).unwrap();
+    let thread = Address::from_public_key(&creator.get_public_key()).get_thread(THREAD_COUNT);
+    let slot = Slot::new(1, thread);
+    let ops = create_operations(&creator, &[10, 20, 30, 40, 50]);
+
+    // same pool, except that the operation paying the least fees pays more than the third one
+    let mut perturbed_ops = ops.clone();
+    perturbed_ops[0] = create_operations(&creator, &[35]).remove(0);
+
+    let pool = refreshed_pool(config, ops, None, test_metrics());
+    pool.get_block_operations(&slot, &Default::default(), Amount::zero());
+    let audit = pool.get_selection_audit(&slot).unwrap();
+    let perturbed_pool = refreshed_pool(config, perturbed_ops.clone(), None, test_metrics());
+    perturbed_pool.get_block_operations(&slot, &Default::default(), Amount::zero());
+    let perturbed_audit = perturbed_pool.get_selection_audit(&slot).unwrap();
+
+    assert_eq!(audit.cut_point, None);
+    assert_eq!(audit.first_divergence(&audit), None);
+    let divergence = audit.first_divergence(&perturbed_audit).unwrap();
+    assert_eq!(divergence, 2);
+    assert_eq!(
+        audit.candidates[..divergence],
+        perturbed_audit.candidates[..divergence]
+    );
+    let moved = &perturbed_audit.candidates[divergence];
+    assert_eq!(moved.id, perturbed_ops[0].id);
+    assert_eq!(moved.fee, Amount::from_raw(35));
+    assert!(
+        moved.priority.as_ref().unwrap().score
+            > audit.candidates[divergence]
+                .priority
+                .as_ref()
+                .unwrap()
+                .score
+    );
+}
+
+#[test]
+fn test_below_fee_floor_metric() {
+    let creator = KeyPair::generate(0).unwrap();
+    let thread = Address::from_public_key(&creator.get_public_key()).get_thread(THREAD_COUNT);
+    let slot = Slot::new(1, thread);
+    let massa_metrics = test_metrics();
+    let pool = refreshed_pool(
+        PoolConfig::default(),
+        create_operations(&creator, &[10, 20, 30]),
+        None,
+        massa_metrics.clone(),
+    );
+
+    pool.get_block_operations(&slot, &Default::default(), Amount::zero());
+    assert_eq!(massa_metrics.get_operations_pool_below_fee_floor(), 0);
+
+    // the two operations paying less than the floor are counted at each selection
+    let (op_ids, _, _) =
+        pool.get_block_operations(&slot, &Default::default(), Amount::from_raw(25));
+    assert_eq!(op_ids.len(), 1);
+    assert_eq!(massa_metrics.get_operations_pool_below_fee_floor(), 2);
+    pool.get_block_operations(&slot, &Default::default(), Amount::from_raw(25));
+    assert_eq!(massa_metrics.get_operations_pool_below_fee_floor(), 4);
+}
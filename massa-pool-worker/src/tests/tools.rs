@@ -1,12 +1,14 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use crate::start_pool_controller;
 use crossbeam_channel as _;
+use massa_channel::broadcast::DedupBroadcaster;
 use massa_execution_exports::MockExecutionController;
 use massa_hash::Hash;
-use massa_models::config::CHAINID;
+use massa_metrics::MassaMetrics;
+use massa_models::config::{CHAINID, THREAD_COUNT};
 use massa_models::{
     address::Address,
     amount::Amount,
@@ -17,13 +19,14 @@ use massa_models::{
     secure_share::SecureShareContent,
     slot::Slot,
 };
-use massa_pool_exports::{PoolBroadcasts, PoolChannels, PoolConfig, PoolController, PoolManager};
+use massa_pool_exports::{
+    ExpiredOperation, PoolBroadcasts, PoolChannels, PoolConfig, PoolController, PoolManager,
+};
 use massa_pos_exports::MockSelectorController as AutoMockSelectorController;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
 use massa_wallet::test_exports::create_test_wallet;
 use parking_lot::RwLock;
-use tokio::sync::broadcast;
 
 #[derive(Default)]
 pub(crate) struct OpGenerator {
@@ -104,6 +107,21 @@ impl PoolTestBoilerPlate {
         cfg: PoolConfig,
         execution_story: Box<MockExecutionController>,
         selector_story: Box<AutoMockSelectorController>,
+    ) -> Self {
+        Self::pool_test_with_expired_operation_sender(
+            cfg,
+            execution_story,
+            selector_story,
+            tokio::sync::broadcast::channel(5000).0,
+        )
+    }
+
+    /// Same as `pool_test`, broadcasting the expired operations to `expired_operation_sender`
+    pub fn pool_test_with_expired_operation_sender(
+        cfg: PoolConfig,
+        execution_story: Box<MockExecutionController>,
+        selector_story: Box<AutoMockSelectorController>,
+        expired_operation_sender: tokio::sync::broadcast::Sender<ExpiredOperation>,
     ) -> Self {
         let storage: Storage = Storage::create_root();
         let keypair = KeyPair::generate(0).unwrap();
@@ -111,8 +129,8 @@ impl PoolTestBoilerPlate {
         let mut addresses = PreHashMap::default();
         addresses.insert(address, keypair);
         let wallet = Arc::new(RwLock::new(create_test_wallet(Some(addresses))));
-        let endorsement_sender = broadcast::channel(2000).0;
-        let operation_sender = broadcast::channel(5000).0;
+        let endorsement_sender = DedupBroadcaster::new("test_endorsements".to_string(), 2000, 2000);
+        let operation_sender = DedupBroadcaster::new("test_operations".to_string(), 5000, 5000);
         let (pool_manager, pool_controller) = start_pool_controller(
             cfg,
             &storage,
@@ -121,10 +139,17 @@ impl PoolTestBoilerPlate {
                 broadcasts: PoolBroadcasts {
                     endorsement_sender,
                     operation_sender,
+                    expired_operation_sender,
+                    pool_event_sender: DedupBroadcaster::new(
+                        "test_pool_events".to_string(),
+                        5000,
+                        0,
+                    ),
                 },
                 selector: selector_story,
             },
             wallet,
+            test_metrics(),
         );
 
         Self {
@@ -144,8 +169,8 @@ pub fn pool_test<F>(
 ) where
     F: FnOnce(Box<dyn PoolController>, Storage),
 {
-    let endorsement_sender = broadcast::channel(2000).0;
-    let operation_sender = broadcast::channel(5000).0;
+    let endorsement_sender = DedupBroadcaster::new("test_endorsements".to_string(), 2000, 2000);
+    let operation_sender = DedupBroadcaster::new("test_operations".to_string(), 5000, 5000);
     let storage = Storage::create_root();
     let keypair = KeyPair::generate(0).unwrap();
     let address = Address::from_public_key(&keypair.get_public_key());
@@ -163,15 +188,29 @@ pub fn pool_test<F>(
             broadcasts: PoolBroadcasts {
                 endorsement_sender,
                 operation_sender,
+                expired_operation_sender: tokio::sync::broadcast::channel(5000).0,
+                pool_event_sender: DedupBroadcaster::new("test_pool_events".to_string(), 5000, 0),
             },
             selector,
         },
         wallet,
+        test_metrics(),
     );
     test(pool_controller, storage);
     pool_manager.stop();
 }
 
+/// Disabled metrics for use in pool tests.
+pub fn test_metrics() -> MassaMetrics {
+    MassaMetrics::new(
+        false,
+        "0.0.0.0:9898".parse().unwrap(),
+        THREAD_COUNT,
+        Duration::from_secs(5),
+    )
+    .0
+}
+
 /// Creates an endorsement for use in pool tests.
 pub fn create_endorsement(
     sender_keypair: &KeyPair,
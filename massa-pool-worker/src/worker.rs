@@ -6,6 +6,7 @@ use crate::controller_impl::{Command, PoolManagerImpl};
 use crate::denunciation_pool::DenunciationPool;
 use crate::operation_pool::OperationPool;
 use crate::{controller_impl::PoolControllerImpl, endorsement_pool::EndorsementPool};
+use massa_metrics::MassaMetrics;
 use massa_pool_exports::PoolConfig;
 use massa_pool_exports::{PoolChannels, PoolController, PoolManager};
 use massa_storage::Storage;
@@ -101,8 +102,17 @@ impl OperationPoolThread {
     fn run(self, config: PoolConfig) {
         let mut start_time = Instant::now();
         let tick = config.operation_pool_refresh_interval.to_duration();
+        // the reference audit runs when no command or refresh is due
+        let audit_tick = (config.operation_pool_ref_audit_interval.as_millis() > 0)
+            .then(|| config.operation_pool_ref_audit_interval.to_duration());
+        let mut last_audit = Instant::now();
         loop {
-            let duration = (start_time + tick).saturating_duration_since(Instant::now());
+            let refresh_at = start_time + tick;
+            let next_at = match audit_tick {
+                Some(audit_tick) => refresh_at.min(last_audit + audit_tick),
+                None => refresh_at,
+            };
+            let duration = next_at.saturating_duration_since(Instant::now());
             if !duration.is_zero() {
                 match self.receiver.recv_timeout(duration) {
                     Err(RecvTimeoutError::Disconnected) | Ok(Command::Stop) => break,
@@ -119,9 +129,12 @@ impl OperationPoolThread {
                     }
                     Err(RecvTimeoutError::Timeout) => {}
                 };
-            } else {
+            } else if refresh_at <= Instant::now() {
                 self.operation_pool.write().refresh();
                 start_time = Instant::now();
+            } else {
+                self.operation_pool.write().audit_refs();
+                last_audit = Instant::now();
             }
         }
     }
@@ -187,6 +200,7 @@ pub fn start_pool_controller(
     storage: &Storage,
     channels: PoolChannels,
     wallet: Arc<RwLock<Wallet>>,
+    massa_metrics: MassaMetrics,
 ) -> (Box<dyn PoolManager>, Box<dyn PoolController>) {
     let (operations_input_sender, operations_input_receiver) =
         sync_channel(config.operations_channel_size);
@@ -199,16 +213,18 @@ pub fn start_pool_controller(
         storage,
         channels.clone(),
         wallet.clone(),
+        massa_metrics.clone(),
     )));
     let endorsement_pool = Arc::new(RwLock::new(EndorsementPool::init(
         config,
         storage,
         channels.clone(),
         wallet,
+        massa_metrics,
     )));
     let denunciation_pool = Arc::new(RwLock::new(DenunciationPool::init(config, channels)));
     let controller = PoolControllerImpl {
-        _config: config,
+        config,
         operation_pool: operation_pool.clone(),
         endorsement_pool: endorsement_pool.clone(),
         denunciation_pool: denunciation_pool.clone(),
@@ -216,6 +232,7 @@ pub fn start_pool_controller(
         endorsements_input_sender: endorsements_input_sender.clone(),
         denunciations_input_sender: denunciations_input_sender.clone(),
         last_cs_final_periods: vec![0u64; usize::from(config.thread_count)],
+        snapshot_sequence: Default::default(),
     };
 
     let operations_thread_handle =
@@ -12,7 +12,11 @@ use massa_db_exports::{
 };
 use massa_hash::{Hash, HashXof, HASH_XOF_SIZE_BYTES};
 use massa_models::amount::Amount;
-use massa_models::{address::Address, prehash::PreHashMap, slot::Slot};
+use massa_models::{
+    address::Address,
+    prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
+};
 use massa_serialization::{DeserializeError, Deserializer, Serializer, U64VarIntSerializer};
 use nom::AsBytes;
 use std::collections::VecDeque;
@@ -768,6 +772,39 @@ impl PoSFinalState {
         deferred_credits
     }
 
+    /// Retrieves the deferred credits in a slot range, sorted by slot then address.
+    /// If `addresses` is given, only the credits of these addresses are returned.
+    pub fn get_deferred_credits_by_slot<R>(
+        &self,
+        range: R,
+        addresses: Option<&PreHashSet<Address>>,
+    ) -> BTreeMap<Slot, Vec<(Address, Amount)>>
+    where
+        R: RangeBounds<Slot>,
+    {
+        // a single address can be filtered while iterating
+        let addr_filter = addresses
+            .filter(|addresses| addresses.len() == 1)
+            .and_then(|addresses| addresses.iter().next());
+        self.get_deferred_credits_range(range, addr_filter)
+            .credits
+            .into_iter()
+            .filter_map(|(slot, credits)| {
+                let mut credits: Vec<(Address, Amount)> = credits
+                    .into_iter()
+                    .filter(|(address, _)| {
+                        addresses.map_or(true, |addresses| addresses.contains(address))
+                    })
+                    .collect();
+                if credits.is_empty() {
+                    return None;
+                }
+                credits.sort_unstable_by_key(|(address, _)| *address);
+                Some((slot, credits))
+            })
+            .collect()
+    }
+
     /// Gets the index of a cycle in history
     pub fn get_cycle_index(&self, cycle: u64) -> Option<usize> {
         let first_cycle = match self.cycle_history_cache.front() {
@@ -1729,6 +1766,140 @@ mod tests {
         assert_eq!(credits_range_4.credits, expected_credits_range_4);
     }
 
+    // This test checks that the deferred credits of a slot range are filtered by address and sorted
+    #[test]
+    fn test_deferred_credits_by_slot() {
+        let initial_deferred_credits_file = tempfile::NamedTempFile::new()
+            .expect("could not create temporary initial deferred credits file");
+        let deferred_credits_file_contents = "{
+            \"AU12pAcVUzsgUBJHaYSAtDKVTYnUT9NorBDjoDovMfAFTLFa16MNa\": [
+                {\"slot\": {\"period\": 1, \"thread\": 0}, \"amount\": \"1\"},
+                {\"slot\": {\"period\": 1, \"thread\": 1}, \"amount\": \"2\"},
+                {\"slot\": {\"period\": 2, \"thread\": 0}, \"amount\": \"3\"},
+                {\"slot\": {\"period\": 4, \"thread\": 1}, \"amount\": \"4\"}
+            ],
+            \"AU1wN8rn4SkwYSTDF3dHFY4U28KtsqKL1NnEjDZhHnHEy6cEQm53\": [
+                {\"slot\": {\"period\": 1, \"thread\": 0}, \"amount\": \"5\"},
+                {\"slot\": {\"period\": 3, \"thread\": 0}, \"amount\": \"6\"}
+            ],
+            \"AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x\": [
+                {\"slot\": {\"period\": 2, \"thread\": 0}, \"amount\": \"7\"},
+                {\"slot\": {\"period\": 4, \"thread\": 1}, \"amount\": \"8\"}
+            ]
+        }";
+        std::fs::write(
+            initial_deferred_credits_file.path(),
+            deferred_credits_file_contents.as_bytes(),
+        )
+        .expect("failed writing initial deferred credits file");
+        let pos_config = PoSConfig {
+            periods_per_cycle: 2,
+            thread_count: 2,
+            cycle_history_length: POS_SAVED_CYCLES,
+            max_rolls_length: MAX_ROLLS_COUNT_LENGTH,
+            max_production_stats_length: MAX_PRODUCTION_STATS_LENGTH,
+            max_credit_length: MAX_DEFERRED_CREDITS_LENGTH,
+            initial_deferred_credits_path: Some(initial_deferred_credits_file.path().to_path_buf()),
+        };
+        let tempdir = tempfile::TempDir::new().expect("cannot create temp directory");
+        let db_config = MassaDBConfig {
+            path: tempdir.path().to_path_buf(),
+            max_history_length: 10,
+            max_final_state_elements_size: 100_000,
+            max_versioning_elements_size: 100_000,
+            thread_count: 2,
+            max_ledger_backups: 10,
+        };
+        let db = Arc::new(RwLock::new(
+            Box::new(MassaDB::new(db_config)) as Box<(dyn MassaDBController + 'static)>
+        ));
+        let init_seed = Hash::compute_from(b"");
+        let deferred_credits_deserializer =
+            DeferredCreditsDeserializer::new(pos_config.thread_count, pos_config.max_credit_length);
+        let cycle_info_deserializer = CycleHistoryDeserializer::new(
+            pos_config.cycle_history_length as u64,
+            pos_config.max_rolls_length,
+            pos_config.max_production_stats_length,
+        );
+        let mut pos_state = PoSFinalState {
+            config: pos_config,
+            db: db.clone(),
+            cycle_history_cache: Default::default(),
+            rng_seed_cache: None,
+            selector: Box::new(MockSelectorController::new()),
+            initial_rolls: Default::default(),
+            initial_seeds: vec![Hash::compute_from(init_seed.to_bytes()), init_seed],
+            deferred_credits_serializer: DeferredCreditsSerializer::new(),
+            deferred_credits_deserializer,
+            cycle_info_serializer: CycleHistorySerializer::new(),
+            cycle_info_deserializer,
+        };
+        let mut batch = DBBatch::new();
+        pos_state
+            .load_initial_deferred_credits(&mut batch)
+            .expect("error while loading initial deferred credits");
+        db.write().write_batch(batch, DBBatch::new(), None);
+
+        let addr1 =
+            Address::from_str("AU12pAcVUzsgUBJHaYSAtDKVTYnUT9NorBDjoDovMfAFTLFa16MNa").unwrap();
+        let addr2 =
+            Address::from_str("AU1wN8rn4SkwYSTDF3dHFY4U28KtsqKL1NnEjDZhHnHEy6cEQm53").unwrap();
+        let addr3 =
+            Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap();
+        let credits = |credits: Vec<(Address, &str)>| {
+            let mut credits: Vec<(Address, Amount)> = credits
+                .into_iter()
+                .map(|(address, amount)| (address, Amount::from_str(amount).unwrap()))
+                .collect();
+            credits.sort_by_key(|(address, _)| *address);
+            credits
+        };
+
+        // the range bounds are included, the credits of a slot are sorted by address
+        let range = pos_state.get_deferred_credits_by_slot(Slot::new(1, 1)..=Slot::new(3, 0), None);
+        let expected: BTreeMap<Slot, Vec<(Address, Amount)>> = vec![
+            (Slot::new(1, 1), credits(vec![(addr1, "2")])),
+            (Slot::new(2, 0), credits(vec![(addr3, "7"), (addr1, "3")])),
+            (Slot::new(3, 0), credits(vec![(addr2, "6")])),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(range, expected);
+        for slot_credits in range.values() {
+            assert!(slot_credits.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        }
+
+        // the slots without credits of the filtered addresses are omitted
+        let filter: PreHashSet<Address> = vec![addr2, addr3].into_iter().collect();
+        let range = pos_state.get_deferred_credits_by_slot(.., Some(&filter));
+        let expected: BTreeMap<Slot, Vec<(Address, Amount)>> = vec![
+            (Slot::new(1, 0), credits(vec![(addr2, "5")])),
+            (Slot::new(2, 0), credits(vec![(addr3, "7")])),
+            (Slot::new(3, 0), credits(vec![(addr2, "6")])),
+            (Slot::new(4, 1), credits(vec![(addr3, "8")])),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(range, expected);
+
+        // a single address is filtered as well
+        let filter: PreHashSet<Address> = vec![addr1].into_iter().collect();
+        let range = pos_state
+            .get_deferred_credits_by_slot(Slot::new(2, 0)..=Slot::new(4, 1), Some(&filter));
+        let expected: BTreeMap<Slot, Vec<(Address, Amount)>> = vec![
+            (Slot::new(2, 0), credits(vec![(addr1, "3")])),
+            (Slot::new(4, 1), credits(vec![(addr1, "4")])),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(range, expected);
+
+        // no credit out of the seeded slots
+        assert!(pos_state
+            .get_deferred_credits_by_slot(Slot::new(5, 0).., None)
+            .is_empty());
+    }
+
     // This test checks that the initial rolls are loaded correctly
     #[test]
     fn test_initial_rolls_loading() {
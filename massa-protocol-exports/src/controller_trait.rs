@@ -8,7 +8,7 @@ use crate::BootstrapPeers;
 
 use crate::PeerId;
 use massa_models::prehash::{PreHashMap, PreHashSet};
-use massa_models::stats::NetworkStats;
+use massa_models::stats::{NetworkStats, NetworkTrafficStats};
 use massa_models::{block_header::SecuredHeader, block_id::BlockId};
 use massa_storage::Storage;
 use peernet::peer::PeerConnectionType;
@@ -72,6 +72,10 @@ pub trait ProtocolController: Send + Sync {
         ProtocolError,
     >;
 
+    /// Get the connection counts and the traffic exchanged with peers.
+    /// The traffic counters are cumulative since the node started.
+    fn get_network_stats(&self) -> Result<NetworkTrafficStats, ProtocolError>;
+
     /// Get a list of peers to be sent to someone that bootstrap to us
     fn get_bootstrap_peers(&self) -> Result<BootstrapPeers, ProtocolError>;
 
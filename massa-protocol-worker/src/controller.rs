@@ -5,7 +5,7 @@ use massa_models::{
     block_header::SecuredHeader,
     block_id::BlockId,
     prehash::{PreHashMap, PreHashSet},
-    stats::NetworkStats,
+    stats::{NetworkStats, NetworkTrafficStats},
 };
 use massa_protocol_exports::{BootstrapPeers, PeerId, ProtocolController, ProtocolError};
 use massa_storage::Storage;
@@ -22,6 +22,7 @@ use crate::{
         operation_handler::commands_propagation::OperationHandlerPropagationCommand,
         peer_handler::models::PeerManagementCmd,
     },
+    traffic,
 };
 
 #[derive(Clone)]
@@ -156,6 +157,15 @@ impl ProtocolController for ProtocolControllerImpl {
             .map_err(|_| ProtocolError::ChannelError("get_stats command receive error".into()))
     }
 
+    fn get_network_stats(&self) -> Result<NetworkTrafficStats, ProtocolError> {
+        let (stats, _) = self.get_stats()?;
+        Ok(traffic::get_traffic_stats(
+            stats.in_connection_count,
+            stats.out_connection_count,
+            stats.banned_peer_count,
+        ))
+    }
+
     fn ban_peers(&self, peer_ids: Vec<PeerId>) -> Result<(), ProtocolError> {
         self.sender_peer_management_thread
             .as_ref()
@@ -1,5 +1,6 @@
 use massa_models::{
-    block_header::{BlockHeader, BlockHeaderDeserializer, SecuredHeader},
+    block_header::{BlockHeader, SecuredHeader},
+    block_header_versioned::{BlockHeaderFormats, VersionedBlockHeaderDeserializer},
     block_id::{BlockId, BlockIdDeserializer, BlockIdSerializer},
     operation::{
         OperationId, OperationIdSerializer, OperationIdsDeserializer, OperationsDeserializer,
@@ -14,7 +15,7 @@ use nom::{
     IResult, Parser,
 };
 use num_enum::{IntoPrimitive, TryFromPrimitive};
-use std::ops::Bound::Included;
+use std::{ops::Bound::Included, sync::Arc};
 
 /// Request block data
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -193,7 +194,8 @@ impl Serializer<BlockMessage> for BlockMessageSerializer {
 
 pub struct BlockMessageDeserializer {
     id_deserializer: U64VarIntDeserializer,
-    block_header_deserializer: SecureShareDeserializer<BlockHeader, BlockHeaderDeserializer>,
+    block_header_deserializer:
+        SecureShareDeserializer<BlockHeader, VersionedBlockHeaderDeserializer>,
     block_id_deserializer: BlockIdDeserializer,
     operation_ids_deserializer: OperationIdsDeserializer,
     operations_deserializer: OperationsDeserializer,
@@ -219,13 +221,13 @@ impl BlockMessageDeserializer {
         Self {
             id_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
             block_header_deserializer: SecureShareDeserializer::new(
-                BlockHeaderDeserializer::new(
+                VersionedBlockHeaderDeserializer::new(Arc::new(BlockHeaderFormats::new(
                     args.thread_count,
                     args.endorsement_count,
                     args.max_denunciations_in_block_header,
                     args.last_start_period,
                     args.chain_id,
-                ),
+                ))),
                 args.chain_id,
             ),
             block_id_deserializer: BlockIdDeserializer::new(),
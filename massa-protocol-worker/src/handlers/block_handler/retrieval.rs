@@ -1206,18 +1206,21 @@ impl RetrievalThread {
         debug!("Fully gathered block {}", block_id);
 
         // Gather all the elements needed to create the block. We must have it all by now.
+        // The block stays in the wishlist until the storage admits it.
         let wishlist_info = self
             .block_wishlist
-            .remove(block_id)
+            .get(block_id)
             .expect("block presence in wishlist should have been checked before");
 
         // Create the block
         let block = Block {
             header: wishlist_info
                 .header
+                .clone()
                 .expect("header presence in wishlist should have been checked above"),
             operations: wishlist_info
                 .operation_ids
+                .clone()
                 .expect("operation_ids presence in wishlist should have been checked above"),
         };
 
@@ -1238,7 +1241,7 @@ impl RetrievalThread {
 
         // Get block storage.
         // It should contain only the operations.
-        let mut block_storage = wishlist_info.storage;
+        let mut block_storage = wishlist_info.storage.clone();
 
         // Add endorsements to storage and claim ref
         // TODO change this if we make endorsements separate from block header
@@ -1248,7 +1251,12 @@ impl RetrievalThread {
         let slot = signed_block.content.header.content.slot;
 
         // add block to storage and claim ref
-        block_storage.store_block(signed_block);
+        if let Err(err) = block_storage.try_store_block(signed_block, false) {
+            // kept in the wishlist: it is gathered again at the next ask round
+            debug!("Putting block {} on hold: {}", block_id, err);
+            return;
+        }
+        self.block_wishlist.remove(block_id);
 
         // Send to consensus
         self.consensus_controller
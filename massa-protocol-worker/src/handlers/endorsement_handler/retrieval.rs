@@ -5,7 +5,7 @@ use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{
-    endorsement::SecureShareEndorsement,
+    endorsement::{EndorsementId, SecureShareEndorsement},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     timeslots::get_block_slot_timestamp,
 };
@@ -229,21 +229,6 @@ pub(crate) fn note_endorsements_from_peer(
         }
     }
 
-    {
-        let mut cache_write = cache.write();
-
-        // add to the cache of endorsements we have checked
-        for endorsement_id in all_endorsement_ids.iter() {
-            cache_write.insert_checked_endorsement(*endorsement_id);
-        }
-
-        // add to the cache of endorsements known by the source node
-        cache_write.insert_peer_known_endorsements(
-            from_peer_id,
-            &all_endorsement_ids.iter().copied().collect::<Vec<_>>(),
-        );
-    }
-
     // From there we note new endorsements and propagate them
 
     // Filter out endorsements if they are too old (max age of the inclusion slot: `max_endorsements_propagation_time`)
@@ -260,6 +245,24 @@ pub(crate) fn note_endorsements_from_peer(
         }
     });
 
+    {
+        let mut cache_write = cache.write();
+
+        // add to the cache of endorsements we have checked,
+        // except the new ones that are only checked once admitted by the storage
+        for endorsement_id in all_endorsement_ids.iter() {
+            if !new_endorsements.contains_key(endorsement_id) {
+                cache_write.insert_checked_endorsement(*endorsement_id);
+            }
+        }
+
+        // add to the cache of endorsements known by the source node
+        cache_write.insert_peer_known_endorsements(
+            from_peer_id,
+            &all_endorsement_ids.iter().copied().collect::<Vec<_>>(),
+        );
+    }
+
     if new_endorsements.is_empty() {
         // no endorsements to note or propagate
         return Ok(());
@@ -267,7 +270,27 @@ pub(crate) fn note_endorsements_from_peer(
 
     // Store new endorsements
     let mut endorsement_store = storage.clone_without_refs();
-    endorsement_store.store_endorsements(new_endorsements.into_values().collect());
+    let new_endorsement_ids: Vec<EndorsementId> = new_endorsements.keys().copied().collect();
+    if let Err(err) =
+        endorsement_store.try_store_endorsements(new_endorsements.into_values().collect(), false)
+    {
+        // not marked as checked: they are processed again if received once the storage has room
+        debug!(
+            "Dropping {} endorsements received from {}: {}",
+            new_endorsement_ids.len(),
+            from_peer_id,
+            err
+        );
+        return Ok(());
+    }
+
+    // add to the cache of endorsements we have checked, once admitted by the storage
+    {
+        let mut cache_write = cache.write();
+        for endorsement_id in new_endorsement_ids {
+            cache_write.insert_checked_endorsement(endorsement_id);
+        }
+    }
 
     // Propagate to other peers
     if let Err(err) = endorsement_propagation_sender.try_send(
@@ -5,7 +5,7 @@ use massa_channel::{receiver::MassaReceiver, sender::MassaSender};
 use massa_logging::massa_trace;
 use massa_metrics::MassaMetrics;
 use massa_models::{
-    operation::{OperationPrefixId, OperationPrefixIds, SecureShareOperation},
+    operation::{OperationId, OperationPrefixId, OperationPrefixIds, SecureShareOperation},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
     secure_share::Id,
     slot::Slot,
@@ -430,29 +430,37 @@ pub(crate) fn note_operations_from_peer(
             .collect::<Vec<_>>(),
     )?;
 
-    {
-        // add to checked operations
-        let mut cache_write = operations_cache.write();
-
-        // add checked operations
-        for op_id in new_operations.keys().copied() {
-            cache_write.insert_checked_operation(op_id);
-        }
-
-        // add to known ops
-        cache_write.insert_peer_known_ops(
-            source_peer_id,
-            &all_received_ids
-                .into_iter()
-                .map(|id| id.into_prefix())
-                .collect::<Vec<_>>(),
-        );
-    }
+    // add to known ops
+    operations_cache.write().insert_peer_known_ops(
+        source_peer_id,
+        &all_received_ids
+            .into_iter()
+            .map(|id| id.into_prefix())
+            .collect::<Vec<_>>(),
+    );
 
     if !new_operations.is_empty() {
         // Store new operations, claim locally
         let mut ops = base_storage.clone_without_refs();
-        ops.store_operations(new_operations.into_values().collect());
+        let new_op_ids: Vec<OperationId> = new_operations.keys().copied().collect();
+        if let Err(err) = ops.try_store_operations(new_operations.into_values().collect(), false) {
+            // not marked as checked: they are processed again if received once the storage has room
+            debug!(
+                "Dropping {} operations received from {}: {}",
+                new_op_ids.len(),
+                source_peer_id,
+                err
+            );
+            return Ok(());
+        }
+
+        // add checked operations, once admitted by the storage
+        {
+            let mut cache_write = operations_cache.write();
+            for op_id in new_op_ids {
+                cache_write.insert_checked_operation(op_id);
+            }
+        }
 
         // propagate new operations
         if let Err(_err) = ops_propagation_sender.try_send(
@@ -28,6 +28,7 @@ use tracing::{debug, error, info, warn};
 use crate::context::Context;
 use crate::handlers::peer_handler::models::PeerState;
 use crate::messages::{Message, MessagesHandler, MessagesSerializer};
+use crate::traffic;
 use crate::wrap_network::ActiveConnectionsTrait;
 
 use self::models::PeerInfo;
@@ -300,8 +301,9 @@ impl MassaHandshake {
     }
 }
 
-impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake {
-    fn perform_handshake(
+impl MassaHandshake {
+    /// Exchange the peer ids, versions and announcements with a peer
+    fn handshake(
         &mut self,
         context: &Context,
         endpoint: &mut Endpoint,
@@ -434,8 +436,9 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
                         .with_peer_management_message_serializer(
                             PeerManagementMessageSerializer::new(),
                         );
+                    // the message is only handled locally, not sent
                     peer_management_message_serializer
-                        .serialize(&Message::PeerManagement(Box::new(message)), &mut bytes)
+                        .serialize_message(&Message::PeerManagement(Box::new(message)), &mut bytes)
                         .map_err(|err| {
                             PeerNetError::HandshakeError.error(
                                 "Massa Handshake",
@@ -565,6 +568,20 @@ impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake
 
         res.map(|(id, _)| id)
     }
+}
+
+impl InitConnectionHandler<PeerId, Context, MessagesHandler> for MassaHandshake {
+    fn perform_handshake(
+        &mut self,
+        context: &Context,
+        endpoint: &mut Endpoint,
+        listeners: &HashMap<SocketAddr, TransportType>,
+        messages_handler: MessagesHandler,
+    ) -> PeerNetResult<PeerId> {
+        let res = self.handshake(context, endpoint, listeners, messages_handler);
+        traffic::record_handshake(res.is_ok());
+        res
+    }
 
     fn fallback_function(
         &mut self,
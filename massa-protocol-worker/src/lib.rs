@@ -6,6 +6,7 @@ mod ip;
 mod manager;
 mod messages;
 mod sig_verifier;
+mod traffic;
 mod worker;
 mod wrap_network;
 mod wrap_peer_db;
@@ -20,6 +20,7 @@ use crate::handlers::{
         models::PeerMessageTuple, PeerManagementMessage, PeerManagementMessageSerializer,
     },
 };
+use crate::traffic;
 
 #[derive(Debug)]
 pub enum Message {
@@ -29,7 +30,7 @@ pub enum Message {
     PeerManagement(Box<PeerManagementMessage>),
 }
 
-#[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
+#[derive(IntoPrimitive, Debug, Clone, Copy, Eq, PartialEq, TryFromPrimitive)]
 #[repr(u64)]
 pub enum MessageTypeId {
     Block = 0,
@@ -134,8 +135,25 @@ impl MessagesSerializer {
 }
 
 impl PeerNetMessagesSerializer<Message> for MessagesSerializer {
-    /// Serialize the message
+    /// Serialize the message, counting its bytes in the traffic sent to peers
     fn serialize(&self, message: &Message, buffer: &mut Vec<u8>) -> PeerNetResult<()> {
+        let initial_len = buffer.len();
+        self.serialize_message(message, buffer)?;
+        traffic::record_sent(
+            MessageTypeId::from(message),
+            buffer.len().saturating_sub(initial_len),
+        );
+        Ok(())
+    }
+}
+
+impl MessagesSerializer {
+    /// Serialize the message without counting it in the traffic, for messages that are not sent
+    pub(crate) fn serialize_message(
+        &self,
+        message: &Message,
+        buffer: &mut Vec<u8>,
+    ) -> PeerNetResult<()> {
         self.id_serializer
             .serialize(&MessageTypeId::from(message).into(), buffer)
             .map_err(|err| {
@@ -220,6 +238,7 @@ pub struct MessagesHandler {
 
 impl PeerNetMessagesHandler<PeerId> for MessagesHandler {
     fn handle(&self, data: &[u8], peer_id: &PeerId) -> PeerNetResult<()> {
+        let message_len = data.len();
         let (data, raw_id) = self
             .id_deserializer
             .deserialize::<DeserializeError>(data)
@@ -235,6 +254,7 @@ impl PeerNetMessagesHandler<PeerId> for MessagesHandler {
                 Some(String::from("Invalid message type id")),
             )
         })?;
+        traffic::record_received(id, message_len);
         match id {
             // Blocks are high-priority: we block if the channel is full.
             // This means that the sender will be blocked until the message is sent.
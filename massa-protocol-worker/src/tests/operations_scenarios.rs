@@ -1,19 +1,26 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Duration;
 
+use massa_channel::MassaChannel;
 use massa_models::config::CHAINID;
 use massa_models::operation::{OperationPrefixId, SecureShareOperation};
 use massa_models::{block_id::BlockId, prehash::PreHashSet, slot::Slot};
+use massa_pool_exports::{MockPoolController, PoolController};
 use massa_protocol_exports::PeerId;
 use massa_protocol_exports::ProtocolConfig;
 use massa_signature::KeyPair;
+use massa_storage::{Storage, StorageCaps, DEFAULT_ORPHAN_REMOVAL_BATCH_SIZE};
 use massa_test_framework::{TestUniverse, WaitPoint};
 use massa_time::MassaTime;
 use mockall::{predicate, Sequence};
+use parking_lot::RwLock;
 
 use crate::handlers::block_handler::AskForBlockInfo;
+use crate::handlers::operation_handler::cache::{OperationCache, SharedOperationCache};
+use crate::handlers::operation_handler::note_operations_from_peer;
 use crate::wrap_network::MockActiveConnectionsTraitWrapper;
 use crate::{
     handlers::{
@@ -661,3 +668,65 @@ fn test_protocol_on_ask_operations() {
     );
     waitpoint.wait();
 }
+
+#[test]
+fn test_operations_rejected_by_storage_are_not_marked_checked() {
+    let protocol_config = ProtocolConfig {
+        thread_count: 2,
+        ..Default::default()
+    };
+    let block_creator = KeyPair::generate(0).unwrap();
+    let operation = ProtocolTestUniverse::create_operation(&block_creator, 1, *CHAINID);
+    let node_a_keypair = KeyPair::generate(0).unwrap();
+    let node_a_peer_id = PeerId::from_public_key(node_a_keypair.get_public_key());
+    let mut cache: SharedOperationCache = Arc::new(RwLock::new(OperationCache::new(100, 100)));
+    let (mut propagation_sender, _propagation_receiver) =
+        MassaChannel::new("test_operations_propagation".to_string(), None);
+    let mut pool_controller = MockPoolController::new();
+    pool_controller
+        .expect_add_operations()
+        .times(1)
+        .return_const(());
+    let mut pool_controller: Box<dyn PoolController> = Box::new(pool_controller);
+
+    // the storage is full: the operation is dropped and left unchecked
+    let full_storage = Storage::create_root_with_caps(
+        DEFAULT_ORPHAN_REMOVAL_BATCH_SIZE,
+        StorageCaps {
+            max_operations: Some(0),
+            ..Default::default()
+        },
+    );
+    note_operations_from_peer(
+        &full_storage,
+        &mut cache,
+        &protocol_config,
+        vec![operation.clone()],
+        &node_a_peer_id,
+        &mut propagation_sender,
+        &mut pool_controller,
+    )
+    .unwrap();
+    assert!(cache
+        .read()
+        .checked_operations
+        .peek(&operation.id)
+        .is_none());
+
+    // received again once the storage has room: it is stored, sent to the pool and checked
+    note_operations_from_peer(
+        &Storage::create_root(),
+        &mut cache,
+        &protocol_config,
+        vec![operation.clone()],
+        &node_a_peer_id,
+        &mut propagation_sender,
+        &mut pool_controller,
+    )
+    .unwrap();
+    assert!(cache
+        .read()
+        .checked_operations
+        .peek(&operation.id)
+        .is_some());
+}
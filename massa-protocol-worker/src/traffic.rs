@@ -0,0 +1,153 @@
+//! Cumulative counters of the traffic exchanged with peers.
+//!
+//! They are process-wide so that they are kept across peer reconnections and protocol restarts,
+//! and only reset when the node restarts.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use massa_models::stats::{MessageTrafficStats, NetworkTrafficStats};
+
+use crate::messages::MessageTypeId;
+
+/// Bytes exchanged for a category of messages
+struct CategoryTraffic {
+    sent: AtomicU64,
+    received: AtomicU64,
+}
+
+impl CategoryTraffic {
+    const fn new() -> Self {
+        Self {
+            sent: AtomicU64::new(0),
+            received: AtomicU64::new(0),
+        }
+    }
+
+    fn stats(&self) -> MessageTrafficStats {
+        MessageTrafficStats {
+            bytes_sent: self.sent.load(Ordering::Relaxed),
+            bytes_received: self.received.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// traffic by category, indexed by `MessageTypeId`
+static TRAFFIC: [CategoryTraffic; 4] = [
+    CategoryTraffic::new(),
+    CategoryTraffic::new(),
+    CategoryTraffic::new(),
+    CategoryTraffic::new(),
+];
+static HANDSHAKES: AtomicU64 = AtomicU64::new(0);
+static HANDSHAKE_FAILURES: AtomicU64 = AtomicU64::new(0);
+
+fn category(id: MessageTypeId) -> &'static CategoryTraffic {
+    &TRAFFIC[u64::from(id) as usize]
+}
+
+/// Count `bytes` of a message of type `id` serialized to be sent to a peer
+pub(crate) fn record_sent(id: MessageTypeId, bytes: usize) {
+    category(id).sent.fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+/// Count `bytes` of a message of type `id` received from a peer
+pub(crate) fn record_received(id: MessageTypeId, bytes: usize) {
+    category(id)
+        .received
+        .fetch_add(bytes as u64, Ordering::Relaxed);
+}
+
+/// Count a handshake performed with a peer
+pub(crate) fn record_handshake(success: bool) {
+    HANDSHAKES.fetch_add(1, Ordering::Relaxed);
+    if !success {
+        HANDSHAKE_FAILURES.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Traffic stats with the given connection counts
+pub(crate) fn get_traffic_stats(
+    in_connection_count: u64,
+    out_connection_count: u64,
+    banned_peer_count: u64,
+) -> NetworkTrafficStats {
+    NetworkTrafficStats {
+        in_connection_count,
+        out_connection_count,
+        banned_peer_count,
+        handshake_count: HANDSHAKES.load(Ordering::Relaxed),
+        handshake_failure_count: HANDSHAKE_FAILURES.load(Ordering::Relaxed),
+        blocks: category(MessageTypeId::Block).stats(),
+        operations: category(MessageTypeId::Operation).stats(),
+        endorsements: category(MessageTypeId::Endorsement).stats(),
+        peers: category(MessageTypeId::PeerManagement).stats(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use massa_channel::MassaChannel;
+    use massa_protocol_exports::PeerId;
+    use massa_serialization::U64VarIntDeserializer;
+    use massa_signature::KeyPair;
+    use peernet::messages::{
+        MessagesHandler as PeerNetMessagesHandler, MessagesSerializer as PeerNetMessagesSerializer,
+    };
+
+    use super::*;
+    use crate::handlers::peer_handler::{PeerManagementMessage, PeerManagementMessageSerializer};
+    use crate::messages::{Message, MessagesHandler, MessagesSerializer};
+
+    #[test]
+    fn test_traffic_counters() {
+        // the counters are shared by the tests running in parallel: only check their increase
+        let before = get_traffic_stats(0, 0, 0);
+        let peer_id = PeerId::from_public_key(KeyPair::generate(0).unwrap().get_public_key());
+
+        let serializer = MessagesSerializer::new()
+            .with_peer_management_message_serializer(PeerManagementMessageSerializer::new());
+        let mut bytes = Vec::new();
+        serializer
+            .serialize(
+                &Message::from(PeerManagementMessage::ListPeers(vec![])),
+                &mut bytes,
+            )
+            .unwrap();
+        let sent = get_traffic_stats(0, 0, 0);
+        assert!(sent.peers.bytes_sent >= before.peers.bytes_sent + bytes.len() as u64);
+
+        let (sender_blocks, _receiver_blocks) = MassaChannel::new("test_blocks".to_string(), None);
+        let (sender_endorsements, _receiver_endorsements) =
+            MassaChannel::new("test_endorsements".to_string(), None);
+        let (sender_operations, _receiver_operations) =
+            MassaChannel::new("test_operations".to_string(), None);
+        let (sender_peers, _receiver_peers) = MassaChannel::new("test_peers".to_string(), None);
+        let handler = MessagesHandler {
+            id_deserializer: U64VarIntDeserializer::new(
+                std::ops::Bound::Included(0),
+                std::ops::Bound::Included(u64::MAX),
+            ),
+            sender_blocks,
+            sender_endorsements,
+            sender_operations,
+            sender_peers,
+        };
+        handler.handle(&bytes, &peer_id).unwrap();
+        let received = get_traffic_stats(0, 0, 0);
+        assert!(received.peers.bytes_received >= sent.peers.bytes_received + bytes.len() as u64);
+
+        record_handshake(true);
+        record_handshake(false);
+        let handshakes = get_traffic_stats(3, 2, 1);
+        assert!(handshakes.handshake_count >= received.handshake_count + 2);
+        assert!(handshakes.handshake_failure_count > received.handshake_failure_count);
+        assert_eq!(
+            (
+                handshakes.in_connection_count,
+                handshakes.out_connection_count,
+                handshakes.banned_peer_count
+            ),
+            (3, 2, 1)
+        );
+    }
+}
@@ -10,6 +10,8 @@ pub struct ClientConfig {
     pub max_request_body_size: u32,
     /// timeout of an request.
     pub request_timeout: MassaTime,
+    /// timeout of the connection establishment, distinct from the request timeout.
+    pub connect_timeout: MassaTime,
     /// maximum concurrent requests.
     pub max_concurrent_requests: usize,
     /// certificate_store, `Native` or `WebPki`
@@ -30,6 +32,8 @@ pub struct HttpConfig {
     pub client_config: ClientConfig,
     /// whether to enable HTTP.
     pub enabled: bool,
+    /// HTTP proxy the requests go through, `http://[user:password@]host[:port]`.
+    pub proxy_url: Option<String>,
 }
 
 /// WebSocket client settings.
@@ -0,0 +1,126 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Response envelopes of the public API.
+//!
+//! Every request asks the node to wrap its results in a [`ResponseEnvelope`]. The envelopes are
+//! checked and unwrapped before the responses reach `jsonrpsee`, so the calls deserialize the
+//! results as usual. Nodes that don't wrap their responses are still supported: only the
+//! responses flagged by the node are unwrapped.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use http::header::CONTENT_LENGTH;
+use http::HeaderValue;
+use hyper::{Body, Request, Response};
+use jsonrpsee::http_client::transport::Error as TransportError;
+use massa_api_exports::envelope::{ResponseEnvelope, API_REVISION, ENVELOPE_HEADER};
+use serde_json::Value;
+use tower::{Layer, Service};
+
+/// Layer requesting and unwrapping the response envelopes
+#[derive(Clone)]
+pub(crate) struct EnvelopeLayer {
+    /// chain the node must be on, not checked if `None`
+    chain_id: Option<u64>,
+}
+
+impl EnvelopeLayer {
+    pub(crate) fn new(chain_id: Option<u64>) -> Self {
+        EnvelopeLayer { chain_id }
+    }
+
+    /// Replace the enveloped result of a JSON-RPC response, or of each response of a batch,
+    /// by the result itself
+    fn unwrap(&self, body: &mut Value) -> Result<(), String> {
+        match body {
+            Value::Array(responses) => {
+                for response in responses {
+                    self.unwrap(response)?;
+                }
+            }
+            Value::Object(response) => {
+                if let Some(result) = response.get_mut("result") {
+                    let envelope: ResponseEnvelope<Value> =
+                        serde_json::from_value(result.take())
+                            .map_err(|e| format!("malformed response envelope: {}", e))?;
+                    if envelope.api_revision != API_REVISION {
+                        return Err(format!(
+                            "node {} answers with API revision {}, expected {}",
+                            envelope.node_version, envelope.api_revision, API_REVISION
+                        ));
+                    }
+                    if let Some(chain_id) = self.chain_id {
+                        if envelope.chain_id != chain_id {
+                            return Err(format!(
+                                "chain id mismatch: node {} is on chain {}, expected {}",
+                                envelope.node_version, envelope.chain_id, chain_id
+                            ));
+                        }
+                    }
+                    *result = envelope.result;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl<S> Layer<S> for EnvelopeLayer {
+    type Service = EnvelopeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        EnvelopeService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service built by [`EnvelopeLayer`]
+#[derive(Clone)]
+pub(crate) struct EnvelopeService<S> {
+    inner: S,
+    layer: EnvelopeLayer,
+}
+
+impl<S> Service<Request<Body>> for EnvelopeService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = TransportError>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = TransportError;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Body>, TransportError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        request
+            .headers_mut()
+            .insert(ENVELOPE_HEADER, HeaderValue::from_static("1"));
+        let response = self.inner.call(request);
+        let layer = self.layer.clone();
+        Box::pin(async move {
+            let response = response.await?;
+            if response.headers().get(ENVELOPE_HEADER) != Some(&HeaderValue::from_static("1")) {
+                return Ok(response);
+            }
+            let (mut parts, body) = response.into_parts();
+            let bytes = hyper::body::to_bytes(body)
+                .await
+                .map_err(|e| TransportError::Http(Box::new(e)))?;
+            let mut body: Value =
+                serde_json::from_slice(&bytes).map_err(|e| TransportError::Http(Box::new(e)))?;
+            layer
+                .unwrap(&mut body)
+                .map_err(|e| TransportError::Http(e.into()))?;
+            parts.headers.remove(CONTENT_LENGTH);
+            Ok(Response::from_parts(parts, Body::from(body.to_string())))
+        })
+    }
+}
@@ -4,9 +4,12 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
+use async_trait::async_trait;
 use http::header::HeaderName;
-use jsonrpsee::core::client::{ClientT, IdKind, Subscription, SubscriptionClientT};
-use jsonrpsee::http_client::transport::HttpBackend;
+use jsonrpsee::core::client::{
+    ClientT, IdKind, Subscription, SubscriptionClientT, SubscriptionKind,
+};
+use jsonrpsee::core::DeserializeOwned;
 use jsonrpsee::http_client::HttpClient;
 use jsonrpsee::rpc_params;
 use jsonrpsee::types::ErrorObject;
@@ -14,26 +17,36 @@ use jsonrpsee::ws_client::{HeaderMap, HeaderValue, WsClient, WsClientBuilder};
 use jsonrpsee::{core::RpcResult, http_client::HttpClientBuilder};
 use jsonrpsee_http_client as _;
 use jsonrpsee_ws_client as _;
-use massa_api_exports::page::PagedVecV2;
+use massa_api_exports::page::{PageRequest, PagedVecV2};
 use massa_api_exports::ApiRequest;
 use massa_api_exports::{
-    address::AddressInfo,
+    address::{AddressFilter, AddressInfo},
     block::{BlockInfo, BlockSummary},
     datastore::{DatastoreEntryInput, DatastoreEntryOutput},
+    debug_dump::{DebugDump, DebugDumpRequest},
+    deferred_credits::{DeferredCredit, DeferredCreditsRequest},
+    denunciation::{DenunciationEvidence, EvidenceOutcome},
     endorsement::EndorsementInfo,
-    execution::{ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, Transfer},
-    node::NodeStatus,
-    operation::{OperationInfo, OperationInput},
+    execution::{
+        ExecuteReadOnlyResponse, ReadOnlyBytecodeExecution, ReadOnlyCall, SlotExecutionBudget,
+        Transfer,
+    },
+    node::{NodeEvent, NodeStatus},
+    operation::{OperationInfo, OperationInput, OperationStreamFilter},
+    pool::PoolEventFilter,
+    slashing::SlashingStats,
     TimeInterval,
 };
+use massa_hash::Hash;
+use massa_models::config::VERSION;
 use massa_models::secure_share::SecureShare;
 use massa_models::slot::Slot;
 use massa_models::{
     address::Address,
-    block::FilledBlock,
+    block::{Block, FilledBlock},
     block_header::BlockHeader,
     block_id::BlockId,
-    clique::Clique,
+    clique::CliqueInfo,
     composite::PubkeySig,
     endorsement::EndorsementId,
     execution::EventFilter,
@@ -43,17 +56,32 @@ use massa_models::{
     prehash::{PreHashMap, PreHashSet},
     version::Version,
 };
+use massa_pool_exports::PoolEvent;
 use massa_proto_rs::massa::api::v1::private_service_client::PrivateServiceClient;
 use massa_proto_rs::massa::api::v1::public_service_client::PublicServiceClient;
 use std::net::{IpAddr, SocketAddr};
+use std::ops::Range;
 use std::str::FromStr;
 use thiserror::Error;
 
 pub mod cert_manager;
 mod config;
+mod envelope;
 pub use config::ClientConfig;
 pub use config::HttpConfig;
 pub use config::WsConfig;
+mod resume;
+mod transport;
+use envelope::{EnvelopeLayer, EnvelopeService};
+pub use massa_api_exports::subscription::SubItem;
+/// Check a signature produced by `node_sign_message_v2` without needing a node
+pub use massa_models::composite::verify_node_signed_message;
+pub use resume::{MissedSlots, ResumableBlock, ResumableBlocks, ResumeToken, ResumeTokenError};
+pub use transport::Endpoint;
+use transport::{Transport, TransportLayer};
+
+#[cfg(test)]
+mod tests;
 
 /// Error when creating a new client
 #[derive(Error, Debug)]
@@ -64,13 +92,42 @@ pub enum ClientError {
     /// Connection error
     #[error("Cannot connect to grpc server: {0}")]
     Connect(#[from] tonic::transport::Error),
+    /// Node unreachable
+    #[error("Cannot reach node: {0}")]
+    Unreachable(String),
+    /// Incompatible node version
+    #[error("Incompatible node version: node is {node}, expected {expected}")]
+    IncompatibleVersion {
+        /// version reported by the node
+        node: Version,
+        /// version the client was built for
+        expected: Version,
+    },
+    /// Invalid HTTP proxy configuration
+    #[error("Invalid proxy configuration: {0}")]
+    Proxy(String),
+    /// Endpoint not usable with the requested transport
+    #[error("Invalid endpoint: {0}")]
+    Endpoint(String),
+    /// Client construction error
+    #[error("Cannot create client: {0}")]
+    Build(String),
 }
 
+/// Code of the error returned by the private methods of a client created without the private API.
+///
+/// The call fails without reaching any node, with the code -32081 and the message
+/// "Private API unavailable: the client was created with `Client::new_public_only`".
+/// The code lies in the range reserved for implementation-defined server errors, unused by the node.
+pub const PRIVATE_API_UNAVAILABLE_CODE: i32 = -32081;
+
 /// Client
 pub struct Client {
     /// public component
     pub public: RpcClient,
-    /// private component
+    /// private component.
+    /// Without the private API (see [`Client::new_public_only`]), all its calls fail
+    /// with a [`PRIVATE_API_UNAVAILABLE_CODE`] error
     pub private: RpcClient,
     /// grpc public client
     pub grpc_public: Option<PublicServiceClient<tonic::transport::Channel>>,
@@ -99,9 +156,11 @@ impl Client {
         let private_url = format!("http://{}", private_socket_addr);
         let grpc_public_url = format!("grpc://{}", grpc_public_socket_addr);
         let grpc_private_url = format!("grpc://{}", grpc_private_socket_addr);
+        let connect_timeout = http_config.client_config.connect_timeout.to_duration();
 
         // try to start grpc client (public api) and connect to the server
         let grpc_pub_client = match tonic::transport::Channel::from_shared(grpc_public_url)?
+            .connect_timeout(connect_timeout)
             .connect()
             .await
         {
@@ -114,6 +173,7 @@ impl Client {
 
         // try to start grpc client (private api) and connect to the server
         let grpc_priv_client = match tonic::transport::Channel::from_shared(grpc_private_url)?
+            .connect_timeout(connect_timeout)
             .connect()
             .await
         {
@@ -125,318 +185,786 @@ impl Client {
         };
 
         Ok(Client {
-            public: RpcClient::from_url(&public_url, http_config).await,
-            private: RpcClient::from_url(&private_url, http_config).await,
+            public: RpcClient::from_endpoint_on_chain(
+                &Endpoint::Url(public_url),
+                http_config,
+                chain_id,
+            )
+            .await?,
+            private: RpcClient::from_endpoint(&Endpoint::Url(private_url), http_config).await?,
             grpc_public: grpc_pub_client,
             grpc_private: grpc_priv_client,
             chain_id,
         })
     }
+
+    /// creates a new client and checks that the node is reachable and compatible
+    /// by probing its public endpoint with `get_status`.
+    /// The chain id is taken from the node status. No grpc client is started.
+    ///
+    /// Use `new` instead if the connection should be established lazily.
+    pub async fn connect(
+        ip: IpAddr,
+        public_port: u16,
+        private_port: u16,
+        http_config: &HttpConfig,
+    ) -> Result<Client, ClientError> {
+        let private_url = format!("http://{}", SocketAddr::new(ip, private_port));
+        let (public, chain_id) = Client::probe_public(ip, public_port, http_config).await?;
+        Ok(Client {
+            public,
+            private: RpcClient::from_endpoint(&Endpoint::Url(private_url), http_config).await?,
+            grpc_public: None,
+            grpc_private: None,
+            chain_id,
+        })
+    }
+
+    /// creates a new client reaching only the public API, checked like in `connect`,
+    /// for tools that never call the private API.
+    /// The calls of the private component fail with a [`PRIVATE_API_UNAVAILABLE_CODE`] error.
+    pub async fn new_public_only(
+        ip: IpAddr,
+        public_port: u16,
+        http_config: &HttpConfig,
+    ) -> Result<Client, ClientError> {
+        let (public, chain_id) = Client::probe_public(ip, public_port, http_config).await?;
+        Ok(Client {
+            public,
+            private: RpcClient::unavailable(),
+            grpc_public: None,
+            grpc_private: None,
+            chain_id,
+        })
+    }
+
+    /// Check that the public API is reachable and compatible,
+    /// returning a public component bound to the chain of the node
+    async fn probe_public(
+        ip: IpAddr,
+        public_port: u16,
+        http_config: &HttpConfig,
+    ) -> Result<(RpcClient, u64), ClientError> {
+        let public_socket_addr = SocketAddr::new(ip, public_port);
+        let public_url = format!("http://{}", public_socket_addr);
+
+        // the Http client connects lazily on the first request,
+        // so report here a node that cannot be reached at all.
+        // Through a proxy, only the proxy could be probed, so let the status request tell.
+        if http_config.proxy_url.is_none() {
+            match tokio::time::timeout(
+                http_config.client_config.connect_timeout.to_duration(),
+                tokio::net::TcpStream::connect(public_socket_addr),
+            )
+            .await
+            {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => return Err(ClientError::Unreachable(e.to_string())),
+                Err(_) => {
+                    return Err(ClientError::Unreachable(format!(
+                        "connection to {} timed out",
+                        public_socket_addr
+                    )))
+                }
+            }
+        }
+
+        let public =
+            RpcClient::from_endpoint(&Endpoint::Url(public_url.clone()), http_config).await?;
+        let status = public
+            .get_status()
+            .await
+            .map_err(|e| ClientError::Unreachable(e.to_string()))?;
+        if !status.version.is_compatible(&VERSION) {
+            return Err(ClientError::IncompatibleVersion {
+                node: status.version,
+                expected: *VERSION,
+            });
+        }
+
+        // the following calls must reach a node of the same chain
+        let public = RpcClient::from_endpoint_on_chain(
+            &Endpoint::Url(public_url),
+            http_config,
+            status.chain_id,
+        )
+        .await?;
+        Ok((public, status.chain_id))
+    }
+
+    /// creates a new client reaching the public and private APIs at arbitrary endpoints,
+    /// for example Unix domain sockets or URLs behind an HTTP proxy.
+    /// No grpc client is started.
+    pub async fn from_endpoints(
+        public: &Endpoint,
+        private: &Endpoint,
+        chain_id: u64,
+        http_config: &HttpConfig,
+    ) -> Result<Client, ClientError> {
+        Ok(Client {
+            public: RpcClient::from_endpoint_on_chain(public, http_config, chain_id).await?,
+            private: RpcClient::from_endpoint(private, http_config).await?,
+            grpc_public: None,
+            grpc_private: None,
+            chain_id,
+        })
+    }
+}
+
+/// Methods of the node JSON-RPC API (v1), implemented by [`RpcClient`].
+///
+/// The methods documented as "Private API" must be called on [`Client::private`].
+/// On a client created with [`Client::new_public_only`], they fail with a
+/// [`PRIVATE_API_UNAVAILABLE_CODE`] error.
+///
+/// Applications can depend on this trait instead of the concrete client and use
+/// `MockMassaRpc` (`test-exports` feature) in their tests.
+#[cfg_attr(any(test, feature = "test-exports"), mockall::automock)]
+#[async_trait]
+pub trait MassaRpc: Send + Sync {
+    /// Gracefully stop the node.
+    /// Private API.
+    async fn stop_node(&self) -> RpcResult<()>;
+
+    /// Sign message with node's key, with domain separation.
+    /// The signature can be checked with [`verify_node_signed_message`].
+    /// Returns the public key that signed the message and the signature.
+    /// Private API.
+    async fn node_sign_message_v2(&self, message: Vec<u8>, domain: String) -> RpcResult<PubkeySig>;
+
+    /// Add a vector of new secret keys for the node to use to stake.
+    /// No confirmation to expect.
+    /// Private API.
+    async fn add_staking_secret_keys(&self, secret_keys: Vec<String>) -> RpcResult<()>;
+
+    /// Remove a vector of addresses used to stake.
+    /// No confirmation to expect.
+    /// Private API.
+    async fn remove_staking_addresses(&self, addresses: Vec<Address>) -> RpcResult<()>;
+
+    /// Return hash-set of staking addresses.
+    /// Private API.
+    async fn get_staking_addresses(&self) -> RpcResult<PreHashSet<Address>>;
+
+    /// Return a dump of the runtime state of the node, with the sections selected by `request`.
+    /// Private API.
+    async fn node_debug_dump(&self, request: DebugDumpRequest) -> RpcResult<DebugDump>;
+
+    /// Compact the final state database of the node on disk.
+    /// I/O intensive, returns once the compaction is over.
+    /// Private API.
+    async fn node_compact_final_state(&self) -> RpcResult<()>;
+
+    /// Bans given ip address(es)
+    /// No confirmation to expect.
+    /// Private API.
+    async fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()>;
+
+    /// Bans given node id(s)
+    /// No confirmation to expect.
+    /// Private API.
+    async fn node_ban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()>;
+
+    /// Unban given ip address(es)
+    /// No confirmation to expect.
+    /// Private API.
+    async fn node_unban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()>;
+
+    /// Unban given node id(s)
+    /// No confirmation to expect.
+    /// Private API.
+    async fn node_unban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()>;
+
+    /// Returns node peers whitelist IP address(es).
+    /// Private API.
+    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>>;
+
+    /// Add IP address(es) to node peers whitelist.
+    /// Private API.
+    async fn node_add_to_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()>;
+
+    /// Remove IP address(es) to node peers whitelist.
+    /// Private API.
+    async fn node_remove_from_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()>;
+
+    /// Returns node bootstrap whitelist IP address(es).
+    /// Private API.
+    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>>;
+
+    /// Allow everyone to bootstrap from the node.
+    /// remove bootstrap whitelist configuration file.
+    /// Private API.
+    async fn node_bootstrap_whitelist_allow_all(&self) -> RpcResult<()>;
+
+    /// Add IP address(es) to node bootstrap whitelist.
+    /// Private API.
+    async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()>;
+
+    /// Remove IP address(es) to bootstrap whitelist.
+    /// Private API.
+    async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()>;
+
+    /// Returns node bootstrap blacklist IP address(es).
+    /// Private API.
+    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>>;
+
+    /// Add IP address(es) to node bootstrap blacklist.
+    /// Private API.
+    async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()>;
+
+    /// Remove IP address(es) to bootstrap blacklist.
+    /// Private API.
+    async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()>;
+
+    ////////////////
+    // public-api //
+    ////////////////
+
+    // Explorer (aggregated stats)
+
+    /// summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count
+    async fn get_status(&self) -> RpcResult<NodeStatus>;
+
+    /// Returns the transfers for slots
+    async fn get_slots_transfers(&self, slots: Vec<Slot>) -> RpcResult<Vec<Vec<Transfer>>>;
+
+    /// Get cliques with their fitness, block count and tip blocks.
+    async fn get_cliques(&self) -> RpcResult<Vec<CliqueInfo>>;
+
+    // Debug (specific information)
+
+    /// Returns operation(s) information associated to a given list of operation(s) ID(s).
+    async fn get_operations(
+        &self,
+        operation_ids: Vec<OperationId>,
+    ) -> RpcResult<Vec<OperationInfo>>;
+
+    /// Returns endorsement(s) information associated to a given list of endorsement(s) ID(s)
+    async fn get_endorsements(
+        &self,
+        endorsement_ids: Vec<EndorsementId>,
+    ) -> RpcResult<Vec<EndorsementInfo>>;
+
+    /// Returns block(s) information associated to a given list of block(s) ID(s)
+    async fn get_blocks(&self, block_ids: Vec<BlockId>) -> RpcResult<Vec<BlockInfo>>;
+
+    /// Get the block of the blockclique at a given slot, if any
+    async fn get_blockclique_block_by_slot(&self, slot: Slot) -> RpcResult<Option<Block>>;
+
+    /// Get a page of the ids of the blocks created by an address and still kept by the node,
+    /// sorted by slot
+    async fn get_blocks_by_creator(
+        &self,
+        address: Address,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<BlockId>>;
+
+    /// Get events emitted by smart contracts with various filters
+    async fn get_filtered_sc_output_event(
+        &self,
+        filter: EventFilter,
+    ) -> RpcResult<Vec<SCOutputEvent>>;
+
+    /// Get info by addresses
+    async fn get_addresses(&self, addresses: Vec<Address>) -> RpcResult<Vec<AddressInfo>>;
+
+    /// Get the bytecode of addresses in their final or candidate state,
+    /// `None` for the addresses without bytecode.
+    /// Check a deployed smart contract against a build with [`verify_bytecode`].
+    async fn get_addresses_bytecode(
+        &self,
+        filters: Vec<AddressFilter>,
+    ) -> RpcResult<Vec<Option<Vec<u8>>>>;
+
+    /// Get a page of the final deferred credits between two slots (both included),
+    /// sorted by slot then address
+    async fn get_deferred_credits(
+        &self,
+        request: DeferredCreditsRequest,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<DeferredCredit>>;
+
+    /// Get the final slashing statistics of the cycles between `min_cycle` and `max_cycle`
+    /// (both included), sorted by cycle
+    async fn get_slashing_stats(
+        &self,
+        min_cycle: u64,
+        max_cycle: u64,
+    ) -> RpcResult<Vec<SlashingStats>>;
+
+    /// Submit pairs of conflicting block headers or endorsements, to be denounced by the node
+    async fn submit_denunciation_evidence(
+        &self,
+        evidences: Vec<DenunciationEvidence>,
+    ) -> RpcResult<Vec<EvidenceOutcome>>;
+
+    /// Get the gas budget of the execution of a slot
+    async fn get_slot_execution_budget(&self, slot: Slot) -> RpcResult<SlotExecutionBudget>;
+
+    /// Get datastore entries
+    async fn get_datastore_entries(
+        &self,
+        input: Vec<DatastoreEntryInput>,
+    ) -> RpcResult<Vec<DatastoreEntryOutput>>;
+
+    // User (interaction with the node)
+
+    /// Adds operations to pool. Returns operations that were ok and sent to pool.
+    async fn send_operations(&self, operations: Vec<OperationInput>)
+        -> RpcResult<Vec<OperationId>>;
+
+    /// execute read only bytecode,
+    /// the cause of a failed execution is given by the `error_kind` of the response,
+    /// its gas per section by the `gas_profile` if `profile` is set in the request,
+    /// and the calls it made by the `call_trace` if `include_trace` is set in the request
+    async fn execute_read_only_bytecode(
+        &self,
+        read_only_execution: ReadOnlyBytecodeExecution,
+    ) -> RpcResult<ExecuteReadOnlyResponse>;
+
+    /// execute read only SC call,
+    /// the cause of a failed execution is given by the `error_kind` of the response,
+    /// its gas per section by the `gas_profile` if `profile` is set in the request,
+    /// and the calls it made by the `call_trace` if `include_trace` is set in the request
+    async fn execute_read_only_call(
+        &self,
+        read_only_execution: ReadOnlyCall,
+    ) -> RpcResult<ExecuteReadOnlyResponse>;
+}
+
+/// Check that the final bytecode of `address` hashes to `expected_hash`,
+/// for example the hash of the build artifact of the smart contract deployed at `address`.
+/// An address without bytecode never matches.
+pub async fn verify_bytecode(
+    rpc: &dyn MassaRpc,
+    address: Address,
+    expected_hash: &Hash,
+) -> RpcResult<bool> {
+    let bytecode = rpc
+        .get_addresses_bytecode(vec![AddressFilter {
+            address,
+            is_final: true,
+        }])
+        .await?
+        .pop()
+        .ok_or_else(|| to_error_obj("missing bytecode in the response".to_owned()))?;
+    Ok(bytecode.is_some_and(|bytecode| Hash::compute_from(&bytecode) == *expected_hash))
 }
 
 /// Rpc client
+///
+/// Cloning a `RpcClient` is cheap: cloned handles share the same underlying
+/// HTTP connection pool and can be moved to other tasks.
+#[derive(Clone)]
 pub struct RpcClient {
-    http_client: HttpClient<HttpBackend>,
+    /// `None` for the private component of a client created without the private API
+    http_client: Option<HttpClient<EnvelopeService<Transport>>>,
 }
 
 impl RpcClient {
     /// Default constructor
+    ///
+    /// # Panics
+    /// If the client cannot be created, for example if the proxy url is invalid.
+    /// Use [`RpcClient::from_endpoint`] to handle the error.
     pub async fn from_url(url: &str, http_config: &HttpConfig) -> RpcClient {
-        RpcClient {
-            http_client: http_client_from_url(url, http_config),
-        }
+        RpcClient::from_endpoint(&Endpoint::Url(url.to_string()), http_config)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e))
     }
 
-    /// Gracefully stop the node.
-    pub async fn stop_node(&self) -> RpcResult<()> {
-        self.http_client
-            .request("stop_node", rpc_params![])
-            .await
-            .map_err(|e| to_error_obj(e.to_string()))
+    /// Create a client reaching the API at `endpoint`,
+    /// through the HTTP proxy of `http_config` if there is one
+    pub async fn from_endpoint(
+        endpoint: &Endpoint,
+        http_config: &HttpConfig,
+    ) -> Result<RpcClient, ClientError> {
+        Ok(RpcClient {
+            http_client: Some(http_client_from_endpoint(
+                endpoint,
+                http_config,
+                EnvelopeLayer::new(None),
+            )?),
+        })
+    }
+
+    /// Create a client reaching the API at `endpoint`, whose calls fail if the node is not on
+    /// the chain `chain_id`
+    pub async fn from_endpoint_on_chain(
+        endpoint: &Endpoint,
+        http_config: &HttpConfig,
+        chain_id: u64,
+    ) -> Result<RpcClient, ClientError> {
+        Ok(RpcClient {
+            http_client: Some(http_client_from_endpoint(
+                endpoint,
+                http_config,
+                EnvelopeLayer::new(Some(chain_id)),
+            )?),
+        })
+    }
+
+    /// Placeholder for the private API of a client created without it
+    pub(crate) fn unavailable() -> RpcClient {
+        RpcClient { http_client: None }
+    }
+
+    /// Whether the client can reach an API,
+    /// false for the private component of a client created with [`Client::new_public_only`]
+    pub fn is_available(&self) -> bool {
+        self.http_client.is_some()
+    }
+
+    fn http_client(&self) -> RpcResult<&HttpClient<EnvelopeService<Transport>>> {
+        self.http_client.as_ref().ok_or_else(|| {
+            ErrorObject::owned(
+                PRIVATE_API_UNAVAILABLE_CODE,
+                "Private API unavailable: the client was created with `Client::new_public_only`",
+                None::<()>,
+            )
+        })
     }
 
     /// Sign message with node's key.
     /// Returns the public key that signed the message and the signature.
+    /// Private API.
+    #[deprecated(
+        note = "signs raw bytes without domain separation, use `node_sign_message_v2` instead"
+    )]
     pub async fn node_sign_message(&self, message: Vec<u8>) -> RpcResult<PubkeySig> {
-        self.http_client
+        self.http_client()?
             .request("node_sign_message", rpc_params![message])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Add a vector of new secret keys for the node to use to stake.
-    /// No confirmation to expect.
-    pub async fn add_staking_secret_keys(&self, secret_keys: Vec<String>) -> RpcResult<()> {
-        self.http_client
+    /// Returns the active stakers and their roll counts for the current cycle.
+    pub(crate) async fn _get_stakers(&self) -> RpcResult<PreHashMap<Address, u64>> {
+        self.http_client()?
+            .request("get_stakers", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    /// Get the block graph within the specified time interval.
+    /// Optional parameters: from `<time_start>` (included) and to `<time_end>` (excluded) millisecond timestamp
+    pub(crate) async fn _get_graph_interval(
+        &self,
+        time_interval: TimeInterval,
+    ) -> RpcResult<Vec<BlockSummary>> {
+        self.http_client()?
+            .request("get_graph_interval", rpc_params![time_interval])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl MassaRpc for RpcClient {
+    async fn stop_node(&self) -> RpcResult<()> {
+        self.http_client()?
+            .request("stop_node", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    async fn node_sign_message_v2(&self, message: Vec<u8>, domain: String) -> RpcResult<PubkeySig> {
+        self.http_client()?
+            .request("node_sign_message_v2", rpc_params![message, domain])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    async fn add_staking_secret_keys(&self, secret_keys: Vec<String>) -> RpcResult<()> {
+        self.http_client()?
             .request("add_staking_secret_keys", rpc_params![secret_keys])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Remove a vector of addresses used to stake.
-    /// No confirmation to expect.
-    pub async fn remove_staking_addresses(&self, addresses: Vec<Address>) -> RpcResult<()> {
-        self.http_client
+    async fn remove_staking_addresses(&self, addresses: Vec<Address>) -> RpcResult<()> {
+        self.http_client()?
             .request("remove_staking_addresses", rpc_params![addresses])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Return hash-set of staking addresses.
-    pub async fn get_staking_addresses(&self) -> RpcResult<PreHashSet<Address>> {
-        self.http_client
+    async fn get_staking_addresses(&self) -> RpcResult<PreHashSet<Address>> {
+        self.http_client()?
             .request("get_staking_addresses", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Bans given ip address(es)
-    /// No confirmation to expect.
-    pub async fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
-        self.http_client
+    async fn node_debug_dump(&self, request: DebugDumpRequest) -> RpcResult<DebugDump> {
+        self.http_client()?
+            .request("node_debug_dump", rpc_params![request])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    async fn node_compact_final_state(&self) -> RpcResult<()> {
+        self.http_client()?
+            .request("node_compact_final_state", rpc_params![])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    async fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        self.http_client()?
             .request("node_ban_by_ip", rpc_params![ips])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Bans given node id(s)
-    /// No confirmation to expect.
-    pub async fn node_ban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()> {
-        self.http_client
+    async fn node_ban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()> {
+        self.http_client()?
             .request("node_ban_by_id", rpc_params![ids])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Unban given ip address(es)
-    /// No confirmation to expect.
-    pub async fn node_unban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
-        self.http_client
+    async fn node_unban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        self.http_client()?
             .request("node_unban_by_ip", rpc_params![ips])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Unban given node id(s)
-    /// No confirmation to expect.
-    pub async fn node_unban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()> {
-        self.http_client
+    async fn node_unban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()> {
+        self.http_client()?
             .request("node_unban_by_id", rpc_params![ids])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns node peers whitelist IP address(es).
-    pub async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        self.http_client
+    async fn node_peers_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
+        self.http_client()?
             .request("node_peers_whitelist", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Add IP address(es) to node peers whitelist.
-    pub async fn node_add_to_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
-        self.http_client
+    async fn node_add_to_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        self.http_client()?
             .request("node_add_to_peers_whitelist", rpc_params![ips])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Remove IP address(es) to node peers whitelist.
-    pub async fn node_remove_from_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
-        self.http_client
+    async fn node_remove_from_peers_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        self.http_client()?
             .request("node_remove_from_peers_whitelist", rpc_params![ips])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns node bootstrap whitelist IP address(es).
-    pub async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
-        self.http_client
+    async fn node_bootstrap_whitelist(&self) -> RpcResult<Vec<IpAddr>> {
+        self.http_client()?
             .request("node_bootstrap_whitelist", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Allow everyone to bootstrap from the node.
-    /// remove bootstrap whitelist configuration file.
-    pub async fn node_bootstrap_whitelist_allow_all(&self) -> RpcResult<()> {
-        self.http_client
+    async fn node_bootstrap_whitelist_allow_all(&self) -> RpcResult<()> {
+        self.http_client()?
             .request("node_bootstrap_whitelist_allow_all", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Add IP address(es) to node bootstrap whitelist.
-    pub async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
-        self.http_client
+    async fn node_add_to_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        self.http_client()?
             .request("node_add_to_bootstrap_whitelist", rpc_params![ips])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Remove IP address(es) to bootstrap whitelist.
-    pub async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
-        self.http_client
+    async fn node_remove_from_bootstrap_whitelist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        self.http_client()?
             .request("node_remove_from_bootstrap_whitelist", rpc_params![ips])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns node bootstrap blacklist IP address(es).
-    pub async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>> {
-        self.http_client
+    async fn node_bootstrap_blacklist(&self) -> RpcResult<Vec<IpAddr>> {
+        self.http_client()?
             .request("node_bootstrap_blacklist", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Add IP address(es) to node bootstrap blacklist.
-    pub async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
-        self.http_client
+    async fn node_add_to_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        self.http_client()?
             .request("node_add_to_bootstrap_blacklist", rpc_params![ips])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Remove IP address(es) to bootstrap blacklist.
-    pub async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
-        self.http_client
+    async fn node_remove_from_bootstrap_blacklist(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
+        self.http_client()?
             .request("node_remove_from_bootstrap_blacklist", rpc_params![ips])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    ////////////////
-    // public-api //
-    ////////////////
-
-    // Explorer (aggregated stats)
-
-    /// summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count
-    pub async fn get_status(&self) -> RpcResult<NodeStatus> {
-        self.http_client
+    async fn get_status(&self) -> RpcResult<NodeStatus> {
+        self.http_client()?
             .request("get_status", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns the transfers for slots
-    pub async fn get_slots_transfers(&self, slots: Vec<Slot>) -> RpcResult<Vec<Vec<Transfer>>> {
-        self.http_client
+    async fn get_slots_transfers(&self, slots: Vec<Slot>) -> RpcResult<Vec<Vec<Transfer>>> {
+        self.http_client()?
             .request("get_slots_transfers", rpc_params![slots])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    pub(crate) async fn _get_cliques(&self) -> RpcResult<Vec<Clique>> {
-        self.http_client
+    async fn get_cliques(&self) -> RpcResult<Vec<CliqueInfo>> {
+        self.http_client()?
             .request("get_cliques", rpc_params![])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    // Debug (specific information)
-
-    /// Returns the active stakers and their roll counts for the current cycle.
-    pub(crate) async fn _get_stakers(&self) -> RpcResult<PreHashMap<Address, u64>> {
-        self.http_client
-            .request("get_stakers", rpc_params![])
-            .await
-            .map_err(|e| to_error_obj(e.to_string()))
-    }
-
-    /// Returns operation(s) information associated to a given list of operation(s) ID(s).
-    pub async fn get_operations(
+    async fn get_operations(
         &self,
         operation_ids: Vec<OperationId>,
     ) -> RpcResult<Vec<OperationInfo>> {
-        self.http_client
+        self.http_client()?
             .request("get_operations", rpc_params![operation_ids])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns endorsement(s) information associated to a given list of endorsement(s) ID(s)
-    pub async fn get_endorsements(
+    async fn get_endorsements(
         &self,
         endorsement_ids: Vec<EndorsementId>,
     ) -> RpcResult<Vec<EndorsementInfo>> {
-        self.http_client
+        self.http_client()?
             .request("get_endorsements", rpc_params![endorsement_ids])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Returns block(s) information associated to a given list of block(s) ID(s)
-    pub async fn get_blocks(&self, block_ids: Vec<BlockId>) -> RpcResult<Vec<BlockInfo>> {
-        self.http_client
+    async fn get_blocks(&self, block_ids: Vec<BlockId>) -> RpcResult<Vec<BlockInfo>> {
+        self.http_client()?
             .request("get_blocks", rpc_params![block_ids])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Get events emitted by smart contracts with various filters
-    pub async fn get_filtered_sc_output_event(
+    async fn get_blockclique_block_by_slot(&self, slot: Slot) -> RpcResult<Option<Block>> {
+        self.http_client()?
+            .request("get_blockclique_block_by_slot", rpc_params![slot])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    async fn get_blocks_by_creator(
+        &self,
+        address: Address,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<BlockId>> {
+        self.http_client()?
+            .request("get_blocks_by_creator", rpc_params![address, page_request])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    async fn get_filtered_sc_output_event(
         &self,
         filter: EventFilter,
     ) -> RpcResult<Vec<SCOutputEvent>> {
-        self.http_client
+        self.http_client()?
             .request("get_filtered_sc_output_event", rpc_params![filter])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Get the block graph within the specified time interval.
-    /// Optional parameters: from `<time_start>` (included) and to `<time_end>` (excluded) millisecond timestamp
-    pub(crate) async fn _get_graph_interval(
+    async fn get_addresses(&self, addresses: Vec<Address>) -> RpcResult<Vec<AddressInfo>> {
+        self.http_client()?
+            .request("get_addresses", rpc_params![addresses])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    async fn get_addresses_bytecode(
         &self,
-        time_interval: TimeInterval,
-    ) -> RpcResult<Vec<BlockSummary>> {
-        self.http_client
-            .request("get_graph_interval", rpc_params![time_interval])
+        filters: Vec<AddressFilter>,
+    ) -> RpcResult<Vec<Option<Vec<u8>>>> {
+        self.http_client()?
+            .request("get_addresses_bytecode", rpc_params![filters])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Get info by addresses
-    pub async fn get_addresses(&self, addresses: Vec<Address>) -> RpcResult<Vec<AddressInfo>> {
-        self.http_client
-            .request("get_addresses", rpc_params![addresses])
+    async fn get_deferred_credits(
+        &self,
+        request: DeferredCreditsRequest,
+        page_request: Option<PageRequest>,
+    ) -> RpcResult<Vec<DeferredCredit>> {
+        self.http_client()?
+            .request("get_deferred_credits", rpc_params![request, page_request])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// Get datastore entries
-    pub async fn get_datastore_entries(
+    async fn get_slashing_stats(
+        &self,
+        min_cycle: u64,
+        max_cycle: u64,
+    ) -> RpcResult<Vec<SlashingStats>> {
+        self.http_client()?
+            .request("get_slashing_stats", rpc_params![min_cycle, max_cycle])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    async fn submit_denunciation_evidence(
+        &self,
+        evidences: Vec<DenunciationEvidence>,
+    ) -> RpcResult<Vec<EvidenceOutcome>> {
+        self.http_client()?
+            .request("submit_denunciation_evidence", rpc_params![evidences])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    async fn get_slot_execution_budget(&self, slot: Slot) -> RpcResult<SlotExecutionBudget> {
+        self.http_client()?
+            .request("get_slot_execution_budget", rpc_params![slot])
+            .await
+            .map_err(|e| to_error_obj(e.to_string()))
+    }
+
+    async fn get_datastore_entries(
         &self,
         input: Vec<DatastoreEntryInput>,
     ) -> RpcResult<Vec<DatastoreEntryOutput>> {
-        self.http_client
+        self.http_client()?
             .request("get_datastore_entries", rpc_params![input])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    // User (interaction with the node)
-
-    /// Adds operations to pool. Returns operations that were ok and sent to pool.
-    pub async fn send_operations(
+    async fn send_operations(
         &self,
         operations: Vec<OperationInput>,
     ) -> RpcResult<Vec<OperationId>> {
-        self.http_client
+        self.http_client()?
             .request("send_operations", rpc_params![operations])
             .await
             .map_err(|e| to_error_obj(e.to_string()))
     }
 
-    /// execute read only bytecode
-    pub async fn execute_read_only_bytecode(
+    async fn execute_read_only_bytecode(
         &self,
         read_only_execution: ReadOnlyBytecodeExecution,
     ) -> RpcResult<ExecuteReadOnlyResponse> {
-        self.http_client
+        self.http_client()?
             .request::<Vec<ExecuteReadOnlyResponse>, Vec<Vec<ReadOnlyBytecodeExecution>>>(
                 "execute_read_only_bytecode",
                 vec![vec![read_only_execution]],
@@ -449,12 +977,11 @@ impl RpcClient {
             })
     }
 
-    /// execute read only SC call
-    pub async fn execute_read_only_call(
+    async fn execute_read_only_call(
         &self,
         read_only_execution: ReadOnlyCall,
     ) -> RpcResult<ExecuteReadOnlyResponse> {
-        self.http_client
+        self.http_client()?
             .request::<Vec<ExecuteReadOnlyResponse>, Vec<Vec<ReadOnlyCall>>>(
                 "execute_read_only_call",
                 vec![vec![read_only_execution]],
@@ -489,45 +1016,187 @@ impl ClientV2 {
     }
 }
 
+/// Result of a version compatibility check against a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompatibilityReport {
+    /// version reported by the node
+    pub node_version: Version,
+    /// version the client expects
+    pub expected_version: Version,
+    /// true if the node version is compatible with the expected one
+    pub compatible: bool,
+}
+
+impl std::fmt::Display for CompatibilityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "node version {} is {}compatible with expected version {}",
+            self.node_version,
+            if self.compatible { "" } else { "not " },
+            self.expected_version
+        )
+    }
+}
+
+/// Ordering of a sequenced notification relative to the ones received before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeqStatus {
+    /// the notification is the one expected
+    InOrder,
+    /// the notifications of the range were skipped: the node dropped them because the
+    /// client lagged behind, or they are yet to be received out of order
+    Gap(Range<u64>),
+    /// the notification was expected earlier: it comes after a later one
+    Reordered {
+        /// sequence number that was expected instead
+        expected: u64,
+    },
+}
+
+/// Checks the sequence numbers given by the node to the notifications of a subscription
+#[derive(Debug, Clone, Default)]
+pub struct SeqChecker {
+    next_seq: u64,
+}
+
+impl SeqChecker {
+    /// Checks the sequence number of the latest received notification
+    pub fn check(&mut self, seq: u64) -> SeqStatus {
+        match seq.cmp(&self.next_seq) {
+            std::cmp::Ordering::Equal => {
+                self.next_seq = seq.saturating_add(1);
+                SeqStatus::InOrder
+            }
+            std::cmp::Ordering::Greater => {
+                let gap = self.next_seq..seq;
+                self.next_seq = seq.saturating_add(1);
+                SeqStatus::Gap(gap)
+            }
+            std::cmp::Ordering::Less => SeqStatus::Reordered {
+                expected: self.next_seq,
+            },
+        }
+    }
+}
+
+/// Notification of a sequenced subscription, with its ordering
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeqNotification<T> {
+    /// sequence number given by the node
+    pub seq: u64,
+    /// the notification itself
+    pub item: T,
+    /// ordering of the notification relative to the ones received before it
+    pub status: SeqStatus,
+}
+
+/// Subscription whose notifications are numbered by the node.
+///
+/// The node numbers the notifications of each subscription from 0, the ones it drops for a
+/// subscriber lagging behind still consuming their numbers. The client checks the numbers:
+/// each notification comes with a [`SeqStatus`] telling the notifications missed before it,
+/// or that it was received out of order.
+pub struct SeqSubscription<T> {
+    subscription: Subscription<SubItem<T>>,
+    checker: SeqChecker,
+}
+
+impl<T: DeserializeOwned> SeqSubscription<T> {
+    fn new(subscription: Subscription<SubItem<T>>) -> Self {
+        SeqSubscription {
+            subscription,
+            checker: SeqChecker::default(),
+        }
+    }
+
+    /// Returns the next notification with its sequence number and ordering,
+    /// or `None` if the subscription was closed.
+    pub async fn next(&mut self) -> Option<Result<SeqNotification<T>, jsonrpsee::core::Error>> {
+        let notification = self.subscription.next().await?;
+        Some(notification.map(|SubItem { seq, item }| SeqNotification {
+            seq,
+            item,
+            status: self.checker.check(seq),
+        }))
+    }
+
+    /// Identifier of the underlying subscription
+    pub fn kind(&self) -> &SubscriptionKind {
+        self.subscription.kind()
+    }
+
+    /// Unsubscribe and consume the subscription
+    pub async fn unsubscribe(self) -> Result<(), jsonrpsee::core::Error> {
+        self.subscription.unsubscribe().await
+    }
+}
+
 /// Rpc V2 client
 pub struct RpcClientV2 {
-    http_client: Option<HttpClient<HttpBackend>>,
+    http_client: Option<HttpClient<Transport>>,
     ws_client: Option<WsClient>,
 }
 
 impl RpcClientV2 {
     /// Default constructor
+    ///
+    /// # Panics
+    /// If the client cannot be created. Use [`RpcClientV2::from_endpoint`] to handle the error.
     pub async fn from_url(
         socket_addr: SocketAddr,
         http_config: &HttpConfig,
         ws_config: &WsConfig,
     ) -> RpcClientV2 {
-        let http_url = format!("http://{}", socket_addr);
-        let ws_url = format!("ws://{}", socket_addr);
-
-        if http_config.enabled && !ws_config.enabled {
-            let http_client = http_client_from_url(&http_url, http_config);
-            return RpcClientV2 {
-                http_client: Some(http_client),
-                ws_client: None,
-            };
-        } else if !http_config.enabled && ws_config.enabled {
-            let ws_client = ws_client_from_url(&ws_url, ws_config).await;
-            return RpcClientV2 {
-                http_client: None,
-                ws_client: Some(ws_client),
-            };
-        } else if !http_config.enabled && !ws_config.enabled {
-            panic!("wrong client configuration, you can't disable both http and ws");
+        RpcClientV2::from_endpoint(
+            &Endpoint::TcpSocketAddr(socket_addr),
+            http_config,
+            ws_config,
+        )
+        .await
+        .unwrap_or_else(|e| panic!("wrong client configuration: {}", e))
+    }
+
+    /// Create a client reaching the API at `endpoint`.
+    ///
+    /// WebSocket is not available over a Unix domain socket nor through an HTTP proxy:
+    /// only HTTP must be enabled in these cases.
+    pub async fn from_endpoint(
+        endpoint: &Endpoint,
+        http_config: &HttpConfig,
+        ws_config: &WsConfig,
+    ) -> Result<RpcClientV2, ClientError> {
+        if !http_config.enabled && !ws_config.enabled {
+            return Err(ClientError::Build(
+                "you can't disable both http and ws".to_string(),
+            ));
         }
 
-        let http_client = http_client_from_url(&http_url, http_config);
-        let ws_client = ws_client_from_url(&ws_url, ws_config).await;
+        let http_client = if http_config.enabled {
+            Some(http_client_from_endpoint(
+                endpoint,
+                http_config,
+                tower::layer::util::Identity::new(),
+            )?)
+        } else {
+            None
+        };
 
-        RpcClientV2 {
-            http_client: Some(http_client),
-            ws_client: Some(ws_client),
-        }
+        let ws_client = if ws_config.enabled {
+            if http_config.proxy_url.is_some() {
+                return Err(ClientError::Proxy(
+                    "WebSocket is not supported through an HTTP proxy".to_string(),
+                ));
+            }
+            Some(ws_client_from_url(&endpoint.ws_url()?, ws_config).await?)
+        } else {
+            None
+        };
+
+        Ok(RpcClientV2 {
+            http_client,
+            ws_client,
+        })
     }
 
     ////////////////
@@ -566,12 +1235,27 @@ impl RpcClientV2 {
     /// Get Massa node version
     pub async fn get_version(&self) -> RpcResult<Version> {
         if let Some(client) = self.http_client.as_ref() {
-            client.request("get_version", rpc_params![]).await.unwrap()
+            client
+                .request("get_version", rpc_params![])
+                .await
+                .map_err(|e| to_error_obj(e.to_string()))
         } else {
             Err(to_error_obj("no Http client instance found".to_owned()))
         }
     }
 
+    /// Fetch the node version and check it against the `expected` one.
+    /// Versions are compatible if they share the same instance and major version,
+    /// a differing minor version is not a compatibility issue.
+    pub async fn check_compatibility(&self, expected: Version) -> RpcResult<CompatibilityReport> {
+        let node_version = self.get_version().await?;
+        Ok(CompatibilityReport {
+            compatible: node_version.is_compatible(&expected),
+            node_version,
+            expected_version: expected,
+        })
+    }
+
     /// New produced blocks
     pub async fn subscribe_new_blocks(
         &self,
@@ -639,9 +1323,150 @@ impl RpcClientV2 {
             Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
         }
     }
+
+    /// New produced blocks, blocks headers, filled blocks, operations and endorsements
+    /// multiplexed in a single subscription.
+    ///
+    /// Events of the same kind are received in the order the node broadcast them, but events
+    /// of different kinds are only interleaved on a best-effort basis: there is no global order
+    /// across kinds (an operation may for example be received after the block including it).
+    pub async fn subscribe_all(&self) -> Result<Subscription<NodeEvent>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe("subscribe_all", rpc_params![], "unsubscribe_all")
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
+
+    /// New produced blocks, numbered by the node.
+    pub async fn subscribe_new_blocks_seq(
+        &self,
+    ) -> Result<SeqSubscription<BlockInfo>, jsonrpsee::core::Error> {
+        self.subscribe_seq("subscribe_new_blocks_seq", "unsubscribe_new_blocks_seq")
+            .await
+    }
+
+    /// New produced blocks headers, numbered by the node.
+    pub async fn subscribe_new_blocks_headers_seq(
+        &self,
+    ) -> Result<SeqSubscription<SecureShare<BlockHeader, BlockId>>, jsonrpsee::core::Error> {
+        self.subscribe_seq(
+            "subscribe_new_blocks_headers_seq",
+            "unsubscribe_new_blocks_headers_seq",
+        )
+        .await
+    }
+
+    /// New produced blocks with operations content, numbered by the node.
+    pub async fn subscribe_new_filled_blocks_seq(
+        &self,
+    ) -> Result<SeqSubscription<FilledBlock>, jsonrpsee::core::Error> {
+        self.subscribe_seq(
+            "subscribe_new_filled_blocks_seq",
+            "unsubscribe_new_filled_blocks_seq",
+        )
+        .await
+    }
+
+    /// New produced blocks with operations content, resumed after the block of `resume`.
+    ///
+    /// Pass the token of the last block processed before a restart: the blocks it already saw
+    /// are skipped, and the slots missed in between are reported by [`ResumableBlocks::missed_slots`],
+    /// their blocks being fetched by [`ResumableBlocks::backfill`]. Without a token, all the new
+    /// blocks are returned.
+    pub async fn subscribe_new_filled_blocks_from(
+        &self,
+        resume: Option<ResumeToken>,
+    ) -> Result<ResumableBlocks, jsonrpsee::core::Error> {
+        self.subscribe_new_filled_blocks()
+            .await
+            .map(|subscription| ResumableBlocks::new(subscription, resume))
+    }
+
+    /// New produced operations, numbered by the node.
+    pub async fn subscribe_new_operations_seq(
+        &self,
+    ) -> Result<SeqSubscription<Operation>, jsonrpsee::core::Error> {
+        self.subscribe_seq(
+            "subscribe_new_operations_seq",
+            "unsubscribe_new_operations_seq",
+        )
+        .await
+    }
+
+    async fn subscribe_seq<T: DeserializeOwned>(
+        &self,
+        subscribe_method: &str,
+        unsubscribe_method: &str,
+    ) -> Result<SeqSubscription<T>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(subscribe_method, rpc_params![], unsubscribe_method)
+                .await
+                .map(SeqSubscription::new)
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
+
+    /// New produced operations matching at least one of the `filter` criteria.
+    /// The filter is applied by the node and must not be empty.
+    pub async fn subscribe_new_operations_filtered(
+        &self,
+        filter: OperationStreamFilter,
+    ) -> Result<Subscription<Operation>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(
+                    "subscribe_new_operations_filtered",
+                    rpc_params![filter],
+                    "unsubscribe_new_operations_filtered",
+                )
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
+
+    /// Admissions and removals of operations and endorsements in the pool of the node,
+    /// matching all the `filter` criteria (an empty criterion matches any event).
+    ///
+    /// The node does not wait for slow subscribers: the subscription ends if it lags too far behind.
+    pub async fn subscribe_pool_events(
+        &self,
+        filter: PoolEventFilter,
+    ) -> Result<Subscription<PoolEvent>, jsonrpsee::core::Error> {
+        if let Some(client) = self.ws_client.as_ref() {
+            client
+                .subscribe(
+                    "subscribe_pool_events",
+                    rpc_params![filter],
+                    "unsubscribe_pool_events",
+                )
+                .await
+        } else {
+            Err(to_error_obj("no WebSocket client instance found".to_owned()).into())
+        }
+    }
 }
 
-fn http_client_from_url(url: &str, http_config: &HttpConfig) -> HttpClient<HttpBackend> {
+/// Build an HTTP client whose requests go through `layer`, then through the transport
+fn http_client_from_endpoint<L>(
+    endpoint: &Endpoint,
+    http_config: &HttpConfig,
+    layer: L,
+) -> Result<HttpClient<L::Service>, ClientError>
+where
+    L: tower::Layer<Transport>,
+    L::Service: tower::Service<
+            hyper::Request<hyper::Body>,
+            Response = hyper::Response<hyper::Body>,
+            Error = jsonrpsee::http_client::transport::Error,
+        > + Clone,
+{
+    let transport = TransportLayer::new(endpoint, http_config)?;
     let mut builder = HttpClientBuilder::default()
         .max_request_size(http_config.client_config.max_request_body_size)
         .request_timeout(http_config.client_config.request_timeout.to_duration())
@@ -656,17 +1481,24 @@ fn http_client_from_url(url: &str, http_config: &HttpConfig) -> HttpClient<HttpB
     }
 
     builder
-        .build(url)
-        .unwrap_or_else(|_| panic!("unable to create Http client for {}", url))
+        .set_middleware(tower::ServiceBuilder::new().layer(layer).layer(transport))
+        .build(endpoint.http_url())
+        .map_err(|e| {
+            ClientError::Build(format!(
+                "unable to create Http client for {}: {}",
+                endpoint, e
+            ))
+        })
 }
 
-async fn ws_client_from_url(url: &str, ws_config: &WsConfig) -> WsClient
+async fn ws_client_from_url(url: &str, ws_config: &WsConfig) -> Result<WsClient, ClientError>
 where
     WsClient: SubscriptionClientT,
 {
     let mut builder = WsClientBuilder::default()
         .max_request_size(ws_config.client_config.max_request_body_size)
         .request_timeout(ws_config.client_config.request_timeout.to_duration())
+        .connection_timeout(ws_config.client_config.connect_timeout.to_duration())
         .max_concurrent_requests(ws_config.client_config.max_concurrent_requests)
         .id_format(get_id_kind(ws_config.client_config.id_kind.as_str()))
         .set_headers(get_headers(&ws_config.client_config.headers))
@@ -679,10 +1511,12 @@ where
         _ => {}
     }
 
-    builder
-        .build(url)
-        .await
-        .unwrap_or_else(|_| panic!("unable to create WebSocket client for {}", url))
+    builder.build(url).await.map_err(|e| {
+        ClientError::Build(format!(
+            "unable to create WebSocket client for {}: {}",
+            url, e
+        ))
+    })
 }
 
 fn get_id_kind(id_kind: &str) -> IdKind {
@@ -0,0 +1,315 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Resumption of the filled blocks subscription across process restarts.
+//!
+//! A consumer persists the [`ResumeToken`] of the last block it processed and passes it back
+//! when subscribing again. The blocks it already processed are skipped, and the slots missed
+//! while it was away or skipped by the stream are reported by [`ResumableBlocks::missed_slots`],
+//! to be backfilled with [`ResumableBlocks::backfill`].
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use jsonrpsee::core::client::{Subscription, SubscriptionKind};
+use jsonrpsee::core::RpcResult;
+use massa_models::block::{Block, FilledBlock};
+use massa_models::block_id::BlockId;
+use massa_models::prehash::PreHashSet;
+use massa_models::slot::Slot;
+use thiserror::Error;
+
+use crate::MassaRpc;
+
+/// Version of the format of the tokens produced by this client
+const RESUME_TOKEN_VERSION: u32 = 1;
+
+/// Number of periods below the last one received in a thread for which the ids of the
+/// received blocks are kept, to tell the late and forked blocks from the duplicates
+const SEEN_BLOCKS_PERIOD_WINDOW: u64 = 128;
+
+/// Position in the stream of filled blocks, from which a subscription can resume.
+///
+/// The blocks of different threads are not received in slot order, so the token holds
+/// the last period seen in each thread: a late block of an earlier slot is still delivered.
+///
+/// The token is opaque: persist its `Display` form and read it back with `FromStr`.
+/// Its format is versioned, so that a token persisted by a client stays readable by later ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumeToken {
+    /// last period seen in each thread, indexed by thread
+    periods: Vec<Option<u64>>,
+}
+
+/// Error when reading a persisted resume token
+#[derive(Error, Debug)]
+#[error("Invalid resume token: {0}")]
+pub struct ResumeTokenError(String);
+
+impl fmt::Display for ResumeToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.", RESUME_TOKEN_VERSION)?;
+        for (thread, period) in self.periods.iter().enumerate() {
+            if thread > 0 {
+                write!(f, ",")?;
+            }
+            if let Some(period) = period {
+                write!(f, "{}", period)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ResumeToken {
+    type Err = ResumeTokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ResumeTokenError(s.to_string());
+        let (version, periods) = s.split_once('.').ok_or_else(invalid)?;
+        if version.parse::<u32>().map_err(|_| invalid())? != RESUME_TOKEN_VERSION {
+            return Err(invalid());
+        }
+        let periods = periods
+            .split(',')
+            .map(|period| match period {
+                "" => Ok(None),
+                period => period.parse().map(Some).map_err(|_| invalid()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if periods.len() > u8::MAX as usize + 1 || periods.iter().all(Option::is_none) {
+            return Err(invalid());
+        }
+        Ok(ResumeToken { periods })
+    }
+}
+
+/// Filled block along with the token to persist once it is processed
+#[derive(Debug, Clone)]
+pub struct ResumableBlock {
+    /// token to resume after this block
+    pub token: ResumeToken,
+    /// the block is not later than a block already received in its thread:
+    /// it was received late or belongs to a fork, and did not move the token
+    pub late: bool,
+    /// the block itself
+    pub block: FilledBlock,
+}
+
+/// Consecutive periods of a thread without a received block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissedSlots {
+    /// thread of the slots
+    pub thread: u8,
+    /// periods of the slots
+    pub periods: Range<u64>,
+}
+
+impl MissedSlots {
+    /// The missed slots, sorted
+    pub fn slots(&self) -> impl Iterator<Item = Slot> + '_ {
+        self.periods
+            .clone()
+            .map(move |period| Slot::new(period, self.thread))
+    }
+}
+
+/// Filled blocks subscription resumed from a [`ResumeToken`]
+pub struct ResumableBlocks {
+    subscription: Subscription<FilledBlock>,
+    cursor: ResumeCursor,
+}
+
+impl ResumableBlocks {
+    pub(crate) fn new(
+        subscription: Subscription<FilledBlock>,
+        resume: Option<ResumeToken>,
+    ) -> Self {
+        ResumableBlocks {
+            subscription,
+            cursor: ResumeCursor::new(resume),
+        }
+    }
+
+    /// Returns the next block that was not seen before the resume token nor received already,
+    /// or `None` if the subscription was closed.
+    ///
+    /// The blocks received late or belonging to a fork are returned too, flagged as
+    /// [`late`](ResumableBlock::late). The duplicates are only detected within
+    /// the last 128 periods of each thread.
+    pub async fn next(&mut self) -> Option<Result<ResumableBlock, jsonrpsee::core::Error>> {
+        loop {
+            let block = match self.subscription.next().await? {
+                Ok(block) => block,
+                Err(e) => return Some(Err(e)),
+            };
+            if let Some((token, late)) = self
+                .cursor
+                .advance(block.header.content.slot, block.header.id)
+            {
+                return Some(Ok(ResumableBlock { token, late, block }));
+            }
+        }
+    }
+
+    /// Periods of each thread without a received block since the resume token, sorted by their
+    /// first slot: the ones missed before the first block received in the thread, and the ones
+    /// skipped by the stream since. The threads without a period in the token have no known
+    /// missed slots before their first block. A block received late removes its slot.
+    pub fn missed_slots(&self) -> Vec<MissedSlots> {
+        self.cursor.missed_slots()
+    }
+
+    /// Fetch the blocks of the blockclique at the first `max_slots` [`missed_slots`](Self::missed_slots),
+    /// sorted by slot, and remove these slots from the missed ones: call it again until there are
+    /// no missed slots left. The missed slots without a block in the blockclique are skipped.
+    pub async fn backfill(
+        &mut self,
+        rpc: &dyn MassaRpc,
+        max_slots: usize,
+    ) -> RpcResult<Vec<Block>> {
+        let mut slots: Vec<Slot> = self
+            .missed_slots()
+            .iter()
+            .flat_map(|missed| missed.slots().take(max_slots).collect::<Vec<_>>())
+            .collect();
+        slots.sort_unstable();
+        slots.truncate(max_slots);
+
+        let mut blocks = Vec::new();
+        for slot in &slots {
+            if let Some(block) = rpc.get_blockclique_block_by_slot(*slot).await? {
+                blocks.push(block);
+            }
+        }
+        for slot in slots {
+            self.cursor.remove_missed(slot);
+        }
+        Ok(blocks)
+    }
+
+    /// Identifier of the underlying subscription
+    pub fn kind(&self) -> &SubscriptionKind {
+        self.subscription.kind()
+    }
+
+    /// Unsubscribe and consume the subscription
+    pub async fn unsubscribe(self) -> Result<(), jsonrpsee::core::Error> {
+        self.subscription.unsubscribe().await
+    }
+}
+
+/// Position of a resumed subscription, thread by thread
+#[derive(Debug)]
+pub(crate) struct ResumeCursor {
+    /// last period of each thread in the token the subscription resumed from
+    resumed_from: Vec<Option<u64>>,
+    /// last period of the blocks received in each thread
+    last_seen: Vec<Option<u64>>,
+    /// ids of the blocks received in each thread by period, within the window of
+    /// `SEEN_BLOCKS_PERIOD_WINDOW` periods below the last one
+    seen_blocks: Vec<BTreeMap<u64, PreHashSet<BlockId>>>,
+    /// ranges of periods without a received block in each thread, from their start to their end
+    missed: Vec<BTreeMap<u64, u64>>,
+}
+
+impl ResumeCursor {
+    pub(crate) fn new(resume: Option<ResumeToken>) -> Self {
+        let resumed_from = resume.map(|token| token.periods).unwrap_or_default();
+        ResumeCursor {
+            last_seen: resumed_from.clone(),
+            seen_blocks: vec![BTreeMap::new(); resumed_from.len()],
+            missed: vec![BTreeMap::new(); resumed_from.len()],
+            resumed_from,
+        }
+    }
+
+    /// Record the block `block_id` of `slot`, returning the token to resume after it and whether
+    /// it was received late, or `None` if it was already seen.
+    /// The blocks of a thread are mostly received in period order, unlike the blocks of
+    /// different threads.
+    pub(crate) fn advance(&mut self, slot: Slot, block_id: BlockId) -> Option<(ResumeToken, bool)> {
+        let thread = slot.thread as usize;
+        if self.last_seen.len() <= thread {
+            self.last_seen.resize(thread + 1, None);
+            self.seen_blocks.resize(thread + 1, BTreeMap::new());
+            self.missed.resize(thread + 1, BTreeMap::new());
+        }
+        let resumed_from = self.resumed_from.get(thread).copied().flatten();
+        if resumed_from.is_some_and(|from| slot.period <= from) {
+            // processed before the token was persisted
+            return None;
+        }
+
+        let late = match self.last_seen[thread] {
+            Some(last) if slot.period <= last => {
+                if last - slot.period <= SEEN_BLOCKS_PERIOD_WINDOW
+                    && !self.seen_blocks[thread]
+                        .entry(slot.period)
+                        .or_default()
+                        .insert(block_id)
+                {
+                    return None;
+                }
+                self.remove_missed(slot);
+                true
+            }
+            last => {
+                if let Some(start) = last.map(|last| last.saturating_add(1)) {
+                    if start < slot.period {
+                        self.missed[thread].insert(start, slot.period);
+                    }
+                }
+                self.last_seen[thread] = Some(slot.period);
+                let seen_blocks = &mut self.seen_blocks[thread];
+                seen_blocks.entry(slot.period).or_default().insert(block_id);
+                *seen_blocks =
+                    seen_blocks.split_off(&slot.period.saturating_sub(SEEN_BLOCKS_PERIOD_WINDOW));
+                false
+            }
+        };
+        Some((
+            ResumeToken {
+                periods: self.last_seen.clone(),
+            },
+            late,
+        ))
+    }
+
+    /// Remove `slot` from the missed ones
+    pub(crate) fn remove_missed(&mut self, slot: Slot) {
+        let Some(missed) = self.missed.get_mut(slot.thread as usize) else {
+            return;
+        };
+        let Some((&start, &end)) = missed.range(..=slot.period).next_back() else {
+            return;
+        };
+        if slot.period >= end {
+            return;
+        }
+        missed.remove(&start);
+        if start < slot.period {
+            missed.insert(start, slot.period);
+        }
+        if slot.period + 1 < end {
+            missed.insert(slot.period + 1, end);
+        }
+    }
+
+    pub(crate) fn missed_slots(&self) -> Vec<MissedSlots> {
+        let mut missed: Vec<MissedSlots> = self
+            .missed
+            .iter()
+            .enumerate()
+            .flat_map(|(thread, ranges)| {
+                ranges.iter().map(move |(start, end)| MissedSlots {
+                    thread: thread as u8,
+                    periods: *start..*end,
+                })
+            })
+            .collect();
+        missed.sort_unstable_by_key(|missed| Slot::new(missed.periods.start, missed.thread));
+        missed
+    }
+}
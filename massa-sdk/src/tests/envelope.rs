@@ -0,0 +1,66 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use massa_api_exports::envelope::API_REVISION;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use super::rpc_client::get_http_config;
+use super::transport::read_request;
+use crate::{Endpoint, RpcClient};
+
+/// chain of the node served by `serve_enveloped`
+const CHAIN_ID: u64 = 77;
+
+/// answer one request with an enveloped `null` result, checking that the client asked for it
+async fn serve_enveloped() -> (String, JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let task = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (head, body) = read_request(&mut stream).await;
+        assert!(head.to_lowercase().contains("massa-envelope: 1\r\n"));
+        let request: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": {
+                "node_version": "TEST.1.0",
+                "chain_id": CHAIN_ID,
+                "api_revision": API_REVISION,
+                "result": null,
+            },
+        })
+        .to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\nmassa-envelope: 1\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+    });
+    (url, task)
+}
+
+#[tokio::test]
+async fn enveloped_response_is_unwrapped() {
+    let (url, server) = serve_enveloped().await;
+    let client =
+        RpcClient::from_endpoint_on_chain(&Endpoint::Url(url), &get_http_config(), CHAIN_ID)
+            .await
+            .unwrap();
+    client.stop_node().await.unwrap();
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn enveloped_response_from_another_chain_is_rejected() {
+    let (url, server) = serve_enveloped().await;
+    let client =
+        RpcClient::from_endpoint_on_chain(&Endpoint::Url(url), &get_http_config(), CHAIN_ID + 1)
+            .await
+            .unwrap();
+    let error = client.stop_node().await.unwrap_err();
+    assert!(error.to_string().contains("chain id mismatch"));
+    server.await.unwrap();
+}
@@ -0,0 +1,109 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use jsonrpsee::core::RpcResult;
+use massa_hash::Hash;
+use massa_models::address::Address;
+use massa_models::prehash::PreHashSet;
+
+use crate::{to_error_obj, verify_bytecode, MassaRpc, MockMassaRpc};
+
+/// Application logic written against the trait, as a downstream crate would do:
+/// ban the given ips unless one of the watched addresses is staking on the node.
+async fn ban_unless_staking(
+    rpc: &dyn MassaRpc,
+    watched: &[Address],
+    ips: Vec<IpAddr>,
+) -> RpcResult<bool> {
+    let staking = rpc.get_staking_addresses().await?;
+    if watched.iter().any(|address| staking.contains(address)) {
+        return Ok(false);
+    }
+    rpc.node_ban_by_ip(ips).await?;
+    Ok(true)
+}
+
+fn get_address() -> Address {
+    Address::from_str("AU12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x").unwrap()
+}
+
+#[tokio::test]
+async fn mocked_rpc_bans_when_not_staking() {
+    let ip = IpAddr::from_str("192.168.0.1").unwrap();
+    let mut rpc = MockMassaRpc::new();
+    rpc.expect_get_staking_addresses()
+        .times(1)
+        .returning(|| Ok(PreHashSet::default()));
+    rpc.expect_node_ban_by_ip()
+        .withf(move |ips| ips == &vec![ip])
+        .times(1)
+        .returning(|_| Ok(()));
+
+    let rpc: Box<dyn MassaRpc> = Box::new(rpc);
+    assert!(ban_unless_staking(rpc.as_ref(), &[get_address()], vec![ip])
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn mocked_rpc_skips_ban_when_staking() {
+    let mut rpc = MockMassaRpc::new();
+    rpc.expect_get_staking_addresses()
+        .times(1)
+        .returning(|| Ok(PreHashSet::from_iter([get_address()])));
+    rpc.expect_node_ban_by_ip().never();
+
+    assert!(!ban_unless_staking(&rpc, &[get_address()], vec![])
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+async fn mocked_rpc_error_is_forwarded() {
+    let mut rpc = MockMassaRpc::new();
+    rpc.expect_get_staking_addresses()
+        .returning(|| Err(to_error_obj("node unreachable".to_string())));
+
+    let err = ban_unless_staking(&rpc, &[], vec![]).await.unwrap_err();
+    assert_eq!(err.message(), "node unreachable");
+}
+
+/// Mocked node whose ledger holds a smart contract at `sc_address` and only user addresses elsewhere
+fn mocked_ledger(sc_address: Address, bytecode: Vec<u8>) -> MockMassaRpc {
+    let mut rpc = MockMassaRpc::new();
+    rpc.expect_get_addresses_bytecode()
+        .returning(move |filters| {
+            Ok(filters
+                .iter()
+                .map(|filter| (filter.address == sc_address).then(|| bytecode.clone()))
+                .collect())
+        });
+    rpc
+}
+
+#[tokio::test]
+async fn verify_bytecode_compares_hashes() {
+    let sc_address =
+        Address::from_str("AS12cx6BJHSrBPPSE86E6LYgYS44dvXoHW77cdPbTT8H41wm6xGN5").unwrap();
+    let bytecode = b"smart contract".to_vec();
+    let rpc = mocked_ledger(sc_address, bytecode.clone());
+
+    assert!(
+        verify_bytecode(&rpc, sc_address, &Hash::compute_from(&bytecode))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !verify_bytecode(&rpc, sc_address, &Hash::compute_from(b"other build"))
+            .await
+            .unwrap()
+    );
+    // a user address has no bytecode to match
+    assert!(
+        !verify_bytecode(&rpc, get_address(), &Hash::compute_from(&bytecode))
+            .await
+            .unwrap()
+    );
+}
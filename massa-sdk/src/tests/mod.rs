@@ -0,0 +1,6 @@
+mod envelope;
+mod mock_rpc;
+mod resume;
+mod rpc_client;
+mod seq;
+mod transport;
@@ -0,0 +1,243 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::net::SocketAddr;
+
+use jsonrpsee::server::{ServerBuilder, ServerHandle};
+use jsonrpsee::{RpcModule, SubscriptionMessage};
+use massa_hash::Hash;
+use massa_models::block::{Block, FilledBlock};
+use massa_models::block_header::{BlockHeader, BlockHeaderSerializer, SecuredHeader};
+use massa_models::block_id::BlockId;
+use massa_models::config::CHAINID;
+use massa_models::secure_share::SecureShareContent;
+use massa_models::slot::Slot;
+use massa_signature::KeyPair;
+
+use crate::resume::ResumeCursor;
+use crate::tests::rpc_client::get_http_config;
+use crate::{MissedSlots, MockMassaRpc, ResumeToken, RpcClientV2, WsConfig};
+
+pub(super) fn create_header(slot: Slot) -> SecuredHeader {
+    BlockHeader::new_verifiable(
+        BlockHeader {
+            current_version: 0,
+            announced_version: None,
+            slot,
+            parents: vec![
+                BlockId::generate_from_hash(Hash::compute_from("Genesis 0".as_bytes())),
+                BlockId::generate_from_hash(Hash::compute_from("Genesis 1".as_bytes())),
+            ],
+            operation_merkle_root: Hash::compute_from(&Vec::new()),
+            endorsements: Vec::new(),
+            denunciations: Vec::new(),
+        },
+        BlockHeaderSerializer::new(),
+        &KeyPair::generate(0).unwrap(),
+        *CHAINID,
+    )
+    .unwrap()
+}
+
+/// Serve a filled blocks subscription sending `blocks` in order
+async fn serve_filled_blocks(blocks: Vec<FilledBlock>) -> (SocketAddr, ServerHandle) {
+    let mut module = RpcModule::new(());
+    module
+        .register_subscription(
+            "subscribe_new_filled_blocks",
+            "new_filled_blocks",
+            "unsubscribe_new_filled_blocks",
+            move |_, pending, _| {
+                let blocks = blocks.clone();
+                async move {
+                    let sink = pending.accept().await?;
+                    for block in blocks {
+                        sink.send(SubscriptionMessage::from_json(&block)?).await?;
+                    }
+                    sink.closed().await;
+                    Ok(())
+                }
+            },
+        )
+        .unwrap();
+    let server = ServerBuilder::default()
+        .build("127.0.0.1:0")
+        .await
+        .expect("failed to build server");
+    let addr = server.local_addr().unwrap();
+    (addr, server.start(module))
+}
+
+fn block_id(name: &str) -> BlockId {
+    BlockId::generate_from_hash(Hash::compute_from(name.as_bytes()))
+}
+
+#[test]
+fn resume_token_round_trip() {
+    let mut cursor = ResumeCursor::new(None);
+    cursor.advance(Slot::new(12, 0), block_id("a")).unwrap();
+    let (token, _) = cursor.advance(Slot::new(11, 2), block_id("b")).unwrap();
+    let persisted = token.to_string();
+    assert_eq!(persisted, "1.12,,11");
+    assert_eq!(persisted.parse::<ResumeToken>().unwrap(), token);
+
+    // unknown versions and malformed tokens are rejected
+    for invalid in ["2.12,3", "1.12,x", "1.,", "1", ""] {
+        assert!(invalid.parse::<ResumeToken>().is_err());
+    }
+}
+
+#[test]
+fn resumed_cursor_tracks_each_thread() {
+    let resume: ResumeToken = "1.10,10".parse().unwrap();
+    let mut cursor = ResumeCursor::new(Some(resume));
+    assert!(cursor.missed_slots().is_empty());
+
+    assert_eq!(cursor.advance(Slot::new(10, 0), block_id("a")), None);
+    let (token, late) = cursor.advance(Slot::new(12, 0), block_id("b")).unwrap();
+    assert_eq!(token.to_string(), "1.12,10");
+    assert!(!late);
+
+    // a block of an earlier slot of another thread received late is still delivered
+    let (token, late) = cursor.advance(Slot::new(11, 1), block_id("c")).unwrap();
+    assert_eq!(token.to_string(), "1.12,11");
+    assert!(!late);
+    assert_eq!(cursor.advance(Slot::new(10, 1), block_id("d")), None);
+
+    // thread 0 missed its period 11, thread 1 did not miss any
+    assert_eq!(
+        cursor.missed_slots(),
+        vec![MissedSlots {
+            thread: 0,
+            periods: 11..12
+        }]
+    );
+}
+
+#[test]
+fn resumed_cursor_delivers_late_and_forked_blocks() {
+    let resume: ResumeToken = "1.10".parse().unwrap();
+    let mut cursor = ResumeCursor::new(Some(resume));
+    cursor.advance(Slot::new(11, 0), block_id("a")).unwrap();
+    let (token, _) = cursor.advance(Slot::new(15, 0), block_id("b")).unwrap();
+    assert_eq!(
+        cursor.missed_slots(),
+        vec![MissedSlots {
+            thread: 0,
+            periods: 12..15
+        }]
+    );
+
+    // a late block fills its slot without moving the token
+    let (late_token, late) = cursor.advance(Slot::new(13, 0), block_id("c")).unwrap();
+    assert!(late);
+    assert_eq!(late_token, token);
+    assert_eq!(
+        cursor.missed_slots(),
+        vec![
+            MissedSlots {
+                thread: 0,
+                periods: 12..13
+            },
+            MissedSlots {
+                thread: 0,
+                periods: 14..15
+            }
+        ]
+    );
+
+    // a block of a fork at an already seen slot is delivered, not its duplicates
+    let (_, late) = cursor.advance(Slot::new(15, 0), block_id("d")).unwrap();
+    assert!(late);
+    assert_eq!(cursor.advance(Slot::new(15, 0), block_id("d")), None);
+    assert_eq!(cursor.advance(Slot::new(13, 0), block_id("c")), None);
+}
+
+#[test]
+fn missed_slots_are_ranges() {
+    let resume: ResumeToken = "1.10".parse().unwrap();
+    let mut cursor = ResumeCursor::new(Some(resume));
+    cursor
+        .advance(Slot::new(10_000_000, 0), block_id("a"))
+        .unwrap();
+    let missed = cursor.missed_slots();
+    assert_eq!(
+        missed,
+        vec![MissedSlots {
+            thread: 0,
+            periods: 11..10_000_000
+        }]
+    );
+    assert_eq!(missed[0].slots().next(), Some(Slot::new(11, 0)));
+}
+
+#[tokio::test]
+async fn resumed_subscription_skips_seen_blocks_and_backfills() {
+    let slots = [
+        Slot::new(10, 0),
+        Slot::new(12, 0),
+        Slot::new(10, 1),
+        Slot::new(11, 1),
+        Slot::new(13, 0),
+    ];
+    let blocks = slots
+        .iter()
+        .map(|slot| FilledBlock {
+            header: create_header(*slot),
+            operations: vec![],
+            locally_produced: false,
+        })
+        .collect();
+    let (addr, server_handle) = serve_filled_blocks(blocks).await;
+    let ws_config = WsConfig {
+        client_config: get_http_config().client_config,
+        enabled: true,
+        max_notifs_per_subscription: 128,
+        max_redirections: 5,
+    };
+    let client = RpcClientV2::from_url(addr, &get_http_config(), &ws_config).await;
+
+    let resume: ResumeToken = "1.10,10".parse().unwrap();
+    let mut subscription = client
+        .subscribe_new_filled_blocks_from(Some(resume))
+        .await
+        .unwrap();
+    let mut received = Vec::new();
+    let mut last_token = None;
+    while received.len() < 3 {
+        let block = subscription.next().await.unwrap().unwrap();
+        received.push(block.block.header.content.slot);
+        last_token = Some(block.token);
+    }
+    assert_eq!(
+        received,
+        vec![Slot::new(12, 0), Slot::new(11, 1), Slot::new(13, 0)]
+    );
+    assert_eq!(last_token.unwrap().to_string(), "1.13,11");
+    assert_eq!(
+        subscription.missed_slots(),
+        vec![MissedSlots {
+            thread: 0,
+            periods: 11..12
+        }]
+    );
+
+    // the blocks of the missed slots are fetched from the blockclique
+    let mut rpc = MockMassaRpc::new();
+    rpc.expect_get_blockclique_block_by_slot()
+        .withf(|slot| *slot == Slot::new(11, 0))
+        .times(1)
+        .returning(|slot| {
+            Ok(Some(Block {
+                header: create_header(slot),
+                operations: vec![],
+            }))
+        });
+    let backfilled = subscription.backfill(&rpc, 10).await.unwrap();
+    assert_eq!(backfilled.len(), 1);
+    assert_eq!(backfilled[0].header.content.slot, Slot::new(11, 0));
+    // the backfilled slots are no longer missed
+    assert!(subscription.missed_slots().is_empty());
+
+    subscription.unsubscribe().await.unwrap();
+    server_handle.stop().unwrap();
+}
@@ -0,0 +1,289 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use jsonrpsee::server::{ServerBuilder, ServerHandle};
+use jsonrpsee::types::ErrorObjectOwned;
+use jsonrpsee::RpcModule;
+use massa_api_exports::node::NodeStatus;
+use massa_models::amount::Amount;
+use massa_models::config::{CompactConfig, VERSION};
+use massa_models::node::NodeId;
+use massa_models::slot::Slot;
+use massa_models::stats::{ConsensusStats, ExecutionStats, NetworkStats};
+use massa_models::version::Version;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+
+use crate::{
+    Client, ClientConfig, ClientError, HttpConfig, MassaRpc, RpcClient, RpcClientV2, WsConfig,
+    PRIVATE_API_UNAVAILABLE_CODE,
+};
+
+pub(super) fn get_http_config() -> HttpConfig {
+    HttpConfig {
+        client_config: ClientConfig {
+            max_request_body_size: 52428800,
+            request_timeout: MassaTime::from_millis(10000),
+            connect_timeout: MassaTime::from_millis(3000),
+            max_concurrent_requests: 100,
+            certificate_store: "Native".to_string(),
+            id_kind: "Number".to_string(),
+            max_log_length: 4096,
+            headers: vec![],
+        },
+        enabled: true,
+        proxy_url: None,
+    }
+}
+
+#[tokio::test]
+async fn cloned_rpc_client_shared_between_tasks() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut module = RpcModule::new(calls.clone());
+    module
+        .register_method("stop_node", |_, calls| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok::<(), ErrorObjectOwned>(())
+        })
+        .unwrap();
+    let server = ServerBuilder::default()
+        .build("127.0.0.1:0")
+        .await
+        .expect("failed to build server");
+    let addr = server.local_addr().unwrap();
+    let server_handle = server.start(module);
+
+    let client = RpcClient::from_url(&format!("http://{}", addr), &get_http_config()).await;
+
+    let task_count = 50;
+    let mut handles = Vec::with_capacity(task_count);
+    for _ in 0..task_count {
+        let client = client.clone();
+        handles.push(tokio::spawn(async move { client.stop_node().await }));
+    }
+    for handle in handles {
+        handle.await.unwrap().unwrap();
+    }
+
+    // the original handle is still usable after its clones were dropped
+    client.stop_node().await.unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), task_count + 1);
+
+    server_handle.stop().unwrap();
+}
+
+#[tokio::test]
+async fn unavailable_private_api() {
+    let client = RpcClient::unavailable();
+    assert!(!client.is_available());
+
+    let err = client.stop_node().await.unwrap_err();
+    assert_eq!(err.code(), PRIVATE_API_UNAVAILABLE_CODE);
+    assert!(err.message().contains("Private API unavailable"));
+    let err = client.get_staking_addresses().await.unwrap_err();
+    assert_eq!(err.code(), PRIVATE_API_UNAVAILABLE_CODE);
+}
+
+/// Status of a node of chain `chain_id`, with the version of the client
+fn node_status(chain_id: u64) -> NodeStatus {
+    let now = MassaTime::now();
+    NodeStatus {
+        node_id: NodeId::new(KeyPair::generate(0).unwrap().get_public_key()),
+        node_ip: None,
+        version: *VERSION,
+        current_time: now,
+        current_cycle: 0,
+        current_cycle_time: now,
+        next_cycle_time: now,
+        connected_nodes: BTreeMap::new(),
+        last_slot: None,
+        next_slot: Slot::new(0, 0),
+        consensus_stats: ConsensusStats {
+            start_timespan: now,
+            end_timespan: now,
+            final_block_count: 0,
+            stale_block_count: 0,
+            clique_count: 0,
+        },
+        clique_count: 0,
+        fitness_gap_to_second_clique: None,
+        pool_stats: (0, 0),
+        network_stats: NetworkStats {
+            in_connection_count: 0,
+            out_connection_count: 0,
+            known_peer_count: 0,
+            banned_peer_count: 0,
+            active_node_count: 0,
+        },
+        network_traffic: None,
+        execution_stats: ExecutionStats {
+            time_window_start: now,
+            time_window_end: now,
+            final_block_count: 0,
+            final_executed_operations_count: 0,
+            active_cursor: Slot::new(0, 0),
+            final_cursor: Slot::new(0, 0),
+        },
+        config: CompactConfig::default(),
+        chain_id,
+        minimal_fees: Amount::default(),
+    }
+}
+
+#[tokio::test]
+async fn public_only_client() {
+    // a node serving only its public API
+    let mut module = RpcModule::new(node_status(77));
+    module
+        .register_method("get_status", |_, status| {
+            Ok::<NodeStatus, ErrorObjectOwned>(status.clone())
+        })
+        .unwrap();
+    let server = ServerBuilder::default()
+        .build("127.0.0.1:0")
+        .await
+        .expect("failed to build server");
+    let addr = server.local_addr().unwrap();
+    let server_handle = server.start(module);
+
+    let client = Client::new_public_only(addr.ip(), addr.port(), &get_http_config())
+        .await
+        .unwrap();
+    assert_eq!(client.chain_id, 77);
+    assert!(client.public.is_available());
+    assert_eq!(client.public.get_status().await.unwrap().chain_id, 77);
+
+    // the private calls fail without reaching any node
+    assert!(!client.private.is_available());
+    let err = client.private.stop_node().await.unwrap_err();
+    assert_eq!(err.code(), PRIVATE_API_UNAVAILABLE_CODE);
+
+    server_handle.stop().unwrap();
+}
+
+/// Serve the `get_status` of a node of chain `chain_id` running `version`
+async fn serve_status(chain_id: u64, version: Version) -> (SocketAddr, ServerHandle) {
+    let mut status = node_status(chain_id);
+    status.version = version;
+    let mut module = RpcModule::new(status);
+    module
+        .register_method("get_status", |_, status| {
+            Ok::<NodeStatus, ErrorObjectOwned>(status.clone())
+        })
+        .unwrap();
+    let server = ServerBuilder::default()
+        .build("127.0.0.1:0")
+        .await
+        .expect("failed to build server");
+    let addr = server.local_addr().unwrap();
+    (addr, server.start(module))
+}
+
+#[tokio::test]
+async fn connect_checks_the_node() {
+    // nothing listens on a port released right after binding it
+    let closed_port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let err = Client::connect(
+        IpAddr::V4(Ipv4Addr::LOCALHOST),
+        closed_port,
+        closed_port,
+        &get_http_config(),
+    )
+    .await
+    .err()
+    .unwrap();
+    assert!(matches!(err, ClientError::Unreachable(_)), "{}", err);
+
+    // a node of another major version is rejected
+    let version = VERSION.to_string();
+    let parts: Vec<&str> = version.split('.').collect();
+    let other_major: Version = format!(
+        "{}.{}.{}",
+        parts[0],
+        parts[1].parse::<u32>().unwrap() + 1,
+        parts[2]
+    )
+    .parse()
+    .unwrap();
+    let (addr, server_handle) = serve_status(77, other_major).await;
+    let err = Client::connect(addr.ip(), addr.port(), closed_port, &get_http_config())
+        .await
+        .err()
+        .unwrap();
+    match err {
+        ClientError::IncompatibleVersion { node, expected } => {
+            assert_eq!(node, other_major);
+            assert_eq!(expected, *VERSION);
+        }
+        err => panic!("unexpected error: {}", err),
+    }
+    server_handle.stop().unwrap();
+
+    // a compatible node gives its chain id, the private API is only reached on use
+    let (addr, server_handle) = serve_status(77, *VERSION).await;
+    let client = Client::connect(addr.ip(), addr.port(), closed_port, &get_http_config())
+        .await
+        .unwrap();
+    assert_eq!(client.chain_id, 77);
+    assert!(client.private.is_available());
+    server_handle.stop().unwrap();
+}
+
+#[tokio::test]
+async fn check_compatibility_compares_major_versions() {
+    let mut module = RpcModule::new(());
+    module
+        .register_method("get_version", |_, _| {
+            Ok::<Version, ErrorObjectOwned>("TEST.2.4".parse().unwrap())
+        })
+        .unwrap();
+    let server = ServerBuilder::default()
+        .build("127.0.0.1:0")
+        .await
+        .expect("failed to build server");
+    let addr = server.local_addr().unwrap();
+    let server_handle = server.start(module);
+
+    let ws_config = WsConfig {
+        client_config: get_http_config().client_config,
+        enabled: false,
+        max_notifs_per_subscription: 128,
+        max_redirections: 5,
+    };
+    let client = RpcClientV2::from_url(addr, &get_http_config(), &ws_config).await;
+
+    // a differing minor version is compatible
+    let report = client
+        .check_compatibility("TEST.2.1".parse().unwrap())
+        .await
+        .unwrap();
+    assert!(report.compatible);
+    assert_eq!(report.node_version.to_string(), "TEST.2.4");
+    assert_eq!(report.expected_version.to_string(), "TEST.2.1");
+
+    // a differing major version or instance is not
+    for expected in ["TEST.3.4", "PROD.2.4"] {
+        let report = client
+            .check_compatibility(expected.parse().unwrap())
+            .await
+            .unwrap();
+        assert!(!report.compatible);
+        assert_eq!(
+            report.to_string(),
+            format!(
+                "node version TEST.2.4 is not compatible with expected version {}",
+                expected
+            )
+        );
+    }
+
+    server_handle.stop().unwrap();
+}
@@ -0,0 +1,89 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use jsonrpsee::server::ServerBuilder;
+use jsonrpsee::{RpcModule, SubscriptionMessage};
+use massa_models::slot::Slot;
+
+use crate::tests::resume::create_header;
+use crate::tests::rpc_client::get_http_config;
+use crate::{RpcClientV2, SeqChecker, SeqStatus, SubItem, WsConfig};
+
+#[test]
+fn seq_checker_reports_gaps() {
+    let mut checker = SeqChecker::default();
+    assert_eq!(checker.check(0), SeqStatus::InOrder);
+    assert_eq!(checker.check(1), SeqStatus::InOrder);
+    assert_eq!(checker.check(4), SeqStatus::Gap(2..4));
+    assert_eq!(checker.check(5), SeqStatus::InOrder);
+}
+
+#[test]
+fn seq_checker_reports_reorders() {
+    let mut checker = SeqChecker::default();
+    assert_eq!(checker.check(0), SeqStatus::InOrder);
+    assert_eq!(checker.check(2), SeqStatus::Gap(1..2));
+    // the notification filling the gap comes late
+    assert_eq!(checker.check(1), SeqStatus::Reordered { expected: 3 });
+    assert_eq!(checker.check(3), SeqStatus::InOrder);
+}
+
+#[tokio::test]
+async fn seq_subscription_checks_node_numbers() {
+    let mut module = RpcModule::new(());
+    module
+        .register_subscription(
+            "subscribe_new_blocks_headers_seq",
+            "new_blocks_headers_seq",
+            "unsubscribe_new_blocks_headers_seq",
+            |_, pending, _| async move {
+                let sink = pending.accept().await?;
+                // the node dropped the notifications 1 and 2 for a lagging subscriber
+                for seq in [0, 3, 4] {
+                    let item = SubItem {
+                        seq,
+                        item: create_header(Slot::new(seq + 1, 0)),
+                    };
+                    sink.send(SubscriptionMessage::from_json(&item)?).await?;
+                }
+                sink.closed().await;
+                Ok(())
+            },
+        )
+        .unwrap();
+    let server = ServerBuilder::default()
+        .build("127.0.0.1:0")
+        .await
+        .expect("failed to build server");
+    let addr = server.local_addr().unwrap();
+    let server_handle = server.start(module);
+
+    let ws_config = WsConfig {
+        client_config: get_http_config().client_config,
+        enabled: true,
+        max_notifs_per_subscription: 128,
+        max_redirections: 5,
+    };
+    let client = RpcClientV2::from_url(addr, &get_http_config(), &ws_config).await;
+    let mut subscription = client.subscribe_new_blocks_headers_seq().await.unwrap();
+
+    let mut received = Vec::new();
+    for _ in 0..3 {
+        let notification = subscription.next().await.unwrap().unwrap();
+        assert_eq!(
+            notification.item.content.slot,
+            Slot::new(notification.seq + 1, 0)
+        );
+        received.push((notification.seq, notification.status));
+    }
+    assert_eq!(
+        received,
+        vec![
+            (0, SeqStatus::InOrder),
+            (3, SeqStatus::Gap(1..3)),
+            (4, SeqStatus::InOrder),
+        ]
+    );
+
+    subscription.unsubscribe().await.unwrap();
+    server_handle.stop().unwrap();
+}
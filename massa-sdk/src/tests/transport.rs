@@ -0,0 +1,175 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+use std::time::{Duration, Instant};
+
+use massa_time::MassaTime;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use super::rpc_client::get_http_config;
+use crate::{ClientError, Endpoint, RpcClient};
+
+/// credentials `user:secret` of the proxy
+const PROXY_AUTHORIZATION: &str = "proxy-authorization: Basic dXNlcjpzZWNyZXQ=";
+
+/// read an HTTP request, return its head and its body
+pub(super) async fn read_request<S: AsyncRead + Unpin>(stream: &mut S) -> (String, Vec<u8>) {
+    let mut data = Vec::new();
+    let head_end = loop {
+        if let Some(pos) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        let mut buf = [0u8; 1024];
+        let read = stream.read(&mut buf).await.unwrap();
+        assert!(read > 0, "connection closed before the end of the request");
+        data.extend_from_slice(&buf[..read]);
+    };
+    let head = String::from_utf8_lossy(&data[..head_end]).to_string();
+    let content_length = head
+        .lines()
+        .find_map(|line| {
+            line.to_lowercase()
+                .strip_prefix("content-length: ")
+                .map(str::to_string)
+        })
+        .map(|length| length.trim().parse::<usize>().unwrap())
+        .unwrap_or(0);
+    let mut body = data[head_end..].to_vec();
+    while body.len() < content_length {
+        let mut buf = [0u8; 1024];
+        let read = stream.read(&mut buf).await.unwrap();
+        assert!(read > 0, "connection closed before the end of the body");
+        body.extend_from_slice(&buf[..read]);
+    }
+    (head, body)
+}
+
+/// answer a JSON-RPC request with a `null` result
+async fn write_null_result<S: AsyncWrite + Unpin>(stream: &mut S, request_body: &[u8]) {
+    let request: serde_json::Value = serde_json::from_slice(request_body).unwrap();
+    let body =
+        serde_json::json!({"jsonrpc": "2.0", "result": null, "id": request["id"]}).to_string();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+}
+
+#[tokio::test]
+async fn http_request_through_proxy() {
+    let proxy = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut http_config = get_http_config();
+    http_config.proxy_url = Some(format!(
+        "http://user:secret@{}",
+        proxy.local_addr().unwrap()
+    ));
+
+    let proxy_task = tokio::spawn(async move {
+        let (mut stream, _) = proxy.accept().await.unwrap();
+        let (head, body) = read_request(&mut stream).await;
+        // the request is forwarded to the proxy in absolute-form, with the credentials
+        assert!(head.starts_with("POST http://node.invalid:33035/ HTTP/1.1\r\n"));
+        assert!(head.contains(PROXY_AUTHORIZATION));
+        write_null_result(&mut stream, &body).await;
+    });
+
+    let client = RpcClient::from_url("http://node.invalid:33035", &http_config).await;
+    client.stop_node().await.unwrap();
+    proxy_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn https_request_tunneled_through_proxy() {
+    let proxy = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut http_config = get_http_config();
+    http_config.client_config.certificate_store = "WebPki".to_string();
+    http_config.proxy_url = Some(format!(
+        "http://user:secret@{}",
+        proxy.local_addr().unwrap()
+    ));
+
+    let proxy_task = tokio::spawn(async move {
+        let (mut stream, _) = proxy.accept().await.unwrap();
+        let (head, _) = read_request(&mut stream).await;
+        assert!(head.starts_with("CONNECT node.invalid:33035 HTTP/1.1\r\n"));
+        assert!(head.contains(PROXY_AUTHORIZATION));
+        stream
+            .write_all(b"HTTP/1.1 403 Forbidden\r\ncontent-length: 0\r\n\r\n")
+            .await
+            .unwrap();
+    });
+
+    let client = RpcClient::from_url("https://node.invalid:33035", &http_config).await;
+    assert!(client.stop_node().await.is_err());
+    proxy_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn invalid_proxy_url() {
+    let mut http_config = get_http_config();
+    http_config.proxy_url = Some("socks5://127.0.0.1:1080".to_string());
+    let result = RpcClient::from_endpoint(
+        &Endpoint::Url("http://127.0.0.1:33035".to_string()),
+        &http_config,
+    )
+    .await;
+    assert!(matches!(result, Err(ClientError::Proxy(_))));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn http_request_over_unix_socket() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("node.sock");
+    let listener = tokio::net::UnixListener::bind(&path).unwrap();
+
+    let server_task = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (head, body) = read_request(&mut stream).await;
+        assert!(head.starts_with("POST / HTTP/1.1\r\n"));
+        write_null_result(&mut stream, &body).await;
+    });
+
+    let client = RpcClient::from_endpoint(&Endpoint::UnixSocket(path), &get_http_config())
+        .await
+        .unwrap();
+    client.stop_node().await.unwrap();
+    server_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn connect_timeout_applied() {
+    let mut http_config = get_http_config();
+    http_config.client_config.connect_timeout = MassaTime::from_millis(200);
+    http_config.client_config.request_timeout = MassaTime::from_millis(30000);
+    // non-routable address: the connection attempt hangs until it times out
+    let client = RpcClient::from_url("http://10.255.255.1:33035", &http_config).await;
+
+    let start = Instant::now();
+    let result = client.stop_node().await;
+    assert!(result.is_err());
+    // the request fails within the connect timeout, long before the request timeout
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn websocket_over_unix_socket_rejected() {
+    use crate::{RpcClientV2, WsConfig};
+
+    let ws_config = WsConfig {
+        client_config: get_http_config().client_config,
+        enabled: true,
+        max_notifs_per_subscription: 128,
+        max_redirections: 5,
+    };
+    let result = RpcClientV2::from_endpoint(
+        &Endpoint::UnixSocket("/tmp/node.sock".into()),
+        &get_http_config(),
+        &ws_config,
+    )
+    .await;
+    assert!(matches!(result, Err(ClientError::Endpoint(_))));
+}
@@ -0,0 +1,456 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! HTTP transport of the JSON-RPC clients.
+//!
+//! The HTTP backend of `jsonrpsee` is replaced by a `hyper` client, connecting within the
+//! `connect_timeout` of the configuration: directly, through the configured HTTP proxy, or
+//! through a Unix domain socket.
+
+use std::fmt::Display;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use base64::Engine;
+use http::header::PROXY_AUTHORIZATION;
+use http::uri::Scheme;
+use http::{HeaderValue, Uri};
+use hyper::client::connect::{Connected, Connection};
+use hyper::client::HttpConnector;
+use hyper::{Body, Request, Response};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use jsonrpsee::http_client::transport::{Error as TransportError, HttpBackend};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::net::TcpStream;
+use tower::{Layer, Service};
+
+use crate::{ClientError, HttpConfig};
+
+/// max size of the response of a proxy to a CONNECT request
+const MAX_CONNECT_RESPONSE_SIZE: usize = 8192;
+
+/// Location of a node API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Endpoint {
+    /// socket address reached over plain HTTP or WebSocket
+    TcpSocketAddr(SocketAddr),
+    /// full URL of the API, for example `https://node.example.com:33035`
+    Url(String),
+    /// path of the Unix domain socket the API listens on
+    #[cfg(unix)]
+    UnixSocket(PathBuf),
+}
+
+impl Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Endpoint::TcpSocketAddr(addr) => write!(f, "{}", addr),
+            Endpoint::Url(url) => write!(f, "{}", url),
+            #[cfg(unix)]
+            Endpoint::UnixSocket(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+impl Endpoint {
+    /// URL of the HTTP API
+    pub(crate) fn http_url(&self) -> String {
+        match self {
+            Endpoint::TcpSocketAddr(addr) => format!("http://{}", addr),
+            Endpoint::Url(url) => url.clone(),
+            // the host is not used to connect, only to build the requests
+            #[cfg(unix)]
+            Endpoint::UnixSocket(_) => "http://localhost".to_string(),
+        }
+    }
+
+    /// URL of the WebSocket API
+    pub(crate) fn ws_url(&self) -> Result<String, ClientError> {
+        match self {
+            Endpoint::TcpSocketAddr(addr) => Ok(format!("ws://{}", addr)),
+            Endpoint::Url(url) => {
+                if let Some(rest) = url.strip_prefix("http://") {
+                    Ok(format!("ws://{}", rest))
+                } else if let Some(rest) = url.strip_prefix("https://") {
+                    Ok(format!("wss://{}", rest))
+                } else {
+                    Ok(url.clone())
+                }
+            }
+            #[cfg(unix)]
+            Endpoint::UnixSocket(_) => Err(ClientError::Endpoint(format!(
+                "WebSocket is not supported over a Unix domain socket ({})",
+                self
+            ))),
+        }
+    }
+}
+
+/// Layer replacing the default HTTP backend
+#[derive(Clone)]
+pub(crate) struct TransportLayer {
+    transport: Transport,
+}
+
+impl TransportLayer {
+    /// Choose the transport to reach `endpoint` with the given configuration
+    pub(crate) fn new(endpoint: &Endpoint, http_config: &HttpConfig) -> Result<Self, ClientError> {
+        let connect_timeout = http_config.client_config.connect_timeout.to_duration();
+        match endpoint {
+            #[cfg(unix)]
+            Endpoint::UnixSocket(path) => {
+                if http_config.proxy_url.is_some() {
+                    return Err(ClientError::Endpoint(format!(
+                        "an HTTP proxy cannot be used to reach a Unix domain socket ({})",
+                        endpoint
+                    )));
+                }
+                let client = hyper::Client::builder().build(UnixConnector {
+                    path: path.clone(),
+                    connect_timeout,
+                });
+                return Ok(TransportLayer {
+                    transport: Transport::Unix(client),
+                });
+            }
+            Endpoint::TcpSocketAddr(_) | Endpoint::Url(_) => {}
+        }
+
+        let builder = HttpsConnectorBuilder::new();
+        let builder = match http_config.client_config.certificate_store.as_str() {
+            "WebPki" => builder.with_webpki_roots(),
+            _ => builder.with_native_roots(),
+        };
+        let builder = builder.https_or_http().enable_http1();
+
+        let Some(proxy_url) = &http_config.proxy_url else {
+            let mut connector = HttpConnector::new();
+            connector.set_connect_timeout(Some(connect_timeout));
+            connector.set_nodelay(true);
+            // the scheme is checked by the HTTPS connector wrapping it
+            connector.enforce_http(false);
+            return Ok(TransportLayer {
+                transport: Transport::Direct(
+                    hyper::Client::builder().build(builder.wrap_connector(connector)),
+                ),
+            });
+        };
+        let connector = ProxyConnector::from_url(proxy_url, connect_timeout)?;
+        let authorization = connector.authorization.clone();
+        let client = hyper::Client::builder().build(builder.wrap_connector(connector));
+        Ok(TransportLayer {
+            transport: Transport::Proxy {
+                client,
+                authorization,
+            },
+        })
+    }
+}
+
+impl Layer<HttpBackend> for TransportLayer {
+    type Service = Transport;
+
+    fn layer(&self, _backend: HttpBackend) -> Transport {
+        self.transport.clone()
+    }
+}
+
+/// Service sending the HTTP requests of the JSON-RPC client
+#[derive(Clone)]
+pub(crate) enum Transport {
+    /// straight to the node
+    Direct(hyper::Client<HttpsConnector<HttpConnector>>),
+    /// through an HTTP proxy
+    Proxy {
+        client: hyper::Client<HttpsConnector<ProxyConnector>>,
+        /// `Proxy-Authorization` header value, if the proxy requires credentials
+        authorization: Option<HeaderValue>,
+    },
+    /// through a Unix domain socket
+    #[cfg(unix)]
+    Unix(hyper::Client<UnixConnector>),
+}
+
+impl Service<Request<Body>> for Transport {
+    type Response = Response<Body>;
+    type Error = TransportError;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Body>, TransportError>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let response = match self {
+            Transport::Direct(client) => client.request(request),
+            Transport::Proxy {
+                client,
+                authorization,
+            } => {
+                // https requests are tunneled, the proxy only sees the CONNECT request
+                if let (Some(authorization), Some(&Scheme::HTTP)) =
+                    (authorization, request.uri().scheme())
+                {
+                    request
+                        .headers_mut()
+                        .insert(PROXY_AUTHORIZATION, authorization.clone());
+                }
+                client.request(request)
+            }
+            #[cfg(unix)]
+            Transport::Unix(client) => client.request(request),
+        };
+        Box::pin(async move {
+            response
+                .await
+                .map_err(|e| TransportError::Http(Box::new(e)))
+        })
+    }
+}
+
+/// Open a connection, failing with a `TimedOut` error if it takes longer than `timeout`
+async fn connect_timeout<S>(
+    timeout: Duration,
+    connect: impl Future<Output = io::Result<S>>,
+) -> io::Result<S> {
+    tokio::time::timeout(timeout, connect)
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "connection timed out"))?
+}
+
+/// Connector opening the connections through an HTTP proxy
+#[derive(Clone)]
+pub(crate) struct ProxyConnector {
+    /// `host:port` of the proxy
+    proxy_address: String,
+    /// `Proxy-Authorization` header value, if the proxy URL contains credentials
+    authorization: Option<HeaderValue>,
+    /// max duration of the connection to the proxy
+    connect_timeout: Duration,
+}
+
+impl ProxyConnector {
+    /// Parse a proxy URL of the form `http://[user:password@]host[:port]`
+    fn from_url(proxy_url: &str, connect_timeout: Duration) -> Result<Self, ClientError> {
+        let uri: Uri = proxy_url
+            .parse()
+            .map_err(|e| ClientError::Proxy(format!("invalid proxy url: {}", e)))?;
+        if uri.scheme() != Some(&Scheme::HTTP) {
+            return Err(ClientError::Proxy(
+                "only http:// proxy urls are supported".to_string(),
+            ));
+        }
+        let authority = uri
+            .authority()
+            .ok_or_else(|| ClientError::Proxy("missing proxy host".to_string()))?;
+        let authorization = match authority.as_str().rsplit_once('@') {
+            Some((credentials, _)) => Some(
+                HeaderValue::from_str(&format!(
+                    "Basic {}",
+                    base64::engine::general_purpose::STANDARD.encode(credentials)
+                ))
+                .map_err(|e| ClientError::Proxy(format!("invalid proxy credentials: {}", e)))?,
+            ),
+            None => None,
+        };
+        Ok(ProxyConnector {
+            proxy_address: format!(
+                "{}:{}",
+                authority.host(),
+                authority.port_u16().unwrap_or(80)
+            ),
+            authorization,
+            connect_timeout,
+        })
+    }
+
+    /// Ask the proxy to open a tunnel to `target`
+    async fn connect_tunnel(&self, stream: &mut TcpStream, target: &Uri) -> io::Result<()> {
+        let host = target
+            .host()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "missing target host"))?;
+        let target_address = format!("{}:{}", host, target.port_u16().unwrap_or(443));
+        let mut request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n", target_address);
+        if let Some(authorization) = &self.authorization {
+            request.push_str("Proxy-Authorization: ");
+            request.push_str(authorization.to_str().unwrap_or_default());
+            request.push_str("\r\n");
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        // read the response head byte by byte so that nothing of the tunneled stream is consumed
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() >= MAX_CONNECT_RESPONSE_SIZE {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "proxy CONNECT response too large",
+                ));
+            }
+            response.push(stream.read_u8().await?);
+        }
+        let status_line = String::from_utf8_lossy(&response);
+        let status_line = status_line.lines().next().unwrap_or_default();
+        match status_line.split_whitespace().nth(1) {
+            Some(status) if status.starts_with('2') => Ok(()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("proxy refused the tunnel: {}", status_line),
+            )),
+        }
+    }
+}
+
+impl Service<Uri> for ProxyConnector {
+    type Response = ProxyStream;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<ProxyStream>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, target: Uri) -> Self::Future {
+        let connector = self.clone();
+        Box::pin(async move {
+            let mut stream = connect_timeout(
+                connector.connect_timeout,
+                TcpStream::connect(&connector.proxy_address),
+            )
+            .await?;
+            stream.set_nodelay(true)?;
+            if target.scheme() == Some(&Scheme::HTTPS) {
+                // TLS is negotiated with the node through the tunnel
+                connector.connect_tunnel(&mut stream, &target).await?;
+                Ok(ProxyStream {
+                    stream,
+                    proxied: false,
+                })
+            } else {
+                // plain requests are sent to the proxy in absolute-form
+                Ok(ProxyStream {
+                    stream,
+                    proxied: true,
+                })
+            }
+        })
+    }
+}
+
+/// Connection to an HTTP proxy
+pub(crate) struct ProxyStream {
+    stream: TcpStream,
+    /// whether requests are forwarded by the proxy rather than tunneled
+    proxied: bool,
+}
+
+impl Connection for ProxyStream {
+    fn connected(&self) -> Connected {
+        Connected::new().proxy(self.proxied)
+    }
+}
+
+impl AsyncRead for ProxyStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for ProxyStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().stream).poll_shutdown(cx)
+    }
+}
+
+/// Connector opening the connections to a Unix domain socket, whatever the request URI
+#[cfg(unix)]
+#[derive(Clone)]
+pub(crate) struct UnixConnector {
+    path: PathBuf,
+    /// max duration of the connection to the socket
+    connect_timeout: Duration,
+}
+
+#[cfg(unix)]
+impl Service<Uri> for UnixConnector {
+    type Response = UnixStream;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<UnixStream>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _target: Uri) -> Self::Future {
+        let path = self.path.clone();
+        let timeout = self.connect_timeout;
+        Box::pin(async move {
+            Ok(UnixStream(
+                connect_timeout(timeout, tokio::net::UnixStream::connect(path)).await?,
+            ))
+        })
+    }
+}
+
+/// Connection to a Unix domain socket
+#[cfg(unix)]
+pub(crate) struct UnixStream(tokio::net::UnixStream);
+
+#[cfg(unix)]
+impl Connection for UnixStream {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+#[cfg(unix)]
+impl AsyncRead for UnixStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_read(cx, buf)
+    }
+}
+
+#[cfg(unix)]
+impl AsyncWrite for UnixStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().0).poll_shutdown(cx)
+    }
+}
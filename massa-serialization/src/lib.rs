@@ -17,6 +17,10 @@ use num::rational::Ratio;
 use num::Integer;
 use thiserror::Error;
 
+mod streaming;
+
+pub use streaming::{DeserializerIterator, StreamingDeserializeError, StreamingDeserializer};
+
 #[non_exhaustive]
 #[derive(Display, Error, Debug, Clone)]
 pub enum SerializeError {
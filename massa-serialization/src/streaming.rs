@@ -0,0 +1,398 @@
+//! Adapters driving a [`Deserializer`] over an [`io::Read`] source instead of a complete buffer,
+//! so that reading a stream of values only keeps one value in memory at a time.
+
+use std::io::{self, Read};
+use std::marker::PhantomData;
+
+use displaydoc::Display;
+use thiserror::Error;
+
+use crate::{DeserializeError, Deserializer};
+
+/// Max number of bytes requested from the source at once
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Max size of a varint encoded `u64`
+const MAX_VARINT_SIZE: usize = 10;
+
+#[non_exhaustive]
+#[derive(Display, Error, Debug)]
+pub enum StreamingDeserializeError {
+    /// IO error: {0}
+    IoError(#[from] io::Error),
+    /// Deserialization error: {0}
+    DeserializeError(String),
+    /// Value larger than the max buffer size of {0} bytes
+    BufferLimitExceeded(usize),
+    /// Stream ended in the middle of a value
+    UnexpectedEof,
+}
+
+/// Deserializes successive values `T` from a reader, reading more bytes while the buffered ones
+/// don't hold a whole value.
+///
+/// The deserializers of Massa parse complete buffers: a truncated value is usually reported as an
+/// error rather than as `nom::Err::Incomplete`. Both are therefore retried with more bytes, and an
+/// error is only returned once the source is exhausted or the buffer holds `max_buffer_size`
+/// bytes, which bounds the memory used by a value that never completes.
+pub struct StreamingDeserializer<T, DT, R>
+where
+    DT: Deserializer<T>,
+    R: Read,
+{
+    deserializer: DT,
+    reader: R,
+    buffer: Vec<u8>,
+    max_buffer_size: usize,
+    eof: bool,
+    failed: bool,
+    phantom_t: PhantomData<T>,
+}
+
+impl<T, DT, R> StreamingDeserializer<T, DT, R>
+where
+    DT: Deserializer<T>,
+    R: Read,
+{
+    /// Deserialize the values of `reader`, buffering at most `max_buffer_size` bytes
+    pub fn new(deserializer: DT, reader: R, max_buffer_size: usize) -> Self {
+        StreamingDeserializer {
+            deserializer,
+            reader,
+            buffer: Vec::new(),
+            max_buffer_size,
+            eof: false,
+            failed: false,
+            phantom_t: PhantomData,
+        }
+    }
+
+    /// Deserialize the next value, `None` if the source ended between two values
+    pub fn next_value(&mut self) -> Result<Option<T>, StreamingDeserializeError> {
+        loop {
+            if !self.buffer.is_empty() {
+                let error = match self
+                    .deserializer
+                    .deserialize::<DeserializeError>(&self.buffer)
+                {
+                    Ok((rest, value)) => {
+                        let consumed = self.buffer.len() - rest.len();
+                        self.buffer.drain(..consumed);
+                        return Ok(Some(value));
+                    }
+                    Err(nom::Err::Incomplete(_)) => StreamingDeserializeError::UnexpectedEof,
+                    Err(err) => StreamingDeserializeError::DeserializeError(err.to_string()),
+                };
+                if self.eof {
+                    return Err(error);
+                }
+            } else if self.eof {
+                return Ok(None);
+            }
+
+            if self.buffer.len() >= self.max_buffer_size {
+                return Err(StreamingDeserializeError::BufferLimitExceeded(
+                    self.max_buffer_size,
+                ));
+            }
+            self.fill_buffer()?;
+        }
+    }
+
+    /// Read the next bytes of the source, without exceeding `max_buffer_size`
+    fn fill_buffer(&mut self) -> Result<(), StreamingDeserializeError> {
+        let len = self.buffer.len();
+        let to_read = READ_CHUNK_SIZE.min(self.max_buffer_size - len);
+        self.buffer.resize(len + to_read, 0);
+        let read = loop {
+            match self.reader.read(&mut self.buffer[len..]) {
+                Ok(read) => break read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    self.buffer.truncate(len);
+                    return Err(err.into());
+                }
+            }
+        };
+        self.buffer.truncate(len + read);
+        if read == 0 {
+            self.eof = true;
+        }
+        Ok(())
+    }
+}
+
+impl<T, DT, R> Iterator for StreamingDeserializer<T, DT, R>
+where
+    DT: Deserializer<T>,
+    R: Read,
+{
+    type Item = Result<T, StreamingDeserializeError>;
+
+    /// Stops after the first error
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.next_value().transpose();
+        if matches!(result, Some(Err(_))) {
+            self.failed = true;
+        }
+        result
+    }
+}
+
+/// Deserializes successive values `T` from a reader where each value is prefixed by its size as
+/// a varint `u64`.
+///
+/// Only one value is buffered at a time, and a value announced larger than `max_value_size` is
+/// rejected before being read.
+pub struct DeserializerIterator<T, DT, R>
+where
+    DT: Deserializer<T>,
+    R: Read,
+{
+    deserializer: DT,
+    reader: R,
+    max_value_size: usize,
+    failed: bool,
+    phantom_t: PhantomData<T>,
+}
+
+impl<T, DT, R> DeserializerIterator<T, DT, R>
+where
+    DT: Deserializer<T>,
+    R: Read,
+{
+    /// Deserialize the values of `reader`, each at most `max_value_size` bytes long
+    pub fn new(deserializer: DT, reader: R, max_value_size: usize) -> Self {
+        DeserializerIterator {
+            deserializer,
+            reader,
+            max_value_size,
+            failed: false,
+            phantom_t: PhantomData,
+        }
+    }
+
+    /// Deserialize the next value, `None` if the source ended between two values
+    pub fn next_value(&mut self) -> Result<Option<T>, StreamingDeserializeError> {
+        let Some(size) = self.read_size()? else {
+            return Ok(None);
+        };
+        if size > self.max_value_size as u64 {
+            return Err(StreamingDeserializeError::BufferLimitExceeded(
+                self.max_value_size,
+            ));
+        }
+        let mut buffer = vec![0u8; size as usize];
+        self.reader.read_exact(&mut buffer).map_err(|err| {
+            if err.kind() == io::ErrorKind::UnexpectedEof {
+                StreamingDeserializeError::UnexpectedEof
+            } else {
+                err.into()
+            }
+        })?;
+        let (rest, value) = self
+            .deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .map_err(|err| StreamingDeserializeError::DeserializeError(err.to_string()))?;
+        if !rest.is_empty() {
+            return Err(StreamingDeserializeError::DeserializeError(format!(
+                "{} bytes left after the value",
+                rest.len()
+            )));
+        }
+        Ok(Some(value))
+    }
+
+    /// Read the varint size prefix of the next value, `None` if the source is exhausted
+    fn read_size(&mut self) -> Result<Option<u64>, StreamingDeserializeError> {
+        let mut prefix = [0u8; MAX_VARINT_SIZE];
+        for len in 0..MAX_VARINT_SIZE {
+            let mut byte = [0u8; 1];
+            loop {
+                match self.reader.read(&mut byte) {
+                    Ok(0) if len == 0 => return Ok(None),
+                    Ok(0) => return Err(StreamingDeserializeError::UnexpectedEof),
+                    Ok(_) => break,
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            prefix[len] = byte[0];
+            if !unsigned_varint::decode::is_last(byte[0]) {
+                continue;
+            }
+            let (size, _) = unsigned_varint::decode::u64(&prefix[..=len]).map_err(|err| {
+                StreamingDeserializeError::DeserializeError(format!("invalid value size: {}", err))
+            })?;
+            return Ok(Some(size));
+        }
+        Err(StreamingDeserializeError::DeserializeError(
+            "value size prefix too long".to_string(),
+        ))
+    }
+}
+
+impl<T, DT, R> Iterator for DeserializerIterator<T, DT, R>
+where
+    DT: Deserializer<T>,
+    R: Read,
+{
+    type Item = Result<T, StreamingDeserializeError>;
+
+    /// Stops after the first error
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let result = self.next_value().transpose();
+        if matches!(result, Some(Err(_))) {
+            self.failed = true;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        OptionDeserializer, OptionSerializer, Serializer, U64VarIntDeserializer,
+        U64VarIntSerializer,
+    };
+    use std::ops::Bound::Included;
+
+    /// Reader returning at most one byte per read
+    struct OneByteReader<'a>(&'a [u8]);
+
+    impl Read for OneByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match (self.0.split_first(), buf.first_mut()) {
+                (Some((first, rest)), Some(byte)) => {
+                    *byte = *first;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    /// Reader returning the same bytes forever
+    struct RepeatReader(u8);
+
+    impl Read for RepeatReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            buf.fill(self.0);
+            Ok(buf.len())
+        }
+    }
+
+    fn deserializer() -> OptionDeserializer<u64, U64VarIntDeserializer> {
+        OptionDeserializer::new(U64VarIntDeserializer::new(Included(0), Included(u64::MAX)))
+    }
+
+    fn values() -> Vec<Option<u64>> {
+        vec![Some(3), None, Some(60_500), Some(u64::MAX), None, Some(0)]
+    }
+
+    #[test]
+    fn test_streaming_deserializer_one_byte_reads() {
+        let serializer = OptionSerializer::new(U64VarIntSerializer::new());
+        let mut buffer = Vec::new();
+        for value in values() {
+            serializer.serialize(&value, &mut buffer).unwrap();
+        }
+
+        // same values as parsing the whole buffer
+        let mut expected = Vec::new();
+        let mut rest = &buffer[..];
+        while !rest.is_empty() {
+            let (new_rest, value) = deserializer()
+                .deserialize::<DeserializeError>(rest)
+                .unwrap();
+            expected.push(value);
+            rest = new_rest;
+        }
+        assert_eq!(expected, values());
+
+        let streamed: Vec<Option<u64>> =
+            StreamingDeserializer::new(deserializer(), OneByteReader(&buffer), 16)
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(streamed, expected);
+    }
+
+    #[test]
+    fn test_streaming_deserializer_truncated_stream() {
+        let serializer = OptionSerializer::new(U64VarIntSerializer::new());
+        let mut buffer = Vec::new();
+        serializer.serialize(&Some(3), &mut buffer).unwrap();
+        serializer.serialize(&Some(u64::MAX), &mut buffer).unwrap();
+        buffer.pop();
+
+        let mut streaming = StreamingDeserializer::new(deserializer(), OneByteReader(&buffer), 16);
+        assert_eq!(streaming.next().unwrap().unwrap(), Some(3));
+        assert!(matches!(
+            streaming.next(),
+            Some(Err(StreamingDeserializeError::DeserializeError(_)))
+        ));
+        assert!(streaming.next().is_none());
+    }
+
+    #[test]
+    fn test_streaming_deserializer_max_buffer_size() {
+        // a `Some` followed by varint continuation bytes never completes
+        let reader = io::Cursor::new(b"1").chain(RepeatReader(0x80));
+        let mut streaming = StreamingDeserializer::new(deserializer(), reader, 32);
+        assert!(matches!(
+            streaming.next(),
+            Some(Err(StreamingDeserializeError::BufferLimitExceeded(32)))
+        ));
+        assert!(streaming.next().is_none());
+    }
+
+    #[test]
+    fn test_deserializer_iterator_one_byte_reads() {
+        let serializer = OptionSerializer::new(U64VarIntSerializer::new());
+        let size_serializer = U64VarIntSerializer::new();
+        let mut buffer = Vec::new();
+        for value in values() {
+            let mut value_buffer = Vec::new();
+            serializer.serialize(&value, &mut value_buffer).unwrap();
+            size_serializer
+                .serialize(&(value_buffer.len() as u64), &mut buffer)
+                .unwrap();
+            buffer.extend(value_buffer);
+        }
+
+        let streamed: Vec<Option<u64>> =
+            DeserializerIterator::new(deserializer(), OneByteReader(&buffer), 16)
+                .collect::<Result<_, _>>()
+                .unwrap();
+        assert_eq!(streamed, values());
+    }
+
+    #[test]
+    fn test_deserializer_iterator_max_value_size() {
+        let mut buffer = Vec::new();
+        U64VarIntSerializer::new()
+            .serialize(&1_000_000, &mut buffer)
+            .unwrap();
+        let mut iterator = DeserializerIterator::new(deserializer(), OneByteReader(&buffer), 16);
+        assert!(matches!(
+            iterator.next(),
+            Some(Err(StreamingDeserializeError::BufferLimitExceeded(16)))
+        ));
+        assert!(iterator.next().is_none());
+
+        // a size prefix that never ends is rejected as well
+        let mut iterator = DeserializerIterator::new(deserializer(), RepeatReader(0x80), 16);
+        assert!(matches!(
+            iterator.next(),
+            Some(Err(StreamingDeserializeError::DeserializeError(_)))
+        ));
+    }
+}
@@ -0,0 +1,99 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use massa_factory_exports::test_exports::create_empty_block;
+use massa_models::{
+    amount::Amount,
+    block_id::BlockId,
+    config::CHAINID,
+    endorsement::{Endorsement, EndorsementId, EndorsementSerializer},
+    operation::{Operation, OperationId, OperationSerializer, OperationType},
+    prehash::PreHashSet,
+    secure_share::SecureShareContent,
+    slot::Slot,
+};
+use massa_signature::KeyPair;
+use massa_storage::Storage;
+
+/// Store a block along with its operations and endorsements, as received by the protocol
+fn prepare_storage(
+    operation_count: u64,
+    endorsement_count: u32,
+) -> (
+    Storage,
+    PreHashSet<BlockId>,
+    PreHashSet<OperationId>,
+    PreHashSet<EndorsementId>,
+) {
+    let keypair = KeyPair::generate(0).unwrap();
+    let block = create_empty_block(&keypair, &Slot::new(1, 0));
+    let operations: Vec<_> = (0..operation_count)
+        .map(|expire_period| {
+            Operation::new_verifiable(
+                Operation {
+                    fee: Amount::default(),
+                    op: OperationType::RollBuy { roll_count: 1 },
+                    expire_period,
+                },
+                OperationSerializer::new(),
+                &keypair,
+                *CHAINID,
+            )
+            .unwrap()
+        })
+        .collect();
+    let endorsements: Vec<_> = (0..endorsement_count)
+        .map(|index| {
+            Endorsement::new_verifiable(
+                Endorsement {
+                    slot: Slot::new(1, 0),
+                    index,
+                    endorsed_block: block.id,
+                },
+                EndorsementSerializer::new(),
+                &keypair,
+                *CHAINID,
+            )
+            .unwrap()
+        })
+        .collect();
+
+    let block_ids = vec![block.id].into_iter().collect();
+    let operation_ids = operations.iter().map(|op| op.id).collect();
+    let endorsement_ids = endorsements.iter().map(|endo| endo.id).collect();
+    let mut storage = Storage::create_root();
+    storage.store_block(block);
+    storage.store_operations(operations);
+    storage.store_endorsements(endorsements);
+    (storage, block_ids, operation_ids, endorsement_ids)
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let (storage, block_ids, operation_ids, endorsement_ids) = prepare_storage(1000, 16);
+
+    let mut group = c.benchmark_group("claim block with operations and endorsements");
+    group.bench_function("three calls", |b| {
+        b.iter_batched(
+            || storage.clone_without_refs(),
+            |mut claimer| {
+                black_box(claimer.claim_block_refs(&block_ids));
+                black_box(claimer.claim_operation_refs(&operation_ids));
+                black_box(claimer.claim_endorsement_refs(&endorsement_ids));
+                claimer
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.bench_function("claim_all", |b| {
+        b.iter_batched(
+            || storage.clone_without_refs(),
+            |mut claimer| {
+                black_box(claimer.claim_all(&block_ids, &operation_ids, &endorsement_ids));
+                claimer
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
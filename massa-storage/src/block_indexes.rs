@@ -1,4 +1,7 @@
-use std::{collections::btree_map, collections::hash_map, collections::BTreeMap, ops::RangeBounds};
+use std::{
+    collections::btree_map, collections::hash_map, collections::BTreeMap, collections::BTreeSet,
+    ops::RangeBounds,
+};
 
 use massa_models::{
     address::Address,
@@ -16,8 +19,8 @@ use massa_models::{
 pub struct BlockIndexes {
     /// Blocks structure container
     blocks: PreHashMap<BlockId, Box<SecureShareBlock>>,
-    /// Structure mapping creators with the created blocks
-    index_by_creator: PreHashMap<Address, PreHashSet<BlockId>>,
+    /// Structure mapping creators with the created blocks, sorted by slot
+    index_by_creator: PreHashMap<Address, BTreeSet<(Slot, BlockId)>>,
     /// Structure mapping slot with their block id
     index_by_slot: BTreeMap<Slot, PreHashSet<BlockId>>,
     /// Structure mapping operation id with ids of blocks they are contained in
@@ -38,7 +41,7 @@ impl BlockIndexes {
             self.index_by_creator
                 .entry(block.content_creator_address)
                 .or_default()
-                .insert(block.id);
+                .insert((block.content.header.content.slot, block.id));
 
             // update slot index
             self.index_by_slot
@@ -72,7 +75,7 @@ impl BlockIndexes {
             if let hash_map::Entry::Occupied(mut occ) =
                 self.index_by_creator.entry(b.content_creator_address)
             {
-                occ.get_mut().remove(&b.id);
+                occ.get_mut().remove(&(b.content.header.content.slot, b.id));
                 if occ.get().is_empty() {
                     occ.remove();
                 }
@@ -128,14 +131,41 @@ impl BlockIndexes {
         self.blocks.contains_key(id)
     }
 
-    /// Get the block ids created by an address.
+    /// Returns the number of stored blocks.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Checks whether no blocks are stored.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Get the block ids created by an address, ordered by slot.
     /// Arguments:
     /// - address: the address to get the blocks created by
+    /// - after: only return the blocks of the slots after this one, to resume from the last slot of a previous page
+    /// - limit: maximum number of block ids to return
     ///
     /// Returns:
-    /// - a reference to the block ids created by the address
-    pub fn get_blocks_created_by(&self, address: &Address) -> Option<&PreHashSet<BlockId>> {
-        self.index_by_creator.get(address)
+    /// - the ids of the blocks created by the address
+    ///
+    /// Note: only the blocks currently in storage are returned, storage is not a full archive.
+    pub fn get_blocks_created_by(
+        &self,
+        address: &Address,
+        after: Option<Slot>,
+        limit: usize,
+    ) -> Vec<BlockId> {
+        let Some(blocks) = self.index_by_creator.get(address) else {
+            return Vec::new();
+        };
+        blocks
+            .iter()
+            .skip_while(|(slot, _)| after.is_some_and(|after| *slot <= after))
+            .take(limit)
+            .map(|(_, id)| *id)
+            .collect()
     }
 
     /// Get the block ids of the blocks at a given slot.
@@ -0,0 +1,60 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use displaydoc::Display;
+
+/// Optional caps on the objects kept in storage, set at the creation of the root storage.
+/// A `None` cap leaves the category unbounded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StorageCaps {
+    /// maximum number of stored operations
+    pub max_operations: Option<usize>,
+    /// maximum number of stored endorsements
+    pub max_endorsements: Option<usize>,
+    /// maximum number of stored blocks
+    pub max_blocks: Option<usize>,
+    /// maximum estimated size in bytes of all the stored objects
+    pub max_bytes: Option<usize>,
+}
+
+/// Category of a storage cap
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageCategory {
+    /// operations
+    Operations,
+    /// endorsements
+    Endorsements,
+    /// blocks
+    Blocks,
+    /// bytes
+    Bytes,
+}
+
+/// Usage of a storage category
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageCategoryUsage {
+    /// number of stored objects, or stored bytes for `StorageCategory::Bytes`
+    pub stored: usize,
+    /// cap of the category, if any
+    pub max: Option<usize>,
+}
+
+impl StorageCategoryUsage {
+    /// Fraction of the cap in use, `None` if the category is unbounded.
+    /// Objects exempted from admission can bring it above 1.
+    pub fn utilization(&self) -> Option<f64> {
+        self.max.map(|max| self.stored as f64 / max.max(1) as f64)
+    }
+}
+
+/// Usage of each storage category
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StorageStats {
+    /// stored operations
+    pub operations: StorageCategoryUsage,
+    /// stored endorsements
+    pub endorsements: StorageCategoryUsage,
+    /// stored blocks
+    pub blocks: StorageCategoryUsage,
+    /// estimated size in bytes of all the stored objects
+    pub bytes: StorageCategoryUsage,
+}
@@ -4,6 +4,7 @@ use massa_models::{
     address::Address,
     endorsement::{EndorsementId, SecureShareEndorsement},
     prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
 };
 
 /// Container for all endorsements and different indexes.
@@ -66,6 +67,16 @@ impl EndorsementIndexes {
         self.endorsements.contains_key(id)
     }
 
+    /// Returns the number of stored endorsements.
+    pub fn len(&self) -> usize {
+        self.endorsements.len()
+    }
+
+    /// Checks whether no endorsements are stored.
+    pub fn is_empty(&self) -> bool {
+        self.endorsements.is_empty()
+    }
+
     /// Get endorsements created by an address
     /// Arguments:
     /// - address: the address to get the endorsements created by
@@ -78,4 +89,37 @@ impl EndorsementIndexes {
     ) -> Option<&PreHashSet<EndorsementId>> {
         self.index_by_creator.get(address)
     }
+
+    /// Get a page of the endorsements created by an address, ordered by slot then index.
+    /// Arguments:
+    /// - address: the address to get the endorsements created by
+    /// - offset: number of endorsements to skip
+    /// - limit: maximum number of endorsements to return
+    ///
+    /// Returns:
+    /// - the ids of the requested page of endorsements
+    ///
+    /// Note: only the endorsements currently in storage are returned, storage is not a full archive.
+    pub fn get_endorsements_created_by_paged(
+        &self,
+        address: &Address,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<EndorsementId> {
+        let Some(ids) = self.index_by_creator.get(address) else {
+            return Vec::new();
+        };
+        let mut ordered: Vec<(Slot, u32, EndorsementId)> = ids
+            .iter()
+            .filter_map(|id| self.endorsements.get(id))
+            .map(|e| (e.content.slot, e.content.index, e.id))
+            .collect();
+        ordered.sort_unstable();
+        ordered
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(_, _, id)| id)
+            .collect()
+    }
 }
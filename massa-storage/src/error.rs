@@ -0,0 +1,14 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use displaydoc::Display;
+use thiserror::Error;
+
+use crate::caps::StorageCategory;
+
+/// Reason why objects were not admitted in storage
+#[non_exhaustive]
+#[derive(Display, Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageAdmissionError {
+    /// storage full: storing the objects would exceed the cap on {0}
+    Full(StorageCategory),
+}
@@ -9,17 +9,22 @@
 #![warn(missing_docs)]
 
 mod block_indexes;
+mod caps;
 mod endorsement_indexes;
+mod error;
 mod operation_indexes;
 
 #[cfg(test)]
 mod tests;
 
 use block_indexes::BlockIndexes;
+pub use caps::{StorageCaps, StorageCategory, StorageCategoryUsage, StorageStats};
 use endorsement_indexes::EndorsementIndexes;
+pub use error::StorageAdmissionError;
 use massa_models::prehash::{CapacityAllocator, PreHashMap, PreHashSet, PreHashed};
 use massa_models::secure_share::Id;
 use massa_models::{
+    address::Address,
     block::SecureShareBlock,
     block_id::BlockId,
     endorsement::{EndorsementId, SecureShareEndorsement},
@@ -29,8 +34,12 @@ use operation_indexes::OperationIndexes;
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use std::fmt::Debug;
 use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::{collections::hash_map, sync::Arc};
 
+/// Default maximum number of orphaned objects removed from an index per write lock acquisition
+pub const DEFAULT_ORPHAN_REMOVAL_BATCH_SIZE: usize = 1024;
+
 /// A storage system for objects (blocks, operations...), shared by various components.
 pub struct Storage {
     /// global block storage
@@ -53,6 +62,63 @@ pub struct Storage {
     local_used_ops: PreHashSet<OperationId>,
     /// locally used endorsement references
     local_used_endorsements: PreHashSet<EndorsementId>,
+
+    /// maximum number of orphaned objects removed from an index before yielding its write lock
+    orphan_removal_batch_size: usize,
+
+    /// caps on the stored objects
+    caps: StorageCaps,
+    /// estimated size in bytes of all the stored objects
+    stored_bytes: Arc<AtomicUsize>,
+}
+
+/// Ids of the stored operations and endorsements created by an address
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CreatedByAddress {
+    /// operations created by the address
+    pub operations: PreHashSet<OperationId>,
+    /// endorsements created by the address
+    pub endorsements: PreHashSet<EndorsementId>,
+}
+
+/// References found and claimed by `Storage::claim_all`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClaimedRefs {
+    /// claimed block references
+    pub blocks: PreHashSet<BlockId>,
+    /// claimed operation references
+    pub operations: PreHashSet<OperationId>,
+    /// claimed endorsement references
+    pub endorsements: PreHashSet<EndorsementId>,
+}
+
+/// Discrepancies found by `Storage::audit_refs_against`
+/// between the operations a module claims to hold and the references of its storage instance
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RefAuditReport {
+    /// claimed but not referenced by the instance, while still in storage
+    pub missing_refs: PreHashSet<OperationId>,
+    /// claimed but not referenced by the instance, and no longer in storage
+    pub missing_objects: PreHashSet<OperationId>,
+    /// referenced by the instance but not claimed
+    pub unclaimed_refs: PreHashSet<OperationId>,
+    /// referenced by the instance but without any owner in storage
+    pub ownerless_refs: PreHashSet<OperationId>,
+}
+
+impl RefAuditReport {
+    /// true if no discrepancy was found
+    pub fn is_empty(&self) -> bool {
+        self.discrepancy_count() == 0
+    }
+
+    /// total number of discrepancies
+    pub fn discrepancy_count(&self) -> usize {
+        self.missing_refs.len()
+            + self.missing_objects.len()
+            + self.unclaimed_refs.len()
+            + self.ownerless_refs.len()
+    }
 }
 
 impl Debug for Storage {
@@ -98,6 +164,19 @@ impl Storage {
     ///
     /// All others instances of Storage must be cloned from this one using `clone()` or `clone_without_refs()`.
     pub fn create_root() -> Storage {
+        Storage::create_root_with_orphan_removal_batch_size(DEFAULT_ORPHAN_REMOVAL_BATCH_SIZE)
+    }
+
+    /// Same as `create_root` but with a custom maximum number of orphaned objects
+    /// removed from an index before its write lock is yielded to waiting threads.
+    /// The value is shared by all the instances cloned from the returned one.
+    pub fn create_root_with_orphan_removal_batch_size(batch_size: usize) -> Storage {
+        Storage::create_root_with_caps(batch_size, StorageCaps::default())
+    }
+
+    /// Same as `create_root_with_orphan_removal_batch_size` but with caps on the stored objects,
+    /// enforced by the `try_store_*` methods of all the instances cloned from the returned one.
+    pub fn create_root_with_caps(batch_size: usize, caps: StorageCaps) -> Storage {
         Storage {
             blocks: Default::default(),
             operations: Default::default(),
@@ -108,6 +187,9 @@ impl Storage {
             local_used_blocks: Default::default(),
             local_used_ops: Default::default(),
             local_used_endorsements: Default::default(),
+            orphan_removal_batch_size: batch_size.max(1),
+            caps,
+            stored_bytes: Default::default(),
         }
     }
 
@@ -126,6 +208,10 @@ impl Storage {
             local_used_ops: Default::default(),
             local_used_blocks: Default::default(),
             local_used_endorsements: Default::default(),
+
+            orphan_removal_batch_size: self.orphan_removal_batch_size,
+            caps: self.caps,
+            stored_bytes: self.stored_bytes.clone(),
         }
     }
 
@@ -203,6 +289,103 @@ impl Storage {
         }
     }
 
+    /// internal helper to locally claim the references that exist in `owners`.
+    /// Returns the claimed references.
+    fn internal_claim_existing_refs<IdT: Id + PartialEq + Eq + Hash + PreHashed + Copy>(
+        ids: &PreHashSet<IdT>,
+        owners: &mut RwLockWriteGuard<PreHashMap<IdT, usize>>,
+        local_used_ids: &mut PreHashSet<IdT>,
+    ) -> PreHashSet<IdT> {
+        let mut claimed = PreHashSet::with_capacity(ids.len());
+
+        // check that all IDs are owned
+        claimed.extend(ids.iter().filter(|id| owners.contains_key(id)));
+
+        // effectively add local ownership on the refs
+        Storage::internal_claim_refs(&claimed, owners, local_used_ids);
+
+        claimed
+    }
+
+    /// internal helper to remove orphaned objects from an index.
+    /// `remove` returns the estimated size in bytes of the removed object, released from `stored_bytes`.
+    /// The write lock is fairly yielded between batches of `batch_size` removals
+    /// so that a large drop does not starve the threads waiting for the index.
+    fn remove_orphans<IndexT, IdT>(
+        index: &RwLock<IndexT>,
+        orphaned_ids: &[IdT],
+        batch_size: usize,
+        stored_bytes: &AtomicUsize,
+        mut remove: impl FnMut(&mut IndexT, &IdT) -> usize,
+    ) {
+        if orphaned_ids.is_empty() {
+            return;
+        }
+        let mut index = index.write();
+        for (batch_index, batch) in orphaned_ids.chunks(batch_size).enumerate() {
+            if batch_index > 0 {
+                RwLockWriteGuard::bump(&mut index);
+            }
+            let removed_bytes: usize = batch.iter().map(|id| remove(&mut index, id)).sum();
+            stored_bytes.fetch_sub(removed_bytes, Ordering::Relaxed);
+        }
+    }
+
+    /// internal helper to check that `added` more objects fit under the cap of `category`
+    fn check_count_cap(
+        stored: usize,
+        added: usize,
+        max: Option<usize>,
+        category: StorageCategory,
+    ) -> Result<(), StorageAdmissionError> {
+        match max {
+            Some(max) if stored.saturating_add(added) > max => {
+                Err(StorageAdmissionError::Full(category))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// internal helper to account for `bytes` more stored bytes.
+    /// Unless `exempt`, nothing is accounted and an error is returned if the byte cap would be exceeded.
+    fn reserve_bytes(&self, bytes: usize, exempt: bool) -> Result<(), StorageAdmissionError> {
+        if exempt {
+            self.stored_bytes.fetch_add(bytes, Ordering::Relaxed);
+            return Ok(());
+        }
+        let max_bytes = self.caps.max_bytes.unwrap_or(usize::MAX);
+        self.stored_bytes
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |stored| {
+                stored
+                    .checked_add(bytes)
+                    .filter(|total| *total <= max_bytes)
+            })
+            .map(|_| ())
+            .map_err(|_| StorageAdmissionError::Full(StorageCategory::Bytes))
+    }
+
+    /// Gets the usage of each storage category
+    pub fn get_stats(&self) -> StorageStats {
+        StorageStats {
+            operations: StorageCategoryUsage {
+                stored: self.operations.read().len(),
+                max: self.caps.max_operations,
+            },
+            endorsements: StorageCategoryUsage {
+                stored: self.endorsements.read().len(),
+                max: self.caps.max_endorsements,
+            },
+            blocks: StorageCategoryUsage {
+                stored: self.blocks.read().len(),
+                max: self.caps.max_blocks,
+            },
+            bytes: StorageCategoryUsage {
+                stored: self.stored_bytes.load(Ordering::Relaxed),
+                max: self.caps.max_bytes,
+            },
+        }
+    }
+
     /// get the block reference ownership
     pub fn get_block_refs(&self) -> &PreHashSet<BlockId> {
         &self.local_used_blocks
@@ -211,21 +394,15 @@ impl Storage {
     /// Claim block references.
     /// Returns the set of block refs that were found and claimed.
     pub fn claim_block_refs(&mut self, ids: &PreHashSet<BlockId>) -> PreHashSet<BlockId> {
-        let mut claimed = PreHashSet::with_capacity(ids.len());
-
         if ids.is_empty() {
-            return claimed;
+            return PreHashSet::default();
         }
 
-        let owners = &mut self.block_owners.write();
-
-        // check that all IDs are owned
-        claimed.extend(ids.iter().filter(|id| owners.contains_key(id)));
-
-        // effectively add local ownership on the refs
-        Storage::internal_claim_refs(&claimed, owners, &mut self.local_used_blocks);
-
-        claimed
+        Storage::internal_claim_existing_refs(
+            ids,
+            &mut self.block_owners.write(),
+            &mut self.local_used_blocks,
+        )
     }
 
     /// Drop block references
@@ -260,27 +437,53 @@ impl Storage {
             }
         }
         // if there are orphaned objects, remove them from storage
-        if !orphaned_ids.is_empty() {
-            let mut blocks = self.blocks.write();
-            for b_id in orphaned_ids {
-                blocks.remove(&b_id);
-            }
-        }
+        Storage::remove_orphans(
+            &self.blocks,
+            &orphaned_ids,
+            self.orphan_removal_batch_size,
+            &self.stored_bytes,
+            |blocks, id| blocks.remove(id).map_or(0, |block| block.serialized_size()),
+        );
     }
 
     /// Store a block
-    /// Note that this also claims a local reference to the block
+    /// Note that this also claims a local reference to the block.
+    /// The block is exempted from the storage caps: use `try_store_block` for untrusted blocks.
     pub fn store_block(&mut self, block: SecureShareBlock) {
+        self.try_store_block(block, true)
+            .expect("exempted block rejected by storage");
+    }
+
+    /// Store a block unless it is new and would exceed the storage caps.
+    /// `exempt` admits it regardless of the caps, for locally-produced blocks.
+    /// Note that this also claims a local reference to the block if it was admitted.
+    pub fn try_store_block(
+        &mut self,
+        block: SecureShareBlock,
+        exempt: bool,
+    ) -> Result<(), StorageAdmissionError> {
         let id = block.id;
         let mut owners = self.block_owners.write();
         let mut blocks = self.blocks.write();
-        blocks.insert(block);
+        if !blocks.contains(&id) {
+            if !exempt {
+                Storage::check_count_cap(
+                    blocks.len(),
+                    1,
+                    self.caps.max_blocks,
+                    StorageCategory::Blocks,
+                )?;
+            }
+            self.reserve_bytes(block.serialized_size(), exempt)?;
+            blocks.insert(block);
+        }
         // update local reference counters
         Storage::internal_claim_refs(
             &vec![id].into_iter().collect(),
             &mut owners,
             &mut self.local_used_blocks,
         );
+        Ok(())
     }
 
     /// Claim operation references.
@@ -289,21 +492,15 @@ impl Storage {
         &mut self,
         ids: &PreHashSet<OperationId>,
     ) -> PreHashSet<OperationId> {
-        let mut claimed = PreHashSet::with_capacity(ids.len());
-
         if ids.is_empty() {
-            return claimed;
+            return PreHashSet::default();
         }
 
-        let owners = &mut self.operation_owners.write();
-
-        // check that all IDs are owned
-        claimed.extend(ids.iter().filter(|id| owners.contains_key(id)));
-
-        // effectively add local ownership on the refs
-        Storage::internal_claim_refs(&claimed, owners, &mut self.local_used_ops);
-
-        claimed
+        Storage::internal_claim_existing_refs(
+            ids,
+            &mut self.operation_owners.write(),
+            &mut self.local_used_ops,
+        )
     }
 
     /// get the operation reference ownership
@@ -311,6 +508,19 @@ impl Storage {
         &self.local_used_ops
     }
 
+    /// Keep only the local operation references whose id satisfies `keep`, dropping the others.
+    /// This only releases the references held by this instance:
+    /// operations still referenced by other instances stay in storage.
+    pub fn retain_operation_refs(&mut self, keep: impl Fn(&OperationId) -> bool) {
+        let dropped: PreHashSet<OperationId> = self
+            .local_used_ops
+            .iter()
+            .filter(|id| !keep(id))
+            .copied()
+            .collect();
+        self.drop_operation_refs(&dropped);
+    }
+
     /// Drop local operation references.
     /// Ignores already-absent refs.
     pub fn drop_operation_refs(&mut self, ids: &PreHashSet<OperationId>) {
@@ -344,64 +554,224 @@ impl Storage {
             }
         }
         // if there are orphaned objects, remove them from storage
-        if !orphaned_ids.is_empty() {
-            let mut ops = self.operations.write();
-            for id in orphaned_ids {
-                ops.remove(&id);
+        Storage::remove_orphans(
+            &self.operations,
+            &orphaned_ids,
+            self.orphan_removal_batch_size,
+            &self.stored_bytes,
+            |ops, id| ops.remove(id).map_or(0, |op| op.serialized_size()),
+        );
+    }
+
+    /// Compare the operations a module claims to hold with the local references of this instance
+    /// and with the owners registered in storage.
+    pub fn audit_refs_against(&self, claimed: &PreHashSet<OperationId>) -> RefAuditReport {
+        let mut report = RefAuditReport::default();
+        let owners = self.operation_owners.read();
+        for id in claimed.difference(&self.local_used_ops) {
+            if owners.contains_key(id) {
+                report.missing_refs.insert(*id);
+            } else {
+                report.missing_objects.insert(*id);
+            }
+        }
+        for id in &self.local_used_ops {
+            if !owners.contains_key(id) {
+                report.ownerless_refs.insert(*id);
+            } else if !claimed.contains(id) {
+                report.unclaimed_refs.insert(*id);
             }
         }
+        report
+    }
+
+    /// Repair the local operation references found inconsistent by `audit_refs_against`:
+    /// * claims the missing references whose object is still in storage
+    /// * drops the unclaimed references
+    /// * forgets the references without owner, that would panic on drop
+    ///
+    /// Returns the set of operation refs that were claimed again.
+    pub fn heal_operation_refs(&mut self, report: &RefAuditReport) -> PreHashSet<OperationId> {
+        self.local_used_ops
+            .retain(|id| !report.ownerless_refs.contains(id));
+        self.drop_operation_refs(&report.unclaimed_refs);
+        self.claim_operation_refs(&report.missing_refs)
+    }
+
+    /// Forget a local operation reference without releasing its owner count.
+    /// Only meant to desynchronize references in tests.
+    #[cfg(any(test, feature = "test-exports"))]
+    pub fn forget_operation_ref_unchecked(&mut self, id: &OperationId) {
+        self.local_used_ops.remove(id);
+    }
+
+    /// Add a local operation reference without registering an owner.
+    /// Only meant to desynchronize references in tests.
+    #[cfg(any(test, feature = "test-exports"))]
+    pub fn insert_operation_ref_unchecked(&mut self, id: OperationId) {
+        self.local_used_ops.insert(id);
     }
 
     /// Store operations
-    /// Claims a local reference to the added operation
+    /// Claims a local reference to the added operation.
+    /// The operations are exempted from the storage caps: use `try_store_operations` for untrusted operations.
     pub fn store_operations(&mut self, operations: Vec<SecureShareOperation>) {
+        self.try_store_operations(operations, true)
+            .expect("exempted operations rejected by storage");
+    }
+
+    /// Store operations unless the new ones would exceed the storage caps, in which case none is stored.
+    /// `exempt` admits them regardless of the caps, for locally-produced operations.
+    /// Claims a local reference to the added operations if they were admitted.
+    pub fn try_store_operations(
+        &mut self,
+        operations: Vec<SecureShareOperation>,
+        exempt: bool,
+    ) -> Result<(), StorageAdmissionError> {
         if operations.is_empty() {
-            return;
+            return Ok(());
         }
         let mut owners = self.operation_owners.write();
         let mut op_store = self.operations.write();
+        let mut new_ids = PreHashSet::<OperationId>::default();
+        let mut new_bytes = 0usize;
+        for op in &operations {
+            if !op_store.contains(&op.id) && new_ids.insert(op.id) {
+                new_bytes = new_bytes.saturating_add(op.serialized_size());
+            }
+        }
+        if !exempt {
+            Storage::check_count_cap(
+                op_store.len(),
+                new_ids.len(),
+                self.caps.max_operations,
+                StorageCategory::Operations,
+            )?;
+        }
+        self.reserve_bytes(new_bytes, exempt)?;
         let ids: PreHashSet<OperationId> = operations.iter().map(|op| op.id).collect();
         for op in operations {
             op_store.insert(op);
         }
         Storage::internal_claim_refs(&ids, &mut owners, &mut self.local_used_ops);
+        Ok(())
     }
 
-    /// Gets a read reference to the operations index
+    /// Gets a read reference to the operations index.
+    /// In async code, prefer `with_operations`: a guard held across an `.await` blocks the writers.
     pub fn read_operations(&self) -> RwLockReadGuard<OperationIndexes> {
         self.operations.read()
     }
 
-    /// Gets a read reference to the endorsements index
+    /// Runs `f` on the operations index, holding the read lock only for the duration of the call
+    pub fn with_operations<R>(&self, f: impl FnOnce(&OperationIndexes) -> R) -> R {
+        f(&self.operations.read())
+    }
+
+    /// Gets a read reference to the endorsements index.
+    /// In async code, prefer `with_endorsements`: a guard held across an `.await` blocks the writers.
     pub fn read_endorsements(&self) -> RwLockReadGuard<EndorsementIndexes> {
         self.endorsements.read()
     }
 
-    /// Gets a read reference to the blocks index
+    /// Runs `f` on the endorsements index, holding the read lock only for the duration of the call
+    pub fn with_endorsements<R>(&self, f: impl FnOnce(&EndorsementIndexes) -> R) -> R {
+        f(&self.endorsements.read())
+    }
+
+    /// Gets a read reference to the blocks index.
+    /// In async code, prefer `with_blocks`: a guard held across an `.await` blocks the writers.
     pub fn read_blocks(&self) -> RwLockReadGuard<BlockIndexes> {
         self.blocks.read()
     }
 
+    /// Runs `f` on the blocks index, holding the read lock only for the duration of the call
+    pub fn with_blocks<R>(&self, f: impl FnOnce(&BlockIndexes) -> R) -> R {
+        f(&self.blocks.read())
+    }
+
+    /// Gets the ids of the stored operations and endorsements created by an address
+    pub fn get_objects_created_by(&self, address: &Address) -> CreatedByAddress {
+        let operations = self.operations.read();
+        let endorsements = self.endorsements.read();
+        CreatedByAddress {
+            operations: operations
+                .get_operations_created_by(address)
+                .cloned()
+                .unwrap_or_default(),
+            endorsements: endorsements
+                .get_endorsements_created_by(address)
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+
     /// Claim endorsement references.
     /// Returns the set of operation refs that were found and claimed.
     pub fn claim_endorsement_refs(
         &mut self,
         ids: &PreHashSet<EndorsementId>,
     ) -> PreHashSet<EndorsementId> {
-        let mut claimed = PreHashSet::with_capacity(ids.len());
-
         if ids.is_empty() {
-            return claimed;
+            return PreHashSet::default();
         }
 
-        let owners = &mut self.endorsement_owners.write();
-
-        // check that all IDs are owned
-        claimed.extend(ids.iter().filter(|id| owners.contains_key(id)));
+        Storage::internal_claim_existing_refs(
+            ids,
+            &mut self.endorsement_owners.write(),
+            &mut self.local_used_endorsements,
+        )
+    }
 
-        // effectively add local ownership on the refs
-        Storage::internal_claim_refs(&claimed, owners, &mut self.local_used_endorsements);
-        claimed
+    /// Claim block, operation and endorsement references at once,
+    /// for example a block along with its operations and endorsements.
+    /// The owner lock of each non-empty category is taken only once, and all of them are held
+    /// during the claim so that other instances see the three categories claimed together.
+    /// Returns the refs of each category that were found and claimed.
+    pub fn claim_all(
+        &mut self,
+        blocks: &PreHashSet<BlockId>,
+        operations: &PreHashSet<OperationId>,
+        endorsements: &PreHashSet<EndorsementId>,
+    ) -> ClaimedRefs {
+        // the owner locks are always taken in the block, operation, endorsement order
+        let mut block_owners = (!blocks.is_empty()).then(|| self.block_owners.write());
+        let mut operation_owners = (!operations.is_empty()).then(|| self.operation_owners.write());
+        let mut endorsement_owners =
+            (!endorsements.is_empty()).then(|| self.endorsement_owners.write());
+
+        ClaimedRefs {
+            blocks: block_owners
+                .as_mut()
+                .map(|owners| {
+                    Storage::internal_claim_existing_refs(
+                        blocks,
+                        owners,
+                        &mut self.local_used_blocks,
+                    )
+                })
+                .unwrap_or_default(),
+            operations: operation_owners
+                .as_mut()
+                .map(|owners| {
+                    Storage::internal_claim_existing_refs(
+                        operations,
+                        owners,
+                        &mut self.local_used_ops,
+                    )
+                })
+                .unwrap_or_default(),
+            endorsements: endorsement_owners
+                .as_mut()
+                .map(|owners| {
+                    Storage::internal_claim_existing_refs(
+                        endorsements,
+                        owners,
+                        &mut self.local_used_endorsements,
+                    )
+                })
+                .unwrap_or_default(),
+        }
     }
 
     /// get the endorsement reference ownership
@@ -442,27 +812,58 @@ impl Storage {
             }
         }
         // if there are orphaned objects, remove them from storage
-        if !orphaned_ids.is_empty() {
-            let mut endos = self.endorsements.write();
-            for id in orphaned_ids {
-                endos.remove(&id);
-            }
-        }
+        Storage::remove_orphans(
+            &self.endorsements,
+            &orphaned_ids,
+            self.orphan_removal_batch_size,
+            &self.stored_bytes,
+            |endos, id| endos.remove(id).map_or(0, |endo| endo.serialized_size()),
+        );
     }
 
     /// Store endorsements
-    /// Claims local references to the added endorsements
+    /// Claims local references to the added endorsements.
+    /// The endorsements are exempted from the storage caps: use `try_store_endorsements` for untrusted endorsements.
     pub fn store_endorsements(&mut self, endorsements: Vec<SecureShareEndorsement>) {
+        self.try_store_endorsements(endorsements, true)
+            .expect("exempted endorsements rejected by storage");
+    }
+
+    /// Store endorsements unless the new ones would exceed the storage caps, in which case none is stored.
+    /// `exempt` admits them regardless of the caps, for locally-produced endorsements.
+    /// Claims local references to the added endorsements if they were admitted.
+    pub fn try_store_endorsements(
+        &mut self,
+        endorsements: Vec<SecureShareEndorsement>,
+        exempt: bool,
+    ) -> Result<(), StorageAdmissionError> {
         if endorsements.is_empty() {
-            return;
+            return Ok(());
         }
         let mut owners = self.endorsement_owners.write();
         let mut endo_store = self.endorsements.write();
+        let mut new_ids = PreHashSet::<EndorsementId>::default();
+        let mut new_bytes = 0usize;
+        for endorsement in &endorsements {
+            if !endo_store.contains(&endorsement.id) && new_ids.insert(endorsement.id) {
+                new_bytes = new_bytes.saturating_add(endorsement.serialized_size());
+            }
+        }
+        if !exempt {
+            Storage::check_count_cap(
+                endo_store.len(),
+                new_ids.len(),
+                self.caps.max_endorsements,
+                StorageCategory::Endorsements,
+            )?;
+        }
+        self.reserve_bytes(new_bytes, exempt)?;
         let ids: PreHashSet<EndorsementId> = endorsements.iter().map(|op| op.id).collect();
         for endorsement in endorsements {
             endo_store.insert(endorsement);
         }
         Storage::internal_claim_refs(&ids, &mut owners, &mut self.local_used_endorsements);
+        Ok(())
     }
 }
 
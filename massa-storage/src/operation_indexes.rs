@@ -81,6 +81,16 @@ impl OperationIndexes {
         self.operations.contains_key(id)
     }
 
+    /// Returns the number of stored operations.
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    /// Checks whether no operations are stored.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
     /// Get operations created by an address
     /// Arguments:
     /// * `address`: the address to get the operations created by
@@ -0,0 +1,125 @@
+use super::references::create_operations;
+use crate::{
+    Storage, StorageAdmissionError, StorageCaps, StorageCategory, DEFAULT_ORPHAN_REMOVAL_BATCH_SIZE,
+};
+use massa_factory_exports::test_exports::create_empty_block;
+use massa_models::{prehash::PreHashSet, slot::Slot};
+use massa_signature::KeyPair;
+
+#[test]
+fn test_operation_cap() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let ops = create_operations(&keypair, 0..8);
+    let mut storage = Storage::create_root_with_caps(
+        DEFAULT_ORPHAN_REMOVAL_BATCH_SIZE,
+        StorageCaps {
+            max_operations: Some(5),
+            ..Default::default()
+        },
+    );
+
+    storage
+        .try_store_operations(ops[..5].to_vec(), false)
+        .unwrap();
+    assert_eq!(
+        storage.try_store_operations(ops[5..7].to_vec(), false),
+        Err(StorageAdmissionError::Full(StorageCategory::Operations))
+    );
+    // a rejected batch is not stored at all
+    assert!(!storage.read_operations().contains(&ops[5].id));
+    assert!(!storage.get_op_refs().contains(&ops[5].id));
+    // already stored operations are admitted at the cap
+    let mut other = storage.clone_without_refs();
+    other
+        .try_store_operations(vec![ops[0].clone()], false)
+        .unwrap();
+    assert!(other.get_op_refs().contains(&ops[0].id));
+    drop(other);
+
+    let stats = storage.get_stats();
+    assert_eq!(stats.operations.stored, 5);
+    assert_eq!(stats.operations.utilization(), Some(1.0));
+    assert_eq!(stats.endorsements.utilization(), None);
+
+    // locally-produced operations are exempted
+    storage
+        .try_store_operations(vec![ops[7].clone()], true)
+        .unwrap();
+    assert_eq!(storage.get_stats().operations.stored, 6);
+
+    // dropping refs evicts operations and resumes admission
+    storage.drop_operation_refs(&[ops[0].id, ops[1].id, ops[7].id].into_iter().collect());
+    storage
+        .try_store_operations(ops[5..7].to_vec(), false)
+        .unwrap();
+    assert_eq!(storage.get_stats().operations.stored, 5);
+}
+
+#[test]
+fn test_byte_cap() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let ops = create_operations(&keypair, 0..4);
+    let op_size = ops[0].serialized_size();
+    let mut storage = Storage::create_root_with_caps(
+        DEFAULT_ORPHAN_REMOVAL_BATCH_SIZE,
+        StorageCaps {
+            max_bytes: Some(3 * op_size),
+            ..Default::default()
+        },
+    );
+    let baseline = storage.get_stats().bytes.stored;
+    assert_eq!(baseline, 0);
+
+    storage
+        .try_store_operations(ops[..2].to_vec(), false)
+        .unwrap();
+    assert_eq!(storage.get_stats().bytes.stored, 2 * op_size);
+    assert_eq!(
+        storage.try_store_operations(ops[2..4].to_vec(), false),
+        Err(StorageAdmissionError::Full(StorageCategory::Bytes))
+    );
+    assert_eq!(storage.get_stats().bytes.stored, 2 * op_size);
+
+    // refs held by other instances keep the operations stored
+    let other = storage.clone();
+    storage.drop_operation_refs(&[ops[0].id].into_iter().collect());
+    assert_eq!(storage.get_stats().bytes.stored, 2 * op_size);
+    drop(other);
+    assert_eq!(storage.get_stats().bytes.stored, op_size);
+
+    storage
+        .try_store_operations(ops[2..4].to_vec(), false)
+        .unwrap();
+    assert_eq!(storage.get_stats().bytes.stored, 3 * op_size);
+
+    let all_refs: PreHashSet<_> = storage.get_op_refs().clone();
+    storage.drop_operation_refs(&all_refs);
+    assert_eq!(storage.get_stats().bytes.stored, baseline);
+}
+
+#[test]
+fn test_block_cap() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let block_1 = create_empty_block(&keypair, &Slot::new(1, 0));
+    let block_2 = create_empty_block(&keypair, &Slot::new(1, 1));
+    let mut storage = Storage::create_root_with_caps(
+        DEFAULT_ORPHAN_REMOVAL_BATCH_SIZE,
+        StorageCaps {
+            max_blocks: Some(1),
+            ..Default::default()
+        },
+    );
+
+    storage.try_store_block(block_1.clone(), false).unwrap();
+    assert_eq!(
+        storage.try_store_block(block_2.clone(), false),
+        Err(StorageAdmissionError::Full(StorageCategory::Blocks))
+    );
+    assert!(!storage.read_blocks().contains(&block_2.id));
+    assert_eq!(storage.get_stats().bytes.stored, block_1.serialized_size());
+
+    storage.drop_block_refs(&[block_1.id].into_iter().collect());
+    assert_eq!(storage.get_stats().bytes.stored, 0);
+    storage.try_store_block(block_2.clone(), false).unwrap();
+    assert_eq!(storage.get_stats().blocks.stored, 1);
+}
@@ -28,6 +28,7 @@ fn test_double_insert() {
     {
         let blocks = storage.read_blocks();
         blocks.get(&block.id).unwrap();
+        assert_eq!(blocks.len(), 1);
     };
     let mut ids = PreHashSet::default();
     ids.insert(block.id);
@@ -35,5 +36,21 @@ fn test_double_insert() {
     {
         let blocks = storage.read_blocks();
         assert!(blocks.get(&block.id).is_none());
+        assert!(blocks.is_empty());
     };
 }
+
+#[test]
+/// Read stored objects through the closure-scoped accessors.
+fn test_with_accessors() {
+    let mut storage = Storage::create_root();
+    let block = create_empty_block(&KeyPair::generate(0).unwrap(), &Slot::new(0, 0));
+    storage.store_block(block.clone());
+
+    let stored_id = storage.with_blocks(|blocks| blocks.get(&block.id).map(|b| b.id));
+    assert_eq!(stored_id, Some(block.id));
+    assert!(storage.with_operations(|ops| ops.is_empty()));
+    assert!(storage.with_endorsements(|endos| endos.is_empty()));
+    // the lock is released once the closure returns
+    drop(storage.blocks.try_write().unwrap());
+}
@@ -1,6 +1,16 @@
-use crate::Storage;
+use crate::{CreatedByAddress, Storage};
 use massa_factory_exports::test_exports::create_empty_block;
-use massa_models::{address::Address, slot::Slot};
+use massa_models::{
+    address::Address,
+    amount::Amount,
+    block_id::BlockId,
+    config::CHAINID,
+    endorsement::{Endorsement, EndorsementSerializer},
+    operation::{Operation, OperationSerializer, OperationType},
+    prehash::PreHashSet,
+    secure_share::SecureShareContent,
+    slot::Slot,
+};
 use massa_signature::KeyPair;
 
 #[test]
@@ -25,11 +35,12 @@ fn test_block_index_by_creator() {
 
     storage.store_block(block.clone());
     let blocks = storage.read_blocks();
-    let stored_blocks = blocks
-        .get_blocks_created_by(&Address::from_public_key(&keypair.get_public_key()))
-        .unwrap();
-    assert_eq!(stored_blocks.len(), 1);
-    assert_eq!(stored_blocks.get(&block.id).unwrap(), &block.id);
+    let stored_blocks = blocks.get_blocks_created_by(
+        &Address::from_public_key(&keypair.get_public_key()),
+        None,
+        10,
+    );
+    assert_eq!(stored_blocks, vec![block.id]);
 }
 
 #[test]
@@ -43,6 +54,160 @@ fn test_block_fail_find() {
     storage.store_block(block);
     let blocks = storage.read_blocks();
     assert!(blocks
-        .get_blocks_created_by(&Address::from_public_key(&keypair2.get_public_key()))
-        .is_none());
+        .get_blocks_created_by(
+            &Address::from_public_key(&keypair2.get_public_key()),
+            None,
+            10
+        )
+        .is_empty());
+}
+
+#[test]
+fn test_blocks_created_by_paged() {
+    let mut storage = Storage::create_root();
+    let keypair = KeyPair::generate(0).unwrap();
+    let keypair2 = KeyPair::generate(0).unwrap();
+    let creator = Address::from_public_key(&keypair.get_public_key());
+    let creator2 = Address::from_public_key(&keypair2.get_public_key());
+    // blocks stored out of slot order, the creators alternating
+    let mut created = Vec::new();
+    let mut created2 = Vec::new();
+    for (period, thread) in [(3, 1), (1, 0), (2, 1), (1, 1), (3, 0), (2, 0)] {
+        let slot = Slot::new(period, thread);
+        if thread == 0 {
+            let block = create_empty_block(&keypair, &slot);
+            created.push((slot, block.id));
+            storage.store_block(block);
+        } else {
+            let block = create_empty_block(&keypair2, &slot);
+            created2.push((slot, block.id));
+            storage.store_block(block);
+        }
+    }
+    created.sort();
+    created2.sort();
+    let ids = |created: &[(Slot, BlockId)]| -> Vec<BlockId> {
+        created.iter().map(|(_, id)| *id).collect()
+    };
+
+    {
+        let blocks = storage.read_blocks();
+        assert_eq!(
+            blocks.get_blocks_created_by(&creator, None, 10),
+            ids(&created)
+        );
+        assert_eq!(
+            blocks.get_blocks_created_by(&creator2, None, 10),
+            ids(&created2)
+        );
+
+        // resume after the last slot of a first page
+        let first_page = blocks.get_blocks_created_by(&creator, None, 2);
+        assert_eq!(first_page, ids(&created[..2]));
+        let second_page = blocks.get_blocks_created_by(&creator, Some(created[1].0), 2);
+        assert_eq!(second_page, ids(&created[2..]));
+        assert!(blocks
+            .get_blocks_created_by(&creator, Some(created[2].0), 2)
+            .is_empty());
+    }
+
+    // evicting the blocks of a creator cleans up its index entry
+    let evicted: PreHashSet<BlockId> = created.iter().map(|(_, id)| *id).collect();
+    storage.drop_block_refs(&evicted);
+    let blocks = storage.read_blocks();
+    assert!(blocks.get_blocks_created_by(&creator, None, 10).is_empty());
+    assert_eq!(blocks.len(), created2.len());
+    assert_eq!(
+        blocks.get_blocks_created_by(&creator2, None, 10),
+        ids(&created2)
+    );
+}
+
+#[test]
+fn test_endorsements_created_by_paged() {
+    let mut storage = Storage::create_root();
+    let keypair = KeyPair::generate(0).unwrap();
+    let endorsed_block = create_empty_block(&keypair, &Slot::new(0, 0)).id;
+    let mut endorsements = Vec::new();
+    for (period, index) in [(3, 1), (1, 2), (3, 0), (2, 5), (1, 0)] {
+        endorsements.push(
+            Endorsement::new_verifiable(
+                Endorsement {
+                    slot: Slot::new(period, 0),
+                    index,
+                    endorsed_block,
+                },
+                EndorsementSerializer::new(),
+                &keypair,
+                *CHAINID,
+            )
+            .unwrap(),
+        );
+    }
+    let expected: Vec<_> = [4, 1, 3, 2, 0]
+        .into_iter()
+        .map(|i| endorsements[i].id)
+        .collect();
+
+    storage.store_endorsements(endorsements);
+    let endorsement_indexes = storage.read_endorsements();
+    let creator = Address::from_public_key(&keypair.get_public_key());
+    assert_eq!(
+        endorsement_indexes.get_endorsements_created_by_paged(&creator, 0, 10),
+        expected
+    );
+    assert_eq!(
+        endorsement_indexes.get_endorsements_created_by_paged(&creator, 1, 2),
+        expected[1..3]
+    );
+    assert!(endorsement_indexes
+        .get_endorsements_created_by_paged(&creator, 5, 2)
+        .is_empty());
+    let other = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    assert!(endorsement_indexes
+        .get_endorsements_created_by_paged(&other, 0, 10)
+        .is_empty());
+}
+
+#[test]
+fn test_objects_created_by() {
+    let mut storage = Storage::create_root();
+    let keypair = KeyPair::generate(0).unwrap();
+    let creator = Address::from_public_key(&keypair.get_public_key());
+    let operation = Operation::new_verifiable(
+        Operation {
+            fee: Amount::default(),
+            op: OperationType::RollBuy { roll_count: 1 },
+            expire_period: 10,
+        },
+        OperationSerializer::new(),
+        &keypair,
+        *CHAINID,
+    )
+    .unwrap();
+    let endorsement = Endorsement::new_verifiable(
+        Endorsement {
+            slot: Slot::new(1, 0),
+            index: 0,
+            endorsed_block: create_empty_block(&keypair, &Slot::new(0, 0)).id,
+        },
+        EndorsementSerializer::new(),
+        &keypair,
+        *CHAINID,
+    )
+    .unwrap();
+
+    storage.store_operations(vec![operation.clone()]);
+    storage.store_endorsements(vec![endorsement.clone()]);
+    let created = storage.get_objects_created_by(&creator);
+    assert_eq!(created.operations.len(), 1);
+    assert!(created.operations.contains(&operation.id));
+    assert_eq!(created.endorsements.len(), 1);
+    assert!(created.endorsements.contains(&endorsement.id));
+
+    let other = Address::from_public_key(&KeyPair::generate(0).unwrap().get_public_key());
+    assert_eq!(
+        storage.get_objects_created_by(&other),
+        CreatedByAddress::default()
+    );
 }
@@ -1,3 +1,4 @@
+mod admission;
 mod basic;
 mod indexes;
 mod references;
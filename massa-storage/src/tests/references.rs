@@ -1,7 +1,16 @@
-use crate::Storage;
+use crate::{ClaimedRefs, RefAuditReport, Storage};
 use massa_factory_exports::test_exports::create_empty_block;
-use massa_models::{prehash::PreHashSet, slot::Slot};
+use massa_models::{
+    amount::Amount,
+    config::CHAINID,
+    endorsement::{Endorsement, EndorsementSerializer},
+    operation::{Operation, OperationSerializer, OperationType, SecureShareOperation},
+    prehash::PreHashSet,
+    secure_share::SecureShareContent,
+    slot::Slot,
+};
 use massa_signature::KeyPair;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[test]
 fn test_clone() {
@@ -74,3 +83,198 @@ fn test_retrieve_all_ref_dropped_automatically() {
         assert!(blocks.get(&block.id).is_none());
     };
 }
+
+pub(super) fn create_operations(
+    keypair: &KeyPair,
+    expire_periods: std::ops::Range<u64>,
+) -> Vec<SecureShareOperation> {
+    expire_periods
+        .map(|expire_period| {
+            Operation::new_verifiable(
+                Operation {
+                    fee: Amount::default(),
+                    op: OperationType::RollBuy { roll_count: 1 },
+                    expire_period,
+                },
+                OperationSerializer::new(),
+                keypair,
+                *CHAINID,
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_retain_operation_refs() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let ops = create_operations(&keypair, 0..10);
+    let mut storage = Storage::create_root();
+    storage.store_operations(ops.clone());
+    let mut other = storage.clone_without_refs();
+    other.claim_operation_refs(&vec![ops[1].id].into_iter().collect());
+
+    let kept: PreHashSet<_> = ops
+        .iter()
+        .filter(|op| op.content.expire_period % 2 == 0)
+        .map(|op| op.id)
+        .collect();
+    storage.retain_operation_refs(|id| kept.contains(id));
+
+    assert_eq!(storage.get_op_refs(), &kept);
+    let stored = storage.read_operations();
+    for op in &ops {
+        // ops[1] is dropped here but still referenced by the other instance
+        let expected = kept.contains(&op.id) || op.id == ops[1].id;
+        assert_eq!(stored.contains(&op.id), expected);
+    }
+}
+
+#[test]
+fn test_batched_orphan_removal_under_concurrent_access() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let dropped_ops = create_operations(&keypair, 0..2000);
+    let kept_ops = create_operations(&keypair, 2000..2100);
+    let concurrent_ops = create_operations(&keypair, 3000..3200);
+
+    let root = Storage::create_root_with_orphan_removal_batch_size(16);
+    let mut kept = root.clone_without_refs();
+    kept.store_operations(kept_ops.clone());
+    let mut dropped = root.clone_without_refs();
+    dropped.store_operations(dropped_ops.clone());
+    // also reference the kept operations so that dropping doesn't orphan them
+    dropped.claim_operation_refs(&kept_ops.iter().map(|op| op.id).collect());
+
+    let done = AtomicBool::new(false);
+    let mut writer = root.clone_without_refs();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    let ops = root.read_operations();
+                    for op in &kept_ops {
+                        assert!(ops.contains(&op.id), "referenced operation was removed");
+                    }
+                }
+            });
+        }
+        let writer_handle = scope.spawn(|| {
+            for op in &concurrent_ops {
+                writer.store_operations(vec![op.clone()]);
+            }
+        });
+        drop(dropped);
+        writer_handle.join().unwrap();
+        done.store(true, Ordering::Relaxed);
+    });
+
+    let ops = root.read_operations();
+    assert!(dropped_ops.iter().all(|op| !ops.contains(&op.id)));
+    assert!(kept_ops.iter().all(|op| ops.contains(&op.id)));
+    assert!(concurrent_ops.iter().all(|op| ops.contains(&op.id)));
+    assert_eq!(ops.len(), kept_ops.len() + concurrent_ops.len());
+}
+
+#[test]
+fn test_claim_all() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let block = create_empty_block(&keypair, &Slot::new(1, 0));
+    let missing_block = create_empty_block(&keypair, &Slot::new(2, 0));
+    let ops = create_operations(&keypair, 0..3);
+    let endorsement = Endorsement::new_verifiable(
+        Endorsement {
+            slot: Slot::new(1, 0),
+            index: 0,
+            endorsed_block: missing_block.id,
+        },
+        EndorsementSerializer::new(),
+        &keypair,
+        *CHAINID,
+    )
+    .unwrap();
+    let mut storage = Storage::create_root();
+    storage.store_block(block.clone());
+    storage.store_operations(ops[..2].to_vec());
+    storage.store_endorsements(vec![endorsement.clone()]);
+
+    let mut other = storage.clone_without_refs();
+    let claimed = other.claim_all(
+        &vec![block.id, missing_block.id].into_iter().collect(),
+        &ops.iter().map(|op| op.id).collect(),
+        &vec![endorsement.id].into_iter().collect(),
+    );
+    let expected = ClaimedRefs {
+        blocks: vec![block.id].into_iter().collect(),
+        operations: ops[..2].iter().map(|op| op.id).collect(),
+        endorsements: vec![endorsement.id].into_iter().collect(),
+    };
+    assert_eq!(claimed, expected);
+    assert_eq!(other.get_block_refs(), &expected.blocks);
+    assert_eq!(other.get_op_refs(), &expected.operations);
+    assert_eq!(other.get_endorsement_refs(), &expected.endorsements);
+
+    // the claimed objects are kept once the original instance is dropped
+    drop(storage);
+    assert!(other.read_blocks().contains(&block.id));
+    assert!(other.read_operations().contains(&ops[0].id));
+    assert!(other.read_endorsements().contains(&endorsement.id));
+
+    // nothing to claim: no lock is needed
+    assert_eq!(
+        other.claim_all(
+            &PreHashSet::default(),
+            &PreHashSet::default(),
+            &PreHashSet::default()
+        ),
+        ClaimedRefs::default()
+    );
+}
+
+#[test]
+fn test_audit_refs_against() {
+    let keypair = KeyPair::generate(0).unwrap();
+    let ops = create_operations(&keypair, 0..5);
+    let ids: Vec<_> = ops.iter().map(|op| op.id).collect();
+    let mut storage = Storage::create_root();
+    storage.store_operations(ops[..4].to_vec());
+    let claimed: PreHashSet<_> = ids[..4].iter().copied().collect();
+    assert!(storage.audit_refs_against(&claimed).is_empty());
+
+    // ids[0] is still referenced by another instance, ids[1] is not
+    let mut other = storage.clone_without_refs();
+    other.claim_operation_refs(&vec![ids[0]].into_iter().collect());
+    storage.drop_operation_refs(&vec![ids[0], ids[1]].into_iter().collect());
+    // ids[4] was never stored
+    storage.insert_operation_ref_unchecked(ids[4]);
+    let claimed: PreHashSet<_> = ids[..3].iter().copied().collect();
+
+    let report = storage.audit_refs_against(&claimed);
+    assert_eq!(
+        report,
+        RefAuditReport {
+            missing_refs: vec![ids[0]].into_iter().collect(),
+            missing_objects: vec![ids[1]].into_iter().collect(),
+            unclaimed_refs: vec![ids[3]].into_iter().collect(),
+            ownerless_refs: vec![ids[4]].into_iter().collect(),
+        }
+    );
+    assert_eq!(report.discrepancy_count(), 4);
+
+    let reclaimed = storage.heal_operation_refs(&report);
+    assert_eq!(reclaimed, report.missing_refs);
+    let expected: PreHashSet<_> = vec![ids[0], ids[2]].into_iter().collect();
+    assert_eq!(storage.get_op_refs(), &expected);
+    assert!(!storage.read_operations().contains(&ids[3]));
+    let claimed: PreHashSet<_> = claimed
+        .difference(&report.missing_objects)
+        .copied()
+        .collect();
+    assert!(storage.audit_refs_against(&claimed).is_empty());
+
+    // a forgotten reference keeps its owner count, so the object stays in storage
+    storage.forget_operation_ref_unchecked(&ids[2]);
+    assert_eq!(
+        storage.audit_refs_against(&claimed).missing_refs,
+        vec![ids[2]].into_iter().collect()
+    );
+}
@@ -0,0 +1,101 @@
+// Copyright (c) 2023 MASSA LABS <info@massa.net>
+
+//! Sources of the current time, to let tests step the time of the workers
+
+use crate::{MassaTime, TimeError};
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Source of the current time
+pub trait Clock: Debug + Send + Sync {
+    /// Current time
+    fn now(&self) -> MassaTime;
+
+    /// Local instant at which to check whether `time` is reached
+    fn instant_at(&self, time: MassaTime) -> Result<Instant, TimeError>;
+}
+
+/// Clock following the system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealClock;
+
+impl Clock for RealClock {
+    fn now(&self) -> MassaTime {
+        MassaTime::now()
+    }
+
+    fn instant_at(&self, time: MassaTime) -> Result<Instant, TimeError> {
+        time.estimate_instant()
+    }
+}
+
+/// Clock that only moves when it is told to. Its clones share the same time.
+///
+/// It cannot tell when a future time will be reached:
+/// the instant of a future time is one polling interval away, so that a waiting worker checks the clock again.
+///
+/// ```
+/// # use massa_time::*;
+/// # use std::time::Instant;
+/// let clock = ManualClock::new(MassaTime::from_millis(1000));
+/// let shared = clock.clone();
+/// clock.advance(MassaTime::from_millis(500));
+/// assert_eq!(shared.now(), MassaTime::from_millis(1500));
+///
+/// // a reached time is due now, a future one is checked again after the polling interval
+/// assert!(shared.instant_at(MassaTime::from_millis(1500)).unwrap() <= Instant::now());
+/// assert!(shared.instant_at(MassaTime::from_millis(1501)).unwrap() > Instant::now());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    /// current time, in milliseconds
+    time: Arc<AtomicU64>,
+    /// interval at which a future time is checked again
+    poll_interval: Duration,
+}
+
+impl ManualClock {
+    /// Default interval at which a future time is checked again
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Creates a clock stopped at `time`
+    pub fn new(time: MassaTime) -> Self {
+        ManualClock {
+            time: Arc::new(AtomicU64::new(time.as_millis())),
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Sets the interval at which a future time is checked again
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Moves the clock to `time`
+    pub fn set(&self, time: MassaTime) {
+        self.time.store(time.as_millis(), Ordering::SeqCst);
+    }
+
+    /// Moves the clock forward by `duration`
+    pub fn advance(&self, duration: MassaTime) {
+        self.time.fetch_add(duration.as_millis(), Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> MassaTime {
+        MassaTime::from_millis(self.time.load(Ordering::SeqCst))
+    }
+
+    fn instant_at(&self, time: MassaTime) -> Result<Instant, TimeError> {
+        let now = Instant::now();
+        if time <= self.now() {
+            return Ok(now);
+        }
+        now.checked_add(self.poll_interval)
+            .ok_or(TimeError::TimeOverflowError)
+    }
+}
@@ -3,8 +3,10 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
+mod clock;
 mod error;
 mod mapping_grpc;
+pub use clock::{Clock, ManualClock, RealClock};
 pub use error::TimeError;
 use massa_serialization::{Deserializer, Serializer, U64VarIntDeserializer, U64VarIntSerializer};
 use nom::error::{context, ContextError, ParseError};
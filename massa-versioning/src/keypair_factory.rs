@@ -1,3 +1,6 @@
+use massa_hash::Hash;
+use massa_models::slot::Slot;
+use massa_serialization::{Serializer, U64VarIntSerializer};
 use massa_signature::KeyPair;
 
 use crate::{
@@ -5,11 +8,44 @@ use crate::{
     versioning_factory::{FactoryError, FactoryStrategy, VersioningFactory},
 };
 
+/// Domain separation tag of the keypairs derived from a seed
+const KEYPAIR_DERIVATION_TAG: &[u8] = b"massa_keypair_derivation";
+
 #[derive(Clone)]
 pub struct KeyPairFactory {
     pub mip_store: MipStore,
 }
 
+impl KeyPairFactory {
+    /// Derive a keypair from a seed, for the given slot.
+    ///
+    /// Only the seed, the slot and the keypair version resolved with `strategy` can change the
+    /// output: the MIPs of other components, and the keypair MIPs that do not change the resolved
+    /// version, have no effect on it.
+    pub fn derive(
+        &self,
+        seed: &[u8],
+        slot: Slot,
+        strategy: FactoryStrategy,
+    ) -> Result<KeyPair, FactoryError> {
+        let version = self.get_component_version_with_strategy(strategy)?;
+
+        let secret = Hash::compute_from_tuple(&[
+            KEYPAIR_DERIVATION_TAG,
+            &version.to_be_bytes(),
+            &slot.to_bytes_key(),
+            seed,
+        ]);
+        let mut bytes = Vec::new();
+        U64VarIntSerializer::new()
+            .serialize(&u64::from(version), &mut bytes)
+            .map_err(|e| FactoryError::OnCreate("KeyPair".to_string(), e.to_string()))?;
+        bytes.extend_from_slice(secret.to_bytes());
+
+        KeyPair::from_bytes(&bytes).map_err(|_| FactoryError::UnimplementedVersion(version))
+    }
+}
+
 impl VersioningFactory for KeyPairFactory {
     type Output = KeyPair;
     type Error = FactoryError;
@@ -36,3 +72,89 @@ impl VersioningFactory for KeyPairFactory {
         Ok(output)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use num::rational::Ratio;
+    use std::collections::BTreeMap;
+
+    use crate::test_helpers::versioning_helpers::advance_state_until;
+    use crate::versioning::{ComponentState, MipInfo, MipState, MipStatsConfig};
+
+    use massa_time::MassaTime;
+
+    fn factory<const N: usize>(mips: [(MipInfo, MipState); N]) -> KeyPairFactory {
+        let mip_stats_cfg = MipStatsConfig {
+            block_count_considered: 10,
+            warn_announced_version_ratio: Ratio::new_raw(30, 100),
+        };
+        KeyPairFactory {
+            mip_store: MipStore::try_from((mips, mip_stats_cfg)).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_keypair_derivation_is_deterministic() {
+        let seed = b"keypair derivation seed";
+        let slot = Slot::new(42, 3);
+        let now = MassaTime::now();
+
+        // an active MIP of another component
+        let vi_address = MipInfo {
+            name: "MIP-0002".to_string(),
+            version: 1,
+            components: BTreeMap::from([(MipComponent::Address, 1)]),
+            start: MassaTime::from_millis(12),
+            timeout: MassaTime::from_millis(15),
+            activation_delay: MassaTime::from_millis(2),
+        };
+        let vs_address = advance_state_until(ComponentState::active(now), &vi_address);
+        // a keypair MIP that is not active yet
+        let vi_keypair = MipInfo {
+            name: "MIP-0003".to_string(),
+            version: 2,
+            components: BTreeMap::from([(MipComponent::KeyPair, 1)]),
+            start: MassaTime::from_millis(25),
+            timeout: MassaTime::from_millis(28),
+            activation_delay: MassaTime::from_millis(2),
+        };
+        let vs_keypair = MipState::new(MassaTime::from_millis(18));
+
+        let expected = factory([])
+            .derive(seed, slot, FactoryStrategy::At(now))
+            .unwrap();
+        assert_eq!(expected.get_version(), 0);
+
+        for fa in [
+            factory([]),
+            factory([(vi_address.clone(), vs_address.clone())]),
+            factory([(vi_keypair.clone(), vs_keypair.clone())]),
+            factory([(vi_address, vs_address), (vi_keypair, vs_keypair)]),
+        ] {
+            for strategy in [FactoryStrategy::At(now), FactoryStrategy::Exact(0)] {
+                let keypair = fa.derive(seed, slot, strategy.clone()).unwrap();
+                assert_eq!(keypair.to_bytes(), expected.to_bytes());
+                let keypair = fa.derive(seed, slot, strategy).unwrap();
+                assert_eq!(keypair.to_bytes(), expected.to_bytes());
+            }
+            // the keypair MIP is not active, it cannot be used
+            assert!(matches!(
+                fa.derive(seed, slot, FactoryStrategy::Exact(1)),
+                Err(FactoryError::OnStateNotReady(1)) | Err(FactoryError::UnknownVersion(1))
+            ));
+        }
+
+        // the seed and the slot change the output
+        let fa = factory([]);
+        let other_seed = fa
+            .derive(b"another seed", slot, FactoryStrategy::At(now))
+            .unwrap();
+        assert_ne!(other_seed.to_bytes(), expected.to_bytes());
+        let other_slot = fa
+            .derive(seed, Slot::new(42, 4), FactoryStrategy::At(now))
+            .unwrap();
+        assert_ne!(other_slot.to_bytes(), expected.to_bytes());
+    }
+}